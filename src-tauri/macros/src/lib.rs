@@ -0,0 +1,85 @@
+// src-tauri/macros/src/lib.rs
+// `#[command]` - `#[tauri::command]` with per-command metrics. The original body runs as an
+// inner function so early returns and `?` are timed too, and an estimate of the JSON size of
+// whatever it returns is recorded alongside the duration through `crate::command_metrics`.
+// The estimate walks the value without serializing it, so large responses aren't encoded twice.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, FnArg, GenericArgument, ItemFn, Pat, PathArguments, ReturnType, Type};
+
+/// Use in place of `#[tauri::command]`; any arguments (`rename_all = ...`) are passed through
+#[proc_macro_attribute]
+pub fn command(args: TokenStream, item: TokenStream) -> TokenStream {
+    let function = parse_macro_input!(item as ItemFn);
+    expand(args.into(), function)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(args: TokenStream2, function: ItemFn) -> syn::Result<TokenStream2> {
+    let ItemFn { attrs, vis, sig, block } = function;
+    let name = sig.ident.to_string();
+    let body_ident = format_ident!("__{}_body", sig.ident);
+
+    let mut body_sig = sig.clone();
+    body_sig.ident = body_ident.clone();
+
+    // Tauri names the IPC arguments after the parameters, so the wrapper keeps them as they are
+    let mut outer_sig = sig;
+    let mut forwarded = Vec::new();
+    for input in outer_sig.inputs.iter_mut() {
+        match input {
+            FnArg::Typed(arg) => match arg.pat.as_mut() {
+                Pat::Ident(pat) => {
+                    pat.mutability = None;
+                    forwarded.push(pat.ident.clone());
+                }
+                other => return Err(syn::Error::new_spanned(other, "command arguments must be plain names")),
+            },
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(receiver, "commands can't take self"))
+            }
+        }
+    }
+
+    let tauri_attr = if args.is_empty() {
+        quote!(#[::tauri::command])
+    } else {
+        quote!(#[::tauri::command(#args)])
+    };
+    // Lint allowances such as `too_many_arguments` apply to the inner function as well
+    let allows = attrs.iter().filter(|attr| attr.path().is_ident("allow"));
+    let awaited = outer_sig.asyncness.map(|_| quote!(.await));
+    let size = if returns_result(&outer_sig.output) {
+        quote!(crate::command_metrics::result_size(&__response))
+    } else {
+        quote!(crate::command_metrics::response_size(&__response))
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #tauri_attr
+        #vis #outer_sig {
+            #(#allows)*
+            #body_sig #block
+
+            let __timer = crate::command_metrics::CommandTimer::start(#name);
+            let __response = #body_ident(#(#forwarded),*) #awaited;
+            __timer.respond(#size);
+            __response
+        }
+    })
+}
+
+// `Result<T, E>` is measured by whichever side was returned, as that's what reaches the frontend
+fn returns_result(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, ty) = output else { return false };
+    let Type::Path(path) = ty.as_ref() else { return false };
+    path.path.segments.last().is_some_and(|segment| {
+        segment.ident == "Result"
+            && matches!(&segment.arguments, PathArguments::AngleBracketed(generics)
+                if generics.args.iter().filter(|arg| matches!(arg, GenericArgument::Type(_))).count() == 2)
+    })
+}
@@ -0,0 +1,221 @@
+// Accessibility bridge - announces key backend events through the platform screen reader
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use crate::json_settings::{load_json_settings, save_json_settings};
+
+// Identical announcements within this window are only spoken once
+const DUPLICATE_WINDOW: Duration = Duration::from_secs(2);
+
+lazy_static::lazy_static! {
+    static ref ACCESSIBILITY_SETTINGS: RwLock<AccessibilitySettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref LAST_ANNOUNCED: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnnouncementCategory {
+    CaptureStarted,
+    ModelResponding,
+    ApprovalRequired,
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AnnouncementPriority {
+    /// Spoken after whatever the screen reader is currently reading
+    #[default]
+    Polite,
+    /// Interrupts current speech
+    Assertive,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AccessibilitySettings {
+    pub announcements_enabled: bool,
+    /// Categories the user turned off
+    pub muted_categories: Vec<AnnouncementCategory>,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            announcements_enabled: true,
+            muted_categories: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AnnouncementEvent<'a> {
+    message: &'a str,
+    category: AnnouncementCategory,
+    priority: AnnouncementPriority,
+    timestamp: i64,
+}
+
+const SETTINGS_FILE: &str = "accessibility_settings.json";
+
+fn is_duplicate(message: &str) -> bool {
+    let Ok(mut last) = LAST_ANNOUNCED.lock() else {
+        return false;
+    };
+    let now = Instant::now();
+    last.retain(|_, at| now.duration_since(*at) < DUPLICATE_WINDOW);
+    if last.contains_key(message) {
+        return true;
+    }
+    last.insert(message.to_string(), now);
+    false
+}
+
+/// Announce a backend event. The native screen reader API is used where we have one;
+/// the frontend also gets an `accessibility-announcement` event for its ARIA live region.
+pub fn announce(app_handle: &AppHandle, category: AnnouncementCategory, priority: AnnouncementPriority, message: &str) {
+    let allowed = ACCESSIBILITY_SETTINGS
+        .read()
+        .map(|settings| settings.announcements_enabled && !settings.muted_categories.contains(&category))
+        .unwrap_or(false);
+    if !allowed || message.trim().is_empty() || is_duplicate(message) {
+        return;
+    }
+
+    if let Err(e) = post_native_announcement(app_handle, message, priority) {
+        eprintln!("[ACCESSIBILITY] Native announcement failed: {}", e);
+    }
+
+    let _ = app_handle.emit("accessibility-announcement", AnnouncementEvent {
+        message,
+        category,
+        priority,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn post_native_announcement(app_handle: &AppHandle, message: &str, priority: AnnouncementPriority) -> Result<(), String> {
+    use objc::runtime::{Class, Object};
+    use objc::{msg_send, sel, sel_impl};
+    use std::ffi::CString;
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        fn NSAccessibilityPostNotificationWithUserInfo(element: *mut Object, notification: *mut Object, user_info: *mut Object);
+    }
+
+    unsafe fn ns_string(value: &str) -> Result<*mut Object, String> {
+        let c_string = CString::new(value).map_err(|e| e.to_string())?;
+        let class = Class::get("NSString").ok_or("NSString unavailable")?;
+        Ok(msg_send![class, stringWithUTF8String: c_string.as_ptr()])
+    }
+
+    let message = message.to_string();
+    // AppKit must be driven from the main thread
+    app_handle
+        .run_on_main_thread(move || unsafe {
+            let Some(app_class) = Class::get("NSApplication") else { return };
+            let Some(dictionary_class) = Class::get("NSDictionary") else { return };
+            let Some(number_class) = Class::get("NSNumber") else { return };
+            let (Ok(notification), Ok(announcement_key), Ok(priority_key), Ok(text)) = (
+                ns_string("AXAnnouncementRequested"),
+                ns_string("AXAnnouncementKey"),
+                ns_string("AXPriorityKey"),
+                ns_string(&message),
+            ) else {
+                return;
+            };
+
+            // NSAccessibilityPriorityMedium = 50, NSAccessibilityPriorityHigh = 90
+            let level: i64 = match priority {
+                AnnouncementPriority::Polite => 50,
+                AnnouncementPriority::Assertive => 90,
+            };
+            let priority_value: *mut Object = msg_send![number_class, numberWithLongLong: level];
+            let keys = [announcement_key, priority_key];
+            let values = [text, priority_value];
+            let user_info: *mut Object = msg_send![dictionary_class,
+                dictionaryWithObjects: values.as_ptr()
+                forKeys: keys.as_ptr()
+                count: 2usize];
+
+            let app: *mut Object = msg_send![app_class, sharedApplication];
+            NSAccessibilityPostNotificationWithUserInfo(app, notification, user_info);
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn post_native_announcement(app_handle: &AppHandle, message: &str, priority: AnnouncementPriority) -> Result<(), String> {
+    use tauri::Manager;
+    use windows::core::BSTR;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Accessibility::{
+        UiaHostProviderFromHwnd, UiaRaiseNotificationEvent, NotificationKind_Other,
+        NotificationProcessing_ImportantMostRecent, NotificationProcessing_MostRecent,
+    };
+
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or("Main window not available")?;
+    let hwnd = window.hwnd().map_err(|e| e.to_string())?;
+    let hwnd = HWND(hwnd.0 as isize);
+
+    let processing = match priority {
+        AnnouncementPriority::Polite => NotificationProcessing_MostRecent,
+        AnnouncementPriority::Assertive => NotificationProcessing_ImportantMostRecent,
+    };
+
+    // Narrator and NVDA read UIA notification events raised on the window's host provider
+    unsafe {
+        let provider = UiaHostProviderFromHwnd(hwnd).map_err(|e| e.to_string())?;
+        UiaRaiseNotificationEvent(
+            &provider,
+            NotificationKind_Other,
+            processing,
+            &BSTR::from(message),
+            &BSTR::from("enteract-announcement"),
+        )
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn post_native_announcement(_app_handle: &AppHandle, _message: &str, _priority: AnnouncementPriority) -> Result<(), String> {
+    // AT-SPI picks announcements up from the webview's live region
+    Ok(())
+}
+
+/// Let the frontend route its own announcements through the same bridge
+#[crate::command]
+pub fn announce_accessibility_message(
+    app_handle: AppHandle,
+    message: String,
+    priority: Option<AnnouncementPriority>,
+) -> Result<(), String> {
+    announce(&app_handle, AnnouncementCategory::Custom, priority.unwrap_or_default(), &message);
+    Ok(())
+}
+
+#[crate::command]
+pub fn get_accessibility_settings() -> Result<AccessibilitySettings, String> {
+    ACCESSIBILITY_SETTINGS
+        .read()
+        .map(|settings| settings.clone())
+        .map_err(|_| "Failed to read accessibility settings".to_string())
+}
+
+#[crate::command]
+pub fn save_accessibility_settings(settings: AccessibilitySettings) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    save_json_settings(SETTINGS_FILE, "accessibility settings", &settings)?;
+
+    *ACCESSIBILITY_SETTINGS
+        .write()
+        .map_err(|_| "Failed to update accessibility settings".to_string())? = settings;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+// src-tauri/src/action_registry.rs
+// Searchable registry of invokable actions (commands, agents, macros, tools) for the command palette
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+use tauri::AppHandle;
+
+pub type ActionFuture = Pin<Box<dyn Future<Output = Result<Value, String>> + Send>>;
+pub type ActionHandler = fn(AppHandle, Value) -> ActionFuture;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionKind {
+    Command,
+    Agent,
+    Macro,
+    Tool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionParam {
+    pub name: String,
+    pub description: String,
+    /// JSON type the palette should collect: "string", "number", "boolean", "object"
+    pub param_type: String,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionDescriptor {
+    pub id: String,
+    pub kind: ActionKind,
+    pub name: String,
+    pub description: String,
+    pub params: Vec<ActionParam>,
+    /// Extra search terms that aren't in the name or description
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+pub(crate) struct RegisteredAction {
+    pub(crate) descriptor: ActionDescriptor,
+    pub(crate) handler: ActionHandler,
+}
+
+lazy_static::lazy_static! {
+    static ref ACTIONS: Vec<RegisteredAction> = builtin_actions();
+}
+
+// Each module lists the actions it offers next to the commands behind them
+fn builtin_actions() -> Vec<RegisteredAction> {
+    #[cfg_attr(not(feature = "mcp"), allow(unused_mut))]
+    let mut actions: Vec<RegisteredAction> = [
+        crate::transparency::actions(),
+        crate::screenshot::actions(),
+        crate::audio_loopback::actions(),
+        crate::whisper_models::actions(),
+        crate::ollama::actions(),
+        crate::data::conversation::usage::actions(),
+        crate::system_info::actions(),
+        crate::app_lock::actions(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    #[cfg(feature = "mcp")]
+    actions.extend(crate::mcp::tools::actions());
+    actions
+}
+
+pub(crate) fn param(name: &str, param_type: &str, required: bool, description: &str) -> ActionParam {
+    ActionParam {
+        name: name.to_string(),
+        description: description.to_string(),
+        param_type: param_type.to_string(),
+        required,
+    }
+}
+
+pub(crate) fn descriptor(id: &str, kind: ActionKind, name: &str, description: &str, params: Vec<ActionParam>, keywords: &[&str]) -> ActionDescriptor {
+    ActionDescriptor {
+        id: id.to_string(),
+        kind,
+        name: name.to_string(),
+        description: description.to_string(),
+        params,
+        keywords: keywords.iter().map(|k| k.to_string()).collect(),
+    }
+}
+
+/// Deserialize an action's params object into the handler's argument struct
+pub(crate) fn args<T: DeserializeOwned>(params: Value) -> Result<T, String> {
+    let params = if params.is_null() { Value::Object(Default::default()) } else { params };
+    serde_json::from_value(params).map_err(|e| format!("Invalid action parameters: {}", e))
+}
+
+pub(crate) fn to_value<T: Serialize>(value: T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("Failed to serialize action result: {}", e))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SessionArgs {
+    pub(crate) session_id: String,
+}
+
+/// Rank an action against a palette query; `None` means it doesn't match.
+/// Every query word has to appear somewhere; name hits rank above description hits.
+fn match_score(descriptor: &ActionDescriptor, query: &str) -> Option<u32> {
+    let name = descriptor.name.to_lowercase();
+    let id = descriptor.id.to_lowercase();
+    let description = descriptor.description.to_lowercase();
+    let mut score = 0;
+
+    for word in query.to_lowercase().split_whitespace() {
+        score += if name.starts_with(word) {
+            100
+        } else if name.split_whitespace().any(|part| part.starts_with(word)) {
+            60
+        } else if id.contains(word) {
+            40
+        } else if descriptor.keywords.iter().any(|k| k.to_lowercase().starts_with(word)) {
+            30
+        } else if description.contains(word) {
+            10
+        } else {
+            return None;
+        };
+    }
+    Some(score)
+}
+
+#[crate::command]
+pub fn list_actions(query: Option<String>, kind: Option<ActionKind>) -> Result<Vec<ActionDescriptor>, String> {
+    let query = query.unwrap_or_default();
+
+    let mut matches: Vec<(u32, &ActionDescriptor)> = ACTIONS
+        .iter()
+        .map(|action| &action.descriptor)
+        .filter(|descriptor| kind.map_or(true, |kind| descriptor.kind == kind))
+        .filter_map(|descriptor| Some((match_score(descriptor, &query)?, descriptor)))
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+
+    Ok(matches.into_iter().map(|(_, descriptor)| descriptor.clone()).collect())
+}
+
+#[crate::command]
+pub async fn invoke_action(app_handle: AppHandle, action_id: String, params: Option<Value>) -> Result<Value, String> {
+    let action = ACTIONS
+        .iter()
+        .find(|action| action.descriptor.id == action_id)
+        .ok_or(format!("Unknown action: {}", action_id))?;
+    let descriptor = &action.descriptor;
+
+    let mut params = params.unwrap_or(Value::Null);
+    for required in descriptor.params.iter().filter(|p| p.required) {
+        if params.get(&required.name).map_or(true, Value::is_null) {
+            return Err(format!("Action '{}' needs '{}'", descriptor.name, required.name));
+        }
+    }
+    // Tool handlers are shared, so tell them which tool they're running
+    if descriptor.kind == ActionKind::Tool {
+        if let Some(object) = params.as_object_mut() {
+            object.insert("__tool".to_string(), Value::String(descriptor.name.clone()));
+        }
+    }
+
+    (action.handler)(app_handle, params).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_action_ids_are_unique() {
+        let mut ids: Vec<&str> = ACTIONS.iter().map(|action| action.descriptor.id.as_str()).collect();
+        let count = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+    }
+
+    #[test]
+    fn test_match_score_ranks_name_over_description() {
+        let capture = descriptor("screenshot.capture", ActionKind::Command, "Capture Screenshot",
+            "Capture the primary screen", vec![], &["image"]);
+        let restore = descriptor("window.emergency_restore", ActionKind::Command, "Restore Window",
+            "Make the main window fully opaque and clickable again", vec![], &[]);
+
+        assert_eq!(match_score(&capture, "cap"), Some(100));
+        assert_eq!(match_score(&restore, "window"), Some(60));
+        assert!(match_score(&capture, "screen").unwrap() > match_score(&capture, "primary").unwrap());
+        assert_eq!(match_score(&restore, "screenshot"), None);
+        assert_eq!(match_score(&capture, ""), Some(0));
+        assert_eq!(match_score(&capture, "image"), Some(30));
+    }
+}
@@ -0,0 +1,377 @@
+// src-tauri/src/app_lock.rs
+// Optional PIN lock that gates dangerous command groups on shared/kiosk machines
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::action_registry::{descriptor, to_value, ActionKind, RegisteredAction};
+
+const DEFAULT_RELOCK_TIMEOUT_SECS: u64 = 300;
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const FAILED_ATTEMPT_LOCKOUT: Duration = Duration::from_secs(30);
+const MIN_PIN_LENGTH: usize = 4;
+
+lazy_static::lazy_static! {
+    static ref APP_LOCK: Mutex<AppLockState> = Mutex::new(AppLockState::load());
+}
+
+/// Command groups that can be put behind the PIN
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandGroup {
+    McpExecution,
+    DataDeletion,
+    SettingsChanges,
+}
+
+impl CommandGroup {
+    fn label(&self) -> &'static str {
+        match self {
+            CommandGroup::McpExecution => "MCP tool execution",
+            CommandGroup::DataDeletion => "data deletion",
+            CommandGroup::SettingsChanges => "settings changes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLockConfig {
+    pub enabled: bool,
+    pub pin_hash: Option<String>,
+    pub salt: Option<String>,
+    #[serde(default = "default_relock_timeout")]
+    pub relock_timeout_secs: u64,
+    #[serde(default = "default_gated_groups")]
+    pub gated_groups: Vec<CommandGroup>,
+}
+
+fn default_relock_timeout() -> u64 {
+    DEFAULT_RELOCK_TIMEOUT_SECS
+}
+
+fn default_gated_groups() -> Vec<CommandGroup> {
+    vec![
+        CommandGroup::McpExecution,
+        CommandGroup::DataDeletion,
+        CommandGroup::SettingsChanges,
+    ]
+}
+
+impl Default for AppLockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pin_hash: None,
+            salt: None,
+            relock_timeout_secs: DEFAULT_RELOCK_TIMEOUT_SECS,
+            gated_groups: default_gated_groups(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub relock_timeout_secs: u64,
+    pub seconds_until_relock: Option<u64>,
+    pub gated_groups: Vec<CommandGroup>,
+    pub lockout_seconds_remaining: Option<u64>,
+}
+
+struct AppLockState {
+    config: AppLockConfig,
+    unlocked_until: Option<Instant>,
+    failed_attempts: u32,
+    lockout_until: Option<Instant>,
+}
+
+impl AppLockState {
+    fn new(config: AppLockConfig) -> Self {
+        Self {
+            config,
+            unlocked_until: None,
+            failed_attempts: 0,
+            lockout_until: None,
+        }
+    }
+
+    fn load() -> Self {
+        let config = get_app_lock_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str::<AppLockConfig>(&json).ok())
+            .unwrap_or_default();
+        Self::new(config)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = get_app_lock_path()
+            .map_err(|e| format!("Failed to get app lock path: {}", e))?;
+        let json = serde_json::to_string_pretty(&self.config)
+            .map_err(|e| format!("Failed to serialize app lock config: {}", e))?;
+        fs::write(path, json)
+            .map_err(|e| format!("Failed to write app lock config: {}", e))
+    }
+
+    fn is_locked(&self, now: Instant) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+        match self.unlocked_until {
+            Some(until) => now >= until,
+            None => true,
+        }
+    }
+
+    fn check(&mut self, group: CommandGroup, now: Instant) -> Result<(), String> {
+        if !self.config.enabled || !self.config.gated_groups.contains(&group) {
+            return Ok(());
+        }
+        if self.is_locked(now) {
+            self.unlocked_until = None;
+            return Err(format!("App is locked: unlock with your PIN to allow {}", group.label()));
+        }
+        // Activity keeps the session unlocked
+        self.unlocked_until = Some(now + self.relock_timeout());
+        Ok(())
+    }
+
+    fn verify_pin(&mut self, pin: &str, now: Instant) -> Result<(), String> {
+        if let Some(until) = self.lockout_until {
+            if now < until {
+                return Err(format!(
+                    "Too many failed attempts, try again in {} seconds",
+                    (until - now).as_secs().max(1)
+                ));
+            }
+            self.lockout_until = None;
+        }
+
+        let (Some(expected), Some(salt)) = (&self.config.pin_hash, &self.config.salt) else {
+            return Err("No PIN has been configured".to_string());
+        };
+
+        if hash_pin(pin, salt) == *expected {
+            self.failed_attempts = 0;
+            Ok(())
+        } else {
+            self.failed_attempts += 1;
+            if self.failed_attempts >= MAX_FAILED_ATTEMPTS {
+                self.failed_attempts = 0;
+                self.lockout_until = Some(now + FAILED_ATTEMPT_LOCKOUT);
+            }
+            Err("Incorrect PIN".to_string())
+        }
+    }
+
+    fn unlock(&mut self, pin: &str, now: Instant) -> Result<(), String> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        self.verify_pin(pin, now)?;
+        self.unlocked_until = Some(now + self.relock_timeout());
+        Ok(())
+    }
+
+    fn relock_timeout(&self) -> Duration {
+        Duration::from_secs(self.config.relock_timeout_secs.max(1))
+    }
+
+    fn status(&self, now: Instant) -> AppLockStatus {
+        let locked = self.is_locked(now);
+        AppLockStatus {
+            enabled: self.config.enabled,
+            locked,
+            relock_timeout_secs: self.config.relock_timeout_secs,
+            seconds_until_relock: match (locked, self.unlocked_until) {
+                (false, Some(until)) if self.config.enabled => Some((until - now).as_secs()),
+                _ => None,
+            },
+            gated_groups: self.config.gated_groups.clone(),
+            lockout_seconds_remaining: self
+                .lockout_until
+                .filter(|until| now < *until)
+                .map(|until| (until - now).as_secs().max(1)),
+        }
+    }
+}
+
+fn get_app_lock_path() -> anyhow::Result<PathBuf> {
+    let app_data = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+    let app_dir = app_data.join("enteract");
+
+    if !app_dir.exists() {
+        fs::create_dir_all(&app_dir)?;
+    }
+
+    Ok(app_dir.join("app_lock.json"))
+}
+
+fn hash_pin(pin: &str, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(pin.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn validate_pin(pin: &str) -> Result<(), String> {
+    if pin.len() < MIN_PIN_LENGTH || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("PIN must be at least {} digits", MIN_PIN_LENGTH));
+    }
+    Ok(())
+}
+
+/// Guard for gated commands. Returns an error while the app is locked and
+/// refreshes the relock timer otherwise.
+pub fn require_unlocked(group: CommandGroup) -> Result<(), String> {
+    let mut state = APP_LOCK.lock().map_err(|_| "Failed to acquire app lock".to_string())?;
+    state.check(group, Instant::now())
+}
+
+#[crate::command]
+pub fn get_app_lock_status() -> Result<AppLockStatus, String> {
+    let state = APP_LOCK.lock().map_err(|_| "Failed to acquire app lock".to_string())?;
+    Ok(state.status(Instant::now()))
+}
+
+#[crate::command]
+pub fn unlock_app(pin: String) -> Result<AppLockStatus, String> {
+    let mut state = APP_LOCK.lock().map_err(|_| "Failed to acquire app lock".to_string())?;
+    let now = Instant::now();
+    state.unlock(&pin, now)?;
+    Ok(state.status(now))
+}
+
+#[crate::command]
+pub fn lock_app() -> Result<AppLockStatus, String> {
+    let mut state = APP_LOCK.lock().map_err(|_| "Failed to acquire app lock".to_string())?;
+    state.unlocked_until = None;
+    Ok(state.status(Instant::now()))
+}
+
+/// Enable the lock or change the PIN. Changing an existing PIN requires the current one.
+#[crate::command]
+pub fn set_app_lock_pin(
+    current_pin: Option<String>,
+    new_pin: String,
+    relock_timeout_secs: Option<u64>,
+    gated_groups: Option<Vec<CommandGroup>>,
+) -> Result<AppLockStatus, String> {
+    validate_pin(&new_pin)?;
+
+    let mut state = APP_LOCK.lock().map_err(|_| "Failed to acquire app lock".to_string())?;
+    let now = Instant::now();
+
+    if state.config.enabled {
+        let current = current_pin.ok_or_else(|| "Current PIN is required".to_string())?;
+        state.verify_pin(&current, now)?;
+    }
+
+    let salt = uuid::Uuid::new_v4().to_string();
+    state.config.pin_hash = Some(hash_pin(&new_pin, &salt));
+    state.config.salt = Some(salt);
+    state.config.enabled = true;
+    if let Some(timeout) = relock_timeout_secs {
+        state.config.relock_timeout_secs = timeout.max(1);
+    }
+    if let Some(groups) = gated_groups {
+        state.config.gated_groups = groups;
+    }
+    state.save()?;
+
+    // The user just proved they know the PIN, start unlocked
+    state.unlocked_until = Some(now + state.relock_timeout());
+    Ok(state.status(now))
+}
+
+#[crate::command]
+pub fn disable_app_lock(pin: String) -> Result<AppLockStatus, String> {
+    let mut state = APP_LOCK.lock().map_err(|_| "Failed to acquire app lock".to_string())?;
+    let now = Instant::now();
+
+    if state.config.enabled {
+        state.verify_pin(&pin, now)?;
+    }
+
+    state.config = AppLockConfig::default();
+    state.unlocked_until = None;
+    state.save()?;
+    Ok(state.status(now))
+}
+
+/// Command palette actions for the app lock
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    vec![RegisteredAction {
+        descriptor: descriptor("app.lock", ActionKind::Command, "Lock App",
+            "Require the PIN again for protected actions", vec![], &["pin", "security"]),
+        handler: |_, _| Box::pin(async move { to_value(lock_app()?) }),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locked_state(pin: &str) -> AppLockState {
+        let salt = "test-salt".to_string();
+        AppLockState::new(AppLockConfig {
+            enabled: true,
+            pin_hash: Some(hash_pin(pin, &salt)),
+            salt: Some(salt),
+            relock_timeout_secs: 60,
+            gated_groups: default_gated_groups(),
+        })
+    }
+
+    #[test]
+    fn test_disabled_lock_allows_everything() {
+        let mut state = AppLockState::new(AppLockConfig::default());
+        assert!(state.check(CommandGroup::DataDeletion, Instant::now()).is_ok());
+    }
+
+    #[test]
+    fn test_unlock_and_relock_timeout() {
+        let mut state = locked_state("1234");
+        let now = Instant::now();
+
+        assert!(state.check(CommandGroup::McpExecution, now).is_err());
+        assert!(state.unlock("0000", now).is_err());
+        assert!(state.unlock("1234", now).is_ok());
+        assert!(state.check(CommandGroup::McpExecution, now).is_ok());
+
+        let later = now + Duration::from_secs(61);
+        assert!(state.check(CommandGroup::McpExecution, later).is_err());
+    }
+
+    #[test]
+    fn test_ungated_group_is_allowed_while_locked() {
+        let mut state = locked_state("1234");
+        state.config.gated_groups = vec![CommandGroup::McpExecution];
+        assert!(state.check(CommandGroup::SettingsChanges, Instant::now()).is_ok());
+    }
+
+    #[test]
+    fn test_repeated_failures_trigger_lockout() {
+        let mut state = locked_state("1234");
+        let now = Instant::now();
+
+        for _ in 0..MAX_FAILED_ATTEMPTS {
+            assert!(state.unlock("9999", now).is_err());
+        }
+        // Even the correct PIN is rejected during the lockout window
+        assert!(state.unlock("1234", now).is_err());
+        assert!(state.unlock("1234", now + FAILED_ATTEMPT_LOCKOUT).is_ok());
+    }
+
+    #[test]
+    fn test_pin_validation() {
+        assert!(validate_pin("12").is_err());
+        assert!(validate_pin("12ab").is_err());
+        assert!(validate_pin("123456").is_ok());
+    }
+}
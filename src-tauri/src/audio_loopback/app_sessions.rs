@@ -0,0 +1,274 @@
+// src-tauri/src/audio_loopback/app_sessions.rs
+// Per-app audio sessions - list the apps playing sound, change their volume or mute them, and
+// duck music players automatically while system audio is being captured.
+//
+// Sessions are on the default output device and addressed by process id. Ducking records each
+// app's volume and puts it back when capture stops, unless the user moved it in the meantime.
+// Only Windows exposes per-app sessions; the commands return an error elsewhere.
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "windows")]
+use std::collections::HashMap;
+#[cfg(target_os = "windows")]
+use std::sync::Mutex;
+
+#[cfg(target_os = "windows")]
+use crate::audio_loopback::windows::audio_sessions;
+
+#[cfg(not(target_os = "windows"))]
+const UNSUPPORTED: &str = "Per-app audio sessions are only available on Windows";
+// A ducked volume that moved further than this was changed by the user, so it isn't restored
+#[cfg(target_os = "windows")]
+const VOLUME_TOLERANCE: f32 = 0.01;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioSessionInfo {
+    pub process_id: u32,
+    /// Executable name, e.g. `Spotify.exe`
+    pub process_name: Option<String>,
+    pub display_name: String,
+    /// 0.0-1.0, relative to the device volume
+    pub volume: f32,
+    pub muted: bool,
+    /// Playing right now rather than just open
+    pub active: bool,
+    pub system_sounds: bool,
+    /// Turned down by ducking and waiting to be restored
+    pub ducked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioDuckingConfig {
+    /// Duck the listed apps while a capture is running
+    pub enabled: bool,
+    /// Fraction of its volume a ducked app keeps
+    pub level: f32,
+    /// Executable names to duck, matched case-insensitively
+    pub apps: Vec<String>,
+}
+
+impl Default for AudioDuckingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            level: 0.2,
+            apps: ["Spotify.exe", "Music.UI.exe", "AppleMusic.exe", "iTunes.exe", "vlc.exe", "foobar2000.exe", "TIDAL.exe"]
+                .iter()
+                .map(|app| app.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl AudioDuckingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.level) {
+            return Err(format!("level must be between 0 and 1 (got {})", self.level));
+        }
+        if self.apps.iter().any(|app| app.trim().is_empty()) {
+            return Err("apps can't contain empty names".to_string());
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn should_duck(&self, session: &AudioSessionInfo) -> bool {
+        !session.system_sounds
+            && session
+                .process_name
+                .as_ref()
+                .is_some_and(|name| self.apps.iter().any(|app| app.trim().eq_ignore_ascii_case(name)))
+    }
+}
+
+#[cfg(target_os = "windows")]
+struct DuckedSession {
+    original: f32,
+    ducked: f32,
+}
+
+#[cfg(target_os = "windows")]
+lazy_static::lazy_static! {
+    // Apps ducking turned down, keyed by process id
+    static ref DUCKED: Mutex<HashMap<u32, DuckedSession>> = Mutex::new(HashMap::new());
+}
+
+fn validate_volume(volume: f32) -> Result<(), String> {
+    if (0.0..=1.0).contains(&volume) {
+        Ok(())
+    } else {
+        Err(format!("Volume must be between 0 and 1 (got {})", volume))
+    }
+}
+
+// Run `f` on a blocking thread, since COM calls shouldn't tie up the async workers
+#[cfg(target_os = "windows")]
+async fn on_sessions<T: Send + 'static>(f: impl FnOnce() -> anyhow::Result<T> + Send + 'static) -> Result<T, String> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Audio session task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+fn configured_ducking() -> AudioDuckingConfig {
+    crate::audio_loopback::settings::read_audio_settings()
+        .ok()
+        .flatten()
+        .map(|settings| settings.audioDucking)
+        .unwrap_or_default()
+}
+
+/// Turn down the configured music apps for a capture that just started
+#[cfg(target_os = "windows")]
+pub async fn duck_for_capture() {
+    let config = configured_ducking();
+    if !config.enabled {
+        return;
+    }
+    let result = on_sessions(move || {
+        let mut ducked = DUCKED.lock().map_err(|_| anyhow::anyhow!("Ducking state is unavailable"))?;
+        for session in audio_sessions::list_sessions()? {
+            if ducked.contains_key(&session.process_id) || session.muted || !config.should_duck(&session) {
+                continue;
+            }
+            let target = session.volume * config.level;
+            match audio_sessions::set_session_volume(session.process_id, target) {
+                Ok(()) => {
+                    println!("[DUCKING] Turned down {} to {:.0}%", session.display_name, target * 100.0);
+                    ducked.insert(session.process_id, DuckedSession { original: session.volume, ducked: target });
+                }
+                Err(e) => eprintln!("[DUCKING] Failed to duck {}: {}", session.display_name, e),
+            }
+        }
+        Ok(())
+    })
+    .await;
+    if let Err(e) = result {
+        eprintln!("[DUCKING] Failed to duck audio sessions: {}", e);
+    }
+}
+
+/// Put ducked apps back to their volume once capture stops
+#[cfg(target_os = "windows")]
+pub async fn restore_ducked_sessions() {
+    let result = on_sessions(|| {
+        let mut ducked = DUCKED.lock().map_err(|_| anyhow::anyhow!("Ducking state is unavailable"))?;
+        if ducked.is_empty() {
+            return Ok(());
+        }
+        for session in audio_sessions::list_sessions()? {
+            let Some(record) = ducked.remove(&session.process_id) else {
+                continue;
+            };
+            if (session.volume - record.ducked).abs() > VOLUME_TOLERANCE {
+                continue;
+            }
+            if let Err(e) = audio_sessions::set_session_volume(session.process_id, record.original) {
+                eprintln!("[DUCKING] Failed to restore {}: {}", session.display_name, e);
+            }
+        }
+        // Whatever is left has exited
+        ducked.clear();
+        Ok(())
+    })
+    .await;
+    if let Err(e) = result {
+        eprintln!("[DUCKING] Failed to restore audio sessions: {}", e);
+    }
+}
+
+#[crate::command]
+pub async fn list_audio_sessions() -> Result<Vec<AudioSessionInfo>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut sessions = on_sessions(audio_sessions::list_sessions).await?;
+        if let Ok(ducked) = DUCKED.lock() {
+            for session in &mut sessions {
+                session.ducked = ducked.contains_key(&session.process_id);
+            }
+        }
+        Ok(sessions)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+/// Set an app's volume (0.0-1.0); a ducked app set by hand is no longer restored
+#[crate::command]
+pub async fn set_audio_session_volume(process_id: u32, volume: f32) -> Result<(), String> {
+    validate_volume(volume)?;
+    #[cfg(target_os = "windows")]
+    {
+        on_sessions(move || audio_sessions::set_session_volume(process_id, volume)).await?;
+        if let Ok(mut ducked) = DUCKED.lock() {
+            ducked.remove(&process_id);
+        }
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = process_id;
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+#[crate::command]
+pub async fn set_audio_session_mute(process_id: u32, muted: bool) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        on_sessions(move || audio_sessions::set_session_mute(process_id, muted)).await
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (process_id, muted);
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+#[crate::command]
+pub fn get_audio_ducking_config() -> Result<AudioDuckingConfig, String> {
+    Ok(configured_ducking())
+}
+
+/// Save the ducking setup with the audio settings; it applies from the next capture
+#[crate::command]
+pub fn set_audio_ducking_config(config: AudioDuckingConfig) -> Result<AudioDuckingConfig, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    config.validate()?;
+    let mut settings = crate::audio_loopback::settings::read_audio_settings()?.unwrap_or_default();
+    settings.audioDucking = config.clone();
+    crate::audio_loopback::settings::write_audio_settings(&settings)?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ducking_matches_listed_apps_only() {
+        let config = AudioDuckingConfig::default();
+        let session = |name: Option<&str>, system_sounds: bool| AudioSessionInfo {
+            process_id: 42,
+            process_name: name.map(|name| name.to_string()),
+            display_name: "Test".to_string(),
+            volume: 0.8,
+            muted: false,
+            active: true,
+            system_sounds,
+            ducked: false,
+        };
+        assert!(config.should_duck(&session(Some("spotify.exe"), false)));
+        assert!(!config.should_duck(&session(Some("Teams.exe"), false)));
+        assert!(!config.should_duck(&session(None, true)));
+
+        assert!(config.validate().is_ok());
+        assert!(AudioDuckingConfig { level: 1.5, ..config.clone() }.validate().is_err());
+        assert!(AudioDuckingConfig { apps: vec![" ".to_string()], ..config }.validate().is_err());
+        assert!(validate_volume(0.5).is_ok());
+        assert!(validate_volume(-0.1).is_err());
+    }
+}
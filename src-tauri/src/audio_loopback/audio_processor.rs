@@ -1,6 +1,7 @@
 // src-tauri/src/audio_loopback/audio_processor.rs
 // use crate::audio_loopback::quality_filter::{estimate_transcription_confidence, is_transcription_quality_ok};
 use anyhow::Result;
+use crate::audio_loopback::channel_mixer::DownmixMatrix;
 use tauri::{AppHandle, Emitter};
 use base64::prelude::*;
 use serde_json;
@@ -8,7 +9,7 @@ use std::fs::OpenOptions;
 use std::io::Write;
 
 // Audio processing for transcription with improved quality filtering
-#[tauri::command]
+#[crate::command]
 pub async fn process_audio_for_transcription(
     audio_data: Vec<u8>,
     sample_rate: u32,
@@ -22,7 +23,8 @@ pub async fn process_audio_for_transcription(
         16,  // We're receiving PCM16
         2,   // Stereo input expected
         sample_rate,
-        16000  // Target Whisper sample rate
+        16000,  // Target Whisper sample rate
+        None
     );
     
     // println!("[PROCESS] Output: {} samples at 16kHz", processed_samples.len()); // Commented out: Audio loopback is working, reducing console noise for debugging focus
@@ -62,17 +64,7 @@ pub async fn process_audio_for_transcription(
     
     // Load settings to get the selected loopback whisper model
     let model_size = match crate::audio_loopback::settings::load_general_settings().await {
-        Ok(Some(settings)) => {
-            if let Some(model) = settings.get("loopbackWhisperModel") {
-                if let Some(model_str) = model.as_str() {
-                    model_str.to_string()
-                } else {
-                    "small".to_string() // Default for loopback - same as microphone
-                }
-            } else {
-                "small".to_string() // Default for loopback - same as microphone
-            }
-        }
+        Ok(Some(settings)) => settings.loopback_whisper_model,
         Ok(None) => "small".to_string(), // No settings found, use default
         Err(_) => "small".to_string() // Error loading settings, use default
     };
@@ -85,21 +77,29 @@ pub async fn process_audio_for_transcription(
         enableVad: false,  // Matching Python script
         silenceThreshold: 0.01,
         maxSegmentLength: 30,
+        source: Some("loopback".to_string()),
+        deviceId: None,
+        languages: Vec::new(),
+        operationId: None,
     };
     
-    match crate::speech::transcribe_audio_base64(audio_base64, config).await {
+    match crate::speech::transcribe_audio_base64(app_handle.clone(), audio_base64, config).await {
         Ok(result) => {
             let text = result.text.trim();
             log_transcription_debug(&format!("[MAIN] Raw Whisper result: '{}'", text), rms, db_level);
             
             if !text.is_empty() && text.len() > 1 {
-                let estimated_confidence = estimate_python_style_confidence(text);
+                // Word-based checks fall apart for scripts without spaces, so count characters there
+                let unspaced = crate::data::conversation::uses_unspaced_script(
+                    &crate::data::conversation::active_conversation_languages(&app_handle).await
+                );
+                let estimated_confidence = estimate_python_style_confidence(text, unspaced);
                 
                 // Clean up the text - remove brackets and convert to proper format
                 let cleaned_text = clean_whisper_output(text);
                 log_transcription_debug(&format!("[MAIN] Cleaned text: '{}'", cleaned_text), rms, db_level);
                 
-                let is_quality_ok = is_python_style_quality_ok(&cleaned_text, estimated_confidence);
+                let is_quality_ok = is_python_style_quality_ok(&cleaned_text, estimated_confidence, unspaced);
                 
                 if !is_quality_ok {
                     log_transcription_debug(&format!("[MAIN FILTERED] {} (conf: {:.3})", cleaned_text, estimated_confidence), rms, db_level);
@@ -109,13 +109,24 @@ pub async fn process_audio_for_transcription(
                 // println!("🎙️ LOOPBACK: {} (conf: {:.3})", cleaned_text, estimated_confidence); // Commented out: Audio loopback is working, reducing console noise for debugging focus
                 log_transcription_debug(&format!("[MAIN SUCCESS] {} (conf: {:.3})", cleaned_text, estimated_confidence), rms, db_level);
                 
-                // Emit transcription event to frontend
+                // Emit transcription event to frontend, naming the device by its alias
+                let device_name = crate::audio_loopback::types::CAPTURE_STATE
+                    .lock()
+                    .ok()
+                    .and_then(|state| state.device_id.clone())
+                    .and_then(|device_id| crate::audio_loopback::device_aliases::display_name(&device_id));
                 let _emit_result = app_handle.emit("loopback-transcription", serde_json::json!({
                     "text": cleaned_text,
                     "timestamp": chrono::Utc::now().timestamp_millis(),
                     "source": "loopback",
                     "confidence": estimated_confidence,
-                    "audioLevel": db_level
+                    "audioLevel": db_level,
+                    "deviceName": device_name,
+                    // Stored with the message as its `timings` for word highlighting
+                    "timings": crate::data::types::TranscriptTimings {
+                        segments: result.segments.clone(),
+                        language_probability: result.language_probability,
+                    }
                 }));
                 
                 return Ok(cleaned_text.to_string());
@@ -135,7 +146,8 @@ pub fn process_audio_chunk(
     bits_per_sample: u16,
     channels: u16,
     input_sample_rate: u32,
-    output_sample_rate: u32
+    output_sample_rate: u32,
+    downmix: Option<&DownmixMatrix>,
 ) -> Vec<f32> {
     if audio_data.is_empty() || channels == 0 || (bits_per_sample != 16 && bits_per_sample != 32) {
         // println!("[CHUNK] Invalid input: empty={}, channels={}, bits={}", 
@@ -174,8 +186,10 @@ pub fn process_audio_chunk(
         _ => return Vec::new()
     }
     
-    // Step 2: EXACT Python stereo handling logic
-    let mut audio_mono = if channels == 2 {
+    // Step 2: Channel handling - a configured matrix wins, otherwise the Python stereo logic
+    let mut audio_mono = if let Some(matrix) = downmix.filter(|m| m.channels() == channels) {
+        downmix_i16(&i16_samples, matrix)
+    } else if channels == 2 {
         // Reshape into stereo pairs
         let stereo_pairs: Vec<[i16; 2]> = i16_samples
             .chunks_exact(2)
@@ -210,6 +224,9 @@ pub fn process_audio_chunk(
                 left_channel
             }
         }
+    } else if channels > 2 {
+        // Interleaved surround / multi-mic frames; passing them through as mono garbles them
+        downmix_i16(&i16_samples, &DownmixMatrix::standard(channels))
     } else {
         i16_samples
     };
@@ -262,6 +279,13 @@ pub fn process_audio_chunk(
     audio_mono.iter().map(|&sample| sample as f32 / 32768.0).collect()
 }
 
+fn downmix_i16(interleaved: &[i16], matrix: &DownmixMatrix) -> Vec<i16> {
+    let samples: Vec<f32> = interleaved.iter().map(|&s| s as f32 / 32768.0).collect();
+    matrix.apply(&samples)
+        .into_iter()
+        .map(|s| (s * 32767.0).clamp(-32768.0, 32767.0) as i16)
+        .collect()
+}
 
 pub fn calculate_audio_level(audio_data: &[f32]) -> f32 {
     if audio_data.is_empty() {
@@ -312,8 +336,20 @@ fn log_transcription_debug(text: &str, rms: f32, db_level: f32) {
     }
 }
 
+// Units for repetition checks: words, or characters for unspaced scripts (Chinese, Japanese, Thai)
+fn analysis_units(text: &str, unspaced: bool) -> Vec<&str> {
+    if unspaced {
+        text.char_indices()
+            .filter(|(_, c)| !c.is_whitespace() && !c.is_ascii_punctuation())
+            .map(|(i, c)| &text[i..i + c.len_utf8()])
+            .collect()
+    } else {
+        text.split_whitespace().collect()
+    }
+}
+
 // Python-style quality filtering (more lenient)
-fn is_python_style_quality_ok(text: &str, confidence: f32) -> bool {
+fn is_python_style_quality_ok(text: &str, confidence: f32, unspaced: bool) -> bool {
     if text.len() < 2 {
         return false;
     }
@@ -324,7 +360,7 @@ fn is_python_style_quality_ok(text: &str, confidence: f32) -> bool {
     }
     
     // Simple repetition check (matching Python script)
-    let words: Vec<&str> = text.split_whitespace().collect();
+    let words = analysis_units(text, unspaced);
     if words.len() > 4 {
         let unique_words: std::collections::HashSet<&str> = words.iter().cloned().collect();
         let unique_ratio = unique_words.len() as f32 / words.len() as f32;
@@ -345,12 +381,12 @@ fn is_python_style_quality_ok(text: &str, confidence: f32) -> bool {
 }
 
 // Python-style confidence estimation (simpler and more lenient)
-fn estimate_python_style_confidence(text: &str) -> f32 {
+fn estimate_python_style_confidence(text: &str, unspaced: bool) -> f32 {
     if text.len() < 2 {
         return 0.2;
     }
     
-    let words: Vec<&str> = text.split_whitespace().collect();
+    let words = analysis_units(text, unspaced);
     if words.is_empty() {
         return 0.2;
     }
@@ -0,0 +1,212 @@
+// src-tauri/src/audio_loopback/capture_limits.rs
+// Maximum capture duration and "still recording?" reminders, so a forgotten capture doesn't record all day
+use crate::audio_loopback::types::CAPTURE_STATE;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+// Warn this long before an automatic stop so the user can extend the capture
+const STOP_WARNING_LEAD: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptureLimits {
+    pub max_duration: Option<Duration>,
+    pub reminder_interval: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+enum LimitAction {
+    Continue,
+    Remind,
+    WarnBeforeStop(Duration),
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureTimerStatus {
+    pub capturing: bool,
+    pub elapsed_seconds: u64,
+    /// Includes any extension; `None` when captures aren't limited
+    pub max_seconds: Option<u64>,
+    pub remaining_seconds: Option<u64>,
+    pub reminder_interval_seconds: Option<u64>,
+}
+
+fn minutes(value: u32) -> Option<Duration> {
+    (value > 0).then(|| Duration::from_secs(value as u64 * 60))
+}
+
+pub fn configured_limits() -> CaptureLimits {
+    let settings = crate::audio_loopback::settings::read_audio_settings()
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    CaptureLimits {
+        max_duration: minutes(settings.maxCaptureMinutes),
+        reminder_interval: minutes(settings.captureReminderMinutes),
+    }
+}
+
+fn next_action(
+    elapsed: Duration,
+    extended_by: Duration,
+    reminders_sent: u32,
+    stop_warning_sent: bool,
+    limits: CaptureLimits,
+) -> LimitAction {
+    if let Some(max) = limits.max_duration.map(|max| max + extended_by) {
+        if elapsed >= max {
+            return LimitAction::Stop;
+        }
+        let remaining = max - elapsed;
+        if !stop_warning_sent && remaining <= STOP_WARNING_LEAD {
+            return LimitAction::WarnBeforeStop(remaining);
+        }
+    }
+    if let Some(interval) = limits.reminder_interval {
+        if elapsed >= interval * (reminders_sent + 1) {
+            return LimitAction::Remind;
+        }
+    }
+    LimitAction::Continue
+}
+
+/// Start the duration clock for a capture that was just marked as running
+pub fn begin_capture_timer(app_handle: &AppHandle) {
+    let generation = {
+        let Ok(mut state) = CAPTURE_STATE.lock() else { return };
+        state.started_at = Some(Instant::now());
+        state.timer_generation += 1;
+        state.extended_by = Duration::ZERO;
+        state.reminders_sent = 0;
+        state.stop_warning_sent = false;
+        state.timer_generation
+    };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            // Settings are re-read so a changed limit applies to the running capture
+            let limits = configured_limits();
+            let (action, elapsed) = {
+                let Ok(mut state) = CAPTURE_STATE.lock() else { return };
+                if !state.is_capturing || state.timer_generation != generation {
+                    return;
+                }
+                let Some(started_at) = state.started_at else { return };
+                let elapsed = started_at.elapsed();
+                let action = next_action(elapsed, state.extended_by, state.reminders_sent, state.stop_warning_sent, limits);
+                match action {
+                    LimitAction::Remind => state.reminders_sent += 1,
+                    LimitAction::WarnBeforeStop(_) => state.stop_warning_sent = true,
+                    _ => {}
+                }
+                (action, elapsed)
+            };
+
+            let elapsed_minutes = elapsed.as_secs() / 60;
+            match action {
+                LimitAction::Continue => {}
+                LimitAction::Remind => {
+                    let _ = app_handle.emit("capture-duration-reminder", serde_json::json!({
+                        "elapsedMinutes": elapsed_minutes,
+                        "message": format!("Still recording after {}?", format_elapsed(elapsed)),
+                    }));
+                }
+                LimitAction::WarnBeforeStop(remaining) => {
+                    let _ = app_handle.emit("capture-auto-stop-warning", serde_json::json!({
+                        "elapsedMinutes": elapsed_minutes,
+                        "remainingSeconds": remaining.as_secs(),
+                    }));
+                }
+                LimitAction::Stop => {
+                    println!("[CAPTURE] Stopping capture after {} (maximum duration reached)", format_elapsed(elapsed));
+                    let result = crate::audio_loopback::stop_audio_loopback_capture().await;
+                    let _ = app_handle.emit("capture-auto-stopped", serde_json::json!({
+                        "elapsedMinutes": elapsed_minutes,
+                        "error": result.err(),
+                    }));
+                    crate::accessibility::announce(
+                        &app_handle,
+                        crate::accessibility::AnnouncementCategory::Custom,
+                        crate::accessibility::AnnouncementPriority::Assertive,
+                        "Audio capture stopped after reaching the maximum duration",
+                    );
+                    return;
+                }
+            }
+        }
+    });
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let minutes = elapsed.as_secs() / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[crate::command]
+pub fn get_capture_timer() -> Result<CaptureTimerStatus, String> {
+    let limits = configured_limits();
+    let state = CAPTURE_STATE.lock().map_err(|_| "Failed to read capture state".to_string())?;
+    let elapsed = match (state.is_capturing, state.started_at) {
+        (true, Some(started_at)) => started_at.elapsed(),
+        _ => Duration::ZERO,
+    };
+    let max = limits.max_duration.map(|max| max + state.extended_by);
+    Ok(CaptureTimerStatus {
+        capturing: state.is_capturing,
+        elapsed_seconds: elapsed.as_secs(),
+        max_seconds: max.map(|max| max.as_secs()),
+        remaining_seconds: max.map(|max| max.saturating_sub(elapsed).as_secs()),
+        reminder_interval_seconds: limits.reminder_interval.map(|interval| interval.as_secs()),
+    })
+}
+
+/// Push the automatic stop of the running capture back by `minutes`
+#[crate::command]
+pub fn extend_capture(minutes: u32) -> Result<CaptureTimerStatus, String> {
+    if minutes == 0 || minutes > 24 * 60 {
+        return Err("Extension must be between 1 and 1440 minutes".to_string());
+    }
+    {
+        let mut state = CAPTURE_STATE.lock().map_err(|_| "Failed to update capture state".to_string())?;
+        if !state.is_capturing {
+            return Err("No capture in progress".to_string());
+        }
+        state.extended_by += Duration::from_secs(minutes as u64 * 60);
+        state.stop_warning_sent = false;
+    }
+    get_capture_timer()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_action_reminds_warns_and_stops() {
+        let limits = CaptureLimits {
+            max_duration: minutes(240),
+            reminder_interval: minutes(120),
+        };
+        let at = |m: u64| Duration::from_secs(m * 60);
+
+        assert_eq!(next_action(at(60), Duration::ZERO, 0, false, limits), LimitAction::Continue);
+        assert_eq!(next_action(at(121), Duration::ZERO, 0, false, limits), LimitAction::Remind);
+        assert_eq!(next_action(at(121), Duration::ZERO, 1, false, limits), LimitAction::Continue);
+        assert_eq!(next_action(at(236), Duration::ZERO, 1, false, limits), LimitAction::WarnBeforeStop(at(4)));
+        assert_eq!(next_action(at(240), Duration::ZERO, 2, true, limits), LimitAction::Stop);
+        // An extension moves the stop back
+        assert_eq!(next_action(at(240), at(30), 2, true, limits), LimitAction::Continue);
+
+        let unlimited = CaptureLimits { max_duration: None, reminder_interval: None };
+        assert_eq!(next_action(at(24 * 60), Duration::ZERO, 0, false, unlimited), LimitAction::Continue);
+    }
+}
@@ -0,0 +1,205 @@
+// src-tauri/src/audio_loopback/channel_mixer.rs
+// Downmixing of interleaved multi-channel audio (surround interfaces, mic arrays) to mono
+
+use serde::{Deserialize, Serialize};
+
+// ITU-R BS.775 style gains for folding centre and surrounds into the front pair
+const CENTER_GAIN: f32 = std::f32::consts::FRAC_1_SQRT_2;
+const SURROUND_GAIN: f32 = 0.5;
+
+/// Per-device channel handling, persisted with the audio settings.
+/// `weights` wins over `selectedChannels`; with neither the default matrix is used.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelMixConfig {
+    /// Zero-based channels to average, e.g. `[2]` for input 3 of an interface
+    pub selected_channels: Vec<u16>,
+    /// One gain per input channel
+    pub weights: Vec<f32>,
+}
+
+impl ChannelMixConfig {
+    pub fn is_default(&self) -> bool {
+        self.selected_channels.is_empty() && self.weights.is_empty()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.weights.iter().any(|w| !w.is_finite()) {
+            return Err("Downmix weights must be finite numbers".to_string());
+        }
+        if !self.weights.is_empty() && self.weights.iter().all(|w| *w == 0.0) {
+            return Err("Downmix weights can't all be zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A 1 x N matrix mapping N interleaved input channels to one mono channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownmixMatrix {
+    weights: Vec<f32>,
+}
+
+impl DownmixMatrix {
+    pub fn from_weights(weights: Vec<f32>) -> Result<Self, String> {
+        if weights.is_empty() {
+            return Err("Downmix matrix needs at least one channel".to_string());
+        }
+        ChannelMixConfig { selected_channels: Vec::new(), weights: weights.clone() }.validate()?;
+        Ok(Self { weights })
+    }
+
+    /// Equal-weight average of every channel
+    pub fn average(channels: u16) -> Self {
+        let channels = channels.max(1) as usize;
+        Self { weights: vec![1.0 / channels as f32; channels] }
+    }
+
+    /// Average only the given channels and ignore the rest
+    pub fn select(channels: u16, selected: &[u16]) -> Result<Self, String> {
+        if let Some(&bad) = selected.iter().find(|&&ch| ch >= channels) {
+            return Err(format!("Channel {} is out of range for a {}-channel device", bad, channels));
+        }
+        if selected.is_empty() {
+            return Err("Select at least one channel".to_string());
+        }
+        let mut weights = vec![0.0; channels as usize];
+        for &ch in selected {
+            weights[ch as usize] = 1.0;
+        }
+        let total: f32 = weights.iter().sum();
+        weights.iter_mut().for_each(|w| *w /= total);
+        Ok(Self { weights })
+    }
+
+    /// Sensible default for a channel count: surround layouts (WAVE channel order)
+    /// drop LFE and attenuate centre/surrounds, anything else is averaged
+    pub fn standard(channels: u16) -> Self {
+        let weights = match channels {
+            // FL FR FC LFE BL BR
+            6 => vec![1.0, 1.0, CENTER_GAIN, 0.0, SURROUND_GAIN, SURROUND_GAIN],
+            // FL FR FC LFE BL BR SL SR
+            8 => vec![1.0, 1.0, CENTER_GAIN, 0.0, SURROUND_GAIN, SURROUND_GAIN, SURROUND_GAIN, SURROUND_GAIN],
+            _ => return Self::average(channels),
+        };
+        let total: f32 = weights.iter().sum();
+        Self { weights: weights.into_iter().map(|w| w / total).collect() }
+    }
+
+    /// Build the matrix for a device from its stored config, falling back to the
+    /// standard matrix when the config doesn't fit the actual channel count
+    pub fn for_config(channels: u16, config: &ChannelMixConfig) -> Self {
+        let configured = if !config.weights.is_empty() {
+            if config.weights.len() == channels as usize {
+                Self::from_weights(config.weights.clone())
+            } else {
+                Err(format!(
+                    "{} downmix weights configured for a {}-channel device",
+                    config.weights.len(),
+                    channels
+                ))
+            }
+        } else if !config.selected_channels.is_empty() {
+            Self::select(channels, &config.selected_channels)
+        } else {
+            return Self::standard(channels);
+        };
+
+        configured.unwrap_or_else(|e| {
+            eprintln!("[CHANNEL_MIXER] Ignoring channel mix config: {}", e);
+            Self::standard(channels)
+        })
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.weights.len() as u16
+    }
+
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Downmix interleaved frames; a trailing partial frame is dropped
+    pub fn apply(&self, interleaved: &[f32]) -> Vec<f32> {
+        interleaved
+            .chunks_exact(self.weights.len())
+            .map(|frame| frame.iter().zip(&self.weights).map(|(s, w)| s * w).sum::<f32>().clamp(-1.0, 1.0))
+            .collect()
+    }
+}
+
+/// The device's configured matrix, or `None` when the user hasn't customised it
+pub fn configured_matrix(device_id: &str, channels: u16) -> Option<DownmixMatrix> {
+    let settings = crate::audio_loopback::settings::read_audio_settings().ok().flatten()?;
+    let config = settings.deviceChannelMix.get(device_id)?;
+    if config.is_default() {
+        return None;
+    }
+    Some(DownmixMatrix::for_config(channels, config))
+}
+
+#[crate::command]
+pub fn get_device_channel_mix(device_id: String) -> Result<ChannelMixConfig, String> {
+    let settings = crate::audio_loopback::settings::read_audio_settings()?.unwrap_or_default();
+    Ok(settings.deviceChannelMix.get(&device_id).cloned().unwrap_or_default())
+}
+
+/// Persist channel selection / downmix weights for a device; applies from the next capture
+#[crate::command]
+pub fn set_device_channel_mix(device_id: String, config: ChannelMixConfig) -> Result<ChannelMixConfig, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    if device_id.trim().is_empty() {
+        return Err("Device id is required".to_string());
+    }
+    config.validate()?;
+
+    let mut settings = crate::audio_loopback::settings::read_audio_settings()?.unwrap_or_default();
+    if config.is_default() {
+        settings.deviceChannelMix.remove(&device_id);
+    } else {
+        settings.deviceChannelMix.insert(device_id, config.clone());
+    }
+    crate::audio_loopback::settings::write_audio_settings(&settings)?;
+    Ok(config)
+}
+
+/// The matrix that would be used for a channel count, so the UI can show and edit it
+#[crate::command]
+pub fn get_downmix_matrix(channels: u16, config: Option<ChannelMixConfig>) -> Result<Vec<f32>, String> {
+    if channels == 0 {
+        return Err("Channel count must be at least 1".to_string());
+    }
+    let matrix = match config {
+        Some(config) => DownmixMatrix::for_config(channels, &config),
+        None => DownmixMatrix::standard(channels),
+    };
+    Ok(matrix.weights().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surround_downmix_drops_lfe() {
+        let matrix = DownmixMatrix::standard(6);
+        // One frame with signal only on the LFE channel, then one on front left
+        let frames = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let mono = matrix.apply(&frames);
+        assert_eq!(mono.len(), 2);
+        assert_eq!(mono[0], 0.0);
+        assert!(mono[1] > 0.0);
+        assert!((matrix.weights().iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_channel_selection_and_fallback() {
+        let config = ChannelMixConfig { selected_channels: vec![2], weights: Vec::new() };
+        let matrix = DownmixMatrix::for_config(4, &config);
+        assert_eq!(matrix.apply(&[0.1, 0.2, 0.3, 0.4]), vec![0.3]);
+
+        // Selection doesn't fit a stereo device, so the default average is used
+        let matrix = DownmixMatrix::for_config(2, &config);
+        assert_eq!(matrix, DownmixMatrix::average(2));
+    }
+}
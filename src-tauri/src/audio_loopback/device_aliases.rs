@@ -0,0 +1,87 @@
+// src-tauri/src/audio_loopback/device_aliases.rs
+// User-assigned friendly names for audio devices, stored against stable device UIDs
+
+use crate::audio_loopback::types::AudioLoopbackDevice;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub const MAX_ALIAS_LENGTH: usize = 64;
+
+lazy_static::lazy_static! {
+    // Last enumerated devices by id, so capture code can name a device without re-enumerating
+    static ref KNOWN_DEVICES: Mutex<HashMap<String, (String, String)>> = Mutex::new(HashMap::new());
+}
+
+pub fn validate_alias(alias: &str) -> Result<(), String> {
+    if alias.trim().is_empty() {
+        return Err("Device alias must not be blank".to_string());
+    }
+    if alias.chars().count() > MAX_ALIAS_LENGTH {
+        return Err(format!("Device alias must be at most {} characters", MAX_ALIAS_LENGTH));
+    }
+    Ok(())
+}
+
+fn stored_aliases() -> HashMap<String, String> {
+    crate::audio_loopback::settings::read_audio_settings()
+        .ok()
+        .flatten()
+        .map(|settings| settings.deviceAliases)
+        .unwrap_or_default()
+}
+
+/// Fill in `alias` and `display_name` for freshly enumerated devices
+pub fn apply_aliases(devices: &mut [AudioLoopbackDevice]) {
+    let aliases = stored_aliases();
+    let mut known = KNOWN_DEVICES.lock().ok();
+    for device in devices.iter_mut() {
+        device.alias = aliases.get(&device.uid).cloned();
+        device.display_name = device.alias.clone().unwrap_or_else(|| device.name.clone());
+        if let Some(known) = known.as_mut() {
+            known.insert(device.id.clone(), (device.uid.clone(), device.name.clone()));
+        }
+    }
+}
+
+/// Friendly name for a device id: its alias, else the system name from the last enumeration
+pub fn display_name(device_id: &str) -> Option<String> {
+    let (uid, name) = KNOWN_DEVICES.lock().ok()?.get(device_id).cloned()?;
+    Some(stored_aliases().get(&uid).cloned().unwrap_or(name))
+}
+
+/// "Alias (id)" for log lines
+pub fn describe_device(device_id: &str) -> String {
+    match display_name(device_id) {
+        Some(name) => format!("{} ({})", name, device_id),
+        None => device_id.to_string(),
+    }
+}
+
+#[crate::command]
+pub fn get_device_aliases() -> Result<HashMap<String, String>, String> {
+    Ok(crate::audio_loopback::settings::read_audio_settings()?
+        .unwrap_or_default()
+        .deviceAliases)
+}
+
+/// Name a device by its UID; `None` or an empty alias removes it
+#[crate::command]
+pub fn set_device_alias(device_uid: String, alias: Option<String>) -> Result<HashMap<String, String>, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    if device_uid.trim().is_empty() {
+        return Err("Device UID is required".to_string());
+    }
+
+    let mut settings = crate::audio_loopback::settings::read_audio_settings()?.unwrap_or_default();
+    match alias.map(|alias| alias.trim().to_string()).filter(|alias| !alias.is_empty()) {
+        Some(alias) => {
+            validate_alias(&alias)?;
+            settings.deviceAliases.insert(device_uid, alias);
+        }
+        None => {
+            settings.deviceAliases.remove(&device_uid);
+        }
+    }
+    crate::audio_loopback::settings::write_audio_settings(&settings)?;
+    Ok(settings.deviceAliases)
+}
@@ -0,0 +1,176 @@
+// src-tauri/src/audio_loopback/device_monitor.rs
+// Device hot-plug monitoring with automatic capture failover
+
+use crate::audio_loopback::types::CAPTURE_STATE;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+#[cfg(target_os = "windows")]
+use crate::audio_loopback::windows::{
+    auto_select_best_device, register_device_change_listener, start_audio_loopback_capture,
+    stop_audio_loopback_capture, WASAPILoopbackEnumerator as PlatformEnumerator,
+};
+#[cfg(target_os = "macos")]
+use crate::audio_loopback::macos::{
+    auto_select_best_device, register_device_change_listener, start_audio_loopback_capture,
+    stop_audio_loopback_capture, CoreAudioLoopbackEnumerator as PlatformEnumerator,
+};
+
+// Platform notifications usually arrive in bursts (state change + default change
+// for every role), so we wait for things to settle before re-enumerating.
+const FAILOVER_DEBOUNCE: Duration = Duration::from_millis(500);
+
+static MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+static FAILOVER_PENDING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DeviceChangeKind {
+    Added,
+    Removed,
+    StateChanged,
+    DefaultChanged,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceChangeEvent {
+    pub kind: DeviceChangeKind,
+    pub device_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceChangedPayload {
+    pub kind: DeviceChangeKind,
+    pub device_id: Option<String>,
+    pub was_capturing: bool,
+    pub previous_device_id: Option<String>,
+    pub new_device_id: Option<String>,
+    pub failed_over: bool,
+    pub error: Option<String>,
+}
+
+/// Register the platform device-change listener once for the lifetime of the app.
+pub fn start_device_monitor(app_handle: AppHandle) -> anyhow::Result<()> {
+    if MONITOR_STARTED.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        let result = register_device_change_listener(move |event| {
+            handle_device_change(app_handle.clone(), event);
+        });
+        if result.is_err() {
+            MONITOR_STARTED.store(false, Ordering::SeqCst);
+        }
+        result
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = app_handle;
+        Ok(())
+    }
+}
+
+/// Entry point for platform listeners. Called from OS notification threads, so
+/// all real work is pushed onto the async runtime.
+pub fn handle_device_change(app_handle: AppHandle, event: DeviceChangeEvent) {
+    if FAILOVER_PENDING.swap(true, Ordering::SeqCst) {
+        // A check is already scheduled and will observe this change too
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FAILOVER_DEBOUNCE).await;
+        FAILOVER_PENDING.store(false, Ordering::SeqCst);
+
+        let payload = evaluate_failover(app_handle.clone(), event).await;
+        if payload.failed_over {
+            println!(
+                "[DEVICE_MONITOR] Capture failed over from {:?} to {:?}",
+                payload.previous_device_id, payload.new_device_id
+            );
+        }
+        let _ = app_handle.emit("audio-device-changed", &payload);
+    });
+}
+
+async fn evaluate_failover(app_handle: AppHandle, event: DeviceChangeEvent) -> DeviceChangedPayload {
+    let (was_capturing, previous_device_id) = {
+        let state = CAPTURE_STATE.lock().unwrap();
+        (state.is_capturing, state.device_id.clone())
+    };
+
+    let mut payload = DeviceChangedPayload {
+        kind: event.kind,
+        device_id: event.device_id,
+        was_capturing,
+        previous_device_id: previous_device_id.clone(),
+        new_device_id: previous_device_id.clone(),
+        failed_over: false,
+        error: None,
+    };
+
+    let current_id = match (was_capturing, previous_device_id) {
+        (true, Some(id)) => id,
+        _ => return payload,
+    };
+
+    if is_device_available(current_id.clone()).await {
+        return payload;
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        let _ = stop_audio_loopback_capture().await;
+        payload.new_device_id = None;
+
+        match auto_select_best_device().await {
+            Ok(Some(device)) => {
+                match start_audio_loopback_capture(device.id.clone(), app_handle).await {
+                    Ok(_) => {
+                        payload.new_device_id = Some(device.id);
+                        payload.failed_over = true;
+                    }
+                    Err(e) => payload.error = Some(format!("Failed to restart capture: {}", e)),
+                }
+            }
+            Ok(None) => {
+                payload.error = Some("Capture device was removed and no fallback device is available".to_string());
+            }
+            Err(e) => payload.error = Some(e),
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = app_handle;
+    }
+
+    payload
+}
+
+async fn is_device_available(device_id: String) -> bool {
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        // Enumeration touches COM / Core Audio, keep it off the async workers
+        tokio::task::spawn_blocking(move || {
+            PlatformEnumerator::new()
+                .and_then(|enumerator| enumerator.find_device_by_id(&device_id))
+                .map(|device| device.is_some())
+                .unwrap_or(false)
+        })
+        .await
+        .unwrap_or(false)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = device_id;
+        true
+    }
+}
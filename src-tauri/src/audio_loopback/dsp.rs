@@ -0,0 +1,318 @@
+// src-tauri/src/audio_loopback/dsp.rs
+// Optional DSP chain: RNNoise denoising and NLMS acoustic echo cancellation on 16kHz mono audio
+
+use nnnoiseless::DenoiseState;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+
+// RNNoise works on 10ms frames at 48kHz; our pipeline runs at 16kHz
+const UPSAMPLE_FACTOR: usize = 3;
+const FRAME_SIZE_16K: usize = DenoiseState::FRAME_SIZE / UPSAMPLE_FACTOR;
+// 64ms of echo path at 16kHz covers typical speaker -> mic latency
+const AEC_TAPS: usize = 1024;
+const AEC_STEP_SIZE: f32 = 0.3;
+const AEC_REGULARIZATION: f32 = 1e-3;
+// Loopback audio kept around as the far-end reference for echo cancellation
+const REFERENCE_HISTORY_SAMPLES: usize = 16000 * 30;
+
+/// Key used when the frontend doesn't tell us which microphone recorded the audio
+pub const DEFAULT_MICROPHONE_KEY: &str = "default_microphone";
+
+lazy_static::lazy_static! {
+    static ref DSP_CONFIGS: RwLock<HashMap<String, DspConfig>> = RwLock::new(load_stored_configs());
+    static ref DSP_STATES: Mutex<HashMap<String, DspState>> = Mutex::new(HashMap::new());
+    static ref ECHO_REFERENCE: Mutex<VecDeque<f32>> = Mutex::new(VecDeque::with_capacity(REFERENCE_HISTORY_SAMPLES));
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DspConfig {
+    pub noise_suppression: bool,
+    /// Only meaningful for microphones; loopback audio is the echo reference itself
+    pub echo_cancellation: bool,
+}
+
+impl DspConfig {
+    fn is_enabled(&self) -> bool {
+        self.noise_suppression || self.echo_cancellation
+    }
+}
+
+#[derive(Default)]
+struct DspState {
+    denoiser: Option<Denoiser>,
+    echo_canceller: Option<EchoCanceller>,
+}
+
+/// Streaming RNNoise wrapper. Output is delayed by one 16kHz frame so every call
+/// returns exactly as many samples as it was given.
+struct Denoiser {
+    state: Box<DenoiseState<'static>>,
+    pending: Vec<f32>,
+    output: VecDeque<f32>,
+    last_input: f32,
+}
+
+impl Denoiser {
+    fn new() -> Self {
+        Self {
+            state: DenoiseState::new(),
+            pending: Vec::with_capacity(FRAME_SIZE_16K),
+            output: std::iter::repeat(0.0).take(FRAME_SIZE_16K).collect(),
+            last_input: 0.0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        self.pending.extend_from_slice(samples);
+
+        let mut upsampled = [0.0f32; DenoiseState::FRAME_SIZE];
+        let mut denoised = [0.0f32; DenoiseState::FRAME_SIZE];
+        while self.pending.len() >= FRAME_SIZE_16K {
+            // Linear interpolation up to 48kHz, in the i16 range RNNoise expects
+            let mut previous = self.last_input;
+            for (i, &sample) in self.pending[..FRAME_SIZE_16K].iter().enumerate() {
+                for k in 0..UPSAMPLE_FACTOR {
+                    let t = (k + 1) as f32 / UPSAMPLE_FACTOR as f32;
+                    upsampled[i * UPSAMPLE_FACTOR + k] = (previous + (sample - previous) * t) * 32768.0;
+                }
+                previous = sample;
+            }
+            self.last_input = previous;
+            self.pending.drain(..FRAME_SIZE_16K);
+
+            self.state.process_frame(&mut denoised, &upsampled);
+            self.output.extend(
+                denoised
+                    .iter()
+                    .skip(UPSAMPLE_FACTOR - 1)
+                    .step_by(UPSAMPLE_FACTOR)
+                    .map(|&s| (s / 32768.0).clamp(-1.0, 1.0)),
+            );
+        }
+
+        for sample in samples.iter_mut() {
+            *sample = self.output.pop_front().unwrap_or(0.0);
+        }
+    }
+}
+
+/// Normalized LMS adaptive filter that predicts the echo of the far-end
+/// reference in the near-end signal and subtracts it.
+struct EchoCanceller {
+    weights: Vec<f32>,
+    history: Vec<f32>,
+    position: usize,
+    energy: f32,
+}
+
+impl EchoCanceller {
+    fn new(taps: usize) -> Self {
+        Self {
+            weights: vec![0.0; taps],
+            history: vec![0.0; taps],
+            position: 0,
+            energy: 0.0,
+        }
+    }
+
+    /// Forget the reference history but keep the learned echo path
+    fn reset_history(&mut self) {
+        self.history.iter_mut().for_each(|x| *x = 0.0);
+        self.position = 0;
+        self.energy = 0.0;
+    }
+
+    fn process(&mut self, near: &mut [f32], reference: &[f32]) {
+        let taps = self.weights.len();
+        for (sample, &far) in near.iter_mut().zip(reference) {
+            let outgoing = self.history[self.position];
+            self.energy += far * far - outgoing * outgoing;
+            self.energy = self.energy.max(0.0);
+            self.history[self.position] = far;
+
+            // history[position] is the newest sample, walking backwards gets older ones
+            let mut estimate = 0.0;
+            for (i, weight) in self.weights.iter().enumerate() {
+                estimate += weight * self.history[(self.position + taps - i) % taps];
+            }
+
+            let error = *sample - estimate;
+            let step = AEC_STEP_SIZE * error / (self.energy + AEC_REGULARIZATION);
+            for (i, weight) in self.weights.iter_mut().enumerate() {
+                *weight += step * self.history[(self.position + taps - i) % taps];
+            }
+
+            self.position = (self.position + 1) % taps;
+            *sample = error.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+fn load_stored_configs() -> HashMap<String, DspConfig> {
+    crate::audio_loopback::settings::read_audio_settings()
+        .ok()
+        .flatten()
+        .map(|settings| settings.deviceDsp)
+        .unwrap_or_default()
+}
+
+fn config_for(device_key: &str) -> DspConfig {
+    DSP_CONFIGS
+        .read()
+        .ok()
+        .and_then(|configs| configs.get(device_key).copied())
+        .unwrap_or_default()
+}
+
+/// Replace the runtime DSP configuration. Stages that were switched off drop
+/// their state so re-enabling them starts from a clean filter.
+pub fn apply_device_dsp_configs(configs: &HashMap<String, DspConfig>) {
+    if let Ok(mut current) = DSP_CONFIGS.write() {
+        *current = configs.clone();
+    }
+    if let Ok(mut states) = DSP_STATES.lock() {
+        states.retain(|device_key, state| {
+            let config = configs.get(device_key).copied().unwrap_or_default();
+            if !config.noise_suppression {
+                state.denoiser = None;
+            }
+            if !config.echo_cancellation {
+                state.echo_canceller = None;
+            }
+            config.is_enabled()
+        });
+    }
+}
+
+/// Run the loopback side of the chain on a block of 16kHz mono samples.
+/// The unprocessed block is kept as the echo reference for microphones.
+pub fn process_loopback(device_id: &str, samples: &mut [f32]) {
+    if let Ok(mut reference) = ECHO_REFERENCE.lock() {
+        let overflow = (reference.len() + samples.len()).saturating_sub(REFERENCE_HISTORY_SAMPLES);
+        reference.drain(..overflow.min(reference.len()));
+        reference.extend(samples.iter().rev().take(REFERENCE_HISTORY_SAMPLES).rev());
+    }
+
+    let config = config_for(device_id);
+    if !config.noise_suppression {
+        return;
+    }
+
+    if let Ok(mut states) = DSP_STATES.lock() {
+        let state = states.entry(device_id.to_string()).or_default();
+        state.denoiser.get_or_insert_with(Denoiser::new).process(samples);
+    }
+}
+
+pub fn reset_echo_reference() {
+    if let Ok(mut reference) = ECHO_REFERENCE.lock() {
+        reference.clear();
+    }
+}
+
+/// Run the microphone side of the chain on a complete 16kHz mono recording:
+/// echo cancellation against recent loopback audio first, then denoising.
+pub fn process_microphone(device_id: Option<&str>, samples: &mut [f32]) {
+    let device_key = device_id.unwrap_or(DEFAULT_MICROPHONE_KEY);
+    let config = config_for(device_key);
+    if !config.is_enabled() || samples.is_empty() {
+        return;
+    }
+
+    // Recordings arrive once they are finished, so the matching far-end audio is
+    // the tail of the reference history.
+    let reference: Option<Vec<f32>> = if config.echo_cancellation {
+        ECHO_REFERENCE.lock().ok().and_then(|history| {
+            if history.is_empty() {
+                return None;
+            }
+            let available = history.len().min(samples.len());
+            let mut aligned = vec![0.0; samples.len() - available];
+            aligned.extend(history.iter().skip(history.len() - available));
+            Some(aligned)
+        })
+    } else {
+        None
+    };
+
+    if let Ok(mut states) = DSP_STATES.lock() {
+        let state = states.entry(device_key.to_string()).or_default();
+
+        if let Some(reference) = reference {
+            let canceller = state.echo_canceller.get_or_insert_with(|| EchoCanceller::new(AEC_TAPS));
+            canceller.reset_history();
+            canceller.process(samples, &reference);
+        }
+
+        if config.noise_suppression {
+            // Each recording is independent, so don't carry frames over between them
+            let mut denoiser = Denoiser::new();
+            denoiser.process(samples);
+            // Flush the frame of delay the streaming denoiser introduces
+            let mut tail = vec![0.0; FRAME_SIZE_16K];
+            denoiser.process(&mut tail);
+            let flushed: Vec<f32> = samples.iter().copied().chain(tail).collect();
+            let len = samples.len();
+            samples.copy_from_slice(&flushed[FRAME_SIZE_16K..FRAME_SIZE_16K + len]);
+        }
+    }
+}
+
+#[crate::command]
+pub fn get_device_dsp_config(device_id: String) -> Result<DspConfig, String> {
+    Ok(config_for(&device_id))
+}
+
+/// Toggle DSP stages for a device at runtime and persist them with the audio settings
+#[crate::command]
+pub fn set_device_dsp_config(device_id: String, config: DspConfig) -> Result<DspConfig, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    if device_id.trim().is_empty() {
+        return Err("Device id is required".to_string());
+    }
+
+    let mut settings = crate::audio_loopback::settings::read_audio_settings()?.unwrap_or_default();
+    if config.is_enabled() {
+        settings.deviceDsp.insert(device_id, config);
+    } else {
+        settings.deviceDsp.remove(&device_id);
+    }
+    crate::audio_loopback::settings::write_audio_settings(&settings)?;
+
+    apply_device_dsp_configs(&settings.deviceDsp);
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denoiser_preserves_block_length() {
+        let mut denoiser = Denoiser::new();
+        for len in [1usize, 159, 160, 161, 1000] {
+            let mut block = vec![0.1; len];
+            denoiser.process(&mut block);
+            assert_eq!(block.len(), len);
+            assert!(block.iter().all(|s| s.is_finite() && s.abs() <= 1.0));
+        }
+    }
+
+    #[test]
+    fn test_echo_canceller_removes_delayed_reference() {
+        let mut canceller = EchoCanceller::new(64);
+        let reference: Vec<f32> = (0..16000).map(|i| ((i * 7919) % 200) as f32 / 200.0 - 0.5).collect();
+        // Near end hears the reference attenuated and delayed by 10 samples
+        let mut near: Vec<f32> = (0..16000)
+            .map(|i| if i >= 10 { reference[i - 10] * 0.6 } else { 0.0 })
+            .collect();
+        let input_energy: f32 = near[12000..].iter().map(|x| x * x).sum();
+
+        canceller.process(&mut near, &reference);
+
+        let residual_energy: f32 = near[12000..].iter().map(|x| x * x).sum();
+        assert!(residual_energy < input_energy * 0.01, "{} vs {}", residual_energy, input_energy);
+    }
+}
@@ -0,0 +1,134 @@
+// src-tauri/src/audio_loopback/macos/aggregate_janitor.rs
+// Lifecycle management for the aggregate devices we create, including cleanup after crashes
+
+use super::core_audio_bindings::{
+    create_aggregate_device, destroy_aggregate_device, get_audio_device_ids,
+    get_default_input_device, get_device_name, get_device_transport_type, get_device_uid,
+    AggregateDevice,
+};
+use anyhow::Result;
+use objc2_core_audio::{kAudioDeviceTransportTypeAggregate, AudioObjectID};
+use std::sync::Mutex;
+
+/// Every aggregate device we create gets a UID starting with this prefix, so
+/// devices leaked by a crashed session can be recognised on the next start.
+pub const AGGREGATE_UID_PREFIX: &str = "com.enteract.aggregate.";
+// UID prefix used by builds before the janitor existed
+const LEGACY_UID_PREFIX: &str = "enteract-microphone-";
+
+lazy_static::lazy_static! {
+    pub static ref AGGREGATE_JANITOR: Mutex<AggregateDeviceJanitor> = Mutex::new(AggregateDeviceJanitor::new());
+}
+
+/// Owns the aggregate devices created by this process and destroys them when
+/// dropped or when `teardown` is called from the app exit hook.
+pub struct AggregateDeviceJanitor {
+    devices: Vec<AggregateDevice>,
+}
+
+impl AggregateDeviceJanitor {
+    pub fn new() -> Self {
+        Self { devices: Vec::new() }
+    }
+
+    /// Create a tagged aggregate device and take ownership of it
+    pub fn create(&mut self, purpose: &str, device_name: String) -> Result<AudioObjectID> {
+        let uid = format!(
+            "{}{}.{}.{}",
+            AGGREGATE_UID_PREFIX,
+            purpose,
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        );
+        let device = create_aggregate_device(device_name, uid)?;
+        let id = device.id();
+        self.devices.push(device);
+        Ok(id)
+    }
+
+    pub fn owned_device(&self, id: AudioObjectID) -> Option<&AggregateDevice> {
+        self.devices.iter().find(|device| device.id() == id)
+    }
+
+    /// Destroy tagged aggregate devices that this process doesn't own.
+    /// Returns how many were removed.
+    pub fn clean_stale(&self) -> Result<usize> {
+        let mut removed = 0;
+        for device_id in get_audio_device_ids()? {
+            if self.owned_device(device_id).is_some() {
+                continue;
+            }
+            if get_device_transport_type(device_id)? != kAudioDeviceTransportTypeAggregate {
+                continue;
+            }
+            if !is_enteract_device(device_id) {
+                continue;
+            }
+            match destroy_aggregate_device(device_id) {
+                Ok(()) => removed += 1,
+                Err(e) => eprintln!("[AGGREGATE_JANITOR] Failed to destroy stale device {}: {}", device_id, e),
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Destroy every device this process created
+    pub fn teardown(&mut self) {
+        for device in self.devices.drain(..) {
+            if let Err(e) = destroy_aggregate_device(device.id()) {
+                eprintln!("[AGGREGATE_JANITOR] Failed to destroy {}: {}", device.name(), e);
+            }
+        }
+    }
+}
+
+impl Default for AggregateDeviceJanitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for AggregateDeviceJanitor {
+    fn drop(&mut self) {
+        self.teardown();
+    }
+}
+
+fn is_enteract_device(device_id: AudioObjectID) -> bool {
+    if let Ok(uid) = get_device_uid(device_id) {
+        if uid.starts_with(AGGREGATE_UID_PREFIX) || uid.starts_with(LEGACY_UID_PREFIX) {
+            return true;
+        }
+    }
+    // Very old builds only marked devices by name
+    get_device_name(device_id)
+        .map(|name| name.to_lowercase().contains("enteract"))
+        .unwrap_or(false)
+}
+
+pub fn clean_stale_aggregate_devices() -> Result<usize> {
+    AGGREGATE_JANITOR
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Aggregate device janitor is poisoned"))?
+        .clean_stale()
+}
+
+pub fn create_microphone_aggregate_device() -> Result<AudioObjectID> {
+    let mut janitor = AGGREGATE_JANITOR
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Aggregate device janitor is poisoned"))?;
+    let id = janitor.create("microphone", "Enteract Microphone Aggregate Device".to_string())?;
+
+    let default_input_device = get_default_input_device()?;
+    if let Some(device) = janitor.owned_device(id) {
+        device.add_sub_device(default_input_device)?;
+    }
+    Ok(id)
+}
+
+/// Called from the app exit hook; statics are never dropped so Drop alone isn't enough
+pub fn teardown_aggregate_devices() {
+    if let Ok(mut janitor) = AGGREGATE_JANITOR.lock() {
+        janitor.teardown();
+    }
+}
@@ -1,4 +1,5 @@
 // src-tauri/src/audio_loopback/macos/audio_recorder.rs
+use crate::audio_loopback::channel_mixer::DownmixMatrix;
 use crate::audio_loopback::macos::core_audio_bindings::{
     catalog_device_streams, get_device_name_safe, DeviceStreamCatalog, StreamInfo,
 };
@@ -16,6 +17,21 @@ use tauri::AppHandle;
 /// Audio processor trait for processing captured audio
 pub trait AudioProcessor {
     fn process_audio(&self, samples: Vec<f32>, sample_rate: f32) -> Result<()>;
+
+    /// Receives interleaved frames with any channel count. The default folds
+    /// them to mono through `downmix`; override to keep individual channels.
+    fn process_frames(
+        &self,
+        interleaved: &[f32],
+        channels: u16,
+        sample_rate: f32,
+        downmix: &DownmixMatrix,
+    ) -> Result<()> {
+        if channels == 1 {
+            return self.process_audio(interleaved.to_vec(), sample_rate);
+        }
+        self.process_audio(downmix.apply(interleaved), sample_rate)
+    }
 }
 
 /// Simple WAV file writer
@@ -149,6 +165,8 @@ pub struct AudioRecorder {
     frame_counter: Mutex<u64>,
     recording_files: Mutex<HashMap<u32, WavFileWriter>>,
     recording_paths: Mutex<HashMap<u32, PathBuf>>,
+    // Configured downmix; None uses the standard matrix for the buffer's channel count
+    downmix: Mutex<Option<DownmixMatrix>>,
 }
 
 impl AudioRecorder {
@@ -163,6 +181,7 @@ impl AudioRecorder {
             frame_counter: Mutex::new(0),
             recording_files: Mutex::new(HashMap::new()),
             recording_paths: Mutex::new(HashMap::new()),
+            downmix: Mutex::new(None),
         }
     }
 
@@ -186,6 +205,11 @@ impl AudioRecorder {
         Ok(())
     }
 
+    /// Channel selection / downmix weights for multi-channel devices
+    pub fn set_downmix_matrix(&mut self, downmix: Option<DownmixMatrix>) {
+        *self.downmix.lock().unwrap() = downmix;
+    }
+
     pub fn get_device_id(&self) -> AudioObjectID {
         *self.device_id.lock().unwrap()
     }
@@ -400,32 +424,10 @@ impl AudioRecorder {
                     }
                 }
 
-                // Convert to mono samples (similar to Objective-C++ implementation)
-                let mut mono_samples = Vec::with_capacity(frames as usize);
-                for frame in 0..frames {
-                    let mut sample = 0.0f32;
-
-                    if channels == 1 {
-                        // Mono: use the single channel
-                        sample = *float_data.offset(frame as isize);
-                    } else if channels == 2 {
-                        // Stereo: average left and right channels
-                        let left = *float_data.offset((frame * 2) as isize);
-                        let right = *float_data.offset((frame * 2 + 1) as isize);
-                        sample = (left + right) * 0.5f32;
-                    } else {
-                        // Multi-channel: average all channels
-                        for ch in 0..channels {
-                            sample += *float_data.offset((frame * channels + ch) as isize);
-                        }
-                        sample /= channels as f32;
-                    }
-
-                    mono_samples.push(sample);
-                }
-
-                // Send audio data to our audio processor (replacing Whisper manager)
-                recorder.process_audio_data(mono_samples);
+                // Hand interleaved frames to the processor, which downmixes them
+                let interleaved =
+                    std::slice::from_raw_parts(float_data, (frames * channels) as usize);
+                recorder.process_audio_data(interleaved, channels as u16);
             }
         }
 
@@ -434,14 +436,19 @@ impl AudioRecorder {
     }
 
     /// Process audio data through the audio processor
-    fn process_audio_data(&self, samples: Vec<f32>) {
+    fn process_audio_data(&self, interleaved: &[f32], channels: u16) {
         // Get the current sample rate
         let sample_rate = self.get_current_sample_rate();
 
+        let configured = self.downmix.lock().ok().and_then(|downmix| {
+            downmix.as_ref().filter(|m| m.channels() == channels).cloned()
+        });
+        let downmix = configured.unwrap_or_else(|| DownmixMatrix::standard(channels));
+
         // Try to get the audio processor and process the audio
         if let Ok(processor_guard) = self.audio_processor.lock() {
             if let Some(processor) = processor_guard.as_ref() {
-                if let Err(e) = processor.process_audio(samples, sample_rate) {
+                if let Err(e) = processor.process_frames(interleaved, channels, sample_rate, &downmix) {
                     println!("[AudioRecorder] Error processing audio: {}", e);
                 }
             }
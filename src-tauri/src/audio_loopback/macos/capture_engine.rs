@@ -4,17 +4,25 @@
 use crate::audio_loopback::audio_processor::{
     calculate_audio_level, process_audio_chunk, process_audio_for_transcription,
 };
-use crate::audio_loopback::macos::audio_recorder::AudioRecorder;
+use crate::audio_loopback::channel_mixer::configured_matrix;
+use crate::audio_loopback::dsp::{process_loopback, reset_echo_reference};
+use crate::audio_loopback::session_recorder::push_loopback;
+use crate::audio_loopback::macos::audio_recorder::{AudioProcessor, AudioRecorder};
 use crate::audio_loopback::macos::device_enumerator::CoreAudioLoopbackEnumerator;
+use crate::audio_loopback::macos::screen_capture_kit::{
+    is_screen_capture_kit_available, ScreenCaptureKitCapture, SCK_SAMPLE_RATE,
+};
+use crate::audio_loopback::ring_buffer::{SampleRingBuffer, RING_BUFFER_CONFIG};
 use crate::audio_loopback::types::*;
 use anyhow::Result;
 use base64::prelude::*;
 use serde_json;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 use tokio::sync::mpsc;
 
-#[tauri::command]
+#[crate::command]
 pub async fn start_audio_loopback_capture(
     device_id: String,
     app_handle: AppHandle,
@@ -27,6 +35,23 @@ pub async fn start_audio_loopback_capture(
         }
     }
 
+    // Without Screen Recording access ScreenCaptureKit delivers silence, so refuse up front
+    if is_screen_capture_kit_available() {
+        let is_output = CoreAudioLoopbackEnumerator::new()
+            .and_then(|enumerator| enumerator.find_device_by_id(&device_id))
+            .ok()
+            .flatten()
+            .is_some_and(|device| device.device_type == DeviceType::Render);
+        if is_output {
+            crate::permissions::require(crate::permissions::PermissionKind::ScreenRecording)?;
+        }
+    }
+
+    println!(
+        "[CAPTURE] Starting loopback capture on {}",
+        crate::audio_loopback::device_aliases::describe_device(&device_id)
+    );
+
     // Create stop channel
     let (stop_tx, stop_rx) = mpsc::channel::<()>(1);
 
@@ -44,18 +69,31 @@ pub async fn start_audio_loopback_capture(
     {
         let mut state = CAPTURE_STATE.lock().unwrap();
         state.is_capturing = true;
+        state.device_id = Some(device_id);
         state.capture_handle = Some(handle);
         state.stop_tx = Some(stop_tx);
     }
+    crate::audio_loopback::capture_limits::begin_capture_timer(&app_handle);
+
+    crate::accessibility::announce(
+        &app_handle,
+        crate::accessibility::AnnouncementCategory::CaptureStarted,
+        crate::accessibility::AnnouncementPriority::Polite,
+        "System audio capture started",
+    );
 
     Ok("Audio capture started".to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn stop_audio_loopback_capture() -> Result<(), String> {
     let (stop_tx, handle) = {
         let mut state = CAPTURE_STATE.lock().unwrap();
         state.is_capturing = false;
+        state.device_id = None;
+        state.started_at = None;
+        // Stale far-end audio would only confuse the echo canceller
+        reset_echo_reference();
         (state.stop_tx.take(), state.capture_handle.take())
     };
 
@@ -72,18 +110,60 @@ pub async fn stop_audio_loopback_capture() -> Result<(), String> {
     Ok(())
 }
 
-// Main audio capture loop for macOS
-fn run_audio_capture_loop_sync(
-    device_id: String,
-    app_handle: AppHandle,
-    mut stop_rx: mpsc::Receiver<()>,
-) -> Result<()> {
-    let enumerator = CoreAudioLoopbackEnumerator::new()?;
-    let device_info = enumerator
-        .find_device_by_id(&device_id)?
-        .ok_or_else(|| anyhow::anyhow!("Device not found"))?;
+/// Hands IO proc samples to the processing loop without blocking the Core Audio thread
+struct RingBufferProcessor {
+    ring: Arc<SampleRingBuffer>,
+}
+
+impl AudioProcessor for RingBufferProcessor {
+    fn process_audio(&self, samples: Vec<f32>, _sample_rate: f32) -> Result<()> {
+        self.ring.push(&samples);
+        Ok(())
+    }
+}
+
+/// Where the samples for a capture come from
+enum CaptureBackend {
+    /// System mix through ScreenCaptureKit; no aggregate device or BlackHole needed
+    ScreenCaptureKit(ScreenCaptureKitCapture),
+    CoreAudio(AudioRecorder),
+}
+
+impl CaptureBackend {
+    fn stop(self) -> Result<()> {
+        match self {
+            CaptureBackend::ScreenCaptureKit(capture) => capture.stop(),
+            CaptureBackend::CoreAudio(mut audio_recorder) => audio_recorder.stop_io(),
+        }
+    }
+}
 
-    // Step 1: Basic AudioRecorder integration
+fn new_ring_buffer(device_rate: u32) -> Arc<SampleRingBuffer> {
+    let ring_config = RING_BUFFER_CONFIG.lock().unwrap().clone();
+    SampleRingBuffer::from_config(&ring_config, device_rate)
+}
+
+/// Output devices are captured through ScreenCaptureKit when the OS supports it,
+/// falling back to the Core Audio path on older systems or if SCK refuses to start
+fn start_capture_backend(
+    device_id: &str,
+    device_info: &AudioLoopbackDevice,
+    app_handle: &AppHandle,
+) -> Result<(CaptureBackend, Arc<SampleRingBuffer>, u32)> {
+    if device_info.device_type == DeviceType::Render && is_screen_capture_kit_available() {
+        let ring = new_ring_buffer(SCK_SAMPLE_RATE);
+        match ScreenCaptureKitCapture::start(ring.clone()) {
+            Ok(capture) => {
+                println!("[CAPTURE] Using ScreenCaptureKit backend");
+                return Ok((CaptureBackend::ScreenCaptureKit(capture), ring, SCK_SAMPLE_RATE));
+            }
+            Err(e) => {
+                eprintln!("[CAPTURE] ScreenCaptureKit unavailable, falling back to Core Audio: {}", e);
+            }
+        }
+    }
+
+    println!("[CAPTURE] Using Core Audio backend");
     let mut audio_recorder = AudioRecorder::new();
     audio_recorder.set_app_handle(app_handle.clone());
 
@@ -94,6 +174,7 @@ fn run_audio_capture_loop_sync(
 
     // Use the new adapt_to_device method which includes stream cataloging
     audio_recorder.adapt_to_device(device_object_id)?;
+    audio_recorder.set_downmix_matrix(configured_matrix(device_id, device_info.channels));
 
     // Log the discovered streams
     println!("[CAPTURE] Discovered streams:");
@@ -110,9 +191,36 @@ fn run_audio_capture_loop_sync(
         audio_recorder.get_current_sample_rate()
     );
 
+    // Bounded hand-off between the IO proc and this processing loop
+    let device_rate = audio_recorder.get_current_sample_rate() as u32;
+    let ring = new_ring_buffer(device_rate);
+    audio_recorder.set_audio_processor(Box::new(RingBufferProcessor { ring: ring.clone() }));
+    audio_recorder.start_io()?;
+
+    Ok((CaptureBackend::CoreAudio(audio_recorder), ring, device_rate))
+}
+
+// Main audio capture loop for macOS
+fn run_audio_capture_loop_sync(
+    device_id: String,
+    app_handle: AppHandle,
+    mut stop_rx: mpsc::Receiver<()>,
+) -> Result<()> {
+    let enumerator = CoreAudioLoopbackEnumerator::new()?;
+    let device_info = enumerator
+        .find_device_by_id(&device_id)?
+        .ok_or_else(|| anyhow::anyhow!("Device not found"))?;
+
+    let (backend, ring, device_rate) = start_capture_backend(&device_id, &device_info, &app_handle)?;
+    {
+        let mut state = CAPTURE_STATE.lock().unwrap();
+        state.ring_buffer = Some(ring.clone());
+    }
+
     let start_time = Instant::now();
     let mut total_samples = 0u64;
     let mut last_emit = Instant::now();
+    let mut raw_samples: Vec<f32> = Vec::with_capacity(device_rate as usize);
 
     // Transcription buffer setup (keep existing)
     let mut transcription_buffer: Vec<f32> = Vec::new();
@@ -123,26 +231,26 @@ fn run_audio_capture_loop_sync(
     let min_audio_length = 1.5;
     let min_audio_samples = (16000.0 * min_audio_length) as usize;
 
-    // Replace the simulation loop with:
     loop {
         if stop_rx.try_recv().is_ok() {
             break;
         }
 
-        // For now, keep the simulation but prepare for real audio
-        let simulated_audio = vec![0.0f32; 1024];
-
-        // TODO: Replace with real audio from AudioRecorder
-        // let audio_data = audio_recorder.get_audio_chunk()?;
+        raw_samples.clear();
+        if ring.pop_into(&mut raw_samples, device_rate as usize) == 0 {
+            std::thread::sleep(Duration::from_millis(10));
+            continue;
+        }
 
-        // Process audio (keep existing logic)
-        let processed_audio = process_audio_chunk(
-            &[], // Empty for now, will be real audio later
-            16,
-            1,
-            48000,
-            16000,
-        );
+        // Both backends already downmixed to mono f32; reuse the shared resampling path
+        let raw_bytes: Vec<u8> = raw_samples
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        let mut processed_audio = process_audio_chunk(&raw_bytes, 32, 1, device_rate, 16000, None);
+        // Optional denoising; also feeds the echo reference used for microphones
+        process_loopback(&device_id, &mut processed_audio);
+        push_loopback(&processed_audio);
 
         // Rest of the existing transcription logic stays the same...
         total_samples += processed_audio.len() as u64;
@@ -231,10 +339,8 @@ fn run_audio_capture_loop_sync(
 
             last_emit = now;
         }
-
-        // Sleep to simulate real-time processing
-        std::thread::sleep(Duration::from_millis(10));
     }
 
+    backend.stop()?;
     Ok(())
 }
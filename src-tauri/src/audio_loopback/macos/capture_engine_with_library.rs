@@ -12,7 +12,7 @@ use tokio::sync::mpsc;
 use base64::prelude::*;
 use serde_json;
 
-#[tauri::command]
+#[crate::command]
 pub async fn start_audio_loopback_capture_with_library(
     device_id: String,
     app_handle: AppHandle
@@ -99,7 +99,7 @@ pub async fn start_audio_loopback_capture_with_library(
     Ok("Audio capture started with library".to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn stop_audio_loopback_capture_with_library() -> Result<(), String> {
     // Update state
     {
@@ -114,7 +114,7 @@ pub async fn stop_audio_loopback_capture_with_library() -> Result<(), String> {
 }
 
 // Example of how to enumerate devices using the library
-#[tauri::command]
+#[crate::command]
 pub async fn enumerate_audio_devices_with_library() -> Result<Vec<audio_capture_lib::types::AudioDevice>, String> {
     let enumerator = audio_capture_lib::device_enumerator::create_device_enumerator()
         .map_err(|e| e.to_string())?;
@@ -126,7 +126,7 @@ pub async fn enumerate_audio_devices_with_library() -> Result<Vec<audio_capture_
 }
 
 // Example of how to create an aggregate device for system audio capture
-#[tauri::command]
+#[crate::command]
 pub async fn create_system_audio_aggregate_device() -> Result<String, String> {
     // This would use the aggregate device functionality from the library
     // to create a virtual device that can capture system audio
@@ -197,6 +197,38 @@ pub fn get_device_name(device_id: AudioObjectID) -> Result<String> {
     Ok(name)
 }
 
+pub fn get_device_uid(device_id: AudioObjectID) -> Result<String> {
+    let property_address = get_property_address(
+        kAudioDevicePropertyDeviceUID,
+        kAudioObjectPropertyScopeGlobal,
+        kAudioObjectPropertyElementMain,
+    );
+
+    let mut uid_size = std::mem::size_of::<*const CFString>() as u32;
+    let mut cf_string_ptr: *const CFString = std::ptr::null();
+    let property_result = unsafe {
+        AudioObjectGetPropertyData(
+            device_id,
+            NonNull::from(&property_address),
+            0,
+            std::ptr::null(),
+            NonNull::from(&mut uid_size),
+            NonNull::new(&mut cf_string_ptr as *mut *const CFString as *mut std::ffi::c_void)
+                .unwrap(),
+        )
+    };
+
+    if property_result != 0 || cf_string_ptr.is_null() {
+        return Err(anyhow::anyhow!(
+            "Failed to get UID for device {}: {}",
+            device_id,
+            property_result
+        ));
+    }
+
+    Ok(unsafe { (*cf_string_ptr).to_string() })
+}
+
 pub struct AggregateDevice {
     id: AudioObjectID,
     name: String,
@@ -238,6 +270,14 @@ impl AggregateDevice {
     //     // Implementation for registering listeners
     // }
 
+    pub fn id(&self) -> AudioObjectID {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn add_sub_device(&self, uid: AudioObjectID) -> Result<()> {
         let mut property_size = 0u32;
         let property_address = get_property_address(
@@ -1061,3 +1101,44 @@ pub fn destroy_io_proc_id(device_id: AudioObjectID, io_proc_id: AudioDeviceIOPro
 
     Ok(())
 }
+
+/// Property listener callback type (matching AudioObjectPropertyListenerProc)
+pub type AudioObjectPropertyListener = unsafe extern "C-unwind" fn(
+    inObjectID: AudioObjectID,
+    inNumberAddresses: u32,
+    inAddresses: std::ptr::NonNull<AudioObjectPropertyAddress>,
+    inClientData: *mut std::ffi::c_void,
+) -> i32;
+
+/// Add a property listener on the system object (matching AudioObjectAddPropertyListener call)
+pub fn add_system_property_listener(
+    selector: AudioObjectPropertySelector,
+    listener: AudioObjectPropertyListener,
+    client_data: *mut std::ffi::c_void,
+) -> Result<()> {
+    let address = get_property_address(
+        selector,
+        kAudioObjectPropertyScopeGlobal,
+        kAudioObjectPropertyElementMain,
+    );
+
+    let result = unsafe {
+        AudioObjectAddPropertyListener(
+            kAudioObjectSystemObject as AudioObjectID,
+            NonNull::from(&address),
+            Some(listener),
+            client_data,
+        )
+    };
+
+    if result != 0 {
+        return Err(anyhow::anyhow!(
+            "Failed to add property listener for selector 0x{:x}: {} (0x{:x})",
+            selector,
+            result,
+            result
+        ));
+    }
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
 // src-tauri/src/audio_loopback/macos/device_enumerator.rs
 use super::core_audio_bindings::{
-    device_has_output_streams, get_audio_device_ids, get_device_format, get_device_name,
-    is_default_device, AudioDeviceType,
+    add_system_property_listener, device_has_output_streams, get_audio_device_ids,
+    get_device_format, get_device_name, get_device_uid, is_default_device, AudioDeviceType,
 };
+use crate::audio_loopback::device_monitor::{DeviceChangeEvent, DeviceChangeKind};
 use crate::audio_loopback::types::{AudioLoopbackDevice, DeviceType, LoopbackMethod};
 use anyhow::Result;
 use objc2_core_audio::*;
@@ -62,7 +63,11 @@ impl CoreAudioLoopbackEnumerator {
 
         Ok(AudioLoopbackDevice {
             id: device_id.to_string(),
+            // AudioObjectIDs are reassigned across reboots; the UID is not
+            uid: get_device_uid(device_id).unwrap_or_else(|_| device_id.to_string()),
+            display_name: name.clone(),
             name,
+            alias: None,
             is_default,
             sample_rate,
             channels,
@@ -126,30 +131,87 @@ impl CoreAudioLoopbackEnumerator {
     }
 }
 
+// Core Audio hardware property listeners
+type DeviceChangeCallback = Box<dyn Fn(DeviceChangeEvent) + Send + Sync>;
+
+unsafe extern "C-unwind" fn device_change_listener(
+    _object_id: AudioObjectID,
+    number_addresses: u32,
+    addresses: std::ptr::NonNull<AudioObjectPropertyAddress>,
+    client_data: *mut std::ffi::c_void,
+) -> i32 {
+    if client_data.is_null() {
+        return 0;
+    }
+    let callback = &*(client_data as *const DeviceChangeCallback);
+    let addresses = std::slice::from_raw_parts(addresses.as_ptr(), number_addresses as usize);
+
+    for address in addresses {
+        let kind = if address.mSelector == kAudioHardwarePropertyDevices {
+            // The device list doesn't say what changed; the monitor re-checks availability
+            DeviceChangeKind::StateChanged
+        } else {
+            DeviceChangeKind::DefaultChanged
+        };
+        callback(DeviceChangeEvent {
+            kind,
+            device_id: None,
+        });
+    }
+
+    0
+}
+
+/// Register listeners for device list and default device changes. The callback
+/// is leaked intentionally since the listeners stay installed for the process lifetime.
+pub fn register_device_change_listener<F>(callback: F) -> Result<()>
+where
+    F: Fn(DeviceChangeEvent) + Send + Sync + 'static,
+{
+    let boxed: Box<DeviceChangeCallback> = Box::new(Box::new(callback));
+    let client_data = Box::into_raw(boxed) as *mut std::ffi::c_void;
+
+    for selector in [
+        kAudioHardwarePropertyDevices,
+        kAudioHardwarePropertyDefaultInputDevice,
+        kAudioHardwarePropertyDefaultOutputDevice,
+    ] {
+        add_system_property_listener(selector, device_change_listener, client_data)?;
+    }
+
+    Ok(())
+}
+
 // Tauri Commands - same interface as Windows
-#[tauri::command]
+#[crate::command]
 pub async fn enumerate_loopback_devices() -> Result<Vec<AudioLoopbackDevice>, String> {
     match CoreAudioLoopbackEnumerator::new() {
         Ok(enumerator) => match enumerator.enumerate_loopback_devices() {
-            Ok(devices) => Ok(devices),
+            Ok(mut devices) => {
+                crate::audio_loopback::device_aliases::apply_aliases(&mut devices);
+                Ok(devices)
+            }
             Err(e) => Err(format!("Failed to enumerate audio devices: {}", e)),
         },
         Err(e) => Err(format!("Failed to initialize audio enumerator: {}", e)),
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn auto_select_best_device() -> Result<Option<AudioLoopbackDevice>, String> {
     match CoreAudioLoopbackEnumerator::new() {
         Ok(enumerator) => match enumerator.auto_select_best_device() {
-            Ok(device) => Ok(device),
+            Ok(mut device) => {
+                crate::audio_loopback::device_aliases::apply_aliases(device.as_mut_slice());
+                Ok(device)
+            }
             Err(e) => Err(format!("Failed to auto-select device: {}", e)),
         },
         Err(e) => Err(format!("Failed to initialize audio enumerator: {}", e)),
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn test_audio_device(device_id: String) -> Result<bool, String> {
     match CoreAudioLoopbackEnumerator::new() {
         Ok(enumerator) => {
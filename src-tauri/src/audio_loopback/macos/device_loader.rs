@@ -1,6 +1,5 @@
 use super::core_audio_bindings::{
-    create_aggregate_device, destroy_aggregate_device, get_audio_device_ids,
-    get_default_input_device, get_device_name, get_device_transport_type, AggregateDevice,
+    get_audio_device_ids, get_device_transport_type, AggregateDevice,
 };
 use anyhow::Result;
 use objc2_core_audio::{kAudioDeviceTransportTypeAggregate, AudioObjectID};
@@ -34,29 +33,3 @@ pub fn load_devices() -> Result<DeviceLists> {
 
     Ok(device_lists)
 }
-
-pub fn clean_own_aggregate_devices() -> Result<()> {
-    let device_ids = get_audio_device_ids()?;
-
-    for device_id in device_ids {
-        let transport_type = get_device_transport_type(device_id)?;
-        if transport_type == kAudioDeviceTransportTypeAggregate {
-            let name = get_device_name(device_id)?;
-            // Destroy only our own aggregate devices.
-            if name.to_lowercase().contains("enteract") {
-                destroy_aggregate_device(device_id)?;
-            }
-        }
-    }
-
-    Ok(())
-}
-
-pub fn create_microphone_aggregate_device() -> Result<AggregateDevice> {
-    let uuid = uuid::Uuid::new_v4().to_string();
-    let device_name = format!("Enteract Microphone Aggregate Device {}", uuid);
-    let device = create_aggregate_device(device_name, format!("enteract-microphone-{}", uuid))?;
-    let default_input_device = get_default_input_device()?;
-    device.add_sub_device(default_input_device)?;
-    Ok(device)
-}
@@ -0,0 +1,142 @@
+// src-tauri/src/audio_loopback/macos/screen_capture_kit.rs
+// System audio capture through ScreenCaptureKit's audio output (macOS 13+)
+
+use crate::audio_loopback::ring_buffer::SampleRingBuffer;
+use anyhow::{Context, Result};
+use core_media_rs::cm_sample_buffer::CMSampleBuffer;
+use screencapturekit::{
+    shareable_content::SCShareableContent,
+    stream::{
+        configuration::SCStreamConfiguration, content_filter::SCContentFilter,
+        output_trait::SCStreamOutputTrait, output_type::SCStreamOutputType, SCStream,
+    },
+};
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+
+/// First macOS release whose ScreenCaptureKit can deliver audio
+const MIN_MACOS_MAJOR_VERSION: u32 = 13;
+pub const SCK_SAMPLE_RATE: u32 = 48000;
+const SCK_CHANNEL_COUNT: u32 = 2;
+
+static MACOS_MAJOR_VERSION: OnceLock<Option<u32>> = OnceLock::new();
+
+fn macos_major_version() -> Option<u32> {
+    *MACOS_MAJOR_VERSION.get_or_init(|| {
+        let output = Command::new("sw_vers").arg("-productVersion").output().ok()?;
+        parse_major_version(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+fn parse_major_version(version: &str) -> Option<u32> {
+    version.trim().split('.').next()?.parse().ok()
+}
+
+/// Whether this OS can capture system audio through ScreenCaptureKit
+pub fn is_screen_capture_kit_available() -> bool {
+    macos_major_version()
+        .map(|major| major >= MIN_MACOS_MAJOR_VERSION)
+        .unwrap_or(false)
+}
+
+/// Whether screen recording access has been granted; the first call shows the system prompt
+pub fn has_screen_recording_permission() -> bool {
+    SCShareableContent::get().is_ok()
+}
+
+/// Downmixes each audio sample buffer to mono and hands it to the processing loop
+struct AudioOutputHandler {
+    ring: Arc<SampleRingBuffer>,
+}
+
+impl SCStreamOutputTrait for AudioOutputHandler {
+    fn did_output_sample_buffer(&self, sample_buffer: CMSampleBuffer, of_type: SCStreamOutputType) {
+        if !matches!(of_type, SCStreamOutputType::Audio) {
+            return;
+        }
+        let Ok(buffer_list) = sample_buffer.get_audio_buffer_list() else {
+            return;
+        };
+
+        // SCK delivers non-interleaved f32, one buffer per channel
+        let channels: Vec<&[f32]> = buffer_list
+            .buffers()
+            .iter()
+            .map(|buffer| bytes_as_f32(buffer.data()))
+            .filter(|samples| !samples.is_empty())
+            .collect();
+        if channels.is_empty() {
+            return;
+        }
+
+        let frames = channels.iter().map(|samples| samples.len()).min().unwrap_or(0);
+        let scale = 1.0 / channels.len() as f32;
+        let mono: Vec<f32> = (0..frames)
+            .map(|i| channels.iter().map(|samples| samples[i]).sum::<f32>() * scale)
+            .collect();
+        self.ring.push(&mono);
+    }
+}
+
+fn bytes_as_f32(bytes: &[u8]) -> &[f32] {
+    // SAFETY: Core Media audio buffers are f32-aligned; any trailing partial sample is dropped
+    let (prefix, samples, _) = unsafe { bytes.align_to::<f32>() };
+    if prefix.is_empty() { samples } else { &[] }
+}
+
+/// An SCStream capturing the whole system mix (minus our own process) into a ring buffer
+pub struct ScreenCaptureKitCapture {
+    stream: SCStream,
+}
+
+impl ScreenCaptureKitCapture {
+    pub fn start(ring: Arc<SampleRingBuffer>) -> Result<Self> {
+        let content = SCShareableContent::get()
+            .map_err(|e| anyhow::anyhow!("Failed to query shareable content: {:?}", e))?;
+        // Audio is captured system-wide; a display is only required to build the filter
+        let display = content
+            .displays()
+            .into_iter()
+            .next()
+            .context("No display available for ScreenCaptureKit")?;
+        let filter = SCContentFilter::new().with_display_excluding_windows(&display, &[]);
+
+        let config = SCStreamConfiguration::new()
+            .set_captures_audio(true)
+            .and_then(|config| config.set_excludes_current_process_audio(true))
+            .and_then(|config| config.set_sample_rate(SCK_SAMPLE_RATE))
+            .and_then(|config| config.set_channel_count(SCK_CHANNEL_COUNT))
+            // Keep the video side as cheap as possible; we never read it
+            .and_then(|config| config.set_width(2))
+            .and_then(|config| config.set_height(2))
+            .map_err(|e| anyhow::anyhow!("Failed to configure ScreenCaptureKit stream: {:?}", e))?;
+
+        let mut stream = SCStream::new(&filter, &config);
+        stream.add_output_handler(AudioOutputHandler { ring }, SCStreamOutputType::Audio);
+        stream
+            .start_capture()
+            .map_err(|e| anyhow::anyhow!("Failed to start ScreenCaptureKit capture: {:?}", e))?;
+
+        println!("[SCK] System audio capture started at {}Hz", SCK_SAMPLE_RATE);
+        Ok(Self { stream })
+    }
+
+    pub fn stop(self) -> Result<()> {
+        self.stream
+            .stop_capture()
+            .map_err(|e| anyhow::anyhow!("Failed to stop ScreenCaptureKit capture: {:?}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_major_version() {
+        assert_eq!(parse_major_version("14.5\n"), Some(14));
+        assert_eq!(parse_major_version("13.0.1"), Some(13));
+        assert_eq!(parse_major_version("12"), Some(12));
+        assert_eq!(parse_major_version(""), None);
+    }
+}
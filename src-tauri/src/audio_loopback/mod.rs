@@ -5,6 +5,15 @@ pub mod types;
 pub mod audio_processor;
 pub mod quality_filter;
 pub mod settings;
+pub mod settings_schema;
+pub mod device_monitor;
+pub mod ring_buffer;
+pub mod dsp;
+pub mod channel_mixer;
+pub mod session_recorder;
+pub mod device_aliases;
+pub mod capture_limits;
+pub mod app_sessions;
 
 // Platform-specific modules
 #[cfg(target_os = "windows")]
@@ -16,9 +25,54 @@ pub mod macos;
 pub use types::{CAPTURE_STATE, CaptureState, AudioLoopbackDevice, DeviceType, LoopbackMethod, AudioDeviceSettings};
 pub use audio_processor::*;
 pub use settings::*;
+pub use device_monitor::start_device_monitor;
+pub use ring_buffer::{configure_capture_buffer, get_capture_buffer_stats};
+pub use dsp::{get_device_dsp_config, set_device_dsp_config};
+pub use channel_mixer::{get_device_channel_mix, set_device_channel_mix, get_downmix_matrix};
+pub use session_recorder::{start_session_recording, stop_session_recording, get_session_recording};
+pub use device_aliases::{get_device_aliases, set_device_alias};
+pub use capture_limits::{get_capture_timer, extend_capture};
+pub use app_sessions::{
+    list_audio_sessions, set_audio_session_volume, set_audio_session_mute,
+    get_audio_ducking_config, set_audio_ducking_config,
+};
 
 // Platform-specific re-exports
 #[cfg(target_os = "windows")]
 pub use windows::*;
 #[cfg(target_os = "macos")]
 pub use macos::*;
+
+use crate::action_registry::{args, descriptor, param, to_value, ActionKind, RegisteredAction};
+
+#[derive(serde::Deserialize)]
+struct DeviceArgs {
+    device_id: String,
+}
+
+/// Command palette actions for loopback capture
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    vec![
+        RegisteredAction {
+            descriptor: descriptor("audio.list_devices", ActionKind::Command, "List Audio Devices",
+                "List loopback capture devices", vec![], &["loopback", "speaker", "microphone"]),
+            handler: |_, _| Box::pin(async move { to_value(enumerate_loopback_devices().await?) }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("audio.start_capture", ActionKind::Command, "Start Audio Capture",
+                "Start capturing and transcribing system audio", vec![param("device_id", "string", true, "Loopback device to capture")], &["record", "loopback"]),
+            handler: |app, params| Box::pin(async move {
+                let DeviceArgs { device_id } = args(params)?;
+                to_value(start_audio_loopback_capture(device_id, app).await?)
+            }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("audio.stop_capture", ActionKind::Command, "Stop Audio Capture",
+                "Stop capturing system audio", vec![], &["record", "loopback"]),
+            handler: |_, _| Box::pin(async move {
+                stop_audio_loopback_capture().await?;
+                Ok(serde_json::Value::Null)
+            }),
+        },
+    ]
+}
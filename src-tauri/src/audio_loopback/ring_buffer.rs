@@ -0,0 +1,301 @@
+// src-tauri/src/audio_loopback/ring_buffer.rs
+// Bounded lock-free SPSC sample buffer between the device callback and the processing thread
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    // Applied to the next capture that starts
+    pub static ref RING_BUFFER_CONFIG: Mutex<RingBufferConfig> = Mutex::new(RingBufferConfig::default());
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Overwrite the oldest unread samples so the processor always sees recent audio
+    DropOldest,
+    /// Discard incoming samples until the processor catches up
+    DropNewest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RingBufferConfig {
+    pub capacity_ms: u32,
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for RingBufferConfig {
+    fn default() -> Self {
+        Self {
+            capacity_ms: 2000,
+            overflow_policy: OverflowPolicy::DropOldest,
+        }
+    }
+}
+
+impl RingBufferConfig {
+    pub fn capacity_samples(&self, sample_rate: u32) -> usize {
+        ((sample_rate as u64 * self.capacity_ms as u64) / 1000).max(1) as usize
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RingBufferStats {
+    pub capacity: usize,
+    pub buffered: usize,
+    pub pushed_frames: u64,
+    pub dropped_frames: u64,
+    pub overflow_events: u64,
+    pub overflow_policy: OverflowPolicy,
+}
+
+/// Single-producer single-consumer ring of f32 samples.
+///
+/// Samples are stored as bit patterns in atomics so the buffer needs no unsafe code.
+/// `head` and `tail` are monotonically increasing positions; the slot index is the
+/// position modulo capacity. With `DropOldest` the producer may advance `head`, so the
+/// consumer publishes its reads with a compare-exchange and retries if it lost the race.
+pub struct SampleRingBuffer {
+    slots: Box<[AtomicU32]>,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    policy: OverflowPolicy,
+    pushed: AtomicU64,
+    dropped: AtomicU64,
+    overflow_events: AtomicU64,
+}
+
+impl SampleRingBuffer {
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            slots: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            policy,
+            pushed: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            overflow_events: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_config(config: &RingBufferConfig, sample_rate: u32) -> Arc<Self> {
+        Arc::new(Self::new(config.capacity_samples(sample_rate), config.overflow_policy))
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        tail.saturating_sub(head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Producer side. Returns the number of samples that were accepted.
+    pub fn push(&self, samples: &[f32]) -> usize {
+        if samples.is_empty() {
+            return 0;
+        }
+
+        let capacity = self.capacity();
+        let tail = self.tail.load(Ordering::Relaxed);
+        self.pushed.fetch_add(samples.len() as u64, Ordering::Relaxed);
+
+        let to_write: &[f32] = match self.policy {
+            OverflowPolicy::DropNewest => {
+                let free = capacity - (tail - self.head.load(Ordering::Acquire));
+                if free < samples.len() {
+                    self.record_drop((samples.len() - free) as u64);
+                }
+                &samples[..free.min(samples.len())]
+            }
+            OverflowPolicy::DropOldest => {
+                // A block larger than the whole ring only keeps its newest samples
+                let block = if samples.len() > capacity {
+                    self.record_drop((samples.len() - capacity) as u64);
+                    &samples[samples.len() - capacity..]
+                } else {
+                    samples
+                };
+
+                loop {
+                    let head = self.head.load(Ordering::Acquire);
+                    let free = capacity - (tail - head);
+                    if free >= block.len() {
+                        break;
+                    }
+                    let evict = block.len() - free;
+                    if self
+                        .head
+                        .compare_exchange(head, head + evict, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+                    {
+                        self.record_drop(evict as u64);
+                        break;
+                    }
+                }
+                block
+            }
+        };
+
+        for (offset, sample) in to_write.iter().enumerate() {
+            self.slots[(tail + offset) % capacity].store(sample.to_bits(), Ordering::Relaxed);
+        }
+        self.tail.store(tail + to_write.len(), Ordering::Release);
+        to_write.len()
+    }
+
+    /// Consumer side. Appends up to `max` samples to `out` and returns how many were read.
+    pub fn pop_into(&self, out: &mut Vec<f32>, max: usize) -> usize {
+        let capacity = self.capacity();
+        let start_len = out.len();
+
+        loop {
+            let head = self.head.load(Ordering::Acquire);
+            let tail = self.tail.load(Ordering::Acquire);
+            let count = tail.saturating_sub(head).min(max);
+            out.truncate(start_len);
+            if count == 0 {
+                return 0;
+            }
+
+            out.extend(
+                (head..head + count)
+                    .map(|pos| f32::from_bits(self.slots[pos % capacity].load(Ordering::Relaxed))),
+            );
+
+            // If the producer evicted under us the samples we copied may be stale
+            if self
+                .head
+                .compare_exchange(head, head + count, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return count;
+            }
+        }
+    }
+
+    pub fn stats(&self) -> RingBufferStats {
+        RingBufferStats {
+            capacity: self.capacity(),
+            buffered: self.len(),
+            pushed_frames: self.pushed.load(Ordering::Relaxed),
+            dropped_frames: self.dropped.load(Ordering::Relaxed),
+            overflow_events: self.overflow_events.load(Ordering::Relaxed),
+            overflow_policy: self.policy,
+        }
+    }
+
+    fn record_drop(&self, frames: u64) {
+        self.dropped.fetch_add(frames, Ordering::Relaxed);
+        self.overflow_events.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[crate::command]
+pub async fn configure_capture_buffer(
+    capacity_ms: u32,
+    overflow_policy: OverflowPolicy,
+) -> Result<RingBufferConfig, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    if !(50..=30_000).contains(&capacity_ms) {
+        return Err("Capture buffer capacity must be between 50ms and 30000ms".to_string());
+    }
+
+    let mut config = RING_BUFFER_CONFIG.lock().unwrap();
+    config.capacity_ms = capacity_ms;
+    config.overflow_policy = overflow_policy;
+    Ok(config.clone())
+}
+
+#[crate::command]
+pub async fn get_capture_buffer_stats() -> Result<Option<RingBufferStats>, String> {
+    let state = crate::audio_loopback::types::CAPTURE_STATE.lock().unwrap();
+    Ok(state.ring_buffer.as_ref().map(|ring| ring.stats()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(ring: &SampleRingBuffer) -> Vec<f32> {
+        let mut out = Vec::new();
+        ring.pop_into(&mut out, usize::MAX);
+        out
+    }
+
+    #[test]
+    fn test_push_and_pop_preserves_order() {
+        let ring = SampleRingBuffer::new(8, OverflowPolicy::DropOldest);
+        assert_eq!(ring.push(&[1.0, 2.0, 3.0]), 3);
+        assert_eq!(ring.len(), 3);
+        assert_eq!(drain(&ring), vec![1.0, 2.0, 3.0]);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_newest_samples() {
+        let ring = SampleRingBuffer::new(4, OverflowPolicy::DropOldest);
+        ring.push(&[1.0, 2.0, 3.0]);
+        ring.push(&[4.0, 5.0, 6.0]);
+        assert_eq!(drain(&ring), vec![3.0, 4.0, 5.0, 6.0]);
+
+        let stats = ring.stats();
+        assert_eq!(stats.pushed_frames, 6);
+        assert_eq!(stats.dropped_frames, 2);
+        assert_eq!(stats.overflow_events, 1);
+    }
+
+    #[test]
+    fn test_drop_newest_rejects_incoming_samples() {
+        let ring = SampleRingBuffer::new(4, OverflowPolicy::DropNewest);
+        ring.push(&[1.0, 2.0, 3.0]);
+        assert_eq!(ring.push(&[4.0, 5.0, 6.0]), 1);
+        assert_eq!(drain(&ring), vec![1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(ring.stats().dropped_frames, 2);
+    }
+
+    #[test]
+    fn test_oversized_block_wraps_around() {
+        let ring = SampleRingBuffer::new(3, OverflowPolicy::DropOldest);
+        ring.push(&[1.0, 2.0]);
+        drain(&ring);
+        ring.push(&[3.0, 4.0, 5.0, 6.0, 7.0]);
+        assert_eq!(drain(&ring), vec![5.0, 6.0, 7.0]);
+    }
+
+    #[test]
+    fn test_concurrent_producer_consumer() {
+        let ring = Arc::new(SampleRingBuffer::new(64, OverflowPolicy::DropNewest));
+        let producer_ring = ring.clone();
+        let total = 10_000usize;
+
+        let producer = std::thread::spawn(move || {
+            let mut sent = 0usize;
+            while sent < total {
+                let accepted = producer_ring.push(&[sent as f32]);
+                sent += accepted;
+                if accepted == 0 {
+                    std::thread::yield_now();
+                }
+            }
+        });
+
+        let mut received = Vec::with_capacity(total);
+        while received.len() < total {
+            if ring.pop_into(&mut received, 16) == 0 {
+                std::thread::yield_now();
+            }
+        }
+        producer.join().unwrap();
+
+        assert!(received.iter().enumerate().all(|(i, &s)| s == i as f32));
+    }
+}
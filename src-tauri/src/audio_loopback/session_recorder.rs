@@ -0,0 +1,435 @@
+// src-tauri/src/audio_loopback/session_recorder.rs
+// Records the combined microphone + loopback stream to disk in rolling WAV segments
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+// Both sources reach us as 16kHz mono after processing
+const SAMPLE_RATE: u32 = 16000;
+const SEGMENT_DURATION_SECS: u64 = 10 * 60;
+const SEGMENT_SAMPLES: u64 = SAMPLE_RATE as u64 * SEGMENT_DURATION_SECS;
+// 16-bit mono
+const SEGMENT_BYTES: u64 = SEGMENT_SAMPLES * 2;
+// Microphone clips only arrive once they're finished, so hold the mix open this long
+const MIX_LATENCY_SAMPLES: u64 = SAMPLE_RATE as u64 * 15;
+// Blocks landing within this distance of where the previous one ended are treated as contiguous
+const RESYNC_TOLERANCE_SAMPLES: u64 = SAMPLE_RATE as u64 / 5;
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+const MANIFEST_FILE: &str = "manifest.json";
+
+lazy_static::lazy_static! {
+    static ref ACTIVE_RECORDING: Mutex<Option<ActiveRecording>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingStatus {
+    Recording,
+    Completed,
+    /// The app exited without stopping the recording; segments up to the crash are intact
+    Interrupted,
+    /// Stopped early because the disk was nearly full; segments written before that are intact
+    StoppedLowDisk,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSegment {
+    pub index: u32,
+    pub file_name: String,
+    pub path: String,
+    pub start_offset_ms: u64,
+    pub duration_ms: u64,
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingManifest {
+    pub session_id: String,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub segment_duration_ms: u64,
+    pub status: RecordingStatus,
+    pub segments: Vec<RecordingSegment>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    Microphone,
+    Loopback,
+}
+
+/// Sums both sources on a shared timeline indexed by samples since the recording started
+struct MixBuffer {
+    started: Instant,
+    flushed: u64,
+    samples: VecDeque<f32>,
+    cursors: [Option<u64>; 2],
+}
+
+impl MixBuffer {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            flushed: 0,
+            samples: VecDeque::new(),
+            cursors: [None, None],
+        }
+    }
+
+    fn now_index(&self) -> u64 {
+        (self.started.elapsed().as_secs_f64() * SAMPLE_RATE as f64) as u64
+    }
+
+    /// Mix in a block that has just finished arriving
+    fn push(&mut self, source: Source, block: &[f32]) {
+        let len = block.len() as u64;
+        let arrived_start = self.now_index().saturating_sub(len);
+        let cursor = &mut self.cursors[source as usize];
+        let start = match *cursor {
+            Some(expected) if expected.abs_diff(arrived_start) <= RESYNC_TOLERANCE_SAMPLES => expected,
+            _ => arrived_start,
+        };
+        *cursor = Some(start + len);
+
+        // Anything older than what's already on disk can't be mixed in any more
+        let skip = self.flushed.saturating_sub(start);
+        if skip >= len {
+            return;
+        }
+        let offset = (start + skip - self.flushed) as usize;
+        let block = &block[skip as usize..];
+        if self.samples.len() < offset + block.len() {
+            self.samples.resize(offset + block.len(), 0.0);
+        }
+        for (i, &sample) in block.iter().enumerate() {
+            let mixed = &mut self.samples[offset + i];
+            *mixed = (*mixed + sample).clamp(-1.0, 1.0);
+        }
+    }
+
+    /// Samples that are final, padded with silence so the file keeps wall-clock timing
+    fn take_ready(&mut self, drain_all: bool) -> Vec<f32> {
+        let ready_until = if drain_all {
+            self.now_index().max(self.flushed + self.samples.len() as u64)
+        } else {
+            self.now_index().saturating_sub(MIX_LATENCY_SAMPLES)
+        };
+        let count = ready_until.saturating_sub(self.flushed) as usize;
+        if self.samples.len() < count {
+            self.samples.resize(count, 0.0);
+        }
+        self.flushed += count as u64;
+        self.samples.drain(..count).collect()
+    }
+}
+
+/// 16-bit PCM WAV writer whose header is kept valid after every write
+struct SegmentWriter {
+    file: File,
+    data_bytes: u32,
+}
+
+impl SegmentWriter {
+    fn create(path: &Path) -> std::io::Result<Self> {
+        let mut file = File::create(path)?;
+        let block_align = 2u16;
+        file.write_all(b"RIFF")?;
+        file.write_all(&36u32.to_le_bytes())?;
+        file.write_all(b"WAVEfmt ")?;
+        file.write_all(&16u32.to_le_bytes())?;
+        file.write_all(&1u16.to_le_bytes())?; // PCM
+        file.write_all(&1u16.to_le_bytes())?; // mono
+        file.write_all(&SAMPLE_RATE.to_le_bytes())?;
+        file.write_all(&(SAMPLE_RATE * block_align as u32).to_le_bytes())?;
+        file.write_all(&block_align.to_le_bytes())?;
+        file.write_all(&16u16.to_le_bytes())?;
+        file.write_all(b"data")?;
+        file.write_all(&0u32.to_le_bytes())?;
+        Ok(Self { file, data_bytes: 0 })
+    }
+
+    fn write(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        let bytes: Vec<u8> = samples
+            .iter()
+            .flat_map(|&s| ((s.clamp(-1.0, 1.0) * 32767.0) as i16).to_le_bytes())
+            .collect();
+        self.file.seek(SeekFrom::End(0))?;
+        self.file.write_all(&bytes)?;
+        self.data_bytes += bytes.len() as u32;
+
+        // Patch the sizes so a crash still leaves a playable file
+        self.file.seek(SeekFrom::Start(4))?;
+        self.file.write_all(&(36 + self.data_bytes).to_le_bytes())?;
+        self.file.seek(SeekFrom::Start(40))?;
+        self.file.write_all(&self.data_bytes.to_le_bytes())?;
+        self.file.flush()
+    }
+
+    fn samples(&self) -> u64 {
+        self.data_bytes as u64 / 2
+    }
+}
+
+struct ActiveRecording {
+    session_id: String,
+    mix: Arc<Mutex<MixBuffer>>,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<Result<RecordingManifest, String>>,
+}
+
+fn recordings_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("recordings"))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// Directory holding a session's segments and manifest
+pub fn session_recording_dir(app_handle: &AppHandle, session_id: &str) -> Result<PathBuf, String> {
+    if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.contains("..") {
+        return Err("Invalid session id".to_string());
+    }
+    Ok(recordings_dir(app_handle)?.join(session_id))
+}
+
+fn write_manifest(dir: &Path, manifest: &RecordingManifest) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize recording manifest: {}", e))?;
+    // Write-then-rename so a crash never leaves a half-written manifest
+    let temp_path = dir.join(format!("{}.tmp", MANIFEST_FILE));
+    std::fs::write(&temp_path, json)
+        .map_err(|e| format!("Failed to write recording manifest: {}", e))?;
+    std::fs::rename(&temp_path, dir.join(MANIFEST_FILE))
+        .map_err(|e| format!("Failed to write recording manifest: {}", e))
+}
+
+pub fn read_manifest(dir: &Path) -> Result<Option<RecordingManifest>, String> {
+    let path = dir.join(MANIFEST_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read recording manifest: {}", e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse recording manifest: {}", e))
+}
+
+fn samples_to_ms(samples: u64) -> u64 {
+    samples * 1000 / SAMPLE_RATE as u64
+}
+
+/// Writer thread: drains finished audio from the mix and rolls segments every ten minutes
+fn run_writer(
+    dir: PathBuf,
+    mut manifest: RecordingManifest,
+    mix: Arc<Mutex<MixBuffer>>,
+    stop: Arc<AtomicBool>,
+) -> Result<RecordingManifest, String> {
+    let mut writer: Option<SegmentWriter> = None;
+    let mut written_samples = 0u64;
+    let mut low_disk = false;
+
+    loop {
+        let stopping = stop.load(Ordering::Acquire);
+        let mut ready = match mix.lock() {
+            Ok(mut mix) => mix.take_ready(stopping),
+            Err(_) => return Err("Recording mix buffer is poisoned".to_string()),
+        };
+
+        // Once out of space, audio is dropped until the recording is stopped
+        if low_disk {
+            ready.clear();
+        }
+
+        while !ready.is_empty() {
+            if writer.as_ref().map_or(true, |w| w.samples() >= SEGMENT_SAMPLES) {
+                if let Some(segment) = manifest.segments.last_mut() {
+                    segment.complete = true;
+                }
+                if let Err(e) = crate::disk_space::preflight(crate::disk_space::Subsystem::Recordings, SEGMENT_BYTES) {
+                    eprintln!("🎙️ Stopping session recording: {}", e);
+                    writer = None;
+                    low_disk = true;
+                    manifest.status = RecordingStatus::StoppedLowDisk;
+                    ready.clear();
+                    break;
+                }
+                let index = manifest.segments.len() as u32;
+                let file_name = format!("segment_{:03}.wav", index);
+                let path = dir.join(&file_name);
+                writer = Some(
+                    SegmentWriter::create(&path)
+                        .map_err(|e| format!("Failed to create recording segment: {}", e))?,
+                );
+                manifest.segments.push(RecordingSegment {
+                    index,
+                    file_name,
+                    path: path.to_string_lossy().to_string(),
+                    start_offset_ms: samples_to_ms(written_samples),
+                    duration_ms: 0,
+                    complete: false,
+                });
+            }
+
+            let current = writer.as_mut().unwrap();
+            let room = (SEGMENT_SAMPLES - current.samples()) as usize;
+            let chunk: Vec<f32> = ready.drain(..room.min(ready.len())).collect();
+            current
+                .write(&chunk)
+                .map_err(|e| format!("Failed to write recording segment: {}", e))?;
+            written_samples += chunk.len() as u64;
+            if let Some(segment) = manifest.segments.last_mut() {
+                segment.duration_ms = samples_to_ms(current.samples());
+            }
+        }
+
+        if stopping {
+            break;
+        }
+        write_manifest(&dir, &manifest)?;
+        std::thread::sleep(FLUSH_INTERVAL);
+    }
+
+    if let Some(segment) = manifest.segments.last_mut() {
+        segment.complete = true;
+    }
+    if !low_disk {
+        manifest.status = RecordingStatus::Completed;
+    }
+    manifest.ended_at = Some(chrono::Utc::now().timestamp_millis());
+    write_manifest(&dir, &manifest)?;
+    Ok(manifest)
+}
+
+fn push(source: Source, samples: &[f32]) {
+    if samples.is_empty() {
+        return;
+    }
+    if let Ok(active) = ACTIVE_RECORDING.lock() {
+        if let Some(recording) = active.as_ref() {
+            if let Ok(mut mix) = recording.mix.lock() {
+                mix.push(source, samples);
+            }
+        }
+    }
+}
+
+/// Feed processed 16kHz loopback audio into the active recording, if any
+pub fn push_loopback(samples: &[f32]) {
+    push(Source::Loopback, samples);
+}
+
+/// Feed a finished 16kHz microphone clip into the active recording, if any
+pub fn push_microphone(samples: &[f32]) {
+    push(Source::Microphone, samples);
+}
+
+#[crate::command]
+pub fn start_session_recording(app_handle: AppHandle, session_id: String) -> Result<RecordingManifest, String> {
+    let mut active = ACTIVE_RECORDING
+        .lock()
+        .map_err(|_| "Failed to access recording state".to_string())?;
+    if let Some(recording) = active.as_ref() {
+        return Err(format!("Session {} is already being recorded", recording.session_id));
+    }
+
+    let dir = session_recording_dir(&app_handle, &session_id)?;
+    if read_manifest(&dir)?.is_some() {
+        return Err(format!("A recording already exists for session {}", session_id));
+    }
+    crate::disk_space::preflight(crate::disk_space::Subsystem::Recordings, SEGMENT_BYTES)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create recording directory: {}", e))?;
+
+    let manifest = RecordingManifest {
+        session_id: session_id.clone(),
+        started_at: chrono::Utc::now().timestamp_millis(),
+        ended_at: None,
+        sample_rate: SAMPLE_RATE,
+        channels: 1,
+        segment_duration_ms: SEGMENT_DURATION_SECS * 1000,
+        status: RecordingStatus::Recording,
+        segments: Vec::new(),
+    };
+    write_manifest(&dir, &manifest)?;
+
+    let mix = Arc::new(Mutex::new(MixBuffer::new()));
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = {
+        let (manifest, mix, stop) = (manifest.clone(), mix.clone(), stop.clone());
+        std::thread::Builder::new()
+            .name("session-recorder".to_string())
+            .spawn(move || run_writer(dir, manifest, mix, stop))
+            .map_err(|e| format!("Failed to start recording thread: {}", e))?
+    };
+
+    *active = Some(ActiveRecording { session_id, mix, stop, handle });
+    Ok(manifest)
+}
+
+#[crate::command]
+pub async fn stop_session_recording() -> Result<RecordingManifest, String> {
+    let recording = ACTIVE_RECORDING
+        .lock()
+        .map_err(|_| "Failed to access recording state".to_string())?
+        .take()
+        .ok_or_else(|| "No session recording in progress".to_string())?;
+
+    recording.stop.store(true, Ordering::Release);
+    tokio::task::spawn_blocking(move || recording.handle.join())
+        .await
+        .map_err(|e| format!("Failed to stop recording: {}", e))?
+        .map_err(|_| "Recording thread panicked".to_string())?
+}
+
+/// Manifest for a session's recording so the frontend can list segments for playback
+#[crate::command]
+pub fn get_session_recording(app_handle: AppHandle, session_id: String) -> Result<Option<RecordingManifest>, String> {
+    let dir = session_recording_dir(&app_handle, &session_id)?;
+    let Some(mut manifest) = read_manifest(&dir)? else {
+        return Ok(None);
+    };
+
+    let is_active = ACTIVE_RECORDING
+        .lock()
+        .map(|active| active.as_ref().map_or(false, |r| r.session_id == session_id))
+        .unwrap_or(false);
+    if manifest.status == RecordingStatus::Recording && !is_active {
+        manifest.status = RecordingStatus::Interrupted;
+        write_manifest(&dir, &manifest)?;
+    }
+    Ok(Some(manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix_sums_sources_and_keeps_blocks_contiguous() {
+        let mut mix = MixBuffer::new();
+        mix.push(Source::Loopback, &[0.25; 100]);
+        mix.push(Source::Loopback, &[0.25; 100]);
+        mix.push(Source::Microphone, &[0.5; 50]);
+
+        let out = mix.take_ready(true);
+        // Second loopback block continues the first instead of overlapping it
+        assert!(out.len() >= 200);
+        assert_eq!(out.iter().filter(|&&s| s == 0.75).count(), 50);
+        assert!(out.iter().all(|&s| s <= 1.0));
+        assert_eq!(mix.flushed, out.len() as u64);
+    }
+}
@@ -1,9 +1,15 @@
 // src-tauri/src/audio_loopback/settings.rs
 use crate::audio_loopback::types::AudioDeviceSettings;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use crate::audio_loopback::dsp::apply_device_dsp_configs;
+use crate::audio_loopback::settings_schema::{
+    parse_audio_settings, parse_general_settings, GeneralSettings, SettingsSource,
+    AUDIO_SETTINGS_VERSION, GENERAL_SETTINGS_VERSION,
+};
+use std::path::{Path, PathBuf};
 use std::fs;
+use serde::Serialize;
 use serde_json;
+use crate::app_lock::{require_unlocked, CommandGroup};
 
 fn get_settings_path() -> anyhow::Result<PathBuf> {
     let app_data = dirs::config_dir()
@@ -29,70 +35,106 @@ fn get_general_settings_path() -> anyhow::Result<PathBuf> {
     Ok(app_dir.join("general_settings.json"))
 }
 
-#[tauri::command]
-pub async fn save_audio_settings(settings: AudioDeviceSettings) -> Result<(), String> {
-    let settings_path = get_settings_path()
-        .map_err(|e| format!("Failed to get settings path: {}", e))?;
-    
-    let json = serde_json::to_string_pretty(&settings)
+fn write_settings_file<T: Serialize>(path: &Path, settings: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(settings)
         .map_err(|e| format!("Failed to serialize settings: {}", e))?;
     
-    fs::write(settings_path, json)
-        .map_err(|e| format!("Failed to write settings file: {}", e))?;
+    fs::write(path, json)
+        .map_err(|e| format!("Failed to write settings file: {}", e))
+}
+
+/// Read a stored settings file, returning the raw JSON and whether it predates the current schema
+fn read_settings_file(path: &Path, current_version: u32) -> Result<Option<(serde_json::Value, bool)>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
     
-    // println!("💾 Audio settings saved"); // Commented out: Audio loopback is working, reducing console noise for debugging focus
-    Ok(())
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read settings file: {}", e))?;
+    
+    let value: serde_json::Value = serde_json::from_str(&json)
+        .map_err(|e| format!("Settings file {} is not valid JSON ({}); fix or delete it to reset", path.display(), e))?;
+    
+    let stored_version = value.get("schemaVersion").and_then(|v| v.as_u64());
+    Ok(Some((value, stored_version != Some(current_version as u64))))
 }
 
-#[tauri::command]
-pub async fn load_audio_settings() -> Result<Option<AudioDeviceSettings>, String> {
+pub(crate) fn write_audio_settings(settings: &AudioDeviceSettings) -> Result<(), String> {
     let settings_path = get_settings_path()
         .map_err(|e| format!("Failed to get settings path: {}", e))?;
     
-    if !settings_path.exists() {
+    write_settings_file(&settings_path, settings)
+}
+
+pub(crate) fn read_audio_settings() -> Result<Option<AudioDeviceSettings>, String> {
+    let settings_path = get_settings_path()
+        .map_err(|e| format!("Failed to get settings path: {}", e))?;
+    
+    let Some((value, needs_migration)) = read_settings_file(&settings_path, AUDIO_SETTINGS_VERSION)? else {
         return Ok(None);
+    };
+    
+    let settings = parse_audio_settings(value, SettingsSource::StoredFile)
+        .map_err(|e| format!("{} (in {})", e, settings_path.display()))?;
+    
+    if needs_migration {
+        // Persist the upgraded blob so later releases only migrate from here
+        write_settings_file(&settings_path, &settings)?;
     }
     
-    let json = fs::read_to_string(settings_path)
-        .map_err(|e| format!("Failed to read settings file: {}", e))?;
+    Ok(Some(settings))
+}
+
+#[crate::command]
+pub async fn save_audio_settings(settings: serde_json::Value) -> Result<(), String> {
+    require_unlocked(CommandGroup::SettingsChanges)?;
+    let settings = parse_audio_settings(settings, SettingsSource::FrontendPayload)?;
+    write_audio_settings(&settings)?;
     
-    let settings: AudioDeviceSettings = serde_json::from_str(&json)
-        .map_err(|e| format!("Failed to parse settings: {}", e))?;
+    // DSP toggles take effect on the next processed block, no capture restart needed
+    apply_device_dsp_configs(&settings.deviceDsp);
+    
+    // println!("💾 Audio settings saved"); // Commented out: Audio loopback is working, reducing console noise for debugging focus
+    Ok(())
+}
+
+#[crate::command]
+pub async fn load_audio_settings() -> Result<Option<AudioDeviceSettings>, String> {
+    let settings = read_audio_settings()?;
     
     // println!("📂 Audio settings loaded"); // Commented out: Audio loopback is working, reducing console noise for debugging focus
-    Ok(Some(settings))
+    Ok(settings)
 }
 
-#[tauri::command]
-pub async fn save_general_settings(settings: HashMap<String, serde_json::Value>) -> Result<(), String> {
+#[crate::command]
+pub async fn save_general_settings(settings: serde_json::Value) -> Result<(), String> {
+    require_unlocked(CommandGroup::SettingsChanges)?;
+    let settings = parse_general_settings(settings, SettingsSource::FrontendPayload)?;
     let settings_path = get_general_settings_path()
         .map_err(|e| format!("Failed to get settings path: {}", e))?;
     
-    let json = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-    
-    fs::write(settings_path, json)
-        .map_err(|e| format!("Failed to write settings file: {}", e))?;
+    write_settings_file(&settings_path, &settings)?;
     
     // println!("💾 General settings saved"); // Commented out: Audio loopback is working, reducing console noise for debugging focus
     Ok(())
 }
 
-#[tauri::command]
-pub async fn load_general_settings() -> Result<Option<HashMap<String, serde_json::Value>>, String> {
+#[crate::command]
+pub async fn load_general_settings() -> Result<Option<GeneralSettings>, String> {
     let settings_path = get_general_settings_path()
         .map_err(|e| format!("Failed to get settings path: {}", e))?;
     
-    if !settings_path.exists() {
+    let Some((value, needs_migration)) = read_settings_file(&settings_path, GENERAL_SETTINGS_VERSION)? else {
         return Ok(None);
-    }
+    };
     
-    let json = fs::read_to_string(settings_path)
-        .map_err(|e| format!("Failed to read settings file: {}", e))?;
+    let settings = parse_general_settings(value, SettingsSource::StoredFile)
+        .map_err(|e| format!("{} (in {})", e, settings_path.display()))?;
     
-    let settings: HashMap<String, serde_json::Value> = serde_json::from_str(&json)
-        .map_err(|e| format!("Failed to parse settings: {}", e))?;
+    if needs_migration {
+        write_settings_file(&settings_path, &settings)?;
+    }
     
     // println!("📂 General settings loaded"); // Commented out: Audio loopback is working, reducing console noise for debugging focus
     Ok(Some(settings))
-}
\ No newline at end of file
+}
@@ -0,0 +1,389 @@
+// src-tauri/src/audio_loopback/settings_schema.rs
+// Versioned settings schemas with migration and validation for persisted settings blobs
+
+use crate::audio_loopback::types::AudioDeviceSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const AUDIO_SETTINGS_VERSION: u32 = 1;
+pub const GENERAL_SETTINGS_VERSION: u32 = 1;
+
+// "auto" is what the settings tab offers; "system" is kept for blobs written by older builds
+const THEMES: &[&str] = &["dark", "light", "auto", "system"];
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+const SAMPLE_RATES: &[u32] = &[8000, 16000, 22050, 44100, 48000, 96000];
+// A day; longer capture limits or reminder intervals are almost certainly typos
+const MAX_CAPTURE_LIMIT_MINUTES: u32 = 24 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GeneralSettings {
+    pub schema_version: u32,
+    pub theme: String,
+    pub auto_start_ollama: bool,
+    pub enable_notifications: bool,
+    pub log_level: String,
+    pub start_minimized: bool,
+    pub start_with_system: bool,
+    pub save_window_position: bool,
+    pub enable_keyboard_shortcuts: bool,
+    pub transcription_language: String,
+    pub enable_auto_save: bool,
+    pub auto_save_interval: u32,
+    pub microphone_whisper_model: String,
+    pub loopback_whisper_model: String,
+    pub enable_transparency: bool,
+    pub default_transparency_level: f64,
+    pub auto_restore_on_error: bool,
+    // Keys owned by newer frontends are preserved rather than silently dropped
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for GeneralSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: GENERAL_SETTINGS_VERSION,
+            theme: "dark".to_string(),
+            auto_start_ollama: false,
+            enable_notifications: true,
+            log_level: "info".to_string(),
+            start_minimized: false,
+            start_with_system: false,
+            save_window_position: true,
+            enable_keyboard_shortcuts: true,
+            transcription_language: "en".to_string(),
+            enable_auto_save: true,
+            auto_save_interval: 5,
+            microphone_whisper_model: "tiny".to_string(),
+            loopback_whisper_model: "small".to_string(),
+            enable_transparency: false,
+            default_transparency_level: 1.0,
+            auto_restore_on_error: true,
+            extra: Map::new(),
+        }
+    }
+}
+
+/// Where a settings blob came from. Files written before versioning have no
+/// `schemaVersion`, while payloads from the running frontend are always current.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingsSource {
+    StoredFile,
+    FrontendPayload,
+}
+
+pub fn parse_general_settings(value: Value, source: SettingsSource) -> Result<GeneralSettings, String> {
+    let value = migrate(value, source, GENERAL_SETTINGS_VERSION, "general", migrate_general_step)?;
+    let mut settings: GeneralSettings = deserialize_with_field_hint(value, "general")?;
+    settings.schema_version = GENERAL_SETTINGS_VERSION;
+    validate_general_settings(&settings)?;
+    Ok(settings)
+}
+
+pub fn parse_audio_settings(value: Value, source: SettingsSource) -> Result<AudioDeviceSettings, String> {
+    let value = migrate(value, source, AUDIO_SETTINGS_VERSION, "audio", migrate_audio_step)?;
+    let mut settings: AudioDeviceSettings = deserialize_with_field_hint(value, "audio")?;
+    settings.schemaVersion = AUDIO_SETTINGS_VERSION;
+    validate_audio_settings(&settings)?;
+    Ok(settings)
+}
+
+fn migrate(
+    value: Value,
+    source: SettingsSource,
+    current: u32,
+    kind: &str,
+    step: fn(u32, &mut Map<String, Value>),
+) -> Result<Value, String> {
+    let mut object = match value {
+        Value::Object(object) => object,
+        other => {
+            return Err(format!(
+                "Invalid {} settings: expected a JSON object, got {}",
+                kind,
+                json_type_name(&other)
+            ))
+        }
+    };
+
+    let default_version = match source {
+        SettingsSource::StoredFile => 0,
+        SettingsSource::FrontendPayload => current,
+    };
+    let version = match object.get("schemaVersion") {
+        None => default_version,
+        Some(v) => v
+            .as_u64()
+            .map(|v| v as u32)
+            .ok_or_else(|| format!("Invalid {} settings: schemaVersion must be a non-negative integer", kind))?,
+    };
+
+    if version > current {
+        return Err(format!(
+            "{} settings were written by a newer version of Enteract (schema v{}, this build supports v{}); update the app or reset the settings",
+            capitalize(kind),
+            version,
+            current
+        ));
+    }
+
+    for from in version..current {
+        step(from, &mut object);
+    }
+    object.insert("schemaVersion".to_string(), Value::from(current));
+    Ok(Value::Object(object))
+}
+
+fn migrate_general_step(from: u32, object: &mut Map<String, Value>) {
+    if from == 0 {
+        // v0 blobs were stored verbatim; a few builds wrote snake_case keys
+        rename_legacy_keys(object, &[
+            ("auto_save_interval", "autoSaveInterval"),
+            ("microphone_whisper_model", "microphoneWhisperModel"),
+            ("loopback_whisper_model", "loopbackWhisperModel"),
+            ("default_transparency_level", "defaultTransparencyLevel"),
+        ]);
+        // The transparency slider used to persist percentages
+        if let Some(level) = object.get("defaultTransparencyLevel").and_then(Value::as_f64) {
+            if level > 1.0 && level <= 100.0 {
+                object.insert("defaultTransparencyLevel".to_string(), Value::from(level / 100.0));
+            }
+        }
+    }
+}
+
+fn migrate_audio_step(from: u32, object: &mut Map<String, Value>) {
+    if from == 0 {
+        rename_legacy_keys(object, &[
+            ("selected_loopback_device", "selectedLoopbackDevice"),
+            ("loopback_enabled", "loopbackEnabled"),
+            ("buffer_size", "bufferSize"),
+            ("sample_rate", "sampleRate"),
+        ]);
+    }
+}
+
+fn rename_legacy_keys(object: &mut Map<String, Value>, renames: &[(&str, &str)]) {
+    for (old, new) in renames {
+        if let Some(value) = object.remove(*old) {
+            object.entry(new.to_string()).or_insert(value);
+        }
+    }
+}
+
+/// serde_json errors on `from_value` don't name the offending field, so on failure
+/// retry each key on its own to point the user at the actual problem.
+fn deserialize_with_field_hint<T>(value: Value, kind: &str) -> Result<T, String>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    match serde_json::from_value::<T>(value.clone()) {
+        Ok(parsed) => Ok(parsed),
+        Err(e) => {
+            if let Value::Object(object) = &value {
+                for (key, field) in object {
+                    let mut single = Map::new();
+                    single.insert(key.clone(), field.clone());
+                    if let Err(field_error) = serde_json::from_value::<T>(Value::Object(single)) {
+                        return Err(format!("Invalid {} settings: field '{}': {}", kind, key, field_error));
+                    }
+                }
+            }
+            Err(format!("Invalid {} settings: {}", kind, e))
+        }
+    }
+}
+
+pub fn validate_general_settings(settings: &GeneralSettings) -> Result<(), String> {
+    check_one_of("theme", &settings.theme, THEMES)?;
+    check_one_of("logLevel", &settings.log_level, LOG_LEVELS)?;
+    // Any catalog variant is selectable, including quantized ids like "small-q5_1"
+    let whisper_models = crate::whisper_models::catalog_ids();
+    let whisper_models: Vec<&str> = whisper_models.iter().map(String::as_str).collect();
+    check_one_of("microphoneWhisperModel", &settings.microphone_whisper_model, &whisper_models)?;
+    check_one_of("loopbackWhisperModel", &settings.loopback_whisper_model, &whisper_models)?;
+
+    if !(1..=120).contains(&settings.auto_save_interval) {
+        return Err(format!(
+            "Invalid general settings: autoSaveInterval must be between 1 and 120 minutes (got {})",
+            settings.auto_save_interval
+        ));
+    }
+    if !(0.0..=1.0).contains(&settings.default_transparency_level) {
+        return Err(format!(
+            "Invalid general settings: defaultTransparencyLevel must be between 0.0 and 1.0 (got {})",
+            settings.default_transparency_level
+        ));
+    }
+    if settings.transcription_language.trim().is_empty() {
+        return Err("Invalid general settings: transcriptionLanguage must not be empty".to_string());
+    }
+    Ok(())
+}
+
+pub fn validate_audio_settings(settings: &AudioDeviceSettings) -> Result<(), String> {
+    if let Some(device) = &settings.selectedLoopbackDevice {
+        if device.trim().is_empty() {
+            return Err("Invalid audio settings: selectedLoopbackDevice must be null or a device id".to_string());
+        }
+    }
+    // The settings slider moves in steps of 1024, so sizes like 3072 are expected
+    if settings.bufferSize % 256 != 0 || !(256..=65536).contains(&settings.bufferSize) {
+        return Err(format!(
+            "Invalid audio settings: bufferSize must be a multiple of 256 between 256 and 65536 (got {})",
+            settings.bufferSize
+        ));
+    }
+    if settings.deviceDsp.keys().any(|device| device.trim().is_empty()) {
+        return Err("Invalid audio settings: deviceDsp entries must be keyed by a device id".to_string());
+    }
+    for (device, mix) in &settings.deviceChannelMix {
+        if device.trim().is_empty() {
+            return Err("Invalid audio settings: deviceChannelMix entries must be keyed by a device id".to_string());
+        }
+        mix.validate()
+            .map_err(|e| format!("Invalid audio settings: deviceChannelMix[{}]: {}", device, e))?;
+    }
+    for (device, alias) in &settings.deviceAliases {
+        if device.trim().is_empty() {
+            return Err("Invalid audio settings: deviceAliases entries must be keyed by a device UID".to_string());
+        }
+        crate::audio_loopback::device_aliases::validate_alias(alias)
+            .map_err(|e| format!("Invalid audio settings: deviceAliases[{}]: {}", device, e))?;
+    }
+    if settings.maxCaptureMinutes > MAX_CAPTURE_LIMIT_MINUTES {
+        return Err(format!(
+            "Invalid audio settings: maxCaptureMinutes must be at most {} (got {})",
+            MAX_CAPTURE_LIMIT_MINUTES, settings.maxCaptureMinutes
+        ));
+    }
+    if settings.captureReminderMinutes > MAX_CAPTURE_LIMIT_MINUTES {
+        return Err(format!(
+            "Invalid audio settings: captureReminderMinutes must be at most {} (got {})",
+            MAX_CAPTURE_LIMIT_MINUTES, settings.captureReminderMinutes
+        ));
+    }
+    settings.audioDucking.validate()
+        .map_err(|e| format!("Invalid audio settings: audioDucking: {}", e))?;
+    if !SAMPLE_RATES.contains(&settings.sampleRate) {
+        return Err(format!(
+            "Invalid audio settings: sampleRate must be one of {:?} (got {})",
+            SAMPLE_RATES, settings.sampleRate
+        ));
+    }
+    Ok(())
+}
+
+fn check_one_of(field: &str, value: &str, allowed: &[&str]) -> Result<(), String> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid general settings: {} must be one of {} (got '{}')",
+            field,
+            allowed.join(", "),
+            value
+        ))
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_legacy_general_file_is_migrated() {
+        let legacy = json!({
+            "theme": "light",
+            "auto_save_interval": 10,
+            "defaultTransparencyLevel": 80,
+            "someFutureFlag": true
+        });
+        let settings = parse_general_settings(legacy, SettingsSource::StoredFile).unwrap();
+        assert_eq!(settings.schema_version, GENERAL_SETTINGS_VERSION);
+        assert_eq!(settings.theme, "light");
+        assert_eq!(settings.auto_save_interval, 10);
+        assert!((settings.default_transparency_level - 0.8).abs() < f64::EPSILON);
+        assert_eq!(settings.extra.get("someFutureFlag"), Some(&json!(true)));
+        // Missing fields fall back to defaults
+        assert_eq!(settings.loopback_whisper_model, "small");
+    }
+
+    #[test]
+    fn test_every_theme_offered_by_the_settings_tab_is_accepted() {
+        for theme in ["dark", "light", "auto"] {
+            let payload = json!({ "theme": theme });
+            assert!(parse_general_settings(payload, SettingsSource::FrontendPayload).is_ok(), "{}", theme);
+        }
+    }
+
+    #[test]
+    fn test_invalid_field_type_names_the_field() {
+        let payload = json!({ "enableNotifications": "yes" });
+        let err = parse_general_settings(payload, SettingsSource::FrontendPayload).unwrap_err();
+        assert!(err.contains("enableNotifications"), "{}", err);
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_rejected() {
+        let payload = json!({ "defaultTransparencyLevel": 1.5 });
+        assert!(parse_general_settings(payload, SettingsSource::FrontendPayload).is_err());
+
+        let payload = json!({ "microphoneWhisperModel": "enormous" });
+        assert!(parse_general_settings(payload, SettingsSource::FrontendPayload).is_err());
+
+        let payload = json!({ "microphoneWhisperModel": "small-q5_1", "loopbackWhisperModel": "large-q5_0" });
+        assert!(parse_general_settings(payload, SettingsSource::FrontendPayload).is_ok());
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let payload = json!({ "schemaVersion": GENERAL_SETTINGS_VERSION + 1 });
+        let err = parse_general_settings(payload, SettingsSource::StoredFile).unwrap_err();
+        assert!(err.contains("newer version"), "{}", err);
+    }
+
+    #[test]
+    fn test_audio_settings_migration_and_validation() {
+        let legacy = json!({ "selected_loopback_device": "device-1", "buffer_size": 2048 });
+        let settings = parse_audio_settings(legacy, SettingsSource::StoredFile).unwrap();
+        assert_eq!(settings.selectedLoopbackDevice.as_deref(), Some("device-1"));
+        assert_eq!(settings.bufferSize, 2048);
+        assert_eq!(settings.sampleRate, 16000);
+
+        let invalid = json!({ "bufferSize": 1000 });
+        assert!(parse_audio_settings(invalid, SettingsSource::FrontendPayload).is_err());
+    }
+
+    #[test]
+    fn test_every_buffer_size_offered_by_the_audio_tab_is_accepted() {
+        for size in (1024..=8192).step_by(1024) {
+            let payload = json!({ "bufferSize": size });
+            assert!(parse_audio_settings(payload, SettingsSource::FrontendPayload).is_ok(), "{}", size);
+        }
+        assert_eq!(parse_audio_settings(json!({ "bufferSize": 3072 }), SettingsSource::FrontendPayload).unwrap().bufferSize, 3072);
+        assert!(parse_audio_settings(json!({ "bufferSize": 128 }), SettingsSource::FrontendPayload).is_err());
+        assert!(parse_audio_settings(json!({ "bufferSize": 131072 }), SettingsSource::FrontendPayload).is_err());
+    }
+}
@@ -1,7 +1,14 @@
 // src-tauri/src/audio_loopback/types.rs
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
+use crate::audio_loopback::ring_buffer::SampleRingBuffer;
+use crate::audio_loopback::dsp::DspConfig;
+use crate::audio_loopback::channel_mixer::ChannelMixConfig;
+use crate::audio_loopback::app_sessions::AudioDuckingConfig;
+pub use crate::shared_types::devices::{AudioLoopbackDevice, DeviceType, LoopbackMethod};
 
 // Audio capture state management
 lazy_static::lazy_static! {
@@ -11,37 +18,24 @@ lazy_static::lazy_static! {
 #[derive(Default)]
 pub struct CaptureState {
     pub is_capturing: bool,
+    pub device_id: Option<String>,
     pub capture_handle: Option<tokio::task::JoinHandle<()>>,
     pub stop_tx: Option<mpsc::Sender<()>>,
+    // Ring buffer of the current (or most recent) capture, kept for stats
+    pub ring_buffer: Option<Arc<SampleRingBuffer>>,
+    // Duration limit bookkeeping, see capture_limits
+    pub started_at: Option<Instant>,
+    pub timer_generation: u64,
+    pub extended_by: Duration,
+    pub reminders_sent: u32,
+    pub stop_warning_sent: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AudioLoopbackDevice {
-    pub id: String,
-    pub name: String,
-    pub is_default: bool,
-    pub sample_rate: u32,
-    pub channels: u16,
-    pub format: String,
-    pub device_type: DeviceType,
-    pub loopback_method: LoopbackMethod,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum DeviceType {
-    Render,
-    Capture,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum LoopbackMethod {
-    RenderLoopback,
-    CaptureDevice,
-    StereoMix,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AudioDeviceSettings {
+    #[serde(alias = "schema_version")]
+    pub schemaVersion: u32,
     #[serde(alias = "selected_loopback_device")]
     pub selectedLoopbackDevice: Option<String>,
     #[serde(alias = "loopback_enabled")]
@@ -50,15 +44,40 @@ pub struct AudioDeviceSettings {
     pub bufferSize: u32,
     #[serde(alias = "sample_rate")]
     pub sampleRate: u32,
+    // Per-device DSP stages, keyed by device id
+    #[serde(alias = "device_dsp")]
+    pub deviceDsp: HashMap<String, DspConfig>,
+    // Channel selection / downmix weights for multi-channel devices, keyed by device id
+    #[serde(alias = "device_channel_mix")]
+    pub deviceChannelMix: HashMap<String, ChannelMixConfig>,
+    // User-assigned friendly names, keyed by device UID
+    #[serde(alias = "device_aliases")]
+    pub deviceAliases: HashMap<String, String>,
+    // Captures are stopped after this many minutes; 0 disables the limit
+    #[serde(alias = "max_capture_minutes")]
+    pub maxCaptureMinutes: u32,
+    // "Still recording?" reminder interval in minutes; 0 disables reminders
+    #[serde(alias = "capture_reminder_minutes")]
+    pub captureReminderMinutes: u32,
+    // Which apps to turn down while capturing, and by how much
+    #[serde(alias = "audio_ducking")]
+    pub audioDucking: AudioDuckingConfig,
 }
 
 impl Default for AudioDeviceSettings {
     fn default() -> Self {
         Self {
+            schemaVersion: crate::audio_loopback::settings_schema::AUDIO_SETTINGS_VERSION,
             selectedLoopbackDevice: None,
             loopbackEnabled: false,
             bufferSize: 4096,
             sampleRate: 16000,
+            deviceDsp: HashMap::new(),
+            deviceChannelMix: HashMap::new(),
+            deviceAliases: HashMap::new(),
+            maxCaptureMinutes: 240,
+            captureReminderMinutes: 120,
+            audioDucking: AudioDuckingConfig::default(),
         }
     }
 }
@@ -0,0 +1,130 @@
+// src-tauri/src/audio_loopback/windows/audio_sessions.rs
+// Per-app audio sessions on the default output device, through IAudioSessionManager2
+use crate::audio_loopback::app_sessions::AudioSessionInfo;
+use anyhow::Result;
+use wasapi::initialize_mta;
+use windows::core::{ComInterface, PWSTR};
+use windows::Win32::Foundation::{CloseHandle, S_OK};
+use windows::Win32::Media::Audio::{
+    eConsole, eRender, AudioSessionStateActive, AudioSessionStateExpired, IAudioSessionControl2,
+    IAudioSessionManager2, IMMDeviceEnumerator, ISimpleAudioVolume, MMDeviceEnumerator,
+};
+use windows::Win32::System::Com::{CoCreateInstance, CoTaskMemFree, CLSCTX_ALL};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+
+struct Session {
+    control: IAudioSessionControl2,
+    volume: ISimpleAudioVolume,
+}
+
+// Sessions that haven't expired; a process can have more than one
+fn sessions() -> Result<Vec<Session>> {
+    initialize_mta().map_err(|_| anyhow::anyhow!("Failed to initialize COM"))?;
+    unsafe {
+        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+            .map_err(|e| anyhow::anyhow!("Failed to create device enumerator: {}", e))?;
+        let device = enumerator
+            .GetDefaultAudioEndpoint(eRender, eConsole)
+            .map_err(|e| anyhow::anyhow!("No default output device: {}", e))?;
+        let manager: IAudioSessionManager2 = device
+            .Activate(CLSCTX_ALL, None)
+            .map_err(|e| anyhow::anyhow!("Failed to open the session manager: {}", e))?;
+        let list = manager
+            .GetSessionEnumerator()
+            .map_err(|e| anyhow::anyhow!("Failed to enumerate audio sessions: {}", e))?;
+
+        let mut sessions = Vec::new();
+        for index in 0..list.GetCount()? {
+            let Ok(control) = list.GetSession(index).and_then(|session| session.cast::<IAudioSessionControl2>()) else {
+                continue;
+            };
+            if control.GetState().map(|state| state == AudioSessionStateExpired).unwrap_or(true) {
+                continue;
+            }
+            if let Ok(volume) = control.cast::<ISimpleAudioVolume>() {
+                sessions.push(Session { control, volume });
+            }
+        }
+        Ok(sessions)
+    }
+}
+
+// Executable name, e.g. `Spotify.exe`; `None` for system sounds or processes we can't open
+pub(crate) fn process_name(process_id: u32) -> Option<String> {
+    if process_id == 0 {
+        return None;
+    }
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let queried = QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut size);
+        let _ = CloseHandle(handle);
+        queried.ok()?;
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+        std::path::Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    }
+}
+
+fn display_name(control: &IAudioSessionControl2) -> Option<String> {
+    unsafe {
+        let name = control.GetDisplayName().ok()?;
+        let text = name.to_string().ok();
+        CoTaskMemFree(Some(name.0 as *const _));
+        // Resource references like "@%SystemRoot%\..." aren't meant for display
+        text.filter(|text| !text.is_empty() && !text.starts_with('@'))
+    }
+}
+
+pub fn list_sessions() -> Result<Vec<AudioSessionInfo>> {
+    let mut infos = Vec::new();
+    for session in sessions()? {
+        unsafe {
+            let process_id = session.control.GetProcessId().unwrap_or(0);
+            let system_sounds = session.control.IsSystemSoundsSession() == S_OK;
+            let process_name = process_name(process_id);
+            let display_name = display_name(&session.control)
+                .or_else(|| process_name.as_ref().map(|name| name.trim_end_matches(".exe").to_string()))
+                .unwrap_or_else(|| if system_sounds { "System sounds".to_string() } else { format!("Process {}", process_id) });
+            infos.push(AudioSessionInfo {
+                process_id,
+                process_name,
+                display_name,
+                volume: session.volume.GetMasterVolume().unwrap_or(1.0),
+                muted: session.volume.GetMute().map(|muted| muted.as_bool()).unwrap_or(false),
+                active: session.control.GetState().map(|state| state == AudioSessionStateActive).unwrap_or(false),
+                system_sounds,
+                ducked: false,
+            });
+        }
+    }
+    Ok(infos)
+}
+
+// Apply `change` to every session of `process_id`; errors if the process has none
+fn with_process_sessions(process_id: u32, change: impl Fn(&ISimpleAudioVolume) -> windows::core::Result<()>) -> Result<()> {
+    let mut matched = false;
+    for session in sessions()? {
+        if unsafe { session.control.GetProcessId() }.ok() == Some(process_id) {
+            change(&session.volume).map_err(|e| anyhow::anyhow!("Failed to change session volume: {}", e))?;
+            matched = true;
+        }
+    }
+    if matched {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("No audio session for process {}", process_id))
+    }
+}
+
+pub fn set_session_volume(process_id: u32, volume: f32) -> Result<()> {
+    with_process_sessions(process_id, |session| unsafe { session.SetMasterVolume(volume, std::ptr::null()) })
+}
+
+pub fn set_session_mute(process_id: u32, muted: bool) -> Result<()> {
+    with_process_sessions(process_id, |session| unsafe { session.SetMute(muted, std::ptr::null()) })
+}
@@ -2,7 +2,13 @@
 use crate::audio_loopback::types::*;
 use crate::audio_loopback::windows::device_enumerator::WASAPILoopbackEnumerator;
 use crate::audio_loopback::audio_processor::{process_audio_for_transcription, process_audio_chunk, calculate_audio_level};
+use crate::audio_loopback::dsp::{process_loopback, reset_echo_reference};
+use crate::audio_loopback::session_recorder::push_loopback;
+use crate::audio_loopback::channel_mixer::configured_matrix;
+use crate::audio_loopback::ring_buffer::{SampleRingBuffer, RING_BUFFER_CONFIG};
 use anyhow::Result;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 use tokio::sync::mpsc;
@@ -10,7 +16,7 @@ use wasapi::{DeviceCollection, Direction, Device, ShareMode, initialize_mta};
 use base64::prelude::*;
 use serde_json;
 
-#[tauri::command]
+#[crate::command]
 pub async fn start_audio_loopback_capture(
     device_id: String,
     app_handle: AppHandle
@@ -23,7 +29,10 @@ pub async fn start_audio_loopback_capture(
         }
     }
     
-    // println!("🎤 Starting audio capture for device: {}", device_id); // Commented out: Audio loopback is working, reducing console noise for debugging focus
+    println!(
+        "[CAPTURE] Starting loopback capture on {}",
+        crate::audio_loopback::device_aliases::describe_device(&device_id)
+    );
     
     // Create stop channel
     let (stop_tx, stop_rx) = mpsc::channel::<()>(1);
@@ -42,20 +51,34 @@ pub async fn start_audio_loopback_capture(
     {
         let mut state = CAPTURE_STATE.lock().unwrap();
         state.is_capturing = true;
+        state.device_id = Some(device_id);
         state.capture_handle = Some(handle);
         state.stop_tx = Some(stop_tx);
     }
+    crate::audio_loopback::capture_limits::begin_capture_timer(&app_handle);
+    crate::audio_loopback::app_sessions::duck_for_capture().await;
     
+    crate::accessibility::announce(
+        &app_handle,
+        crate::accessibility::AnnouncementCategory::CaptureStarted,
+        crate::accessibility::AnnouncementPriority::Polite,
+        "System audio capture started",
+    );
+
     Ok("Audio capture started".to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn stop_audio_loopback_capture() -> Result<(), String> {
     // println!("⏹️ Stopping audio capture"); // Commented out: Audio loopback is working, reducing console noise for debugging focus
     
     let (stop_tx, handle) = {
         let mut state = CAPTURE_STATE.lock().unwrap();
         state.is_capturing = false;
+        state.device_id = None;
+        state.started_at = None;
+        // Stale far-end audio would only confuse the echo canceller
+        reset_echo_reference();
         (state.stop_tx.take(), state.capture_handle.take())
     };
     
@@ -68,6 +91,7 @@ pub async fn stop_audio_loopback_capture() -> Result<(), String> {
     if let Some(handle) = handle {
         let _ = handle.await;
     }
+    crate::audio_loopback::app_sessions::restore_ducked_sessions().await;
     
     Ok(())
 }
@@ -143,6 +167,9 @@ fn run_audio_capture_loop_sync(
         return Err(anyhow::anyhow!("Unsupported bits per sample: {}", bits_per_sample));
     }
     
+    // User channel selection / downmix weights; None keeps the default handling
+    let downmix = configured_matrix(&device_id, channels);
+    
     // Start the stream
     audio_client.start_stream()
         .map_err(|_| anyhow::anyhow!("Failed to start stream"))?;
@@ -150,19 +177,29 @@ fn run_audio_capture_loop_sync(
     std::thread::sleep(Duration::from_millis(100));
     
     let start_time = Instant::now();
-    let mut total_samples = 0u64;
-    let mut last_emit = Instant::now();
     let mut error_count = 0u32;
     
-    // Transcription buffer setup - MATCHING PYTHON CONFIG
-    let mut transcription_buffer: Vec<f32> = Vec::new();
-    let transcription_buffer_duration = 4.0;  // Python: BUFFER_DURATION = 4.0
-    // Important: Buffer size is at 16kHz (Whisper rate), not device rate
-    let transcription_buffer_size = (16000.0 * transcription_buffer_duration) as usize;
-    let mut last_transcription = Instant::now();
-    let transcription_interval = Duration::from_millis(800);  // Python: PROCESSING_INTERVAL = 0.8
-    let min_audio_length = 1.5;  // Python: MIN_AUDIO_LENGTH = 1.5
-    let min_audio_samples = (16000.0 * min_audio_length) as usize;  // At 16kHz
+    // Bounded hand-off so slow transcription/emit work can't stall device reads
+    let ring_config = RING_BUFFER_CONFIG.lock().unwrap().clone();
+    let ring = SampleRingBuffer::from_config(&ring_config, 16000);
+    {
+        let mut state = CAPTURE_STATE.lock().unwrap();
+        state.ring_buffer = Some(ring.clone());
+    }
+    
+    let processing_stop = Arc::new(AtomicBool::new(false));
+    let processing_thread = {
+        let ring = ring.clone();
+        let processing_stop = processing_stop.clone();
+        let app_handle = app_handle.clone();
+        let device_id = device_id.clone();
+        let device_sample_rate = device_info.sample_rate;
+        let runtime = tokio::runtime::Handle::current();
+        std::thread::Builder::new()
+            .name("wasapi-capture-processing".to_string())
+            .spawn(move || run_processing_loop(ring, processing_stop, app_handle, device_id, device_sample_rate, runtime))
+            .map_err(|e| anyhow::anyhow!("Failed to spawn processing thread: {}", e))?
+    };
     
     // Main capture loop with reduced logging
     loop {
@@ -246,9 +283,61 @@ fn run_audio_capture_loop_sync(
             bits_per_sample,
             channels,
             format.get_samplespersec(),
-            16000  // Always resample to 16kHz for Whisper
+            16000,  // Always resample to 16kHz for Whisper
+            downmix.as_ref()
         );
         
+        ring.push(&processed_audio);
+    }
+    
+    let _ = audio_client.stop_stream();
+    processing_stop.store(true, Ordering::Release);
+    let _ = processing_thread.join();
+    // println!("Audio capture stopped"); // Commented out: Audio loopback is working, reducing console noise for debugging focus
+    
+    Ok(())
+}
+
+// Transcription and audio-chunk emission, fed from the capture ring buffer
+fn run_processing_loop(
+    ring: Arc<SampleRingBuffer>,
+    stop: Arc<AtomicBool>,
+    app_handle: AppHandle,
+    device_id: String,
+    device_sample_rate: u32,
+    runtime: tokio::runtime::Handle,
+) {
+    let start_time = Instant::now();
+    let mut total_samples = 0u64;
+    let mut last_emit = Instant::now();
+    
+    // Transcription buffer setup - MATCHING PYTHON CONFIG
+    let mut transcription_buffer: Vec<f32> = Vec::new();
+    let transcription_buffer_duration = 4.0;  // Python: BUFFER_DURATION = 4.0
+    // Important: Buffer size is at 16kHz (Whisper rate), not device rate
+    let transcription_buffer_size = (16000.0 * transcription_buffer_duration) as usize;
+    let mut last_transcription = Instant::now();
+    let transcription_interval = Duration::from_millis(800);  // Python: PROCESSING_INTERVAL = 0.8
+    let min_audio_length = 1.5;  // Python: MIN_AUDIO_LENGTH = 1.5
+    let min_audio_samples = (16000.0 * min_audio_length) as usize;  // At 16kHz
+    
+    let mut processed_audio: Vec<f32> = Vec::with_capacity(4096);
+    
+    loop {
+        processed_audio.clear();
+        if ring.pop_into(&mut processed_audio, 16000) == 0 {
+            // Drain whatever is left before honouring the stop signal
+            if stop.load(Ordering::Acquire) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+            continue;
+        }
+        
+        // Optional denoising; also feeds the echo reference used for microphones
+        process_loopback(&device_id, &mut processed_audio);
+        push_loopback(&processed_audio);
+        
         total_samples += processed_audio.len() as u64;
         transcription_buffer.extend_from_slice(&processed_audio);
         
@@ -298,7 +387,7 @@ fn run_audio_capture_loop_sync(
                 //          pcm16_bytes.len(), buffer_rms);
                 // Commented out: Audio loopback is working, reducing console noise for debugging focus
                 
-                tokio::spawn(async move {
+                runtime.spawn(async move {
                     match process_audio_for_transcription(
                         audio_bytes_clone,
                         sample_rate,
@@ -341,7 +430,7 @@ fn run_audio_capture_loop_sync(
             let _emit_result = app_handle.emit("audio-chunk", serde_json::json!({
                 "deviceId": device_id,
                 "audioData": base64::prelude::BASE64_STANDARD.encode(&audio_bytes),
-                "sampleRate": device_info.sample_rate,
+                "sampleRate": device_sample_rate,
                 "channels": 1,
                 "level": level,
                 "timestamp": chrono::Utc::now().timestamp_millis(),
@@ -352,11 +441,6 @@ fn run_audio_capture_loop_sync(
             last_emit = now;
         }
     }
-    
-    let _ = audio_client.stop_stream();
-    // println!("Audio capture stopped"); // Commented out: Audio loopback is working, reducing console noise for debugging focus
-    
-    Ok(())
 }
 
 // Helper function to find WASAPI device
@@ -1,7 +1,15 @@
 // src-tauri/src/audio_loopback/windows/device_enumerator.rs
 use crate::audio_loopback::types::*;
+use crate::audio_loopback::device_monitor::{DeviceChangeEvent, DeviceChangeKind};
 use anyhow::Result;
 use wasapi::{DeviceCollection, Direction, Device, ShareMode, get_default_device, initialize_mta};
+use windows::core::{implement, PCWSTR};
+use windows::Win32::Media::Audio::{
+    eConsole, EDataFlow, ERole, IMMDeviceEnumerator, IMMNotificationClient,
+    IMMNotificationClient_Impl, MMDeviceEnumerator, DEVICE_STATE,
+};
+use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+use windows::Win32::UI::Shell::PropertiesSystem::PROPERTYKEY;
 
 // WASAPI Device Enumerator Implementation
 pub struct WASAPILoopbackEnumerator {
@@ -152,8 +160,12 @@ impl WASAPILoopbackEnumerator {
         let (sample_rate, channels, format) = self.get_device_format(device)?;
         
         Ok(AudioLoopbackDevice {
+            // WASAPI endpoint ids are already stable
+            uid: id.clone(),
             id,
+            display_name: name.clone(),
             name,
+            alias: None,
             is_default,
             sample_rate,
             channels,
@@ -172,8 +184,12 @@ impl WASAPILoopbackEnumerator {
         let (sample_rate, channels, format) = self.get_device_format(device)?;
         
         Ok(AudioLoopbackDevice {
+            // WASAPI endpoint ids are already stable
+            uid: id.clone(),
             id,
+            display_name: name.clone(),
             name,
+            alias: None,
             is_default,
             sample_rate,
             channels,
@@ -314,13 +330,112 @@ impl WASAPILoopbackEnumerator {
     }
 }
 
+// WASAPI endpoint notifications (IMMNotificationClient)
+type DeviceChangeCallback = Box<dyn Fn(DeviceChangeEvent) + Send + Sync>;
+
+#[implement(IMMNotificationClient)]
+struct DeviceNotificationClient {
+    callback: DeviceChangeCallback,
+}
+
+impl DeviceNotificationClient {
+    fn notify(&self, kind: DeviceChangeKind, device_id: &PCWSTR) {
+        let device_id = if device_id.is_null() {
+            None
+        } else {
+            unsafe { device_id.to_string().ok() }
+        };
+        (self.callback)(DeviceChangeEvent { kind, device_id });
+    }
+}
+
+impl IMMNotificationClient_Impl for DeviceNotificationClient {
+    fn OnDeviceStateChanged(&self, pwstrdeviceid: &PCWSTR, _dwnewstate: DEVICE_STATE) -> windows::core::Result<()> {
+        self.notify(DeviceChangeKind::StateChanged, pwstrdeviceid);
+        Ok(())
+    }
+
+    fn OnDeviceAdded(&self, pwstrdeviceid: &PCWSTR) -> windows::core::Result<()> {
+        self.notify(DeviceChangeKind::Added, pwstrdeviceid);
+        Ok(())
+    }
+
+    fn OnDeviceRemoved(&self, pwstrdeviceid: &PCWSTR) -> windows::core::Result<()> {
+        self.notify(DeviceChangeKind::Removed, pwstrdeviceid);
+        Ok(())
+    }
+
+    fn OnDefaultDeviceChanged(&self, _flow: EDataFlow, role: ERole, pwstrdefaultdeviceid: &PCWSTR) -> windows::core::Result<()> {
+        // Windows fires this once per role; only the console role matters for capture
+        if role == eConsole {
+            self.notify(DeviceChangeKind::DefaultChanged, pwstrdefaultdeviceid);
+        }
+        Ok(())
+    }
+
+    fn OnPropertyValueChanged(&self, _pwstrdeviceid: &PCWSTR, _key: &PROPERTYKEY) -> windows::core::Result<()> {
+        // Property changes (names, formats) don't affect capture availability
+        Ok(())
+    }
+}
+
+/// Register an endpoint notification client for the lifetime of the process.
+/// The COM objects live on a dedicated MTA thread so they are never dropped.
+pub fn register_device_change_listener<F>(callback: F) -> Result<()>
+where
+    F: Fn(DeviceChangeEvent) + Send + Sync + 'static,
+{
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel::<Result<()>>();
+
+    std::thread::Builder::new()
+        .name("wasapi-device-monitor".to_string())
+        .spawn(move || {
+            let registration = (|| -> Result<(IMMDeviceEnumerator, IMMNotificationClient)> {
+                initialize_mta()
+                    .map_err(|_| anyhow::anyhow!("Failed to initialize COM"))?;
+                let enumerator: IMMDeviceEnumerator = unsafe {
+                    CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                }
+                .map_err(|e| anyhow::anyhow!("Failed to create device enumerator: {}", e))?;
+                let client: IMMNotificationClient = DeviceNotificationClient {
+                    callback: Box::new(callback),
+                }
+                .into();
+                unsafe { enumerator.RegisterEndpointNotificationCallback(&client) }
+                    .map_err(|e| anyhow::anyhow!("Failed to register device notifications: {}", e))?;
+                Ok((enumerator, client))
+            })();
+
+            match registration {
+                Ok(_registered) => {
+                    let _ = ready_tx.send(Ok(()));
+                    // Keep the enumerator and client alive; notifications arrive on COM threads
+                    loop {
+                        std::thread::park();
+                    }
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                }
+            }
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to spawn device monitor thread: {}", e))?;
+
+    ready_rx
+        .recv()
+        .map_err(|_| anyhow::anyhow!("Device monitor thread exited unexpectedly"))?
+}
+
 // Tauri Commands
-#[tauri::command]
+#[crate::command]
 pub async fn enumerate_loopback_devices() -> Result<Vec<AudioLoopbackDevice>, String> {
     match WASAPILoopbackEnumerator::new() {
         Ok(enumerator) => {
             match enumerator.enumerate_loopback_devices() {
-                Ok(devices) => Ok(devices),
+                Ok(mut devices) => {
+                    crate::audio_loopback::device_aliases::apply_aliases(&mut devices);
+                    Ok(devices)
+                }
                 Err(e) => Err(format!("Failed to enumerate audio devices: {}", e))
             }
         },
@@ -328,12 +443,15 @@ pub async fn enumerate_loopback_devices() -> Result<Vec<AudioLoopbackDevice>, St
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn auto_select_best_device() -> Result<Option<AudioLoopbackDevice>, String> {
     match WASAPILoopbackEnumerator::new() {
         Ok(enumerator) => {
             match enumerator.auto_select_best_device() {
-                Ok(device) => Ok(device),
+                Ok(mut device) => {
+                    crate::audio_loopback::device_aliases::apply_aliases(device.as_mut_slice());
+                    Ok(device)
+                }
                 Err(e) => Err(format!("Failed to auto-select device: {}", e))
             }
         },
@@ -341,7 +459,7 @@ pub async fn auto_select_best_device() -> Result<Option<AudioLoopbackDevice>, St
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn test_audio_device(device_id: String) -> Result<bool, String> {
     match WASAPILoopbackEnumerator::new() {
         Ok(enumerator) => {
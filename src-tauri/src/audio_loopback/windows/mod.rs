@@ -3,6 +3,7 @@
 
 pub mod device_enumerator;
 pub mod capture_engine;
+pub mod audio_sessions;
 
 pub use device_enumerator::*;
 pub use capture_engine::*;
@@ -0,0 +1,279 @@
+// Cancellation for long-running operations - AI responses, embedding jobs, transcription, OCR
+// and MCP tool execution each register a token under an id, and `cancel_operation(id)` stops
+// whichever of them is running.
+//
+// A token is also made the task's current token while the operation runs, so code deep inside
+// it (the worker process round trip, the Whisper abort callback, OCR) can check it without
+// every function in between taking a parameter.
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// The error a cancelled operation returns
+pub const CANCELLED: &str = "Operation cancelled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationKind {
+    AiResponse,
+    #[cfg_attr(not(feature = "enhanced-rag"), allow(dead_code))]
+    Embedding,
+    Transcription,
+    #[cfg_attr(not(feature = "mcp"), allow(dead_code))]
+    McpTool,
+    #[cfg_attr(not(feature = "mcp"), allow(dead_code))]
+    McpPlan,
+    ModelPull,
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        self.state.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// `Err(CANCELLED)` once cancelled, for `?` between steps
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Resolves when the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            // Registered before the check so a cancel in between isn't missed
+            let notified = self.state.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Run `future`, dropping it and returning `Err(CANCELLED)` if the token is cancelled first
+    pub async fn run<T>(&self, future: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => Err(CANCELLED.to_string()),
+            result = future => result,
+        }
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+struct Registered {
+    kind: OperationKind,
+    label: String,
+    started_at: i64,
+    token: CancellationToken,
+}
+
+lazy_static::lazy_static! {
+    static ref OPERATIONS: Mutex<HashMap<String, Registered>> = Mutex::new(HashMap::new());
+}
+
+tokio::task_local! {
+    static CURRENT: CancellationToken;
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: OperationKind,
+    pub label: String,
+    pub started_at: i64,
+    pub cancelled: bool,
+}
+
+/// A running operation; it stays cancellable by id until this is dropped
+pub struct Operation {
+    id: String,
+    token: CancellationToken,
+}
+
+impl Operation {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Run `future` with this operation's token as the task's current token, stopping it when
+    /// the operation is cancelled
+    pub async fn run<T>(&self, future: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+        let token = self.token.clone();
+        CURRENT.scope(token.clone(), token.run(future)).await
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        if let Ok(mut operations) = OPERATIONS.lock() {
+            // A newer operation may have taken over the id
+            if operations.get(&self.id).is_some_and(|registered| registered.token.same(&self.token)) {
+                operations.remove(&self.id);
+            }
+        }
+    }
+}
+
+/// Register an operation under `id`, replacing any earlier one with the same id
+pub fn start(id: impl Into<String>, kind: OperationKind, label: impl Into<String>) -> Operation {
+    let id = id.into();
+    let token = CancellationToken::new();
+    if let Ok(mut operations) = OPERATIONS.lock() {
+        operations.insert(id.clone(), Registered {
+            kind,
+            label: label.into(),
+            started_at: chrono::Utc::now().timestamp_millis(),
+            token: token.clone(),
+        });
+    }
+    Operation { id, token }
+}
+
+/// Cancel the operation registered under `id`; false if nothing is running under it
+pub fn cancel(id: &str) -> bool {
+    let token = OPERATIONS
+        .lock()
+        .ok()
+        .and_then(|operations| operations.get(id).map(|registered| registered.token.clone()));
+    match token {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Cancel every running operation of one of `kinds`; returns how many were cancelled
+#[cfg_attr(not(feature = "mcp"), allow(dead_code))]
+pub fn cancel_kinds(kinds: &[OperationKind]) -> usize {
+    let tokens: Vec<CancellationToken> = OPERATIONS
+        .lock()
+        .map(|operations| {
+            operations
+                .values()
+                .filter(|registered| kinds.contains(&registered.kind) && !registered.token.is_cancelled())
+                .map(|registered| registered.token.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    tokens.iter().for_each(CancellationToken::cancel);
+    tokens.len()
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn is_cancelled(id: &str) -> bool {
+    OPERATIONS
+        .lock()
+        .ok()
+        .and_then(|operations| operations.get(id).map(|registered| registered.token.is_cancelled()))
+        .unwrap_or(false)
+}
+
+/// The token of the operation this task is running, if any
+pub fn current() -> Option<CancellationToken> {
+    CURRENT.try_with(|token| token.clone()).ok()
+}
+
+/// Run `future`, stopping it if the task's current operation is cancelled
+#[cfg_attr(not(feature = "mcp"), allow(dead_code))]
+pub async fn cancellable<T>(future: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+    match current() {
+        Some(token) => token.run(future).await,
+        None => future.await,
+    }
+}
+
+/// Stop a running operation: an AI response (by session id), an embedding job
+/// (`embedding:<document id>`), a transcription, a model pull (`pull:<model>`), an MCP tool
+/// call or plan. Returns whether anything was running under `id`.
+#[crate::command]
+pub fn cancel_operation(id: String) -> Result<bool, String> {
+    let found = cancel(&id);
+    if found {
+        println!("🛑 Cancellation requested for operation: {}", id);
+    }
+    Ok(found)
+}
+
+#[crate::command]
+pub fn list_operations() -> Result<Vec<OperationInfo>, String> {
+    let operations = OPERATIONS.lock().map_err(|_| "Failed to read operations".to_string())?;
+    let mut list: Vec<OperationInfo> = operations
+        .iter()
+        .map(|(id, registered)| OperationInfo {
+            id: id.clone(),
+            kind: registered.kind,
+            label: registered.label.clone(),
+            started_at: registered.started_at,
+            cancelled: registered.token.is_cancelled(),
+        })
+        .collect();
+    list.sort_by_key(|operation| operation.started_at);
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_stops_running_operation_and_reaches_current_token() {
+        let operation = start("test-op", OperationKind::Transcription, "test");
+        let waiting = operation.run(async {
+            // The nested code sees the operation's token without being passed it
+            assert!(current().is_some());
+            cancellable(std::future::pending::<Result<(), String>>()).await
+        });
+        let canceller = async {
+            tokio::task::yield_now().await;
+            assert!(cancel("test-op"));
+        };
+        let (result, _) = tokio::join!(waiting, canceller);
+        assert_eq!(result, Err(CANCELLED.to_string()));
+        assert!(is_cancelled("test-op"));
+        assert!(current().is_none());
+
+        // A replaced operation's guard doesn't unregister its successor
+        let newer = start("test-op", OperationKind::Transcription, "newer");
+        drop(operation);
+        assert!(!newer.is_cancelled());
+        assert!(list_operations().unwrap().iter().any(|op| op.id == "test-op" && op.label == "newer"));
+        drop(newer);
+        assert!(!cancel("test-op"));
+    }
+}
@@ -1,7 +1,50 @@
 use anyhow::{Result, anyhow};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 use tiktoken_rs::cl100k_base;
 
+use crate::simple_embedding_service::cosine_similarity;
+
+/// How a document's text is split into chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkingStrategy {
+    /// Sentence and paragraph boundaries, falling back to fixed token windows
+    Standard,
+    /// One section per heading, each chunk prefixed with its heading path
+    Markdown,
+    /// Top-level functions, types and classes, small ones packed together
+    Code,
+    /// Runs of sentences whose embeddings stay similar; needs the embedding service
+    Semantic,
+}
+
+impl ChunkingStrategy {
+    /// Whether the text keeps its line breaks, which these strategies split on
+    pub fn keeps_layout(self) -> bool {
+        matches!(self, ChunkingStrategy::Markdown | ChunkingStrategy::Code)
+    }
+}
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "mjs", "ts", "tsx", "go", "java", "kt", "cs", "swift", "c", "h", "cpp", "cc", "hpp",
+];
+
+fn default_strategies() -> HashMap<String, ChunkingStrategy> {
+    let mut strategies: HashMap<String, ChunkingStrategy> = ["md", "markdown", "text/markdown"]
+        .iter()
+        .map(|key| (key.to_string(), ChunkingStrategy::Markdown))
+        .collect();
+    strategies.extend(CODE_EXTENSIONS.iter().map(|extension| (extension.to_string(), ChunkingStrategy::Code)));
+    strategies
+}
+
+fn default_semantic_threshold() -> f32 {
+    0.75
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChunkingConfig {
     pub chunk_size: usize,
@@ -10,6 +53,12 @@ pub struct ChunkingConfig {
     pub min_chunk_size: usize,
     pub respect_sentence_boundaries: bool,
     pub respect_paragraph_boundaries: bool,
+    /// Strategy by file extension or MIME type; anything not listed is chunked with `Standard`
+    #[serde(default = "default_strategies")]
+    pub strategies: HashMap<String, ChunkingStrategy>,
+    /// How similar neighbouring sentences must be to stay in one semantic chunk
+    #[serde(default = "default_semantic_threshold")]
+    pub semantic_similarity_threshold: f32,
 }
 
 impl Default for ChunkingConfig {
@@ -21,10 +70,103 @@ impl Default for ChunkingConfig {
             min_chunk_size: 100,
             respect_sentence_boundaries: true,
             respect_paragraph_boundaries: true,
+            strategies: default_strategies(),
+            semantic_similarity_threshold: default_semantic_threshold(),
+        }
+    }
+}
+
+impl ChunkingConfig {
+    /// The strategy for a document, by its extension first and then its MIME type
+    pub fn strategy_for(&self, file_name: &str, file_type: &str) -> ChunkingStrategy {
+        let extension = Path::new(file_name).extension().and_then(|extension| extension.to_str()).map(str::to_lowercase);
+        let mime = file_type.split(';').next().unwrap_or("").trim().to_lowercase();
+        extension
+            .and_then(|extension| self.strategies.get(&extension))
+            .or_else(|| self.strategies.get(&mime))
+            .copied()
+            .unwrap_or(ChunkingStrategy::Standard)
+    }
+}
+
+/// Languages code chunking knows the definitions of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    /// Java, Kotlin, C#, Swift - methods sit one level into a class
+    ClassBased,
+    C,
+}
+
+impl CodeLanguage {
+    fn from_file_name(file_name: &str) -> Option<Self> {
+        let extension = Path::new(file_name).extension()?.to_str()?.to_lowercase();
+        Some(match extension.as_str() {
+            "rs" => CodeLanguage::Rust,
+            "py" => CodeLanguage::Python,
+            "js" | "jsx" | "mjs" | "ts" | "tsx" => CodeLanguage::JavaScript,
+            "go" => CodeLanguage::Go,
+            "java" | "kt" | "cs" | "swift" => CodeLanguage::ClassBased,
+            "c" | "h" | "cpp" | "cc" | "hpp" => CodeLanguage::C,
+            _ => return None,
+        })
+    }
+
+    /// Lines above a definition that belong to it: doc comments, attributes, decorators
+    fn is_attachment(self, line: &str) -> bool {
+        let line = line.trim_start();
+        match self {
+            CodeLanguage::Python => line.starts_with('@') || line.starts_with('#'),
+            CodeLanguage::Rust => line.starts_with("//") || line.starts_with("#["),
+            _ => line.starts_with("//") || line.starts_with("/*") || line.starts_with('*') || line.starts_with('@') || line.starts_with('['),
         }
     }
 }
 
+lazy_static::lazy_static! {
+    static ref MARKDOWN_HEADING: Regex = Regex::new(r"^ {0,3}(#{1,6})\s+(.*?)\s*#*\s*$").unwrap();
+    static ref CODE_DEFINITIONS: HashMap<&'static str, Regex> = [
+        ("rust", r#"^(pub(\([^)]*\))?\s+)?((async|unsafe|const|default|extern\s+"[^"]*")\s+)*(fn|struct|enum|union|trait|impl|mod|type|macro_rules!)\b"#),
+        ("python", r"^(async\s+def|def|class)\s"),
+        ("javascript", r"^(export\s+(default\s+)?)?(declare\s+)?(abstract\s+)?(async\s+)?(function\*?|class|interface|type|enum|const|let|namespace)\s"),
+        ("go", r"^(func|type)\s"),
+        ("class_based", r"^( {0,4}|\t?)((public|private|protected|internal|static|final|abstract|override|open|sealed|async|virtual|data|suspend)\s+)*(class|interface|enum|struct|record|object|fun|func|void|[A-Za-z_][\w<>\[\],]*\s+[A-Za-z_]\w*\s*\()"),
+        ("c", r"^[A-Za-z_][\w\s\*&:<>,]*\b[A-Za-z_]\w*\s*\([^;]*$|^(struct|class|enum|union|typedef|namespace)\b"),
+    ]
+    .into_iter()
+    .map(|(name, pattern)| (name, Regex::new(pattern).unwrap()))
+    .collect();
+    // Statements the C-like patterns would take for a definition
+    static ref NOT_DEFINITIONS: Regex = Regex::new(r"^(if|for|while|switch|return|else|do|throw|new|await|yield)\b").unwrap();
+}
+
+fn is_definition(language: CodeLanguage, line: &str) -> bool {
+    let key = match language {
+        CodeLanguage::Rust => "rust",
+        CodeLanguage::Python => "python",
+        CodeLanguage::JavaScript => "javascript",
+        CodeLanguage::Go => "go",
+        CodeLanguage::ClassBased => "class_based",
+        CodeLanguage::C => "c",
+    };
+    let keyword_line = matches!(language, CodeLanguage::C | CodeLanguage::ClassBased) && NOT_DEFINITIONS.is_match(line.trim_start());
+    CODE_DEFINITIONS[key].is_match(line) && !keyword_line
+}
+
+/// Byte spans of the lines of `text[start..end]`, newlines included
+fn line_spans(text: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut position = start;
+    for line in text[start..end].split_inclusive('\n') {
+        spans.push((position, position + line.len()));
+        position += line.len();
+    }
+    spans
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextChunk {
     pub content: String,
@@ -32,6 +174,40 @@ pub struct TextChunk {
     pub end_char: usize,
     pub token_count: usize,
     pub chunk_index: usize,
+    /// `ChunkLocation` JSON, set by `DocumentOutline::annotate`
+    #[serde(default)]
+    pub metadata: Option<String>,
+}
+
+/// Spans of `text` from one top-level definition to the next, each taking the comments and
+/// attributes above it; whatever comes before the first (imports, headers) is a span too
+fn code_spans(text: &str, language: CodeLanguage) -> Vec<(usize, usize)> {
+    let lines = line_spans(text, 0, text.len());
+    if lines.is_empty() {
+        return Vec::new();
+    }
+    let mut boundaries = vec![0];
+    for (index, &(start, end)) in lines.iter().enumerate().skip(1) {
+        if !is_definition(language, text[start..end].trim_end()) {
+            continue;
+        }
+        let previous = *boundaries.last().unwrap_or(&0);
+        let mut first = index;
+        while first > previous + 1 && language.is_attachment(&text[lines[first - 1].0..lines[first - 1].1]) {
+            first -= 1;
+        }
+        if first > previous {
+            boundaries.push(first);
+        }
+    }
+    boundaries
+        .iter()
+        .enumerate()
+        .map(|(i, &first)| {
+            let last = boundaries.get(i + 1).map_or(lines.len(), |&next| next);
+            (lines[first].0, lines[last - 1].1)
+        })
+        .collect()
 }
 
 #[derive(Clone)]
@@ -111,6 +287,7 @@ impl ChunkingService {
                             end_char: chunk_end,
                             token_count: current_tokens,
                             chunk_index,
+                            metadata: None,
                         });
                         chunk_index += 1;
                     }
@@ -139,6 +316,7 @@ impl ChunkingService {
                     end_char: current_start + current_chunk.len(),
                     token_count: current_tokens,
                     chunk_index,
+                    metadata: None,
                 });
             }
         }
@@ -171,6 +349,7 @@ impl ChunkingService {
                     end_char,
                     token_count: chunk_tokens.len(),
                     chunk_index,
+                    metadata: None,
                 });
                 
                 chunk_index += 1;
@@ -268,6 +447,215 @@ impl ChunkingService {
         Ok(decoded_text.len())
     }
     
+    /// Chunk `text` with `strategy`; `file_name` picks the language for code. Semantic chunking
+    /// needs sentence embeddings, so it goes through `semantic_units` and `semantic_chunk` instead
+    /// and falls back to `Standard` here.
+    pub fn chunk_with_strategy(&self, text: &str, strategy: ChunkingStrategy, file_name: &str) -> Result<Vec<TextChunk>> {
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let chunks = match strategy {
+            ChunkingStrategy::Markdown => self.markdown_chunk(text)?,
+            ChunkingStrategy::Code => match CodeLanguage::from_file_name(file_name) {
+                Some(language) => self.code_chunk(text, language)?,
+                None => self.pack_spans(text, &line_spans(text, 0, text.len()), "")?,
+            },
+            ChunkingStrategy::Standard | ChunkingStrategy::Semantic => Vec::new(),
+        };
+        if chunks.is_empty() {
+            return self.chunk_text(text);
+        }
+        Ok(chunks)
+    }
+    
+    /// `text[start..end]` cut into spans of at most `chunk_size` tokens at line ends, and inside
+    /// a line only when one line is longer than that
+    fn split_span(&self, text: &str, start: usize, end: usize) -> Result<Vec<(usize, usize)>> {
+        let mut spans = Vec::new();
+        let (mut span_start, mut span_tokens) = (start, 0);
+        for (line_start, line_end) in line_spans(text, start, end) {
+            let line_tokens = self.count_tokens(&text[line_start..line_end])?;
+            if line_tokens > self.config.chunk_size {
+                if span_start < line_start {
+                    spans.push((span_start, line_start));
+                }
+                // About four bytes a token
+                let mut cut_start = line_start;
+                while cut_start < line_end {
+                    let mut cut_end = (cut_start + self.config.chunk_size * 4).min(line_end);
+                    while !text.is_char_boundary(cut_end) {
+                        cut_end += 1;
+                    }
+                    spans.push((cut_start, cut_end));
+                    cut_start = cut_end;
+                }
+                (span_start, span_tokens) = (line_end, 0);
+            } else if span_tokens + line_tokens > self.config.chunk_size && span_start < line_start {
+                spans.push((span_start, line_start));
+                (span_start, span_tokens) = (line_start, line_tokens);
+            } else {
+                span_tokens += line_tokens;
+            }
+        }
+        if span_start < end {
+            spans.push((span_start, end));
+        }
+        Ok(spans)
+    }
+    
+    /// Consecutive spans packed into chunks of up to `chunk_size` tokens, each starting with
+    /// `prefix`; a span too big for one chunk is split first
+    fn pack_spans(&self, text: &str, spans: &[(usize, usize)], prefix: &str) -> Result<Vec<TextChunk>> {
+        let budget = self.config.chunk_size.saturating_sub(self.count_tokens(prefix)?).max(1);
+        let mut pieces = Vec::new();
+        for &(start, end) in spans {
+            let tokens = self.count_tokens(&text[start..end])?;
+            if tokens > budget {
+                for (piece_start, piece_end) in self.split_span(text, start, end)? {
+                    pieces.push((piece_start, piece_end, self.count_tokens(&text[piece_start..piece_end])?));
+                }
+            } else {
+                pieces.push((start, end, tokens));
+            }
+        }
+        
+        let mut chunks = Vec::new();
+        let mut group: Option<(usize, usize, usize)> = None;
+        for (start, end, tokens) in pieces {
+            group = match group {
+                Some((group_start, _, group_tokens)) if group_tokens + tokens <= budget => Some((group_start, end, group_tokens + tokens)),
+                Some((group_start, group_end, _)) => {
+                    self.push_chunk(&mut chunks, text, group_start, group_end, prefix)?;
+                    Some((start, end, tokens))
+                }
+                None => Some((start, end, tokens)),
+            };
+        }
+        if let Some((group_start, group_end, _)) = group {
+            self.push_chunk(&mut chunks, text, group_start, group_end, prefix)?;
+        }
+        Ok(chunks)
+    }
+    
+    fn push_chunk(&self, chunks: &mut Vec<TextChunk>, text: &str, start: usize, end: usize, prefix: &str) -> Result<()> {
+        let body = text[start..end].trim();
+        if body.is_empty() {
+            return Ok(());
+        }
+        let content = format!("{}{}", prefix, body);
+        chunks.push(TextChunk {
+            token_count: self.count_tokens(&content)?,
+            content,
+            start_char: start,
+            end_char: end,
+            chunk_index: chunks.len(),
+            metadata: None,
+        });
+        Ok(())
+    }
+    
+    /// One section per heading (headings inside code fences don't count). A chunk holds a
+    /// section's text under a line with its heading path, "Guide > Install > Linux"; long
+    /// sections are split across several chunks with the same line.
+    fn markdown_chunk(&self, text: &str) -> Result<Vec<TextChunk>> {
+        // (heading path, body start, body end)
+        let mut sections: Vec<(Vec<String>, usize, usize)> = vec![(Vec::new(), 0, 0)];
+        let mut headings: Vec<(usize, String)> = Vec::new();
+        let mut in_fence = false;
+        for (start, end) in line_spans(text, 0, text.len()) {
+            let line = text[start..end].trim_end();
+            if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+                in_fence = !in_fence;
+            }
+            let heading = if in_fence { None } else { MARKDOWN_HEADING.captures(line) };
+            match heading {
+                Some(heading) => {
+                    let level = heading[1].len();
+                    headings.retain(|(existing, _)| *existing < level);
+                    headings.push((level, heading[2].to_string()));
+                    sections.push((headings.iter().map(|(_, title)| title.clone()).collect(), end, end));
+                }
+                None => {
+                    if let Some(section) = sections.last_mut() {
+                        section.2 = end;
+                    }
+                }
+            }
+        }
+        
+        let mut chunks = Vec::new();
+        for (path, start, end) in sections {
+            if text[start..end].trim().is_empty() {
+                continue;
+            }
+            let prefix = if path.is_empty() { String::new() } else { format!("{}\n\n", path.join(" > ")) };
+            for mut chunk in self.pack_spans(text, &[(start, end)], &prefix)? {
+                chunk.chunk_index = chunks.len();
+                chunks.push(chunk);
+            }
+        }
+        Ok(chunks)
+    }
+    
+    /// Split at top-level definitions, then pack small definitions together and split long
+    /// ones at line ends
+    fn code_chunk(&self, text: &str, language: CodeLanguage) -> Result<Vec<TextChunk>> {
+        self.pack_spans(text, &code_spans(text, language), "")
+    }
+    
+    /// Sentence spans of `text` to embed for `semantic_chunk`
+    pub fn semantic_units(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut units = Vec::new();
+        let mut start = 0;
+        let bytes = text.as_bytes();
+        for (index, &byte) in bytes.iter().enumerate() {
+            let sentence_end = matches!(byte, b'.' | b'!' | b'?') && matches!(bytes.get(index + 1), None | Some(b' ' | b'\t' | b'\r' | b'\n'));
+            if byte == b'\n' || sentence_end {
+                if !text[start..=index].trim().is_empty() {
+                    units.push((start, index + 1));
+                }
+                start = index + 1;
+            }
+        }
+        if !text[start..].trim().is_empty() {
+            units.push((start, text.len()));
+        }
+        units
+    }
+    
+    /// Group consecutive `units` into chunks, starting a new chunk where a unit's embedding is
+    /// less similar to the previous one's than `semantic_similarity_threshold` (a topic change)
+    /// or the chunk would pass `chunk_size` tokens
+    pub fn semantic_chunk(&self, text: &str, units: &[(usize, usize)], embeddings: &[Vec<f32>]) -> Result<Vec<TextChunk>> {
+        if units.len() != embeddings.len() {
+            return Err(anyhow!("Expected {} sentence embeddings, got {}", units.len(), embeddings.len()));
+        }
+        let mut groups: Vec<(usize, usize)> = Vec::new();
+        let mut group_tokens = 0;
+        for (i, &(start, end)) in units.iter().enumerate() {
+            let tokens = self.count_tokens(&text[start..end])?;
+            let similar = i > 0 && cosine_similarity(&embeddings[i - 1], &embeddings[i]) >= self.config.semantic_similarity_threshold;
+            match groups.last_mut() {
+                Some(group) if similar && group_tokens + tokens <= self.config.chunk_size => {
+                    group.1 = end;
+                    group_tokens += tokens;
+                }
+                _ => {
+                    groups.push((start, end));
+                    group_tokens = tokens;
+                }
+            }
+        }
+        let mut chunks = Vec::new();
+        for (start, end) in groups {
+            for mut chunk in self.pack_spans(text, &[(start, end)], "")? {
+                chunk.chunk_index = chunks.len();
+                chunks.push(chunk);
+            }
+        }
+        Ok(chunks)
+    }
+    
     pub fn get_config(&self) -> &ChunkingConfig {
         &self.config
     }
@@ -284,10 +672,307 @@ pub fn extract_text_from_pdf(content: &[u8]) -> Result<String> {
         .map_err(|e| anyhow!("PDF extraction failed: {}", e))
 }
 
+/// PDF text, one string per page
+pub fn extract_pages_from_pdf(content: &[u8]) -> Result<Vec<String>> {
+    pdf_extract::extract_text_from_mem_by_pages(content)
+        .map_err(|e| anyhow!("PDF extraction failed: {}", e))
+}
+
+// Pages with less text than this are taken for scans and OCR'd
+const MIN_PAGE_TEXT_CHARS: usize = 20;
+
+/// A PDF page's own text, and its images (PNG or JPEG) when the text is too sparse to be its content
+pub struct PdfPage {
+    pub number: u32,
+    pub text: String,
+    pub scans: Vec<Vec<u8>>,
+}
+
+// An image XObject as bytes an OCR engine reads; `None` for encodings it can't (JBIG2, CCITT, JPEG 2000)
+fn scan_image(document: &lopdf::Document, image: &lopdf::xobject::PdfImage) -> Option<Vec<u8>> {
+    let filters = image.filters.clone().unwrap_or_default();
+    if filters.iter().any(|filter| filter == "DCTDecode") {
+        return Some(image.content.to_vec());
+    }
+    if filters.iter().any(|filter| filter != "FlateDecode") {
+        return None;
+    }
+    let pixels = document.get_object(image.id).ok()?.as_stream().ok()?.decompressed_content().ok()?;
+    let (width, height) = (u32::try_from(image.width).ok()?, u32::try_from(image.height).ok()?);
+    let gray = match image.color_space.as_deref() {
+        Some("DeviceGray") | Some("CalGray") => true,
+        Some("DeviceRGB") | Some("CalRGB") => false,
+        _ => return None,
+    };
+    let picture = match (gray, image.bits_per_component.unwrap_or(8)) {
+        (true, 8) => image::DynamicImage::ImageLuma8(image::GrayImage::from_raw(width, height, pixels)?),
+        (false, 8) => image::DynamicImage::ImageRgb8(image::RgbImage::from_raw(width, height, pixels)?),
+        // Bilevel scans: rows are padded to whole bytes, and 1 is white
+        (true, 1) => {
+            let row_bytes = width.div_ceil(8) as usize;
+            image::DynamicImage::ImageLuma8(image::GrayImage::from_fn(width, height, |x, y| {
+                let byte = pixels.get(y as usize * row_bytes + x as usize / 8).copied().unwrap_or(0xff);
+                image::Luma([if byte & (0x80 >> (x % 8)) != 0 { 255 } else { 0 }])
+            }))
+        }
+        _ => return None,
+    };
+    let mut png = Vec::new();
+    picture.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).ok()?;
+    Some(png)
+}
+
+/// Every page's text, with the images of near-empty pages to OCR
+pub fn pdf_pages(content: &[u8]) -> Result<Vec<PdfPage>> {
+    let document = lopdf::Document::load_mem(content).map_err(|e| anyhow!("PDF parsing failed: {}", e))?;
+    let mut pages = Vec::new();
+    for (number, page_id) in document.get_pages() {
+        let text = document.extract_text(&[number]).unwrap_or_default();
+        let scans = if text.chars().filter(|c| !c.is_whitespace()).count() < MIN_PAGE_TEXT_CHARS {
+            document
+                .get_page_images(page_id)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|image| scan_image(&document, image))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        pages.push(PdfPage { number, text, scans });
+    }
+    Ok(pages)
+}
+
+// Office Open XML documents and EPUBs are zip archives of XML parts
+type Archive<'a> = zip::ZipArchive<std::io::Cursor<&'a [u8]>>;
+
+fn open_archive(content: &[u8]) -> Result<Archive<'_>> {
+    zip::ZipArchive::new(std::io::Cursor::new(content)).map_err(|e| anyhow!("Not a valid zip archive: {}", e))
+}
+
+fn archive_entry(archive: &mut Archive<'_>, name: &str) -> Result<String> {
+    let mut entry = archive.by_name(name).map_err(|e| anyhow!("Missing {}: {}", name, e))?;
+    let mut text = String::new();
+    std::io::Read::read_to_string(&mut entry, &mut text)?;
+    Ok(text)
+}
+
+/// Archive entries under `prefix` named `<prefix><number>.xml`, in number order
+fn numbered_entries(archive: &Archive<'_>, prefix: &str) -> Vec<String> {
+    let mut entries: Vec<(u32, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            let number = name.strip_prefix(prefix)?.strip_suffix(".xml")?.parse().ok()?;
+            Some((number, name.to_string()))
+        })
+        .collect();
+    entries.sort();
+    entries.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Text of an XML part: what's inside `text_tags` (all text when empty), a line break after each
+/// of `block_tags`. Tags match by local name, so namespace prefixes don't matter.
+fn xml_text(xml: &str, text_tags: &[&[u8]], block_tags: &[&[u8]]) -> Result<String> {
+    use quick_xml::events::Event;
+    
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut text = String::new();
+    let mut in_text = 0usize;
+    let mut skipping = 0usize;
+    loop {
+        match reader.read_event().map_err(|e| anyhow!("Invalid XML: {}", e))? {
+            Event::Start(e) => {
+                let name = e.local_name();
+                if matches!(name.as_ref(), b"script" | b"style" | b"head") {
+                    skipping += 1;
+                } else if text_tags.contains(&name.as_ref()) {
+                    in_text += 1;
+                }
+            }
+            Event::End(e) => {
+                let name = e.local_name();
+                if matches!(name.as_ref(), b"script" | b"style" | b"head") {
+                    skipping = skipping.saturating_sub(1);
+                } else if text_tags.contains(&name.as_ref()) {
+                    in_text = in_text.saturating_sub(1);
+                } else if block_tags.contains(&name.as_ref()) && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"tab" => text.push('\t'),
+                b"br" | b"cr" => text.push('\n'),
+                _ => {}
+            },
+            Event::Text(e) if skipping == 0 && (in_text > 0 || text_tags.is_empty()) => {
+                text.push_str(&e.unescape().map_err(|e| anyhow!("Invalid XML text: {}", e))?);
+            }
+            Event::CData(e) if skipping == 0 && (in_text > 0 || text_tags.is_empty()) => {
+                text.push_str(&String::from_utf8_lossy(&e));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
 pub fn extract_text_from_docx(content: &[u8]) -> Result<String> {
-    // TODO: Implement DOCX extraction
-    // For now, return placeholder
-    Ok("DOCX text extraction not yet implemented".to_string())
+    let mut archive = open_archive(content)?;
+    let document = archive_entry(&mut archive, "word/document.xml")?;
+    xml_text(&document, &[b"t"], &[b"p"])
+}
+
+/// Slide text in slide order, slides separated by a blank line
+pub fn extract_text_from_pptx(content: &[u8]) -> Result<String> {
+    let mut archive = open_archive(content)?;
+    let mut slides = Vec::new();
+    for name in numbered_entries(&archive, "ppt/slides/slide") {
+        slides.push(xml_text(&archive_entry(&mut archive, &name)?, &[b"t"], &[b"p"])?);
+    }
+    Ok(slides.join("\n"))
+}
+
+// The workbook's string table, one entry per <si> including empty ones, so indexes line up
+fn shared_strings(xml: &str) -> Result<Vec<String>> {
+    use quick_xml::events::Event;
+    
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event().map_err(|e| anyhow!("Invalid sharedStrings.xml: {}", e))? {
+            Event::Start(e) if e.local_name().as_ref() == b"t" => in_text = true,
+            Event::Text(e) if in_text => current.push_str(&e.unescape().map_err(|e| anyhow!("Invalid XML text: {}", e))?),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"si" => strings.push(std::mem::take(&mut current)),
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+/// Each sheet's rows as tab-separated lines, shared strings resolved
+pub fn extract_text_from_xlsx(content: &[u8]) -> Result<String> {
+    use quick_xml::events::Event;
+    
+    let mut archive = open_archive(content)?;
+    // Most cell text lives in the shared string table; a workbook of numbers has none
+    let shared_strings = match archive_entry(&mut archive, "xl/sharedStrings.xml") {
+        Ok(xml) => shared_strings(&xml)?,
+        Err(_) => Vec::new(),
+    };
+    
+    let mut text = String::new();
+    for name in numbered_entries(&archive, "xl/worksheets/sheet") {
+        let xml = archive_entry(&mut archive, &name)?;
+        let mut reader = quick_xml::Reader::from_str(&xml);
+        let mut row: Vec<String> = Vec::new();
+        let mut shared = false;
+        let mut in_value = false;
+        loop {
+            match reader.read_event().map_err(|e| anyhow!("Invalid XML in {}: {}", name, e))? {
+                Event::Start(e) => match e.local_name().as_ref() {
+                    b"c" => {
+                        shared = e.try_get_attribute("t")?.is_some_and(|t| &*t.value == b"s");
+                    }
+                    b"v" | b"t" => in_value = true,
+                    _ => {}
+                },
+                Event::Text(e) if in_value => {
+                    let value = e.unescape().map_err(|e| anyhow!("Invalid XML text in {}: {}", name, e))?;
+                    let value = match value.trim().parse::<usize>() {
+                        Ok(index) if shared => shared_strings.get(index).cloned().unwrap_or_default(),
+                        _ => value.into_owned(),
+                    };
+                    row.push(value);
+                }
+                Event::End(e) => match e.local_name().as_ref() {
+                    b"v" | b"t" => in_value = false,
+                    b"row" => {
+                        if row.iter().any(|cell| !cell.trim().is_empty()) {
+                            text.push_str(&row.join("\t"));
+                            text.push('\n');
+                        }
+                        row.clear();
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Chapter text in reading (spine) order
+pub fn extract_text_from_epub(content: &[u8]) -> Result<String> {
+    use quick_xml::events::Event;
+    
+    let mut archive = open_archive(content)?;
+    let container = archive_entry(&mut archive, "META-INF/container.xml")?;
+    
+    // container.xml points at the package document, which lists the chapters
+    let mut package_path = None;
+    let mut reader = quick_xml::Reader::from_str(&container);
+    loop {
+        match reader.read_event().map_err(|e| anyhow!("Invalid container.xml: {}", e))? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"rootfile" => {
+                if let Some(path) = e.try_get_attribute("full-path")? {
+                    package_path = Some(path.unescape_value()?.into_owned());
+                    break;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let package_path = package_path.ok_or_else(|| anyhow!("EPUB has no package document"))?;
+    let package = archive_entry(&mut archive, &package_path)?;
+    let base = package_path.rsplit_once('/').map(|(dir, _)| format!("{}/", dir)).unwrap_or_default();
+    
+    let mut manifest: HashMap<String, String> = HashMap::new();
+    let mut spine: Vec<String> = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(&package);
+    loop {
+        match reader.read_event().map_err(|e| anyhow!("Invalid EPUB package document: {}", e))? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"item" => {
+                    if let (Some(id), Some(href)) = (e.try_get_attribute("id")?, e.try_get_attribute("href")?) {
+                        manifest.insert(id.unescape_value()?.into_owned(), href.unescape_value()?.into_owned());
+                    }
+                }
+                b"itemref" => {
+                    if let Some(idref) = e.try_get_attribute("idref")? {
+                        spine.push(idref.unescape_value()?.into_owned());
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    
+    let blocks: &[&[u8]] = &[b"p", b"div", b"li", b"tr", b"h1", b"h2", b"h3", b"h4", b"h5", b"h6", b"blockquote", b"pre"];
+    let mut chapters = Vec::new();
+    for href in spine.iter().filter_map(|id| manifest.get(id)) {
+        // Hrefs are relative to the package document and may carry a fragment
+        let path = format!("{}{}", base, href.split('#').next().unwrap_or(href));
+        match archive_entry(&mut archive, &path) {
+            Ok(chapter) => chapters.push(xml_text(&chapter, &[], blocks)?),
+            Err(e) => eprintln!("Skipping EPUB chapter: {}", e),
+        }
+    }
+    Ok(chapters.join("\n"))
 }
 
 pub fn clean_text(text: &str) -> String {
@@ -305,6 +990,167 @@ pub fn clean_text(text: &str) -> String {
         .join(" ")
 }
 
+/// Like `clean_text` but keeping line breaks and indentation, for the strategies that split on them
+pub fn clean_structured_text(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line: String = line.trim_end().chars().filter(|&c| !c.is_control() || c == '\t').collect();
+        // At most one blank line in a row
+        if line.is_empty() && !lines.last().is_some_and(|last| !last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim_end().to_string()
+}
+
+/// Where a chunk sits in its document; stored as the chunk's metadata so search results and
+/// citations can point at a page and section
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkLocation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    /// Last page, when the chunk runs onto later pages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_end: Option<u32>,
+    /// The heading the chunk falls under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    /// Headings from the top level down to `heading`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub section_path: Vec<String>,
+}
+
+impl ChunkLocation {
+    pub fn from_metadata(metadata: Option<&str>) -> Option<Self> {
+        serde_json::from_str(metadata?).ok()
+    }
+
+    /// "page 12, §3.2 Installation", or `None` when nothing is known
+    pub fn label(&self) -> Option<String> {
+        let page = match (self.page, self.page_end) {
+            (Some(page), Some(end)) if end > page => Some(format!("pages {}-{}", page, end)),
+            (Some(page), _) => Some(format!("page {}", page)),
+            _ => None,
+        };
+        let heading = self.heading.as_ref().map(|heading| {
+            if heading.starts_with(|c: char| c.is_ascii_digit()) { format!("§{}", heading) } else { heading.clone() }
+        });
+        let parts: Vec<String> = page.into_iter().chain(heading).collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Heading {
+    offset: usize,
+    level: usize,
+    title: String,
+}
+
+lazy_static::lazy_static! {
+    // "3.2 Installation", "3.2. Installation" or "3 Setup" on a line of its own
+    static ref NUMBERED_HEADING: Regex = Regex::new(r"^\s*(\d{1,2}(?:\.\d{1,2})+\.?|\d{1,2})\s+([A-Z][^.!?:;]{0,80})$").unwrap();
+}
+
+/// Headings in `text` as (byte offset of the line, level, title). Markdown `#` headings count
+/// only for Markdown; elsewhere it's numbered section headings.
+fn find_headings(text: &str, markdown: bool) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+    for (start, end) in line_spans(text, 0, text.len()) {
+        let line = text[start..end].trim_end();
+        if markdown {
+            if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+                in_fence = !in_fence;
+            } else if let Some(heading) = MARKDOWN_HEADING.captures(line).filter(|_| !in_fence) {
+                headings.push(Heading { offset: start, level: heading[1].len(), title: heading[2].to_string() });
+            }
+        } else if let Some(heading) = NUMBERED_HEADING.captures(line) {
+            let number = heading[1].trim_end_matches('.');
+            headings.push(Heading {
+                offset: start,
+                level: number.split('.').count(),
+                title: format!("{} {}", number, heading[2].trim()),
+            });
+        }
+    }
+    headings
+}
+
+/// Where pages start and headings are in a document's cleaned text
+#[derive(Debug, Clone, Default)]
+pub struct DocumentOutline {
+    /// Byte offset of each page; empty for documents without pages
+    page_starts: Vec<usize>,
+    headings: Vec<Heading>,
+}
+
+impl DocumentOutline {
+    pub fn locate(&self, start: usize, end: usize) -> ChunkLocation {
+        let page_at = |offset: usize| self.page_starts.iter().rposition(|&page_start| page_start <= offset).map(|index| index as u32 + 1);
+        let page = page_at(start);
+        let page_end = page_at(end.saturating_sub(1).max(start)).filter(|&last| Some(last) != page);
+        let mut section_path: Vec<&Heading> = Vec::new();
+        for heading in self.headings.iter().take_while(|heading| heading.offset <= start) {
+            section_path.retain(|parent| parent.level < heading.level);
+            section_path.push(heading);
+        }
+        ChunkLocation {
+            page,
+            page_end,
+            heading: section_path.last().map(|heading| heading.title.clone()),
+            section_path: section_path.iter().map(|heading| heading.title.clone()).collect(),
+        }
+    }
+
+    /// Set each chunk's metadata to its location
+    pub fn annotate(&self, chunks: &mut [TextChunk]) {
+        for chunk in chunks {
+            let location = self.locate(chunk.start_char, chunk.end_char);
+            if location != ChunkLocation::default() {
+                chunk.metadata = serde_json::to_string(&location).ok();
+            }
+        }
+    }
+}
+
+/// Clean a document's pages (one page for documents without pages) into its text, keeping
+/// where each page and heading lands in that text
+pub fn clean_pages(pages: &[String], strategy: ChunkingStrategy) -> (String, DocumentOutline) {
+    let mut content = String::new();
+    let mut outline = DocumentOutline::default();
+    let separator = if strategy.keeps_layout() { "\n" } else { " " };
+    for page in pages {
+        let cleaned = if strategy.keeps_layout() { clean_structured_text(page) } else { clean_text(page) };
+        if !content.is_empty() && !cleaned.is_empty() {
+            content.push_str(separator);
+        }
+        let page_start = content.len();
+        if pages.len() > 1 {
+            outline.page_starts.push(page_start);
+        }
+        match strategy {
+            ChunkingStrategy::Code => {}
+            ChunkingStrategy::Markdown => {
+                outline.headings.extend(find_headings(&cleaned, true).into_iter().map(|heading| Heading { offset: page_start + heading.offset, ..heading }));
+            }
+            ChunkingStrategy::Standard | ChunkingStrategy::Semantic => {
+                // Headings are found by line, before cleaning joins the lines; cleaning is per
+                // token, so the text before a line cleans to what precedes it in the result
+                outline.headings.extend(find_headings(page, false).into_iter().map(|heading| {
+                    let before = clean_text(&page[..heading.offset]);
+                    let offset = page_start + before.len() + usize::from(!before.is_empty());
+                    Heading { offset, ..heading }
+                }));
+            }
+        }
+        content.push_str(&cleaned);
+    }
+    (content, outline)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,6 +1180,132 @@ mod tests {
         assert_eq!(clean, "This is messy text.");
     }
     
+    #[test]
+    fn test_office_and_epub_extraction() {
+        use std::io::Write;
+        
+        fn archive(entries: &[(&str, &str)]) -> Vec<u8> {
+            let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+            for (name, content) in entries {
+                writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+                writer.write_all(content.as_bytes()).unwrap();
+            }
+            writer.finish().unwrap().into_inner()
+        }
+        
+        let docx = archive(&[("word/document.xml", r#"<w:document xmlns:w="w"><w:body>
+            <w:p><w:r><w:t>Quarterly</w:t></w:r><w:r><w:t xml:space="preserve"> report</w:t></w:r></w:p>
+            <w:p><w:r><w:t>Revenue &amp; costs</w:t></w:r></w:p></w:body></w:document>"#)]);
+        assert_eq!(clean_text(&extract_text_from_docx(&docx).unwrap()), "Quarterly report Revenue & costs");
+        
+        let xlsx = archive(&[
+            ("xl/sharedStrings.xml", r#"<sst><si><t>Name</t></si><si><t/></si><si><r><t>Ada</t></r><r><t> L.</t></r></si></sst>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet><sheetData>
+                <row><c t="s"><v>0</v></c><c><v>42</v></c></row>
+                <row><c t="s"><v>2</v></c><c t="inlineStr"><is><t>inline</t></is></c></row>
+            </sheetData></worksheet>"#),
+        ]);
+        assert_eq!(extract_text_from_xlsx(&xlsx).unwrap().trim(), "Name\t42\nAda L.\tinline");
+        
+        let pptx = archive(&[
+            ("ppt/slides/slide10.xml", r#"<p:sld><a:p><a:r><a:t>Last</a:t></a:r></a:p></p:sld>"#),
+            ("ppt/slides/slide2.xml", r#"<p:sld><a:p><a:r><a:t>First</a:t></a:r></a:p></p:sld>"#),
+        ]);
+        assert_eq!(clean_text(&extract_text_from_pptx(&pptx).unwrap()), "First Last");
+        
+        let epub = archive(&[
+            ("META-INF/container.xml", r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles></container>"#),
+            ("OEBPS/content.opf", r#"<package><manifest><item id="c1" href="one.xhtml"/><item id="c2" href="two.xhtml"/></manifest>
+                <spine><itemref idref="c2"/><itemref idref="c1"/></spine></package>"#),
+            ("OEBPS/one.xhtml", r#"<html><head><title>Skip me</title></head><body><p>Chapter one</p></body></html>"#),
+            ("OEBPS/two.xhtml", r#"<html><body><h1>Intro</h1><p>Hello<br/>world</p><script>var x;</script></body></html>"#),
+        ]);
+        assert_eq!(clean_text(&extract_text_from_epub(&epub).unwrap()), "Intro Hello world Chapter one");
+        
+        assert!(extract_text_from_docx(b"not a zip").is_err());
+    }
+    
+    #[test]
+    fn test_chunking_strategies() {
+        let config = ChunkingConfig::default();
+        assert_eq!(config.strategy_for("notes.MD", ""), ChunkingStrategy::Markdown);
+        assert_eq!(config.strategy_for("main.rs", "text/plain"), ChunkingStrategy::Code);
+        assert_eq!(config.strategy_for("notes.txt", "text/markdown; charset=utf-8"), ChunkingStrategy::Markdown);
+        assert_eq!(config.strategy_for("report.pdf", "application/pdf"), ChunkingStrategy::Standard);
+        // Settings saved before strategies existed get the defaults
+        let stored: ChunkingConfig = serde_json::from_str(
+            r#"{"chunk_size":256,"chunk_overlap":0,"max_chunk_size":400,"min_chunk_size":10,
+                "respect_sentence_boundaries":true,"respect_paragraph_boundaries":true}"#,
+        ).unwrap();
+        assert_eq!(stored.strategies, default_strategies());
+        assert_eq!(clean_structured_text("  a  \n\n\n\tb\u{7}\n"), "  a\n\n\tb");
+
+        let service = ChunkingService::new(None).unwrap();
+        let markdown = "# Guide\nIntro text.\n\n## Install\nRun the installer.\n```\n# not a heading\n```\n\n## Use\nOpen the app.\n";
+        let chunks = service.chunk_with_strategy(markdown, ChunkingStrategy::Markdown, "guide.md").unwrap();
+        let contents: Vec<&str> = chunks.iter().map(|chunk| chunk.content.as_str()).collect();
+        assert_eq!(contents, [
+            "Guide\n\nIntro text.",
+            "Guide > Install\n\nRun the installer.\n```\n# not a heading\n```",
+            "Guide > Use\n\nOpen the app.",
+        ]);
+        assert_eq!(&markdown[chunks[2].start_char..chunks[2].end_char], "Open the app.\n");
+
+        let rust = "use std::fmt;\n\n/// Adds.\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n#[derive(Debug)]\nstruct Point {\n    x: i32,\n}\n";
+        let spans: Vec<&str> = code_spans(rust, CodeLanguage::Rust).into_iter().map(|(start, end)| &rust[start..end]).collect();
+        assert_eq!(spans, [
+            "use std::fmt;\n\n",
+            "/// Adds.\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n",
+            "#[derive(Debug)]\nstruct Point {\n    x: i32,\n}\n",
+        ]);
+        let python = "import os\n\n@cache\ndef f():\n    return 1\n\nclass A:\n    def g(self):\n        pass\n";
+        let spans: Vec<&str> = code_spans(python, CodeLanguage::Python).into_iter().map(|(start, end)| &python[start..end]).collect();
+        assert_eq!(spans, ["import os\n\n", "@cache\ndef f():\n    return 1\n\n", "class A:\n    def g(self):\n        pass\n"]);
+
+        // A topic change between the second and third sentence
+        let text = "Cats purr. Cats nap. Stocks fell. Markets slid.";
+        let units = service.semantic_units(text);
+        assert_eq!(units.len(), 4);
+        let embeddings = vec![vec![1.0, 0.0], vec![1.0, 0.1], vec![0.0, 1.0], vec![0.1, 1.0]];
+        let chunks = service.semantic_chunk(text, &units, &embeddings).unwrap();
+        let contents: Vec<&str> = chunks.iter().map(|chunk| chunk.content.as_str()).collect();
+        assert_eq!(contents, ["Cats purr. Cats nap.", "Stocks fell. Markets slid."]);
+        assert!(service.semantic_chunk(text, &units, &embeddings[..2]).is_err());
+    }
+
+    #[test]
+    fn test_chunk_locations() {
+        let pages = vec![
+            "Preface text.\n3 Setup\nBefore you start.".to_string(),
+            "more setup notes\n3.2 Installation\nRun   the installer.".to_string(),
+        ];
+        let (text, outline) = clean_pages(&pages, ChunkingStrategy::Standard);
+        assert_eq!(text, "Preface text. 3 Setup Before you start. more setup notes 3.2 Installation Run the installer.");
+        let at = |needle: &str| text.find(needle).unwrap();
+        assert_eq!(outline.locate(0, 10), ChunkLocation { page: Some(1), ..Default::default() });
+        let install = outline.locate(at("Run"), text.len());
+        assert_eq!((install.page, install.page_end), (Some(2), None));
+        assert_eq!(install.section_path, ["3 Setup", "3.2 Installation"]);
+        assert_eq!(install.label().as_deref(), Some("page 2, §3.2 Installation"));
+        let across = outline.locate(at("Before"), at("notes"));
+        assert_eq!(across.label().as_deref(), Some("pages 1-2, §3 Setup"));
+
+        let markdown = "# Guide\n\n## Install\n\nOpen the app.\n".to_string();
+        let (text, outline) = clean_pages(&[markdown], ChunkingStrategy::Markdown);
+        let mut chunks = vec![TextChunk {
+            content: "Open the app.".to_string(),
+            start_char: text.find("Open").unwrap(),
+            end_char: text.len(),
+            token_count: 4,
+            chunk_index: 0,
+            metadata: None,
+        }];
+        outline.annotate(&mut chunks);
+        let location = ChunkLocation::from_metadata(chunks[0].metadata.as_deref()).unwrap();
+        assert_eq!((location.page, location.section_path), (None, vec!["Guide".to_string(), "Install".to_string()]));
+        assert_eq!(ChunkLocation::default().label(), None);
+    }
+
     #[test]
     fn test_token_counting() {
         let service = ChunkingService::new(None).unwrap();
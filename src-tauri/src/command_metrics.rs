@@ -0,0 +1,566 @@
+// src-tauri/src/command_metrics.rs
+// Per-command IPC timing and payload size metrics with a rolling latency histogram.
+// Every command is declared with `#[crate::command]`, which times its body and measures its
+// response here, so nothing depends on commands remembering to start a timer.
+
+use serde::{ser, Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::InvokeBody;
+
+// Number of most recent samples kept per command for percentiles and the histogram
+const ROLLING_WINDOW: usize = 512;
+const HISTOGRAM_BOUNDS_MS: &[f64] = &[1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0];
+
+lazy_static::lazy_static! {
+    static ref COMMAND_METRICS: Mutex<HashMap<String, CommandStats>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Default)]
+struct RollingSamples {
+    samples_ms: VecDeque<f64>,
+    total: u64,
+}
+
+impl RollingSamples {
+    fn push(&mut self, duration: Duration) {
+        if self.samples_ms.len() == ROLLING_WINDOW {
+            self.samples_ms.pop_front();
+        }
+        self.samples_ms.push_back(duration.as_secs_f64() * 1000.0);
+        self.total += 1;
+    }
+}
+
+#[derive(Default)]
+struct CommandStats {
+    calls: u64,
+    total_request_bytes: u64,
+    max_request_bytes: u64,
+    // Time spent inside the invoke handler. For sync commands this is the full
+    // execution time; async commands only spawn here and return immediately.
+    dispatch: RollingSamples,
+    // Recorded by `CommandTimer` around each command body
+    execution: RollingSamples,
+    responses: u64,
+    total_response_bytes: u64,
+    max_response_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    /// Upper bound in milliseconds, `None` for the overflow bucket
+    pub le_ms: Option<f64>,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandMetricsSummary {
+    pub command: String,
+    pub calls: u64,
+    pub avg_request_bytes: u64,
+    pub max_request_bytes: u64,
+    /// Estimated JSON size of what the command returned, error messages included
+    pub avg_response_bytes: u64,
+    pub max_response_bytes: u64,
+    /// "execution" when the command body was timed, "dispatch" otherwise
+    pub timing_source: String,
+    pub sample_count: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub histogram: Vec<HistogramBucket>,
+}
+
+pub use enteract_macros::command;
+
+/// Guard that records the execution time of a command when dropped, so a command whose
+/// future is dropped before finishing is still timed. Started by `#[command]`.
+pub struct CommandTimer {
+    command: &'static str,
+    started: Instant,
+    response_bytes: Option<u64>,
+}
+
+impl CommandTimer {
+    pub fn start(command: &'static str) -> Self {
+        Self {
+            command,
+            started: Instant::now(),
+            response_bytes: None,
+        }
+    }
+
+    /// Stop timing with the size of the response the command returned
+    pub fn respond(mut self, response_bytes: u64) {
+        self.response_bytes = Some(response_bytes);
+    }
+}
+
+impl Drop for CommandTimer {
+    fn drop(&mut self) {
+        if let Ok(mut metrics) = COMMAND_METRICS.lock() {
+            let stats = metrics.entry(self.command.to_string()).or_default();
+            stats.execution.push(self.started.elapsed());
+            if let Some(bytes) = self.response_bytes {
+                stats.responses += 1;
+                stats.total_response_bytes += bytes;
+                stats.max_response_bytes = stats.max_response_bytes.max(bytes);
+            }
+        }
+    }
+}
+
+/// Estimates the JSON size of a value by walking it, without formatting anything. Strings
+/// count their length and numbers their digits, so a base64 screenshot or a long document list
+/// costs next to nothing to measure; escapes and float formatting are approximated.
+struct SizeEstimate(u64);
+
+#[derive(Debug)]
+struct EstimateError;
+
+impl std::fmt::Display for EstimateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("response can't be measured")
+    }
+}
+
+impl std::error::Error for EstimateError {}
+
+impl ser::Error for EstimateError {
+    fn custom<T: std::fmt::Display>(_: T) -> Self {
+        EstimateError
+    }
+}
+
+fn digits(value: u64) -> u64 {
+    value.checked_ilog10().map_or(1, |log| u64::from(log) + 1)
+}
+
+// Floats are counted as a typical short decimal rather than formatted
+const FLOAT_ESTIMATE: u64 = 8;
+
+impl SizeEstimate {
+    fn add(&mut self, bytes: u64) -> Result<(), EstimateError> {
+        self.0 += bytes;
+        Ok(())
+    }
+
+    fn signed(&mut self, value: i64) -> Result<(), EstimateError> {
+        self.add(u64::from(value < 0) + digits(value.unsigned_abs()))
+    }
+
+    fn quoted(&mut self, text: &str) -> Result<(), EstimateError> {
+        self.add(text.len() as u64 + 2)
+    }
+
+    fn compound(&mut self) -> Compound<'_> {
+        self.0 += 2;
+        Compound { estimate: self, first: true }
+    }
+
+    // `{"Variant":...}`
+    fn variant(&mut self, variant: &str) -> &mut Self {
+        self.0 += variant.len() as u64 + 5;
+        self
+    }
+}
+
+struct Compound<'a> {
+    estimate: &'a mut SizeEstimate,
+    first: bool,
+}
+
+impl Compound<'_> {
+    fn separator(&mut self) {
+        if !self.first {
+            self.estimate.0 += 1;
+        }
+        self.first = false;
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EstimateError> {
+        self.separator();
+        value.serialize(&mut *self.estimate)
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), EstimateError> {
+        self.separator();
+        self.estimate.0 += key.len() as u64 + 3;
+        value.serialize(&mut *self.estimate)
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut SizeEstimate {
+    type Ok = ();
+    type Error = EstimateError;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, value: bool) -> Result<(), EstimateError> {
+        self.add(if value { 4 } else { 5 })
+    }
+    fn serialize_i8(self, value: i8) -> Result<(), EstimateError> {
+        self.signed(value.into())
+    }
+    fn serialize_i16(self, value: i16) -> Result<(), EstimateError> {
+        self.signed(value.into())
+    }
+    fn serialize_i32(self, value: i32) -> Result<(), EstimateError> {
+        self.signed(value.into())
+    }
+    fn serialize_i64(self, value: i64) -> Result<(), EstimateError> {
+        self.signed(value)
+    }
+    fn serialize_u8(self, value: u8) -> Result<(), EstimateError> {
+        self.add(digits(value.into()))
+    }
+    fn serialize_u16(self, value: u16) -> Result<(), EstimateError> {
+        self.add(digits(value.into()))
+    }
+    fn serialize_u32(self, value: u32) -> Result<(), EstimateError> {
+        self.add(digits(value.into()))
+    }
+    fn serialize_u64(self, value: u64) -> Result<(), EstimateError> {
+        self.add(digits(value))
+    }
+    fn serialize_f32(self, _: f32) -> Result<(), EstimateError> {
+        self.add(FLOAT_ESTIMATE)
+    }
+    fn serialize_f64(self, _: f64) -> Result<(), EstimateError> {
+        self.add(FLOAT_ESTIMATE)
+    }
+    fn serialize_char(self, value: char) -> Result<(), EstimateError> {
+        self.add(value.len_utf8() as u64 + 2)
+    }
+    fn serialize_str(self, value: &str) -> Result<(), EstimateError> {
+        self.quoted(value)
+    }
+    // serde_json writes bytes as an array of numbers
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), EstimateError> {
+        self.add(value.len() as u64 * 4 + 1)
+    }
+    fn serialize_none(self) -> Result<(), EstimateError> {
+        self.add(4)
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), EstimateError> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<(), EstimateError> {
+        self.add(4)
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), EstimateError> {
+        self.add(4)
+    }
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<(), EstimateError> {
+        self.quoted(variant)
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<(), EstimateError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), EstimateError> {
+        value.serialize(self.variant(variant))
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Compound<'a>, EstimateError> {
+        Ok(self.compound())
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Compound<'a>, EstimateError> {
+        Ok(self.compound())
+    }
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Compound<'a>, EstimateError> {
+        Ok(self.compound())
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a>, EstimateError> {
+        Ok(self.variant(variant).compound())
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Compound<'a>, EstimateError> {
+        Ok(self.compound())
+    }
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Compound<'a>, EstimateError> {
+        Ok(self.compound())
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a>, EstimateError> {
+        Ok(self.variant(variant).compound())
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = EstimateError;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EstimateError> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), EstimateError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = EstimateError;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EstimateError> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), EstimateError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = EstimateError;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EstimateError> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), EstimateError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = EstimateError;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EstimateError> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), EstimateError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = EstimateError;
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), EstimateError> {
+        self.element(key)?;
+        self.estimate.add(1)
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EstimateError> {
+        value.serialize(&mut *self.estimate)
+    }
+    fn end(self) -> Result<(), EstimateError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = EstimateError;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), EstimateError> {
+        self.field(key, value)
+    }
+    fn end(self) -> Result<(), EstimateError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = EstimateError;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), EstimateError> {
+        self.field(key, value)
+    }
+    fn end(self) -> Result<(), EstimateError> {
+        Ok(())
+    }
+}
+
+/// Estimated size of `response` as JSON, the way the IPC layer sends it
+pub fn response_size<T: Serialize + ?Sized>(response: &T) -> u64 {
+    let mut estimate = SizeEstimate(0);
+    let _ = response.serialize(&mut estimate);
+    estimate.0
+}
+
+pub fn result_size<T: Serialize, E: Serialize>(response: &Result<T, E>) -> u64 {
+    match response {
+        Ok(value) => response_size(value),
+        Err(error) => response_size(error),
+    }
+}
+
+/// Called from the invoke handler wrapper for every IPC call
+pub fn record_dispatch(command: &str, duration: Duration, request_bytes: u64) {
+    if let Ok(mut metrics) = COMMAND_METRICS.lock() {
+        let stats = metrics.entry(command.to_string()).or_default();
+        stats.calls += 1;
+        stats.total_request_bytes += request_bytes;
+        stats.max_request_bytes = stats.max_request_bytes.max(request_bytes);
+        stats.dispatch.push(duration);
+    }
+}
+
+pub fn payload_size(body: &InvokeBody) -> u64 {
+    match body {
+        InvokeBody::Json(value) => serde_json::to_vec(value).map(|v| v.len() as u64).unwrap_or(0),
+        InvokeBody::Raw(bytes) => bytes.len() as u64,
+    }
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+fn summarize(command: &str, stats: &CommandStats) -> CommandMetricsSummary {
+    let (timing_source, samples) = if stats.execution.total > 0 {
+        ("execution", &stats.execution)
+    } else {
+        ("dispatch", &stats.dispatch)
+    };
+
+    let mut sorted: Vec<f64> = samples.samples_ms.iter().copied().collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut histogram: Vec<HistogramBucket> = HISTOGRAM_BOUNDS_MS
+        .iter()
+        .map(|&bound| HistogramBucket { le_ms: Some(bound), count: 0 })
+        .chain(std::iter::once(HistogramBucket { le_ms: None, count: 0 }))
+        .collect();
+    for &sample in &sorted {
+        let index = HISTOGRAM_BOUNDS_MS
+            .iter()
+            .position(|&bound| sample <= bound)
+            .unwrap_or(HISTOGRAM_BOUNDS_MS.len());
+        histogram[index].count += 1;
+    }
+
+    let mean_ms = if sorted.is_empty() {
+        0.0
+    } else {
+        sorted.iter().sum::<f64>() / sorted.len() as f64
+    };
+
+    CommandMetricsSummary {
+        command: command.to_string(),
+        calls: stats.calls,
+        avg_request_bytes: if stats.calls > 0 { stats.total_request_bytes / stats.calls } else { 0 },
+        max_request_bytes: stats.max_request_bytes,
+        avg_response_bytes: if stats.responses > 0 { stats.total_response_bytes / stats.responses } else { 0 },
+        max_response_bytes: stats.max_response_bytes,
+        timing_source: timing_source.to_string(),
+        sample_count: sorted.len(),
+        mean_ms,
+        p50_ms: percentile(&sorted, 50.0),
+        p95_ms: percentile(&sorted, 95.0),
+        p99_ms: percentile(&sorted, 99.0),
+        max_ms: sorted.last().copied().unwrap_or(0.0),
+        histogram,
+    }
+}
+
+/// Metrics for every command that has been invoked, slowest p95 first
+#[command]
+pub fn get_command_metrics(command: Option<String>) -> Result<Vec<CommandMetricsSummary>, String> {
+    let metrics = COMMAND_METRICS
+        .lock()
+        .map_err(|_| "Failed to acquire command metrics".to_string())?;
+
+    let mut summaries: Vec<CommandMetricsSummary> = metrics
+        .iter()
+        .filter(|(name, _)| command.as_ref().map_or(true, |c| c == *name))
+        .map(|(name, stats)| summarize(name, stats))
+        .collect();
+    summaries.sort_by(|a, b| b.p95_ms.partial_cmp(&a.p95_ms).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(summaries)
+}
+
+#[command]
+pub fn reset_command_metrics() -> Result<(), String> {
+    COMMAND_METRICS
+        .lock()
+        .map_err(|_| "Failed to acquire command metrics".to_string())?
+        .clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_prefers_execution_samples() {
+        let mut stats = CommandStats::default();
+        stats.calls = 2;
+        stats.total_request_bytes = 300;
+        stats.max_request_bytes = 200;
+        stats.dispatch.push(Duration::from_micros(10));
+        stats.dispatch.push(Duration::from_micros(10));
+        stats.execution.push(Duration::from_millis(40));
+        stats.execution.push(Duration::from_millis(400));
+
+        let summary = summarize("search_enhanced_documents", &stats);
+        assert_eq!(summary.timing_source, "execution");
+        assert_eq!(summary.avg_request_bytes, 150);
+        assert_eq!(summary.sample_count, 2);
+        assert!((summary.max_ms - 400.0).abs() < 1.0);
+
+        let counted: u64 = summary.histogram.iter().map(|b| b.count).sum();
+        assert_eq!(counted, 2);
+        assert_eq!(summary.histogram.iter().find(|b| b.le_ms == Some(50.0)).unwrap().count, 1);
+        assert_eq!(summary.histogram.iter().find(|b| b.le_ms == Some(500.0)).unwrap().count, 1);
+    }
+
+    #[test]
+    fn test_timer_records_response_size() {
+        let ok: Result<Vec<&str>, String> = Ok(vec!["a", "bc"]);
+        let err: Result<Vec<&str>, String> = Err("missing".to_string());
+        assert_eq!(result_size(&ok), r#"["a","bc"]"#.len() as u64);
+        assert_eq!(result_size(&err), r#""missing""#.len() as u64);
+
+        CommandTimer::start("test_timer_records_response_size").respond(result_size(&ok));
+        CommandTimer::start("test_timer_records_response_size").respond(result_size(&err));
+        let summary = get_command_metrics(Some("test_timer_records_response_size".to_string())).unwrap().remove(0);
+        assert_eq!(summary.timing_source, "execution");
+        assert_eq!(summary.sample_count, 2);
+        assert_eq!((summary.avg_response_bytes, summary.max_response_bytes), (9, 10));
+    }
+
+    #[test]
+    fn test_response_size_is_estimated_without_serializing() {
+        let response = serde_json::json!({ "id": "abc", "pages": [1, 20, -300], "parent": null, "ok": true, "tags": { "a": false } });
+        assert_eq!(response_size(&response), serde_json::to_string(&response).unwrap().len() as u64);
+
+        let screenshot = "A".repeat(1 << 20);
+        assert_eq!(response_size(&screenshot), (1 << 20) + 2);
+    }
+
+    #[test]
+    fn test_rolling_window_is_bounded() {
+        let mut samples = RollingSamples::default();
+        for _ in 0..(ROLLING_WINDOW + 10) {
+            samples.push(Duration::from_millis(1));
+        }
+        assert_eq!(samples.samples_ms.len(), ROLLING_WINDOW);
+        assert_eq!(samples.total, (ROLLING_WINDOW + 10) as u64);
+    }
+}
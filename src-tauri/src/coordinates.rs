@@ -0,0 +1,279 @@
+// Screen coordinate normalization - the one place that knows how screenshot pixels, the virtual
+// desktop and mouse input line up across monitors with different scale factors.
+//
+// Every coordinate the input tools take or return is a desktop coordinate: a position on the
+// virtual desktop in the units the OS lays monitors out in (physical pixels on Windows), with
+// the primary monitor's top-left corner at (0, 0). Screenshots are mapped onto it with an
+// `ImageMapping`, and the cursor is moved with per-monitor DPI awareness so Windows doesn't
+// rescale the point on 125%/150% displays.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorGeometry {
+    pub id: u32,
+    /// Top-left corner on the virtual desktop
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// 1.0 at 100%, 1.5 at 150%
+    pub scale_factor: f32,
+    pub is_primary: bool,
+}
+
+impl MonitorGeometry {
+    fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.right() && y < self.bottom()
+    }
+
+    // Closest point on this monitor
+    fn clamp(&self, x: i32, y: i32) -> (i32, i32) {
+        (x.clamp(self.x, self.right() - 1), y.clamp(self.y, self.bottom() - 1))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopLayout {
+    pub monitors: Vec<MonitorGeometry>,
+}
+
+impl DesktopLayout {
+    pub fn new(monitors: Vec<MonitorGeometry>) -> Result<Self, String> {
+        if monitors.iter().all(|monitor| monitor.width == 0 || monitor.height == 0) {
+            return Err("No monitors found".to_string());
+        }
+        Ok(Self { monitors })
+    }
+
+    /// The monitor flagged primary, else the one at the desktop origin, else the first
+    pub fn primary(&self) -> &MonitorGeometry {
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.is_primary)
+            .or_else(|| self.monitor_at(0, 0))
+            .unwrap_or(&self.monitors[0])
+    }
+
+    pub fn monitor(&self, id: u32) -> Option<&MonitorGeometry> {
+        self.monitors.iter().find(|monitor| monitor.id == id)
+    }
+
+    pub fn monitor_at(&self, x: i32, y: i32) -> Option<&MonitorGeometry> {
+        self.monitors.iter().find(|monitor| monitor.contains(x, y))
+    }
+
+    /// The monitor at `index` in `monitors` - the order get_screen_info and list_monitors report
+    pub fn monitor_by_index(&self, index: usize) -> Result<&MonitorGeometry, String> {
+        self.monitors
+            .get(index)
+            .ok_or_else(|| format!("No monitor {}; there are {} (numbered from 0)", index, self.monitors.len()))
+    }
+
+    /// A point relative to monitor `index`'s top-left corner, as a desktop point
+    pub fn from_monitor(&self, index: usize, x: i32, y: i32) -> Result<(i32, i32), String> {
+        let monitor = self.monitor_by_index(index)?;
+        Ok((monitor.x + x, monitor.y + y))
+    }
+
+    /// The nearest point that is on some monitor - monitors of different sizes leave gaps in
+    /// the virtual desktop that the cursor can't reach
+    pub fn clamp(&self, x: i32, y: i32) -> (i32, i32) {
+        if self.monitor_at(x, y).is_some() {
+            return (x, y);
+        }
+        self.monitors
+            .iter()
+            .filter(|monitor| monitor.width > 0 && monitor.height > 0)
+            .map(|monitor| monitor.clamp(x, y))
+            .min_by_key(|&(cx, cy)| {
+                let (dx, dy) = ((cx - x) as i64, (cy - y) as i64);
+                dx * dx + dy * dy
+            })
+            .unwrap_or((x, y))
+    }
+}
+
+/// Where a captured image sits on the desktop. Image pixels need not be desktop units: a
+/// downscaled capture, or a Retina one, has a different number of pixels per unit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMapping {
+    pub monitor_id: u32,
+    /// Desktop position of the image's top-left pixel
+    pub origin_x: i32,
+    pub origin_y: i32,
+    /// Desktop units per image pixel
+    pub scale_x: f64,
+    pub scale_y: f64,
+    /// The monitor's scale factor, for reference
+    pub monitor_scale_factor: f32,
+}
+
+impl ImageMapping {
+    /// Mapping for an `image_width` x `image_height` capture of `region` (desktop x, y, width,
+    /// height) on `monitor`; `None` means the whole monitor
+    pub fn for_capture(
+        monitor: &MonitorGeometry,
+        region: Option<(i32, i32, u32, u32)>,
+        image_width: u32,
+        image_height: u32,
+    ) -> Self {
+        let (origin_x, origin_y, width, height) = region.unwrap_or((monitor.x, monitor.y, monitor.width, monitor.height));
+        let ratio = |units: u32, pixels: u32| if pixels == 0 { 1.0 } else { units as f64 / pixels as f64 };
+        Self {
+            monitor_id: monitor.id,
+            origin_x,
+            origin_y,
+            scale_x: ratio(width, image_width),
+            scale_y: ratio(height, image_height),
+            monitor_scale_factor: monitor.scale_factor,
+        }
+    }
+
+    pub fn to_desktop(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            self.origin_x + (x as f64 * self.scale_x).round() as i32,
+            self.origin_y + (y as f64 * self.scale_y).round() as i32,
+        )
+    }
+
+    /// The image pixel at desktop point (`x`, `y`); may fall outside the image
+    pub fn to_image(&self, x: i32, y: i32) -> (i32, i32) {
+        let pixels = |units: i32, scale: f64| if scale == 0.0 { units } else { (units as f64 / scale).round() as i32 };
+        (pixels(x - self.origin_x, self.scale_x), pixels(y - self.origin_y, self.scale_y))
+    }
+
+    /// A rectangle in image pixels as a desktop rectangle
+    pub fn rect_to_desktop(&self, x: i32, y: i32, width: i32, height: i32) -> (i32, i32, i32, i32) {
+        let (left, top) = self.to_desktop(x, y);
+        let (right, bottom) = self.to_desktop(x + width, y + height);
+        (left, top, right - left, bottom - top)
+    }
+}
+
+/// The monitors as the OS currently arranges them
+pub fn current_layout() -> Result<DesktopLayout, String> {
+    let monitors = xcap::Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let geometries = monitors
+        .iter()
+        .filter_map(|monitor| {
+            Some(MonitorGeometry {
+                id: monitor.id().ok()?,
+                x: monitor.x().ok()?,
+                y: monitor.y().ok()?,
+                width: monitor.width().ok()?,
+                height: monitor.height().ok()?,
+                scale_factor: monitor.scale_factor().unwrap_or(1.0),
+                is_primary: monitor.is_primary().unwrap_or(false),
+            })
+        })
+        .collect();
+    DesktopLayout::new(geometries)
+}
+
+/// Move the cursor to a desktop point, clamped onto the nearest monitor. Returns where it went.
+///
+/// `SetCursorPos` takes logical coordinates in a DPI-unaware thread, so the thread is made
+/// per-monitor aware around the call to keep it in physical pixels.
+#[cfg(target_os = "windows")]
+pub fn move_cursor(x: i32, y: i32) -> Result<(i32, i32), String> {
+    use winapi::shared::windef::DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2;
+    use winapi::um::winuser::{SetCursorPos, SetThreadDpiAwarenessContext};
+
+    let (x, y) = current_layout().map(|layout| layout.clamp(x, y)).unwrap_or((x, y));
+    let moved = unsafe {
+        let previous = SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+        let moved = SetCursorPos(x, y) != 0;
+        if !previous.is_null() {
+            SetThreadDpiAwarenessContext(previous);
+        }
+        moved
+    };
+    if moved {
+        Ok((x, y))
+    } else {
+        Err(format!("Failed to move cursor to ({}, {})", x, y))
+    }
+}
+
+/// The cursor's desktop position, in the same units `move_cursor` takes
+#[cfg(target_os = "windows")]
+pub fn cursor_position() -> Result<(i32, i32), String> {
+    use winapi::shared::windef::{DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, POINT};
+    use winapi::um::winuser::{GetCursorPos, SetThreadDpiAwarenessContext};
+
+    let mut point = POINT { x: 0, y: 0 };
+    let found = unsafe {
+        let previous = SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+        let found = GetCursorPos(&mut point) != 0;
+        if !previous.is_null() {
+            SetThreadDpiAwarenessContext(previous);
+        }
+        found
+    };
+    if found {
+        Ok((point.x, point.y))
+    } else {
+        Err("Failed to get cursor position".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(id: u32, x: i32, y: i32, width: u32, height: u32, scale_factor: f32, is_primary: bool) -> MonitorGeometry {
+        MonitorGeometry { id, x, y, width, height, scale_factor, is_primary }
+    }
+
+    #[test]
+    fn test_mixed_dpi_layout_maps_captures_and_clamps_points() {
+        // A 4K laptop panel at 150%, a 1080p monitor at 100% to its left and slightly lower,
+        // and a 1440p monitor at 125% to its right
+        let layout = DesktopLayout::new(vec![
+            monitor(1, 0, 0, 3840, 2160, 1.5, true),
+            monitor(2, -1920, 400, 1920, 1080, 1.0, false),
+            monitor(3, 3840, 0, 2560, 1440, 1.25, false),
+        ])
+        .unwrap();
+        assert_eq!(layout.primary().id, 1);
+        assert_eq!(layout.monitor_at(-1, 400).map(|m| m.id), Some(2));
+        assert_eq!(layout.monitor_at(3840, 0).map(|m| m.id), Some(3));
+        assert_eq!(layout.monitor_at(-1, 399), None);
+        assert_eq!(layout.monitor_by_index(2).map(|m| m.id), Ok(3));
+        assert!(layout.monitor_by_index(3).is_err());
+        assert_eq!(layout.from_monitor(1, 100, 50), Ok((-1820, 450)));
+
+        // Full-resolution capture of the left monitor: pixels are offset by its origin
+        let left = ImageMapping::for_capture(layout.monitor(2).unwrap(), None, 1920, 1080);
+        assert_eq!(left.to_desktop(100, 50), (-1820, 450));
+
+        // A half-size capture of the 150% panel still lands on the right physical pixel
+        let panel = ImageMapping::for_capture(layout.primary(), None, 1920, 1080);
+        assert_eq!(panel.to_desktop(960, 540), (1920, 1080));
+        assert_eq!(panel.rect_to_desktop(10, 20, 30, 40), (20, 40, 60, 80));
+        assert_eq!(panel.to_image(1920, 1080), (960, 540));
+
+        // Region captures start at the region, not the monitor
+        let region = ImageMapping::for_capture(layout.monitor(3).unwrap(), Some((4000, 100, 400, 300)), 400, 300);
+        assert_eq!(region.to_desktop(0, 0), (4000, 100));
+        assert_eq!(region.monitor_scale_factor, 1.25);
+
+        // Points in the gaps go to the nearest monitor edge
+        assert_eq!(layout.clamp(-500, 100), (-500, 400));
+        assert_eq!(layout.clamp(5000, 2000), (5000, 1439));
+        assert_eq!(layout.clamp(7000, -50), (6399, 0));
+        assert_eq!(layout.clamp(200, 300), (200, 300));
+
+        assert!(DesktopLayout::new(Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,338 @@
+// Audit log of MCP tool executions - append-only, with each entry's SHA-256 hash covering the
+// hash of the entry before it
+//
+// Editing or deleting any entry breaks the chain from that point on, which verify_audit_integrity
+// reports. Triggers refuse UPDATE and DELETE through SQLite, so only someone editing the file
+// directly can change history, and then the chain shows it. Cutting entries off the end leaves a
+// valid chain, so users who need to prove completeness should write down the head hash.
+//
+// Results aren't stored (screenshots would swamp the file); their hash is, so a saved result can
+// still be matched to its entry.
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use crate::command;
+
+use crate::data::worker;
+
+/// `previous_hash` of the first entry
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+const DEFAULT_PAGE: u32 = 100;
+const MAX_PAGE: u32 = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub sequence: i64,
+    pub recorded_at: String,
+    pub session_id: String,
+    pub tool_name: String,
+    /// Parameters as JSON
+    pub parameters: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// SHA-256 of the result JSON
+    pub result_hash: String,
+    pub execution_time_ms: u64,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditIntegrityReport {
+    pub valid: bool,
+    pub entries_checked: u64,
+    /// First entry that doesn't fit the chain
+    pub first_invalid_sequence: Option<i64>,
+    pub problem: Option<String>,
+    /// Hash of the last entry; record it to detect entries removed from the end later
+    pub head_hash: String,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Hash over every field of the entry but the hash itself
+fn entry_hash(entry: &AuditEntry) -> String {
+    // A JSON array leaves no doubt where one field ends and the next begins
+    let fields = serde_json::json!([
+        entry.sequence,
+        entry.recorded_at,
+        entry.session_id,
+        entry.tool_name,
+        entry.parameters,
+        entry.success,
+        entry.error,
+        entry.result_hash,
+        entry.execution_time_ms,
+        entry.previous_hash,
+    ]);
+    sha256_hex(fields.to_string().as_bytes())
+}
+
+/// Checks entries one at a time, oldest first
+struct ChainVerifier {
+    next_sequence: i64,
+    previous_hash: String,
+    checked: u64,
+}
+
+impl Default for ChainVerifier {
+    fn default() -> Self {
+        Self { next_sequence: 1, previous_hash: GENESIS_HASH.to_string(), checked: 0 }
+    }
+}
+
+impl ChainVerifier {
+    fn check(&mut self, entry: &AuditEntry) -> Result<(), String> {
+        if entry.sequence != self.next_sequence {
+            return Err(format!("Expected entry {} but found {}; entries are missing", self.next_sequence, entry.sequence));
+        }
+        if entry.previous_hash != self.previous_hash {
+            return Err("Its previous hash doesn't match the entry before it".to_string());
+        }
+        if entry_hash(entry) != entry.hash {
+            return Err("Its contents don't match its hash".to_string());
+        }
+        self.next_sequence += 1;
+        self.previous_hash = entry.hash.clone();
+        self.checked += 1;
+        Ok(())
+    }
+}
+
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<AuditEntry> {
+    Ok(AuditEntry {
+        sequence: row.get(0)?,
+        recorded_at: row.get(1)?,
+        session_id: row.get(2)?,
+        tool_name: row.get(3)?,
+        parameters: row.get(4)?,
+        success: row.get(5)?,
+        error: row.get(6)?,
+        result_hash: row.get(7)?,
+        execution_time_ms: row.get::<_, i64>(8)? as u64,
+        previous_hash: row.get(9)?,
+        hash: row.get(10)?,
+    })
+}
+
+const COLUMNS: &str = "sequence, recorded_at, session_id, tool_name, parameters, success, error, result_hash,
+    execution_time_ms, previous_hash, hash";
+
+/// The audit database, opened by each database worker on first use
+pub struct AuditLog {
+    connection: Connection,
+}
+
+impl AuditLog {
+    pub fn new(app_handle: &AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+        std::fs::create_dir_all(&app_data_dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+        let connection = Connection::open(app_data_dir.join("mcp_audit.db"))
+            .map_err(|e| format!("Failed to open audit log: {}", e))?;
+        Self::with_connection(connection)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, String> {
+        let _ = connection.query_row("PRAGMA journal_mode = WAL", [], |row| row.get::<_, String>(0));
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS mcp_audit_log (
+                    sequence INTEGER PRIMARY KEY,
+                    recorded_at TEXT NOT NULL,
+                    session_id TEXT NOT NULL,
+                    tool_name TEXT NOT NULL,
+                    parameters TEXT NOT NULL,
+                    success INTEGER NOT NULL,
+                    error TEXT,
+                    result_hash TEXT NOT NULL,
+                    execution_time_ms INTEGER NOT NULL,
+                    previous_hash TEXT NOT NULL,
+                    hash TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_mcp_audit_log_session ON mcp_audit_log(session_id, sequence);
+                CREATE TRIGGER IF NOT EXISTS mcp_audit_log_no_update BEFORE UPDATE ON mcp_audit_log
+                BEGIN SELECT RAISE(ABORT, 'The audit log is append-only'); END;
+                CREATE TRIGGER IF NOT EXISTS mcp_audit_log_no_delete BEFORE DELETE ON mcp_audit_log
+                BEGIN SELECT RAISE(ABORT, 'The audit log is append-only'); END;",
+            )
+            .map_err(|e| format!("Failed to create audit log: {}", e))?;
+        Ok(Self { connection })
+    }
+
+    /// Chain `entry` onto the last one; its sequence and hashes are filled in here
+    fn append(&mut self, mut entry: AuditEntry) -> Result<AuditEntry, String> {
+        let tx = self.connection.transaction().map_err(|e| e.to_string())?;
+        let last: Option<(i64, String)> = tx
+            .query_row("SELECT sequence, hash FROM mcp_audit_log ORDER BY sequence DESC LIMIT 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .optional()
+            .map_err(|e| e.to_string())?;
+        let (sequence, previous_hash) = last.unwrap_or((0, GENESIS_HASH.to_string()));
+        entry.sequence = sequence + 1;
+        entry.previous_hash = previous_hash;
+        entry.hash = entry_hash(&entry);
+        tx.execute(
+            &format!("INSERT INTO mcp_audit_log ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)", COLUMNS),
+            params![
+                entry.sequence,
+                entry.recorded_at,
+                entry.session_id,
+                entry.tool_name,
+                entry.parameters,
+                entry.success,
+                entry.error,
+                entry.result_hash,
+                entry.execution_time_ms as i64,
+                entry.previous_hash,
+                entry.hash,
+            ],
+        )
+        .map_err(|e| format!("Failed to append to audit log: {}", e))?;
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(entry)
+    }
+
+    /// Newest first, starting below `before_sequence`
+    fn entries(&self, session_id: Option<&str>, before_sequence: Option<i64>, limit: u32) -> Result<Vec<AuditEntry>, String> {
+        let mut statement = self
+            .connection
+            .prepare(&format!(
+                "SELECT {} FROM mcp_audit_log
+                 WHERE (?1 IS NULL OR session_id = ?1) AND (?2 IS NULL OR sequence < ?2)
+                 ORDER BY sequence DESC LIMIT ?3",
+                COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = statement
+            .query_map(params![session_id, before_sequence, limit], entry_from_row)
+            .map_err(|e| e.to_string())?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(|e| format!("Failed to read audit log: {}", e))
+    }
+
+    fn verify(&self) -> Result<AuditIntegrityReport, String> {
+        let mut statement = self
+            .connection
+            .prepare(&format!("SELECT {} FROM mcp_audit_log ORDER BY sequence", COLUMNS))
+            .map_err(|e| e.to_string())?;
+        let mut rows = statement.query([]).map_err(|e| e.to_string())?;
+        let mut verifier = ChainVerifier::default();
+        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+            let entry = entry_from_row(row).map_err(|e| format!("Unreadable audit entry: {}", e))?;
+            if let Err(problem) = verifier.check(&entry) {
+                return Ok(AuditIntegrityReport {
+                    valid: false,
+                    entries_checked: verifier.checked,
+                    first_invalid_sequence: Some(entry.sequence),
+                    problem: Some(problem),
+                    head_hash: verifier.previous_hash,
+                });
+            }
+        }
+        Ok(AuditIntegrityReport {
+            valid: true,
+            entries_checked: verifier.checked,
+            first_invalid_sequence: None,
+            problem: None,
+            head_hash: verifier.previous_hash,
+        })
+    }
+}
+
+impl AuditEntry {
+    /// An entry for a finished tool call, before it's chained
+    pub fn tool_call(
+        session_id: &str,
+        tool_name: &str,
+        parameters: &serde_json::Value,
+        success: bool,
+        result: &serde_json::Value,
+        error: Option<&str>,
+        execution_time_ms: u64,
+    ) -> Self {
+        Self {
+            sequence: 0,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            session_id: session_id.to_string(),
+            tool_name: tool_name.to_string(),
+            parameters: parameters.to_string(),
+            success,
+            error: error.map(str::to_string),
+            result_hash: sha256_hex(result.to_string().as_bytes()),
+            execution_time_ms,
+            previous_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+}
+
+/// Add an entry to the audit log; writes are queued in call order
+pub fn record(app_handle: &AppHandle, entry: AuditEntry) {
+    worker::submit(app_handle, "mcp_audit_log", move |db| db.audit()?.append(entry).map(|_| ()));
+}
+
+/// Audit entries, newest first; page with `before_sequence`
+#[command]
+pub async fn get_mcp_audit_log(
+    app_handle: AppHandle,
+    session_id: Option<String>,
+    before_sequence: Option<i64>,
+    limit: Option<u32>,
+) -> Result<Vec<AuditEntry>, String> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+    worker::read(&app_handle, move |db| db.audit()?.entries(session_id.as_deref(), before_sequence, limit)).await
+}
+
+/// Recompute the hash chain over the whole log
+#[command]
+pub async fn verify_audit_integrity(app_handle: AppHandle) -> Result<AuditIntegrityReport, String> {
+    worker::read(&app_handle, |db| db.audit()?.verify()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_detects_edits_and_gaps() {
+        let mut log = AuditLog::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        for tool in ["take_screenshot", "click", "type"] {
+            let entry = AuditEntry::tool_call("session", tool, &serde_json::json!({}), true, &serde_json::json!({}), None, 5);
+            log.append(entry).unwrap();
+        }
+        let report = log.verify().unwrap();
+        assert!(report.valid);
+        assert_eq!(report.entries_checked, 3);
+
+        // Newest first, paged by sequence
+        let entries = log.entries(Some("session"), Some(3), 10).unwrap();
+        assert_eq!(entries.iter().map(|entry| entry.sequence).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(entries[0].previous_hash, entries[1].hash);
+        assert_eq!(report.head_hash, log.entries(None, None, 1).unwrap()[0].hash);
+
+        // SQLite refuses edits outright
+        assert!(log.connection.execute("DELETE FROM mcp_audit_log WHERE sequence = 2", []).is_err());
+
+        // Edits made around the triggers break the chain at the edited entry
+        log.connection.execute_batch("DROP TRIGGER mcp_audit_log_no_update; DROP TRIGGER mcp_audit_log_no_delete;").unwrap();
+        log.connection.execute("UPDATE mcp_audit_log SET tool_name = 'scroll' WHERE sequence = 2", []).unwrap();
+        let report = log.verify().unwrap();
+        assert_eq!((report.valid, report.first_invalid_sequence, report.entries_checked), (false, Some(2), 1));
+        assert!(report.problem.unwrap().contains("hash"));
+
+        log.connection.execute("DELETE FROM mcp_audit_log WHERE sequence = 2", []).unwrap();
+        let report = log.verify().unwrap();
+        assert_eq!(report.first_invalid_sequence, Some(3));
+        assert!(report.problem.unwrap().contains("missing"));
+    }
+}
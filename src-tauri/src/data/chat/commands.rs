@@ -1,25 +1,24 @@
 // Tauri commands for chat storage operations
-use tauri::{AppHandle, command};
+use tauri::AppHandle;
+use crate::command;
 use crate::data::types::{SaveChatsPayload, LoadChatsResponse};
-use super::storage::ChatStorage;
+use crate::data::worker;
 
 #[command]
-pub fn save_chat_sessions(
+pub async fn save_chat_sessions(
     app_handle: AppHandle,
     payload: SaveChatsPayload,
 ) -> Result<(), String> {
-    match ChatStorage::new(&app_handle) {
-        Ok(mut storage) => storage.save_chat_sessions(payload)
-            .map_err(|e| format!("Failed to save chat sessions: {}", e)),
-        Err(e) => Err(format!("Failed to initialize chat storage: {}", e))
-    }
+    worker::write(&app_handle, move |db| {
+        db.chats()?.save_chat_sessions(payload)
+            .map_err(|e| format!("Failed to save chat sessions: {}", e))
+    }).await
 }
 
 #[command]
-pub fn load_chat_sessions(app_handle: AppHandle) -> Result<LoadChatsResponse, String> {
-    match ChatStorage::new(&app_handle) {
-        Ok(storage) => storage.load_chat_sessions()
-            .map_err(|e| format!("Failed to load chat sessions: {}", e)),
-        Err(e) => Err(format!("Failed to initialize chat storage: {}", e))
-    }
+pub async fn load_chat_sessions(app_handle: AppHandle) -> Result<LoadChatsResponse, String> {
+    worker::read(&app_handle, |db| {
+        db.chats()?.load_chat_sessions()
+            .map_err(|e| format!("Failed to load chat sessions: {}", e))
+    }).await
 }
\ No newline at end of file
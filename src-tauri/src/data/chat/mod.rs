@@ -2,7 +2,11 @@
 
 pub mod storage;
 pub mod commands;
+pub mod pending;
+pub mod scheduled;
 
 // Re-export the main functionality
 pub use storage::*;
-pub use commands::*;
\ No newline at end of file
+pub use commands::*;
+pub use pending::*;
+pub use scheduled::*;
\ No newline at end of file
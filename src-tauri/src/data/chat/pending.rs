@@ -0,0 +1,117 @@
+// Partial AI responses written out while they stream, so a crash doesn't lose them
+//
+// A `ResponseJournal` lives as long as one streamed response and rewrites its row every second
+// or so. Dropping the journal - on completion, cancel, timeout or error - removes the row; only
+// a run that dies mid-stream leaves one behind for `recover_pending_responses`.
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use crate::command;
+use crate::data::worker;
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+const FLUSH_BYTES: usize = 2048;
+
+lazy_static::lazy_static! {
+    // Rows from this run belong to streams still in progress
+    static ref RUN_ID: String = uuid::Uuid::new_v4().to_string();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingResponse {
+    pub session_id: String,
+    pub agent: String,
+    pub model: String,
+    /// Text streamed before the last flush
+    pub content: String,
+    pub started_at: i64,
+    pub updated_at: i64,
+}
+
+pub fn current_run_id() -> &'static str {
+    &RUN_ID
+}
+
+pub struct ResponseJournal {
+    app_handle: AppHandle,
+    response: PendingResponse,
+    unflushed: usize,
+    last_flush: Instant,
+}
+
+impl ResponseJournal {
+    pub fn start(app_handle: &AppHandle, session_id: &str, agent: &str, model: &str) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        Self {
+            app_handle: app_handle.clone(),
+            response: PendingResponse {
+                session_id: session_id.to_string(),
+                agent: agent.to_string(),
+                model: model.to_string(),
+                content: String::new(),
+                started_at: now,
+                updated_at: now,
+            },
+            unflushed: 0,
+            last_flush: Instant::now(),
+        }
+    }
+
+    pub fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.response.content.push_str(text);
+        self.unflushed += text.len();
+        if self.unflushed >= FLUSH_BYTES || self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        self.unflushed = 0;
+        self.last_flush = Instant::now();
+        self.response.updated_at = chrono::Utc::now().timestamp_millis();
+        let response = self.response.clone();
+        worker::submit(&self.app_handle, "pending_response", move |db| {
+            db.chats()?
+                .save_pending_response(&response, current_run_id())
+                .map_err(|e| e.to_string())
+        });
+    }
+}
+
+impl Drop for ResponseJournal {
+    fn drop(&mut self) {
+        // Nothing was written for a response that never produced text
+        if self.response.content.is_empty() {
+            return;
+        }
+        // Queued after the last flush, so the row can't reappear
+        let session_id = std::mem::take(&mut self.response.session_id);
+        worker::submit(&self.app_handle, "pending_response", move |db| {
+            db.chats()?.delete_pending_response(&session_id).map_err(|e| e.to_string())
+        });
+    }
+}
+
+/// Responses that were still streaming when a previous run ended, oldest first
+#[command]
+pub async fn recover_pending_responses(app_handle: AppHandle) -> Result<Vec<PendingResponse>, String> {
+    worker::write(&app_handle, |db| {
+        db.chats()?
+            .load_pending_responses(current_run_id())
+            .map_err(|e| format!("Failed to load pending responses: {}", e))
+    }).await
+}
+
+/// Forget a recovered response once the frontend has kept or discarded it
+#[command]
+pub async fn discard_pending_response(app_handle: AppHandle, session_id: String) -> Result<(), String> {
+    worker::write(&app_handle, move |db| {
+        db.chats()?
+            .delete_pending_response(&session_id)
+            .map_err(|e| format!("Failed to discard pending response: {}", e))
+    }).await
+}
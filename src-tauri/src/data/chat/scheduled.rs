@@ -0,0 +1,287 @@
+// Scheduled agent tasks - recurring research or document digests, stored with a cron-like
+// schedule and run in the background, each run saved as a chat session
+//
+// Schedules are five cron fields (minute hour day-of-month month day-of-week) in local time,
+// with `*`, lists, ranges and `/step`, or one of `@hourly`, `@daily` and `@weekly`.
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use crate::app_lock::{require_unlocked, CommandGroup};
+use crate::command;
+use crate::data::worker;
+
+// A schedule that matches nothing in a year is treated as never firing
+const MAX_LOOKAHEAD_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    // Cron ORs day-of-month and day-of-week when both are restricted
+    day_of_month_any: bool,
+    day_of_week_any: bool,
+}
+
+// Bitmask of the values one field allows
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("Invalid step '{}' in {}", step, name))?;
+                if step == 0 {
+                    return Err(format!("Step can't be 0 in {}", name));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let value = |text: &str| -> Result<u32, String> {
+            match text.parse::<u32>() {
+                Ok(value) if (min..=max).contains(&value) => Ok(value),
+                _ => Err(format!("'{}' is outside {}-{} in {}", text, min, max, name)),
+            }
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `5/15` means from 5 to the end in steps of 15
+            (start, if step > 1 { max } else { start })
+        };
+        if start > end {
+            return Err(format!("Range {}-{} runs backwards in {}", start, end, name));
+        }
+        for v in (start..=end).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "Schedule '{}' needs five fields: minute hour day-of-month month day-of-week",
+                expression
+            ));
+        };
+        // Sunday is both 0 and 7
+        let mut days_of_week = parse_field(day_of_week, 0, 7, "day of week")?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & 0x7f;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")? as u32,
+            days_of_month: parse_field(day_of_month, 1, 31, "day of month")? as u32,
+            months: parse_field(month, 1, 12, "month")? as u16,
+            days_of_week: days_of_week as u8,
+            day_of_month_any: day_of_month == "*",
+            day_of_week_any: day_of_week == "*",
+        })
+    }
+
+    fn matches_day(&self, date: &DateTime<Local>) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let by_month = self.days_of_month & (1 << date.day()) != 0;
+        let by_week = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.day_of_month_any, self.day_of_week_any) {
+            (true, true) => true,
+            (true, false) => by_week,
+            (false, true) => by_month,
+            (false, false) => by_month || by_week,
+        }
+    }
+
+    /// First matching minute strictly after `after`
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_LOOKAHEAD_DAYS);
+        let mut time = start;
+        while time < limit {
+            if !self.matches_day(&time) {
+                // Straight to the next midnight; a skipped DST hour is just passed over
+                let next_day = time.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                time = Local.from_local_datetime(&next_day).earliest()?;
+                continue;
+            }
+            if self.hours & (1 << time.hour()) == 0 {
+                time = time.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << time.minute()) != 0 {
+                return Some(time);
+            }
+            time += Duration::minutes(1);
+        }
+        None
+    }
+}
+
+/// What a task does each time it runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ScheduledTaskKind {
+    /// Summarize enhanced RAG documents tagged `tag` that arrived since the last run
+    DocumentDigest { tag: String },
+    /// Run the research agent on a topic
+    Research { topic: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTask {
+    pub id: String,
+    pub name: String,
+    pub kind: ScheduledTaskKind,
+    pub schedule: String,
+    pub enabled: bool,
+    pub created_at: i64,
+    /// Last run that didn't fail; digests cover documents added since
+    pub last_run_at: Option<i64>,
+    pub next_run_at: Option<i64>,
+    /// "completed", "skipped: …" or "failed: …"
+    pub last_status: Option<String>,
+    /// Chat session holding the last run's result
+    pub last_session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskInput {
+    /// Existing task to update; a new one is created without it
+    pub id: Option<String>,
+    pub name: String,
+    pub kind: ScheduledTaskKind,
+    pub schedule: String,
+    pub enabled: bool,
+}
+
+/// When `schedule` next fires after `after_ms`, as a millisecond timestamp
+pub fn next_run_at(schedule: &str, after_ms: i64) -> Result<Option<i64>, String> {
+    let schedule = Schedule::parse(schedule)?;
+    let after = Local.timestamp_millis_opt(after_ms).single().ok_or("Invalid timestamp")?;
+    Ok(schedule.next_after(after).map(|time| time.timestamp_millis()))
+}
+
+fn validate_kind(kind: &ScheduledTaskKind) -> Result<(), String> {
+    match kind {
+        ScheduledTaskKind::DocumentDigest { tag } if tag.trim().trim_start_matches('#').is_empty() => {
+            Err("A document digest needs a tag".to_string())
+        }
+        ScheduledTaskKind::Research { topic } if topic.trim().is_empty() => {
+            Err("A research task needs a topic".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[command]
+pub async fn list_scheduled_tasks(app_handle: AppHandle) -> Result<Vec<ScheduledTask>, String> {
+    worker::read(&app_handle, |db| {
+        db.chats()?
+            .load_scheduled_tasks()
+            .map_err(|e| format!("Failed to load scheduled tasks: {}", e))
+    }).await
+}
+
+/// Create or update a task; its next run is worked out from the schedule
+#[command]
+pub async fn save_scheduled_task(app_handle: AppHandle, task: ScheduledTaskInput) -> Result<ScheduledTask, String> {
+    validate_kind(&task.kind)?;
+    let name = task.name.trim().to_string();
+    if name.is_empty() {
+        return Err("A scheduled task needs a name".to_string());
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let next_run_at = next_run_at(&task.schedule, now)?;
+
+    worker::write(&app_handle, move |db| {
+        let storage = db.chats()?;
+        let existing = match &task.id {
+            Some(id) => Some(
+                storage
+                    .load_scheduled_task(id)
+                    .map_err(|e| format!("Failed to load scheduled task: {}", e))?
+                    .ok_or_else(|| format!("Scheduled task {} not found", id))?,
+            ),
+            None => None,
+        };
+        let saved = ScheduledTask {
+            id: task.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            name,
+            kind: task.kind,
+            schedule: task.schedule.trim().to_string(),
+            enabled: task.enabled,
+            created_at: existing.as_ref().map(|t| t.created_at).unwrap_or(now),
+            last_run_at: existing.as_ref().and_then(|t| t.last_run_at),
+            next_run_at,
+            last_status: existing.as_ref().and_then(|t| t.last_status.clone()),
+            last_session_id: existing.and_then(|t| t.last_session_id),
+        };
+        storage
+            .save_scheduled_task(&saved)
+            .map_err(|e| format!("Failed to save scheduled task: {}", e))?;
+        Ok(saved)
+    }).await
+}
+
+#[command]
+pub async fn delete_scheduled_task(app_handle: AppHandle, id: String) -> Result<(), String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
+    worker::write(&app_handle, move |db| {
+        db.chats()?
+            .delete_scheduled_task(&id)
+            .map_err(|e| format!("Failed to delete scheduled task: {}", e))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_schedule_parsing_and_next_run() {
+        // Weekday mornings at 8:30
+        let weekdays = Schedule::parse("30 8 * * 1-5").unwrap();
+        // Friday 2026-10-16 at 9:00 -> Monday 8:30
+        assert_eq!(weekdays.next_after(at(2026, 10, 16, 9, 0)), Some(at(2026, 10, 19, 8, 30)));
+        assert_eq!(weekdays.next_after(at(2026, 10, 14, 8, 29)), Some(at(2026, 10, 14, 8, 30)));
+
+        // Every 15 minutes, strictly after the given time
+        let quarter = Schedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(quarter.next_after(at(2026, 10, 14, 10, 15)), Some(at(2026, 10, 14, 10, 30)));
+
+        // Day of month and day of week are ORed when both are set; 7 is Sunday
+        let either = Schedule::parse("0 12 1 * 7").unwrap();
+        assert_eq!(either.next_after(at(2026, 10, 14, 0, 0)), Some(at(2026, 10, 18, 12, 0)));
+        assert_eq!(either.next_after(at(2026, 10, 31, 0, 0)), Some(at(2026, 11, 1, 12, 0)));
+
+        assert_eq!(Schedule::parse("@daily").unwrap(), Schedule::parse("0 0 * * *").unwrap());
+        assert_eq!(Schedule::parse("0 0 30 2 *").unwrap().next_after(at(2026, 1, 1, 0, 0)), None);
+        assert!(Schedule::parse("0 8 * *").is_err());
+        assert!(Schedule::parse("60 8 * * *").is_err());
+        assert!(Schedule::parse("0 9-5 * * *").is_err());
+        assert!(Schedule::parse("*/0 * * * *").is_err());
+    }
+}
@@ -5,6 +5,8 @@ use crate::data::types::{
     ChatSession, ChatMessage, MessageAttachment, ThinkingProcess, ThinkingStep, MessageMetadata,
     SaveChatsPayload, LoadChatsResponse
 };
+use super::pending::PendingResponse;
+use super::scheduled::{ScheduledTask, ScheduledTaskKind};
 use std::path::PathBuf;
 
 pub struct ChatStorage {
@@ -140,10 +142,37 @@ impl ChatStorage {
                 analysis_types TEXT, -- JSON array stored as text
                 search_queries TEXT, -- JSON array stored as text
                 sources TEXT, -- JSON array stored as text
+                seed INTEGER,
                 FOREIGN KEY (message_id) REFERENCES chat_messages(id) ON DELETE CASCADE
             );
 
+            -- Partial responses of streams in progress; rows from an earlier run are recoverable
+            CREATE TABLE IF NOT EXISTS pending_responses (
+                session_id TEXT PRIMARY KEY,
+                run_id TEXT NOT NULL,
+                agent TEXT NOT NULL,
+                model TEXT NOT NULL,
+                content TEXT NOT NULL,
+                started_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            -- Recurring agent tasks; each run's result is saved as a chat session
+            CREATE TABLE IF NOT EXISTS scheduled_tasks (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                kind TEXT NOT NULL, -- JSON ScheduledTaskKind
+                schedule TEXT NOT NULL,
+                enabled INTEGER NOT NULL CHECK(enabled IN (0, 1)),
+                created_at INTEGER NOT NULL,
+                last_run_at INTEGER,
+                next_run_at INTEGER,
+                last_status TEXT,
+                last_session_id TEXT
+            );
+
             -- Indexes for performance
+            CREATE INDEX IF NOT EXISTS idx_scheduled_tasks_next_run ON scheduled_tasks(enabled, next_run_at);
             CREATE INDEX IF NOT EXISTS idx_chat_sessions_updated_desc ON chat_sessions(updated_at DESC);
             CREATE INDEX IF NOT EXISTS idx_chat_messages_session_timestamp ON chat_messages(session_id, timestamp);
             CREATE INDEX IF NOT EXISTS idx_message_attachments_message ON message_attachments(message_id);
@@ -152,6 +181,12 @@ impl ChatStorage {
             CREATE INDEX IF NOT EXISTS idx_message_metadata_message ON message_metadata(message_id);
         "#)?;
 
+        // Add seed column if it doesn't exist (for existing databases)
+        let _ = self.connection.execute(
+            "ALTER TABLE message_metadata ADD COLUMN seed INTEGER",
+            params![],
+        );
+
         Ok(())
     }
 
@@ -228,13 +263,14 @@ impl ChatStorage {
                 // Insert message metadata if present
                 if let Some(metadata) = message.metadata {
                     tx.execute(
-                        "INSERT INTO message_metadata (message_id, agent_type, model, tokens, processing_time, analysis_types, search_queries, sources)
-                         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                        "INSERT INTO message_metadata (message_id, agent_type, model, tokens, processing_time, analysis_types, search_queries, sources, seed)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
                         params![
                             message.id, metadata.agent_type, metadata.model, metadata.tokens, metadata.processing_time,
                             metadata.analysis_type.map(|v| serde_json::to_string(&v).unwrap_or_default()),
                             metadata.search_queries.map(|v| serde_json::to_string(&v).unwrap_or_default()),
-                            metadata.sources.map(|v| serde_json::to_string(&v).unwrap_or_default())
+                            metadata.sources.map(|v| serde_json::to_string(&v).unwrap_or_default()),
+                            metadata.seed
                         ]
                     )?;
                 }
@@ -412,7 +448,7 @@ impl ChatStorage {
 
     fn load_metadata_for_message(&self, message_id: i32) -> Result<MessageMetadata> {
         let mut stmt = self.connection.prepare(
-            "SELECT agent_type, model, tokens, processing_time, analysis_types, search_queries, sources 
+            "SELECT agent_type, model, tokens, processing_time, analysis_types, search_queries, sources, seed 
              FROM message_metadata WHERE message_id = ?"
         )?;
 
@@ -429,9 +465,167 @@ impl ChatStorage {
                 analysis_type: analysis_types.and_then(|s| serde_json::from_str(&s).ok()),
                 search_queries: search_queries.and_then(|s| serde_json::from_str(&s).ok()),
                 sources: sources.and_then(|s| serde_json::from_str(&s).ok()),
+                seed: row.get("seed")?,
             })
         })
     }
+
+    pub fn save_pending_response(&mut self, response: &PendingResponse, run_id: &str) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO pending_responses
+                (session_id, run_id, agent, model, content, started_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                response.session_id, run_id, response.agent, response.model,
+                response.content, response.started_at, response.updated_at
+            ]
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_pending_response(&mut self, session_id: &str) -> Result<()> {
+        self.connection.execute("DELETE FROM pending_responses WHERE session_id = ?", params![session_id])?;
+        Ok(())
+    }
+
+    /// Pending responses left by runs other than `current_run_id`
+    pub fn load_pending_responses(&self, current_run_id: &str) -> Result<Vec<PendingResponse>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT session_id, agent, model, content, started_at, updated_at
+             FROM pending_responses WHERE run_id != ? ORDER BY started_at"
+        )?;
+
+        let responses = stmt.query_map([current_run_id], |row| {
+            Ok(PendingResponse {
+                session_id: row.get("session_id")?,
+                agent: row.get("agent")?,
+                model: row.get("model")?,
+                content: row.get("content")?,
+                started_at: row.get("started_at")?,
+                updated_at: row.get("updated_at")?,
+            })
+        })?;
+
+        responses.collect()
+    }
+
+    /// Add one session without touching the others, for results produced in the background
+    pub fn insert_chat_session(&mut self, session: ChatSession) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        tx.execute(
+            "INSERT INTO chat_sessions (id, title, created_at, updated_at, model_id) VALUES (?, ?, ?, ?, ?)",
+            params![session.id, session.title, session.created_at, session.updated_at, session.model_id]
+        )?;
+        for message in session.history {
+            tx.execute(
+                "INSERT INTO chat_messages (id, session_id, text, sender, timestamp, is_interim, confidence, source, message_type)
+                 VALUES (NULL, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    session.id, message.text, message.sender, message.timestamp,
+                    message.is_interim.map(|b| if b { 1 } else { 0 }),
+                    message.confidence, message.source, message.message_type
+                ]
+            )?;
+            let message_id = tx.last_insert_rowid();
+            if let Some(metadata) = message.metadata {
+                tx.execute(
+                    "INSERT INTO message_metadata (message_id, agent_type, model, tokens, processing_time, analysis_types, search_queries, sources, seed)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        message_id, metadata.agent_type, metadata.model, metadata.tokens, metadata.processing_time,
+                        metadata.analysis_type.map(|v| serde_json::to_string(&v).unwrap_or_default()),
+                        metadata.search_queries.map(|v| serde_json::to_string(&v).unwrap_or_default()),
+                        metadata.sources.map(|v| serde_json::to_string(&v).unwrap_or_default()),
+                        metadata.seed
+                    ]
+                )?;
+            }
+        }
+        tx.commit()
+    }
+
+    pub fn save_scheduled_task(&mut self, task: &ScheduledTask) -> Result<()> {
+        let kind = serde_json::to_string(&task.kind).unwrap_or_default();
+        self.connection.execute(
+            "INSERT OR REPLACE INTO scheduled_tasks
+                (id, name, kind, schedule, enabled, created_at, last_run_at, next_run_at, last_status, last_session_id)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                task.id, task.name, kind, task.schedule, if task.enabled { 1 } else { 0 },
+                task.created_at, task.last_run_at, task.next_run_at, task.last_status, task.last_session_id
+            ]
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_scheduled_task(&mut self, id: &str) -> Result<()> {
+        self.connection.execute("DELETE FROM scheduled_tasks WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    /// Record a finished run and when the task fires next
+    pub fn record_scheduled_run(
+        &mut self,
+        id: &str,
+        ran_at: i64,
+        next_run_at: Option<i64>,
+        status: &str,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        self.connection.execute(
+            "UPDATE scheduled_tasks
+             SET last_run_at = ?, next_run_at = ?, last_status = ?,
+                 last_session_id = COALESCE(?, last_session_id)
+             WHERE id = ?",
+            params![ran_at, next_run_at, status, session_id, id]
+        )?;
+        Ok(())
+    }
+
+    fn query_scheduled_tasks(&self, filter: &str, args: &[&dyn rusqlite::ToSql]) -> Result<Vec<ScheduledTask>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT id, name, kind, schedule, enabled, created_at, last_run_at, next_run_at, last_status, last_session_id
+             FROM scheduled_tasks {}",
+            filter
+        ))?;
+
+        let tasks = stmt.query_map(args, |row| {
+            let kind: String = row.get("kind")?;
+            let kind: ScheduledTaskKind = serde_json::from_str(&kind).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+            })?;
+            Ok(ScheduledTask {
+                id: row.get("id")?,
+                name: row.get("name")?,
+                kind,
+                schedule: row.get("schedule")?,
+                enabled: row.get::<_, i32>("enabled")? != 0,
+                created_at: row.get("created_at")?,
+                last_run_at: row.get("last_run_at")?,
+                next_run_at: row.get("next_run_at")?,
+                last_status: row.get("last_status")?,
+                last_session_id: row.get("last_session_id")?,
+            })
+        })?;
+
+        tasks.collect()
+    }
+
+    pub fn load_scheduled_tasks(&self) -> Result<Vec<ScheduledTask>> {
+        self.query_scheduled_tasks("ORDER BY created_at", &[])
+    }
+
+    pub fn load_scheduled_task(&self, id: &str) -> Result<Option<ScheduledTask>> {
+        Ok(self.query_scheduled_tasks("WHERE id = ?", &[&id])?.into_iter().next())
+    }
+
+    /// Enabled tasks whose next run is at or before `now`
+    pub fn due_scheduled_tasks(&self, now: i64) -> Result<Vec<ScheduledTask>> {
+        self.query_scheduled_tasks(
+            "WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ? ORDER BY next_run_at",
+            &[&now],
+        )
+    }
 }
 
 // Helper function to get database path
@@ -1,56 +1,57 @@
 // Tauri commands for conversation storage operations
-use tauri::{AppHandle, command};
+// Storage work runs on the database worker threads so a slow query never ties up command handling
+use tauri::AppHandle;
+use crate::command;
 use crate::data::types::{
     SaveConversationsPayload, LoadConversationsResponse,
     ConversationMessage, ConversationInsight, ConversationMessageUpdate
 };
-use super::storage::ConversationStorage;
+use crate::data::worker;
+use crate::app_lock::{require_unlocked, CommandGroup};
 
 #[command]
-pub fn save_conversations(
+pub async fn save_conversations(
     app_handle: AppHandle,
     payload: SaveConversationsPayload,
 ) -> Result<(), String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => storage.save_conversations(payload)
-            .map_err(|e| format!("Failed to save conversations: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
-    }
+    worker::write(&app_handle, move |db| {
+        db.conversations()?.save_conversations(payload)
+            .map_err(|e| format!("Failed to save conversations: {}", e))
+    }).await
 }
 
 #[command]
-pub fn load_conversations(app_handle: AppHandle) -> Result<LoadConversationsResponse, String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(storage) => storage.load_conversations()
-            .map_err(|e| format!("Failed to load conversations: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
-    }
+pub async fn load_conversations(app_handle: AppHandle) -> Result<LoadConversationsResponse, String> {
+    worker::read(&app_handle, |db| {
+        db.conversations()?.load_conversations()
+            .map_err(|e| format!("Failed to load conversations: {}", e))
+    }).await
 }
 
 #[command]
-pub fn delete_conversation(
+pub async fn delete_conversation(
     app_handle: AppHandle,
     conversation_id: String,
 ) -> Result<(), String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => storage.delete_conversation(&conversation_id)
-            .map_err(|e| format!("Failed to delete conversation: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
-    }
+    require_unlocked(CommandGroup::DataDeletion)?;
+    worker::write(&app_handle, move |db| {
+        db.conversations()?.delete_conversation(&conversation_id)
+            .map_err(|e| format!("Failed to delete conversation: {}", e))
+    }).await
 }
 
 #[command]
-pub fn clear_all_conversations(app_handle: AppHandle) -> Result<(), String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => storage.clear_all_conversations()
-            .map_err(|e| format!("Failed to clear conversations: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
-    }
+pub async fn clear_all_conversations(app_handle: AppHandle) -> Result<(), String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
+    worker::write(&app_handle, |db| {
+        db.conversations()?.clear_all_conversations()
+            .map_err(|e| format!("Failed to clear conversations: {}", e))
+    }).await
 }
 
 // Message-level operations
 #[command]
-pub fn save_conversation_message(
+pub async fn save_conversation_message(
     app_handle: AppHandle,
     session_id: String,
     message: ConversationMessage,
@@ -72,143 +73,118 @@ pub fn save_conversation_message(
         return Err(error_msg);
     }
     
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => {
-            let result = storage.save_conversation_message(&session_id, message);
-            match result {
-                Ok(_) => {
-                    println!("✅ Message saved successfully");
-                    Ok(())
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to save conversation message: {}", e);
-                    println!("❌ {}", error_msg);
-                    Err(error_msg)
-                }
-            }
-        }
-        Err(e) => {
-            let error_msg = format!("Failed to initialize conversation storage: {}", e);
-            println!("❌ {}", error_msg);
-            Err(error_msg)
-        }
+    let result = worker::write(&app_handle, move |db| {
+        db.conversations()?.save_conversation_message(&session_id, message)
+            .map_err(|e| format!("Failed to save conversation message: {}", e))
+    }).await;
+    match &result {
+        Ok(_) => println!("✅ Message saved successfully"),
+        Err(e) => println!("❌ {}", e),
     }
+    result
 }
 
 #[command]
-pub fn batch_save_conversation_messages(
+pub async fn batch_save_conversation_messages(
     app_handle: AppHandle,
     session_id: String,
     messages: Vec<ConversationMessage>,
 ) -> Result<(), String> {
     println!("📥 batch_save_conversation_messages called - session_id: {}, message_count: {}", session_id, messages.len());
     
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => {
-            let result = storage.batch_save_conversation_messages(&session_id, messages);
-            match result {
-                Ok(_) => {
-                    println!("✅ Batch messages saved successfully");
-                    Ok(())
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to batch save conversation messages: {}", e);
-                    println!("❌ {}", error_msg);
-                    Err(error_msg)
-                }
-            }
-        }
-        Err(e) => {
-            let error_msg = format!("Failed to initialize conversation storage: {}", e);
-            println!("❌ {}", error_msg);
-            Err(error_msg)
-        }
+    let result = worker::write(&app_handle, move |db| {
+        db.conversations()?.batch_save_conversation_messages(&session_id, messages)
+            .map_err(|e| format!("Failed to batch save conversation messages: {}", e))
+    }).await;
+    match &result {
+        Ok(_) => println!("✅ Batch messages saved successfully"),
+        Err(e) => println!("❌ {}", e),
     }
+    result
 }
 
 #[command]
-pub fn update_conversation_message(
+pub async fn update_conversation_message(
     app_handle: AppHandle,
     session_id: String,
     message_id: String,
     updates: ConversationMessageUpdate,
 ) -> Result<(), String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => storage.update_conversation_message(&session_id, &message_id, updates)
-            .map_err(|e| format!("Failed to update conversation message: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
-    }
+    worker::write(&app_handle, move |db| {
+        db.conversations()?.update_conversation_message(&session_id, &message_id, updates)
+            .map_err(|e| format!("Failed to update conversation message: {}", e))
+    }).await
 }
 
 #[command]
-pub fn delete_conversation_message(
+pub async fn delete_conversation_message(
     app_handle: AppHandle,
     session_id: String,
     message_id: String,
 ) -> Result<(), String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => storage.delete_conversation_message(&session_id, &message_id)
-            .map_err(|e| format!("Failed to delete conversation message: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
-    }
+    require_unlocked(CommandGroup::DataDeletion)?;
+    worker::write(&app_handle, move |db| {
+        db.conversations()?.delete_conversation_message(&session_id, &message_id)
+            .map_err(|e| format!("Failed to delete conversation message: {}", e))
+    }).await
 }
 
 // Insight operations
 #[command]
-pub fn save_conversation_insight(
+pub async fn save_conversation_insight(
     app_handle: AppHandle,
     session_id: String,
     insight: ConversationInsight,
 ) -> Result<(), String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => storage.save_conversation_insight(&session_id, insight)
-            .map_err(|e| format!("Failed to save conversation insight: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
-    }
+    worker::write(&app_handle, move |db| {
+        db.conversations()?.save_conversation_insight(&session_id, insight)
+            .map_err(|e| format!("Failed to save conversation insight: {}", e))
+    }).await
 }
 
 #[command]
-pub fn get_conversation_insights(
+pub async fn get_conversation_insights(
     app_handle: AppHandle,
     session_id: String,
 ) -> Result<Vec<ConversationInsight>, String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(storage) => storage.get_conversation_insights(&session_id)
-            .map_err(|e| format!("Failed to get conversation insights: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
-    }
+    worker::read(&app_handle, move |db| {
+        db.conversations()?.get_conversation_insights(&session_id)
+            .map_err(|e| format!("Failed to get conversation insights: {}", e))
+    }).await
 }
 
 // Session metadata operations  
 #[command]
-pub fn update_session_metadata(
+pub async fn update_session_metadata(
     app_handle: AppHandle,
     session_id: String,
     name: Option<String>,
     end_time: Option<Option<i64>>,
     is_active: Option<bool>,
 ) -> Result<(), String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => {
-            let name_ref = name.as_deref();
-            storage.update_session_metadata(&session_id, name_ref, end_time, is_active)
-                .map_err(|e| format!("Failed to update session metadata: {}", e))
-        }
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
+    // Batched transcription time belongs to the meeting that is about to close
+    if is_active == Some(false) {
+        super::usage::flush_pending_usage(&app_handle);
     }
+    worker::write(&app_handle, move |db| {
+        db.conversations()?.update_session_metadata(&session_id, name.as_deref(), end_time, is_active)
+            .map_err(|e| format!("Failed to update session metadata: {}", e))
+    }).await
 }
 
 #[command]
-pub fn update_session_active_state(
+pub async fn update_session_active_state(
     app_handle: AppHandle,
     session_id: String,
     is_active: bool,
 ) -> Result<(), String> {
-    match ConversationStorage::new(&app_handle) {
-        Ok(mut storage) => storage.update_session_active_state(&session_id, is_active)
-            .map_err(|e| format!("Failed to update session active state: {}", e)),
-        Err(e) => Err(format!("Failed to initialize conversation storage: {}", e))
+    if !is_active {
+        super::usage::flush_pending_usage(&app_handle);
     }
+    worker::write(&app_handle, move |db| {
+        db.conversations()?.update_session_active_state(&session_id, is_active)
+            .map_err(|e| format!("Failed to update session active state: {}", e))
+    }).await
 }
 
 #[command]
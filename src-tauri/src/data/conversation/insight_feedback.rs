@@ -0,0 +1,178 @@
+// Thumbs up/down on conversation insights, fed back into the insights prompt
+//
+// Ratings keep a copy of the insight's text, so a regenerated or edited insight doesn't change
+// what the user actually rated. They go away with their meeting.
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use crate::command;
+use crate::data::worker;
+
+// Examples of each kind given to the model; older ratings still count, just not in the prompt
+const PROMPT_EXAMPLES: usize = 5;
+const MAX_EXAMPLE_CHARS: usize = 280;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InsightRating {
+    Up,
+    Down,
+}
+
+impl InsightRating {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+        }
+    }
+
+    pub fn parse(rating: &str) -> Option<Self> {
+        match rating {
+            "up" => Some(Self::Up),
+            "down" => Some(Self::Down),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsightFeedback {
+    pub insight_id: String,
+    pub session_id: String,
+    pub rating: InsightRating,
+    pub insight_text: String,
+    pub rated_at: i64,
+}
+
+// One line per example, trimmed so a long insight can't crowd out the conversation
+fn example_line(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > MAX_EXAMPLE_CHARS {
+        let cut: String = text.chars().take(MAX_EXAMPLE_CHARS).collect();
+        format!("- {}…", cut.trim_end())
+    } else {
+        format!("- {}", text)
+    }
+}
+
+/// System prompt section built from the most recent ratings, or `None` before the first one
+pub fn describe_feedback_for_prompt(feedback: &[InsightFeedback]) -> Option<String> {
+    let examples = |rating: InsightRating| -> Vec<String> {
+        feedback
+            .iter()
+            .filter(|entry| entry.rating == rating)
+            .take(PROMPT_EXAMPLES)
+            .map(|entry| example_line(&entry.insight_text))
+            .collect()
+    };
+    let liked = examples(InsightRating::Up);
+    let disliked = examples(InsightRating::Down);
+    if liked.is_empty() && disliked.is_empty() {
+        return None;
+    }
+
+    let mut section = String::from("## USER FEEDBACK\n");
+    if !liked.is_empty() {
+        section.push_str("The user found insights like these helpful; match their focus and tone:\n");
+        section.push_str(&liked.join("\n"));
+        section.push('\n');
+    }
+    if !disliked.is_empty() {
+        if !liked.is_empty() {
+            section.push('\n');
+        }
+        section.push_str("The user marked insights like these unhelpful; avoid similar content and phrasing:\n");
+        section.push_str(&disliked.join("\n"));
+        section.push('\n');
+    }
+    Some(section.trim_end().to_string())
+}
+
+/// The feedback section for the next insights prompt; errors just leave it out
+pub async fn feedback_prompt_section(app_handle: &AppHandle) -> Option<String> {
+    let feedback = worker::read(app_handle, |db| {
+        db.conversations()?
+            .load_recent_insight_feedback(PROMPT_EXAMPLES)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .unwrap_or_else(|e| {
+        eprintln!("[INSIGHTS] Failed to read insight feedback: {}", e);
+        Vec::new()
+    });
+    describe_feedback_for_prompt(&feedback)
+}
+
+/// Rate a saved insight; `None` clears an earlier rating
+#[command]
+pub async fn rate_conversation_insight(
+    app_handle: AppHandle,
+    session_id: String,
+    insight_id: String,
+    rating: Option<InsightRating>,
+) -> Result<(), String> {
+    worker::write(&app_handle, move |db| {
+        let storage = db.conversations()?;
+        match rating {
+            Some(rating) => {
+                let saved = storage
+                    .save_insight_feedback(&session_id, &insight_id, rating)
+                    .map_err(|e| format!("Failed to save insight feedback: {}", e))?;
+                if !saved {
+                    return Err(format!("Insight {} not found in session {}", insight_id, session_id));
+                }
+                Ok(())
+            }
+            None => storage
+                .delete_insight_feedback(&insight_id)
+                .map_err(|e| format!("Failed to clear insight feedback: {}", e)),
+        }
+    }).await
+}
+
+#[command]
+pub async fn get_insight_feedback(
+    app_handle: AppHandle,
+    session_id: String,
+) -> Result<Vec<InsightFeedback>, String> {
+    worker::read(&app_handle, move |db| {
+        db.conversations()?
+            .get_session_insight_feedback(&session_id)
+            .map_err(|e| format!("Failed to get insight feedback: {}", e))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feedback(rating: InsightRating, text: &str) -> InsightFeedback {
+        InsightFeedback {
+            insight_id: text.to_string(),
+            session_id: "meeting-1".to_string(),
+            rating,
+            insight_text: text.to_string(),
+            rated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_prompt_section_lists_liked_and_disliked_examples() {
+        assert_eq!(describe_feedback_for_prompt(&[]), None);
+
+        let entries = vec![
+            feedback(InsightRating::Up, "Ask   which\nrollout date\nthey committed to"),
+            feedback(InsightRating::Down, "Great meeting so far!"),
+            feedback(InsightRating::Up, &"x".repeat(400)),
+        ];
+        let section = describe_feedback_for_prompt(&entries).unwrap();
+        assert!(section.starts_with("## USER FEEDBACK\nThe user found insights like these helpful"));
+        assert!(section.contains("- Ask which rollout date they committed to\n"));
+        assert!(section.contains(&format!("- {}…", "x".repeat(MAX_EXAMPLE_CHARS))));
+        assert!(section.ends_with("avoid similar content and phrasing:\n- Great meeting so far!"));
+
+        let only_disliked = describe_feedback_for_prompt(&entries[1..2]).unwrap();
+        assert!(!only_disliked.contains("helpful"));
+    }
+}
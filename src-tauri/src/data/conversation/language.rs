@@ -0,0 +1,159 @@
+// Spoken languages declared per conversation, shared by transcription, analysis and prompts
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use crate::command;
+use crate::data::worker;
+
+// Loopback transcription asks for the active conversation's languages on every chunk
+const ACTIVE_LANGUAGES_TTL: Duration = Duration::from_secs(5);
+
+lazy_static::lazy_static! {
+    static ref ACTIVE_LANGUAGES_CACHE: Mutex<Option<(Instant, Vec<String>)>> = Mutex::new(None);
+}
+
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("en", "English"), ("es", "Spanish"), ("fr", "French"), ("de", "German"), ("it", "Italian"),
+    ("pt", "Portuguese"), ("nl", "Dutch"), ("sv", "Swedish"), ("no", "Norwegian"), ("da", "Danish"),
+    ("fi", "Finnish"), ("pl", "Polish"), ("cs", "Czech"), ("uk", "Ukrainian"), ("ru", "Russian"),
+    ("tr", "Turkish"), ("el", "Greek"), ("he", "Hebrew"), ("ar", "Arabic"), ("fa", "Persian"),
+    ("hi", "Hindi"), ("bn", "Bengali"), ("ur", "Urdu"), ("ta", "Tamil"), ("th", "Thai"),
+    ("vi", "Vietnamese"), ("id", "Indonesian"), ("ms", "Malay"), ("tl", "Tagalog"),
+    ("zh", "Chinese"), ("ja", "Japanese"), ("ko", "Korean"),
+];
+
+// Scripts written without spaces between words
+const UNSPACED_LANGUAGES: &[&str] = &["zh", "ja", "th", "lo", "km", "my"];
+
+pub fn language_name(code: &str) -> &str {
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(_, name)| *name)
+        .unwrap_or(code)
+}
+
+/// Code for an English language name ("english" -> "en"), as some APIs report names
+pub fn language_code(name: &str) -> Option<&'static str> {
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(_, known)| known.eq_ignore_ascii_case(name))
+        .map(|(code, _)| *code)
+}
+
+/// Lowercase, de-duplicate and validate Whisper-style language codes ("en", "es", "haw")
+pub fn normalize_languages(languages: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for language in languages {
+        let code = language.trim().to_lowercase();
+        if code.is_empty() || code == "auto" {
+            continue;
+        }
+        if !(2..=3).contains(&code.len()) || !code.chars().all(|c| c.is_ascii_lowercase()) {
+            return Err(format!("'{}' is not a language code like \"en\" or \"es\"", language));
+        }
+        if !normalized.contains(&code) {
+            normalized.push(code);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Whether word-based text analysis should count characters instead
+pub fn uses_unspaced_script(languages: &[String]) -> bool {
+    languages.iter().any(|code| UNSPACED_LANGUAGES.contains(&code.as_str()))
+}
+
+/// A sentence for the model's system prompt, or `None` when the conversation didn't declare any
+pub fn describe_for_prompt(languages: &[String]) -> Option<String> {
+    match languages {
+        [] => None,
+        [only] => Some(format!(
+            "The conversation is held in {}. Write your analysis in {}.",
+            language_name(only),
+            language_name(only)
+        )),
+        [primary, ..] => Some(format!(
+            "The conversation switches between {}. Quote and suggest phrasing in the language \
+             the speakers are currently using, and write the rest of your analysis in {}.",
+            languages.iter().map(|code| language_name(code)).collect::<Vec<_>>().join(", "),
+            language_name(primary)
+        )),
+    }
+}
+
+/// Languages of the conversation currently being recorded, cached briefly
+pub async fn active_conversation_languages(app_handle: &AppHandle) -> Vec<String> {
+    if let Ok(cache) = ACTIVE_LANGUAGES_CACHE.lock() {
+        if let Some((at, languages)) = cache.as_ref() {
+            if at.elapsed() < ACTIVE_LANGUAGES_TTL {
+                return languages.clone();
+            }
+        }
+    }
+
+    let languages = worker::read(app_handle, |db| {
+        let storage = db.conversations()?;
+        let languages = match storage.active_session_id().map_err(|e| e.to_string())? {
+            Some(session_id) => storage.get_session_languages(&session_id).map_err(|e| e.to_string())?,
+            None => Vec::new(),
+        };
+        Ok(languages)
+    })
+    .await
+    .unwrap_or_else(|e| {
+        eprintln!("[LANGUAGE] Failed to read conversation languages: {}", e);
+        Vec::new()
+    });
+
+    if let Ok(mut cache) = ACTIVE_LANGUAGES_CACHE.lock() {
+        *cache = Some((Instant::now(), languages.clone()));
+    }
+    languages
+}
+
+#[command]
+pub async fn set_conversation_languages(
+    app_handle: AppHandle,
+    session_id: String,
+    languages: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let languages = normalize_languages(languages)?;
+    let stored = languages.clone();
+    worker::write(&app_handle, move |db| {
+        db.conversations()?
+            .update_session_languages(&session_id, &stored)
+            .map_err(|e| format!("Failed to update conversation languages: {}", e))
+    }).await?;
+
+    if let Ok(mut cache) = ACTIVE_LANGUAGES_CACHE.lock() {
+        *cache = None;
+    }
+    Ok(languages)
+}
+
+#[command]
+pub async fn get_conversation_languages(app_handle: AppHandle, session_id: String) -> Result<Vec<String>, String> {
+    worker::read(&app_handle, move |db| {
+        db.conversations()?
+            .get_session_languages(&session_id)
+            .map_err(|e| format!("Failed to load conversation languages: {}", e))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_describe_languages() {
+        let languages = normalize_languages(vec![" EN ".into(), "es".into(), "en".into(), "auto".into()]).unwrap();
+        assert_eq!(languages, vec!["en", "es"]);
+        assert!(normalize_languages(vec!["english".into()]).is_err());
+
+        let prompt = describe_for_prompt(&languages).unwrap();
+        assert!(prompt.contains("English, Spanish"));
+        assert_eq!(describe_for_prompt(&[]), None);
+        assert!(uses_unspaced_script(&["en".into(), "ja".into()]));
+    }
+}
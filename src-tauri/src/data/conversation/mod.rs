@@ -2,7 +2,21 @@
 
 pub mod storage;
 pub mod commands;
+pub mod replay;
+pub mod usage;
+pub mod language;
+pub mod usage_stats;
+pub mod token_ledger;
+pub mod insight_feedback;
+pub mod speaking_metrics;
 
 // Re-export the main functionality
 pub use storage::*;
-pub use commands::*;
\ No newline at end of file
+pub use commands::*;
+pub use replay::*;
+pub use usage::*;
+pub use language::*;
+pub use usage_stats::*;
+pub use token_ledger::*;
+pub use insight_feedback::*;
+pub use speaking_metrics::*;
\ No newline at end of file
@@ -0,0 +1,359 @@
+// Conversation replay - re-emits a stored conversation's messages and insights in their original timing
+use tauri::{AppHandle, Emitter, Manager};
+use crate::command;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use crate::data::types::{ConversationMessage, ConversationInsight, ConversationSession};
+use crate::data::worker;
+
+// Upper bound on a single sleep so pause/seek/stop are picked up promptly
+const REPLAY_TICK: Duration = Duration::from_millis(100);
+// How often position updates are emitted for the scrubber
+const POSITION_INTERVAL: Duration = Duration::from_millis(500);
+const MIN_SPEED: f64 = 0.1;
+const MAX_SPEED: f64 = 16.0;
+
+lazy_static::lazy_static! {
+    static ref ACTIVE_REPLAYS: Mutex<HashMap<String, Arc<ReplayControl>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ReplayItem {
+    Message { message: ConversationMessage },
+    Insight { insight: ConversationInsight },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayEvent {
+    pub offset_ms: i64,
+    /// Position in the archived recording, when one exists
+    pub audio_offset_ms: Option<i64>,
+    #[serde(flatten)]
+    pub item: ReplayItem,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayInfo {
+    pub replay_id: String,
+    pub session_id: String,
+    pub session_name: String,
+    pub duration_ms: i64,
+    pub event_count: usize,
+    pub audio_path: Option<String>,
+    pub speed: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ReplayPayload {
+    Start { info: ReplayInfo },
+    Event { event: ReplayEvent },
+    Position { position_ms: i64, duration_ms: i64, paused: bool },
+    /// Everything that happened before the new position, so the UI can rebuild its view
+    Seek { position_ms: i64, events: Vec<ReplayEvent> },
+    End { position_ms: i64, stopped: bool },
+}
+
+#[derive(Debug)]
+struct ReplayCommands {
+    paused: bool,
+    stopped: bool,
+    speed: f64,
+    seek_to: Option<i64>,
+}
+
+struct ReplayControl {
+    commands: Mutex<ReplayCommands>,
+    wake: Notify,
+}
+
+impl ReplayControl {
+    fn update(&self, f: impl FnOnce(&mut ReplayCommands)) {
+        if let Ok(mut commands) = self.commands.lock() {
+            f(&mut commands);
+        }
+        self.wake.notify_one();
+    }
+}
+
+fn clamp_speed(speed: f64) -> f64 {
+    if speed.is_finite() { speed.clamp(MIN_SPEED, MAX_SPEED) } else { 1.0 }
+}
+
+/// Build the ordered timeline for a session, relative to its start time.
+/// `audio_origin` is the wall-clock time the recording started, when there is one.
+pub fn build_timeline(session: &ConversationSession, audio_origin: Option<i64>) -> Vec<ReplayEvent> {
+    let origin = session.start_time;
+    let offset = |timestamp: i64| (timestamp - origin).max(0);
+    let audio_offset = |timestamp: i64| audio_origin.map(|start| (timestamp - start).max(0));
+
+    let mut events: Vec<ReplayEvent> = session.messages.iter()
+        .filter(|message| message.is_preview != Some(true))
+        .map(|message| ReplayEvent {
+            offset_ms: offset(message.timestamp),
+            audio_offset_ms: audio_offset(message.timestamp),
+            item: ReplayItem::Message { message: message.clone() },
+        })
+        .chain(session.insights.iter().map(|insight| ReplayEvent {
+            offset_ms: offset(insight.timestamp),
+            audio_offset_ms: audio_offset(insight.timestamp),
+            item: ReplayItem::Insight { insight: insight.clone() },
+        }))
+        .collect();
+
+    // Stable sort keeps messages ahead of insights that share a timestamp
+    events.sort_by_key(|event| event.offset_ms);
+    events
+}
+
+/// Locate archived audio and the wall-clock time it starts at. Segmented session
+/// recordings are referenced by their manifest; single-file archives by the WAV itself.
+fn audio_archive(app_handle: &AppHandle, session: &ConversationSession) -> Option<(PathBuf, i64)> {
+    if let Ok(dir) = crate::audio_loopback::session_recorder::session_recording_dir(app_handle, &session.id) {
+        if let Ok(Some(manifest)) = crate::audio_loopback::session_recorder::read_manifest(&dir) {
+            return Some((dir.join("manifest.json"), manifest.started_at));
+        }
+    }
+    let path = app_handle.path().app_data_dir().ok()?
+        .join("recordings")
+        .join(format!("{}.wav", session.id));
+    path.exists().then_some((path, session.start_time))
+}
+
+/// Start replaying a stored conversation. Events are emitted on `conversation-replay-{replay_id}`.
+#[command]
+pub async fn start_conversation_replay(
+    app_handle: AppHandle,
+    session_id: String,
+    speed: Option<f64>,
+    start_offset_ms: Option<i64>,
+) -> Result<ReplayInfo, String> {
+    let id = session_id.clone();
+    let session = worker::read(&app_handle, move |db| {
+        db.conversations()?
+            .load_conversation(&id)
+            .map_err(|e| format!("Failed to load conversation: {}", e))
+    }).await?
+    .ok_or_else(|| format!("Conversation {} not found", session_id))?;
+
+    let audio = audio_archive(&app_handle, &session);
+    let timeline = build_timeline(&session, audio.as_ref().map(|(_, start)| *start));
+    let last_event_ms = timeline.last().map(|event| event.offset_ms).unwrap_or(0);
+    let duration_ms = session.end_time
+        .map(|end| (end - session.start_time).max(0))
+        .unwrap_or(0)
+        .max(last_event_ms);
+
+    let speed = clamp_speed(speed.unwrap_or(1.0));
+    let info = ReplayInfo {
+        replay_id: uuid::Uuid::new_v4().to_string(),
+        session_id,
+        session_name: session.name,
+        duration_ms,
+        event_count: timeline.len(),
+        audio_path: audio.map(|(path, _)| path.to_string_lossy().to_string()),
+        speed,
+    };
+
+    let control = Arc::new(ReplayControl {
+        commands: Mutex::new(ReplayCommands {
+            paused: false,
+            stopped: false,
+            speed,
+            seek_to: start_offset_ms.filter(|offset| *offset > 0),
+        }),
+        wake: Notify::new(),
+    });
+    ACTIVE_REPLAYS.lock()
+        .map_err(|_| "Failed to register replay".to_string())?
+        .insert(info.replay_id.clone(), control.clone());
+
+    let replay_info = info.clone();
+    tauri::async_runtime::spawn(async move {
+        run_replay(app_handle, replay_info, timeline, control).await;
+    });
+
+    Ok(info)
+}
+
+async fn run_replay(app_handle: AppHandle, info: ReplayInfo, timeline: Vec<ReplayEvent>, control: Arc<ReplayControl>) {
+    let event_name = format!("conversation-replay-{}", info.replay_id);
+    let emit = |payload: ReplayPayload| {
+        let _ = app_handle.emit(&event_name, payload);
+    };
+
+    emit(ReplayPayload::Start { info: info.clone() });
+
+    let mut position_ms: f64 = 0.0;
+    let mut next_index = 0usize;
+    let mut last_tick = Instant::now();
+    let mut last_position_emit = Instant::now();
+    let mut stopped = false;
+
+    loop {
+        let (paused, speed, seek_to) = {
+            let mut commands = match control.commands.lock() {
+                Ok(commands) => commands,
+                Err(_) => break,
+            };
+            if commands.stopped {
+                stopped = true;
+                break;
+            }
+            (commands.paused, commands.speed, commands.seek_to.take())
+        };
+
+        let now = Instant::now();
+        if !paused {
+            position_ms += now.duration_since(last_tick).as_secs_f64() * 1000.0 * speed;
+        }
+        last_tick = now;
+
+        if let Some(target) = seek_to {
+            let target = target.clamp(0, info.duration_ms);
+            position_ms = target as f64;
+            next_index = timeline.partition_point(|event| event.offset_ms <= target);
+            emit(ReplayPayload::Seek {
+                position_ms: target,
+                events: timeline[..next_index].to_vec(),
+            });
+        }
+
+        while next_index < timeline.len() && timeline[next_index].offset_ms as f64 <= position_ms {
+            emit(ReplayPayload::Event { event: timeline[next_index].clone() });
+            next_index += 1;
+        }
+
+        if next_index >= timeline.len() && position_ms >= info.duration_ms as f64 {
+            break;
+        }
+
+        if last_position_emit.elapsed() >= POSITION_INTERVAL {
+            emit(ReplayPayload::Position {
+                position_ms: position_ms as i64,
+                duration_ms: info.duration_ms,
+                paused,
+            });
+            last_position_emit = Instant::now();
+        }
+
+        // Sleep until the next event (or end), but wake early for control changes
+        let target_ms = timeline.get(next_index)
+            .map(|event| event.offset_ms as f64)
+            .unwrap_or(info.duration_ms as f64);
+        let wait = if paused {
+            REPLAY_TICK
+        } else {
+            Duration::from_secs_f64(((target_ms - position_ms).max(0.0) / speed) / 1000.0).min(REPLAY_TICK)
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = control.wake.notified() => {}
+        }
+    }
+
+    emit(ReplayPayload::End {
+        position_ms: (position_ms as i64).min(info.duration_ms),
+        stopped,
+    });
+
+    if let Ok(mut replays) = ACTIVE_REPLAYS.lock() {
+        replays.remove(&info.replay_id);
+    }
+}
+
+fn with_replay(replay_id: &str, f: impl FnOnce(&mut ReplayCommands)) -> Result<(), String> {
+    let control = ACTIVE_REPLAYS.lock()
+        .map_err(|_| "Failed to access replays".to_string())?
+        .get(replay_id)
+        .cloned()
+        .ok_or_else(|| format!("Replay {} is not running", replay_id))?;
+    control.update(f);
+    Ok(())
+}
+
+#[command]
+pub fn pause_conversation_replay(replay_id: String) -> Result<(), String> {
+    with_replay(&replay_id, |commands| commands.paused = true)
+}
+
+#[command]
+pub fn resume_conversation_replay(replay_id: String) -> Result<(), String> {
+    with_replay(&replay_id, |commands| commands.paused = false)
+}
+
+#[command]
+pub fn seek_conversation_replay(replay_id: String, position_ms: i64) -> Result<(), String> {
+    with_replay(&replay_id, |commands| commands.seek_to = Some(position_ms))
+}
+
+#[command]
+pub fn set_conversation_replay_speed(replay_id: String, speed: f64) -> Result<(), String> {
+    with_replay(&replay_id, |commands| commands.speed = clamp_speed(speed))
+}
+
+#[command]
+pub fn stop_conversation_replay(replay_id: String) -> Result<(), String> {
+    with_replay(&replay_id, |commands| commands.stopped = true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, timestamp: i64, is_preview: Option<bool>) -> ConversationMessage {
+        ConversationMessage {
+            id: id.to_string(),
+            message_type: "user".to_string(),
+            source: "microphone".to_string(),
+            content: id.to_string(),
+            timestamp,
+            confidence: None,
+            is_preview,
+            is_typing: None,
+            persistence_state: None,
+            retry_count: None,
+            last_save_attempt: None,
+            save_error: None,
+            timings: None,
+        }
+    }
+
+    #[test]
+    fn test_timeline_orders_relative_to_session_start() {
+        let session = ConversationSession {
+            id: "session".to_string(),
+            name: "Standup".to_string(),
+            start_time: 1_000,
+            end_time: Some(10_000),
+            messages: vec![message("late", 5_000, None), message("early", 2_000, None), message("preview", 3_000, Some(true))],
+            is_active: false,
+            insights: vec![ConversationInsight {
+                id: "insight".to_string(),
+                text: "Summary".to_string(),
+                timestamp: 2_000,
+                context_length: 1,
+                insight_type: "insight".to_string(),
+            }],
+            languages: Vec::new(),
+        };
+
+        let timeline = build_timeline(&session, None);
+        let offsets: Vec<i64> = timeline.iter().map(|event| event.offset_ms).collect();
+        assert_eq!(offsets, vec![1_000, 1_000, 4_000]);
+        assert!(matches!(&timeline[0].item, ReplayItem::Message { message } if message.id == "early"));
+        assert!(matches!(&timeline[1].item, ReplayItem::Insight { .. }));
+        assert!(timeline.iter().all(|event| event.audio_offset_ms.is_none()));
+
+        // Recording started a second after the session
+        let with_audio = build_timeline(&session, Some(2_000));
+        assert_eq!(with_audio[2].audio_offset_ms, Some(3_000));
+    }
+}
@@ -0,0 +1,377 @@
+// Speaking metrics per conversation - talk time, pace, interruptions, filler words and questions
+// for each side, with a few coaching tips for the user
+//
+// The microphone is the user and loopback audio is everyone else. A message is taken to start
+// at its timestamp and to last as long as its word timings say, or as long as its words would
+// take at an average pace when it has none. Results are stored and recomputed only when the
+// conversation has new messages.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+use crate::command;
+use crate::data::types::ConversationMessage;
+use crate::data::worker;
+use super::language::uses_unspaced_script;
+
+// Pace assumed for messages without word timings
+const ESTIMATED_WORDS_PER_MINUTE: f64 = 150.0;
+// Starting this long before the other side finished counts as talking over them
+const INTERRUPTION_OVERLAP_MS: i64 = 300;
+// WPM over less talk time than this says more about rounding than pace
+const MIN_PACE_SAMPLE_MS: i64 = 30_000;
+const TOP_FILLERS: usize = 3;
+
+const FILLER_WORDS: &[&str] = &["um", "umm", "uh", "uhm", "erm", "er", "ah", "hmm", "basically", "literally"];
+const FILLER_PHRASES: &[(&str, &str)] = &[("you", "know"), ("i", "mean"), ("sort", "of"), ("kind", "of")];
+
+// Coaching thresholds
+const HIGH_TALK_SHARE: f64 = 0.65;
+const FAST_PACE_WPM: f64 = 170.0;
+const SLOW_PACE_WPM: f64 = 110.0;
+const HIGH_FILLERS_PER_100: f64 = 3.0;
+const FREQUENT_INTERRUPTIONS: usize = 3;
+const QUESTIONLESS_WORDS: usize = 150;
+const LONG_TURN_MS: i64 = 3 * 60_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Speaker {
+    /// The microphone
+    You,
+    /// Loopback audio
+    Others,
+}
+
+impl Speaker {
+    pub(crate) fn of(message: &ConversationMessage) -> Self {
+        match message.source.as_str() {
+            "microphone" => Speaker::You,
+            "loopback" => Speaker::Others,
+            _ if message.message_type == "user" => Speaker::You,
+            _ => Speaker::Others,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FillerCount {
+    pub filler: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerMetrics {
+    pub speaker: Speaker,
+    pub message_count: usize,
+    /// Characters rather than words for languages written without spaces
+    pub word_count: usize,
+    pub talk_time_ms: i64,
+    /// Of both sides' talk time, 0.0-1.0
+    pub talk_share: f64,
+    /// `None` with too little talk time to tell
+    pub words_per_minute: Option<f64>,
+    /// Part of the talk time came from the average pace rather than word timings
+    pub estimated: bool,
+    /// Times this side started talking over the other
+    pub interruptions: usize,
+    pub questions: usize,
+    pub filler_count: usize,
+    pub fillers_per_100_words: f64,
+    pub top_fillers: Vec<FillerCount>,
+    pub longest_turn_ms: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakingMetrics {
+    pub session_id: String,
+    pub computed_at: i64,
+    /// Messages the metrics cover; they're recomputed when this changes
+    pub message_count: usize,
+    pub last_message_at: Option<i64>,
+    pub speakers: Vec<SpeakerMetrics>,
+    pub tips: Vec<String>,
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn filler_counts(words: &[String]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    let mut index = 0;
+    while index < words.len() {
+        let phrase = words.get(index + 1).and_then(|next| {
+            FILLER_PHRASES
+                .iter()
+                .find(|(first, second)| words[index] == *first && next == second)
+        });
+        if let Some((first, second)) = phrase {
+            *counts.entry(format!("{} {}", first, second)).or_insert(0) += 1;
+            index += 2;
+            continue;
+        }
+        if FILLER_WORDS.contains(&words[index].as_str()) {
+            *counts.entry(words[index].clone()).or_insert(0) += 1;
+        }
+        index += 1;
+    }
+    counts
+}
+
+fn question_count(text: &str) -> usize {
+    text.split_inclusive(['.', '!', '?'])
+        .filter(|sentence| sentence.trim().len() > 1 && sentence.trim_end().ends_with('?'))
+        .count()
+}
+
+// How long a message lasted and whether that was estimated
+fn message_duration_ms(message: &ConversationMessage, word_count: usize) -> (i64, bool) {
+    let timed = message.timings.as_ref().and_then(|timings| {
+        let start = timings.segments.first()?.start;
+        let end = timings.segments.last()?.end;
+        (end > start).then(|| ((end - start) * 1000.0) as i64)
+    });
+    match timed {
+        Some(duration) => (duration, false),
+        None => ((word_count as f64 / ESTIMATED_WORDS_PER_MINUTE * 60_000.0) as i64, true),
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    messages: usize,
+    words: usize,
+    talk_time_ms: i64,
+    estimated: bool,
+    interruptions: usize,
+    questions: usize,
+    fillers: HashMap<String, usize>,
+    longest_turn_ms: i64,
+}
+
+/// Metrics for a conversation's messages; `unspaced` counts characters instead of words
+pub fn compute_speaking_metrics(session_id: &str, messages: &[ConversationMessage], unspaced: bool) -> SpeakingMetrics {
+    let mut ordered: Vec<&ConversationMessage> = messages
+        .iter()
+        .filter(|message| message.is_preview != Some(true) && !message.content.trim().is_empty())
+        .collect();
+    ordered.sort_by_key(|message| message.timestamp);
+
+    let mut tallies: HashMap<Speaker, Tally> = HashMap::new();
+    // (speaker, start, end) of the previous message and of the current turn
+    let mut previous: Option<(Speaker, i64)> = None;
+    let mut turn: Option<(Speaker, i64, i64)> = None;
+    for message in &ordered {
+        let speaker = Speaker::of(message);
+        let message_words = words(&message.content);
+        let word_count = if unspaced {
+            message.content.chars().filter(|c| c.is_alphanumeric()).count()
+        } else {
+            message_words.len()
+        };
+        let (duration, estimated) = message_duration_ms(message, word_count);
+        let (start, end) = (message.timestamp, message.timestamp + duration);
+
+        let tally = tallies.entry(speaker).or_default();
+        tally.messages += 1;
+        tally.words += word_count;
+        tally.talk_time_ms += duration;
+        tally.estimated |= estimated;
+        tally.questions += question_count(&message.content);
+        if !unspaced {
+            for (filler, count) in filler_counts(&message_words) {
+                *tally.fillers.entry(filler).or_insert(0) += count;
+            }
+        }
+        if let Some((other, other_end)) = previous {
+            if other != speaker && start < other_end - INTERRUPTION_OVERLAP_MS {
+                tally.interruptions += 1;
+            }
+        }
+        previous = Some((speaker, end));
+
+        turn = match turn {
+            Some((current, turn_start, turn_end)) if current == speaker => Some((current, turn_start, turn_end.max(end))),
+            _ => Some((speaker, start, end)),
+        };
+        if let Some((_, turn_start, turn_end)) = turn {
+            tally.longest_turn_ms = tally.longest_turn_ms.max(turn_end - turn_start);
+        }
+    }
+
+    let total_talk: i64 = tallies.values().map(|tally| tally.talk_time_ms).sum();
+    let mut speakers: Vec<SpeakerMetrics> = [Speaker::You, Speaker::Others]
+        .iter()
+        .filter_map(|speaker| tallies.remove(speaker).map(|tally| (*speaker, tally)))
+        .map(|(speaker, tally)| {
+            let filler_count: usize = tally.fillers.values().sum();
+            let mut top_fillers: Vec<FillerCount> = tally
+                .fillers
+                .into_iter()
+                .map(|(filler, count)| FillerCount { filler, count })
+                .collect();
+            top_fillers.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.filler.cmp(&b.filler)));
+            top_fillers.truncate(TOP_FILLERS);
+            SpeakerMetrics {
+                speaker,
+                message_count: tally.messages,
+                word_count: tally.words,
+                talk_time_ms: tally.talk_time_ms,
+                talk_share: if total_talk > 0 { tally.talk_time_ms as f64 / total_talk as f64 } else { 0.0 },
+                words_per_minute: (tally.talk_time_ms >= MIN_PACE_SAMPLE_MS)
+                    .then(|| tally.words as f64 / (tally.talk_time_ms as f64 / 60_000.0)),
+                estimated: tally.estimated,
+                interruptions: tally.interruptions,
+                questions: tally.questions,
+                filler_count,
+                fillers_per_100_words: if tally.words > 0 { filler_count as f64 * 100.0 / tally.words as f64 } else { 0.0 },
+                top_fillers,
+                longest_turn_ms: tally.longest_turn_ms,
+            }
+        })
+        .collect();
+    speakers.sort_by_key(|metrics| metrics.speaker != Speaker::You);
+
+    let tips = speakers
+        .iter()
+        .find(|metrics| metrics.speaker == Speaker::You)
+        .map(|you| coaching_tips(you, speakers.len() > 1))
+        .unwrap_or_default();
+    SpeakingMetrics {
+        session_id: session_id.to_string(),
+        computed_at: chrono::Utc::now().timestamp_millis(),
+        message_count: messages.len(),
+        last_message_at: messages.iter().map(|message| message.timestamp).max(),
+        speakers,
+        tips,
+    }
+}
+
+fn coaching_tips(you: &SpeakerMetrics, others_spoke: bool) -> Vec<String> {
+    let mut tips = Vec::new();
+    if others_spoke && you.talk_share > HIGH_TALK_SHARE {
+        tips.push(format!(
+            "You spoke for {:.0}% of the conversation; leaving more room for others invites their input.",
+            you.talk_share * 100.0
+        ));
+    }
+    match you.words_per_minute {
+        Some(wpm) if wpm > FAST_PACE_WPM => tips.push(format!(
+            "Your pace averaged {:.0} words per minute; slowing down helps listeners keep up.",
+            wpm
+        )),
+        Some(wpm) if wpm < SLOW_PACE_WPM => tips.push(format!(
+            "Your pace averaged {:.0} words per minute; a little more energy keeps attention.",
+            wpm
+        )),
+        _ => {}
+    }
+    if you.fillers_per_100_words > HIGH_FILLERS_PER_100 {
+        if let Some(top) = you.top_fillers.first() {
+            tips.push(format!(
+                "You used {} filler words ({:.1} per 100 words), most often \"{}\"; a short pause works better.",
+                you.filler_count, you.fillers_per_100_words, top.filler
+            ));
+        }
+    }
+    if you.interruptions >= FREQUENT_INTERRUPTIONS {
+        tips.push(format!("You started talking over the other side {} times.", you.interruptions));
+    }
+    if others_spoke && you.questions == 0 && you.word_count >= QUESTIONLESS_WORDS {
+        tips.push("You didn't ask any questions; asking keeps the other side engaged.".to_string());
+    }
+    if you.longest_turn_ms > LONG_TURN_MS {
+        tips.push(format!(
+            "Your longest stretch ran {:.1} minutes; check in with the others more often.",
+            you.longest_turn_ms as f64 / 60_000.0
+        ));
+    }
+    tips
+}
+
+/// Speaking metrics for a conversation, computed on first request and again after new messages
+#[command]
+pub async fn get_speaking_metrics(app_handle: AppHandle, session_id: String) -> Result<SpeakingMetrics, String> {
+    worker::write(&app_handle, move |db| {
+        let storage = db.conversations()?;
+        let session = storage
+            .load_conversation(&session_id)
+            .map_err(|e| format!("Failed to load conversation: {}", e))?
+            .ok_or_else(|| format!("Conversation {} not found", session_id))?;
+        let last_message_at = session.messages.iter().map(|message| message.timestamp).max();
+        let stored = storage
+            .load_speaking_metrics(&session_id)
+            .map_err(|e| format!("Failed to load speaking metrics: {}", e))?;
+        if let Some(stored) = stored {
+            if stored.message_count == session.messages.len() && stored.last_message_at == last_message_at {
+                return Ok(stored);
+            }
+        }
+
+        let metrics = compute_speaking_metrics(&session_id, &session.messages, uses_unspaced_script(&session.languages));
+        storage
+            .save_speaking_metrics(&metrics)
+            .map_err(|e| format!("Failed to save speaking metrics: {}", e))?;
+        Ok(metrics)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(source: &str, content: &str, timestamp: i64) -> ConversationMessage {
+        ConversationMessage {
+            id: format!("{}-{}", source, timestamp),
+            message_type: if source == "microphone" { "user" } else { "system" }.to_string(),
+            source: source.to_string(),
+            content: content.to_string(),
+            timestamp,
+            confidence: None,
+            is_preview: None,
+            is_typing: None,
+            persistence_state: None,
+            retry_count: None,
+            last_save_attempt: None,
+            save_error: None,
+            timings: None,
+        }
+    }
+
+    #[test]
+    fn test_metrics_count_talk_time_interruptions_fillers_and_questions() {
+        // 30 words at the estimated pace take 12 seconds
+        let thirty_words = "word ".repeat(30);
+        let messages = vec![
+            message("loopback", &thirty_words, 0),
+            // Starts 4s before the other side is done
+            message("microphone", "Um, so, you know, what's the plan? And when? Um.", 8_000),
+            message("microphone", &thirty_words, 20_000),
+        ];
+        let metrics = compute_speaking_metrics("meeting-1", &messages, false);
+        assert_eq!(metrics.message_count, 3);
+        assert_eq!(metrics.last_message_at, Some(20_000));
+
+        let you = &metrics.speakers[0];
+        assert_eq!(you.speaker, Speaker::You);
+        assert_eq!(you.message_count, 2);
+        assert_eq!(you.interruptions, 1);
+        assert_eq!(you.questions, 2);
+        assert_eq!(you.filler_count, 3);
+        assert_eq!(you.top_fillers[0], FillerCount { filler: "um".to_string(), count: 2 });
+        assert!(you.estimated);
+        // Both of the user's messages form one turn
+        assert!(you.longest_turn_ms > 12_000);
+
+        let others = &metrics.speakers[1];
+        assert_eq!(others.talk_time_ms, 12_000);
+        assert_eq!(others.interruptions, 0);
+        assert!((you.talk_share + others.talk_share - 1.0).abs() < 1e-9);
+    }
+}
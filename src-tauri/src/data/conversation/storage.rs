@@ -1,10 +1,15 @@
 // SQLite storage implementation for conversation sessions
-use rusqlite::{Connection, Result, params};
+use rusqlite::{Connection, OptionalExtension, Result, params};
 use tauri::{AppHandle, Manager};
 use crate::data::types::{
     ConversationSession, ConversationMessage, ConversationInsight, ConversationMessageUpdate,
-    SaveConversationsPayload, LoadConversationsResponse
+    SaveConversationsPayload, LoadConversationsResponse, TranscriptTimings
 };
+use super::usage::ModelUsage;
+use super::usage_stats::{StatKind, UsageStatRow};
+use super::token_ledger::TokenLedgerEntry;
+use super::insight_feedback::{InsightFeedback, InsightRating};
+use super::speaking_metrics::SpeakingMetrics;
 use std::path::PathBuf;
 
 pub struct ConversationStorage {
@@ -72,7 +77,8 @@ impl ConversationStorage {
                 name TEXT NOT NULL,
                 start_time INTEGER NOT NULL,
                 end_time INTEGER,
-                is_active INTEGER NOT NULL CHECK(is_active IN (0, 1))
+                is_active INTEGER NOT NULL CHECK(is_active IN (0, 1)),
+                languages TEXT
             );
 
             -- Conversation messages table
@@ -84,6 +90,7 @@ impl ConversationStorage {
                 content TEXT NOT NULL,
                 timestamp INTEGER NOT NULL,
                 confidence REAL,
+                timings TEXT,
                 FOREIGN KEY (session_id) REFERENCES conversation_sessions(id) ON DELETE CASCADE
             );
 
@@ -98,6 +105,64 @@ impl ConversationStorage {
                 FOREIGN KEY (session_id) REFERENCES conversation_sessions(id) ON DELETE CASCADE
             );
 
+            -- Per-meeting model usage and estimated cost
+            CREATE TABLE IF NOT EXISTS conversation_usage (
+                session_id TEXT NOT NULL,
+                model TEXT NOT NULL,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                requests INTEGER NOT NULL DEFAULT 0,
+                transcription_ms INTEGER NOT NULL DEFAULT 0,
+                estimated_cost REAL NOT NULL DEFAULT 0,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (session_id, model),
+                FOREIGN KEY (session_id) REFERENCES conversation_sessions(id) ON DELETE CASCADE
+            );
+
+            -- Local usage statistics, one row per day and counter
+            CREATE TABLE IF NOT EXISTS usage_stats (
+                day TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                count INTEGER NOT NULL DEFAULT 0,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, kind, name)
+            );
+
+            -- Token ledger, one row per day, chat session, agent and model
+            CREATE TABLE IF NOT EXISTS token_ledger (
+                day TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                agent TEXT NOT NULL,
+                model TEXT NOT NULL,
+                requests INTEGER NOT NULL DEFAULT 0,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                estimated_cost REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, session_id, agent, model)
+            );
+            CREATE INDEX IF NOT EXISTS idx_token_ledger_session ON token_ledger(session_id);
+
+            -- Thumbs up/down per insight, with the text that was rated
+            CREATE TABLE IF NOT EXISTS insight_feedback (
+                insight_id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                rating TEXT NOT NULL CHECK(rating IN ('up', 'down')),
+                insight_text TEXT NOT NULL,
+                rated_at INTEGER NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES conversation_sessions(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_insight_feedback_rating ON insight_feedback(rating, rated_at DESC);
+
+            -- Speaking metrics per conversation, as JSON, with what they were computed from
+            CREATE TABLE IF NOT EXISTS speaking_metrics (
+                session_id TEXT PRIMARY KEY,
+                metrics TEXT NOT NULL,
+                computed_at INTEGER NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES conversation_sessions(id) ON DELETE CASCADE
+            );
+
             -- Indexes for performance
             CREATE INDEX IF NOT EXISTS idx_conversation_sessions_active_start ON conversation_sessions(is_active, start_time DESC);
             CREATE INDEX IF NOT EXISTS idx_conversation_messages_session_timestamp ON conversation_messages(session_id, timestamp);
@@ -107,6 +172,18 @@ impl ConversationStorage {
             CREATE INDEX IF NOT EXISTS idx_conversation_insights_type ON conversation_insights(insight_type);
         "#)?;
 
+        // Add languages column if it doesn't exist (for existing databases)
+        let _ = self.connection.execute(
+            "ALTER TABLE conversation_sessions ADD COLUMN languages TEXT",
+            params![],
+        );
+
+        // Add word timings column if it doesn't exist (for existing databases)
+        let _ = self.connection.execute(
+            "ALTER TABLE conversation_messages ADD COLUMN timings TEXT",
+            params![],
+        );
+
         println!("✅ Conversation tables initialized successfully");
         Ok(())
     }
@@ -159,20 +236,22 @@ impl ConversationStorage {
         if session_exists {
             // Update existing session metadata only
             tx.execute(
-                "UPDATE conversation_sessions SET name = ?, start_time = ?, end_time = ?, is_active = ? WHERE id = ?",
+                // Languages set through set_conversation_languages survive saves that don't carry them
+                "UPDATE conversation_sessions SET name = ?, start_time = ?, end_time = ?, is_active = ?,
+                 languages = COALESCE(?, languages) WHERE id = ?",
                 params![
                     session.name, session.start_time, session.end_time,
-                    if session.is_active { 1 } else { 0 }, session.id
+                    if session.is_active { 1 } else { 0 }, languages_to_sql(&session.languages), session.id
                 ]
             )?;
             println!("🔄 Updated session metadata: {}", session.id);
         } else {
             // Insert new session
             tx.execute(
-                "INSERT INTO conversation_sessions (id, name, start_time, end_time, is_active) VALUES (?, ?, ?, ?, ?)",
+                "INSERT INTO conversation_sessions (id, name, start_time, end_time, is_active, languages) VALUES (?, ?, ?, ?, ?, ?)",
                 params![
                     session.id, session.name, session.start_time, session.end_time,
-                    if session.is_active { 1 } else { 0 }
+                    if session.is_active { 1 } else { 0 }, languages_to_sql(&session.languages)
                 ]
             )?;
             println!("🆕 Created new session: {}", session.id);
@@ -182,11 +261,11 @@ impl ConversationStorage {
         for message in session.messages {
             // Use INSERT OR IGNORE to avoid conflicts with concurrent individual message saves
             tx.execute(
-                "INSERT OR IGNORE INTO conversation_messages (id, session_id, type, source, content, timestamp, confidence) 
-                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                "INSERT OR IGNORE INTO conversation_messages (id, session_id, type, source, content, timestamp, confidence, timings) 
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
                 params![
                     message.id, session.id, message.message_type, message.source,
-                    message.content, message.timestamp, message.confidence
+                    message.content, message.timestamp, message.confidence, timings_to_sql(&message.timings)
                 ]
             )?;
         }
@@ -273,7 +352,7 @@ impl ConversationStorage {
 
         // Query all sessions
         let mut session_stmt = self.connection.prepare(
-            "SELECT id, name, start_time, end_time, is_active FROM conversation_sessions ORDER BY start_time DESC"
+            "SELECT id, name, start_time, end_time, is_active, languages FROM conversation_sessions ORDER BY start_time DESC"
         )?;
 
         let session_iter = session_stmt.query_map(params![], |row| {
@@ -283,11 +362,12 @@ impl ConversationStorage {
                 row.get::<_, i64>("start_time")?,
                 row.get::<_, Option<i64>>("end_time")?,
                 row.get::<_, i32>("is_active")? != 0,
+                languages_from_sql(row.get::<_, Option<String>>("languages")?),
             ))
         })?;
 
         for session_result in session_iter {
-            let (id, name, start_time, end_time, is_active) = session_result?;
+            let (id, name, start_time, end_time, is_active, languages) = session_result?;
             
             // Load messages and insights for this session
             let messages = self.load_conversation_messages(&id)?;
@@ -301,6 +381,7 @@ impl ConversationStorage {
                 is_active,
                 messages,
                 insights,
+                languages,
             });
         }
 
@@ -312,7 +393,7 @@ impl ConversationStorage {
         let mut messages = Vec::new();
 
         let mut stmt = self.connection.prepare(
-            "SELECT id, type, source, content, timestamp, confidence 
+            "SELECT id, type, source, content, timestamp, confidence, timings 
              FROM conversation_messages WHERE session_id = ? ORDER BY timestamp"
         )?;
 
@@ -331,6 +412,9 @@ impl ConversationStorage {
                 retry_count: None,
                 last_save_attempt: None,
                 save_error: None,
+                timings: row
+                    .get::<_, Option<String>>("timings")?
+                    .and_then(|json| serde_json::from_str(&json).ok()),
             })
         })?;
 
@@ -421,11 +505,11 @@ impl ConversationStorage {
         }
 
         let affected = self.connection.execute(
-            "INSERT INTO conversation_messages (id, session_id, type, source, content, timestamp, confidence) 
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO conversation_messages (id, session_id, type, source, content, timestamp, confidence, timings) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
             params![
                 message.id, session_id, message.message_type, message.source,
-                message.content, message.timestamp, message.confidence
+                message.content, message.timestamp, message.confidence, timings_to_sql(&message.timings)
             ]
         ).map_err(|e| {
             println!("❌ Failed to insert message: {}", e);
@@ -462,11 +546,11 @@ impl ConversationStorage {
 
             if !exists {
                 tx.execute(
-                    "INSERT INTO conversation_messages (id, session_id, type, source, content, timestamp, confidence) 
-                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    "INSERT INTO conversation_messages (id, session_id, type, source, content, timestamp, confidence, timings) 
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
                     params![
                         message.id, session_id, message.message_type, message.source,
-                        message.content, message.timestamp, message.confidence
+                        message.content, message.timestamp, message.confidence, timings_to_sql(&message.timings)
                     ]
                 )?;
                 saved_count += 1;
@@ -496,6 +580,10 @@ impl ConversationStorage {
             set_clauses.push("timestamp = ?");
             sql_params.push(rusqlite::types::Value::Integer(timestamp));
         }
+        if let Some(json) = timings_to_sql(&updates.timings) {
+            set_clauses.push("timings = ?");
+            sql_params.push(rusqlite::types::Value::Text(json));
+        }
 
         if set_clauses.is_empty() {
             return Ok(()); // No updates to apply
@@ -552,6 +640,278 @@ impl ConversationStorage {
         self.load_conversation_insights(session_id)
     }
 
+    /// Rate an insight, copying its current text. `false` when the insight isn't stored.
+    pub fn save_insight_feedback(&mut self, session_id: &str, insight_id: &str, rating: InsightRating) -> Result<bool> {
+        let affected = self.connection.execute(
+            "INSERT INTO insight_feedback (insight_id, session_id, rating, insight_text, rated_at)
+             SELECT id, session_id, ?, text, ? FROM conversation_insights WHERE id = ? AND session_id = ?
+             ON CONFLICT(insight_id) DO UPDATE SET
+                rating = excluded.rating,
+                insight_text = excluded.insight_text,
+                rated_at = excluded.rated_at",
+            params![rating.as_str(), chrono::Utc::now().timestamp_millis(), insight_id, session_id]
+        )?;
+        Ok(affected > 0)
+    }
+
+    pub fn delete_insight_feedback(&mut self, insight_id: &str) -> Result<()> {
+        self.connection.execute("DELETE FROM insight_feedback WHERE insight_id = ?", params![insight_id])?;
+        Ok(())
+    }
+
+    pub fn get_session_insight_feedback(&self, session_id: &str) -> Result<Vec<InsightFeedback>> {
+        self.query_insight_feedback(
+            "SELECT insight_id, session_id, rating, insight_text, rated_at
+             FROM insight_feedback WHERE session_id = ? ORDER BY rated_at",
+            params![session_id],
+        )
+    }
+
+    /// The newest `per_rating` liked and disliked insights, newest first
+    pub fn load_recent_insight_feedback(&self, per_rating: usize) -> Result<Vec<InsightFeedback>> {
+        let mut feedback = Vec::new();
+        for rating in [InsightRating::Up, InsightRating::Down] {
+            feedback.extend(self.query_insight_feedback(
+                "SELECT insight_id, session_id, rating, insight_text, rated_at
+                 FROM insight_feedback WHERE rating = ? ORDER BY rated_at DESC LIMIT ?",
+                params![rating.as_str(), per_rating as i64],
+            )?);
+        }
+        Ok(feedback)
+    }
+
+    fn query_insight_feedback(&self, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<InsightFeedback>> {
+        let mut stmt = self.connection.prepare(sql)?;
+        let rows = stmt.query_map(params, |row| {
+            let rating: String = row.get("rating")?;
+            Ok(InsightRating::parse(&rating).map(|rating| InsightFeedback {
+                insight_id: row.get("insight_id")?,
+                session_id: row.get("session_id")?,
+                rating,
+                insight_text: row.get("insight_text")?,
+                rated_at: row.get("rated_at")?,
+            }))
+        })?;
+        rows.filter_map(|row| row.transpose()).collect()
+    }
+
+    pub fn save_speaking_metrics(&mut self, metrics: &SpeakingMetrics) -> Result<()> {
+        let json = serde_json::to_string(metrics).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.connection.execute(
+            "INSERT OR REPLACE INTO speaking_metrics (session_id, metrics, computed_at) VALUES (?, ?, ?)",
+            params![metrics.session_id, json, metrics.computed_at]
+        )?;
+        Ok(())
+    }
+
+    /// Stored metrics; `None` if there are none or they no longer parse
+    pub fn load_speaking_metrics(&self, session_id: &str) -> Result<Option<SpeakingMetrics>> {
+        let json: Option<String> = self.connection
+            .query_row("SELECT metrics FROM speaking_metrics WHERE session_id = ?", params![session_id], |row| row.get(0))
+            .optional()?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    pub fn load_conversation(&self, session_id: &str) -> Result<Option<ConversationSession>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, name, start_time, end_time, is_active, languages FROM conversation_sessions WHERE id = ?"
+        )?;
+
+        let session = stmt.query_row([session_id], |row| {
+            Ok((
+                row.get::<_, String>("id")?,
+                row.get::<_, String>("name")?,
+                row.get::<_, i64>("start_time")?,
+                row.get::<_, Option<i64>>("end_time")?,
+                row.get::<_, i32>("is_active")? != 0,
+                languages_from_sql(row.get::<_, Option<String>>("languages")?),
+            ))
+        });
+
+        let (id, name, start_time, end_time, is_active, languages) = match session {
+            Ok(session) => session,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Some(ConversationSession {
+            messages: self.load_conversation_messages(&id)?,
+            insights: self.load_conversation_insights(&id)?,
+            id,
+            name,
+            start_time,
+            end_time,
+            is_active,
+            languages,
+        }))
+    }
+
+    pub fn update_session_languages(&mut self, session_id: &str, languages: &[String]) -> Result<()> {
+        let affected = self.connection.execute(
+            "UPDATE conversation_sessions SET languages = ? WHERE id = ?",
+            params![serde_json::to_string(languages).unwrap_or_else(|_| "[]".to_string()), session_id]
+        )?;
+
+        if affected == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        Ok(())
+    }
+
+    pub fn get_session_languages(&self, session_id: &str) -> Result<Vec<String>> {
+        match self.connection.query_row(
+            "SELECT languages FROM conversation_sessions WHERE id = ?",
+            params![session_id],
+            |row| row.get::<_, Option<String>>(0),
+        ) {
+            Ok(languages) => Ok(languages_from_sql(languages)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The meeting currently being recorded, if any
+    pub fn active_session_id(&self) -> Result<Option<String>> {
+        match self.connection.query_row(
+            "SELECT id FROM conversation_sessions WHERE is_active = 1 ORDER BY start_time DESC LIMIT 1",
+            params![],
+            |row| row.get::<_, String>(0),
+        ) {
+            Ok(id) => Ok(Some(id)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Add usage deltas to a meeting's running per-model totals
+    pub fn add_session_usage(&mut self, session_id: &str, usage: &[ModelUsage]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        for entry in usage {
+            tx.execute(
+                "INSERT INTO conversation_usage
+                    (session_id, model, prompt_tokens, completion_tokens, requests, transcription_ms, estimated_cost, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(session_id, model) DO UPDATE SET
+                    prompt_tokens = prompt_tokens + excluded.prompt_tokens,
+                    completion_tokens = completion_tokens + excluded.completion_tokens,
+                    requests = requests + excluded.requests,
+                    transcription_ms = transcription_ms + excluded.transcription_ms,
+                    estimated_cost = estimated_cost + excluded.estimated_cost,
+                    updated_at = excluded.updated_at",
+                params![
+                    session_id, entry.model, entry.prompt_tokens as i64, entry.completion_tokens as i64,
+                    entry.requests as i64, entry.transcription_ms as i64, entry.estimated_cost, now
+                ]
+            )?;
+        }
+        tx.commit()
+    }
+
+    pub fn load_session_usage(&self, session_id: &str) -> Result<Vec<ModelUsage>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT model, prompt_tokens, completion_tokens, requests, transcription_ms, estimated_cost
+             FROM conversation_usage WHERE session_id = ? ORDER BY estimated_cost DESC, model"
+        )?;
+
+        let usage = stmt.query_map([session_id], |row| {
+            Ok(ModelUsage {
+                model: row.get("model")?,
+                prompt_tokens: row.get::<_, i64>("prompt_tokens")? as u64,
+                completion_tokens: row.get::<_, i64>("completion_tokens")? as u64,
+                requests: row.get::<_, i64>("requests")? as u64,
+                transcription_ms: row.get::<_, i64>("transcription_ms")? as u64,
+                estimated_cost: row.get("estimated_cost")?,
+            })
+        })?;
+
+        usage.collect()
+    }
+
+    pub fn add_usage_stats(&mut self, rows: &[UsageStatRow]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        for row in rows {
+            tx.execute(
+                "INSERT INTO usage_stats (day, kind, name, count, prompt_tokens, completion_tokens)
+                 VALUES (?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(day, kind, name) DO UPDATE SET
+                    count = count + excluded.count,
+                    prompt_tokens = prompt_tokens + excluded.prompt_tokens,
+                    completion_tokens = completion_tokens + excluded.completion_tokens",
+                params![
+                    row.day, row.kind.as_str(), row.name, row.count as i64,
+                    row.prompt_tokens as i64, row.completion_tokens as i64
+                ]
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Rows from `since_day` (`YYYY-MM-DD`) onwards
+    pub fn load_usage_stats(&self, since_day: &str) -> Result<Vec<UsageStatRow>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT day, kind, name, count, prompt_tokens, completion_tokens
+             FROM usage_stats WHERE day >= ? ORDER BY day, kind, name"
+        )?;
+
+        let rows = stmt.query_map([since_day], |row| {
+            let kind: String = row.get("kind")?;
+            Ok(StatKind::parse(&kind).map(|kind| UsageStatRow {
+                day: row.get("day")?,
+                kind,
+                name: row.get("name")?,
+                count: row.get::<_, i64>("count")? as u64,
+                prompt_tokens: row.get::<_, i64>("prompt_tokens")? as u64,
+                completion_tokens: row.get::<_, i64>("completion_tokens")? as u64,
+            }))
+        })?;
+
+        // Kinds this version doesn't know are skipped
+        rows.filter_map(|row| row.transpose()).collect()
+    }
+
+    pub fn add_token_usage(&mut self, entry: &TokenLedgerEntry) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO token_ledger
+                (day, session_id, agent, model, requests, prompt_tokens, completion_tokens, estimated_cost)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(day, session_id, agent, model) DO UPDATE SET
+                requests = requests + excluded.requests,
+                prompt_tokens = prompt_tokens + excluded.prompt_tokens,
+                completion_tokens = completion_tokens + excluded.completion_tokens,
+                estimated_cost = estimated_cost + excluded.estimated_cost",
+            params![
+                entry.day, entry.session_id, entry.agent, entry.model, entry.requests as i64,
+                entry.prompt_tokens as i64, entry.completion_tokens as i64, entry.estimated_cost
+            ]
+        )?;
+        Ok(())
+    }
+
+    /// Ledger rows from `since_day` (`YYYY-MM-DD`) onwards, optionally for one session
+    pub fn load_token_usage(&self, since_day: &str, session_id: Option<&str>) -> Result<Vec<TokenLedgerEntry>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT day, session_id, agent, model, requests, prompt_tokens, completion_tokens, estimated_cost
+             FROM token_ledger WHERE day >= ?1 AND (?2 IS NULL OR session_id = ?2)
+             ORDER BY day, session_id, agent, model"
+        )?;
+
+        let entries = stmt.query_map(params![since_day, session_id], |row| {
+            Ok(TokenLedgerEntry {
+                day: row.get("day")?,
+                session_id: row.get("session_id")?,
+                agent: row.get("agent")?,
+                model: row.get("model")?,
+                requests: row.get::<_, i64>("requests")? as u64,
+                prompt_tokens: row.get::<_, i64>("prompt_tokens")? as u64,
+                completion_tokens: row.get::<_, i64>("completion_tokens")? as u64,
+                estimated_cost: row.get("estimated_cost")?,
+            })
+        })?;
+
+        entries.collect()
+    }
+
     pub fn delete_conversation(&mut self, conversation_id: &str) -> Result<()> {
         let affected = self.connection.execute(
             "DELETE FROM conversation_sessions WHERE id = ?",
@@ -571,6 +931,25 @@ impl ConversationStorage {
     }
 }
 
+// Languages are stored as a JSON array; NULL leaves the column untouched on update
+fn languages_to_sql(languages: &[String]) -> Option<String> {
+    if languages.is_empty() {
+        None
+    } else {
+        serde_json::to_string(languages).ok()
+    }
+}
+
+fn languages_from_sql(value: Option<String>) -> Vec<String> {
+    value
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn timings_to_sql(timings: &Option<TranscriptTimings>) -> Option<String> {
+    timings.as_ref().and_then(|timings| serde_json::to_string(timings).ok())
+}
+
 // Helper function to get database path
 fn get_database_path(app_handle: &AppHandle) -> std::result::Result<PathBuf, String> {
     let app_data_dir = app_handle
@@ -0,0 +1,271 @@
+// Token ledger - prompt and completion tokens per agent, chat session and day
+//
+// Every finished generation adds one entry. Meeting usage (see `usage`) answers "what did this
+// meeting cost"; the ledger answers "which agent is using the tokens" across all sessions.
+use chrono::{Duration as DayDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tauri::AppHandle;
+use crate::command;
+use super::usage::current_rates;
+use super::usage_stats::csv_field;
+use crate::data::worker;
+
+const DEFAULT_LEDGER_DAYS: u32 = 30;
+
+/// Requests made outside an agent, e.g. the plain generate commands
+pub const DIRECT_AGENT: &str = "direct";
+
+/// Usage of one model by one agent in one session on one day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenLedgerEntry {
+    /// Local date, `YYYY-MM-DD`
+    pub day: String,
+    /// Stream session the request ran under; empty for one-shot requests
+    pub session_id: String,
+    pub agent: String,
+    pub model: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub estimated_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTotals {
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub estimated_cost: f64,
+}
+
+impl TokenTotals {
+    fn add(&mut self, entry: &TokenLedgerEntry) {
+        self.requests += entry.requests;
+        self.prompt_tokens += entry.prompt_tokens;
+        self.completion_tokens += entry.completion_tokens;
+        self.estimated_cost += entry.estimated_cost;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentTokenUsage {
+    pub agent: String,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyTokenUsage {
+    pub day: String,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTokenUsage {
+    pub session_id: String,
+    /// Agents that ran in the session, alphabetically
+    pub agents: Vec<String>,
+    #[serde(flatten)]
+    pub totals: TokenTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsageStats {
+    /// First day covered, inclusive
+    pub since: String,
+    pub days: u32,
+    pub currency: String,
+    pub totals: TokenTotals,
+    /// Most tokens first
+    pub agents: Vec<AgentTokenUsage>,
+    /// Oldest first; days without requests are left out
+    pub daily: Vec<DailyTokenUsage>,
+    /// Most tokens first; one-shot requests aren't listed
+    pub sessions: Vec<SessionTokenUsage>,
+}
+
+/// Add a finished generation to the ledger
+pub fn record_tokens(
+    app_handle: &AppHandle,
+    session_id: &str,
+    agent: &str,
+    model: &str,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+) {
+    let entry = TokenLedgerEntry {
+        day: Local::now().format("%Y-%m-%d").to_string(),
+        session_id: session_id.to_string(),
+        agent: agent.to_string(),
+        model: model.to_string(),
+        requests: 1,
+        prompt_tokens,
+        completion_tokens,
+        estimated_cost: current_rates().generation_cost(model, prompt_tokens, completion_tokens),
+    };
+    worker::submit(app_handle, "token_ledger", move |db| {
+        db.conversations()?.add_token_usage(&entry).map_err(|e| e.to_string())
+    });
+}
+
+async fn load_entries(
+    app_handle: &AppHandle,
+    days: u32,
+    session_id: Option<String>,
+) -> Result<(String, Vec<TokenLedgerEntry>), String> {
+    let since = (Local::now().date_naive() - DayDuration::days(days as i64 - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let from = since.clone();
+    // Read on the writer so entries submitted just before are included
+    let entries = worker::write(app_handle, move |db| {
+        db.conversations()?
+            .load_token_usage(&from, session_id.as_deref())
+            .map_err(|e| format!("Failed to load token usage: {}", e))
+    }).await?;
+    Ok((since, entries))
+}
+
+fn total_tokens(totals: &TokenTotals) -> u64 {
+    totals.prompt_tokens + totals.completion_tokens
+}
+
+fn build_stats(since: String, days: u32, currency: String, entries: &[TokenLedgerEntry]) -> TokenUsageStats {
+    let mut totals = TokenTotals::default();
+    let mut agents: HashMap<&str, TokenTotals> = HashMap::new();
+    let mut daily: BTreeMap<&str, TokenTotals> = BTreeMap::new();
+    let mut sessions: HashMap<&str, (Vec<String>, TokenTotals)> = HashMap::new();
+    for entry in entries {
+        totals.add(entry);
+        agents.entry(&entry.agent).or_default().add(entry);
+        daily.entry(&entry.day).or_default().add(entry);
+        if !entry.session_id.is_empty() {
+            let (session_agents, session_totals) = sessions.entry(&entry.session_id).or_default();
+            if !session_agents.contains(&entry.agent) {
+                session_agents.push(entry.agent.clone());
+            }
+            session_totals.add(entry);
+        }
+    }
+
+    let mut agents: Vec<AgentTokenUsage> = agents
+        .into_iter()
+        .map(|(agent, totals)| AgentTokenUsage { agent: agent.to_string(), totals })
+        .collect();
+    agents.sort_by(|a, b| total_tokens(&b.totals).cmp(&total_tokens(&a.totals)).then_with(|| a.agent.cmp(&b.agent)));
+    let mut sessions: Vec<SessionTokenUsage> = sessions
+        .into_iter()
+        .map(|(session_id, (mut agents, totals))| {
+            agents.sort();
+            SessionTokenUsage { session_id: session_id.to_string(), agents, totals }
+        })
+        .collect();
+    sessions.sort_by(|a, b| {
+        total_tokens(&b.totals).cmp(&total_tokens(&a.totals)).then_with(|| a.session_id.cmp(&b.session_id))
+    });
+
+    TokenUsageStats {
+        since,
+        days,
+        currency,
+        totals,
+        agents,
+        daily: daily
+            .into_iter()
+            .map(|(day, totals)| DailyTokenUsage { day: day.to_string(), totals })
+            .collect(),
+        sessions,
+    }
+}
+
+fn to_csv(entries: &[TokenLedgerEntry]) -> String {
+    let mut csv = String::from("day,session_id,agent,model,requests,prompt_tokens,completion_tokens,estimated_cost\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{:.6}\n",
+            entry.day,
+            csv_field(&entry.session_id),
+            csv_field(&entry.agent),
+            csv_field(&entry.model),
+            entry.requests,
+            entry.prompt_tokens,
+            entry.completion_tokens,
+            entry.estimated_cost
+        ));
+    }
+    csv
+}
+
+/// Token usage over the last `days` days (30 by default), optionally for one session
+#[command]
+pub async fn get_usage_stats(
+    app_handle: AppHandle,
+    days: Option<u32>,
+    session_id: Option<String>,
+) -> Result<TokenUsageStats, String> {
+    let days = days.unwrap_or(DEFAULT_LEDGER_DAYS).max(1);
+    let (since, entries) = load_entries(&app_handle, days, session_id).await?;
+    Ok(build_stats(since, days, current_rates().currency, &entries))
+}
+
+/// The raw ledger for the same period as CSV, for the frontend to save
+#[command]
+pub async fn export_usage_stats_csv(
+    app_handle: AppHandle,
+    days: Option<u32>,
+    session_id: Option<String>,
+) -> Result<String, String> {
+    let days = days.unwrap_or(DEFAULT_LEDGER_DAYS).max(1);
+    let (_, entries) = load_entries(&app_handle, days, session_id).await?;
+    Ok(to_csv(&entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(day: &str, session_id: &str, agent: &str, tokens: (u64, u64)) -> TokenLedgerEntry {
+        TokenLedgerEntry {
+            day: day.to_string(),
+            session_id: session_id.to_string(),
+            agent: agent.to_string(),
+            model: "gemma3:1b-it-qat".to_string(),
+            requests: 1,
+            prompt_tokens: tokens.0,
+            completion_tokens: tokens.1,
+            estimated_cost: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_stats_group_by_agent_day_and_session() {
+        let entries = vec![
+            entry("2026-10-01", "chat-1", "enteract", (100, 50)),
+            entry("2026-10-01", "chat-1", "coding", (400, 200)),
+            entry("2026-10-02", "chat-2", "enteract", (10, 5)),
+            entry("2026-10-02", "", DIRECT_AGENT, (20, 20)),
+        ];
+
+        let stats = build_stats("2026-10-01".to_string(), 2, "USD".to_string(), &entries);
+        assert_eq!(stats.totals.requests, 4);
+        assert_eq!(stats.totals.prompt_tokens, 530);
+        assert_eq!(stats.agents[0].agent, "coding");
+        assert_eq!(stats.agents[1].totals.completion_tokens, 55);
+        assert_eq!(stats.daily.len(), 2);
+        assert_eq!(stats.daily[1].totals.requests, 2);
+        // One-shot requests count towards totals but not sessions
+        assert_eq!(stats.sessions.len(), 2);
+        assert_eq!(stats.sessions[0].agents, vec!["coding", "enteract"]);
+
+        let csv = to_csv(&entries[..1]);
+        assert_eq!(csv.lines().nth(1).unwrap(), "2026-10-01,chat-1,enteract,gemma3:1b-it-qat,1,100,50,0.000000");
+    }
+}
@@ -0,0 +1,271 @@
+// Per-meeting usage accounting - model tokens, transcription time and estimated cost
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use crate::command;
+use super::storage::ConversationStorage;
+use crate::data::worker;
+use crate::action_registry::{args, descriptor, param, to_value, ActionKind, RegisteredAction, SessionArgs};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+// Transcription runs every second or so; batch it instead of writing the database each time
+const TRANSCRIPTION_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+lazy_static::lazy_static! {
+    static ref USAGE_RATES: RwLock<UsageRates> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref PENDING_TRANSCRIPTION: Mutex<PendingTranscription> = Mutex::new(PendingTranscription::default());
+}
+
+#[derive(Default)]
+struct PendingTranscription {
+    audio_ms: HashMap<String, u64>,
+    since: Option<Instant>,
+}
+
+/// Prices for one model. Local models default to free.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelRate {
+    pub input_per_million_tokens: f64,
+    pub output_per_million_tokens: f64,
+    pub per_audio_minute: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UsageRates {
+    pub currency: String,
+    /// Keyed by model name, e.g. `gpt-4o` or `whisper-small`
+    pub models: HashMap<String, ModelRate>,
+}
+
+impl Default for UsageRates {
+    fn default() -> Self {
+        Self {
+            currency: "USD".to_string(),
+            models: HashMap::new(),
+        }
+    }
+}
+
+impl UsageRates {
+    /// Exact model name first, then the name without its tag (`llama3:8b` -> `llama3`)
+    pub fn rate_for(&self, model: &str) -> ModelRate {
+        self.models
+            .get(model)
+            .or_else(|| model.split(':').next().and_then(|base| self.models.get(base)))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn generation_cost(&self, model: &str, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        let rate = self.rate_for(model);
+        (prompt_tokens as f64 * rate.input_per_million_tokens
+            + completion_tokens as f64 * rate.output_per_million_tokens)
+            / 1_000_000.0
+    }
+
+    pub fn transcription_cost(&self, model: &str, audio_ms: u64) -> f64 {
+        self.rate_for(model).per_audio_minute * audio_ms as f64 / 60_000.0
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (model, rate) in &self.models {
+            let values = [rate.input_per_million_tokens, rate.output_per_million_tokens, rate.per_audio_minute];
+            if values.iter().any(|v| !v.is_finite() || *v < 0.0) {
+                return Err(format!("Rates for '{}' must be non-negative numbers", model));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Accumulated usage of one model within one meeting
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    pub model: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub requests: u64,
+    pub transcription_ms: u64,
+    pub estimated_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingUsageReport {
+    pub session_id: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub transcription_minutes: f64,
+    pub estimated_cost: f64,
+    pub currency: String,
+    pub models: Vec<ModelUsage>,
+}
+
+impl MeetingUsageReport {
+    fn from_models(session_id: String, currency: String, models: Vec<ModelUsage>) -> Self {
+        Self {
+            session_id,
+            prompt_tokens: models.iter().map(|m| m.prompt_tokens).sum(),
+            completion_tokens: models.iter().map(|m| m.completion_tokens).sum(),
+            transcription_minutes: models.iter().map(|m| m.transcription_ms).sum::<u64>() as f64 / 60_000.0,
+            estimated_cost: models.iter().map(|m| m.estimated_cost).sum(),
+            currency,
+            models,
+        }
+    }
+}
+
+const SETTINGS_FILE: &str = "usage_rates.json";
+
+pub(super) fn current_rates() -> UsageRates {
+    read_settings(&USAGE_RATES)
+}
+
+/// Record a finished model response against the active meeting, if there is one
+pub fn record_generation(app_handle: &AppHandle, model: &str, prompt_tokens: u64, completion_tokens: u64) {
+    super::usage_stats::record_model(model, prompt_tokens, completion_tokens);
+    let usage = ModelUsage {
+        model: model.to_string(),
+        prompt_tokens,
+        completion_tokens,
+        requests: 1,
+        transcription_ms: 0,
+        estimated_cost: current_rates().generation_cost(model, prompt_tokens, completion_tokens),
+    };
+    flush_usage(app_handle, vec![usage]);
+}
+
+/// Add transcribed audio for a model; written out in batches
+pub fn record_transcription(app_handle: &AppHandle, model: &str, audio_ms: u64) {
+    let due = {
+        let Ok(mut pending) = PENDING_TRANSCRIPTION.lock() else { return };
+        *pending.audio_ms.entry(model.to_string()).or_insert(0) += audio_ms;
+        let since = *pending.since.get_or_insert_with(Instant::now);
+        since.elapsed() >= TRANSCRIPTION_FLUSH_INTERVAL
+    };
+    if due {
+        flush_usage(app_handle, Vec::new());
+    }
+}
+
+/// Write any batched transcription time now, e.g. before a meeting is closed
+pub fn flush_pending_usage(app_handle: &AppHandle) {
+    flush_usage(app_handle, Vec::new());
+}
+
+fn take_pending_transcription() -> Vec<ModelUsage> {
+    let Ok(mut pending) = PENDING_TRANSCRIPTION.lock() else { return Vec::new() };
+    pending.since = None;
+    let rates = current_rates();
+    pending
+        .audio_ms
+        .drain()
+        .map(|(model, audio_ms)| ModelUsage {
+            estimated_cost: rates.transcription_cost(&model, audio_ms),
+            model,
+            transcription_ms: audio_ms,
+            ..Default::default()
+        })
+        .collect()
+}
+
+// Queued behind earlier writes, so a session update issued afterwards still sees this usage
+fn flush_usage(app_handle: &AppHandle, mut usage: Vec<ModelUsage>) {
+    usage.extend(take_pending_transcription());
+    if usage.is_empty() {
+        return;
+    }
+    worker::submit(app_handle, "usage", move |db| write_usage(db.conversations()?, &usage));
+}
+
+fn write_usage(storage: &mut ConversationStorage, usage: &[ModelUsage]) -> Result<(), String> {
+    if usage.is_empty() {
+        return Ok(());
+    }
+    // Usage outside a meeting isn't attributed anywhere
+    let Some(session_id) = storage.active_session_id().map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+    storage.add_session_usage(&session_id, usage).map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn get_meeting_usage(app_handle: AppHandle, session_id: String) -> Result<MeetingUsageReport, String> {
+    let pending = take_pending_transcription();
+    // Runs on the writer so batched transcription time is stored before it's read back
+    let id = session_id.clone();
+    let models = worker::write(&app_handle, move |db| {
+        let storage = db.conversations()?;
+        if let Err(e) = write_usage(storage, &pending) {
+            eprintln!("[USAGE] Failed to flush usage: {}", e);
+        }
+        storage
+            .load_session_usage(&id)
+            .map_err(|e| format!("Failed to load meeting usage: {}", e))
+    }).await?;
+    Ok(MeetingUsageReport::from_models(session_id, current_rates().currency, models))
+}
+
+#[command]
+pub fn get_usage_rates() -> Result<UsageRates, String> {
+    USAGE_RATES
+        .read()
+        .map(|rates| rates.clone())
+        .map_err(|_| "Failed to read usage rates".to_string())
+}
+
+/// New rates apply to usage recorded from now on; stored costs aren't recalculated
+#[command]
+pub fn save_usage_rates(rates: UsageRates) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    rates.validate()?;
+    save_json_settings(SETTINGS_FILE, "usage rates", &rates)?;
+
+    *USAGE_RATES
+        .write()
+        .map_err(|_| "Failed to update usage rates".to_string())? = rates;
+    Ok(())
+}
+
+/// Command palette actions for meeting usage
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    vec![RegisteredAction {
+        descriptor: descriptor("conversation.usage", ActionKind::Command, "Meeting Usage",
+            "Token usage, transcription time and estimated cost of a meeting",
+            vec![param("session_id", "string", true, "Conversation to report on")], &["cost", "tokens"]),
+        handler: |app, params| Box::pin(async move {
+            let SessionArgs { session_id } = args(params)?;
+            to_value(get_meeting_usage(app, session_id).await?)
+        }),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_uses_base_model_rate() {
+        let mut rates = UsageRates::default();
+        rates.models.insert("gpt-4o".to_string(), ModelRate {
+            input_per_million_tokens: 2.5,
+            output_per_million_tokens: 10.0,
+            per_audio_minute: 0.0,
+        });
+        rates.models.insert("whisper-small".to_string(), ModelRate {
+            per_audio_minute: 0.006,
+            ..Default::default()
+        });
+
+        let cost = rates.generation_cost("gpt-4o:latest", 1_000_000, 500_000);
+        assert!((cost - 7.5).abs() < 1e-9);
+        assert!((rates.transcription_cost("whisper-small", 120_000) - 0.012).abs() < 1e-9);
+        // Unknown (local) models are free
+        assert_eq!(rates.generation_cost("llama3:8b", 10_000, 10_000), 0.0);
+    }
+}
@@ -0,0 +1,326 @@
+// Local usage statistics - feature counters, daily active minutes and model usage
+//
+// Nothing here leaves the machine. Counts are batched in memory, written to the local
+// database once a minute and only ever read back by `get_usage_report` and its CSV export.
+use chrono::{Duration as DayDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use crate::command;
+use super::storage::ConversationStorage;
+use crate::data::worker;
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_REPORT_DAYS: u32 = 30;
+
+lazy_static::lazy_static! {
+    static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+    static ref PENDING_STATS: Mutex<PendingStats> = Mutex::new(PendingStats::default());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatKind {
+    /// Calls to one command
+    Feature,
+    /// Minutes in which the app handled at least one command
+    ActiveMinutes,
+    /// Generation requests to one model
+    Model,
+}
+
+impl StatKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Feature => "feature",
+            Self::ActiveMinutes => "active_minutes",
+            Self::Model => "model",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "feature" => Some(Self::Feature),
+            "active_minutes" => Some(Self::ActiveMinutes),
+            "model" => Some(Self::Model),
+            _ => None,
+        }
+    }
+}
+
+/// One day's count for one feature, model or the active-minute total
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStatRow {
+    /// Local date, `YYYY-MM-DD`
+    pub day: String,
+    pub kind: StatKind,
+    /// Command or model name; empty for active minutes
+    pub name: String,
+    pub count: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureCount {
+    pub feature: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyActivity {
+    pub day: String,
+    pub active_minutes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsageTotal {
+    pub model: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    /// First day covered, inclusive
+    pub since: String,
+    pub days: u32,
+    pub total_active_minutes: u64,
+    /// Most used first
+    pub features: Vec<FeatureCount>,
+    /// Oldest first; days without activity are left out
+    pub daily: Vec<DailyActivity>,
+    pub models: Vec<ModelUsageTotal>,
+}
+
+#[derive(Default)]
+struct PendingStats {
+    rows: HashMap<(String, StatKind, String), (u64, u64, u64)>,
+    last_active_minute: Option<i64>,
+}
+
+impl PendingStats {
+    fn add(&mut self, kind: StatKind, name: &str, count: u64, prompt_tokens: u64, completion_tokens: u64) {
+        let day = Local::now().format("%Y-%m-%d").to_string();
+        let entry = self.rows.entry((day, kind, name.to_string())).or_insert((0, 0, 0));
+        entry.0 += count;
+        entry.1 += prompt_tokens;
+        entry.2 += completion_tokens;
+    }
+
+    fn take(&mut self) -> Vec<UsageStatRow> {
+        self.rows
+            .drain()
+            .map(|((day, kind, name), (count, prompt_tokens, completion_tokens))| UsageStatRow {
+                day,
+                kind,
+                name,
+                count,
+                prompt_tokens,
+                completion_tokens,
+            })
+            .collect()
+    }
+}
+
+pub fn init(app_handle: &AppHandle) {
+    if let Ok(mut handle) = APP_HANDLE.lock() {
+        *handle = Some(app_handle.clone());
+    }
+    std::thread::spawn(|| loop {
+        std::thread::sleep(FLUSH_INTERVAL);
+        let app_handle = APP_HANDLE.lock().ok().and_then(|handle| handle.clone());
+        if let Some(app_handle) = app_handle {
+            flush_stats(&app_handle);
+        }
+    });
+}
+
+/// Count a command call, and the current minute as active if it isn't already
+pub fn record_command(command: &str) {
+    let Ok(mut pending) = PENDING_STATS.lock() else { return };
+    pending.add(StatKind::Feature, command, 1, 0, 0);
+    let minute = Local::now().timestamp() / 60;
+    if pending.last_active_minute != Some(minute) {
+        pending.last_active_minute = Some(minute);
+        pending.add(StatKind::ActiveMinutes, "", 1, 0, 0);
+    }
+}
+
+pub fn record_model(model: &str, prompt_tokens: u64, completion_tokens: u64) {
+    if let Ok(mut pending) = PENDING_STATS.lock() {
+        pending.add(StatKind::Model, model, 1, prompt_tokens, completion_tokens);
+    }
+}
+
+fn take_pending_stats() -> Vec<UsageStatRow> {
+    PENDING_STATS.lock().map(|mut pending| pending.take()).unwrap_or_default()
+}
+
+fn flush_stats(app_handle: &AppHandle) {
+    let rows = take_pending_stats();
+    if rows.is_empty() {
+        return;
+    }
+    worker::submit(app_handle, "usage_stats", move |db| {
+        db.conversations()?.add_usage_stats(&rows).map_err(|e| e.to_string())
+    });
+}
+
+// Flushes batched counts on the writer first, so the rows read back include them
+async fn load_rows(app_handle: &AppHandle, days: u32) -> Result<(String, Vec<UsageStatRow>), String> {
+    let days = days.max(1);
+    let since = (Local::now().date_naive() - DayDuration::days(days as i64 - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let pending = take_pending_stats();
+    let from = since.clone();
+    let rows = worker::write(app_handle, move |db| {
+        let storage: &mut ConversationStorage = db.conversations()?;
+        if !pending.is_empty() {
+            if let Err(e) = storage.add_usage_stats(&pending) {
+                eprintln!("[USAGE] Failed to flush usage stats: {}", e);
+            }
+        }
+        storage
+            .load_usage_stats(&from)
+            .map_err(|e| format!("Failed to load usage stats: {}", e))
+    }).await?;
+    Ok((since, rows))
+}
+
+fn build_report(since: String, days: u32, rows: &[UsageStatRow]) -> UsageReport {
+    let mut features: HashMap<&str, u64> = HashMap::new();
+    let mut daily: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut models: HashMap<&str, ModelUsageTotal> = HashMap::new();
+    for row in rows {
+        match row.kind {
+            StatKind::Feature => *features.entry(&row.name).or_insert(0) += row.count,
+            StatKind::ActiveMinutes => *daily.entry(&row.day).or_insert(0) += row.count,
+            StatKind::Model => {
+                let total = models.entry(&row.name).or_insert_with(|| ModelUsageTotal {
+                    model: row.name.clone(),
+                    requests: 0,
+                    prompt_tokens: 0,
+                    completion_tokens: 0,
+                });
+                total.requests += row.count;
+                total.prompt_tokens += row.prompt_tokens;
+                total.completion_tokens += row.completion_tokens;
+            }
+        }
+    }
+
+    let mut features: Vec<FeatureCount> = features
+        .into_iter()
+        .map(|(feature, count)| FeatureCount { feature: feature.to_string(), count })
+        .collect();
+    features.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.feature.cmp(&b.feature)));
+    let mut models: Vec<ModelUsageTotal> = models.into_values().collect();
+    models.sort_by(|a, b| b.requests.cmp(&a.requests).then_with(|| a.model.cmp(&b.model)));
+
+    UsageReport {
+        since,
+        days,
+        total_active_minutes: daily.values().sum(),
+        features,
+        daily: daily
+            .into_iter()
+            .map(|(day, active_minutes)| DailyActivity { day: day.to_string(), active_minutes })
+            .collect(),
+        models,
+    }
+}
+
+pub(super) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(rows: &[UsageStatRow]) -> String {
+    let mut rows: Vec<&UsageStatRow> = rows.iter().collect();
+    rows.sort_by(|a, b| (&a.day, a.kind.as_str(), &a.name).cmp(&(&b.day, b.kind.as_str(), &b.name)));
+    let mut csv = String::from("day,kind,name,count,prompt_tokens,completion_tokens\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            row.day,
+            row.kind.as_str(),
+            csv_field(&row.name),
+            row.count,
+            row.prompt_tokens,
+            row.completion_tokens
+        ));
+    }
+    csv
+}
+
+/// Usage over the last `days` days (30 by default), including today
+#[command]
+pub async fn get_usage_report(app_handle: AppHandle, days: Option<u32>) -> Result<UsageReport, String> {
+    let days = days.unwrap_or(DEFAULT_REPORT_DAYS).max(1);
+    let (since, rows) = load_rows(&app_handle, days).await?;
+    Ok(build_report(since, days, &rows))
+}
+
+/// The same period as CSV, one row per day and counter, for the frontend to save
+#[command]
+pub async fn export_usage_report_csv(app_handle: AppHandle, days: Option<u32>) -> Result<String, String> {
+    let days = days.unwrap_or(DEFAULT_REPORT_DAYS).max(1);
+    let (_, rows) = load_rows(&app_handle, days).await?;
+    Ok(to_csv(&rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(day: &str, kind: StatKind, name: &str, count: u64, tokens: (u64, u64)) -> UsageStatRow {
+        UsageStatRow {
+            day: day.to_string(),
+            kind,
+            name: name.to_string(),
+            count,
+            prompt_tokens: tokens.0,
+            completion_tokens: tokens.1,
+        }
+    }
+
+    #[test]
+    fn test_report_aggregates_days_and_csv_escapes_names() {
+        let rows = vec![
+            row("2026-10-02", StatKind::Feature, "start_conversation_replay", 2, (0, 0)),
+            row("2026-10-01", StatKind::Feature, "start_conversation_replay", 1, (0, 0)),
+            row("2026-10-01", StatKind::Feature, "get_usage_report", 4, (0, 0)),
+            row("2026-10-02", StatKind::ActiveMinutes, "", 30, (0, 0)),
+            row("2026-10-01", StatKind::ActiveMinutes, "", 12, (0, 0)),
+            row("2026-10-01", StatKind::Model, "llama3:8b", 2, (100, 40)),
+            row("2026-10-02", StatKind::Model, "llama3:8b", 1, (50, 10)),
+        ];
+
+        let report = build_report("2026-10-01".to_string(), 2, &rows);
+        assert_eq!(report.total_active_minutes, 42);
+        assert_eq!(report.daily[0].day, "2026-10-01");
+        assert_eq!(report.features[0].feature, "get_usage_report");
+        assert_eq!(report.features[1].count, 3);
+        assert_eq!(report.models.len(), 1);
+        assert_eq!((report.models[0].requests, report.models[0].prompt_tokens), (3, 150));
+
+        let csv = to_csv(&[row("2026-10-01", StatKind::Model, "vendor/model, \"large\"", 1, (5, 6))]);
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "2026-10-01,model,\"vendor/model, \"\"large\"\"\",1,5,6"
+        );
+    }
+}
@@ -3,6 +3,7 @@ use serde::{Serialize, Deserialize};
 use std::sync::{Arc, Mutex};
 use std::collections::VecDeque;
 use std::time::Instant;
+use crate::app_lock::{require_unlocked, CommandGroup};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LogLevel {
@@ -331,7 +332,7 @@ macro_rules! db_critical {
 }
 
 // Tauri commands for log access
-use tauri::command;
+use crate::command;
 
 #[command]
 pub fn get_database_logs(last_n: Option<usize>) -> Result<Vec<LogEntry>, String> {
@@ -355,5 +356,6 @@ pub fn get_database_log_stats() -> Result<LogStats, String> {
 
 #[command]
 pub fn clear_database_logs() -> Result<(), String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
     DB_LOGGER.clear_logs()
 }
\ No newline at end of file
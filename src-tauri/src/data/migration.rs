@@ -1,12 +1,14 @@
 // Migration utilities for SQLite database initialization
 // This module handles database setup and schema creation
 
-use tauri::{AppHandle, Manager, command};
+use tauri::{AppHandle, Manager};
+use crate::command;
 use serde::{Serialize, Deserialize};
 use rusqlite::{Connection, params, Result as SqliteResult, Error as SqliteError};
 use std::path::PathBuf;
 use std::fs;
 use std::time::Instant;
+use crate::app_lock::{require_unlocked, CommandGroup};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatabaseInfo {
@@ -45,9 +47,14 @@ pub struct DatabaseConnectionPool {
     pub connection_errors: usize,
 }
 
-/// Comprehensive database health check
+/// Comprehensive database health check, run on the database reader
 #[command]
-pub fn check_database_health(app_handle: AppHandle) -> Result<DatabaseHealth, String> {
+pub async fn check_database_health(app_handle: AppHandle) -> Result<DatabaseHealth, String> {
+    let app = app_handle.clone();
+    crate::data::worker::read(&app_handle, move |_| database_health(app)).await
+}
+
+fn database_health(app_handle: AppHandle) -> Result<DatabaseHealth, String> {
     let start_time = Instant::now();
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
@@ -342,7 +349,7 @@ pub fn initialize_database(app_handle: AppHandle) -> Result<String, String> {
     
     // Verify schema creation
     println!("🔍 Verifying schema creation...");
-    let health = check_database_health(app_handle.clone())
+    let health = database_health(app_handle.clone())
         .map_err(|e| format!("Failed to verify database health: {}", e))?;
     
     if !health.is_healthy {
@@ -364,7 +371,12 @@ pub fn initialize_database(app_handle: AppHandle) -> Result<String, String> {
 
 /// Get information about the current database
 #[command]
-pub fn get_database_info(app_handle: AppHandle) -> Result<DatabaseInfo, String> {
+pub async fn get_database_info(app_handle: AppHandle) -> Result<DatabaseInfo, String> {
+    let app = app_handle.clone();
+    crate::data::worker::read(&app_handle, move |_| database_info(app)).await
+}
+
+fn database_info(app_handle: AppHandle) -> Result<DatabaseInfo, String> {
     let db_path = get_database_path(&app_handle)?;
     
     if !db_path.exists() {
@@ -425,6 +437,7 @@ pub fn get_database_info(app_handle: AppHandle) -> Result<DatabaseInfo, String>
 /// Clean up old JSON files after confirming SQLite is working
 #[command]
 pub fn cleanup_legacy_files(app_handle: AppHandle, confirm: bool) -> Result<Vec<String>, String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
     if !confirm {
         return Err("Confirmation required to delete legacy files".to_string());
     }
@@ -532,6 +545,7 @@ fn get_database_schema() -> String {
         analysis_types TEXT, -- JSON array stored as text
         search_queries TEXT, -- JSON array stored as text
         sources TEXT, -- JSON array stored as text
+        seed INTEGER,
         FOREIGN KEY (message_id) REFERENCES chat_messages(id) ON DELETE CASCADE
     );
 
@@ -541,7 +555,8 @@ fn get_database_schema() -> String {
         name TEXT NOT NULL,
         start_time INTEGER NOT NULL,
         end_time INTEGER,
-        is_active INTEGER NOT NULL CHECK(is_active IN (0, 1))
+        is_active INTEGER NOT NULL CHECK(is_active IN (0, 1)),
+        languages TEXT
     );
 
     -- Conversation messages table
@@ -553,6 +568,7 @@ fn get_database_schema() -> String {
         content TEXT NOT NULL,
         timestamp INTEGER NOT NULL,
         confidence REAL,
+        timings TEXT,
         FOREIGN KEY (session_id) REFERENCES conversation_sessions(id) ON DELETE CASCADE
     );
 
@@ -567,6 +583,20 @@ fn get_database_schema() -> String {
         FOREIGN KEY (session_id) REFERENCES conversation_sessions(id) ON DELETE CASCADE
     );
 
+    -- Per-meeting model usage and estimated cost
+    CREATE TABLE IF NOT EXISTS conversation_usage (
+        session_id TEXT NOT NULL,
+        model TEXT NOT NULL,
+        prompt_tokens INTEGER NOT NULL DEFAULT 0,
+        completion_tokens INTEGER NOT NULL DEFAULT 0,
+        requests INTEGER NOT NULL DEFAULT 0,
+        transcription_ms INTEGER NOT NULL DEFAULT 0,
+        estimated_cost REAL NOT NULL DEFAULT 0,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (session_id, model),
+        FOREIGN KEY (session_id) REFERENCES conversation_sessions(id) ON DELETE CASCADE
+    );
+
     -- Performance indexes for chat system
     CREATE INDEX IF NOT EXISTS idx_chat_sessions_updated_desc ON chat_sessions(updated_at DESC);
     CREATE INDEX IF NOT EXISTS idx_chat_messages_session_timestamp ON chat_messages(session_id, timestamp);
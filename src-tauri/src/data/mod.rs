@@ -8,6 +8,9 @@ pub mod migration;       // Database initialization and cleanup
 pub mod errors;          // Error handling types and utilities
 pub mod connection_pool; // Database connection pooling
 pub mod logging;         // Comprehensive logging system
+pub mod reconciliation;  // Orphaned storage artifact detection and cleanup
+pub mod worker;          // Dedicated reader/writer threads for database work
+pub mod audit;           // Hash-chained audit log of MCP tool executions
 
 // Re-export all the commonly used types and functions
 pub use types::*;
@@ -17,6 +20,11 @@ pub use errors::*;
 pub use chat::{
     save_chat_sessions,
     load_chat_sessions,
+    recover_pending_responses,
+    discard_pending_response,
+    list_scheduled_tasks,
+    save_scheduled_task,
+    delete_scheduled_task,
 };
 
 // Re-export conversation commands
@@ -34,6 +42,24 @@ pub use conversation::{
     update_session_metadata,
     update_session_active_state,
     ping_backend,
+    start_conversation_replay,
+    pause_conversation_replay,
+    resume_conversation_replay,
+    seek_conversation_replay,
+    set_conversation_replay_speed,
+    stop_conversation_replay,
+    get_meeting_usage,
+    get_usage_rates,
+    save_usage_rates,
+    get_usage_report,
+    export_usage_report_csv,
+    get_usage_stats,
+    export_usage_stats_csv,
+    rate_conversation_insight,
+    get_insight_feedback,
+    get_speaking_metrics,
+    set_conversation_languages,
+    get_conversation_languages,
 };
 
 // Re-export migration commands
@@ -44,6 +70,18 @@ pub use migration::{
     check_database_health,
 };
 
+// Re-export reconciliation commands
+pub use reconciliation::{
+    scan_orphaned_artifacts,
+    cleanup_orphaned_artifacts,
+};
+
+// Re-export audit log commands
+pub use audit::{
+    get_mcp_audit_log,
+    verify_audit_integrity,
+};
+
 // Re-export logging commands
 pub use logging::{
     get_database_logs,
@@ -0,0 +1,293 @@
+// Startup reconciliation of orphaned storage artifacts
+// Finds document folders without DB rows, leftover WAV recordings and stale temp uploads
+
+use tauri::{AppHandle, Manager};
+use crate::command;
+use serde::{Serialize, Deserialize};
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::app_lock::{require_unlocked, CommandGroup};
+
+// Anything younger than this may still be in use by a running capture or upload
+const STALE_ARTIFACT_AGE: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanKind {
+    DocumentStorage,
+    Recording,
+    TempUpload,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedArtifact {
+    pub kind: OrphanKind,
+    pub path: String,
+    pub size_bytes: u64,
+    pub modified_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanReport {
+    pub artifacts: Vec<OrphanedArtifact>,
+    pub total_size_bytes: u64,
+    pub scanned_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanCleanupResult {
+    pub removed: Vec<String>,
+    pub failed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// Scan the app data directory for storage artifacts that nothing references anymore
+#[command]
+pub fn scan_orphaned_artifacts(app_handle: AppHandle) -> Result<OrphanReport, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    Ok(build_report(&app_data_dir, &std::env::temp_dir()))
+}
+
+/// Remove orphaned artifacts found by a fresh scan, optionally limited to some kinds.
+/// Only paths from the scan are ever deleted.
+#[command]
+pub fn cleanup_orphaned_artifacts(
+    app_handle: AppHandle,
+    confirm: bool,
+    kinds: Option<Vec<OrphanKind>>,
+) -> Result<OrphanCleanupResult, String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
+    if !confirm {
+        return Err("Confirmation required to delete orphaned artifacts".to_string());
+    }
+
+    let report = scan_orphaned_artifacts(app_handle)?;
+    let selected: Option<HashSet<OrphanKind>> = kinds.map(|k| k.into_iter().collect());
+
+    let mut result = OrphanCleanupResult {
+        removed: Vec::new(),
+        failed: Vec::new(),
+        freed_bytes: 0,
+    };
+
+    for artifact in report.artifacts {
+        if let Some(selected) = &selected {
+            if !selected.contains(&artifact.kind) {
+                continue;
+            }
+        }
+
+        let path = Path::new(&artifact.path);
+        let removal = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+
+        match removal {
+            Ok(()) => {
+                result.freed_bytes += artifact.size_bytes;
+                result.removed.push(artifact.path);
+            }
+            Err(e) => {
+                eprintln!("Failed to remove orphaned artifact {}: {}", artifact.path, e);
+                result.failed.push(artifact.path);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn build_report(app_data_dir: &Path, temp_dir: &Path) -> OrphanReport {
+    let mut artifacts = Vec::new();
+    artifacts.extend(find_orphaned_document_dirs(app_data_dir));
+    artifacts.extend(find_stale_recordings(app_data_dir));
+    artifacts.extend(find_stale_temp_uploads(temp_dir));
+
+    let total_size_bytes = artifacts.iter().map(|a| a.size_bytes).sum();
+
+    OrphanReport {
+        artifacts,
+        total_size_bytes,
+        scanned_at: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+fn find_orphaned_document_dirs(app_data_dir: &Path) -> Vec<OrphanedArtifact> {
+    let storage_path = app_data_dir.join("document_storage");
+    let entries = match fs::read_dir(&storage_path) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    // Both the legacy and enhanced RAG systems share document_storage/<document_id>
+    let mut known_ids = HashSet::new();
+    let sources = [
+        (app_data_dir.join("rag_documents.db"), "SELECT id FROM documents"),
+        (app_data_dir.join("enhanced_rag_documents.db"), "SELECT id FROM enhanced_documents"),
+    ];
+    for (db_path, query) in sources.iter() {
+        if !db_path.exists() {
+            continue;
+        }
+        match load_ids(db_path, query) {
+            Ok(ids) => known_ids.extend(ids),
+            Err(e) => {
+                // Without a reliable view of the DB we can't tell what is orphaned
+                eprintln!("Skipping document storage reconciliation, failed to read {}: {}", db_path.display(), e);
+                return Vec::new();
+            }
+        }
+    }
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir() && is_stale(&entry.path()))
+        .filter(|entry| !known_ids.contains(&entry.file_name().to_string_lossy().to_string()))
+        .map(|entry| artifact_for(OrphanKind::DocumentStorage, entry.path()))
+        .collect()
+}
+
+fn find_stale_recordings(app_data_dir: &Path) -> Vec<OrphanedArtifact> {
+    let capture_active = crate::audio_loopback::CAPTURE_STATE
+        .lock()
+        .map(|state| state.is_capturing)
+        .unwrap_or(true);
+    if capture_active {
+        return Vec::new();
+    }
+
+    let mut artifacts = Vec::new();
+    for dir in [app_data_dir.to_path_buf(), app_data_dir.join("recordings")] {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with("recording_") && name.ends_with(".wav") && is_stale(&entry.path()) {
+                artifacts.push(artifact_for(OrphanKind::Recording, entry.path()));
+            }
+        }
+    }
+    artifacts
+}
+
+fn find_stale_temp_uploads(temp_dir: &Path) -> Vec<OrphanedArtifact> {
+    let entries = match fs::read_dir(temp_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    // NamedTempFile names look like ".tmpXXXXXX.pcm"; they survive a crash mid-transcription
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with(".tmp") && name.ends_with(".pcm")
+        })
+        .filter(|entry| entry.path().is_file() && is_stale(&entry.path()))
+        .map(|entry| artifact_for(OrphanKind::TempUpload, entry.path()))
+        .collect()
+}
+
+fn load_ids(db_path: &Path, query: &str) -> rusqlite::Result<Vec<String>> {
+    let conn = Connection::open(db_path)?;
+    let mut stmt = conn.prepare(query)?;
+    let ids = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    ids.collect()
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age >= STALE_ARTIFACT_AGE)
+        .unwrap_or(false)
+}
+
+fn artifact_for(kind: OrphanKind, path: PathBuf) -> OrphanedArtifact {
+    let modified_at = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64);
+
+    OrphanedArtifact {
+        kind,
+        size_bytes: path_size(&path),
+        path: path.to_string_lossy().to_string(),
+        modified_at,
+    }
+}
+
+fn path_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| path_size(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0)
+    } else {
+        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn backdate(path: &Path) {
+        let modified = SystemTime::now() - STALE_ARTIFACT_AGE * 2;
+        fs::File::open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn test_document_dirs_without_rows_are_orphaned() {
+        let app_dir = tempdir().unwrap();
+        let storage = app_dir.path().join("document_storage");
+        fs::create_dir_all(storage.join("known-doc")).unwrap();
+        fs::create_dir_all(storage.join("orphan-doc")).unwrap();
+        fs::write(storage.join("orphan-doc").join("file.txt"), b"hello").unwrap();
+        backdate(&storage.join("known-doc"));
+        backdate(&storage.join("orphan-doc"));
+
+        let conn = Connection::open(app_dir.path().join("enhanced_rag_documents.db")).unwrap();
+        conn.execute("CREATE TABLE enhanced_documents (id TEXT PRIMARY KEY)", []).unwrap();
+        conn.execute("INSERT INTO enhanced_documents (id) VALUES ('known-doc')", []).unwrap();
+        drop(conn);
+
+        let orphans = find_orphaned_document_dirs(app_dir.path());
+        assert_eq!(orphans.len(), 1);
+        assert!(orphans[0].path.ends_with("orphan-doc"));
+        assert_eq!(orphans[0].size_bytes, 5);
+    }
+
+    #[test]
+    fn test_fresh_document_dirs_are_kept() {
+        let app_dir = tempdir().unwrap();
+        // An upload in progress has a folder before its row is committed
+        fs::create_dir_all(app_dir.path().join("document_storage").join("uploading-doc")).unwrap();
+        assert!(find_orphaned_document_dirs(app_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_fresh_temp_files_are_kept() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join(".tmpabc123.pcm"), b"pcm").unwrap();
+        assert!(find_stale_temp_uploads(temp.path()).is_empty());
+    }
+}
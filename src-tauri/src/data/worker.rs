@@ -0,0 +1,151 @@
+// Database worker threads - keep SQLite off the command handlers
+//
+// Writes go to a single writer thread; reads go to a second thread with its own connection,
+// which WAL mode lets run alongside the writer, so a heavy analytics or search query never
+// holds up saving the next message. Each thread keeps its connections open between jobs.
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use super::audit::AuditLog;
+use super::chat::storage::ChatStorage;
+use super::conversation::storage::ConversationStorage;
+
+type Job = Box<dyn FnOnce(&mut DbConnections) + Send>;
+
+lazy_static::lazy_static! {
+    static ref WORKERS: Mutex<Option<Workers>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbRole {
+    Read,
+    Write,
+}
+
+struct Workers {
+    reader: mpsc::Sender<Job>,
+    writer: mpsc::Sender<Job>,
+}
+
+/// Connections owned by one worker thread, opened on first use
+pub struct DbConnections {
+    app_handle: AppHandle,
+    conversations: Option<ConversationStorage>,
+    chats: Option<ChatStorage>,
+    audit: Option<AuditLog>,
+}
+
+impl DbConnections {
+    pub fn conversations(&mut self) -> Result<&mut ConversationStorage, String> {
+        if self.conversations.is_none() {
+            let storage = ConversationStorage::new(&self.app_handle)
+                .map_err(|e| format!("Failed to initialize conversation storage: {}", e))?;
+            self.conversations = Some(storage);
+        }
+        Ok(self.conversations.as_mut().expect("conversation storage was just opened"))
+    }
+
+    pub fn chats(&mut self) -> Result<&mut ChatStorage, String> {
+        if self.chats.is_none() {
+            let storage = ChatStorage::new(&self.app_handle)
+                .map_err(|e| format!("Failed to initialize chat storage: {}", e))?;
+            self.chats = Some(storage);
+        }
+        Ok(self.chats.as_mut().expect("chat storage was just opened"))
+    }
+
+    pub fn audit(&mut self) -> Result<&mut AuditLog, String> {
+        if self.audit.is_none() {
+            self.audit = Some(AuditLog::new(&self.app_handle)?);
+        }
+        Ok(self.audit.as_mut().expect("audit log was just opened"))
+    }
+}
+
+fn spawn_worker(app_handle: &AppHandle, role: DbRole) -> mpsc::Sender<Job> {
+    let (sender, receiver) = mpsc::channel::<Job>();
+    let app_handle = app_handle.clone();
+    std::thread::Builder::new()
+        .name(format!("db-{:?}", role).to_lowercase())
+        .spawn(move || {
+            let mut connections = DbConnections { app_handle, conversations: None, chats: None, audit: None };
+            for job in receiver {
+                // A panicking job shouldn't take the worker down with it; reopen connections afterwards
+                if catch_unwind(AssertUnwindSafe(|| job(&mut connections))).is_err() {
+                    eprintln!("❌ Database {:?} job panicked; reopening connections", role);
+                    connections.conversations = None;
+                    connections.chats = None;
+                    connections.audit = None;
+                }
+            }
+        })
+        .expect("Failed to start database worker");
+    sender
+}
+
+fn sender(app_handle: &AppHandle, role: DbRole) -> Result<mpsc::Sender<Job>, String> {
+    let mut workers = WORKERS.lock().map_err(|_| "Database workers unavailable".to_string())?;
+    let workers = workers.get_or_insert_with(|| Workers {
+        reader: spawn_worker(app_handle, DbRole::Read),
+        writer: spawn_worker(app_handle, DbRole::Write),
+    });
+    Ok(match role {
+        DbRole::Read => workers.reader.clone(),
+        DbRole::Write => workers.writer.clone(),
+    })
+}
+
+/// Run a job on a database worker and wait for its result without blocking the calling thread
+pub async fn run<T, F>(app_handle: &AppHandle, role: DbRole, job: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut DbConnections) -> Result<T, String> + Send + 'static,
+{
+    let (reply, response) = tokio::sync::oneshot::channel();
+    sender(app_handle, role)?
+        .send(Box::new(move |connections| {
+            let _ = reply.send(job(connections));
+        }))
+        .map_err(|_| "Database worker has stopped".to_string())?;
+    response
+        .await
+        .map_err(|_| "Database worker dropped the request".to_string())?
+}
+
+pub async fn read<T, F>(app_handle: &AppHandle, job: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut DbConnections) -> Result<T, String> + Send + 'static,
+{
+    run(app_handle, DbRole::Read, job).await
+}
+
+pub async fn write<T, F>(app_handle: &AppHandle, job: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut DbConnections) -> Result<T, String> + Send + 'static,
+{
+    run(app_handle, DbRole::Write, job).await
+}
+
+/// Queue a write without waiting for it, for callers that can't await. Writes run in the
+/// order they're queued, so later writes and awaited writes still see this one.
+pub fn submit<F>(app_handle: &AppHandle, label: &'static str, job: F)
+where
+    F: FnOnce(&mut DbConnections) -> Result<(), String> + Send + 'static,
+{
+    let queued = sender(app_handle, DbRole::Write).and_then(|sender| {
+        sender
+            .send(Box::new(move |connections| {
+                if let Err(e) = job(connections) {
+                    eprintln!("❌ Database write '{}' failed: {}", label, e);
+                }
+            }))
+            .map_err(|_| "Database worker has stopped".to_string())
+    });
+    if let Err(e) = queued {
+        eprintln!("❌ Could not queue database write '{}': {}", label, e);
+    }
+}
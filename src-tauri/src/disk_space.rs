@@ -0,0 +1,339 @@
+// src-tauri/src/disk_space.rs
+// Disk space guard rails - free-space monitoring, per-subsystem quotas and pre-flight checks
+//
+// Recordings, Whisper model downloads and RAG documents all write into the app data directory.
+// Each of them asks `preflight` before writing, which fails with a readable error when the write
+// would eat into the reserve or push the subsystem past its quota. A background check warns the
+// frontend as free space crosses the warning and reserve thresholds.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const MB: u64 = 1024 * 1024;
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<DiskSpaceSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+    static ref LAST_LEVEL: Mutex<SpaceLevel> = Mutex::new(SpaceLevel::Ok);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Subsystem {
+    Recordings,
+    Models,
+    Rag,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 3] = [Subsystem::Recordings, Subsystem::Models, Subsystem::Rag];
+
+    fn label(self) -> &'static str {
+        match self {
+            Subsystem::Recordings => "recording",
+            Subsystem::Models => "model download",
+            Subsystem::Rag => "document upload",
+        }
+    }
+
+    // Directories under the app data directory that count towards the quota
+    fn dirs(self) -> &'static [&'static str] {
+        match self {
+            Subsystem::Recordings => &["recordings"],
+            Subsystem::Models => &["whisper_models"],
+            Subsystem::Rag => &["document_storage", "tantivy_index", "model_cache"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpaceLevel {
+    Ok,
+    /// Below the warning threshold
+    Low,
+    /// Inside the reserve; new writes are refused
+    Critical,
+}
+
+/// Quotas in megabytes; `None` means no limit beyond the reserve
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubsystemQuotas {
+    pub recordings_mb: Option<u64>,
+    pub models_mb: Option<u64>,
+    pub rag_mb: Option<u64>,
+}
+
+impl SubsystemQuotas {
+    fn bytes(&self, subsystem: Subsystem) -> Option<u64> {
+        let mb = match subsystem {
+            Subsystem::Recordings => self.recordings_mb,
+            Subsystem::Models => self.models_mb,
+            Subsystem::Rag => self.rag_mb,
+        };
+        mb.map(|mb| mb * MB)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiskSpaceSettings {
+    /// Free space no write may dip into
+    pub reserve_mb: u64,
+    /// Free space below which the frontend is warned
+    pub warning_mb: u64,
+    pub quotas: SubsystemQuotas,
+}
+
+impl Default for DiskSpaceSettings {
+    fn default() -> Self {
+        Self {
+            reserve_mb: 1024,
+            warning_mb: 5 * 1024,
+            quotas: SubsystemQuotas::default(),
+        }
+    }
+}
+
+impl DiskSpaceSettings {
+    fn validate(&self) -> Result<(), String> {
+        if self.warning_mb < self.reserve_mb {
+            return Err("The warning threshold can't be below the reserve".to_string());
+        }
+        Ok(())
+    }
+
+    fn level(&self, free_bytes: u64) -> SpaceLevel {
+        if free_bytes < self.reserve_mb * MB {
+            SpaceLevel::Critical
+        } else if free_bytes < self.warning_mb * MB {
+            SpaceLevel::Low
+        } else {
+            SpaceLevel::Ok
+        }
+    }
+
+    /// Whether writing `needed` more bytes is allowed given the disk and the subsystem's usage
+    fn check(&self, subsystem: Subsystem, needed: u64, free_bytes: u64, used_bytes: u64) -> Result<(), String> {
+        let reserve = self.reserve_mb * MB;
+        if free_bytes.saturating_sub(needed) < reserve {
+            return Err(format!(
+                "Not enough disk space for this {}: {} needed, {} free and {} is kept in reserve",
+                subsystem.label(),
+                format_size(needed),
+                format_size(free_bytes),
+                format_size(reserve)
+            ));
+        }
+        if let Some(quota) = self.quotas.bytes(subsystem) {
+            if used_bytes + needed > quota {
+                return Err(format!(
+                    "This {} would exceed its {} quota ({} already used); free up space or raise the quota in settings",
+                    subsystem.label(),
+                    format_size(quota),
+                    format_size(used_bytes)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemUsage {
+    pub subsystem: Subsystem,
+    pub used_bytes: u64,
+    pub quota_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpaceStatus {
+    pub data_dir: String,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+    pub level: SpaceLevel,
+    pub subsystems: Vec<SubsystemUsage>,
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * MB {
+        format!("{:.1} GB", bytes as f64 / (1024 * MB) as f64)
+    } else {
+        format!("{} MB", bytes.div_ceil(MB))
+    }
+}
+
+const SETTINGS_FILE: &str = "disk_space.json";
+
+fn data_dir() -> Option<&'static PathBuf> {
+    DATA_DIR.get()
+}
+
+// Free space is asked of the closest directory that exists, since a subsystem's may not yet
+fn free_space(path: &Path) -> Result<(u64, u64), String> {
+    let existing = path.ancestors().find(|dir| dir.exists()).unwrap_or(path);
+    let free = fs2::available_space(existing).map_err(|e| format!("Failed to read free disk space: {}", e))?;
+    let total = fs2::total_space(existing).unwrap_or(0);
+    Ok((free, total))
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn subsystem_usage(subsystem: Subsystem) -> u64 {
+    let Some(data_dir) = data_dir() else { return 0 };
+    subsystem.dirs().iter().map(|dir| dir_size(&data_dir.join(dir))).sum()
+}
+
+fn emit_warning(level: SpaceLevel, free_bytes: u64, message: &str, subsystem: Option<Subsystem>) {
+    let app_handle = APP_HANDLE.lock().ok().and_then(|handle| handle.clone());
+    if let Some(app_handle) = app_handle {
+        let _ = app_handle.emit("disk-space-warning", serde_json::json!({
+            "level": level,
+            "freeBytes": free_bytes,
+            "subsystem": subsystem,
+            "message": message,
+        }));
+    }
+}
+
+/// Check before writing `needed_bytes` for a subsystem. Passes when the data directory isn't
+/// known yet, since there's nothing to measure against.
+pub fn preflight(subsystem: Subsystem, needed_bytes: u64) -> Result<(), String> {
+    let Some(data_dir) = data_dir() else { return Ok(()) };
+    let (free_bytes, _) = free_space(data_dir)?;
+    let settings = read_settings(&SETTINGS);
+    let used_bytes = if settings.quotas.bytes(subsystem).is_some() { subsystem_usage(subsystem) } else { 0 };
+    settings
+        .check(subsystem, needed_bytes, free_bytes, used_bytes)
+        .inspect_err(|message| {
+            eprintln!("💾 Refused {}: {}", subsystem.label(), message);
+            emit_warning(settings.level(free_bytes), free_bytes, message, Some(subsystem));
+        })
+}
+
+fn evaluate() {
+    let Some(data_dir) = data_dir() else { return };
+    let Ok((free_bytes, _)) = free_space(data_dir) else { return };
+    let level = read_settings(&SETTINGS).level(free_bytes);
+    let Ok(mut last) = LAST_LEVEL.lock() else { return };
+    // Warn once per step down rather than every minute
+    if level > *last {
+        let message = match level {
+            SpaceLevel::Critical => format!("Disk almost full ({} free); recordings and downloads are paused", format_size(free_bytes)),
+            _ => format!("Disk space is running low ({} free)", format_size(free_bytes)),
+        };
+        emit_warning(level, free_bytes, &message, None);
+    }
+    *last = level;
+}
+
+pub fn init(app_handle: &AppHandle) {
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = DATA_DIR.set(dir);
+        }
+        Err(e) => eprintln!("💾 No app data directory, disk space checks are off: {}", e),
+    }
+    if let Ok(mut handle) = APP_HANDLE.lock() {
+        *handle = Some(app_handle.clone());
+    }
+    std::thread::spawn(|| loop {
+        evaluate();
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+#[crate::command]
+pub async fn get_disk_space_status() -> Result<DiskSpaceStatus, String> {
+    let data_dir = data_dir().cloned().ok_or("App data directory isn't available")?;
+    // Walking the RAG index can take a moment
+    tokio::task::spawn_blocking(move || {
+        let (free_bytes, total_bytes) = free_space(&data_dir)?;
+        let settings = read_settings(&SETTINGS);
+        Ok(DiskSpaceStatus {
+            data_dir: data_dir.to_string_lossy().to_string(),
+            free_bytes,
+            total_bytes,
+            level: settings.level(free_bytes),
+            subsystems: Subsystem::ALL
+                .iter()
+                .map(|subsystem| SubsystemUsage {
+                    subsystem: *subsystem,
+                    used_bytes: subsystem_usage(*subsystem),
+                    quota_bytes: settings.quotas.bytes(*subsystem),
+                })
+                .collect(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to measure disk usage: {}", e))?
+}
+
+#[crate::command]
+pub fn get_disk_space_settings() -> Result<DiskSpaceSettings, String> {
+    Ok(read_settings(&SETTINGS))
+}
+
+#[crate::command]
+pub fn save_disk_space_settings(settings: DiskSpaceSettings) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    settings.validate()?;
+    save_json_settings(SETTINGS_FILE, "disk space settings", &settings)?;
+
+    *SETTINGS
+        .write()
+        .map_err(|_| "Failed to update disk space settings".to_string())? = settings;
+    // Re-evaluate against the new thresholds straight away
+    if let Ok(mut last) = LAST_LEVEL.lock() {
+        *last = SpaceLevel::Ok;
+    }
+    evaluate();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_enforces_reserve_and_quota() {
+        let settings = DiskSpaceSettings {
+            reserve_mb: 1024,
+            warning_mb: 4096,
+            quotas: SubsystemQuotas { models_mb: Some(2048), ..Default::default() },
+        };
+        let gb = 1024 * MB;
+
+        assert_eq!(settings.level(10 * gb), SpaceLevel::Ok);
+        assert_eq!(settings.level(3 * gb), SpaceLevel::Low);
+        assert_eq!(settings.level(gb / 2), SpaceLevel::Critical);
+
+        assert!(settings.check(Subsystem::Recordings, 500 * MB, 2 * gb, 0).is_ok());
+        // Would leave less than the reserve
+        assert!(settings.check(Subsystem::Recordings, 1500 * MB, 2 * gb, 0).is_err());
+        // Plenty of disk, but over the models quota
+        let err = settings.check(Subsystem::Models, 600 * MB, 50 * gb, 1500 * MB).unwrap_err();
+        assert!(err.contains("quota"));
+        assert!(settings.check(Subsystem::Rag, 600 * MB, 50 * gb, 1500 * MB).is_ok());
+    }
+}
@@ -0,0 +1,126 @@
+// src-tauri/src/embedding_worker.rs
+// Background embedding worker - drains the `processing_queue` table a few documents at a time
+// instead of spawning a task per upload.
+//
+// One dispatcher claims the next due task whenever fewer than the configured number are running;
+// it wakes when something is queued or a task finishes, and polls so retries come due. A failed
+// task goes back to the queue with an exponentially growing delay until it runs out of attempts.
+// Tasks left `processing` by a crash are put back to `pending` at startup.
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+pub const DEFAULT_CONCURRENCY: usize = 2;
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+// Longest the dispatcher sleeps before checking for retries that came due
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(10);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(15 * 60);
+// Window completions are counted over for throughput
+pub const THROUGHPUT_WINDOW_MINUTES: i64 = 10;
+
+pub fn default_concurrency() -> usize {
+    DEFAULT_CONCURRENCY
+}
+
+pub fn default_max_attempts() -> u32 {
+    DEFAULT_MAX_ATTEMPTS
+}
+
+/// How long to wait before the next try of a task that has failed `attempts` times
+pub fn retry_delay(attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    RETRY_BASE_DELAY.saturating_mul(factor).min(MAX_RETRY_DELAY)
+}
+
+/// A queued task claimed by the dispatcher
+#[derive(Debug, Clone)]
+pub struct QueueTask {
+    pub id: String,
+    pub document_id: String,
+    /// Failed tries before this one
+    pub attempts: u32,
+}
+
+/// Shared between the dispatcher and whoever queues work
+#[derive(Clone, Default)]
+pub struct EmbeddingWorker {
+    wake: Arc<Notify>,
+    running: Arc<AtomicUsize>,
+}
+
+impl EmbeddingWorker {
+    /// Tell the dispatcher there may be work to claim
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    pub async fn wait(&self) {
+        tokio::select! {
+            _ = self.wake.notified() => {}
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+    }
+
+    pub fn running(&self) -> usize {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Count a task as running until the guard drops, which wakes the dispatcher
+    pub fn start_task(&self) -> RunningTask {
+        self.running.fetch_add(1, Ordering::SeqCst);
+        RunningTask { worker: self.clone() }
+    }
+}
+
+pub struct RunningTask {
+    worker: EmbeddingWorker,
+}
+
+impl Drop for RunningTask {
+    fn drop(&mut self) {
+        self.worker.running.fetch_sub(1, Ordering::SeqCst);
+        self.worker.wake();
+    }
+}
+
+/// Depth and throughput of the embedding queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessingQueueStatus {
+    /// Waiting to run, retries included
+    pub pending: i64,
+    /// Pending tasks that have failed before and are waiting out their backoff
+    pub retrying: i64,
+    pub processing: i64,
+    /// Gave up after the last attempt
+    pub failed: i64,
+    pub completed_last_hour: i64,
+    /// Documents embedded per minute over the last ten minutes
+    pub documents_per_minute: f64,
+    pub concurrency: usize,
+    pub oldest_pending_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(1), Duration::from_secs(10));
+        assert_eq!(retry_delay(2), Duration::from_secs(20));
+        assert_eq!(retry_delay(4), Duration::from_secs(80));
+        assert_eq!(retry_delay(30), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(u32::MAX), MAX_RETRY_DELAY);
+
+        let worker = EmbeddingWorker::default();
+        let task = worker.start_task();
+        assert_eq!(worker.running(), 1);
+        drop(task);
+        assert_eq!(worker.running(), 0);
+    }
+}
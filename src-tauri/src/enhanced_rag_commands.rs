@@ -1,14 +1,17 @@
-use crate::enhanced_rag_system::{EnhancedRagSystem, EnhancedDocument, EnhancedDocumentChunk, EnhancedRagSettings};
+use crate::enhanced_rag_system::{EnhancedRagSystem, EnhancedDocument, EnhancedDocumentChunk, EnhancedRagSettings, BulkOperationResult, DocumentUpdateResult, DocumentCollection, EmbeddingMigrationStatus};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use tauri::{Emitter, State};
+use crate::app_lock::{require_unlocked, CommandGroup};
+use crate::embedding_worker::ProcessingQueueStatus;
+use crate::search_service::SearchIndexStats;
 
 // Global enhanced RAG system instance
 #[derive(Clone)]
 pub struct EnhancedRagSystemState(pub Arc<Mutex<Option<EnhancedRagSystem>>>);
 
-#[tauri::command]
+#[crate::command]
 pub async fn initialize_enhanced_rag_system(
     app_handle: tauri::AppHandle,
     state: State<'_, EnhancedRagSystemState>,
@@ -24,6 +27,9 @@ pub async fn initialize_enhanced_rag_system(
     // Initialize new system
     match EnhancedRagSystem::new(&app_handle).await {
         Ok(system) => {
+            if let Err(e) = crate::folder_watch::start(&app_handle, &system) {
+                eprintln!("{}", e);
+            }
             let mut rag_state = state.0.lock().map_err(|e| e.to_string())?;
             *rag_state = Some(system);
             Ok("Enhanced RAG system initialized successfully".to_string())
@@ -32,8 +38,18 @@ pub async fn initialize_enhanced_rag_system(
     }
 }
 
-#[tauri::command]
+/// Payload of the `enhanced-document-ocr-progress` event
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OcrProgress<'a> {
+    file_name: &'a str,
+    completed: usize,
+    total: usize,
+}
+
+#[crate::command]
 pub async fn upload_enhanced_document(
+    app_handle: tauri::AppHandle,
     file_name: String,
     file_content: Vec<u8>,
     file_type: String,
@@ -46,13 +62,139 @@ pub async fn upload_enhanced_document(
             None => Err("Enhanced RAG system not initialized".to_string())
         }
     }?;
+    crate::disk_space::preflight(crate::disk_space::Subsystem::Rag, file_content.len() as u64)?;
+    
+    let ocr_file_name = file_name.clone();
+    let ocr_progress = move |completed, total| {
+        let _ = app_handle.emit("enhanced-document-ocr-progress", OcrProgress { file_name: &ocr_file_name, completed, total });
+    };
+    system.upload_document(file_name, file_content, file_type, ocr_progress)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Fetch a web page and store its article text (boilerplate stripped) as a document
+#[crate::command]
+pub async fn ingest_url(
+    url: String,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<EnhancedDocument, String> {
+    crate::offline_mode::require_online(crate::offline_mode::RemoteFeature::WebIngestion)?;
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+    
+    let max_bytes = (system.get_settings().max_document_size_mb * 1024.0 * 1024.0) as usize;
+    let (final_url, html) = crate::web_ingest::fetch(url.trim(), max_bytes).await?;
+    let article = crate::web_ingest::extract_article(&html);
+    if article.text.trim().is_empty() {
+        return Err(format!("No readable article text found at {}", final_url));
+    }
+    crate::disk_space::preflight(crate::disk_space::Subsystem::Rag, article.text.len() as u64)?;
+    
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("source".to_string(), Value::from("web"));
+    metadata.insert("url".to_string(), Value::from(final_url.clone()));
+    if let Some(title) = &article.title {
+        metadata.insert("title".to_string(), Value::from(title.clone()));
+    }
+    metadata.insert("fetchedAt".to_string(), Value::from(chrono::Utc::now().to_rfc3339()));
+    let file_name = crate::web_ingest::file_name(article.title.as_deref(), &final_url);
+    system.upload_text_document(file_name, article.text, metadata)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Store a finished conversation's transcript as a document, or refresh the one stored before
+#[crate::command]
+pub async fn index_conversation(
+    app_handle: tauri::AppHandle,
+    session_id: String,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<EnhancedDocument, String> {
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
     
-    system.upload_document(file_name, file_content, file_type)
+    let id = session_id.clone();
+    let session = crate::data::worker::read(&app_handle, move |db| {
+        db.conversations()?
+            .load_conversation(&id)
+            .map_err(|e| format!("Failed to load conversation: {}", e))
+    }).await?
+    .ok_or_else(|| format!("Conversation {} not found", session_id))?;
+    if session.is_active {
+        return Err("The conversation is still being recorded".to_string());
+    }
+    let text = crate::transcript_ingest::transcript_text(&session);
+    if text.trim().is_empty() {
+        return Err("The conversation has no transcript to index".to_string());
+    }
+    crate::disk_space::preflight(crate::disk_space::Subsystem::Rag, text.len() as u64)?;
+    
+    let metadata = crate::transcript_ingest::metadata(&session);
+    let existing = system.find_document_by_metadata("conversationId", &session_id).map_err(|e| e.to_string())?;
+    match existing {
+        Some(document_id) => system.update_text_document(&document_id, &text, metadata).await,
+        None => system.upload_text_document(crate::transcript_ingest::file_name(&session), text, metadata).await,
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Replace a document's text, re-embedding and re-indexing only the chunks that changed
+#[crate::command]
+pub async fn update_enhanced_document(
+    document_id: String,
+    new_content: String,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<DocumentUpdateResult, String> {
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+    crate::disk_space::preflight(crate::disk_space::Subsystem::Rag, new_content.len() as u64)?;
+    
+    system.update_document(&document_id, &new_content)
         .await
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+/// Replace a stored document with a new version of its file, e.g. an edited PDF flagged as a
+/// near duplicate on upload
+#[crate::command]
+pub async fn replace_enhanced_document(
+    document_id: String,
+    file_name: String,
+    file_content: Vec<u8>,
+    file_type: String,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<DocumentUpdateResult, String> {
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+    crate::disk_space::preflight(crate::disk_space::Subsystem::Rag, file_content.len() as u64)?;
+
+    system.replace_document(&document_id, file_name, file_content, file_type)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[crate::command]
 pub async fn get_all_enhanced_documents(
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<Vec<EnhancedDocument>, String> {
@@ -67,11 +209,12 @@ pub async fn get_all_enhanced_documents(
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn delete_enhanced_document(
     document_id: String,
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<String, String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
     let system = {
         let rag_state = state.0.lock().map_err(|e| e.to_string())?;
         match &*rag_state {
@@ -87,10 +230,168 @@ pub async fn delete_enhanced_document(
     Ok(format!("Document {} deleted successfully", document_id))
 }
 
-#[tauri::command]
+/// Payload of the `enhanced-document-bulk-progress` event
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BulkProgress<'a> {
+    operation_id: &'a str,
+    action: &'a str,
+    document_id: &'a str,
+    completed: usize,
+    total: usize,
+}
+
+fn bulk_progress<'a>(app_handle: &'a tauri::AppHandle, operation_id: &'a str, action: &'a str) -> impl Fn(usize, usize, &str) + Send + Sync + 'a {
+    move |completed, total, document_id| {
+        let _ = app_handle.emit("enhanced-document-bulk-progress", BulkProgress {
+            operation_id,
+            action,
+            document_id,
+            completed,
+            total,
+        });
+    }
+}
+
+fn bulk_operation_id(document_ids: &[String], operation_id: Option<String>) -> Result<String, String> {
+    if document_ids.is_empty() {
+        return Err("No documents selected".to_string());
+    }
+    Ok(operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()))
+}
+
+/// Delete several documents with one index commit; progress goes out as
+/// `enhanced-document-bulk-progress` events tagged with `operation_id`
+#[crate::command]
+pub async fn bulk_delete_enhanced_documents(
+    app_handle: tauri::AppHandle,
+    document_ids: Vec<String>,
+    operation_id: Option<String>,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<BulkOperationResult, String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
+    let operation_id = bulk_operation_id(&document_ids, operation_id)?;
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+    
+    system.delete_documents(&operation_id, &document_ids, bulk_progress(&app_handle, &operation_id, "delete"))
+        .map_err(|e| e.to_string())
+}
+
+/// Regenerate embeddings for several documents, saving them in one transaction and one index
+/// commit. Cancellable with `cancel_operation(operation_id)`.
+#[crate::command]
+pub async fn bulk_reembed_enhanced_documents(
+    app_handle: tauri::AppHandle,
+    document_ids: Vec<String>,
+    operation_id: Option<String>,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<BulkOperationResult, String> {
+    let operation_id = bulk_operation_id(&document_ids, operation_id)?;
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+    
+    let operation = crate::cancellation::start(
+        &operation_id,
+        crate::cancellation::OperationKind::Embedding,
+        format!("re-embed {} documents", document_ids.len()),
+    );
+    system.reembed_documents(&operation_id, &document_ids, operation.token(), bulk_progress(&app_handle, &operation_id, "reembed"))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Rebuild the vector search index from the stored chunk embeddings
+#[crate::command]
+pub async fn rebuild_vector_index(
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<usize, String> {
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+    
+    tokio::task::spawn_blocking(move || system.rebuild_vector_index())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Merge the search index's segments and drop deleted chunks
+#[crate::command]
+pub async fn compact_search_index(
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<SearchIndexStats, String> {
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+    
+    tokio::task::spawn_blocking(move || system.compact_search_index())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[crate::command]
+pub async fn get_search_index_stats(
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<SearchIndexStats, String> {
+    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    
+    match &*rag_state {
+        Some(system) => system.get_search_index_stats().map_err(|e| e.to_string()),
+        None => Err("Enhanced RAG system not initialized".to_string())
+    }
+}
+
+/// Add and remove tags on several documents at once
+#[crate::command]
+pub async fn bulk_tag_enhanced_documents(
+    app_handle: tauri::AppHandle,
+    document_ids: Vec<String>,
+    add_tags: Vec<String>,
+    remove_tags: Vec<String>,
+    operation_id: Option<String>,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<BulkOperationResult, String> {
+    let operation_id = bulk_operation_id(&document_ids, operation_id)?;
+    if add_tags.iter().chain(&remove_tags).all(|tag| tag.trim().is_empty()) {
+        return Err("No tags to add or remove".to_string());
+    }
+    let system = {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+    
+    system.tag_documents(&operation_id, &document_ids, &add_tags, &remove_tags, bulk_progress(&app_handle, &operation_id, "tag"))
+        .map_err(|e| e.to_string())
+}
+
+#[crate::command]
 pub async fn search_enhanced_documents(
     query: String,
     context_document_ids: Vec<String>,
+    collection_id: Option<String>,
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<Vec<EnhancedDocumentChunk>, String> {
     let system = {
@@ -101,12 +402,78 @@ pub async fn search_enhanced_documents(
         }
     }?;
     
-    system.search_documents(&query, context_document_ids)
+    system.search_documents(&query, context_document_ids, collection_id.as_deref())
         .await
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[crate::command]
+pub async fn create_enhanced_collection(
+    name: String,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<DocumentCollection, String> {
+    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    match &*rag_state {
+        Some(system) => system.create_collection(&name).map_err(|e| e.to_string()),
+        None => Err("Enhanced RAG system not initialized".to_string())
+    }
+}
+
+#[crate::command]
+pub async fn rename_enhanced_collection(
+    collection_id: String,
+    name: String,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<(), String> {
+    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    match &*rag_state {
+        Some(system) => system.rename_collection(&collection_id, &name).map_err(|e| e.to_string()),
+        None => Err("Enhanced RAG system not initialized".to_string())
+    }
+}
+
+/// Delete a collection; its documents stay, in no collection
+#[crate::command]
+pub async fn delete_enhanced_collection(
+    collection_id: String,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<(), String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
+    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    match &*rag_state {
+        Some(system) => system.delete_collection(&collection_id).map_err(|e| e.to_string()),
+        None => Err("Enhanced RAG system not initialized".to_string())
+    }
+}
+
+#[crate::command]
+pub async fn list_enhanced_collections(
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<Vec<DocumentCollection>, String> {
+    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    match &*rag_state {
+        Some(system) => system.list_collections().map_err(|e| e.to_string()),
+        None => Err("Enhanced RAG system not initialized".to_string())
+    }
+}
+
+/// Move documents into a collection, or out of any with no `collection_id`
+#[crate::command]
+pub async fn assign_documents_to_collection(
+    document_ids: Vec<String>,
+    collection_id: Option<String>,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<usize, String> {
+    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    match &*rag_state {
+        Some(system) => system
+            .assign_documents_to_collection(&document_ids, collection_id.as_deref())
+            .map_err(|e| e.to_string()),
+        None => Err("Enhanced RAG system not initialized".to_string())
+    }
+}
+
+#[crate::command]
 pub async fn generate_enhanced_embeddings(
     document_id: String,
     state: State<'_, EnhancedRagSystemState>,
@@ -124,10 +491,11 @@ pub async fn generate_enhanced_embeddings(
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn clear_enhanced_embedding_cache(
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<String, String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
     let system = {
         let rag_state = state.0.lock().map_err(|e| e.to_string())?;
         match &*rag_state {
@@ -141,11 +509,12 @@ pub async fn clear_enhanced_embedding_cache(
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn update_enhanced_rag_settings(
     settings: EnhancedRagSettings,
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<String, String> {
+    require_unlocked(CommandGroup::SettingsChanges)?;
     let rag_state = state.0.lock().map_err(|e| e.to_string())?;
     
     match &*rag_state {
@@ -158,7 +527,7 @@ pub async fn update_enhanced_rag_settings(
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_enhanced_rag_settings(
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<EnhancedRagSettings, String> {
@@ -172,7 +541,7 @@ pub async fn get_enhanced_rag_settings(
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_enhanced_storage_stats(
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<HashMap<String, Value>, String> {
@@ -187,7 +556,40 @@ pub async fn get_enhanced_storage_stats(
     }
 }
 
-#[tauri::command]
+/// How many documents are waiting for, being given or failed to get embeddings, and how fast
+/// the worker is getting through them
+#[crate::command]
+pub async fn get_processing_queue_status(
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<ProcessingQueueStatus, String> {
+    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    
+    match &*rag_state {
+        Some(system) => {
+            system.get_processing_queue_status()
+                .map_err(|e| e.to_string())
+        }
+        None => Err("Enhanced RAG system not initialized".to_string())
+    }
+}
+
+/// Which embedding model is in use and how many documents still carry another model's embeddings
+#[crate::command]
+pub async fn get_embedding_migration_status(
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<EmbeddingMigrationStatus, String> {
+    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    
+    match &*rag_state {
+        Some(system) => {
+            system.get_embedding_migration_status()
+                .map_err(|e| e.to_string())
+        }
+        None => Err("Enhanced RAG system not initialized".to_string())
+    }
+}
+
+#[crate::command]
 pub async fn get_embedding_status(
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<HashMap<String, Value>, String> {
@@ -217,10 +619,11 @@ pub async fn get_embedding_status(
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn check_document_duplicate(
     file_name: String,
     file_content: Vec<u8>,
+    file_type: Option<String>,
     state: State<'_, EnhancedRagSystemState>,
 ) -> Result<HashMap<String, Value>, String> {
     use sha2::{Sha256, Digest};
@@ -248,6 +651,26 @@ pub async fn check_document_duplicate(
         }
         Ok(None) => {
             result.insert("is_duplicate".to_string(), serde_json::json!(false));
+            // Not byte-identical; it may still be an edited version of a stored document
+            let file_type = file_type
+                .filter(|file_type| !file_type.is_empty())
+                .or_else(|| crate::folder_watch::file_type(std::path::Path::new(&file_name)).map(str::to_string))
+                .unwrap_or_else(|| "text/plain".to_string());
+            match system.check_near_duplicate(&file_name, &file_content, &file_type).await {
+                Ok(Some(near)) => {
+                    result.insert("is_near_duplicate".to_string(), serde_json::json!(true));
+                    result.insert("similarity".to_string(), serde_json::json!(near.similarity));
+                    result.insert("near_duplicate_document".to_string(), serde_json::to_value(near.document).unwrap());
+                }
+                Ok(None) => {
+                    result.insert("is_near_duplicate".to_string(), serde_json::json!(false));
+                }
+                // Text that can't be extracted fails the upload itself; don't block on it here
+                Err(e) => {
+                    eprintln!("Near-duplicate check failed for {}: {}", file_name, e);
+                    result.insert("is_near_duplicate".to_string(), serde_json::json!(false));
+                }
+            }
         }
         Err(e) => {
             return Err(format!("Failed to check duplicate: {}", e));
@@ -257,7 +680,7 @@ pub async fn check_document_duplicate(
     Ok(result)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_document_embedding_status(
     document_ids: Vec<String>,
     state: State<'_, EnhancedRagSystemState>,
@@ -274,7 +697,7 @@ pub async fn get_document_embedding_status(
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn ensure_documents_ready_for_search(
     document_ids: Vec<String>,
     state: State<'_, EnhancedRagSystemState>,
@@ -292,7 +715,7 @@ pub async fn ensure_documents_ready_for_search(
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn generate_embeddings_for_selection(
     document_ids: Vec<String>,
     state: State<'_, EnhancedRagSystemState>,
@@ -310,7 +733,7 @@ pub async fn generate_embeddings_for_selection(
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn validate_enhanced_file_upload(
     file_name: String,
     file_size: usize,
@@ -329,7 +752,10 @@ pub async fn validate_enhanced_file_upload(
             
             // Check supported file types
             let supported_types = vec!["text/plain", "application/pdf", "text/markdown", 
-                                     "application/msword", "application/vnd.openxmlformats-officedocument.wordprocessingml.document"];
+                                     "application/msword", "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                                     "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                                     "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+                                     "application/epub+zip", "image/png", "image/jpeg"];
             let type_valid = supported_types.iter().any(|&t| file_type.contains(t)) || file_type.starts_with("text/");
             
             validation.insert("valid".to_string(), serde_json::json!(size_valid && type_valid));
@@ -1,64 +1,160 @@
 use anyhow::{Result, anyhow};
 use rusqlite::{Connection, params, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use std::fs;
 use chrono::Utc;
 use uuid::Uuid;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use sha2::{Sha256, Digest};
 
-use crate::simple_embedding_service::{SimpleEmbeddingService as EmbeddingService, EmbeddingConfig};
-use crate::search_service::{SearchService, SearchConfig, SearchResult};
-use crate::chunking_service::{ChunkingService, ChunkingConfig, TextChunk, extract_text_from_pdf, clean_text};
+use crate::simple_embedding_service::{SimpleEmbeddingService, EmbeddingConfig, EmbeddingProvider, EmbeddingProviderKind, cosine_similarity};
+use crate::ollama_embedding_service::OllamaEmbeddingProvider;
+use crate::search_service::{SearchService, SearchConfig, SearchResult, SearchIndexStats};
+use crate::near_duplicate;
+use crate::storage_quota::{self, CachedDocument, EvictionEvent, QuotaEvent};
+use crate::embedding_worker::{EmbeddingWorker, ProcessingQueueStatus, QueueTask, retry_delay, THROUGHPUT_WINDOW_MINUTES};
+use crate::chunking_service::{
+    ChunkingService, ChunkingConfig, ChunkingStrategy, TextChunk, extract_text_from_pdf, extract_text_from_docx, extract_text_from_xlsx,
+    extract_text_from_pptx, extract_text_from_epub, extract_pages_from_pdf, pdf_pages, clean_pages,
+};
+pub use crate::shared_types::documents::{EnhancedDocument, EnhancedDocumentChunk};
 
+// Chunks embedded between cancellation checks
+const EMBEDDING_BATCH_SIZE: usize = 16;
+
+/// Search results that cleared the relevance thresholds
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct EnhancedDocument {
-    pub id: String,
-    pub file_name: String,
-    pub file_path: String,
-    pub file_type: String,
-    pub file_size: i64,
-    pub content: String,
-    pub created_at: String,
-    pub updated_at: String,
-    pub access_count: i32,
-    pub last_accessed: Option<String>,
-    pub is_cached: bool,
-    pub embedding_status: String, // "pending", "processing", "completed", "failed"
-    pub chunk_count: i32,
-    pub metadata: Option<String>,
-    pub content_hash: Option<String>,
+pub struct RelevantChunks {
+    pub chunks: Vec<EnhancedDocumentChunk>,
+    /// Nothing cleared the thresholds, so the documents don't answer the query
+    pub insufficient_context: bool,
+    /// Matches dropped for scoring below the thresholds
+    pub below_threshold: usize,
 }
 
+/// What a bulk delete, re-embed or tag did to each document it was given
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct EnhancedDocumentChunk {
-    pub id: String,
+pub struct BulkOperationResult {
+    pub operation_id: String,
+    pub succeeded: Vec<String>,
+    pub failed: Vec<BulkFailure>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BulkFailure {
     pub document_id: String,
-    pub chunk_index: i32,
-    pub content: String,
-    pub start_char: i32,
-    pub end_char: i32,
-    pub token_count: i32,
-    pub embedding: Option<Vec<f32>>,
-    pub similarity_score: Option<f32>,
-    pub bm25_score: Option<f32>,
-    pub metadata: Option<String>,
+    pub error: String,
+}
+
+impl BulkOperationResult {
+    fn new(operation_id: &str) -> Self {
+        Self { operation_id: operation_id.to_string(), succeeded: Vec::new(), failed: Vec::new() }
+    }
+
+    fn fail(&mut self, document_id: &str, error: impl Into<String>) {
+        self.failed.push(BulkFailure { document_id: document_id.to_string(), error: error.into() });
+    }
+}
+
+/// `ids` without repeats, in their original order
+fn unique_ids(ids: &[String]) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for id in ids {
+        if !unique.contains(id) {
+            unique.push(id.clone());
+        }
+    }
+    unique
+}
+
+fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Pair new chunks with old ones of the same content: for each new chunk hash, the id of the old
+/// chunk it can keep (each old chunk used once), plus the old ids nothing kept
+fn match_chunks(old: &[(String, String)], new: &[String]) -> (Vec<Option<String>>, Vec<String>) {
+    let mut unused: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (id, hash) in old.iter().rev() {
+        unused.entry(hash.as_str()).or_default().push(id.as_str());
+    }
+    let kept: Vec<Option<String>> = new
+        .iter()
+        .map(|hash| unused.get_mut(hash.as_str()).and_then(|ids| ids.pop()).map(str::to_string))
+        .collect();
+    let removed = old
+        .iter()
+        .filter(|(id, _)| !kept.iter().flatten().any(|kept| kept == id))
+        .map(|(id, _)| id.clone())
+        .collect();
+    (kept, removed)
+}
+
+#[cfg(feature = "mcp")]
+async fn ocr_image(image: &[u8]) -> Result<String> {
+    crate::mcp::tools::ocr_image_text(image).await.map_err(|e| anyhow!("OCR failed: {}", e))
+}
+
+#[cfg(not(feature = "mcp"))]
+async fn ocr_image(_image: &[u8]) -> Result<String> {
+    Err(anyhow!("OCR isn't available in this build"))
+}
+
+fn embedding_bytes(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+/// A document's metadata JSON with `add` tags added and `remove` tags removed, matched
+/// case-insensitively. Tags live under `"tags"`; other metadata keys are kept.
+fn apply_tags(metadata: Option<&str>, add: &[String], remove: &[String]) -> Result<String> {
+    let mut object = match metadata.map(str::trim).filter(|metadata| !metadata.is_empty()) {
+        Some(metadata) => match serde_json::from_str::<serde_json::Value>(metadata)? {
+            serde_json::Value::Object(object) => object,
+            _ => return Err(anyhow!("Document metadata isn't a JSON object")),
+        },
+        None => serde_json::Map::new(),
+    };
+    let mut tags: Vec<String> = object
+        .get("tags")
+        .and_then(|tags| tags.as_array())
+        .map(|tags| tags.iter().filter_map(|tag| tag.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    tags.retain(|tag| !remove.iter().any(|removed| removed.trim().eq_ignore_ascii_case(tag)));
+    for tag in add.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    object.insert("tags".to_string(), serde_json::json!(tags));
+    Ok(serde_json::Value::Object(object).to_string())
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EnhancedRagSettings {
     pub max_document_size_mb: f64,
+    /// Quota for stored files, checked on upload; 0 for none
     pub max_collection_size_gb: f64,
+    /// Documents that keep their embeddings; the least recently used beyond this are evicted. 0 for no limit
     pub max_cached_documents: usize,
+    /// Compress the original files of evicted documents, and of the least recently used ones
+    /// when an upload doesn't fit in the quota
+    #[serde(default)]
+    pub archive_evicted_files: bool,
     pub auto_embedding: bool,
     pub background_processing: bool,
     pub reranking_enabled: bool,
     pub chunking_config: ChunkingConfig,
     pub embedding_config: EmbeddingConfig,
     pub search_config: SearchConfig,
+    /// Documents the background worker embeds at once
+    #[serde(default = "crate::embedding_worker::default_concurrency")]
+    pub embedding_concurrency: usize,
+    /// Tries per queued document before it's marked failed
+    #[serde(default = "crate::embedding_worker::default_max_attempts")]
+    pub embedding_max_attempts: u32,
 }
 
 impl Default for EnhancedRagSettings {
@@ -67,16 +163,27 @@ impl Default for EnhancedRagSettings {
             max_document_size_mb: 50.0,
             max_collection_size_gb: 2.0,
             max_cached_documents: 10,
+            archive_evicted_files: false,
             auto_embedding: true,
             background_processing: true,
             reranking_enabled: false, // Disabled by default for performance
             chunking_config: ChunkingConfig::default(),
             embedding_config: EmbeddingConfig::default(),
             search_config: SearchConfig::default(),
+            embedding_concurrency: crate::embedding_worker::DEFAULT_CONCURRENCY,
+            embedding_max_attempts: crate::embedding_worker::DEFAULT_MAX_ATTEMPTS,
         }
     }
 }
 
+/// The embedding provider `config` selects
+fn embedding_provider(cache_path: PathBuf, config: &EmbeddingConfig) -> Arc<dyn EmbeddingProvider> {
+    match config.provider {
+        EmbeddingProviderKind::Simple => Arc::new(SimpleEmbeddingService::new(cache_path, Some(config.clone()))),
+        EmbeddingProviderKind::Ollama => Arc::new(OllamaEmbeddingProvider::new(config)),
+    }
+}
+
 #[derive(Clone)]
 pub struct EnhancedRagSystem {
     db_path: PathBuf,
@@ -84,9 +191,63 @@ pub struct EnhancedRagSystem {
     index_path: PathBuf,
     cache_path: PathBuf,
     settings: Arc<Mutex<EnhancedRagSettings>>,
-    embedding_service: Arc<EmbeddingService>,
+    // Swapped when the embedding config changes
+    embedding_service: Arc<RwLock<Arc<dyn EmbeddingProvider>>>,
     search_service: Arc<SearchService>,
     chunking_service: Arc<Mutex<ChunkingService>>,
+    embedding_worker: EmbeddingWorker,
+    // Storage events for the UI
+    app_handle: tauri::AppHandle,
+}
+
+/// What updating a document's content changed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentUpdateResult {
+    pub document: EnhancedDocument,
+    /// Chunks whose content was unchanged and kept their embedding
+    pub unchanged_chunks: usize,
+    /// Chunks that are new or changed; embedded now, or queued when the embedder isn't ready
+    pub changed_chunks: usize,
+    pub removed_chunks: usize,
+}
+
+/// A named group of documents that search can be scoped to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentCollection {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub document_count: i64,
+}
+
+/// Progress moving stored embeddings to the current embedding model
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbeddingMigrationStatus {
+    pub model: String,
+    /// Documents whose embeddings all came from the current model
+    pub current_documents: i64,
+    /// Documents with embeddings from another model, waiting to be re-embedded
+    pub stale_documents: i64,
+    pub vector_count: usize,
+}
+
+/// A stored document an upload looks like a version of
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NearDuplicate {
+    pub document: EnhancedDocument,
+    /// Estimated share of word shingles the two texts have in common, 0.0 to 1.0
+    pub similarity: f64,
+}
+
+/// A document imported from a watched folder
+#[derive(Debug, Clone)]
+pub struct SourceDocument {
+    pub id: String,
+    pub source_path: String,
+    /// SHA-256 of the file as last imported
+    pub source_hash: Option<String>,
+    pub deleted: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -112,11 +273,11 @@ impl EnhancedRagSystem {
         
         let settings = Arc::new(Mutex::new(EnhancedRagSettings::default()));
         
-        // Initialize services
-        let embedding_service = Arc::new(EmbeddingService::new(
-            cache_path.clone(), 
-            Some(settings.lock().unwrap().embedding_config.clone())
-        ));
+        // Initialize services; the embedding provider is replaced once stored settings are loaded
+        let embedding_service = Arc::new(RwLock::new(embedding_provider(
+            cache_path.clone(),
+            &settings.lock().unwrap().embedding_config,
+        )));
         
         let search_service = Arc::new(SearchService::new(
             index_path.clone(),
@@ -136,6 +297,8 @@ impl EnhancedRagSystem {
             embedding_service,
             search_service,
             chunking_service,
+            embedding_worker: EmbeddingWorker::default(),
+            app_handle: app_handle.clone(),
         };
         
         // Initialize database and services
@@ -143,16 +306,111 @@ impl EnhancedRagSystem {
         system.search_service.initialize_writer()?;
         
         // Initialize embedding service in background
-        let embedding_service_clone = system.embedding_service.clone();
+        system.start_embedding_provider(&system.get_settings().embedding_config);
+        system.start_embedding_worker();
+        
+        // Embeddings from a model used before, or saved before the vector index existed, or with
+        // a lost index file
+        let rebuilding = system.clone();
+        tokio::task::spawn_blocking(move || {
+            if rebuilding.search_service.recovered() {
+                match rebuilding.rebuild_search_index() {
+                    Ok(count) => println!("Search index rebuilt with {} chunks", count),
+                    Err(e) => eprintln!("Failed to rebuild search index: {}", e),
+                }
+            }
+            if let Err(e) = rebuilding.migrate_embeddings() {
+                eprintln!("Failed to check embeddings against the current model: {}", e);
+            }
+            if rebuilding.search_service.vector_count() == 0 {
+                match rebuilding.rebuild_vector_index() {
+                    Ok(0) => {}
+                    Ok(count) => println!("Vector index rebuilt with {} chunks", count),
+                    Err(e) => eprintln!("Failed to rebuild vector index: {}", e),
+                }
+            }
+        });
+        
+        Ok(system)
+    }
+    
+    fn embedder(&self) -> Arc<dyn EmbeddingProvider> {
+        self.embedding_service.read().unwrap().clone()
+    }
+    
+    fn embedding_model(&self) -> String {
+        self.embedder().model_id()
+    }
+    
+    /// Re-embed documents whose embeddings came from another model. Their vectors leave the
+    /// vector index now, but their chunks stay in the BM25 index until the worker replaces them,
+    /// so they remain searchable by keyword meanwhile. Returns how many documents were queued.
+    pub fn migrate_embeddings(&self) -> Result<usize> {
+        let model = self.embedding_model();
+        let conn = Connection::open(&self.db_path)?;
+        // Embeddings from before models were recorded are taken to be the current model's
+        conn.execute(
+            "UPDATE enhanced_document_chunks SET embedding_model = ?1, embedding_dim = length(embedding) / 4
+             WHERE embedding IS NOT NULL AND embedding_model IS NULL",
+            params![model],
+        )?;
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT c.document_id FROM enhanced_document_chunks c
+             JOIN enhanced_documents d ON d.id = c.document_id
+             WHERE c.embedding IS NOT NULL AND c.embedding_model != ?1 AND d.deleted_at IS NULL"
+        )?;
+        let stale = stmt.query_map([&model], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+        if stale.is_empty() {
+            return Ok(0);
+        }
+        
+        let indexed = self.rebuild_vector_index()?;
+        for document_id in &stale {
+            conn.execute(
+                "UPDATE enhanced_documents SET embedding_status = 'pending', is_cached = 0 WHERE id = ?1",
+                params![document_id],
+            )?;
+            self.enqueue_embedding(document_id, "embedding_migration", 0)?;
+        }
+        println!("Embedding model is now {}: re-embedding {} documents ({} chunks still indexed by vector)", model, stale.len(), indexed);
+        Ok(stale.len())
+    }
+    
+    /// How many documents are embedded by the current model and how many still need re-embedding
+    pub fn get_embedding_migration_status(&self) -> Result<EmbeddingMigrationStatus> {
+        let model = self.embedding_model();
+        let conn = Connection::open(&self.db_path)?;
+        let (current_documents, stale_documents): (i64, i64) = conn.query_row(
+            "SELECT COALESCE(SUM(stale = 0), 0), COALESCE(SUM(stale > 0), 0) FROM (
+                 SELECT c.document_id, SUM(c.embedding_model IS NOT ?1) AS stale
+                 FROM enhanced_document_chunks c
+                 JOIN enhanced_documents d ON d.id = c.document_id
+                 WHERE c.embedding IS NOT NULL AND d.deleted_at IS NULL
+                 GROUP BY c.document_id
+             )",
+            params![model],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(EmbeddingMigrationStatus {
+            model,
+            current_documents,
+            stale_documents,
+            vector_count: self.search_service.vector_count(),
+        })
+    }
+    
+    /// Switch to the provider `config` selects and initialize it in the background. Chunks
+    /// embedded by a different provider or model need re-embedding to be searchable by vector.
+    fn start_embedding_provider(&self, config: &EmbeddingConfig) {
+        let provider = embedding_provider(self.cache_path.clone(), config);
+        *self.embedding_service.write().unwrap() = provider.clone();
         tokio::spawn(async move {
-            if let Err(e) = embedding_service_clone.initialize().await {
+            if let Err(e) = provider.initialize().await {
                 eprintln!("Failed to initialize embedding service: {}", e);
             } else {
                 println!("Embedding service initialized successfully");
             }
         });
-        
-        Ok(system)
     }
     
     fn initialize_database(&self) -> Result<()> {
@@ -185,6 +443,39 @@ impl EnhancedRagSystem {
             "ALTER TABLE enhanced_documents ADD COLUMN content_hash TEXT",
             [],
         );
+        // Set when a watched folder's file goes away; the document is hidden and out of the index
+        let _ = conn.execute(
+            "ALTER TABLE enhanced_documents ADD COLUMN deleted_at TEXT",
+            [],
+        );
+        
+        // MinHash signature of the content, for near-duplicate checks; filled in lazily
+        let _ = conn.execute(
+            "ALTER TABLE enhanced_documents ADD COLUMN minhash BLOB",
+            [],
+        );
+        
+        // Original file compressed into a zip beside it
+        let _ = conn.execute(
+            "ALTER TABLE enhanced_documents ADD COLUMN archived INTEGER DEFAULT 0",
+            [],
+        );
+        
+        // Documents filed in a collection; NULL for none
+        let _ = conn.execute(
+            "ALTER TABLE enhanced_documents ADD COLUMN collection_id TEXT",
+            [],
+        );
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS enhanced_collections (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
         
         // Create enhanced document_chunks table
         conn.execute(
@@ -204,6 +495,11 @@ impl EnhancedRagSystem {
             [],
         )?;
         
+        // Which embedding model made each chunk's embedding, and its size, so a model change can
+        // find the embeddings it left behind
+        let _ = conn.execute("ALTER TABLE enhanced_document_chunks ADD COLUMN embedding_model TEXT", []);
+        let _ = conn.execute("ALTER TABLE enhanced_document_chunks ADD COLUMN embedding_dim INTEGER", []);
+        
         // Create processing_queue table for background tasks
         conn.execute(
             "CREATE TABLE IF NOT EXISTS processing_queue (
@@ -220,6 +516,25 @@ impl EnhancedRagSystem {
             [],
         )?;
         
+        // Retry bookkeeping for the embedding worker. Queue rows used to stay 'pending' forever,
+        // so on first migration only the documents still waiting for embeddings keep theirs.
+        if conn.execute("ALTER TABLE processing_queue ADD COLUMN attempts INTEGER DEFAULT 0", []).is_ok() {
+            conn.execute(
+                "UPDATE processing_queue SET status = 'completed'
+                 WHERE status = 'pending'
+                   AND document_id NOT IN (SELECT id FROM enhanced_documents WHERE embedding_status IN ('pending', 'processing'))",
+                [],
+            )?;
+        }
+        let _ = conn.execute("ALTER TABLE processing_queue ADD COLUMN next_attempt_at TEXT", []);
+        let _ = conn.execute("ALTER TABLE processing_queue ADD COLUMN priority INTEGER DEFAULT 0", []);
+        // Tasks interrupted by the app closing run again; finished ones are kept a week for stats
+        conn.execute("UPDATE processing_queue SET status = 'pending' WHERE status = 'processing'", [])?;
+        conn.execute(
+            "DELETE FROM processing_queue WHERE status IN ('completed', 'cancelled') AND completed_at < ?1",
+            params![(Utc::now() - chrono::Duration::days(7)).to_rfc3339()],
+        )?;
+        
         // Create user_settings table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS enhanced_user_settings (
@@ -237,6 +552,7 @@ impl EnhancedRagSystem {
             "CREATE INDEX IF NOT EXISTS idx_enhanced_documents_embedding_status ON enhanced_documents(embedding_status)",
             "CREATE INDEX IF NOT EXISTS idx_processing_queue_status ON processing_queue(status)",
             "CREATE INDEX IF NOT EXISTS idx_processing_queue_document_id ON processing_queue(document_id)",
+            "CREATE INDEX IF NOT EXISTS idx_enhanced_documents_collection_id ON enhanced_documents(collection_id)",
         ];
         
         for index_sql in indexes {
@@ -258,9 +574,9 @@ impl EnhancedRagSystem {
         let mut stmt = conn.prepare(
             "SELECT id, file_name, file_path, file_type, file_size, content,
                     created_at, updated_at, access_count, last_accessed, is_cached,
-                    embedding_status, chunk_count, metadata, content_hash
+                    embedding_status, chunk_count, metadata, content_hash, collection_id
              FROM enhanced_documents
-             WHERE content_hash = ?1"
+             WHERE content_hash = ?1 AND deleted_at IS NULL"
         )?;
         
         let document = stmt.query_row(params![content_hash], |row| {
@@ -280,17 +596,21 @@ impl EnhancedRagSystem {
                 chunk_count: row.get(12)?,
                 metadata: row.get(13)?,
                 content_hash: row.get(14)?,
+                collection_id: row.get(15)?,
             })
         }).optional()?;
         
         Ok(document)
     }
     
+    /// Store, chunk and (with auto-embedding) embed a document. Images and scanned PDF pages are
+    /// OCR'd; `ocr_progress` is called with (pages done, pages to OCR) as that goes.
     pub async fn upload_document(
         &self,
         file_name: String,
         file_content: Vec<u8>,
         file_type: String,
+        ocr_progress: impl Fn(usize, usize) + Send + Sync,
     ) -> Result<EnhancedDocument> {
         // Calculate content hash for duplicate detection
         let mut hasher = Sha256::new();
@@ -317,6 +637,7 @@ impl EnhancedRagSystem {
                 file_size_mb, max_size_mb
             ));
         }
+        self.reserve_storage(file_content.len() as u64)?;
         
         // Generate unique ID
         let doc_id = Uuid::new_v4().to_string();
@@ -327,11 +648,12 @@ impl EnhancedRagSystem {
         fs::write(&file_path, &file_content)?;
         
         // Extract and clean text content
-        let raw_text = self.extract_text_content(&file_content, &file_type)?;
-        let clean_content = clean_text(&raw_text);
+        let pages = self.extract_text_content(&file_content, &file_type, &ocr_progress).await?;
+        let (clean_content, outline) = clean_pages(&pages, self.chunking_strategy(&file_name, &file_type));
         
-        // Create document chunks
-        let chunks = self.create_document_chunks(&doc_id, &clean_content).await?;
+        // Create document chunks, each knowing its page and section
+        let mut chunks = self.create_document_chunks(&clean_content, &file_name, &file_type).await?;
+        outline.annotate(&mut chunks);
         
         // Create document record
         let now = Utc::now().to_rfc3339();
@@ -351,11 +673,13 @@ impl EnhancedRagSystem {
             chunk_count: chunks.len() as i32,
             metadata: None,
             content_hash: Some(content_hash),
+            collection_id: None,
         };
         
         // Save to database
         self.save_document_to_db(&document)?;
         self.save_chunks_to_db(&doc_id, &chunks)?;
+        self.store_signature(&doc_id, &document.content)?;
         
         // Queue for embedding generation if enabled
         if auto_embedding {
@@ -367,31 +691,531 @@ impl EnhancedRagSystem {
         Ok(document)
     }
     
-    fn extract_text_content(&self, file_content: &[u8], file_type: &str) -> Result<String> {
-        match file_type {
+    fn store_signature(&self, document_id: &str, content: &str) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "UPDATE enhanced_documents SET minhash = ?1 WHERE id = ?2",
+            params![near_duplicate::signature(content).map(|signature| near_duplicate::to_bytes(&signature)), document_id],
+        )?;
+        Ok(())
+    }
+    
+    /// The stored document most like `content`, if it's similar enough to be another version
+    /// of it. Documents stored before signatures existed get one on the way.
+    pub fn find_near_duplicate(&self, content: &str, exclude_id: Option<&str>) -> Result<Option<NearDuplicate>> {
+        let Some(signature) = near_duplicate::signature(content) else { return Ok(None) };
+        let stored: Vec<(String, Option<Vec<u8>>, Option<String>)> = {
+            let conn = Connection::open(&self.db_path)?;
+            let mut stmt = conn.prepare(
+                "SELECT id, minhash, CASE WHEN minhash IS NULL THEN content END
+                 FROM enhanced_documents WHERE deleted_at IS NULL"
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        let mut best: Option<(String, f64)> = None;
+        for (id, minhash, unsigned_content) in stored {
+            if exclude_id == Some(id.as_str()) {
+                continue;
+            }
+            let other = match (minhash, unsigned_content) {
+                (Some(bytes), _) => near_duplicate::from_bytes(&bytes),
+                (None, Some(text)) => {
+                    self.store_signature(&id, &text)?;
+                    match near_duplicate::signature(&text) {
+                        Some(other) => other,
+                        None => continue,
+                    }
+                }
+                (None, None) => continue,
+            };
+            let similarity = near_duplicate::similarity(&signature, &other);
+            if similarity >= near_duplicate::NEAR_DUPLICATE_THRESHOLD && !best.as_ref().is_some_and(|(_, score)| *score >= similarity) {
+                best = Some((id, similarity));
+            }
+        }
+        match best {
+            Some((id, similarity)) => Ok(self.get_document(&id)?.map(|document| NearDuplicate { document, similarity })),
+            None => Ok(None),
+        }
+    }
+    
+    /// Extract a file the way `upload_document` would and look for a near-duplicate of its text
+    pub async fn check_near_duplicate(&self, file_name: &str, file_content: &[u8], file_type: &str) -> Result<Option<NearDuplicate>> {
+        let pages = self.extract_text_content(file_content, file_type, &|_, _| {}).await?;
+        let (content, _) = clean_pages(&pages, self.chunking_strategy(file_name, file_type));
+        self.find_near_duplicate(&content, None)
+    }
+    
+    /// Make an uploaded file the new version of an existing document: same id, collection and
+    /// tags, with only the chunks that changed re-embedded
+    pub async fn replace_document(
+        &self,
+        document_id: &str,
+        file_name: String,
+        file_content: Vec<u8>,
+        file_type: String,
+    ) -> Result<DocumentUpdateResult> {
+        let old = self.get_document(document_id)?
+            .ok_or_else(|| anyhow!("Document {} not found", document_id))?;
+        let pages = self.extract_text_content(&file_content, &file_type, &|_, _| {}).await?;
+        
+        // Name and type first, so the new content is chunked for the new type
+        let file_path = self.storage_path.join(document_id).join(&file_name);
+        fs::create_dir_all(file_path.parent().unwrap())?;
+        fs::write(&file_path, &file_content)?;
+        if PathBuf::from(&old.file_path) != file_path && PathBuf::from(&old.file_path).starts_with(&self.storage_path) {
+            let _ = fs::remove_file(&old.file_path);
+        }
+        self.clear_archive(document_id, Path::new(&old.file_path))?;
+        let mut hasher = Sha256::new();
+        hasher.update(&file_content);
+        hasher.update(file_name.as_bytes());
+        let content_hash = format!("{:x}", hasher.finalize());
+        {
+            let conn = Connection::open(&self.db_path)?;
+            conn.execute(
+                "UPDATE enhanced_documents SET file_name = ?1, file_type = ?2, file_path = ?3 WHERE id = ?4",
+                params![file_name, file_type, file_path.to_string_lossy().to_string(), document_id],
+            )?;
+        }
+        
+        let mut result = self.update_document_pages(document_id, &pages).await?;
+        // Same hash as an upload of this file, so uploading it again is caught as an exact duplicate
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "UPDATE enhanced_documents SET content_hash = ?1, file_size = ?2 WHERE id = ?3",
+            params![content_hash, file_content.len() as i64, document_id],
+        )?;
+        result.document.content_hash = Some(content_hash);
+        result.document.file_size = file_content.len() as i64;
+        println!("Document {} replaced with {}", document_id, file_name);
+        Ok(result)
+    }
+    
+    /// Store already-extracted text (a web article) as a document, merging `metadata` (where the
+    /// text came from) into the document's metadata
+    pub async fn upload_text_document(
+        &self,
+        file_name: String,
+        text: String,
+        metadata: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<EnhancedDocument> {
+        let mut document = self.upload_document(file_name, text.into_bytes(), "text/plain".to_string(), |_, _| {}).await?;
+        document.metadata = Some(self.merge_metadata(&document.id, metadata)?);
+        Ok(document)
+    }
+    
+    /// Merge `metadata` into a document's metadata object, returning the result
+    fn merge_metadata(&self, document_id: &str, metadata: serde_json::Map<String, serde_json::Value>) -> Result<String> {
+        let conn = Connection::open(&self.db_path)?;
+        let existing: Option<String> = conn
+            .query_row("SELECT metadata FROM enhanced_documents WHERE id = ?1", params![document_id], |row| row.get(0))
+            .optional()?
+            .flatten();
+        let mut object = match existing.as_deref().map(serde_json::from_str::<serde_json::Value>) {
+            Some(Ok(serde_json::Value::Object(object))) => object,
+            _ => serde_json::Map::new(),
+        };
+        object.extend(metadata);
+        let merged = serde_json::Value::Object(object).to_string();
+        conn.execute(
+            "UPDATE enhanced_documents SET metadata = ?1 WHERE id = ?2",
+            params![merged, document_id],
+        )?;
+        Ok(merged)
+    }
+    
+    /// Store a file from a watched folder, recording its path and hash in the metadata
+    pub async fn upload_source_file(
+        &self,
+        file_name: String,
+        file_content: Vec<u8>,
+        file_type: String,
+        metadata: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<EnhancedDocument> {
+        let mut document = self.upload_document(file_name, file_content, file_type, |_, _| {}).await?;
+        document.metadata = Some(self.merge_metadata(&document.id, metadata)?);
+        Ok(document)
+    }
+    
+    /// Replace a watched file's document with the file's new content, re-embedding only the
+    /// chunks that changed
+    pub async fn update_source_file(
+        &self,
+        document_id: &str,
+        file_content: Vec<u8>,
+        file_type: &str,
+        metadata: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<DocumentUpdateResult> {
+        let pages = self.extract_text_content(&file_content, file_type, &|_, _| {}).await?;
+        let mut result = self.update_document_pages(document_id, &pages).await?;
+        let stored = PathBuf::from(&result.document.file_path);
+        if stored.starts_with(&self.storage_path) {
+            fs::write(&stored, &file_content)?;
+            self.clear_archive(document_id, &stored)?;
+        }
+        result.document.metadata = Some(self.merge_metadata(document_id, metadata)?);
+        Ok(result)
+    }
+    
+    /// Replace a document's text (a re-indexed transcript), merging `metadata` into its metadata
+    pub async fn update_text_document(
+        &self,
+        document_id: &str,
+        text: &str,
+        metadata: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<EnhancedDocument> {
+        let mut document = self.update_document(document_id, text).await?.document;
+        document.metadata = Some(self.merge_metadata(document_id, metadata)?);
+        Ok(document)
+    }
+    
+    /// The live document whose metadata has `key` set to `value`, e.g. the transcript of a conversation
+    pub fn find_document_by_metadata(&self, key: &str, value: &str) -> Result<Option<String>> {
+        let conn = Connection::open(&self.db_path)?;
+        let id = conn.query_row(
+            "SELECT id FROM enhanced_documents
+             WHERE deleted_at IS NULL AND json_valid(metadata) AND json_extract(metadata, '$.' || ?1) = ?2
+             ORDER BY created_at DESC LIMIT 1",
+            params![key, value],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(id)
+    }
+    
+    /// Documents that came from a file under `folder` (watched-folder imports), deleted ones too
+    pub fn source_documents(&self, folder: &Path) -> Result<Vec<SourceDocument>> {
+        let conn = Connection::open(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, json_extract(metadata, '$.sourcePath'), json_extract(metadata, '$.sourceHash'), deleted_at IS NOT NULL
+             FROM enhanced_documents
+             WHERE json_valid(metadata) AND json_extract(metadata, '$.sourcePath') IS NOT NULL"
+        )?;
+        let documents = stmt.query_map([], |row| {
+            Ok(SourceDocument {
+                id: row.get(0)?,
+                source_path: row.get(1)?,
+                source_hash: row.get(2)?,
+                deleted: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(documents.into_iter().filter(|document| Path::new(&document.source_path).starts_with(folder)).collect())
+    }
+    
+    /// Hide a document and take it out of the search index, keeping its rows and embeddings so
+    /// `restore_document` can bring it back as it was
+    pub fn soft_delete_document(&self, document_id: &str) -> Result<()> {
+        self.search_service.delete_document(document_id)?;
+        self.search_service.commit()?;
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "UPDATE enhanced_documents SET deleted_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), document_id],
+        )?;
+        Ok(())
+    }
+    
+    /// Undo `soft_delete_document`, re-indexing the stored embeddings (or queueing the document
+    /// for embedding when some chunks never got one from the current model)
+    pub async fn restore_document(&self, document_id: &str) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("UPDATE enhanced_documents SET deleted_at = NULL WHERE id = ?1", params![document_id])?;
+        let mut stmt = conn.prepare(
+            "SELECT CASE WHEN embedding_model = ?2 THEN embedding END
+             FROM enhanced_document_chunks WHERE document_id = ?1 ORDER BY chunk_index"
+        )?;
+        let stored = stmt.query_map(params![document_id, self.embedding_model()], |row| row.get::<_, Option<Vec<u8>>>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let embeddings: Option<Vec<Vec<f32>>> = stored.into_iter().map(|bytes| {
+            bytes.map(|bytes| bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+        }).collect();
+        let chunks = self.get_document_chunks(document_id)?;
+        match embeddings {
+            Some(embeddings) if !chunks.is_empty() => self.index_chunks_for_search(document_id, &chunks, &embeddings).await,
+            _ => self.queue_embedding_generation(document_id).await,
+        }
+    }
+    
+    fn get_document(&self, document_id: &str) -> Result<Option<EnhancedDocument>> {
+        let conn = Connection::open(&self.db_path)?;
+        let document = conn.query_row(
+            "SELECT id, file_name, file_path, file_type, file_size, content,
+                    created_at, updated_at, access_count, last_accessed, is_cached,
+                    embedding_status, chunk_count, metadata, content_hash, collection_id
+             FROM enhanced_documents
+             WHERE id = ?1",
+            params![document_id],
+            |row| {
+                Ok(EnhancedDocument {
+                    id: row.get(0)?,
+                    file_name: row.get(1)?,
+                    file_path: row.get(2)?,
+                    file_type: row.get(3)?,
+                    file_size: row.get(4)?,
+                    content: row.get(5)?,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                    access_count: row.get(8)?,
+                    last_accessed: row.get(9)?,
+                    is_cached: row.get::<_, i32>(10)? != 0,
+                    embedding_status: row.get(11)?,
+                    chunk_count: row.get(12)?,
+                    metadata: row.get(13)?,
+                    content_hash: row.get(14)?,
+                    collection_id: row.get(15)?,
+                })
+            },
+        ).optional()?;
+        Ok(document)
+    }
+    
+    /// Replace a document's content, re-embedding only the chunks whose text changed. Unchanged
+    /// chunks keep their id and embedding, the search index is updated chunk by chunk, and access
+    /// statistics are left alone.
+    pub async fn update_document(&self, document_id: &str, new_content: &str) -> Result<DocumentUpdateResult> {
+        self.update_document_pages(document_id, &[new_content.to_string()]).await
+    }
+    
+    async fn update_document_pages(&self, document_id: &str, pages: &[String]) -> Result<DocumentUpdateResult> {
+        let mut document = self.get_document(document_id)?
+            .ok_or_else(|| anyhow!("Document {} not found", document_id))?;
+        let (content, outline) = clean_pages(pages, self.chunking_strategy(&document.file_name, &document.file_type));
+        if content.trim().is_empty() {
+            return Err(anyhow!("New content is empty"));
+        }
+        let mut new_chunks = self.create_document_chunks(&content, &document.file_name, &document.file_type).await?;
+        outline.annotate(&mut new_chunks);
+        
+        // Old chunks with their stored embeddings, matched to the new ones by content hash
+        // Embeddings from another model count as missing
+        let old_chunks: Vec<(String, String, Option<Vec<u8>>)> = {
+            let conn = Connection::open(&self.db_path)?;
+            let mut stmt = conn.prepare(
+                "SELECT id, content, CASE WHEN embedding_model = ?2 THEN embedding END
+                 FROM enhanced_document_chunks WHERE document_id = ?1 ORDER BY chunk_index"
+            )?;
+            let rows = stmt.query_map(params![document_id, self.embedding_model()], |row| Ok((row.get(0)?, row.get::<_, String>(1)?, row.get(2)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        let old_hashes: Vec<(String, String)> = old_chunks.iter().map(|(id, text, _)| (id.clone(), content_hash(text))).collect();
+        let new_hashes: Vec<String> = new_chunks.iter().map(|chunk| content_hash(&chunk.content)).collect();
+        let (kept, removed) = match_chunks(&old_hashes, &new_hashes);
+        let old_embedded: HashMap<&str, bool> = old_chunks.iter().map(|(id, _, embedding)| (id.as_str(), embedding.is_some())).collect();
+        
+        // A kept chunk that was never embedded needs embedding as much as a changed one
+        let ids: Vec<String> = kept.iter().map(|id| id.clone().unwrap_or_else(|| Uuid::new_v4().to_string())).collect();
+        let to_embed: Vec<usize> = (0..new_chunks.len())
+            .filter(|&i| !kept[i].as_deref().is_some_and(|id| old_embedded.get(id).copied().unwrap_or(false)))
+            .collect();
+        let changed_chunks = kept.iter().filter(|id| id.is_none()).count();
+        
+        let embedder = self.embedder();
+        let embeddings: Option<Vec<Vec<f32>>> = if to_embed.is_empty() {
+            Some(Vec::new())
+        } else if embedder.is_initialized() {
+            let texts: Vec<String> = to_embed.iter().map(|&i| new_chunks[i].content.clone()).collect();
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for batch in texts.chunks(EMBEDDING_BATCH_SIZE) {
+                embeddings.extend(embedder.embed_documents(batch.to_vec()).await
+                    .map_err(|e| anyhow!("Failed to generate embeddings: {}", e))?);
+            }
+            Some(embeddings)
+        } else {
+            None
+        };
+        
+        let now = Utc::now().to_rfc3339();
+        let mut hasher = Sha256::new();
+        hasher.update(content.as_bytes());
+        hasher.update(document.file_name.as_bytes());
+        document.content_hash = Some(format!("{:x}", hasher.finalize()));
+        document.file_size = content.len() as i64;
+        document.content = content;
+        document.chunk_count = new_chunks.len() as i32;
+        document.updated_at = now.clone();
+        // Every chunk has an embedding now, or none are searchable by vector until the queue runs
+        document.embedding_status = if embeddings.is_some() { "completed" } else { "pending" }.to_string();
+        document.is_cached = embeddings.is_some();
+        
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE enhanced_documents SET content = ?1, content_hash = ?2, file_size = ?3, chunk_count = ?4,
+                    updated_at = ?5, embedding_status = ?6, is_cached = ?7
+             WHERE id = ?8",
+            params![document.content, document.content_hash, document.file_size, document.chunk_count,
+                    now, document.embedding_status, document.is_cached as i32, document_id],
+        )?;
+        for id in &removed {
+            tx.execute("DELETE FROM enhanced_document_chunks WHERE id = ?1", params![id])?;
+        }
+        for (i, chunk) in new_chunks.iter().enumerate() {
+            if kept[i].is_some() {
+                tx.execute(
+                    "UPDATE enhanced_document_chunks SET chunk_index = ?1, start_char = ?2, end_char = ?3, token_count = ?4, metadata = ?5 WHERE id = ?6",
+                    params![i as i32, chunk.start_char as i32, chunk.end_char as i32, chunk.token_count as i32, chunk.metadata, ids[i]],
+                )?;
+            } else {
+                tx.execute(
+                    "INSERT INTO enhanced_document_chunks (
+                        id, document_id, chunk_index, content, start_char, end_char,
+                        token_count, metadata, created_at
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![ids[i], document_id, i as i32, chunk.content, chunk.start_char as i32,
+                            chunk.end_char as i32, chunk.token_count as i32, chunk.metadata, now],
+                )?;
+            }
+        }
+        if let Some(embeddings) = &embeddings {
+            let model = embedder.model_id();
+            for (&i, embedding) in to_embed.iter().zip(embeddings) {
+                tx.execute(
+                    "UPDATE enhanced_document_chunks SET embedding = ?1, embedding_model = ?2, embedding_dim = ?3 WHERE id = ?4",
+                    params![embedding_bytes(embedding), model, embedding.len() as i64, ids[i]],
+                )?;
+            }
+        }
+        tx.commit()?;
+        self.store_signature(document_id, &document.content)?;
+        
+        // Unchanged chunks stay in the index as they are; a queued embedding re-indexes them all
+        match embeddings {
+            None => self.search_service.delete_document(document_id)?,
+            Some(embeddings) => {
+                for id in &removed {
+                    self.search_service.delete_chunk(id)?;
+                }
+                let search_chunks = to_embed.iter().zip(embeddings).map(|(&i, embedding)| {
+                    if kept[i].is_some() {
+                        self.search_service.delete_chunk(&ids[i])?;
+                    }
+                    Ok(crate::search_service::DocumentChunk {
+                        id: ids[i].clone(),
+                        document_id: document_id.to_string(),
+                        content: new_chunks[i].content.clone(),
+                        embedding: Some(embedding),
+                        metadata: new_chunks[i].metadata.clone(),
+                    })
+                }).collect::<Result<Vec<_>>>()?;
+                self.search_service.add_documents(search_chunks)?;
+            }
+        }
+        self.search_service.commit()?;
+        
+        if !document.is_cached {
+            self.queue_embedding_generation(document_id).await?;
+        } else if let Err(e) = self.enforce_cache_limit(&[document_id.to_string()]) {
+            eprintln!("Failed to evict embeddings past the cache limit: {}", e);
+        }
+        
+        println!("Document {} updated: {} chunks kept, {} changed, {} removed", document_id, new_chunks.len() - changed_chunks, changed_chunks, removed.len());
+        Ok(DocumentUpdateResult {
+            document,
+            unchanged_chunks: new_chunks.len() - changed_chunks,
+            changed_chunks,
+            removed_chunks: removed.len(),
+        })
+    }
+    
+    /// A file's text by page; one page for everything but PDFs
+    async fn extract_text_content(&self, file_content: &[u8], file_type: &str, ocr_progress: &(dyn Fn(usize, usize) + Send + Sync)) -> Result<Vec<String>> {
+        let text = match file_type {
+            t if t.contains("wordprocessingml") || t.ends_with("docx") => {
+                extract_text_from_docx(file_content)
+            }
+            t if t.contains("spreadsheetml") || t.ends_with("xlsx") => {
+                extract_text_from_xlsx(file_content)
+            }
+            t if t.contains("presentationml") || t.ends_with("pptx") => {
+                extract_text_from_pptx(file_content)
+            }
+            t if t.contains("epub") => {
+                extract_text_from_epub(file_content)
+            }
             t if t.contains("text") || t.contains("plain") => {
                 Ok(String::from_utf8_lossy(file_content).to_string())
             }
             t if t.contains("pdf") => {
-                extract_text_from_pdf(file_content)
+                return self.extract_pdf_text(file_content, ocr_progress).await;
             }
             t if t.contains("image") => {
-                // TODO: Implement OCR
-                Ok("Image OCR not yet implemented".to_string())
+                ocr_progress(0, 1);
+                let text = ocr_image(file_content).await?;
+                ocr_progress(1, 1);
+                Ok(text)
             }
             _ => {
                 // Try to parse as text
                 Ok(String::from_utf8_lossy(file_content).to_string())
             }
+        };
+        Ok(vec![text?])
+    }
+    
+    /// PDF text by page, OCR'ing the pages that are only scanned images
+    async fn extract_pdf_text(&self, file_content: &[u8], ocr_progress: &(dyn Fn(usize, usize) + Send + Sync)) -> Result<Vec<String>> {
+        // Without page breaks the whole text still makes one page
+        let text_pages = || extract_pages_from_pdf(file_content)
+            .or_else(|_| extract_text_from_pdf(file_content).map(|text| vec![text]));
+        let pages = match pdf_pages(file_content) {
+            Ok(pages) => pages,
+            Err(e) => {
+                eprintln!("Failed to read PDF pages, using text extraction only: {}", e);
+                return text_pages();
+            }
+        };
+        let total = pages.iter().filter(|page| !page.scans.is_empty()).count();
+        if total == 0 {
+            return text_pages();
+        }
+        
+        println!("OCR'ing {} scanned pages", total);
+        let mut done = 0;
+        ocr_progress(done, total);
+        let mut text = Vec::with_capacity(pages.len());
+        for page in pages {
+            if page.scans.is_empty() {
+                text.push(page.text);
+                continue;
+            }
+            let mut scanned = Vec::with_capacity(page.scans.len());
+            for scan in &page.scans {
+                match ocr_image(scan).await {
+                    Ok(page_text) => scanned.push(page_text),
+                    Err(e) => eprintln!("OCR of PDF page {} failed: {}", page.number, e),
+                }
+            }
+            text.push(scanned.join("\n"));
+            done += 1;
+            ocr_progress(done, total);
         }
+        Ok(text)
+    }
+    
+    fn chunking_strategy(&self, file_name: &str, file_type: &str) -> ChunkingStrategy {
+        self.chunking_service.lock().unwrap().get_config().strategy_for(file_name, file_type)
     }
     
-    async fn create_document_chunks(&self, document_id: &str, content: &str) -> Result<Vec<TextChunk>> {
-        let chunking_service = self.chunking_service.lock().unwrap();
-        let chunks = chunking_service.chunk_text(content)?;
-        
-        // Convert to our TextChunk format with document_id
-        Ok(chunks)
+    /// Chunk a document with the strategy configured for its type. Semantic chunking embeds
+    /// every sentence, so without a ready embedder it falls back to standard chunking.
+    async fn create_document_chunks(&self, content: &str, file_name: &str, file_type: &str) -> Result<Vec<TextChunk>> {
+        let strategy = self.chunking_strategy(file_name, file_type);
+        if strategy == ChunkingStrategy::Semantic {
+            let embedder = self.embedder();
+            if embedder.is_initialized() {
+                let units = self.chunking_service.lock().unwrap().semantic_units(content);
+                let sentences: Vec<String> = units.iter().map(|&(start, end)| content[start..end].trim().to_string()).collect();
+                let mut embeddings = Vec::with_capacity(sentences.len());
+                for batch in sentences.chunks(EMBEDDING_BATCH_SIZE) {
+                    embeddings.extend(embedder.embed_documents(batch.to_vec()).await
+                        .map_err(|e| anyhow!("Failed to embed sentences for chunking: {}", e))?);
+                }
+                return self.chunking_service.lock().unwrap().semantic_chunk(content, &units, &embeddings);
+            }
+            println!("Embedding service not ready, chunking {} without semantic boundaries", file_name);
+        }
+        self.chunking_service.lock().unwrap().chunk_with_strategy(content, strategy, file_name)
     }
     
     fn save_document_to_db(&self, document: &EnhancedDocument) -> Result<()> {
@@ -400,8 +1224,8 @@ impl EnhancedRagSystem {
             "INSERT INTO enhanced_documents (
                 id, file_name, file_path, file_type, file_size, content,
                 created_at, updated_at, access_count, last_accessed, is_cached,
-                embedding_status, chunk_count, metadata, content_hash
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                embedding_status, chunk_count, metadata, content_hash, collection_id
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
             params![
                 document.id,
                 document.file_name,
@@ -418,6 +1242,7 @@ impl EnhancedRagSystem {
                 document.chunk_count,
                 document.metadata,
                 document.content_hash,
+                document.collection_id,
             ],
         )?;
         Ok(())
@@ -432,8 +1257,8 @@ impl EnhancedRagSystem {
             conn.execute(
                 "INSERT INTO enhanced_document_chunks (
                     id, document_id, chunk_index, content, start_char, end_char,
-                    token_count, created_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    token_count, metadata, created_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
                 params![
                     chunk_id,
                     document_id,
@@ -442,6 +1267,7 @@ impl EnhancedRagSystem {
                     chunk.start_char as i32,
                     chunk.end_char as i32,
                     chunk.token_count as i32,
+                    chunk.metadata,
                     now,
                 ],
             )?;
@@ -450,56 +1276,200 @@ impl EnhancedRagSystem {
     }
     
     async fn queue_embedding_generation(&self, document_id: &str) -> Result<()> {
-        // Add to processing queue
-        let queue_id = Uuid::new_v4().to_string();
-        let now = Utc::now().to_rfc3339();
-        
+        self.enqueue_embedding(document_id, "embedding_generation", 0)
+    }
+    
+    async fn queue_priority_embedding_generation(&self, document_id: &str) -> Result<()> {
+        self.enqueue_embedding(document_id, "priority_embedding_generation", 1)
+    }
+    
+    /// Queue a document for the embedding worker. A document already waiting keeps its one task,
+    /// raised to `priority` and made due now.
+    fn enqueue_embedding(&self, document_id: &str, task_type: &str, priority: i32) -> Result<()> {
         let conn = Connection::open(&self.db_path)?;
-        conn.execute(
-            "INSERT INTO processing_queue (id, document_id, task_type, status, created_at)
-             VALUES (?1, ?2, 'embedding_generation', 'pending', ?3)",
-            params![queue_id, document_id, now],
+        let waiting = conn.execute(
+            "UPDATE processing_queue SET priority = MAX(COALESCE(priority, 0), ?1), next_attempt_at = NULL
+             WHERE document_id = ?2 AND status = 'pending'",
+            params![priority, document_id],
         )?;
-        
-        // Process in background
-        let system_clone = self.clone();
-        let document_id_clone = document_id.to_string();
+        if waiting == 0 {
+            conn.execute(
+                "INSERT INTO processing_queue (id, document_id, task_type, status, created_at, attempts, priority)
+                 VALUES (?1, ?2, ?3, 'pending', ?4, 0, ?5)",
+                params![Uuid::new_v4().to_string(), document_id, task_type, Utc::now().to_rfc3339(), priority],
+            )?;
+        }
+        self.embedding_worker.wake();
+        Ok(())
+    }
+    
+    /// Drain the processing queue in the background, `embedding_concurrency` documents at a time
+    fn start_embedding_worker(&self) {
+        let system = self.clone();
         tokio::spawn(async move {
-            if let Err(e) = system_clone.process_embeddings(&document_id_clone).await {
-                eprintln!("Failed to process embeddings for document {}: {}", document_id_clone, e);
+            loop {
+                let limit = system.get_settings().embedding_concurrency.max(1);
+                if system.embedding_worker.running() < limit {
+                    match system.claim_queue_task() {
+                        Ok(Some(task)) => {
+                            let running = system.embedding_worker.start_task();
+                            let worker = system.clone();
+                            tokio::spawn(async move {
+                                worker.run_queue_task(task).await;
+                                drop(running);
+                            });
+                            continue;
+                        }
+                        Ok(None) => {}
+                        Err(e) => eprintln!("Failed to read the processing queue: {}", e),
+                    }
+                }
+                system.embedding_worker.wait().await;
             }
         });
-        
-        Ok(())
     }
     
-    async fn queue_priority_embedding_generation(&self, document_id: &str) -> Result<()> {
-        // Add to processing queue with priority
-        let queue_id = Uuid::new_v4().to_string();
+    /// The next due task, marked processing; one task per document runs at a time
+    fn claim_queue_task(&self) -> Result<Option<QueueTask>> {
+        let conn = Connection::open(&self.db_path)?;
         let now = Utc::now().to_rfc3339();
-        
+        let task = conn.query_row(
+            "SELECT id, document_id, COALESCE(attempts, 0) FROM processing_queue
+             WHERE status = 'pending' AND (next_attempt_at IS NULL OR next_attempt_at <= ?1)
+               AND document_id NOT IN (SELECT document_id FROM processing_queue WHERE status = 'processing')
+             ORDER BY COALESCE(priority, 0) DESC, created_at
+             LIMIT 1",
+            params![now],
+            |row| Ok(QueueTask { id: row.get(0)?, document_id: row.get(1)?, attempts: row.get(2)? }),
+        ).optional()?;
+        if let Some(task) = &task {
+            conn.execute(
+                "UPDATE processing_queue SET status = 'processing', started_at = ?1 WHERE id = ?2",
+                params![now, task.id],
+            )?;
+        }
+        Ok(task)
+    }
+    
+    async fn run_queue_task(&self, task: QueueTask) {
+        let result = self.process_embeddings(&task.document_id).await;
+        if let Err(e) = &result {
+            eprintln!("Failed to process embeddings for document {}: {}", task.document_id, e);
+        }
+        if let Err(e) = self.finish_queue_task(&task, result.err().map(|e| e.to_string())) {
+            eprintln!("Failed to update processing queue task {}: {}", task.id, e);
+        }
+    }
+    
+    /// Record how a task went. Failures are retried with backoff until `embedding_max_attempts`;
+    /// cancelled embeddings and deleted documents aren't retried.
+    fn finish_queue_task(&self, task: &QueueTask, error: Option<String>) -> Result<()> {
         let conn = Connection::open(&self.db_path)?;
-        conn.execute(
-            "INSERT INTO processing_queue (id, document_id, task_type, status, created_at)
-             VALUES (?1, ?2, 'priority_embedding_generation', 'pending', ?3)",
-            params![queue_id, document_id, now],
-        )?;
+        let now = Utc::now();
+        let Some(error) = error else {
+            // Requests queued before this run started are covered by it
+            conn.execute(
+                "UPDATE processing_queue SET status = 'completed', completed_at = ?1, error_message = NULL
+                 WHERE id = ?2
+                    OR (document_id = ?3 AND status = 'pending'
+                        AND created_at <= (SELECT started_at FROM processing_queue WHERE id = ?2))",
+                params![now.to_rfc3339(), task.id, task.document_id],
+            )?;
+            return Ok(());
+        };
         
-        // Process immediately in background
-        let system_clone = self.clone();
-        let document_id_clone = document_id.to_string();
-        tokio::spawn(async move {
-            if let Err(e) = system_clone.process_embeddings(&document_id_clone).await {
-                eprintln!("Failed to process priority embeddings for document {}: {}", document_id_clone, e);
+        let attempts = task.attempts + 1;
+        let document_status: Option<String> = conn.query_row(
+            "SELECT embedding_status FROM enhanced_documents WHERE id = ?1",
+            params![task.document_id],
+            |row| row.get(0),
+        ).optional()?;
+        let (status, next_attempt_at) = match document_status.as_deref() {
+            None => ("failed", None),
+            Some("cancelled") => ("cancelled", None),
+            _ if attempts >= self.get_settings().embedding_max_attempts.max(1) => ("failed", None),
+            _ => {
+                let delay = chrono::Duration::from_std(retry_delay(attempts)).unwrap_or_else(|_| chrono::Duration::minutes(15));
+                ("pending", Some((now + delay).to_rfc3339()))
             }
-        });
-        
+        };
+        conn.execute(
+            "UPDATE processing_queue SET status = ?1, attempts = ?2, next_attempt_at = ?3, error_message = ?4,
+                    completed_at = CASE WHEN ?1 = 'pending' THEN NULL ELSE ?5 END
+             WHERE id = ?6",
+            params![status, attempts, next_attempt_at, error, now.to_rfc3339(), task.id],
+        )?;
+        if status == "pending" {
+            println!("Retrying embeddings for document {} in {:?} (attempt {} failed)", task.document_id, retry_delay(attempts), attempts);
+            self.update_embedding_status(&task.document_id, "pending")?;
+        } else if status == "failed" && document_status.is_some() {
+            self.update_embedding_status(&task.document_id, "failed")?;
+        }
         Ok(())
     }
     
+    /// Depth, retries and throughput of the embedding queue
+    pub fn get_processing_queue_status(&self) -> Result<ProcessingQueueStatus> {
+        let conn = Connection::open(&self.db_path)?;
+        let now = Utc::now();
+        let count = |sql: &str, since: Option<chrono::DateTime<Utc>>| -> Result<i64> {
+            Ok(match since {
+                Some(since) => conn.query_row(sql, params![since.to_rfc3339()], |row| row.get(0))?,
+                None => conn.query_row(sql, [], |row| row.get(0))?,
+            })
+        };
+        let pending = count("SELECT COUNT(*) FROM processing_queue WHERE status = 'pending'", None)?;
+        let retrying = count("SELECT COUNT(*) FROM processing_queue WHERE status = 'pending' AND attempts > 0", None)?;
+        let processing = count("SELECT COUNT(*) FROM processing_queue WHERE status = 'processing'", None)?;
+        let failed = count("SELECT COUNT(*) FROM processing_queue WHERE status = 'failed'", None)?;
+        let completed_last_hour = count(
+            "SELECT COUNT(*) FROM processing_queue WHERE status = 'completed' AND completed_at >= ?1",
+            Some(now - chrono::Duration::hours(1)),
+        )?;
+        let completed_in_window = count(
+            "SELECT COUNT(*) FROM processing_queue WHERE status = 'completed' AND completed_at >= ?1",
+            Some(now - chrono::Duration::minutes(THROUGHPUT_WINDOW_MINUTES)),
+        )?;
+        let oldest_pending_at = conn.query_row(
+            "SELECT MIN(created_at) FROM processing_queue WHERE status = 'pending'",
+            [],
+            |row| row.get(0),
+        )?;
+        let last_error = conn.query_row(
+            "SELECT error_message FROM processing_queue
+             WHERE error_message IS NOT NULL AND status IN ('pending', 'failed')
+             ORDER BY COALESCE(completed_at, started_at) DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(ProcessingQueueStatus {
+            pending,
+            retrying,
+            processing,
+            failed,
+            completed_last_hour,
+            documents_per_minute: completed_in_window as f64 / THROUGHPUT_WINDOW_MINUTES as f64,
+            concurrency: self.get_settings().embedding_concurrency.max(1),
+            oldest_pending_at,
+            last_error,
+        })
+    }
+    
+    /// Embed a document's chunks; cancellable as `embedding:<document id>`
     async fn process_embeddings(&self, document_id: &str) -> Result<()> {
-        // Wait for embedding service to be ready
-        while !self.embedding_service.is_initialized() {
+        let operation = crate::cancellation::start(
+            format!("embedding:{}", document_id),
+            crate::cancellation::OperationKind::Embedding,
+            document_id,
+        );
+        
+        // Wait for embedding service to be ready; one provider for the whole document
+        let embedder = self.embedder();
+        while !embedder.is_initialized() {
+            if operation.is_cancelled() {
+                self.update_embedding_status(document_id, "cancelled")?;
+                return Err(anyhow!("Embedding cancelled for document {}", document_id));
+            }
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         }
         
@@ -515,24 +1485,36 @@ impl EnhancedRagSystem {
         // Generate embeddings for chunks
         let chunk_texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
         
-        match self.embedding_service.embed_documents(chunk_texts) {
-            Ok(embeddings) => {
-                // Save embeddings to database and search index
-                self.save_embeddings_to_db(document_id, &chunks, &embeddings)?;
-                self.index_chunks_for_search(document_id, &chunks, &embeddings).await?;
-                
-                // Update document status
-                self.update_embedding_status(document_id, "completed")?;
-                self.update_document_cached_status(document_id, true)?;
-                
-                println!("Successfully processed embeddings for document {}", document_id);
+        let mut embeddings = Vec::with_capacity(chunk_texts.len());
+        for batch in chunk_texts.chunks(EMBEDDING_BATCH_SIZE) {
+            if operation.is_cancelled() {
+                self.update_embedding_status(document_id, "cancelled")?;
+                return Err(anyhow!("Embedding cancelled for document {}", document_id));
             }
-            Err(e) => {
-                self.update_embedding_status(document_id, "failed")?;
-                return Err(anyhow!("Failed to generate embeddings: {}", e));
+            match embedder.embed_documents(batch.to_vec()).await {
+                Ok(batch_embeddings) => embeddings.extend(batch_embeddings),
+                Err(e) => {
+                    self.update_embedding_status(document_id, "failed")?;
+                    return Err(anyhow!("Failed to generate embeddings: {}", e));
+                }
             }
+            // Gives a cancel request a chance to land between batches
+            tokio::task::yield_now().await;
+        }
+        
+        // Save embeddings to database and search index
+        self.save_embeddings_to_db(&chunks, &embeddings, &embedder.model_id())?;
+        self.index_chunks_for_search(document_id, &chunks, &embeddings).await?;
+        
+        // Update document status
+        self.update_embedding_status(document_id, "completed")?;
+        self.update_document_cached_status(document_id, true)?;
+        if let Err(e) = self.enforce_cache_limit(&[document_id.to_string()]) {
+            eprintln!("Failed to evict embeddings past the cache limit: {}", e);
         }
         
+        println!("Successfully processed embeddings for document {}", document_id);
+        
         Ok(())
     }
     
@@ -564,25 +1546,21 @@ impl EnhancedRagSystem {
         Ok(chunks.collect::<Result<Vec<_>, _>>()?)
     }
     
-    fn save_embeddings_to_db(&self, document_id: &str, chunks: &[EnhancedDocumentChunk], embeddings: &[Vec<f32>]) -> Result<()> {
+    fn save_embeddings_to_db(&self, chunks: &[EnhancedDocumentChunk], embeddings: &[Vec<f32>], model: &str) -> Result<()> {
         let conn = Connection::open(&self.db_path)?;
         
         for (chunk, embedding) in chunks.iter().zip(embeddings.iter()) {
-            // Serialize embedding as bytes
-            let embedding_bytes = embedding.iter()
-                .flat_map(|&f| f.to_le_bytes().to_vec())
-                .collect::<Vec<u8>>();
-            
             conn.execute(
-                "UPDATE enhanced_document_chunks SET embedding = ?1 WHERE id = ?2",
-                params![embedding_bytes, chunk.id],
+                "UPDATE enhanced_document_chunks SET embedding = ?1, embedding_model = ?2, embedding_dim = ?3 WHERE id = ?4",
+                params![embedding_bytes(embedding), model, embedding.len() as i64, chunk.id],
             )?;
         }
         
         Ok(())
     }
     
-    async fn index_chunks_for_search(&self, _document_id: &str, chunks: &[EnhancedDocumentChunk], embeddings: &[Vec<f32>]) -> Result<()> {
+    /// Replace a document's search entries with `chunks`; its old ones stay searchable by BM25 until now
+    async fn index_chunks_for_search(&self, document_id: &str, chunks: &[EnhancedDocumentChunk], embeddings: &[Vec<f32>]) -> Result<()> {
         let search_chunks: Vec<crate::search_service::DocumentChunk> = chunks.iter()
             .zip(embeddings.iter())
             .map(|(chunk, embedding)| crate::search_service::DocumentChunk {
@@ -594,6 +1572,7 @@ impl EnhancedRagSystem {
             })
             .collect();
         
+        self.search_service.delete_document(document_id)?;
         self.search_service.add_documents(search_chunks)?;
         self.search_service.commit()?;
         
@@ -624,13 +1603,26 @@ impl EnhancedRagSystem {
         Ok(())
     }
     
-    pub async fn search_documents(&self, query: &str, context_document_ids: Vec<String>) -> Result<Vec<EnhancedDocumentChunk>> {
+    pub async fn search_documents(&self, query: &str, context_document_ids: Vec<String>, collection_id: Option<&str>) -> Result<Vec<EnhancedDocumentChunk>> {
+        Ok(self.search_relevant(query, context_document_ids, collection_id).await?.chunks)
+    }
+    
+    /// Search, keeping only chunks that clear the configured score thresholds. When none do,
+    /// `insufficient_context` is set instead of passing weak matches off as sources. With a
+    /// `collection_id`, only documents in that collection are searched.
+    pub async fn search_relevant(&self, query: &str, context_document_ids: Vec<String>, collection_id: Option<&str>) -> Result<RelevantChunks> {
+        let collection_documents = match collection_id {
+            Some(collection_id) => Some(self.collection_document_ids(collection_id)?),
+            None => None,
+        };
+        
         // Update access count for queried documents
         self.update_document_access(&context_document_ids)?;
         
         // Generate query embedding
-        let query_embedding = if self.embedding_service.is_initialized() {
-            match self.embedding_service.embed_query(query) {
+        let embedder = self.embedder();
+        let query_embedding = if embedder.is_initialized() {
+            match embedder.embed_query(query).await {
                 Ok(emb) => Some(emb),
                 Err(e) => {
                     eprintln!("Failed to generate query embedding: {}", e);
@@ -642,41 +1634,71 @@ impl EnhancedRagSystem {
         };
         
         // Perform search
-        let search_results = if let Some(embedding) = query_embedding {
+        let search_results = if let Some(embedding) = &query_embedding {
             // Use hybrid search (BM25 + vector)
-            self.search_service.hybrid_search(query, &embedding, 20)?
+            self.search_service.hybrid_search(query, embedding, 20)?
         } else {
             // Fall back to BM25 only
             self.search_service.search_bm25(query, 20)?
         };
         
-        // Filter by context documents if specified
-        let filtered_results = if context_document_ids.is_empty() {
-            search_results
-        } else {
-            search_results.into_iter()
-                .filter(|result| context_document_ids.contains(&result.document_id))
-                .collect()
-        };
+        // Filter by context documents and collection if specified
+        let filtered_results: Vec<SearchResult> = search_results.into_iter()
+            .filter(|result| context_document_ids.is_empty() || context_document_ids.contains(&result.document_id))
+            .filter(|result| match &collection_documents {
+                Some(ids) => ids.contains(&result.document_id),
+                None => true,
+            })
+            .collect();
         
         // Convert search results to enhanced document chunks
-        let enhanced_chunks = self.convert_search_results_to_chunks(filtered_results)?;
+        let enhanced_chunks = self.convert_search_results_to_chunks(filtered_results, query_embedding.as_deref())?;
+        
+        // Drop matches too weak to cite
+        let config = self.get_settings().search_config;
+        let candidates = enhanced_chunks.len();
+        let chunks: Vec<EnhancedDocumentChunk> = enhanced_chunks
+            .into_iter()
+            .filter(|(chunk, similarity)| config.is_relevant(chunk.bm25_score.unwrap_or(0.0), *similarity))
+            .map(|(chunk, _)| chunk)
+            .collect();
+        let below_threshold = candidates - chunks.len();
+        if below_threshold > 0 {
+            println!("🔎 {} of {} matches for the query were below the relevance thresholds", below_threshold, candidates);
+        }
         
-        Ok(enhanced_chunks)
+        Ok(RelevantChunks {
+            insufficient_context: chunks.is_empty(),
+            chunks,
+            below_threshold,
+        })
     }
     
-    fn convert_search_results_to_chunks(&self, search_results: Vec<SearchResult>) -> Result<Vec<EnhancedDocumentChunk>> {
+    // Chunks for `search_results` in order, each with its cosine similarity to `query_embedding`
+    // when both have an embedding
+    fn convert_search_results_to_chunks(&self, search_results: Vec<SearchResult>, query_embedding: Option<&[f32]>) -> Result<Vec<(EnhancedDocumentChunk, Option<f32>)>> {
         let conn = Connection::open(&self.db_path)?;
+        let model = self.embedding_model();
         let mut chunks = Vec::new();
         
         for result in search_results {
+            // An embedding from another model isn't comparable with the query's
             let mut stmt = conn.prepare(
-                "SELECT id, document_id, chunk_index, content, start_char, end_char, token_count, metadata
+                "SELECT id, document_id, chunk_index, content, start_char, end_char, token_count, metadata,
+                        CASE WHEN embedding_model = ?2 THEN embedding END
                  FROM enhanced_document_chunks WHERE id = ?1"
             )?;
             
-            let chunk_result = stmt.query_row([&result.chunk_id], |row| {
-                Ok(EnhancedDocumentChunk {
+            let chunk_result = stmt.query_row(params![result.chunk_id, model], |row| {
+                let stored: Option<Vec<u8>> = row.get(8)?;
+                let similarity = query_embedding.zip(stored).map(|(query, bytes)| {
+                    let embedding: Vec<f32> = bytes
+                        .chunks_exact(4)
+                        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                        .collect();
+                    cosine_similarity(query, &embedding)
+                });
+                let chunk = EnhancedDocumentChunk {
                     id: row.get(0)?,
                     document_id: row.get(1)?,
                     chunk_index: row.get(2)?,
@@ -685,10 +1707,11 @@ impl EnhancedRagSystem {
                     end_char: row.get(5)?,
                     token_count: row.get(6)?,
                     embedding: None,
-                    similarity_score: Some(result.score),
+                    similarity_score: Some(similarity.unwrap_or(result.score)),
                     bm25_score: Some(result.bm25_score),
                     metadata: row.get(7)?,
-                })
+                };
+                Ok((chunk, similarity))
             });
             
             if let Ok(chunk) = chunk_result {
@@ -719,13 +1742,162 @@ impl EnhancedRagSystem {
         Ok(())
     }
     
+    // Documents matching `filter`, least recently used first
+    fn documents_by_use(&self, filter: &str) -> Result<Vec<CachedDocument>> {
+        let conn = Connection::open(&self.db_path)?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, last_accessed, created_at, access_count FROM enhanced_documents WHERE {}",
+            filter
+        ))?;
+        let mut documents = stmt.query_map([], |row| {
+            Ok(CachedDocument {
+                id: row.get(0)?,
+                last_accessed: row.get(1)?,
+                created_at: row.get(2)?,
+                access_count: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        storage_quota::rank_by_use(&mut documents);
+        Ok(documents)
+    }
+    
+    /// Make room for an upload of `bytes` within the collection quota, archiving the least
+    /// recently used originals first when archiving is on
+    fn reserve_storage(&self, bytes: u64) -> Result<()> {
+        let (max_collection_size_gb, archive) = {
+            let settings = self.settings.lock().unwrap();
+            (settings.max_collection_size_gb, settings.archive_evicted_files)
+        };
+        if max_collection_size_gb <= 0.0 {
+            return Ok(());
+        }
+        let quota = storage_quota::quota_bytes(max_collection_size_gb);
+        let mut used = storage_quota::directory_size(&self.storage_path);
+        if used + bytes <= quota {
+            return Ok(());
+        }
+        
+        if archive {
+            let mut archived = Vec::new();
+            for document in self.documents_by_use("archived = 0 AND deleted_at IS NULL")? {
+                if used + bytes <= quota {
+                    break;
+                }
+                match self.archive_original(&document.id) {
+                    Ok(Some(saved)) => {
+                        used = used.saturating_sub(saved);
+                        archived.push(document.id);
+                    }
+                    Ok(None) => {}
+                    Err(e) => eprintln!("Failed to archive the original of {}: {}", document.id, e),
+                }
+            }
+            if !archived.is_empty() {
+                println!("Archived {} originals to make room for an upload", archived.len());
+                let _ = self.app_handle.emit("enhanced-rag-documents-evicted", EvictionEvent {
+                    archived_files: archived.len(),
+                    document_ids: archived,
+                    reason: "quota",
+                });
+            }
+            if used + bytes <= quota {
+                return Ok(());
+            }
+        }
+        
+        let _ = self.app_handle.emit("enhanced-rag-storage-quota", QuotaEvent { used_bytes: used, quota_bytes: quota, requested_bytes: bytes });
+        Err(anyhow!(
+            "Storage quota of {:.1}GB reached: {:.1}MB in use, the upload needs {:.1}MB",
+            max_collection_size_gb, used as f64 / (1024.0 * 1024.0), bytes as f64 / (1024.0 * 1024.0)
+        ))
+    }
+    
+    // Compress a document's original file; the bytes saved, or `None` when there was nothing to archive
+    fn archive_original(&self, document_id: &str) -> Result<Option<u64>> {
+        let conn = Connection::open(&self.db_path)?;
+        let file_path: Option<String> = conn.query_row(
+            "SELECT file_path FROM enhanced_documents WHERE id = ?1 AND archived = 0",
+            params![document_id],
+            |row| row.get(0),
+        ).optional()?;
+        let Some(file_path) = file_path.map(PathBuf::from) else { return Ok(None) };
+        if !file_path.starts_with(&self.storage_path) || !file_path.is_file() {
+            return Ok(None);
+        }
+        let saved = storage_quota::archive_file(&file_path)?;
+        conn.execute("UPDATE enhanced_documents SET archived = 1 WHERE id = ?1", params![document_id])?;
+        Ok(Some(saved))
+    }
+    
+    // Drop the archive of a document's original once the original is written again
+    fn clear_archive(&self, document_id: &str, file_path: &Path) -> Result<()> {
+        let _ = fs::remove_file(storage_quota::archive_path(file_path));
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute("UPDATE enhanced_documents SET archived = 0 WHERE id = ?1", params![document_id])?;
+        Ok(())
+    }
+    
+    /// Unload the embeddings of the least recently used documents past `max_cached_documents`,
+    /// archiving their originals when that's on. `keep` (documents just embedded) are never
+    /// picked. Returns the evicted document ids.
+    pub fn enforce_cache_limit(&self, keep: &[String]) -> Result<Vec<String>> {
+        let (limit, archive) = {
+            let settings = self.settings.lock().unwrap();
+            (settings.max_cached_documents, settings.archive_evicted_files)
+        };
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut cached = self.documents_by_use("is_cached = 1 AND deleted_at IS NULL")?;
+        let kept = cached.iter().filter(|document| keep.contains(&document.id)).count();
+        cached.retain(|document| !keep.contains(&document.id));
+        let evicted = storage_quota::eviction_order(cached, limit.saturating_sub(kept));
+        if evicted.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let conn = Connection::open(&self.db_path)?;
+        let now = Utc::now().to_rfc3339();
+        let mut archived_files = 0;
+        for document in &evicted {
+            self.search_service.unload_vectors(&document.id)?;
+            conn.execute(
+                "UPDATE enhanced_document_chunks SET embedding = NULL, embedding_model = NULL, embedding_dim = NULL WHERE document_id = ?1",
+                params![document.id],
+            )?;
+            conn.execute(
+                "UPDATE enhanced_documents SET is_cached = 0, embedding_status = 'evicted', updated_at = ?1 WHERE id = ?2",
+                params![now, document.id],
+            )?;
+            if archive {
+                match self.archive_original(&document.id) {
+                    Ok(Some(_)) => archived_files += 1,
+                    Ok(None) => {}
+                    Err(e) => eprintln!("Failed to archive the original of {}: {}", document.id, e),
+                }
+            }
+        }
+        // Saves the vector index without the unloaded vectors
+        self.search_service.commit()?;
+        
+        let document_ids: Vec<String> = evicted.into_iter().map(|document| document.id).collect();
+        println!("Evicted embeddings of {} least recently used documents", document_ids.len());
+        let _ = self.app_handle.emit("enhanced-rag-documents-evicted", EvictionEvent {
+            document_ids: document_ids.clone(),
+            reason: "cache_limit",
+            archived_files,
+        });
+        Ok(document_ids)
+    }
+    
     pub fn get_all_documents(&self) -> Result<Vec<EnhancedDocument>> {
         let conn = Connection::open(&self.db_path)?;
         let mut stmt = conn.prepare(
             "SELECT id, file_name, file_path, file_type, file_size, content,
                     created_at, updated_at, access_count, last_accessed, is_cached,
-                    embedding_status, chunk_count, metadata, content_hash
+                    embedding_status, chunk_count, metadata, content_hash, collection_id
              FROM enhanced_documents
+             WHERE deleted_at IS NULL
              ORDER BY created_at DESC"
         )?;
         
@@ -746,6 +1918,7 @@ impl EnhancedRagSystem {
                 chunk_count: row.get(12)?,
                 metadata: row.get(13)?,
                 content_hash: row.get(14)?,
+                collection_id: row.get(15)?,
             })
         })?;
         
@@ -759,6 +1932,7 @@ impl EnhancedRagSystem {
         
         // Delete from database (cascades to chunks)
         let conn = Connection::open(&self.db_path)?;
+        conn.execute("DELETE FROM processing_queue WHERE document_id = ?1", params![document_id])?;
         conn.execute("DELETE FROM enhanced_documents WHERE id = ?1", params![document_id])?;
         
         // Delete files from storage
@@ -770,8 +1944,352 @@ impl EnhancedRagSystem {
         Ok(())
     }
     
+    /// Delete many documents in one database transaction and one search-index commit.
+    /// `progress` is called with (done, total, document id) after each document.
+    pub fn delete_documents(&self, operation_id: &str, document_ids: &[String], progress: impl Fn(usize, usize, &str)) -> Result<BulkOperationResult> {
+        let ids = unique_ids(document_ids);
+        let mut result = BulkOperationResult::new(operation_id);
+        
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        for (index, id) in ids.iter().enumerate() {
+            // Foreign keys aren't enforced on these connections, so chunks and queue entries go explicitly
+            tx.execute("DELETE FROM enhanced_document_chunks WHERE document_id = ?1", params![id])?;
+            tx.execute("DELETE FROM processing_queue WHERE document_id = ?1", params![id])?;
+            if tx.execute("DELETE FROM enhanced_documents WHERE id = ?1", params![id])? == 0 {
+                result.fail(id, "Document not found");
+            } else {
+                result.succeeded.push(id.clone());
+            }
+            progress(index + 1, ids.len(), id);
+        }
+        tx.commit()?;
+        
+        // The index only changes once the rows are gone for good
+        for id in &result.succeeded {
+            self.search_service.delete_document(id)?;
+        }
+        self.search_service.commit()?;
+        
+        for id in &result.succeeded {
+            let doc_path = self.storage_path.join(id);
+            if doc_path.exists() {
+                if let Err(e) = fs::remove_dir_all(&doc_path) {
+                    eprintln!("Failed to remove storage for document {}: {}", id, e);
+                }
+            }
+        }
+        Ok(result)
+    }
+    
+    /// Re-embed many documents, writing the embeddings in one transaction and re-indexing them
+    /// with one search-index commit. Stops between batches once `token` is cancelled; documents
+    /// embedded by then are still saved.
+    pub async fn reembed_documents(
+        &self,
+        operation_id: &str,
+        document_ids: &[String],
+        token: &crate::cancellation::CancellationToken,
+        progress: impl Fn(usize, usize, &str) + Send + Sync,
+    ) -> Result<BulkOperationResult> {
+        let embedder = self.embedder();
+        if !embedder.is_initialized() {
+            return Err(anyhow!("Embedding service not initialized"));
+        }
+        let ids = unique_ids(document_ids);
+        let mut result = BulkOperationResult::new(operation_id);
+        let mut embedded: Vec<(String, Vec<EnhancedDocumentChunk>, Vec<Vec<f32>>)> = Vec::new();
+        
+        for (index, id) in ids.iter().enumerate() {
+            match self.embed_for_bulk(&*embedder, id, token).await {
+                Ok((chunks, embeddings)) => embedded.push((id.clone(), chunks, embeddings)),
+                Err(e) => result.fail(id, e),
+            }
+            progress(index + 1, ids.len(), id);
+        }
+        
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        let now = Utc::now().to_rfc3339();
+        let model = embedder.model_id();
+        for (id, chunks, embeddings) in &embedded {
+            for (chunk, embedding) in chunks.iter().zip(embeddings.iter()) {
+                tx.execute(
+                    "UPDATE enhanced_document_chunks SET embedding = ?1, embedding_model = ?2, embedding_dim = ?3 WHERE id = ?4",
+                    params![embedding_bytes(embedding), model, embedding.len() as i64, chunk.id],
+                )?;
+            }
+            tx.execute(
+                "UPDATE enhanced_documents SET embedding_status = 'completed', is_cached = 1, updated_at = ?1 WHERE id = ?2",
+                params![now, id],
+            )?;
+        }
+        tx.commit()?;
+        
+        // Old entries go first so a re-embedded chunk isn't indexed twice
+        let mut search_chunks = Vec::new();
+        for (id, chunks, embeddings) in embedded {
+            self.search_service.delete_document(&id)?;
+            search_chunks.extend(chunks.into_iter().zip(embeddings).map(|(chunk, embedding)| crate::search_service::DocumentChunk {
+                id: chunk.id,
+                document_id: chunk.document_id,
+                content: chunk.content,
+                embedding: Some(embedding),
+                metadata: chunk.metadata,
+            }));
+            result.succeeded.push(id);
+        }
+        self.search_service.add_documents(search_chunks)?;
+        self.search_service.commit()?;
+        if let Err(e) = self.enforce_cache_limit(&result.succeeded) {
+            eprintln!("Failed to evict embeddings past the cache limit: {}", e);
+        }
+        Ok(result)
+    }
+    
+    // One document's chunks and fresh embeddings for `reembed_documents`, which saves them
+    async fn embed_for_bulk(
+        &self,
+        embedder: &dyn EmbeddingProvider,
+        document_id: &str,
+        token: &crate::cancellation::CancellationToken,
+    ) -> std::result::Result<(Vec<EnhancedDocumentChunk>, Vec<Vec<f32>>), String> {
+        token.check()?;
+        let chunks = self.get_document_chunks(document_id).map_err(|e| e.to_string())?;
+        if chunks.is_empty() {
+            return Err("Document not found or has no chunks".to_string());
+        }
+        let set_status = |status: &str| self.update_embedding_status(document_id, status).map_err(|e| e.to_string());
+        set_status("processing")?;
+        
+        let mut embeddings = Vec::with_capacity(chunks.len());
+        for batch in chunks.chunks(EMBEDDING_BATCH_SIZE) {
+            if token.is_cancelled() {
+                set_status("cancelled")?;
+                return Err(crate::cancellation::CANCELLED.to_string());
+            }
+            match embedder.embed_documents(batch.iter().map(|c| c.content.clone()).collect()).await {
+                Ok(batch_embeddings) => embeddings.extend(batch_embeddings),
+                Err(e) => {
+                    set_status("failed")?;
+                    return Err(format!("Failed to generate embeddings: {}", e));
+                }
+            }
+            // Gives a cancel request a chance to land between batches
+            tokio::task::yield_now().await;
+        }
+        Ok((chunks, embeddings))
+    }
+    
+    /// Rebuild the vector index from the embeddings the current model stored with each chunk;
+    /// returns how many went in
+    pub fn rebuild_vector_index(&self) -> Result<usize> {
+        let conn = Connection::open(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.document_id, c.embedding FROM enhanced_document_chunks c
+             JOIN enhanced_documents d ON d.id = c.document_id
+             WHERE c.embedding IS NOT NULL AND c.embedding_model = ?1 AND d.deleted_at IS NULL"
+        )?;
+        let chunks = stmt.query_map([self.embedding_model()], |row| {
+            let bytes: Vec<u8> = row.get(2)?;
+            let embedding: Vec<f32> = bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            Ok((row.get(0)?, row.get(1)?, embedding))
+        })?.collect::<Result<Vec<_>, _>>()?;
+        self.search_service.rebuild_vectors(chunks)
+    }
+    
+    /// Refill the search index from the chunks in the database, after it was found corrupted.
+    /// Every stored chunk goes back in, with its embedding when the current model made it;
+    /// returns how many went in.
+    pub fn rebuild_search_index(&self) -> Result<usize> {
+        let conn = Connection::open(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.document_id, c.content, c.metadata, CASE WHEN c.embedding_model = ?1 THEN c.embedding END
+             FROM enhanced_document_chunks c
+             JOIN enhanced_documents d ON d.id = c.document_id
+             WHERE d.deleted_at IS NULL"
+        )?;
+        let chunks = stmt.query_map([self.embedding_model()], |row| {
+            let embedding: Option<Vec<u8>> = row.get(4)?;
+            Ok(crate::search_service::DocumentChunk {
+                id: row.get(0)?,
+                document_id: row.get(1)?,
+                content: row.get(2)?,
+                embedding: embedding.map(|bytes| {
+                    bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+                }),
+                metadata: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        let count = chunks.len();
+        self.search_service.clear_index()?;
+        self.search_service.add_documents(chunks)?;
+        self.search_service.commit()?;
+        Ok(count)
+    }
+    
+    /// Merge the search index's segments, dropping deleted chunks for good
+    pub fn compact_search_index(&self) -> Result<SearchIndexStats> {
+        self.search_service.compact()
+    }
+    
+    pub fn get_search_index_stats(&self) -> Result<SearchIndexStats> {
+        self.search_service.stats()
+    }
+    
+    /// Add and remove tags on many documents in one transaction
+    pub fn tag_documents(
+        &self,
+        operation_id: &str,
+        document_ids: &[String],
+        add: &[String],
+        remove: &[String],
+        progress: impl Fn(usize, usize, &str),
+    ) -> Result<BulkOperationResult> {
+        let ids = unique_ids(document_ids);
+        let mut result = BulkOperationResult::new(operation_id);
+        let now = Utc::now().to_rfc3339();
+        
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        for (index, id) in ids.iter().enumerate() {
+            let metadata: Option<Option<String>> = tx
+                .query_row("SELECT metadata FROM enhanced_documents WHERE id = ?1", params![id], |row| row.get(0))
+                .optional()?;
+            match metadata.map(|metadata| apply_tags(metadata.as_deref(), add, remove)) {
+                None => result.fail(id, "Document not found"),
+                Some(Err(e)) => result.fail(id, e.to_string()),
+                Some(Ok(metadata)) => {
+                    tx.execute(
+                        "UPDATE enhanced_documents SET metadata = ?1, updated_at = ?2 WHERE id = ?3",
+                        params![metadata, now, id],
+                    )?;
+                    result.succeeded.push(id.clone());
+                }
+            }
+            progress(index + 1, ids.len(), id);
+        }
+        tx.commit()?;
+        Ok(result)
+    }
+    
+    /// Check a collection name is usable and not taken by another collection
+    fn validate_collection_name(conn: &Connection, name: &str, except_id: Option<&str>) -> Result<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Collection name is empty"));
+        }
+        let taken: Option<String> = conn
+            .query_row(
+                "SELECT id FROM enhanced_collections WHERE name = ?1 COLLATE NOCASE AND id != ?2",
+                params![name, except_id.unwrap_or("")],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if taken.is_some() {
+            return Err(anyhow!("A collection named \"{}\" already exists", name));
+        }
+        Ok(name.to_string())
+    }
+    
+    pub fn create_collection(&self, name: &str) -> Result<DocumentCollection> {
+        let conn = Connection::open(&self.db_path)?;
+        let name = Self::validate_collection_name(&conn, name, None)?;
+        let now = Utc::now().to_rfc3339();
+        let collection = DocumentCollection {
+            id: Uuid::new_v4().to_string(),
+            name,
+            created_at: now.clone(),
+            updated_at: now,
+            document_count: 0,
+        };
+        conn.execute(
+            "INSERT INTO enhanced_collections (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![collection.id, collection.name, collection.created_at, collection.updated_at],
+        )?;
+        Ok(collection)
+    }
+    
+    pub fn rename_collection(&self, collection_id: &str, name: &str) -> Result<()> {
+        let conn = Connection::open(&self.db_path)?;
+        let name = Self::validate_collection_name(&conn, name, Some(collection_id))?;
+        let updated = conn.execute(
+            "UPDATE enhanced_collections SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, Utc::now().to_rfc3339(), collection_id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("Collection {} not found", collection_id));
+        }
+        Ok(())
+    }
+    
+    /// Delete a collection. Its documents are kept and end up in no collection.
+    pub fn delete_collection(&self, collection_id: &str) -> Result<()> {
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        tx.execute("UPDATE enhanced_documents SET collection_id = NULL WHERE collection_id = ?1", params![collection_id])?;
+        if tx.execute("DELETE FROM enhanced_collections WHERE id = ?1", params![collection_id])? == 0 {
+            return Err(anyhow!("Collection {} not found", collection_id));
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    pub fn list_collections(&self) -> Result<Vec<DocumentCollection>> {
+        let conn = Connection::open(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.name, c.created_at, c.updated_at,
+                    (SELECT COUNT(*) FROM enhanced_documents d WHERE d.collection_id = c.id AND d.deleted_at IS NULL)
+             FROM enhanced_collections c
+             ORDER BY c.name COLLATE NOCASE"
+        )?;
+        let collections = stmt.query_map([], |row| {
+            Ok(DocumentCollection {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                updated_at: row.get(3)?,
+                document_count: row.get(4)?,
+            })
+        })?;
+        Ok(collections.collect::<Result<Vec<_>, _>>()?)
+    }
+    
+    /// File documents in a collection, or in none with `None`. Returns how many were moved.
+    pub fn assign_documents_to_collection(&self, document_ids: &[String], collection_id: Option<&str>) -> Result<usize> {
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        if let Some(collection_id) = collection_id {
+            let exists: Option<String> = tx
+                .query_row("SELECT id FROM enhanced_collections WHERE id = ?1", params![collection_id], |row| row.get(0))
+                .optional()?;
+            if exists.is_none() {
+                return Err(anyhow!("Collection {} not found", collection_id));
+            }
+        }
+        let mut assigned = 0;
+        for id in unique_ids(document_ids) {
+            assigned += tx.execute(
+                "UPDATE enhanced_documents SET collection_id = ?1 WHERE id = ?2",
+                params![collection_id, id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(assigned)
+    }
+    
+    fn collection_document_ids(&self, collection_id: &str) -> Result<HashSet<String>> {
+        let conn = Connection::open(&self.db_path)?;
+        let mut stmt = conn.prepare("SELECT id FROM enhanced_documents WHERE collection_id = ?1")?;
+        let ids = stmt.query_map([collection_id], |row| row.get(0))?;
+        Ok(ids.collect::<Result<HashSet<String>, _>>()?)
+    }
+    
     pub async fn generate_embeddings(&self, document_id: &str) -> Result<String> {
-        if !self.embedding_service.is_initialized() {
+        if !self.embedder().is_initialized() {
             return Err(anyhow!("Embedding service not initialized"));
         }
         
@@ -780,7 +2298,7 @@ impl EnhancedRagSystem {
     }
     
     pub async fn generate_embeddings_for_selection(&self, document_ids: &[String]) -> Result<String> {
-        if !self.embedding_service.is_initialized() {
+        if !self.embedder().is_initialized() {
             return Err(anyhow!("Embedding service not initialized"));
         }
         
@@ -810,8 +2328,20 @@ impl EnhancedRagSystem {
     pub fn update_settings(&self, new_settings: EnhancedRagSettings) -> Result<()> {
         // Update in-memory settings
         let mut settings = self.settings.lock().unwrap();
+        let embedding_changed = settings.embedding_config != new_settings.embedding_config;
         *settings = new_settings.clone();
         drop(settings);
+        self.chunking_service.lock().unwrap().update_config(new_settings.chunking_config.clone());
+        
+        if embedding_changed {
+            self.start_embedding_provider(&new_settings.embedding_config);
+            let migrating = self.clone();
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = migrating.migrate_embeddings() {
+                    eprintln!("Failed to start re-embedding for the new model: {}", e);
+                }
+            });
+        }
         
         // Save to database
         let conn = Connection::open(&self.db_path)?;
@@ -824,6 +2354,11 @@ impl EnhancedRagSystem {
             params![settings_json, now],
         )?;
         
+        // The limit may have been lowered
+        if let Err(e) = self.enforce_cache_limit(&[]) {
+            eprintln!("Failed to evict embeddings past the cache limit: {}", e);
+        }
+        
         Ok(())
     }
     
@@ -840,6 +2375,7 @@ impl EnhancedRagSystem {
         
         if let Ok(settings_json) = result {
             if let Ok(stored_settings) = serde_json::from_str::<EnhancedRagSettings>(&settings_json) {
+                self.chunking_service.lock().unwrap().update_config(stored_settings.chunking_config.clone());
                 let mut settings = self.settings.lock().unwrap();
                 *settings = stored_settings;
             }
@@ -856,12 +2392,17 @@ impl EnhancedRagSystem {
         let cached_count: i64 = conn.query_row("SELECT COUNT(*) FROM enhanced_documents WHERE is_cached = 1", [], |row| row.get(0))?;
         let total_chunks: i64 = conn.query_row("SELECT COUNT(*) FROM enhanced_document_chunks", [], |row| row.get(0))?;
         let embedded_chunks: i64 = conn.query_row("SELECT COUNT(*) FROM enhanced_document_chunks WHERE embedding IS NOT NULL", [], |row| row.get(0))?;
+        let evicted_count: i64 = conn.query_row("SELECT COUNT(*) FROM enhanced_documents WHERE embedding_status = 'evicted'", [], |row| row.get(0))?;
+        let archived_count: i64 = conn.query_row("SELECT COUNT(*) FROM enhanced_documents WHERE archived = 1", [], |row| row.get(0))?;
         
         let mut stats = HashMap::new();
         stats.insert("total_documents".to_string(), serde_json::json!(doc_count));
         stats.insert("total_size_bytes".to_string(), serde_json::json!(total_size));
         stats.insert("total_size_mb".to_string(), serde_json::json!(total_size as f64 / (1024.0 * 1024.0)));
         stats.insert("cached_documents".to_string(), serde_json::json!(cached_count));
+        stats.insert("evicted_documents".to_string(), serde_json::json!(evicted_count));
+        stats.insert("archived_documents".to_string(), serde_json::json!(archived_count));
+        stats.insert("storage_used_bytes".to_string(), serde_json::json!(storage_quota::directory_size(&self.storage_path)));
         stats.insert("total_chunks".to_string(), serde_json::json!(total_chunks));
         stats.insert("embedded_chunks".to_string(), serde_json::json!(embedded_chunks));
         stats.insert("embedding_coverage".to_string(), serde_json::json!(
@@ -870,6 +2411,7 @@ impl EnhancedRagSystem {
         
         let settings = self.settings.lock().unwrap();
         stats.insert("max_cached_documents".to_string(), serde_json::json!(settings.max_cached_documents));
+        stats.insert("max_collection_size_gb".to_string(), serde_json::json!(settings.max_collection_size_gb));
         stats.insert("max_document_size_mb".to_string(), serde_json::json!(settings.max_document_size_mb));
         stats.insert("embedding_model".to_string(), serde_json::json!(settings.embedding_config.model_name));
         stats.insert("reranking_enabled".to_string(), serde_json::json!(settings.reranking_enabled));
@@ -906,7 +2448,7 @@ impl EnhancedRagSystem {
                 Ok(embedding_status) => {
                     match embedding_status.as_str() {
                         "completed" => ready_documents.push(doc_id.clone()),
-                        "pending" => {
+                        "pending" | "evicted" => {
                             pending_documents.push(doc_id.clone());
                             // Trigger priority embedding for pending documents
                             let _ = self.queue_priority_embedding_generation(doc_id).await;
@@ -1024,4 +2566,41 @@ impl EnhancedRagSystem {
         
         Ok(status_map)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_tags_merges_into_existing_metadata() {
+        let tags = |tags: &[&str]| tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        let parsed = |metadata: &str| serde_json::from_str::<serde_json::Value>(metadata).unwrap();
+
+        let tagged = apply_tags(None, &tags(&["work", " Q3 ", ""]), &[]).unwrap();
+        assert_eq!(parsed(&tagged)["tags"], serde_json::json!(["work", "Q3"]));
+
+        // Other keys survive, repeats are dropped and removal ignores case
+        let tagged = apply_tags(Some(r#"{"source":"email","tags":["work","q3"]}"#), &tags(&["WORK", "draft"]), &tags(&["Q3"])).unwrap();
+        assert_eq!(parsed(&tagged), serde_json::json!({ "source": "email", "tags": ["work", "draft"] }));
+        assert!(apply_tags(Some("not json"), &tags(&["x"]), &[]).is_err());
+        assert!(apply_tags(Some("[1, 2]"), &tags(&["x"]), &[]).is_err());
+
+        assert_eq!(unique_ids(&tags(&["b", "a", "b"])), tags(&["b", "a"]));
+    }
+
+    #[test]
+    fn test_match_chunks_keeps_unchanged_content() {
+        let old = vec![
+            ("c1".to_string(), content_hash("intro")),
+            ("c2".to_string(), content_hash("body")),
+            ("c3".to_string(), content_hash("body")),
+            ("c4".to_string(), content_hash("outro")),
+        ];
+        // "outro" was edited, one "body" dropped and a chunk inserted at the front
+        let new: Vec<String> = ["preface", "intro", "body", "outro v2"].iter().map(|text| content_hash(text)).collect();
+        let (kept, removed) = match_chunks(&old, &new);
+        assert_eq!(kept, vec![None, Some("c1".to_string()), Some("c2".to_string()), None]);
+        assert_eq!(removed, vec!["c3".to_string(), "c4".to_string()]);
+    }
+}
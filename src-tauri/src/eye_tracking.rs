@@ -31,6 +31,11 @@ pub struct MLTrackingStats {
     pub last_update: u64,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PresenceData {
+    pub faces: u32,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CalibrationPoint {
     pub screen_x: f64,
@@ -157,9 +162,22 @@ impl MLEyeTracker {
                         
                         if trimmed.starts_with("GAZE:") {
                             if let Ok(gaze_data) = serde_json::from_str::<MLGazeData>(&trimmed[5..]) {
-                                if let Ok(mut tracker) = eye_tracker_clone.lock() {
+                                let (x, y, confidence) = (gaze_data.x, gaze_data.y, gaze_data.confidence);
+                                let screen = if let Ok(mut tracker) = eye_tracker_clone.lock() {
                                     tracker.update_gaze_data(gaze_data);
+                                    tracker.config.as_ref().map(|c| (c.screen_width, c.screen_height))
+                                } else {
+                                    None
+                                };
+                                if let Some((width, height)) = screen {
+                                    crate::privacy_mode::observe_gaze(x, y, confidence, width, height);
                                 }
+                                crate::teleprompter::observe_gaze(x, y, confidence);
+                            }
+                        } else if trimmed.starts_with("PRESENCE:") {
+                            // Face count from the presence camera: PRESENCE:{"faces":1}
+                            if let Ok(presence) = serde_json::from_str::<PresenceData>(&trimmed[9..]) {
+                                crate::privacy_mode::observe_presence(presence.faces);
                             }
                         } else if trimmed.starts_with("CALIBRATION:") {
                             if let Ok(cal_point) = serde_json::from_str::<CalibrationPoint>(&trimmed[12..]) {
@@ -200,6 +218,8 @@ impl MLEyeTracker {
         self.is_tracking = false;
         self.is_calibrating = false;
         self.last_gaze_data = None;
+        // Without the tracker there's nothing to judge by, so stale gaze mustn't keep panels hidden
+        crate::privacy_mode::reset_observations();
         
         println!("👁️  Stopped ML eye tracking");
         Ok(())
@@ -301,7 +321,7 @@ impl MLEyeTracker {
 }
 
 // Tauri command implementations with proper error handling
-#[tauri::command]
+#[crate::command]
 pub async fn start_ml_eye_tracking(config: MLEyeTrackingConfig) -> Result<String, String> {
     match get_eye_tracker().lock() {
         Ok(mut tracker) => {
@@ -312,7 +332,7 @@ pub async fn start_ml_eye_tracking(config: MLEyeTrackingConfig) -> Result<String
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn stop_ml_eye_tracking() -> Result<String, String> {
     match get_eye_tracker().lock() {
         Ok(mut tracker) => {
@@ -323,7 +343,7 @@ pub async fn stop_ml_eye_tracking() -> Result<String, String> {
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_ml_gaze_data() -> Result<Option<MLGazeData>, String> {
     match get_eye_tracker().lock() {
         Ok(tracker) => {
@@ -337,7 +357,7 @@ pub async fn get_ml_gaze_data() -> Result<Option<MLGazeData>, String> {
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn calibrate_ml_eye_tracking() -> Result<String, String> {
     match get_eye_tracker().lock() {
         Ok(mut tracker) => {
@@ -349,7 +369,7 @@ pub async fn calibrate_ml_eye_tracking() -> Result<String, String> {
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_ml_tracking_stats() -> Result<MLTrackingStats, String> {
     match get_eye_tracker().lock() {
         Ok(tracker) => Ok(tracker.get_stats().clone()),
@@ -357,7 +377,7 @@ pub async fn get_ml_tracking_stats() -> Result<MLTrackingStats, String> {
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn pause_ml_tracking() -> Result<String, String> {
     match get_eye_tracker().lock() {
         Ok(mut tracker) => {
@@ -368,7 +388,7 @@ pub async fn pause_ml_tracking() -> Result<String, String> {
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn resume_ml_tracking() -> Result<String, String> {
     match get_eye_tracker().lock() {
         Ok(mut tracker) => {
@@ -379,7 +399,7 @@ pub async fn resume_ml_tracking() -> Result<String, String> {
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn detect_window_drag() -> Result<bool, String> {
     match get_eye_tracker().lock() {
         Ok(tracker) => Ok(tracker.detect_window_drag()),
@@ -56,7 +56,7 @@ impl Default for FileValidationConfig {
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn upload_file_base64(
     file_name: String,
     file_data: String, // Base64 encoded
@@ -155,7 +155,7 @@ pub async fn upload_file_base64(
     Ok(result)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn validate_file_upload(
     file_size: u64,
     mime_type: String,
@@ -177,7 +177,7 @@ pub async fn validate_file_upload(
     Ok(true)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_file_upload_config() -> FileValidationConfig {
     FileValidationConfig::default()
 }
@@ -247,7 +247,7 @@ fn extract_docx_text(_docx_data: &[u8]) -> Result<String, String> {
     Err("DOCX text extraction not yet implemented".to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn process_clipboard_image(image_base64: String) -> Result<FileUploadResult, String> {
     let _file_id = format!("clipboard_{}", uuid::Uuid::new_v4());
     let file_name = format!("clipboard_image_{}.png", chrono::Utc::now().timestamp());
@@ -256,7 +256,7 @@ pub async fn process_clipboard_image(image_base64: String) -> Result<FileUploadR
 }
 
 // Add utility for cleanup if needed
-#[tauri::command]
+#[crate::command]
 pub async fn cleanup_temp_files() -> Result<(), String> {
     // Implementation for cleaning up temporary files if we store them locally
     // For now, we're keeping everything in memory/base64
@@ -0,0 +1,333 @@
+// src-tauri/src/folder_watch.rs
+// Watched folders - directories whose documents are kept in the enhanced RAG store without
+// uploading them by hand
+//
+// A file dropped into a watched folder is uploaded, a changed file re-indexes the chunks that
+// changed, and a removed file soft-deletes its document (hidden and out of the search index, so
+// putting the file back restores it without re-embedding). Each import records the file's path
+// and hash in the document metadata, which is how later events find it. Filesystem events are
+// debounced, and every affected path is then reconciled against what's on disk, so a rename or
+// an editor's save-via-temp-file comes out right whatever events it produced. Folders are
+// scanned in full when added and at startup, which catches changes made while the app was closed.
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::mpsc;
+
+use crate::enhanced_rag_commands::EnhancedRagSystemState;
+use crate::enhanced_rag_system::EnhancedRagSystem;
+use crate::json_settings::{load_json_settings, save_json_settings};
+
+// Quiet time after the last event before syncing, so a file being written syncs once
+const DEBOUNCE: Duration = Duration::from_millis(1500);
+
+lazy_static::lazy_static! {
+    static ref WATCHER: Mutex<Option<FolderWatcher>> = Mutex::new(None);
+}
+
+struct FolderWatcher {
+    watcher: RecommendedWatcher,
+    system: EnhancedRagSystem,
+    app_handle: AppHandle,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedFolder {
+    pub path: String,
+    /// Whether subfolders are watched too
+    pub recursive: bool,
+    pub added_at: String,
+}
+
+/// Payload of the `watched-folder-sync` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncEvent<'a> {
+    path: &'a str,
+    /// "added", "updated", "removed" or "restored"
+    action: &'a str,
+    document_id: &'a str,
+}
+
+const SETTINGS_FILE: &str = "watched_folders.json";
+
+fn load_folders() -> Vec<WatchedFolder> {
+    load_json_settings(SETTINGS_FILE)
+}
+
+fn save_folders(folders: &[WatchedFolder]) -> Result<(), String> {
+    save_json_settings(SETTINGS_FILE, "watched folders", folders)
+}
+
+fn recursive_mode(folder: &WatchedFolder) -> RecursiveMode {
+    if folder.recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive }
+}
+
+/// The upload file type for a file name, or `None` for files that aren't imported: unsupported
+/// types, hidden files and the temporary files editors and browsers write while saving
+pub(crate) fn file_type(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    if name.starts_with('.') || name.starts_with("~$") || name.ends_with('~') {
+        return None;
+    }
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    Some(match extension.as_str() {
+        "txt" | "log" | "csv" | "json" | "xml" | "html" | "htm" | "rst" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        // Source files; the extension picks code-aware chunking
+        "rs" | "py" | "js" | "jsx" | "mjs" | "ts" | "tsx" | "go" | "java" | "kt" | "cs" | "swift" | "c" | "h" | "cpp" | "cc" | "hpp" => "text/plain",
+        "pdf" => "application/pdf",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "epub" => "application/epub+zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        _ => return None,
+    })
+}
+
+/// Whether `path` is in `folder` as the folder is watched
+fn in_folder(folder: &WatchedFolder, path: &Path) -> bool {
+    match path.strip_prefix(&folder.path) {
+        Ok(relative) => folder.recursive || relative.components().count() <= 1,
+        Err(_) => false,
+    }
+}
+
+fn file_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn emit(app_handle: &AppHandle, path: &Path, action: &str, document_id: &str) {
+    let path = path.to_string_lossy();
+    println!("📁 Watched file {}: {}", action, path);
+    let _ = app_handle.emit("watched-folder-sync", SyncEvent { path: &path, action, document_id });
+}
+
+/// Bring the store in line with `path` as it is on disk now: import or update a file, restore a
+/// file that came back, soft-delete what's gone (a removed directory takes its files with it)
+async fn sync_path(system: &EnhancedRagSystem, app_handle: &AppHandle, folder: &WatchedFolder, path: &Path) -> anyhow::Result<()> {
+    if path.is_dir() {
+        // A folder moved in may not report its files one by one
+        let mut files = Vec::new();
+        files_in(path, folder.recursive, &mut files);
+        for file in files {
+            sync_file(system, app_handle, folder, &file).await?;
+        }
+    } else if path.is_file() {
+        sync_file(system, app_handle, folder, path).await?;
+    } else {
+        for document in system.source_documents(path)?.iter().filter(|document| !document.deleted) {
+            system.soft_delete_document(&document.id)?;
+            emit(app_handle, Path::new(&document.source_path), "removed", &document.id);
+        }
+    }
+    Ok(())
+}
+
+async fn sync_file(system: &EnhancedRagSystem, app_handle: &AppHandle, folder: &WatchedFolder, path: &Path) -> anyhow::Result<()> {
+    let Some(file_type) = file_type(path) else { return Ok(()) };
+    let content = tokio::fs::read(path).await?;
+    let hash = file_hash(&content);
+    let source_path = path.to_string_lossy().to_string();
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("source".to_string(), "folder".into());
+    metadata.insert("sourcePath".to_string(), source_path.clone().into());
+    metadata.insert("sourceHash".to_string(), hash.clone().into());
+    metadata.insert("watchedFolder".to_string(), folder.path.clone().into());
+
+    match system.source_documents(path)?.into_iter().find(|document| document.source_path == source_path) {
+        None => {
+            let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let document = system.upload_source_file(file_name, content, file_type.to_string(), metadata).await?;
+            emit(app_handle, path, "added", &document.id);
+        }
+        Some(document) => {
+            if document.deleted {
+                system.restore_document(&document.id).await?;
+                emit(app_handle, path, "restored", &document.id);
+            }
+            if document.source_hash.as_deref() != Some(hash.as_str()) {
+                system.update_source_file(&document.id, content, file_type, metadata).await?;
+                emit(app_handle, path, "updated", &document.id);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn files_in(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() && recursive => files_in(&path, recursive, files),
+            Ok(kind) if kind.is_file() => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// Sync every file in `folder`, and soft-delete documents whose file is no longer there
+async fn scan(system: &EnhancedRagSystem, app_handle: &AppHandle, folder: &WatchedFolder) {
+    let mut paths = Vec::new();
+    files_in(Path::new(&folder.path), folder.recursive, &mut paths);
+    if let Ok(known) = system.source_documents(Path::new(&folder.path)) {
+        paths.extend(known.into_iter().map(|document| PathBuf::from(document.source_path)).filter(|path| !path.exists()));
+    }
+    for path in paths {
+        if let Err(e) = sync_path(system, app_handle, folder, &path).await {
+            eprintln!("Failed to sync watched file {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Sync batches of changed paths once they go quiet
+async fn run(system: EnhancedRagSystem, app_handle: AppHandle, mut events: mpsc::UnboundedReceiver<PathBuf>) {
+    while let Some(first) = events.recv().await {
+        let mut pending = BTreeSet::from([first]);
+        while let Ok(Some(path)) = tokio::time::timeout(DEBOUNCE, events.recv()).await {
+            pending.insert(path);
+        }
+        let folders = load_folders();
+        for path in pending {
+            let Some(folder) = folders.iter().find(|folder| in_folder(folder, &path)) else { continue };
+            if let Err(e) = sync_path(&system, &app_handle, folder, &path).await {
+                eprintln!("Failed to sync watched file {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Watch the configured folders for `system`, scanning each for changes made while the app was closed
+pub fn start(app_handle: &AppHandle, system: &EnhancedRagSystem) -> Result<(), String> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| match result {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+            for path in event.paths {
+                let _ = sender.send(path);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Folder watch error: {}", e),
+    })
+    .map_err(|e| format!("Failed to start folder watcher: {}", e))?;
+
+    let folders = load_folders();
+    for folder in &folders {
+        if let Err(e) = watcher.watch(Path::new(&folder.path), recursive_mode(folder)) {
+            eprintln!("Failed to watch {}: {}", folder.path, e);
+        }
+    }
+    tauri::async_runtime::spawn(run(system.clone(), app_handle.clone(), receiver));
+    let (scanning, scan_handle) = (system.clone(), app_handle.clone());
+    tauri::async_runtime::spawn(async move {
+        for folder in &folders {
+            scan(&scanning, &scan_handle, folder).await;
+        }
+    });
+
+    let mut state = WATCHER.lock().map_err(|_| "Folder watcher state poisoned".to_string())?;
+    *state = Some(FolderWatcher { watcher, system: system.clone(), app_handle: app_handle.clone() });
+    Ok(())
+}
+
+/// Watch a folder, importing the documents already in it
+#[crate::command]
+pub async fn add_watched_folder(
+    path: String,
+    recursive: Option<bool>,
+    state: State<'_, EnhancedRagSystemState>,
+) -> Result<WatchedFolder, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    {
+        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
+        if rag_state.is_none() {
+            return Err("Enhanced RAG system not initialized".to_string());
+        }
+    }
+    let canonical = std::fs::canonicalize(path.trim()).map_err(|e| format!("Folder not found: {}", e))?;
+    if !canonical.is_dir() {
+        return Err(format!("{} is not a folder", canonical.display()));
+    }
+    let folder = WatchedFolder {
+        path: canonical.to_string_lossy().to_string(),
+        recursive: recursive.unwrap_or(true),
+        added_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let mut folders = load_folders();
+    if folders.iter().any(|existing| existing.path == folder.path || (existing.recursive && canonical.starts_with(&existing.path))) {
+        return Err(format!("{} is already watched", folder.path));
+    }
+
+    let (system, app_handle) = {
+        let mut watcher = WATCHER.lock().map_err(|_| "Folder watcher state poisoned".to_string())?;
+        let watcher = watcher.as_mut().ok_or("Folder watcher isn't running")?;
+        watcher.watcher
+            .watch(&canonical, recursive_mode(&folder))
+            .map_err(|e| format!("Failed to watch {}: {}", folder.path, e))?;
+        (watcher.system.clone(), watcher.app_handle.clone())
+    };
+    folders.push(folder.clone());
+    save_folders(&folders)?;
+
+    let scanned = folder.clone();
+    tauri::async_runtime::spawn(async move { scan(&system, &app_handle, &scanned).await });
+    Ok(folder)
+}
+
+/// Stop watching a folder. Documents already imported from it stay in the store.
+#[crate::command]
+pub async fn remove_watched_folder(path: String) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::DataDeletion)?;
+    let mut folders = load_folders();
+    let index = folders
+        .iter()
+        .position(|folder| folder.path == path)
+        .ok_or_else(|| format!("{} isn't watched", path))?;
+    let folder = folders.remove(index);
+    save_folders(&folders)?;
+    if let Some(watcher) = WATCHER.lock().map_err(|_| "Folder watcher state poisoned".to_string())?.as_mut() {
+        if let Err(e) = watcher.watcher.unwatch(Path::new(&folder.path)) {
+            eprintln!("Failed to unwatch {}: {}", folder.path, e);
+        }
+    }
+    Ok(())
+}
+
+#[crate::command]
+pub fn list_watched_folders() -> Vec<WatchedFolder> {
+    load_folders()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_types_and_folder_membership() {
+        assert_eq!(file_type(Path::new("/docs/Notes.MD")), Some("text/markdown"));
+        assert_eq!(file_type(Path::new("/docs/report.pdf")), Some("application/pdf"));
+        assert_eq!(file_type(Path::new("/docs/scan.jpeg")), Some("image/jpeg"));
+        // Unsupported, hidden and temporary files are left alone
+        assert_eq!(file_type(Path::new("/docs/setup.exe")), None);
+        assert_eq!(file_type(Path::new("/docs/.notes.txt")), None);
+        assert_eq!(file_type(Path::new("/docs/~$report.docx")), None);
+        assert_eq!(file_type(Path::new("/docs/notes.txt~")), None);
+        assert_eq!(file_type(Path::new("/docs/README")), None);
+
+        let folder = |recursive| WatchedFolder { path: "/docs".to_string(), recursive, added_at: String::new() };
+        assert!(in_folder(&folder(false), Path::new("/docs/a.txt")));
+        assert!(!in_folder(&folder(false), Path::new("/docs/sub/a.txt")));
+        assert!(in_folder(&folder(true), Path::new("/docs/sub/a.txt")));
+        assert!(!in_folder(&folder(true), Path::new("/docsets/a.txt")));
+    }
+}
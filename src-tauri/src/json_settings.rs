@@ -0,0 +1,59 @@
+// src-tauri/src/json_settings.rs
+// Load and save for the small JSON settings files kept in the enteract config directory
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+fn settings_path(file_name: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("enteract").join(file_name))
+}
+
+/// Read a settings file, falling back to the defaults when it's missing or doesn't parse
+pub(crate) fn load_json_settings<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    settings_path(file_name)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Write a settings file; `what` names the settings in error messages
+pub(crate) fn save_json_settings<T: Serialize + ?Sized>(file_name: &str, what: &str, settings: &T) -> Result<(), String> {
+    let path = settings_path(file_name).ok_or("Could not find config directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| format!("Failed to serialize {}: {}", what, e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", what, e))
+}
+
+/// Snapshot of the in-memory settings, or the defaults if the lock is poisoned
+pub(crate) fn read_settings<T: Clone + Default>(settings: &RwLock<T>) -> T {
+    settings.read().map(|settings| settings.clone()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_live_in_the_enteract_config_dir() {
+        if let Some(path) = settings_path("privacy_mode.json") {
+            assert!(path.ends_with("enteract/privacy_mode.json"));
+        }
+    }
+
+    #[test]
+    fn test_poisoned_lock_reads_as_defaults() {
+        let settings = std::sync::Arc::new(RwLock::new(vec![1, 2, 3]));
+        assert_eq!(read_settings(&settings), vec![1, 2, 3]);
+
+        let poisoned = settings.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoned.write().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(read_settings(&settings).is_empty());
+    }
+}
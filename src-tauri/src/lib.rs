@@ -1,28 +1,73 @@
 // src-tauri/src/main.rs
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 // Import our modules
 mod transparency;
 mod window_manager;
+#[cfg(feature = "eye-tracking")]
 mod eye_tracking;
+#[cfg(feature = "eye-tracking")]
+mod teleprompter; // Script overlay that scrolls as the reader's gaze moves down it
 mod speech;
 mod ollama;
 mod screenshot;
 mod file_handler;
 mod data; // Data storage module (JSON, SQLite, migration, hybrid)
+mod shared_types; // Command-boundary types with generated TypeScript bindings
 pub mod audio_loopback; // New audio loopback module
 mod system_prompts; // System prompts module
 mod system_info; // System information module
 mod rag_system; // RAG document system module
 mod rag_commands; // RAG command handlers
+#[cfg(feature = "enhanced-rag")]
 mod simple_embedding_service; // Simple embedding service
+#[cfg(feature = "enhanced-rag")]
+mod ollama_embedding_service; // Embeddings through the local Ollama server
+#[cfg(feature = "enhanced-rag")]
 mod search_service; // Tantivy search service
+#[cfg(feature = "enhanced-rag")]
+mod vector_index; // HNSW index over chunk embeddings
+#[cfg(feature = "enhanced-rag")]
+mod embedding_worker; // Background worker draining the embedding queue
+#[cfg(feature = "enhanced-rag")]
+mod near_duplicate; // MinHash near-duplicate detection for uploads
+#[cfg(feature = "enhanced-rag")]
+mod storage_quota; // Collection quota and LRU eviction for the enhanced RAG store
+#[cfg(feature = "enhanced-rag")]
 mod chunking_service; // Enhanced text chunking service
+#[cfg(feature = "enhanced-rag")]
+mod web_ingest; // Readable article text from web pages
+#[cfg(feature = "enhanced-rag")]
+mod transcript_ingest; // Conversation transcripts as RAG documents
+#[cfg(feature = "enhanced-rag")]
+mod folder_watch; // Watched folders synced into the enhanced RAG store
+#[cfg(feature = "enhanced-rag")]
 mod enhanced_rag_system; // Enhanced RAG system
+#[cfg(feature = "enhanced-rag")]
 mod enhanced_rag_commands; // Enhanced RAG command handlers
+#[cfg(feature = "mcp")]
 mod mcp; // MCP module for multi-command processing
+mod app_lock; // PIN lock for dangerous command groups
+mod command_metrics; // IPC command timing metrics
+mod ndjson_parser; // Tolerant NDJSON parsing for Ollama streams
+mod accessibility; // Screen reader announcements for backend events
+mod whisper_models; // Whisper model downloads, variants and registry
+mod whisper_backend; // CPU/GPU backend selection for Whisper inference
+mod action_registry; // Searchable action registry for the command palette
+mod json_settings; // Load and save for the JSON settings files in the config directory
+mod privacy_mode; // Gaze-contingent blur/hide of sensitive panels
+mod permissions; // macOS privacy permission preflight and settings deep links
+mod disk_space; // Free-space monitoring, quotas and pre-flight checks before large writes
+mod offline_mode; // Connectivity monitor and manual switch that disable internet-only features
+mod coordinates; // Screenshot/desktop/input coordinate mapping across mixed-DPI monitors
+#[cfg(feature = "wake-word")]
+mod wake_word; // Always-on wake phrase listener for hands-free activation
+pub mod worker_process; // Out-of-process transcription and OCR with automatic respawn
+mod onboarding; // First-run setup steps driving the frontend wizard
+mod cancellation; // Cancellation tokens for long-running operations, cancelled by id
+mod startup; // Staged startup: setup, deferred and lazy initialization with timings
 
 // Re-export the commands from modules
 use transparency::{set_window_transparency, emergency_restore_window, toggle_transparency, initialize_window_transparency};
@@ -30,25 +75,43 @@ use window_manager::{
     move_window_to_position, get_window_position, get_window_size, get_screen_size,
     get_virtual_desktop_size, get_monitor_layout, set_window_bounds
 };
+#[cfg(feature = "eye-tracking")]
 use eye_tracking::{
     start_ml_eye_tracking, stop_ml_eye_tracking, get_ml_gaze_data, calibrate_ml_eye_tracking,
     get_ml_tracking_stats, pause_ml_tracking, resume_ml_tracking, detect_window_drag
 };
+#[cfg(feature = "eye-tracking")]
+use teleprompter::{
+    start_teleprompter, stop_teleprompter, get_teleprompter, set_teleprompter_position,
+    set_teleprompter_paused, set_teleprompter_layout
+};
 use speech::{
     initialize_whisper_model, transcribe_audio_base64, transcribe_audio_file,
-    check_whisper_model_availability, download_whisper_model, list_available_models
+    check_whisper_model_availability, download_whisper_model, list_available_models,
+    get_transcription_provider, set_transcription_provider
+};
+use whisper_models::{
+    list_whisper_models, get_whisper_models_disk_usage, delete_whisper_model, evict_whisper_models
 };
+use whisper_backend::{get_whisper_backend, set_whisper_backend, benchmark_whisper_backends};
 use ollama::{
-    get_ollama_models, get_ollama_status, pull_ollama_model, delete_ollama_model,
+    get_ollama_models, get_ollama_status, pull_ollama_model, cancel_pull, delete_ollama_model,
     generate_ollama_response, generate_ollama_response_stream, get_ollama_model_info,
     generate_enteract_agent_response, generate_vision_analysis, generate_deep_research,
     generate_conversational_ai, generate_coding_agent_response, cancel_ai_response,
-    get_gpu_acceleration_status,
-
-    // MCP enhanced commands
-    generate_mcp_enabled_response, create_mcp_session_for_ai, get_mcp_session_for_ai
+    get_gpu_acceleration_status, get_llm_backends, save_llm_backends, list_backend_models,
+    set_llm_endpoint, get_agent_registry, update_agent_config, reset_agent_config,
+    get_context_stats, run_scheduled_task_now, generate_structured_response, compare_models,
+    get_model_comparisons, check_agent_models, get_model_gpu_layers,
+    get_response_cache_settings, save_response_cache_settings, clear_response_cache,
+    list_prompt_templates, get_prompt_template, save_prompt_template, delete_prompt_template,
+    preview_prompt_template, set_prompt_variable,
 };
-use screenshot::{capture_screenshot, capture_screenshot_area};
+#[cfg(feature = "enhanced-rag")]
+use ollama::generate_rag_response;
+#[cfg(feature = "mcp")]
+use ollama::{generate_mcp_enabled_response, create_mcp_session_for_ai, get_mcp_session_for_ai};
+use screenshot::{capture_screenshot, capture_screenshot_area, list_monitors};
 use file_handler::{
     upload_file_base64, validate_file_upload, get_file_upload_config,
     process_clipboard_image, cleanup_temp_files
@@ -59,9 +122,30 @@ use file_handler::{
 use audio_loopback::{
     enumerate_loopback_devices, auto_select_best_device, test_audio_device,
     save_audio_settings, load_audio_settings, save_general_settings, load_general_settings,
-    start_audio_loopback_capture, stop_audio_loopback_capture, process_audio_for_transcription
+    start_audio_loopback_capture, stop_audio_loopback_capture, process_audio_for_transcription,
+    configure_capture_buffer, get_capture_buffer_stats, get_device_dsp_config, set_device_dsp_config,
+    get_device_channel_mix, set_device_channel_mix, get_downmix_matrix,
+    start_session_recording, stop_session_recording, get_session_recording,
+    get_device_aliases, set_device_alias, get_capture_timer, extend_capture,
+    list_audio_sessions, set_audio_session_volume, set_audio_session_mute,
+    get_audio_ducking_config, set_audio_ducking_config
 };
-use system_info::get_system_info;
+use system_info::{get_system_info, get_build_capabilities};
+use app_lock::{get_app_lock_status, unlock_app, lock_app, set_app_lock_pin, disable_app_lock};
+use accessibility::{announce_accessibility_message, get_accessibility_settings, save_accessibility_settings};
+// `#[crate::command]` declares every IPC command, so each one is timed and measured
+use command_metrics::{command, get_command_metrics, reset_command_metrics};
+use action_registry::{list_actions, invoke_action};
+use privacy_mode::{get_privacy_mode, save_privacy_settings, set_privacy_override};
+use permissions::{get_permission_statuses, get_permission_status, open_permission_settings};
+use disk_space::{get_disk_space_settings, get_disk_space_status, save_disk_space_settings};
+use offline_mode::{check_connectivity_now, get_offline_status, set_offline_mode};
+#[cfg(feature = "wake-word")]
+use wake_word::{get_wake_word_status, save_wake_word_settings, start_wake_word, stop_wake_word};
+use worker_process::{get_worker_processes, save_worker_process_settings, restart_worker_process};
+use onboarding::{get_setup_state, run_setup_step, skip_setup_step, complete_setup, reset_setup};
+use cancellation::{cancel_operation, list_operations};
+use startup::get_startup_report;
 
 // Import RAG commands
 use rag_commands::{
@@ -71,55 +155,116 @@ use rag_commands::{
 };
 
 // Import Enhanced RAG commands
+#[cfg(feature = "enhanced-rag")]
 use enhanced_rag_commands::{
-    EnhancedRagSystemState, initialize_enhanced_rag_system, upload_enhanced_document,
+    EnhancedRagSystemState, initialize_enhanced_rag_system, upload_enhanced_document, update_enhanced_document, replace_enhanced_document, ingest_url, index_conversation,
     get_all_enhanced_documents, delete_enhanced_document, search_enhanced_documents,
     generate_enhanced_embeddings, clear_enhanced_embedding_cache, update_enhanced_rag_settings,
-    get_enhanced_rag_settings, get_enhanced_storage_stats, get_embedding_status,
+    get_enhanced_rag_settings, get_enhanced_storage_stats, get_processing_queue_status, get_embedding_migration_status, get_embedding_status,
     validate_enhanced_file_upload, check_document_duplicate, get_document_embedding_status,
-    ensure_documents_ready_for_search, generate_embeddings_for_selection
+    ensure_documents_ready_for_search, generate_embeddings_for_selection,
+    bulk_delete_enhanced_documents, bulk_reembed_enhanced_documents, bulk_tag_enhanced_documents,
+    rebuild_vector_index, compact_search_index, get_search_index_stats, create_enhanced_collection, rename_enhanced_collection, delete_enhanced_collection,
+    list_enhanced_collections, assign_documents_to_collection
 };
+#[cfg(feature = "enhanced-rag")]
+use folder_watch::{add_watched_folder, remove_watched_folder, list_watched_folders};
 
 // Import MCP commands
+#[cfg(feature = "mcp")]
 use mcp::{
     start_mcp_session, end_mcp_session, get_mcp_session_info, list_mcp_tools,
     execute_mcp_tool, respond_to_mcp_approval, get_mcp_session_logs, 
     list_active_mcp_sessions, create_mcp_session_manager, get_mcp_tool_schema,
     get_mcp_session_status, create_execution_plan, approve_execution_plan,
-    execute_approved_plan, MCPSessionManager
+    execute_approved_plan, get_ocr_languages, get_mcp_content_policy, set_mcp_content_policy,
+    check_mcp_typed_text, get_mcp_recording_settings, save_mcp_recording_settings, list_mcp_recordings,
+    get_mcp_recording, export_mcp_recording, delete_mcp_recording, replay_mcp_recording,
+    stop_all_mcp_activity, resume_mcp_activity, is_mcp_activity_stopped,
+    get_mcp_server_settings, save_mcp_server_settings, get_mcp_server_status, list_external_mcp_servers,
+    save_external_mcp_server, remove_external_mcp_server, reconnect_external_mcp_server, MCPSessionManager
 };
 
 // Import SQLite data storage commands
 use data::{
     // Database initialization and management
     initialize_database, get_database_info, cleanup_legacy_files, check_database_health,
+    scan_orphaned_artifacts, cleanup_orphaned_artifacts,
+    // MCP audit log
+    get_mcp_audit_log, verify_audit_integrity,
     // Chat operations (Claude conversations)
     save_chat_sessions, load_chat_sessions,
+    recover_pending_responses, discard_pending_response,
+    list_scheduled_tasks, save_scheduled_task, delete_scheduled_task,
     // Conversation operations (Audio conversations)
     save_conversations, load_conversations, delete_conversation, clear_all_conversations,
     save_conversation_message, batch_save_conversation_messages,
     update_conversation_message, delete_conversation_message,
     save_conversation_insight, get_conversation_insights,
     update_session_metadata, update_session_active_state, ping_backend,
+    // Conversation replay
+    start_conversation_replay, pause_conversation_replay, resume_conversation_replay,
+    seek_conversation_replay, set_conversation_replay_speed, stop_conversation_replay,
+    // Meeting usage and cost
+    get_meeting_usage, get_usage_rates, save_usage_rates,
+    // Local usage statistics
+    get_usage_report, export_usage_report_csv,
+    // Token ledger per agent and session
+    get_usage_stats, export_usage_stats_csv,
+    // Insight feedback
+    rate_conversation_insight, get_insight_feedback,
+    // Speaking metrics
+    get_speaking_metrics,
+    // Conversation languages
+    set_conversation_languages, get_conversation_languages,
     // Logging commands
     get_database_logs, get_database_logs_by_operation, get_database_logs_by_level,
     get_database_log_stats, clear_database_logs
 };
 
-use crate::audio_loopback::macos::device_loader::{load_devices, clean_own_aggregate_devices, create_microphone_aggregate_device};
+#[cfg(target_os = "macos")]
+use crate::audio_loopback::macos::device_loader::load_devices;
+#[cfg(target_os = "macos")]
+use crate::audio_loopback::macos::aggregate_janitor::{
+    clean_stale_aggregate_devices, create_microphone_aggregate_device, teardown_aggregate_devices
+};
 
-#[tauri::command]
+#[command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+// Records call counts, request sizes and dispatch time for every IPC command, and feeds local usage stats
+fn instrumented_handler<R: tauri::Runtime>(
+    handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let request_bytes = command_metrics::payload_size(invoke.message.payload());
+        let started = std::time::Instant::now();
+        let handled = handler(invoke);
+        command_metrics::record_dispatch(&command, started.elapsed(), request_bytes);
+        data::conversation::usage_stats::record_command(&command);
+        handled
+    }
+}
+
+/// Entry point of `enteract --mcp-stdio`, the bridge MCP clients start to reach the running app
+#[cfg(feature = "mcp")]
+pub fn run_mcp_stdio_bridge() -> i32 {
+    mcp::server_transport::run_stdio_bridge()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    crate::startup::begin();
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(RagSystemState(std::sync::Arc::new(std::sync::Mutex::new(None))))
-        .manage(EnhancedRagSystemState(std::sync::Arc::new(std::sync::Mutex::new(None))))
         .setup(|app| {
+            #[cfg(feature = "enhanced-rag")]
+            app.manage(EnhancedRagSystemState(std::sync::Arc::new(std::sync::Mutex::new(None))));
+            
             // Setup emergency global hotkey for transparency restore
             #[cfg(desktop)]
             {
@@ -131,7 +276,33 @@ pub fn run() {
                 // For now, we'll rely on window-level keyboard shortcuts
             }
             
-            // Audio loopback functionality is initialized on-demand
+            // Cheap setup only: anything slow here holds up the window
+            let handle = app.handle();
+            crate::startup::setup_step(handle, "whisper_models", || crate::whisper_models::init(handle));
+            crate::startup::setup_step(handle, "privacy_mode", || crate::privacy_mode::init(handle));
+            crate::startup::setup_step(handle, "disk_space", || crate::disk_space::init(handle));
+            crate::startup::setup_step(handle, "offline_mode", || crate::offline_mode::init(handle));
+            crate::startup::setup_step(handle, "worker_process", || crate::worker_process::init(handle));
+            crate::startup::setup_step(handle, "usage_stats", || crate::data::conversation::usage_stats::init(handle));
+            crate::startup::setup_step(handle, "prompt_templates", || crate::ollama::init_prompt_templates(handle));
+            crate::startup::setup_step(handle, "scheduler", || crate::ollama::init_scheduler(handle));
+            crate::startup::setup_step(handle, "response_cache", || crate::ollama::init_response_cache(handle));
+            #[cfg(feature = "mcp")]
+            crate::startup::setup_step(handle, "mcp_recording", || crate::mcp::recording::init(handle));
+            
+            // The wake word listener loads a Whisper model, so it starts after the window
+            #[cfg(feature = "wake-word")]
+            {
+                let app_handle_wake = app.handle().clone();
+                crate::startup::defer("wake_word", async move { crate::wake_word::init(&app_handle_wake) });
+            }
+            
+            // Audio loopback functionality is initialized on-demand,
+            // but device hot-plug monitoring runs for the whole session
+            let app_handle_devices = app.handle().clone();
+            crate::startup::defer("audio_device_monitor", async move {
+                crate::audio_loopback::start_device_monitor(app_handle_devices)
+            });
             
             // TEST: Load audio devices at startup
             #[cfg(target_os = "macos")]
@@ -145,21 +316,21 @@ pub fn run() {
                         eprintln!("[STARTUP] Failed to load audio devices: {}", e);
                     }
                 }
-                // Clean up any existing aggregate devices.
-                println!("[STARTUP] Cleaning up existing aggregate devices...");
-                match clean_own_aggregate_devices() {
-                    Ok(()) => {
-                        println!("[STARTUP] Successfully cleaned up existing aggregate devices");
+                // Remove aggregate devices leaked by a previous session that crashed
+                println!("[STARTUP] Cleaning up stale aggregate devices...");
+                match clean_stale_aggregate_devices() {
+                    Ok(removed) => {
+                        println!("[STARTUP] Removed {} stale aggregate devices", removed);
                     }
                     Err(e) => {
-                        eprintln!("[STARTUP] Failed to clean up existing aggregate devices: {}", e);
+                        eprintln!("[STARTUP] Failed to clean up stale aggregate devices: {}", e);
                     }
                 }
 
                 println!("[STARTUP] Creating microphone aggregate device...");
                 match create_microphone_aggregate_device() {
-                    Ok(device) => {
-                        println!("[STARTUP] Successfully created microphone aggregate device");
+                    Ok(device_id) => {
+                        println!("[STARTUP] Successfully created microphone aggregate device {}", device_id);
                     }
                     Err(e) => {
                         eprintln!("[STARTUP] Failed to create microphone aggregate device: {}", e);
@@ -170,32 +341,26 @@ pub fn run() {
             
             // Enhanced RAG system will be initialized on-demand from frontend
             
-            // Keep legacy RAG system for compatibility
-            let app_handle_legacy = app.handle().clone();
-            let rag_state = app.state::<RagSystemState>().inner().clone();
-            tauri::async_runtime::spawn(async move {
-                if let Ok(mut state_guard) = rag_state.0.lock() {
-                    match crate::rag_system::RagSystem::new(&app_handle_legacy) {
-                        Ok(system) => {
-                            *state_guard = Some(system);
-                            println!("Legacy RAG system initialized successfully");
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to initialize legacy RAG system: {}", e);
-                        }
-                    }
-                }
-            });
-
-            // Initialize MCP session manager
-            let mcp_sessions = create_mcp_session_manager();
-            app.manage(mcp_sessions);
+            // The legacy RAG system is created by the first command that uses it
+            
+            // Initialize MCP session manager (an empty map; sessions start on request)
+            #[cfg(feature = "mcp")]
+            app.manage(create_mcp_session_manager());
+            // The MCP server for outside clients puts its session in that map
+            #[cfg(feature = "mcp")]
+            crate::startup::setup_step(app.handle(), "mcp_server", || crate::mcp::server_transport::init(app.handle()));
+            // External MCP servers take a while to start, so they connect after the window is up
+            #[cfg(feature = "mcp")]
+            {
+                crate::mcp::client::init(app.handle());
+                crate::startup::defer("mcp_clients", crate::mcp::client::connect_all());
+            }
             
             // Initialize SQLite database with comprehensive health checks
             let app_handle_db = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
+            crate::startup::defer("database", async move {
                 // First check database health
-                match crate::data::check_database_health(app_handle_db.clone()) {
+                match crate::data::check_database_health(app_handle_db.clone()).await {
                     Ok(health) => {
                         if health.is_healthy {
                             println!("✅ Database is already healthy and ready");
@@ -208,7 +373,7 @@ pub fn run() {
                                     println!("✅ Database initialization completed: {}", result);
                                     
                                     // Verify health after initialization
-                                    match crate::data::check_database_health(app_handle_db) {
+                                    match crate::data::check_database_health(app_handle_db).await {
                                         Ok(post_health) => {
                                             if post_health.is_healthy {
                                                 println!("🎉 Database is now healthy after initialization");
@@ -236,9 +401,32 @@ pub fn run() {
                 }
             });
             
+            // Report storage left behind by crashed sessions so the UI can offer cleanup
+            let app_handle_orphans = app.handle().clone();
+            crate::startup::defer("orphaned_artifacts", async move {
+                match crate::data::scan_orphaned_artifacts(app_handle_orphans.clone()) {
+                    Ok(report) if !report.artifacts.is_empty() => {
+                        println!("🧹 Found {} orphaned storage artifacts ({} bytes)", report.artifacts.len(), report.total_size_bytes);
+                        let _ = app_handle_orphans.emit("orphaned-artifacts-found", &report);
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("❌ Orphaned artifact scan failed: {}", e),
+                }
+            });
+            
+            crate::startup::finish_setup(app.handle());
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .on_window_event(|window, event| {
+            // Tear down our aggregate devices when the main window goes away
+            if let tauri::WindowEvent::Destroyed = event {
+                if window.label() == "main" {
+                    #[cfg(target_os = "macos")]
+                    teardown_aggregate_devices();
+                }
+            }
+        })
+        .invoke_handler(instrumented_handler(tauri::generate_handler![
             // Existing commands
             greet,
             
@@ -256,14 +444,79 @@ pub fn run() {
             set_window_bounds,
             
             // Eye tracking
+            #[cfg(feature = "eye-tracking")]
             start_ml_eye_tracking,
+            #[cfg(feature = "eye-tracking")]
             stop_ml_eye_tracking,
+            #[cfg(feature = "eye-tracking")]
             get_ml_gaze_data,
+            #[cfg(feature = "eye-tracking")]
             calibrate_ml_eye_tracking,
+            #[cfg(feature = "eye-tracking")]
             get_ml_tracking_stats,
+            #[cfg(feature = "eye-tracking")]
             pause_ml_tracking,
+            #[cfg(feature = "eye-tracking")]
             resume_ml_tracking,
+            #[cfg(feature = "eye-tracking")]
             detect_window_drag,
+            #[cfg(feature = "eye-tracking")]
+            start_teleprompter,
+            #[cfg(feature = "eye-tracking")]
+            stop_teleprompter,
+            #[cfg(feature = "eye-tracking")]
+            get_teleprompter,
+            #[cfg(feature = "eye-tracking")]
+            set_teleprompter_position,
+            #[cfg(feature = "eye-tracking")]
+            set_teleprompter_paused,
+            #[cfg(feature = "eye-tracking")]
+            set_teleprompter_layout,
+            
+            // Privacy mode (gaze input needs eye tracking; the manual override works without it)
+            get_privacy_mode,
+            save_privacy_settings,
+            set_privacy_override,
+            get_permission_statuses,
+            get_permission_status,
+            open_permission_settings,
+            
+            // Disk space
+            get_disk_space_status,
+            get_disk_space_settings,
+            save_disk_space_settings,
+            
+            // Offline mode
+            get_offline_status,
+            set_offline_mode,
+            check_connectivity_now,
+            
+            // Wake word
+            #[cfg(feature = "wake-word")]
+            get_wake_word_status,
+            #[cfg(feature = "wake-word")]
+            save_wake_word_settings,
+            #[cfg(feature = "wake-word")]
+            start_wake_word,
+            #[cfg(feature = "wake-word")]
+            stop_wake_word,
+            
+            // Worker processes
+            get_worker_processes,
+            save_worker_process_settings,
+            restart_worker_process,
+            
+            // First-run setup
+            get_setup_state,
+            run_setup_step,
+            skip_setup_step,
+            complete_setup,
+            reset_setup,
+            
+            // Long-running operations
+            cancel_operation,
+            list_operations,
+            get_startup_report,
             
             // Speech transcription
             initialize_whisper_model,
@@ -272,25 +525,61 @@ pub fn run() {
             check_whisper_model_availability,
             download_whisper_model,
             list_available_models,
+            list_whisper_models,
+            get_whisper_models_disk_usage,
+            delete_whisper_model,
+            evict_whisper_models,
+            get_whisper_backend,
+            set_whisper_backend,
+            benchmark_whisper_backends,
+            get_transcription_provider,
+            set_transcription_provider,
             
             // Ollama AI
             get_ollama_models,
             get_ollama_status,
             pull_ollama_model,
+            cancel_pull,
             delete_ollama_model,
             generate_ollama_response,
             generate_ollama_response_stream,
             get_ollama_model_info,
+            get_llm_backends,
+            save_llm_backends,
+            list_backend_models,
+            set_llm_endpoint,
+            get_agent_registry,
+            update_agent_config,
+            reset_agent_config,
             generate_enteract_agent_response,
             generate_vision_analysis,
             generate_deep_research,
             generate_conversational_ai,
             generate_coding_agent_response,
+            #[cfg(feature = "enhanced-rag")]
+            generate_rag_response,
+            get_context_stats,
+            run_scheduled_task_now,
+            generate_structured_response,
+            compare_models,
+            get_model_comparisons,
+            check_agent_models,
+            get_model_gpu_layers,
+            get_response_cache_settings,
+            save_response_cache_settings,
+            clear_response_cache,
+            list_prompt_templates,
+            get_prompt_template,
+            save_prompt_template,
+            delete_prompt_template,
+            preview_prompt_template,
+            set_prompt_variable,
             cancel_ai_response,
             get_gpu_acceleration_status,
             
             // Screenshot
             capture_screenshot,
+            list_monitors,
             capture_screenshot_area,
             
             // File handling
@@ -305,10 +594,19 @@ pub fn run() {
             get_database_info,
             cleanup_legacy_files,
             check_database_health,
+            scan_orphaned_artifacts,
+            cleanup_orphaned_artifacts,
+            get_mcp_audit_log,
+            verify_audit_integrity,
             
             // Chat data storage (Claude conversations)
             save_chat_sessions,
             load_chat_sessions,
+            recover_pending_responses,
+            discard_pending_response,
+            list_scheduled_tasks,
+            save_scheduled_task,
+            delete_scheduled_task,
             
             // Conversation data storage (Audio conversations)
             save_conversations,
@@ -327,9 +625,41 @@ pub fn run() {
             start_audio_loopback_capture,
             stop_audio_loopback_capture,
             process_audio_for_transcription,
+            configure_capture_buffer,
+            get_capture_buffer_stats,
+            get_device_dsp_config,
+            set_device_dsp_config,
+            get_device_channel_mix,
+            set_device_channel_mix,
+            get_downmix_matrix,
+            start_session_recording,
+            stop_session_recording,
+            get_session_recording,
+            get_device_aliases,
+            set_device_alias,
+            get_capture_timer,
+            extend_capture,
+            list_audio_sessions,
+            set_audio_session_volume,
+            set_audio_session_mute,
+            get_audio_ducking_config,
+            set_audio_ducking_config,
             
             // System info
             get_system_info,
+            get_build_capabilities,
+            
+            // App lock
+            get_app_lock_status,
+            unlock_app,
+            lock_app,
+            set_app_lock_pin,
+            disable_app_lock,
+            
+            // Accessibility
+            announce_accessibility_message,
+            get_accessibility_settings,
+            save_accessibility_settings,
             
             // Message-level persistence
             save_conversation_message,
@@ -344,6 +674,32 @@ pub fn run() {
             save_conversation_insight,
             get_conversation_insights,
             
+            // Conversation replay
+            start_conversation_replay,
+            pause_conversation_replay,
+            resume_conversation_replay,
+            seek_conversation_replay,
+            set_conversation_replay_speed,
+            stop_conversation_replay,
+
+            // Meeting usage and cost
+            get_meeting_usage,
+            get_usage_rates,
+            save_usage_rates,
+            get_usage_report,
+            export_usage_report_csv,
+            get_usage_stats,
+            export_usage_stats_csv,
+
+            // Insight feedback
+            rate_conversation_insight,
+            get_insight_feedback,
+            get_speaking_metrics,
+
+            // Conversation languages
+            set_conversation_languages,
+            get_conversation_languages,
+            
             // RAG system commands (legacy)
             initialize_rag_system,
             upload_document,
@@ -357,42 +713,156 @@ pub fn run() {
             clear_embedding_cache,
             
             // Enhanced RAG system commands
+            #[cfg(feature = "enhanced-rag")]
             initialize_enhanced_rag_system,
+            #[cfg(feature = "enhanced-rag")]
             upload_enhanced_document,
+            #[cfg(feature = "enhanced-rag")]
+            update_enhanced_document,
+            #[cfg(feature = "enhanced-rag")]
+            replace_enhanced_document,
+            #[cfg(feature = "enhanced-rag")]
+            ingest_url,
+            #[cfg(feature = "enhanced-rag")]
+            index_conversation,
+            #[cfg(feature = "enhanced-rag")]
             get_all_enhanced_documents,
+            #[cfg(feature = "enhanced-rag")]
             delete_enhanced_document,
+            #[cfg(feature = "enhanced-rag")]
             search_enhanced_documents,
+            #[cfg(feature = "enhanced-rag")]
             generate_enhanced_embeddings,
+            #[cfg(feature = "enhanced-rag")]
             clear_enhanced_embedding_cache,
+            #[cfg(feature = "enhanced-rag")]
             update_enhanced_rag_settings,
+            #[cfg(feature = "enhanced-rag")]
             get_enhanced_rag_settings,
+            #[cfg(feature = "enhanced-rag")]
             get_enhanced_storage_stats,
+            #[cfg(feature = "enhanced-rag")]
+            get_processing_queue_status,
+            #[cfg(feature = "enhanced-rag")]
+            get_embedding_migration_status,
+            #[cfg(feature = "enhanced-rag")]
             get_embedding_status,
+            #[cfg(feature = "enhanced-rag")]
             validate_enhanced_file_upload,
+            #[cfg(feature = "enhanced-rag")]
             check_document_duplicate,
+            #[cfg(feature = "enhanced-rag")]
             get_document_embedding_status,
+            #[cfg(feature = "enhanced-rag")]
             ensure_documents_ready_for_search,
+            #[cfg(feature = "enhanced-rag")]
             generate_embeddings_for_selection,
+            #[cfg(feature = "enhanced-rag")]
+            bulk_delete_enhanced_documents,
+            #[cfg(feature = "enhanced-rag")]
+            bulk_reembed_enhanced_documents,
+            #[cfg(feature = "enhanced-rag")]
+            bulk_tag_enhanced_documents,
+            #[cfg(feature = "enhanced-rag")]
+            rebuild_vector_index,
+            #[cfg(feature = "enhanced-rag")]
+            compact_search_index,
+            #[cfg(feature = "enhanced-rag")]
+            get_search_index_stats,
+            #[cfg(feature = "enhanced-rag")]
+            create_enhanced_collection,
+            #[cfg(feature = "enhanced-rag")]
+            rename_enhanced_collection,
+            #[cfg(feature = "enhanced-rag")]
+            delete_enhanced_collection,
+            #[cfg(feature = "enhanced-rag")]
+            list_enhanced_collections,
+            #[cfg(feature = "enhanced-rag")]
+            assign_documents_to_collection,
+            #[cfg(feature = "enhanced-rag")]
+            add_watched_folder,
+            #[cfg(feature = "enhanced-rag")]
+            remove_watched_folder,
+            #[cfg(feature = "enhanced-rag")]
+            list_watched_folders,
 
             // MCP commands
+            #[cfg(feature = "mcp")]
             start_mcp_session,
+            #[cfg(feature = "mcp")]
             end_mcp_session,
+            #[cfg(feature = "mcp")]
             get_mcp_session_info,
+            #[cfg(feature = "mcp")]
             list_mcp_tools,
+            #[cfg(feature = "mcp")]
             execute_mcp_tool,
+            #[cfg(feature = "mcp")]
             respond_to_mcp_approval,
+            #[cfg(feature = "mcp")]
             get_mcp_session_logs,
+            #[cfg(feature = "mcp")]
             list_active_mcp_sessions,
+            #[cfg(feature = "mcp")]
             get_mcp_tool_schema,
+            #[cfg(feature = "mcp")]
             get_mcp_session_status,
+            #[cfg(feature = "mcp")]
+            get_ocr_languages,
+            #[cfg(feature = "mcp")]
+            get_mcp_content_policy,
+            #[cfg(feature = "mcp")]
+            set_mcp_content_policy,
+            #[cfg(feature = "mcp")]
+            check_mcp_typed_text,
+            #[cfg(feature = "mcp")]
+            get_mcp_recording_settings,
+            #[cfg(feature = "mcp")]
+            save_mcp_recording_settings,
+            #[cfg(feature = "mcp")]
+            list_mcp_recordings,
+            #[cfg(feature = "mcp")]
+            get_mcp_recording,
+            #[cfg(feature = "mcp")]
+            export_mcp_recording,
+            #[cfg(feature = "mcp")]
+            delete_mcp_recording,
+            #[cfg(feature = "mcp")]
+            replay_mcp_recording,
+            #[cfg(feature = "mcp")]
+            stop_all_mcp_activity,
+            #[cfg(feature = "mcp")]
+            resume_mcp_activity,
+            #[cfg(feature = "mcp")]
+            is_mcp_activity_stopped,
+            #[cfg(feature = "mcp")]
+            get_mcp_server_settings,
+            #[cfg(feature = "mcp")]
+            save_mcp_server_settings,
+            #[cfg(feature = "mcp")]
+            get_mcp_server_status,
+            #[cfg(feature = "mcp")]
+            list_external_mcp_servers,
+            #[cfg(feature = "mcp")]
+            save_external_mcp_server,
+            #[cfg(feature = "mcp")]
+            remove_external_mcp_server,
+            #[cfg(feature = "mcp")]
+            reconnect_external_mcp_server,
             
             // LLM-driven MCP commands
+            #[cfg(feature = "mcp")]
             create_execution_plan,
+            #[cfg(feature = "mcp")]
             approve_execution_plan,
+            #[cfg(feature = "mcp")]
             execute_approved_plan,
             // Enhanced AI commands with MCP
+            #[cfg(feature = "mcp")]
             generate_mcp_enabled_response,
+            #[cfg(feature = "mcp")]
             create_mcp_session_for_ai,
+            #[cfg(feature = "mcp")]
             get_mcp_session_for_ai,
             
             // Message-level conversation operations
@@ -414,8 +884,16 @@ pub fn run() {
             get_database_logs_by_level,
             get_database_log_stats,
             clear_database_logs,
+            
+            // Command metrics
+            get_command_metrics,
+            reset_command_metrics,
+
+            // Command palette
+            list_actions,
+            invoke_action,
 
-        ])
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
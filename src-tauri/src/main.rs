@@ -2,5 +2,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Transcription and OCR worker processes are this executable started with `--worker <kind>`
+    if let Some(kind) = enteract_lib::worker_process::worker_from_args() {
+        std::process::exit(enteract_lib::worker_process::run_worker(kind));
+    }
+    // MCP clients such as Claude Desktop start `enteract --mcp-stdio` and talk to the app through it
+    #[cfg(feature = "mcp")]
+    if std::env::args().nth(1).as_deref() == Some("--mcp-stdio") {
+        std::process::exit(enteract_lib::run_mcp_stdio_bridge());
+    }
     enteract_lib::run()
 }
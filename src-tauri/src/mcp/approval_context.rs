@@ -0,0 +1,168 @@
+// src-tauri/src/mcp/approval_context.rs
+// What an approval request shows besides the tool name and parameters - a screenshot cropped
+// around the point the call acts on, the text OCR finds there, and a plain-English summary of
+// what the call would do and how risky it is.
+//
+// The point comes from the parameters (x/y, or from_x/from_y for a drag). click_on_text and
+// click_and_type are located by OCR the way the tools will locate them, and a click or scroll
+// without coordinates acts at the cursor. Context is best effort: a failed capture or OCR leaves
+// that part out rather than holding up the approval.
+use crate::coordinates::DesktopLayout;
+use crate::mcp::tools::{debug_ocr_scan, find_text_in_image, locations_on_desktop, take_screenshot_full, take_screenshot_region};
+use crate::mcp::text_match::TextMatchOptions;
+use crate::mcp::types::*;
+
+const CROP_WIDTH: u32 = 480;
+const CROP_HEIGHT: u32 = 320;
+const OCR_CONFIDENCE: f64 = 0.5;
+const MAX_OCR_CHARS: usize = 500;
+const MAX_QUOTED_CHARS: usize = 60;
+
+fn point(params: &serde_json::Value, x: &str, y: &str) -> Option<(i32, i32)> {
+    Some((params[x].as_i64()? as i32, params[y].as_i64()? as i32))
+}
+
+/// Text to quote in the summary, shortened
+fn quoted(text: &str) -> String {
+    let mut quoted: String = text.chars().take(MAX_QUOTED_CHARS).collect();
+    if text.chars().count() > MAX_QUOTED_CHARS {
+        quoted.push('…');
+    }
+    format!("\"{}\"", quoted)
+}
+
+/// What the call would do, in a sentence
+fn action(tool_name: &str, params: &serde_json::Value) -> String {
+    let text = |key: &str| params[key].as_str().map(quoted).unwrap_or_else(|| "text".to_string());
+    let at = match (point(params, "x", "y"), params["monitor"].as_u64()) {
+        (Some((x, y)), Some(monitor)) => format!(" at ({}, {}) on monitor {}", x, y, monitor),
+        (Some((x, y)), None) => format!(" at ({}, {})", x, y),
+        (None, _) => " at the cursor".to_string(),
+    };
+    match tool_name {
+        "click" | "click_at" => format!("It will click{}.", at),
+        "scroll" => format!("It will scroll{}.", at),
+        "type" => format!("It will type {} into the focused window.", text("text")),
+        "key_press" => format!("It will press {}.", text("key")),
+        "drag" => match (point(params, "from_x", "from_y"), point(params, "to_x", "to_y")) {
+            (Some(from), Some(to)) => format!("It will drag from ({}, {}) to ({}, {}).", from.0, from.1, to.0, to.1),
+            _ => "It will drag with the mouse.".to_string(),
+        },
+        "click_on_text" => format!("It will click on {}.", text("text")),
+        "click_and_type" => format!("It will click on {} and type {}.", text("click_target"), text("text_to_type")),
+        "focus_window" | "move_resize_window" => "It will move or focus another window.".to_string(),
+        "clipboard_write" => "It will replace what's on the clipboard.".to_string(),
+        "write_file" => format!("It will write to {}.", text("path")),
+        "run_command" => format!("It will run the command {}.", text("command")),
+        name => match name.split_once("__") {
+            Some((server, tool)) => format!("It will call {} on the {} MCP server, which this app can't look into.", tool, server),
+            None => format!("It will run {}.", name),
+        },
+    }
+}
+
+/// The summary shown with an approval request
+pub fn risk_summary(tool_name: &str, params: &serde_json::Value, danger_level: DangerLevel, policy_flagged: bool) -> String {
+    let risk = match danger_level {
+        DangerLevel::Low => "Low risk: it only looks at the screen or the system.",
+        DangerLevel::Medium => "Medium risk: it acts on the screen, which is usually easy to undo.",
+        DangerLevel::High => "High risk: it can change or send things that are hard to undo.",
+        DangerLevel::Critical => "Critical risk: it can run programs or change files with your permissions.",
+    };
+    let mut summary = format!("{} {}", action(tool_name, params), risk);
+    if policy_flagged {
+        summary.push_str(" The content policy flagged the text it would type.");
+    }
+    summary
+}
+
+/// The desktop point the call acts on, if it acts on one
+async fn target(tool_name: &str, params: &serde_json::Value) -> Option<(i32, i32)> {
+    if let Some((x, y)) = point(params, "x", "y").or_else(|| point(params, "from_x", "from_y")) {
+        return match params["monitor"].as_u64() {
+            Some(monitor) => crate::coordinates::current_layout().ok()?.from_monitor(monitor as usize, x, y).ok(),
+            None => Some((x, y)),
+        };
+    }
+    match tool_name {
+        "click_on_text" | "click_and_type" => {
+            let text = params["text"].as_str().or_else(|| params["click_target"].as_str())?;
+            let confidence = params["confidence_threshold"].as_f64().unwrap_or(0.8);
+            let screenshot = take_screenshot_full(Some("png".to_string()), None).await.ok()?;
+            let matching = TextMatchOptions {
+                fuzzy: params["fuzzy"].as_bool().unwrap_or(false),
+                ..TextMatchOptions::default()
+            };
+            let found = find_text_in_image(&screenshot.image_base64, text, confidence, &matching, None).await.ok()?;
+            let location = locations_on_desktop(found, &screenshot.mapping).into_iter().next()?;
+            Some((location.center_x, location.center_y))
+        }
+        "click" | "scroll" => crate::coordinates::cursor_position().ok(),
+        _ => None,
+    }
+}
+
+/// A crop around (`x`, `y`), kept on the monitor the point is on
+fn crop_region(layout: &DesktopLayout, x: i32, y: i32) -> ScreenRegion {
+    let monitor = layout.monitor_at(x, y).unwrap_or_else(|| layout.primary());
+    let width = CROP_WIDTH.min(monitor.width);
+    let height = CROP_HEIGHT.min(monitor.height);
+    let left = (x - width as i32 / 2).clamp(monitor.x, monitor.x + (monitor.width - width) as i32);
+    let top = (y - height as i32 / 2).clamp(monitor.y, monitor.y + (monitor.height - height) as i32);
+    ScreenRegion { x: left, y: top, width, height }
+}
+
+/// Screenshot and OCR text around the call's target; `None` when it has no target
+pub(crate) async fn gather(tool_name: &str, params: &serde_json::Value) -> Option<ApprovalContext> {
+    let (target_x, target_y) = target(tool_name, params).await?;
+    let layout = crate::coordinates::current_layout()
+        .inspect_err(|e| log::warn!("No screen layout for the approval screenshot: {}", e))
+        .ok()?;
+    let region = crop_region(&layout, target_x, target_y);
+    let screenshot = take_screenshot_region(region.clone(), Some("png".to_string()), None)
+        .await
+        .inspect_err(|e| log::warn!("Failed to capture approval screenshot: {}", e))
+        .ok();
+    let ocr_text = match &screenshot {
+        Some(screenshot) => debug_ocr_scan(&screenshot.image_base64, OCR_CONFIDENCE, false)
+            .await
+            .ok()
+            .map(|words| words.into_iter().map(|word| word.text).collect::<Vec<_>>().join(" "))
+            .filter(|text| !text.is_empty())
+            .map(|text| text.chars().take(MAX_OCR_CHARS).collect()),
+        None => None,
+    };
+    Some(ApprovalContext {
+        target_x,
+        target_y,
+        region,
+        screenshot_base64: screenshot.map(|screenshot| screenshot.image_base64),
+        ocr_text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinates::MonitorGeometry;
+
+    #[test]
+    fn test_summaries_and_crops() {
+        let summary = risk_summary("click_at", &serde_json::json!({ "x": 10, "y": 20 }), DangerLevel::Medium, false);
+        assert_eq!(summary, "It will click at (10, 20). Medium risk: it acts on the screen, which is usually easy to undo.");
+        let summary = risk_summary("type", &serde_json::json!({ "text": "x".repeat(70) }), DangerLevel::High, true);
+        assert!(summary.starts_with(&format!("It will type \"{}…\"", "x".repeat(60))));
+        assert!(summary.ends_with("The content policy flagged the text it would type."));
+        assert!(risk_summary("github__create_issue", &serde_json::json!({}), DangerLevel::High, false).contains("on the github MCP server"));
+
+        let layout = DesktopLayout::new(vec![
+            MonitorGeometry { id: 1, x: 0, y: 0, width: 1920, height: 1080, scale_factor: 1.0, is_primary: true },
+            MonitorGeometry { id: 2, x: 1920, y: 0, width: 1280, height: 1024, scale_factor: 1.0, is_primary: false },
+        ])
+        .unwrap();
+        assert_eq!(crop_region(&layout, 960, 540), ScreenRegion { x: 720, y: 380, width: 480, height: 320 });
+        // Near an edge the crop stays on the point's monitor
+        assert_eq!(crop_region(&layout, 1930, 5), ScreenRegion { x: 1920, y: 0, width: 480, height: 320 });
+        assert_eq!(crop_region(&layout, 1910, 1075), ScreenRegion { x: 1440, y: 760, width: 480, height: 320 });
+    }
+}
@@ -0,0 +1,615 @@
+// src-tauri/src/mcp/client.rs
+// MCP client - connects to MCP servers the user configures (a filesystem server, GitHub,
+// browser-use, ...) and adds their tools to every session next to the built-in ones, so the
+// tool list and execution plans can mix both. A server's tools are named `<server>__<tool>`.
+//
+// Servers run as a child process speaking JSON-RPC over stdio (`npx ...`, `uvx ...`; on Windows
+// give `npx.cmd`), or are reached over the SSE transport. The protocol doesn't say how dangerous
+// a tool is, so each server has one danger level for all its tools, High unless set otherwise,
+// and calls go through the session's permissions and approvals like any other tool.
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{oneshot, Mutex};
+
+use crate::mcp::commands::MCPSessionManager;
+use crate::mcp::protocol::{JsonRpcRequest, JsonRpcResponse, PROTOCOL_VERSION};
+use crate::mcp::tools::{tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+const TOOL_SEPARATOR: &str = "__";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_TOOL_PAGES: usize = 20;
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref CONFIGS: RwLock<Vec<ExternalServerConfig>> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref CONNECTIONS: Mutex<HashMap<String, Arc<Connection>>> = Mutex::new(HashMap::new());
+    // Tools and errors per server, readable without awaiting when sessions build their tool set
+    static ref STATES: RwLock<HashMap<String, ServerState>> = RwLock::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExternalTransport {
+    Stdio {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        env: HashMap<String, String>,
+    },
+    Sse {
+        url: String,
+        /// Sent with every request, e.g. an Authorization header
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_danger_level() -> DangerLevel {
+    DangerLevel::High
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalServerConfig {
+    /// Prefix of the server's tool names: `github` gives `github__create_issue`
+    pub name: String,
+    pub transport: ExternalTransport,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Given to every tool of the server
+    #[serde(default = "default_danger_level")]
+    pub danger_level: DangerLevel,
+}
+
+impl ExternalServerConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty()
+            || self.name.contains(TOOL_SEPARATOR)
+            || !self.name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+        {
+            return Err("Server names use letters, digits, '-' and single '_' only".to_string());
+        }
+        match &self.transport {
+            ExternalTransport::Stdio { command, .. } if command.trim().is_empty() => Err("A stdio server needs a command".to_string()),
+            ExternalTransport::Sse { url, .. } if reqwest::Url::parse(url).is_err() => Err(format!("Invalid server URL: {}", url)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A tool as the server describes it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, rename = "inputSchema")]
+    pub input_schema: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ServerState {
+    tools: Vec<RemoteTool>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalServerStatus {
+    pub config: ExternalServerConfig,
+    pub connected: bool,
+    /// Names as sessions see them
+    pub tools: Vec<String>,
+    pub error: Option<String>,
+}
+
+const SETTINGS_FILE: &str = "mcp_clients.json";
+
+fn write_configs(configs: Vec<ExternalServerConfig>) -> Result<(), String> {
+    save_json_settings(SETTINGS_FILE, "MCP servers", &configs)?;
+    *CONFIGS.write().map_err(|_| "Failed to update MCP servers".to_string())? = configs;
+    Ok(())
+}
+
+fn set_state(server: &str, state: Option<ServerState>) {
+    if let Ok(mut states) = STATES.write() {
+        match state {
+            Some(state) => states.insert(server.to_string(), state),
+            None => states.remove(server),
+        };
+    }
+}
+
+pub fn qualified_name(server: &str, tool: &str) -> String {
+    format!("{}{}{}", server, TOOL_SEPARATOR, tool)
+}
+
+type Pending = Arc<std::sync::Mutex<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>>;
+
+enum Outgoing {
+    Stdio(Mutex<tokio::process::ChildStdin>),
+    Sse {
+        client: reqwest::Client,
+        endpoint: reqwest::Url,
+        headers: HashMap<String, String>,
+    },
+}
+
+/// A live connection; dropping it stops the reader and, for stdio, the server process
+struct Connection {
+    outgoing: Outgoing,
+    pending: Pending,
+    next_id: AtomicU64,
+    reader: tokio::task::JoinHandle<()>,
+    _child: Option<tokio::process::Child>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Hand a response to whoever is waiting for it; requests and notifications from the server
+/// aren't answered
+fn dispatch(pending: &Pending, text: &str) {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(text) else { return };
+    if value.get("method").is_some() {
+        return;
+    }
+    let Ok(response) = serde_json::from_value::<JsonRpcResponse>(value) else { return };
+    let Some(id) = response.id.as_u64() else { return };
+    if let Some(sender) = pending.lock().ok().and_then(|mut pending| pending.remove(&id)) {
+        let _ = sender.send(response);
+    }
+}
+
+/// Fail every waiting request once the connection is gone
+fn close(pending: &Pending) {
+    if let Ok(mut pending) = pending.lock() {
+        pending.clear();
+    }
+}
+
+/// The next complete event in `buffer` as (event name, data), removing it from the buffer
+fn next_event(buffer: &mut Vec<u8>) -> Option<(String, String)> {
+    let end = buffer.windows(2).position(|pair| pair == b"\n\n")?;
+    let block: Vec<u8> = buffer.drain(..end + 2).collect();
+    let block = String::from_utf8_lossy(&block);
+    let mut event = "message".to_string();
+    let mut data = Vec::new();
+    for line in block.lines() {
+        if let Some(name) = line.strip_prefix("event:") {
+            event = name.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push(value.strip_prefix(' ').unwrap_or(value));
+        }
+    }
+    Some((event, data.join("\n")))
+}
+
+impl Connection {
+    async fn open(transport: &ExternalTransport) -> Result<Self, String> {
+        let pending: Pending = Arc::default();
+        match transport {
+            ExternalTransport::Stdio { command, args, env } => {
+                let mut child = tokio::process::Command::new(command)
+                    .args(args)
+                    .envs(env)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::inherit())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .map_err(|e| format!("Failed to start {}: {}", command, e))?;
+                let stdin = child.stdin.take().ok_or("Server stdin unavailable")?;
+                let stdout = child.stdout.take().ok_or("Server stdout unavailable")?;
+                let reader_pending = pending.clone();
+                let reader = tokio::spawn(async move {
+                    let mut lines = BufReader::new(stdout).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        dispatch(&reader_pending, &line);
+                    }
+                    close(&reader_pending);
+                });
+                Ok(Self { outgoing: Outgoing::Stdio(Mutex::new(stdin)), pending, next_id: AtomicU64::new(1), reader, _child: Some(child) })
+            }
+            ExternalTransport::Sse { url, headers } => {
+                let base = reqwest::Url::parse(url).map_err(|e| format!("Invalid server URL: {}", e))?;
+                let client = reqwest::Client::new();
+                let mut request = client.get(base.clone()).header("Accept", "text/event-stream");
+                for (name, value) in headers {
+                    request = request.header(name, value);
+                }
+                let response = request.send().await.map_err(|e| format!("Failed to connect to {}: {}", url, e))?;
+                if !response.status().is_success() {
+                    return Err(format!("{} refused the connection ({})", url, response.status()));
+                }
+                // The server names the URL to post messages to in its first event
+                let (endpoint_sender, endpoint_receiver) = oneshot::channel::<String>();
+                let reader_pending = pending.clone();
+                let reader = tokio::spawn(async move {
+                    let mut endpoint_sender = Some(endpoint_sender);
+                    let mut stream = response.bytes_stream();
+                    let mut buffer = Vec::new();
+                    while let Some(Ok(chunk)) = stream.next().await {
+                        buffer.extend(chunk.iter().filter(|&&byte| byte != b'\r'));
+                        while let Some((event, data)) = next_event(&mut buffer) {
+                            match event.as_str() {
+                                "endpoint" => {
+                                    if let Some(sender) = endpoint_sender.take() {
+                                        let _ = sender.send(data);
+                                    }
+                                }
+                                "message" => dispatch(&reader_pending, &data),
+                                _ => {}
+                            }
+                        }
+                    }
+                    close(&reader_pending);
+                });
+                let endpoint = match tokio::time::timeout(CONNECT_TIMEOUT, endpoint_receiver).await {
+                    Ok(Ok(endpoint)) => base.join(&endpoint).map_err(|e| format!("Invalid message endpoint: {}", e))?,
+                    _ => {
+                        reader.abort();
+                        return Err(format!("{} didn't send a message endpoint", url));
+                    }
+                };
+                let outgoing = Outgoing::Sse { client, endpoint, headers: headers.clone() };
+                Ok(Self { outgoing, pending, next_id: AtomicU64::new(1), reader, _child: None })
+            }
+        }
+    }
+
+    async fn send(&self, message: &JsonRpcRequest) -> Result<(), String> {
+        let text = serde_json::to_string(message).map_err(|e| format!("Failed to encode request: {}", e))?;
+        match &self.outgoing {
+            Outgoing::Stdio(stdin) => {
+                let mut stdin = stdin.lock().await;
+                stdin.write_all(text.as_bytes()).await.map_err(|e| format!("Failed to send request: {}", e))?;
+                stdin.write_all(b"\n").await.map_err(|e| format!("Failed to send request: {}", e))?;
+                stdin.flush().await.map_err(|e| format!("Failed to send request: {}", e))
+            }
+            Outgoing::Sse { client, endpoint, headers } => {
+                let mut request = client.post(endpoint.clone()).header("Content-Type", "application/json").body(text);
+                for (name, value) in headers {
+                    request = request.header(name, value);
+                }
+                let response = request.send().await.map_err(|e| format!("Failed to send request: {}", e))?;
+                if !response.status().is_success() {
+                    return Err(format!("Server refused the request ({})", response.status()));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn notify(&self, method: &str, params: serde_json::Value) -> Result<(), String> {
+        self.send(&JsonRpcRequest::new(None, method, params)).await
+    }
+
+    async fn request(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (sender, receiver) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id, sender);
+        }
+        let forget = || {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.remove(&id);
+            }
+        };
+        if let Err(e) = self.send(&JsonRpcRequest::new(Some(serde_json::json!(id)), method, params)).await {
+            forget();
+            return Err(e);
+        }
+        let response = match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => return Err("The MCP server closed the connection".to_string()),
+            Err(_) => {
+                forget();
+                return Err(format!("The MCP server didn't answer {} in time", method));
+            }
+        };
+        match response.error {
+            Some(error) => Err(format!("{} ({})", error.message, error.code)),
+            None => Ok(response.result.unwrap_or_default()),
+        }
+    }
+
+    /// Handshake, then every tool the server lists
+    async fn initialize(&self) -> Result<Vec<RemoteTool>, String> {
+        self.request("initialize", serde_json::json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "enteract", "version": env!("CARGO_PKG_VERSION") }
+        }))
+        .await?;
+        self.notify("notifications/initialized", serde_json::Value::Null).await?;
+
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_TOOL_PAGES {
+            let params = match &cursor {
+                Some(cursor) => serde_json::json!({ "cursor": cursor }),
+                None => serde_json::Value::Null,
+            };
+            let page = self.request("tools/list", params).await?;
+            let listed: Vec<RemoteTool> = serde_json::from_value(page["tools"].clone())
+                .map_err(|e| format!("Invalid tool list: {}", e))?;
+            tools.extend(listed);
+            cursor = page["nextCursor"].as_str().map(str::to_string);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(tools)
+    }
+}
+
+/// A `tools/call` result as this app's tool output; `Err` when the server flagged an error
+fn call_output(result: &serde_json::Value) -> Result<serde_json::Value, String> {
+    let content = result["content"].as_array().cloned().unwrap_or_default();
+    let text: Vec<&str> = content
+        .iter()
+        .filter(|block| block["type"] == "text")
+        .filter_map(|block| block["text"].as_str())
+        .collect();
+    let text = text.join("\n");
+    if result["isError"].as_bool().unwrap_or(false) {
+        return Err(if text.is_empty() { "The tool reported an error".to_string() } else { text });
+    }
+    let mut output = serde_json::json!({ "success": true, "text": text, "content": content });
+    // Screenshots from other servers look like the built-in ones
+    if let Some(image) = content.iter().find(|block| block["type"] == "image") {
+        output["image_base64"] = image["data"].clone();
+        output["format"] = serde_json::json!(image["mimeType"].as_str().and_then(|mime| mime.strip_prefix("image/")).unwrap_or("png"));
+    }
+    if let Some(structured) = result.get("structuredContent") {
+        output["structured"] = structured.clone();
+    }
+    Ok(output)
+}
+
+async fn call_tool(server: &str, tool: &str, arguments: serde_json::Value) -> Result<serde_json::Value, String> {
+    let connection = CONNECTIONS
+        .lock()
+        .await
+        .get(server)
+        .cloned()
+        .ok_or_else(|| format!("MCP server {} isn't connected", server))?;
+    let result = connection
+        .request("tools/call", serde_json::json!({ "name": tool, "arguments": arguments }))
+        .await?;
+    call_output(&result)
+}
+
+#[derive(Clone)]
+pub struct ExternalTool {
+    qualified_name: String,
+    server: String,
+    tool: RemoteTool,
+    danger_level: DangerLevel,
+}
+
+#[async_trait]
+impl ComputerUseTool for ExternalTool {
+    fn name(&self) -> &str { &self.qualified_name }
+
+    fn description(&self) -> String {
+        format!("{} (from the {} MCP server)", self.tool.description.trim(), self.server)
+    }
+
+    fn danger_level(&self) -> DangerLevel { self.danger_level }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        match &self.tool.input_schema {
+            serde_json::Value::Null => serde_json::json!({ "type": "object", "properties": {} }),
+            schema => schema.clone(),
+        }
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        log::info!("Session {}: Calling {} on MCP server {}", session_id, self.tool.name, self.server);
+        let outcome = crate::cancellation::cancellable(call_tool(&self.server, &self.tool.name, params)).await;
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+/// Tools of every connected server, for a session's tool set
+pub fn external_tools() -> Vec<(String, Box<dyn ComputerUseTool + Send + Sync>)> {
+    let configs = read_settings(&CONFIGS);
+    let Ok(states) = STATES.read() else { return Vec::new() };
+    configs
+        .iter()
+        .filter_map(|config| states.get(&config.name).map(|state| (config, state)))
+        .flat_map(|(config, state)| {
+            state.tools.iter().map(move |tool| {
+                let qualified_name = qualified_name(&config.name, &tool.name);
+                let external: Box<dyn ComputerUseTool + Send + Sync> = Box::new(ExternalTool {
+                    qualified_name: qualified_name.clone(),
+                    server: config.name.clone(),
+                    tool: tool.clone(),
+                    danger_level: config.danger_level,
+                });
+                (qualified_name, external)
+            })
+        })
+        .collect()
+}
+
+/// Replace the external tools of every open session with the current ones
+async fn refresh_sessions() {
+    let Some(sessions) = APP_HANDLE.get().and_then(|app_handle| app_handle.try_state::<MCPSessionManager>()) else { return };
+    let sessions: Vec<_> = sessions.lock().await.values().cloned().collect();
+    for session in sessions {
+        let mut tools = session.tools.lock().await;
+        // Built-in tool names never contain the separator
+        tools.retain(|name, _| !name.contains(TOOL_SEPARATOR));
+        tools.extend(external_tools());
+    }
+}
+
+async fn disconnect(name: &str) {
+    CONNECTIONS.lock().await.remove(name);
+    set_state(name, None);
+}
+
+async fn connect(config: &ExternalServerConfig) -> Result<usize, String> {
+    disconnect(&config.name).await;
+    let opened = tokio::time::timeout(CONNECT_TIMEOUT, async {
+        let connection = Connection::open(&config.transport).await?;
+        let tools = connection.initialize().await?;
+        Ok::<_, String>((connection, tools))
+    })
+    .await
+    .unwrap_or_else(|_| Err(format!("Timed out connecting to MCP server {}", config.name)));
+
+    let outcome = match opened {
+        Ok((connection, tools)) => {
+            let count = tools.len();
+            CONNECTIONS.lock().await.insert(config.name.clone(), Arc::new(connection));
+            set_state(&config.name, Some(ServerState { tools, error: None }));
+            println!("🔌 Connected to MCP server {} ({} tools)", config.name, count);
+            Ok(count)
+        }
+        Err(e) => {
+            eprintln!("❌ MCP server {} failed to connect: {}", config.name, e);
+            set_state(&config.name, Some(ServerState { tools: Vec::new(), error: Some(e.clone()) }));
+            Err(e)
+        }
+    };
+    refresh_sessions().await;
+    outcome
+}
+
+/// Connect every enabled server; run after startup, since starting servers takes a while
+pub async fn connect_all() {
+    for config in read_settings(&CONFIGS).into_iter().filter(|config| config.enabled) {
+        let _ = connect(&config).await;
+    }
+}
+
+pub fn init(app_handle: &AppHandle) {
+    let _ = APP_HANDLE.set(app_handle.clone());
+}
+
+async fn status(config: ExternalServerConfig) -> ExternalServerStatus {
+    let connected = CONNECTIONS.lock().await.contains_key(&config.name);
+    let state = STATES.read().ok().and_then(|states| states.get(&config.name).cloned()).unwrap_or_default();
+    ExternalServerStatus {
+        tools: state.tools.iter().map(|tool| qualified_name(&config.name, &tool.name)).collect(),
+        error: state.error,
+        connected,
+        config,
+    }
+}
+
+#[crate::command]
+pub async fn list_external_mcp_servers() -> Result<Vec<ExternalServerStatus>, String> {
+    let mut statuses = Vec::new();
+    for config in read_settings(&CONFIGS) {
+        statuses.push(status(config).await);
+    }
+    Ok(statuses)
+}
+
+/// Add or update a server, then connect (or disconnect) it to match `enabled`
+#[crate::command]
+pub async fn save_external_mcp_server(config: ExternalServerConfig) -> Result<ExternalServerStatus, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    config.validate()?;
+    let mut configs = read_settings(&CONFIGS);
+    match configs.iter_mut().find(|existing| existing.name == config.name) {
+        Some(existing) => *existing = config.clone(),
+        None => configs.push(config.clone()),
+    }
+    write_configs(configs)?;
+
+    if config.enabled {
+        // A failed connection is reported in the status, not as an error: the config is saved
+        let _ = connect(&config).await;
+    } else {
+        disconnect(&config.name).await;
+        refresh_sessions().await;
+    }
+    Ok(status(config).await)
+}
+
+#[crate::command]
+pub async fn remove_external_mcp_server(name: String) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    let mut configs = read_settings(&CONFIGS);
+    let before = configs.len();
+    configs.retain(|config| config.name != name);
+    if configs.len() == before {
+        return Err(format!("MCP server not found: {}", name));
+    }
+    write_configs(configs)?;
+    disconnect(&name).await;
+    refresh_sessions().await;
+    Ok(())
+}
+
+#[crate::command]
+pub async fn reconnect_external_mcp_server(name: String) -> Result<ExternalServerStatus, String> {
+    let config = read_settings(&CONFIGS)
+        .into_iter()
+        .find(|config| config.name == name)
+        .ok_or_else(|| format!("MCP server not found: {}", name))?;
+    connect(&config).await?;
+    Ok(status(config).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_results_and_config_validation() {
+        let mut buffer = b"event: endpoint\ndata: /messages?id=1\n\ndata: {\"a\":\ndata: 1}\n\n: keep\n\nevent: mess".to_vec();
+        assert_eq!(next_event(&mut buffer), Some(("endpoint".to_string(), "/messages?id=1".to_string())));
+        assert_eq!(next_event(&mut buffer), Some(("message".to_string(), "{\"a\":\n1}".to_string())));
+        assert_eq!(next_event(&mut buffer), Some(("message".to_string(), String::new())));
+        assert_eq!(next_event(&mut buffer), None);
+        assert_eq!(buffer, b"event: mess");
+
+        let output = call_output(&serde_json::json!({
+            "content": [{ "type": "text", "text": "line 1" }, { "type": "image", "data": "aGk=", "mimeType": "image/jpeg" }, { "type": "text", "text": "line 2" }]
+        }))
+        .unwrap();
+        assert_eq!((output["text"].as_str(), output["image_base64"].as_str(), output["format"].as_str()), (Some("line 1\nline 2"), Some("aGk="), Some("jpeg")));
+        assert_eq!(call_output(&serde_json::json!({ "content": [{ "type": "text", "text": "No such repo" }], "isError": true })).unwrap_err(), "No such repo");
+
+        let config: ExternalServerConfig = serde_json::from_value(serde_json::json!({
+            "name": "github",
+            "transport": { "type": "stdio", "command": "npx", "args": ["-y", "@modelcontextprotocol/server-github"] }
+        }))
+        .unwrap();
+        assert!(config.enabled && config.danger_level == DangerLevel::High);
+        assert!(config.validate().is_ok());
+        assert!(ExternalServerConfig { name: "git__hub".to_string(), ..config.clone() }.validate().is_err());
+        assert!(ExternalServerConfig { transport: ExternalTransport::Sse { url: "not a url".to_string(), headers: HashMap::new() }, ..config }.validate().is_err());
+        assert_eq!(qualified_name("github", "create_issue"), "github__create_issue");
+    }
+}
@@ -0,0 +1,277 @@
+// src-tauri/src/mcp/clipboard_tools.rs
+// Clipboard tools - read and write the system clipboard directly, text or image, so the agent
+// can move data between apps without sending Ctrl+C/Ctrl+V into whatever has focus.
+//
+// Images come and go as base64 PNG (writes also take JPEG). Text written here goes through
+// the content policy like typed text, since it is usually pasted next.
+use async_trait::async_trait;
+use base64::Engine;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::mcp::tools::{tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+
+const DEFAULT_MAX_CHARS: usize = 20_000;
+const MAX_IMAGE_BASE64_BYTES: usize = 20 * 1024 * 1024;
+
+lazy_static::lazy_static! {
+    // On Linux the clipboard only holds what we wrote while the instance that wrote it is alive
+    static ref CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardFormat {
+    #[default]
+    Text,
+    Image,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClipboardReadParams {
+    pub format: Option<ClipboardFormat>,
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClipboardWriteParams {
+    pub text: Option<String>,
+    pub image_base64: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum ClipboardContent {
+    Text(String),
+    Image(Vec<u8>),
+}
+
+impl ClipboardWriteParams {
+    fn content(self) -> Result<ClipboardContent, String> {
+        match (self.text, self.image_base64) {
+            (Some(text), None) => Ok(ClipboardContent::Text(text)),
+            (None, Some(image)) => {
+                if image.len() > MAX_IMAGE_BASE64_BYTES {
+                    return Err(format!("Images are limited to {}MB of base64", MAX_IMAGE_BASE64_BYTES / (1024 * 1024)));
+                }
+                // Accept data URLs as well as bare base64
+                let encoded = image.split_once("base64,").map_or(image.as_str(), |(_, data)| data);
+                base64::engine::general_purpose::STANDARD
+                    .decode(encoded.trim())
+                    .map(ClipboardContent::Image)
+                    .map_err(|e| format!("image_base64 isn't valid base64: {}", e))
+            }
+            (Some(_), Some(_)) => Err("Pass either text or image_base64, not both".to_string()),
+            (None, None) => Err("Nothing to copy: pass text or image_base64".to_string()),
+        }
+    }
+}
+
+/// At most `max_chars` characters of `text`, and whether anything was cut
+fn truncate_chars(text: String, max_chars: usize) -> (String, bool) {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => (text[..cut].to_string(), true),
+        None => (text, false),
+    }
+}
+
+fn with_clipboard<T>(f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>) -> Result<T, String> {
+    let mut guard = CLIPBOARD.lock().map_err(|_| "Clipboard is busy".to_string())?;
+    if guard.is_none() {
+        *guard = Some(arboard::Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?);
+    }
+    let clipboard = guard.as_mut().ok_or("Failed to open clipboard")?;
+    f(clipboard).map_err(|e| match e {
+        arboard::Error::ContentNotAvailable => "The clipboard doesn't hold that kind of content".to_string(),
+        e => e.to_string(),
+    })
+}
+
+fn read_clipboard(params: ClipboardReadParams) -> Result<serde_json::Value, String> {
+    match params.format.unwrap_or_default() {
+        ClipboardFormat::Text => {
+            let text = with_clipboard(|clipboard| clipboard.get_text())?;
+            let length = text.chars().count();
+            let (text, truncated) = truncate_chars(text, params.max_chars.unwrap_or(DEFAULT_MAX_CHARS));
+            Ok(serde_json::json!({
+                "success": true,
+                "format": "text",
+                "text": text,
+                "length": length,
+                "truncated": truncated
+            }))
+        }
+        ClipboardFormat::Image => {
+            let image = with_clipboard(|clipboard| clipboard.get_image())?;
+            let (width, height) = (image.width as u32, image.height as u32);
+            let rgba = image::RgbaImage::from_raw(width, height, image.bytes.into_owned())
+                .ok_or("The clipboard image has an unexpected size")?;
+            let mut png = Vec::new();
+            rgba.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+                .map_err(|e| format!("Failed to encode clipboard image: {}", e))?;
+            Ok(serde_json::json!({
+                "success": true,
+                "format": "image",
+                "image_base64": base64::engine::general_purpose::STANDARD.encode(&png),
+                "width": width,
+                "height": height
+            }))
+        }
+    }
+}
+
+fn write_clipboard(content: ClipboardContent) -> Result<serde_json::Value, String> {
+    match content {
+        ClipboardContent::Text(text) => {
+            let length = text.chars().count();
+            with_clipboard(|clipboard| clipboard.set_text(text))?;
+            Ok(serde_json::json!({
+                "success": true,
+                "format": "text",
+                "length": length,
+                "message": format!("Copied {} characters to the clipboard", length)
+            }))
+        }
+        ClipboardContent::Image(bytes) => {
+            let rgba = image::load_from_memory(&bytes)
+                .map_err(|e| format!("Failed to decode image: {}", e))?
+                .to_rgba8();
+            let (width, height) = rgba.dimensions();
+            with_clipboard(|clipboard| clipboard.set_image(arboard::ImageData {
+                width: width as usize,
+                height: height as usize,
+                bytes: Cow::Owned(rgba.into_raw()),
+            }))?;
+            Ok(serde_json::json!({
+                "success": true,
+                "format": "image",
+                "width": width,
+                "height": height,
+                "message": format!("Copied a {}x{} image to the clipboard", width, height)
+            }))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ClipboardReadTool;
+
+#[async_trait]
+impl ComputerUseTool for ClipboardReadTool {
+    fn name(&self) -> &str { "clipboard_read" }
+
+    fn description(&self) -> String {
+        "Read the clipboard as text, or as a base64 PNG image".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Medium }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "format": {
+                    "type": "string",
+                    "enum": ["text", "image"],
+                    "default": "text",
+                    "description": "What to read from the clipboard"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "default": DEFAULT_MAX_CHARS,
+                    "description": "Longest text to return; longer text is cut off"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: ClipboardReadParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for clipboard_read: {}", e))?;
+
+        log::info!("Session {}: Reading clipboard as {:?}", session_id, params.format.unwrap_or_default());
+
+        let outcome = read_clipboard(params).map_err(|e| format!("Failed to read clipboard: {}", e));
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct ClipboardWriteTool;
+
+#[async_trait]
+impl ComputerUseTool for ClipboardWriteTool {
+    fn name(&self) -> &str { "clipboard_write" }
+
+    fn description(&self) -> String {
+        "Put text, or a base64 PNG/JPEG image, on the clipboard".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Medium }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "Text to copy"
+                },
+                "image_base64": {
+                    "type": "string",
+                    "description": "Base64 PNG or JPEG image to copy, instead of text"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: ClipboardWriteParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for clipboard_write: {}", e))?;
+
+        log::info!("Session {}: Writing to clipboard", session_id);
+
+        let outcome = params
+            .content()
+            .and_then(write_clipboard)
+            .map_err(|e| format!("Failed to write clipboard: {}", e));
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_params_and_truncation() {
+        let params = |text: Option<&str>, image: Option<&str>| ClipboardWriteParams {
+            text: text.map(str::to_string),
+            image_base64: image.map(str::to_string),
+        };
+        assert_eq!(params(Some("hi"), None).content(), Ok(ClipboardContent::Text("hi".to_string())));
+        // Bare base64 and data URLs decode to the same bytes
+        assert_eq!(params(None, Some("iVBORw==")).content(), Ok(ClipboardContent::Image(vec![0x89, 0x50, 0x4e, 0x47])));
+        assert_eq!(params(None, Some("data:image/png;base64,iVBORw==")).content(), params(None, Some("iVBORw==")).content());
+        assert!(params(None, Some("not base64!")).content().is_err());
+        assert!(params(Some("hi"), Some("iVBORw==")).content().is_err());
+        assert!(params(None, None).content().is_err());
+
+        assert_eq!(truncate_chars("héllo".to_string(), 2), ("hé".to_string(), true));
+        assert_eq!(truncate_chars("héllo".to_string(), 5), ("héllo".to_string(), false));
+    }
+}
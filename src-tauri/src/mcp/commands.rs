@@ -6,17 +6,24 @@ use tauri::{AppHandle, State};
 
 use crate::mcp::types::*;
 use crate::mcp::server::MCPSession;
+use crate::app_lock::{require_unlocked, CommandGroup};
 
 // Global state for active MCP sessions
 pub type MCPSessionManager = Arc<Mutex<HashMap<String, Arc<MCPSession>>>>;
 
-#[tauri::command]
+/// `permission_profile` names a preset ("read-only", "no-keyboard", "screen-only", "full") that
+/// replaces the config's own permissions
+#[crate::command]
 pub async fn start_mcp_session(
     config: Option<MCPSessionConfig>,
+    permission_profile: Option<String>,
     app_handle: AppHandle,
     sessions: State<'_, MCPSessionManager>,
 ) -> Result<MCPSessionInfo, String> {
-    let session_config = config.unwrap_or_default();
+    let mut session_config = config.unwrap_or_default();
+    if let Some(profile) = permission_profile {
+        session_config.permissions = PermissionProfile::preset(&profile)?;
+    }
     let session = Arc::new(MCPSession::new(session_config, app_handle));
     
     // Initialize the session
@@ -42,7 +49,7 @@ pub async fn start_mcp_session(
     Ok(session_info)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn end_mcp_session(
     session_id: String,
     sessions: State<'_, MCPSessionManager>,
@@ -61,7 +68,7 @@ pub async fn end_mcp_session(
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_mcp_session_info(
     session_id: String,
     sessions: State<'_, MCPSessionManager>,
@@ -73,7 +80,7 @@ pub async fn get_mcp_session_info(
     Ok(session.get_info().await)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn list_mcp_tools(
     session_id: String,
     sessions: State<'_, MCPSessionManager>,
@@ -85,27 +92,36 @@ pub async fn list_mcp_tools(
     Ok(session.get_available_tools().await)
 }
 
-#[tauri::command]
+/// Run one tool; `operation_id` lets `cancel_operation` stop it, OCR and worker calls included
+#[crate::command]
 pub async fn execute_mcp_tool(
     session_id: String,
     tool_name: String,
     parameters: serde_json::Value,
+    operation_id: Option<String>,
     sessions: State<'_, MCPSessionManager>,
 ) -> Result<ToolExecutionResult, String> {
+    require_unlocked(CommandGroup::McpExecution)?;
     let sessions_guard = sessions.lock().await;
     let session = sessions_guard.get(&session_id)
         .ok_or(format!("Session not found: {}", session_id))?;
     
-    session.execute_tool(&tool_name, parameters).await
+    let operation = crate::cancellation::start(
+        operation_id.unwrap_or_else(|| format!("mcp-tool:{}", uuid::Uuid::new_v4())),
+        crate::cancellation::OperationKind::McpTool,
+        tool_name.clone(),
+    );
+    operation.run(session.execute_tool(&tool_name, parameters)).await
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn respond_to_mcp_approval(
     session_id: String,
     approved: bool,
     reason: Option<String>,
     sessions: State<'_, MCPSessionManager>,
 ) -> Result<(), String> {
+    require_unlocked(CommandGroup::McpExecution)?;
     let sessions_guard = sessions.lock().await;
     let session = sessions_guard.get(&session_id)
         .ok_or(format!("Session not found: {}", session_id))?;
@@ -119,7 +135,7 @@ pub async fn respond_to_mcp_approval(
     session.handle_approval_response(response).await
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_mcp_session_logs(
     session_id: String,
     sessions: State<'_, MCPSessionManager>,
@@ -132,7 +148,7 @@ pub async fn get_mcp_session_logs(
     Ok(log_entries.clone())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn list_active_mcp_sessions(
     sessions: State<'_, MCPSessionManager>,
 ) -> Result<Vec<MCPSessionInfo>, String> {
@@ -146,7 +162,7 @@ pub async fn list_active_mcp_sessions(
     Ok(session_infos)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_mcp_tool_schema(
     session_id: String,
     tool_name: String,
@@ -163,7 +179,7 @@ pub async fn get_mcp_tool_schema(
     Ok(tool.parameters_schema.clone())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_mcp_session_status(
     session_id: String,
     sessions: State<'_, MCPSessionManager>,
@@ -177,7 +193,7 @@ pub async fn get_mcp_session_status(
 }
 
 // New LLM-driven MCP commands
-#[tauri::command]
+#[crate::command]
 pub async fn create_execution_plan(
     session_id: String,
     user_request: String,
@@ -192,29 +208,79 @@ pub async fn create_execution_plan(
     let available_tools = session.get_available_tools().await;
     
     // Call LLM to generate execution plan
-    session.generate_execution_plan(&user_request, available_tools).await
+    let plan = session.generate_execution_plan(&user_request, available_tools).await?;
+    crate::mcp::plan::validate(&plan)?;
+    session.plans.lock().await.insert(
+        plan.plan_id.clone(),
+        crate::mcp::plan::StoredPlan { plan: plan.clone(), approval: None },
+    );
+    if plan.requires_approval {
+        crate::accessibility::announce(
+            &app_handle,
+            crate::accessibility::AnnouncementCategory::ApprovalRequired,
+            crate::accessibility::AnnouncementPriority::Assertive,
+            &format!("A plan with {} steps is awaiting your approval", plan.steps.len()),
+        );
+    }
+    Ok(plan)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn approve_execution_plan(
     plan_approval: ExecutionPlanApproval,
     sessions: State<'_, MCPSessionManager>,
 ) -> Result<(), String> {
-    // Store the approval for later execution
-    println!("✅ Execution plan approved: {}", plan_approval.plan_id);
-    Ok(())
+    require_unlocked(CommandGroup::McpExecution)?;
+    let sessions_guard = sessions.lock().await;
+    for session in sessions_guard.values() {
+        if let Some(stored) = session.plans.lock().await.get_mut(&plan_approval.plan_id) {
+            println!("✅ Execution plan {}: {}", if plan_approval.approved { "approved" } else { "rejected" }, plan_approval.plan_id);
+            stored.approval = Some(plan_approval);
+            return Ok(());
+        }
+    }
+    Err(format!("Plan not found: {}", plan_approval.plan_id))
 }
 
-#[tauri::command]
+/// Run a stored plan step by step, retrying and branching as its steps say; cancellable by plan id
+#[crate::command]
 pub async fn execute_approved_plan(
     plan_id: String,
     sessions: State<'_, MCPSessionManager>,
 ) -> Result<Vec<ToolExecutionResult>, String> {
-    // Execute the approved plan step by step
+    require_unlocked(CommandGroup::McpExecution)?;
+    let (session, stored) = {
+        let sessions_guard = sessions.lock().await;
+        let mut found = None;
+        for session in sessions_guard.values() {
+            if let Some(stored) = session.plans.lock().await.get(&plan_id) {
+                found = Some((session.clone(), stored.clone()));
+                break;
+            }
+        }
+        found.ok_or(format!("Plan not found: {}", plan_id))?
+    };
+
+    let approved_steps = match &stored.approval {
+        Some(approval) if !approval.approved => return Err(format!("Plan was rejected: {}", plan_id)),
+        // An empty list approves the whole plan
+        Some(approval) if !approval.approved_steps.is_empty() => {
+            Some(approval.approved_steps.iter().cloned().collect::<std::collections::HashSet<_>>())
+        }
+        Some(_) => None,
+        None if stored.plan.requires_approval => return Err(format!("Plan needs approval first: {}", plan_id)),
+        None => None,
+    };
+
+    let operation = crate::cancellation::start(&plan_id, crate::cancellation::OperationKind::McpPlan, "plan");
     println!("🚀 Executing plan: {}", plan_id);
-    
-    // TODO: Implement step-by-step execution with context passing
-    Ok(vec![])
+    session.log(
+        LogLevel::Info,
+        format!("Executing plan with {} steps: {}", stored.plan.steps.len(), stored.plan.user_request),
+        None,
+    ).await;
+
+    crate::mcp::plan::run(&session, &stored.plan, approved_steps.as_ref(), operation.token()).await
 }
 // Initialize the MCP session manager
 pub fn create_mcp_session_manager() -> MCPSessionManager {
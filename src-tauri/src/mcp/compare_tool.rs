@@ -0,0 +1,365 @@
+// src-tauri/src/mcp/compare_tool.rs
+// Screenshot diffing - compares a screenshot from before an action with one from after it, so a
+// plan can check that a click actually did something (or, with `expect_change: false`, that it
+// didn't) and retry or branch when it didn't.
+//
+// The comparison is SSIM over 8x8 blocks of the grayscale images: changed blocks are grouped into
+// bounding boxes, and the overall score is the mean over all blocks. SSIM looks at structure rather
+// than raw pixel values, so JPEG noise and slight brightness shifts don't count as changes.
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+use crate::coordinates::ImageMapping;
+use crate::mcp::tools::{take_screenshot_full, take_screenshot_region, tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+
+const BLOCK: u32 = 8;
+// The usual SSIM stabilizers for 8-bit images: (0.01 * 255)^2 and (0.03 * 255)^2
+const C1: f64 = 6.5025;
+const C2: f64 = 58.5225;
+/// Blocks less similar than this count as changed
+const BLOCK_CHANGED_BELOW: f64 = 0.9;
+const MAX_REGIONS: usize = 20;
+const MAX_DELAY_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompareScreenshotsParams {
+    /// An earlier screenshot, e.g. `{{before.image_base64}}` from a take_screenshot step; taken
+    /// now (then `delay_ms` waited) when left out
+    pub before_image_base64: Option<String>,
+    /// Taken after `delay_ms` when left out
+    pub after_image_base64: Option<String>,
+    /// Where given screenshots sit on the desktop, as take_screenshot returned it
+    pub mapping: Option<ImageMapping>,
+    /// Part of the screen to capture, for screenshots this tool takes
+    pub region: Option<ScreenRegion>,
+    pub delay_ms: Option<u64>,
+    /// Desktop area that should (or shouldn't) have changed
+    pub expected_region: Option<ScreenRegion>,
+    pub expect_change: Option<bool>,
+}
+
+/// Block-by-block SSIM of two same-sized images
+#[derive(Debug)]
+struct BlockDiff {
+    width: u32,
+    height: u32,
+    columns: u32,
+    rows: u32,
+    ssim: Vec<f64>,
+}
+
+fn block_ssim(a: &image::GrayImage, b: &image::GrayImage, x0: u32, y0: u32, width: u32, height: u32) -> f64 {
+    let n = (width * height) as f64;
+    let pixels = || (y0..y0 + height).flat_map(move |y| (x0..x0 + width).map(move |x| (x, y)));
+    let (sum_a, sum_b) = pixels().fold((0.0, 0.0), |(sa, sb), (x, y)| (sa + a.get_pixel(x, y)[0] as f64, sb + b.get_pixel(x, y)[0] as f64));
+    let (mean_a, mean_b) = (sum_a / n, sum_b / n);
+    let (mut var_a, mut var_b, mut covariance) = (0.0, 0.0, 0.0);
+    for (x, y) in pixels() {
+        let da = a.get_pixel(x, y)[0] as f64 - mean_a;
+        let db = b.get_pixel(x, y)[0] as f64 - mean_b;
+        var_a += da * da;
+        var_b += db * db;
+        covariance += da * db;
+    }
+    let (var_a, var_b, covariance) = (var_a / n, var_b / n, covariance / n);
+    ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+        / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2))
+}
+
+impl BlockDiff {
+    fn new(a: &image::GrayImage, b: &image::GrayImage) -> Self {
+        let (width, height) = a.dimensions();
+        let columns = width.div_ceil(BLOCK);
+        let rows = height.div_ceil(BLOCK);
+        let mut ssim = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            for column in 0..columns {
+                let (x0, y0) = (column * BLOCK, row * BLOCK);
+                ssim.push(block_ssim(a, b, x0, y0, BLOCK.min(width - x0), BLOCK.min(height - y0)));
+            }
+        }
+        Self { width, height, columns, rows, ssim }
+    }
+
+    fn changed(&self, column: u32, row: u32) -> bool {
+        self.ssim[(row * self.columns + column) as usize] < BLOCK_CHANGED_BELOW
+    }
+
+    /// Blocks touching the image rectangle, as (first column, first row, last column, last row)
+    fn blocks_in(&self, x: u32, y: u32, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        if width == 0 || height == 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        let right = (x + width).min(self.width) - 1;
+        let bottom = (y + height).min(self.height) - 1;
+        Some((x / BLOCK, y / BLOCK, right / BLOCK, bottom / BLOCK))
+    }
+
+    /// Mean SSIM over the blocks touching a rectangle, and whether any of them changed
+    fn within(&self, x: u32, y: u32, width: u32, height: u32) -> Option<(f64, bool)> {
+        let (left, top, right, bottom) = self.blocks_in(x, y, width, height)?;
+        let blocks: Vec<(u32, u32)> = (top..=bottom).flat_map(|row| (left..=right).map(move |column| (column, row))).collect();
+        let mean = blocks.iter().map(|&(column, row)| self.ssim[(row * self.columns + column) as usize]).sum::<f64>() / blocks.len() as f64;
+        Some((mean, blocks.iter().any(|&(column, row)| self.changed(column, row))))
+    }
+
+    fn mean(&self) -> f64 {
+        if self.ssim.is_empty() { 1.0 } else { self.ssim.iter().sum::<f64>() / self.ssim.len() as f64 }
+    }
+
+    fn changed_fraction(&self) -> f64 {
+        if self.ssim.is_empty() { return 0.0 }
+        self.ssim.iter().filter(|&&ssim| ssim < BLOCK_CHANGED_BELOW).count() as f64 / self.ssim.len() as f64
+    }
+
+    /// Bounding boxes (x, y, width, height in image pixels) of groups of touching changed blocks,
+    /// largest first
+    fn regions(&self) -> Vec<(u32, u32, u32, u32)> {
+        let mut seen = vec![false; self.ssim.len()];
+        let mut regions = Vec::new();
+        for start in 0..self.ssim.len() as u32 {
+            let (column, row) = (start % self.columns, start / self.columns);
+            if seen[start as usize] || !self.changed(column, row) {
+                continue;
+            }
+            seen[start as usize] = true;
+            let (mut left, mut top, mut right, mut bottom) = (column, row, column, row);
+            let mut stack = vec![(column, row)];
+            while let Some((column, row)) = stack.pop() {
+                left = left.min(column);
+                top = top.min(row);
+                right = right.max(column);
+                bottom = bottom.max(row);
+                for next_row in row.saturating_sub(1)..=(row + 1).min(self.rows - 1) {
+                    for next_column in column.saturating_sub(1)..=(column + 1).min(self.columns - 1) {
+                        let index = (next_row * self.columns + next_column) as usize;
+                        if !seen[index] && self.changed(next_column, next_row) {
+                            seen[index] = true;
+                            stack.push((next_column, next_row));
+                        }
+                    }
+                }
+            }
+            let (x, y) = (left * BLOCK, top * BLOCK);
+            regions.push((x, y, ((right + 1) * BLOCK).min(self.width) - x, ((bottom + 1) * BLOCK).min(self.height) - y));
+        }
+        regions.sort_by_key(|&(_, _, width, height)| std::cmp::Reverse(width as u64 * height as u64));
+        regions.truncate(MAX_REGIONS);
+        regions
+    }
+}
+
+fn decode(image_base64: &str) -> Result<image::GrayImage, String> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(image_base64.trim())
+        .map_err(|e| format!("Failed to decode screenshot: {}", e))?;
+    let image = image::load_from_memory(&bytes).map_err(|e| format!("Failed to read screenshot: {}", e))?;
+    Ok(image.to_luma8())
+}
+
+fn region_json(mapping: Option<&ImageMapping>, (x, y, width, height): (u32, u32, u32, u32)) -> serde_json::Value {
+    let (x, y, width, height) = match mapping {
+        Some(mapping) => mapping.rect_to_desktop(x as i32, y as i32, width as i32, height as i32),
+        None => (x as i32, y as i32, width as i32, height as i32),
+    };
+    serde_json::json!({ "x": x, "y": y, "width": width, "height": height })
+}
+
+#[derive(Clone)]
+pub struct CompareScreenshotsTool;
+
+impl CompareScreenshotsTool {
+    async fn screenshot(region: Option<&ScreenRegion>) -> Result<ScreenshotResult, String> {
+        match region {
+            Some(region) => take_screenshot_region(region.clone(), Some("png".to_string()), None).await,
+            None => take_screenshot_full(Some("png".to_string()), None).await,
+        }
+    }
+
+    async fn compare(&self, params: CompareScreenshotsParams) -> Result<serde_json::Value, String> {
+        let delay = Duration::from_millis(params.delay_ms.unwrap_or(300).min(MAX_DELAY_MS));
+        let mut mapping = params.mapping;
+        let before = match params.before_image_base64 {
+            Some(image) => image,
+            None => {
+                let screenshot = Self::screenshot(params.region.as_ref()).await?;
+                mapping = Some(screenshot.mapping);
+                screenshot.image_base64
+            }
+        };
+        let after = match params.after_image_base64 {
+            Some(image) => image,
+            None => {
+                crate::cancellation::cancellable(async {
+                    tokio::time::sleep(delay).await;
+                    Ok(())
+                })
+                .await?;
+                let screenshot = Self::screenshot(params.region.as_ref()).await?;
+                mapping = Some(screenshot.mapping);
+                screenshot.image_base64
+            }
+        };
+
+        let before = decode(&before)?;
+        let mut after = decode(&after)?;
+        if after.dimensions() != before.dimensions() {
+            after = image::imageops::resize(&after, before.width(), before.height(), image::imageops::FilterType::Triangle);
+        }
+        let diff = BlockDiff::new(&before, &after);
+        let regions = diff.regions();
+        let changed = !regions.is_empty();
+
+        let mut result = serde_json::json!({
+            "changed": changed,
+            "ssim": diff.mean(),
+            "changed_fraction": diff.changed_fraction(),
+            "changed_regions": regions.iter().map(|&region| region_json(mapping.as_ref(), region)).collect::<Vec<_>>(),
+        });
+
+        // What has to have changed (or stayed the same) for the check to pass
+        let expect_change = params.expect_change.unwrap_or(true);
+        let observed = match &params.expected_region {
+            Some(expected) => {
+                let ((left, top), (right, bottom)) = match &mapping {
+                    Some(mapping) => (
+                        mapping.to_image(expected.x, expected.y),
+                        mapping.to_image(expected.x + expected.width as i32, expected.y + expected.height as i32),
+                    ),
+                    None => ((expected.x, expected.y), (expected.x + expected.width as i32, expected.y + expected.height as i32)),
+                };
+                let (left, top) = (left.max(0) as u32, top.max(0) as u32);
+                let (width, height) = ((right.max(0) as u32).saturating_sub(left), (bottom.max(0) as u32).saturating_sub(top));
+                let (ssim, region_changed) = diff
+                    .within(left, top, width, height)
+                    .ok_or("The expected region is outside the screenshot")?;
+                result["expected_region_ssim"] = serde_json::json!(ssim);
+                result["expected_region_changed"] = serde_json::json!(region_changed);
+                region_changed
+            }
+            None => changed,
+        };
+        let place = if params.expected_region.is_some() { "The expected region" } else { "The screen" };
+        if observed != expect_change {
+            return Err(if expect_change {
+                format!("{} didn't change (SSIM {:.3})", place, diff.mean())
+            } else {
+                format!("{} changed ({} changed areas)", place, regions.len())
+            });
+        }
+
+        result["success"] = serde_json::json!(true);
+        result["message"] = serde_json::json!(format!(
+            "{} {} (SSIM {:.3}, {} changed areas)",
+            place,
+            if observed { "changed" } else { "didn't change" },
+            diff.mean(),
+            regions.len()
+        ));
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl ComputerUseTool for CompareScreenshotsTool {
+    fn name(&self) -> &str { "compare_screenshots" }
+
+    fn description(&self) -> String {
+        "Compare screenshots from before and after an action; fails unless the screen (or an expected region) changed".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Low }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let region = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "x": { "type": "integer" },
+                "y": { "type": "integer" },
+                "width": { "type": "integer" },
+                "height": { "type": "integer" }
+            },
+            "required": ["x", "y", "width", "height"]
+        });
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "before_image_base64": {
+                    "type": "string",
+                    "description": "Screenshot from before the action, e.g. from take_screenshot; taken now when left out"
+                },
+                "after_image_base64": {
+                    "type": "string",
+                    "description": "Screenshot from after the action; taken after delay_ms when left out"
+                },
+                "mapping": {
+                    "type": "object",
+                    "description": "The mapping take_screenshot returned with the given screenshots, so regions are in desktop coordinates"
+                },
+                "region": region.clone(),
+                "delay_ms": {
+                    "type": "integer",
+                    "maximum": MAX_DELAY_MS,
+                    "default": 300,
+                    "description": "Wait this long before taking a screenshot"
+                },
+                "expected_region": region,
+                "expect_change": {
+                    "type": "boolean",
+                    "default": true,
+                    "description": "Set to false to check that nothing changed"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: CompareScreenshotsParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for compare_screenshots: {}", e))?;
+
+        log::info!("Session {}: Comparing screenshots", session_id);
+
+        let outcome = self.compare(params).await;
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_finds_changed_regions() {
+        // A textured background, so blocks have structure to compare
+        let before = image::GrayImage::from_fn(40, 32, |x, y| image::Luma([((x * 7 + y * 13) % 200) as u8]));
+        let mut after = before.clone();
+        for y in 8..16 {
+            for x in 16..28 {
+                after.put_pixel(x, y, image::Luma([255 - before.get_pixel(x, y)[0]]));
+            }
+        }
+
+        assert!(BlockDiff::new(&before, &before).regions().is_empty());
+        assert!((BlockDiff::new(&before, &before).mean() - 1.0).abs() < 1e-9);
+
+        let diff = BlockDiff::new(&before, &after);
+        // Columns 2 and 3 of row 1, merged into one box
+        assert_eq!(diff.regions(), vec![(16, 8, 16, 8)]);
+        assert!(diff.mean() < 1.0);
+        assert!((diff.changed_fraction() - 2.0 / 20.0).abs() < 1e-9);
+        assert!(diff.within(20, 10, 2, 2).unwrap().1);
+        assert!(!diff.within(0, 24, 8, 8).unwrap().1);
+        assert!(diff.within(40, 0, 8, 8).is_none());
+
+        let mapping = ImageMapping { monitor_id: 1, origin_x: 100, origin_y: 0, scale_x: 2.0, scale_y: 2.0, monitor_scale_factor: 2.0 };
+        assert_eq!(region_json(Some(&mapping), (16, 8, 16, 8)), serde_json::json!({ "x": 132, "y": 16, "width": 32, "height": 16 }));
+    }
+}
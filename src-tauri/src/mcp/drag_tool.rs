@@ -0,0 +1,217 @@
+// src-tauri/src/mcp/drag_tool.rs
+// Drag and drop - press a mouse button at one point, move through eased intermediate steps and
+// release at another, for rearranging items, resizing panes or selecting a region of text.
+//
+// Many apps only recognize a drag when the pointer arrives through a series of moves rather than
+// one jump, so the path is split into `steps` points spread over `duration_ms`, with the easing
+// deciding how the points bunch up at the ends.
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::mcp::tools::{tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+
+const MAX_STEPS: u32 = 200;
+const MAX_DURATION_MS: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    Linear,
+    /// Slow start and finish, the way a person drags
+    #[default]
+    EaseInOut,
+    EaseOut,
+}
+
+impl Easing {
+    fn apply(self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DragParams {
+    pub from_x: i32,
+    pub from_y: i32,
+    pub to_x: i32,
+    pub to_y: i32,
+    pub button: Option<MouseButton>,
+    /// How long the move from start to end takes
+    pub duration_ms: Option<u64>,
+    /// Intermediate moves between the start and the end
+    pub steps: Option<u32>,
+    pub easing: Option<Easing>,
+}
+
+/// Start point, then `steps` eased points ending exactly on the target
+fn drag_path(from: (i32, i32), to: (i32, i32), steps: u32, easing: Easing) -> Vec<(i32, i32)> {
+    let steps = steps.max(1);
+    let mut path = vec![from];
+    for step in 1..=steps {
+        let t = easing.apply(step as f64 / steps as f64);
+        let point = (
+            from.0 + ((to.0 - from.0) as f64 * t).round() as i32,
+            from.1 + ((to.1 - from.1) as f64 * t).round() as i32,
+        );
+        // Easing bunches points up at the ends; repeated moves add nothing
+        if path.last() != Some(&point) {
+            path.push(point);
+        }
+    }
+    if path.last() != Some(&to) {
+        path.push(to);
+    }
+    path
+}
+
+#[cfg(target_os = "windows")]
+async fn perform_drag(path: Vec<(i32, i32)>, button: MouseButton, step_delay: Duration) -> Result<(), String> {
+    use winapi::um::winuser::{
+        mouse_event, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
+        MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP
+    };
+
+    let (&(start_x, start_y), rest) = path.split_first().ok_or("A drag needs at least one point")?;
+    let (down_event, up_event) = match button {
+        MouseButton::Left => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
+        MouseButton::Right => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
+        MouseButton::Middle => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
+    };
+
+    crate::coordinates::move_cursor(start_x, start_y)?;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    unsafe { mouse_event(down_event, 0, 0, 0, 0) };
+    // Apps usually want a moment between the press and the first move to start a drag
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let mut moved = Ok(());
+    for &(x, y) in rest {
+        tokio::time::sleep(step_delay).await;
+        if let Err(e) = crate::coordinates::move_cursor(x, y) {
+            moved = Err(e);
+            break;
+        }
+    }
+    // Release even when a move failed, so the button doesn't stay held
+    unsafe { mouse_event(up_event, 0, 0, 0, 0) };
+    moved
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn perform_drag(path: Vec<(i32, i32)>, button: MouseButton, step_delay: Duration) -> Result<(), String> {
+    crate::mcp::input::drag(path, button, step_delay).await
+}
+
+#[derive(Clone)]
+pub struct DragTool;
+
+#[async_trait]
+impl ComputerUseTool for DragTool {
+    fn name(&self) -> &str { "drag" }
+
+    fn description(&self) -> String {
+        "Drag with a mouse button held from one point to another, e.g. to move items, resize panes or select text".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Medium }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "from_x": { "type": "integer", "description": "X coordinate to press at" },
+                "from_y": { "type": "integer", "description": "Y coordinate to press at" },
+                "to_x": { "type": "integer", "description": "X coordinate to release at" },
+                "to_y": { "type": "integer", "description": "Y coordinate to release at" },
+                "button": {
+                    "type": "string",
+                    "enum": ["left", "right", "middle"],
+                    "default": "left",
+                    "description": "Mouse button to hold"
+                },
+                "duration_ms": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": MAX_DURATION_MS,
+                    "default": 500,
+                    "description": "How long the move takes; longer is slower"
+                },
+                "steps": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_STEPS,
+                    "default": 20,
+                    "description": "Intermediate pointer moves along the way"
+                },
+                "easing": {
+                    "type": "string",
+                    "enum": ["linear", "ease_in_out", "ease_out"],
+                    "default": "ease_in_out",
+                    "description": "How the speed changes along the path"
+                }
+            },
+            "required": ["from_x", "from_y", "to_x", "to_y"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: DragParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for drag: {}", e))?;
+
+        let button = params.button.unwrap_or(MouseButton::Left);
+        let steps = params.steps.unwrap_or(20).clamp(1, MAX_STEPS);
+        let duration_ms = params.duration_ms.unwrap_or(500).min(MAX_DURATION_MS);
+        let easing = params.easing.unwrap_or_default();
+        let from = (params.from_x, params.from_y);
+        let to = (params.to_x, params.to_y);
+        let path = drag_path(from, to, steps, easing);
+        let step_delay = Duration::from_millis(duration_ms / (path.len() as u64 - 1).max(1));
+
+        log::info!("Session {}: Dragging {:?} from {:?} to {:?} in {} moves", session_id, button, from, to, path.len() - 1);
+
+        let moves = path.len() - 1;
+        let outcome = perform_drag(path, button, step_delay).await.map(|_| serde_json::json!({
+            "success": true,
+            "from": { "x": from.0, "y": from.1 },
+            "to": { "x": to.0, "y": to.1 },
+            "button": button,
+            "moves": moves,
+            "message": format!("Dragged from ({}, {}) to ({}, {})", from.0, from.1, to.0, to.1)
+        }));
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drag_path_eases_onto_the_target() {
+        let linear = drag_path((0, 0), (100, 50), 4, Easing::Linear);
+        assert_eq!(linear, vec![(0, 0), (25, 13), (50, 25), (75, 38), (100, 50)]);
+
+        let eased = drag_path((10, 10), (210, 10), 20, Easing::EaseInOut);
+        assert_eq!((eased.first(), eased.last()), (Some(&(10, 10)), Some(&(210, 10))));
+        assert!(eased.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        // Slow at the start: the first step covers less ground than the middle one
+        let middle = eased.len() / 2;
+        assert!(eased[1].0 - eased[0].0 < eased[middle].0 - eased[middle - 1].0);
+
+        // A drag to the same point still presses and releases there
+        assert_eq!(drag_path((5, 5), (5, 5), 10, Easing::EaseOut), vec![(5, 5)]);
+        assert!((Easing::EaseOut.apply(1.0) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,491 @@
+// src-tauri/src/mcp/file_tools.rs
+// File system tools - read, write and list files so execution plans can work with documents,
+// confined to the folders in the session's `FileSandboxPolicy`.
+//
+// Paths are resolved through symlinks before they are checked, so a link inside a root can't
+// lead outside it; relative paths are taken from the first root. Every write goes to the user
+// for approval, even in sessions that otherwise skip approvals.
+use async_trait::async_trait;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::time::Instant;
+use tokio::io::AsyncReadExt;
+
+use crate::mcp::tools::{tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+
+const DEFAULT_MAX_READ_BYTES: u64 = 1024 * 1024;
+const MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_WRITE_BYTES: usize = 10 * 1024 * 1024;
+const DEFAULT_MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileEncoding {
+    #[default]
+    Text,
+    Base64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteMode {
+    #[default]
+    Overwrite,
+    Append,
+    /// Fail if the file already exists
+    Create,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReadFileParams {
+    pub path: String,
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WriteFileParams {
+    pub path: String,
+    pub content: String,
+    pub encoding: Option<FileEncoding>,
+    pub mode: Option<WriteMode>,
+    #[serde(default)]
+    pub create_dirs: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListDirectoryParams {
+    pub path: Option<String>,
+    #[serde(default)]
+    pub include_hidden: bool,
+    pub max_entries: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryEntry {
+    pub name: String,
+    /// `file`, `directory` or `symlink`
+    pub kind: &'static str,
+    pub size: Option<u64>,
+    pub modified: Option<String>,
+}
+
+impl FileSandboxPolicy {
+    // Roots that exist, resolved the same way checked paths are
+    fn canonical_roots(&self) -> Vec<PathBuf> {
+        self.allowed_roots
+            .iter()
+            .map(|root| root.trim())
+            .filter(|root| !root.is_empty())
+            .filter_map(|root| std::fs::canonicalize(root).ok())
+            .collect()
+    }
+
+    /// The real path `path` names, if it lies inside one of the roots. The file itself needn't
+    /// exist yet, but nothing after its nearest existing ancestor may step up with `..`, and a
+    /// dangling symlink counts as existing so it can't be resolved (and later written) through.
+    pub(crate) fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let roots = self.canonical_roots();
+        let first_root = roots
+            .first()
+            .ok_or("No folders are open to the file tools; add one to the session's file_sandbox")?;
+        let requested = Path::new(path.trim());
+        if requested.as_os_str().is_empty() {
+            return Err("Path can't be empty".to_string());
+        }
+        let requested = if requested.is_absolute() { requested.to_path_buf() } else { first_root.join(requested) };
+
+        let existing = requested
+            .ancestors()
+            .find(|ancestor| ancestor.symlink_metadata().is_ok())
+            .ok_or_else(|| format!("{} doesn't exist", requested.display()))?;
+        let rest = requested.strip_prefix(existing).map_err(|e| e.to_string())?;
+        if rest.components().any(|component| !matches!(component, Component::Normal(_))) {
+            return Err(format!("{} leaves the allowed folders", requested.display()));
+        }
+        let resolved = std::fs::canonicalize(existing)
+            .map_err(|e| format!("Failed to resolve {}: {}", existing.display(), e))?
+            .join(rest);
+
+        if roots.iter().any(|root| resolved.starts_with(root)) {
+            Ok(resolved)
+        } else {
+            Err(format!("{} is outside the allowed folders", requested.display()))
+        }
+    }
+
+    pub(crate) fn resolve_for_write(&self, path: &str) -> Result<PathBuf, String> {
+        if self.read_only {
+            return Err("The file sandbox is read-only".to_string());
+        }
+        self.resolve(path)
+    }
+}
+
+fn modified(metadata: &std::fs::Metadata) -> Option<String> {
+    metadata
+        .modified()
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339())
+}
+
+async fn read_file(policy: &FileSandboxPolicy, params: ReadFileParams) -> Result<serde_json::Value, String> {
+    let path = policy.resolve(&params.path)?;
+    let metadata = tokio::fs::metadata(&path).await.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} isn't a file", path.display()));
+    }
+    let max_bytes = params.max_bytes.unwrap_or(DEFAULT_MAX_READ_BYTES).clamp(1, MAX_READ_BYTES);
+    // One byte past the limit tells whether there was more, without loading the whole file
+    let file = tokio::fs::File::open(&path).await.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut bytes = Vec::new();
+    file.take(max_bytes + 1)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let truncated = bytes.len() as u64 > max_bytes;
+    bytes.truncate(max_bytes as usize);
+
+    // Text that was cut mid-character still reads as text
+    let (encoding, content) = match std::str::from_utf8(&bytes) {
+        Ok(text) => ("text", text.to_string()),
+        Err(e) if truncated && e.error_len().is_none() => ("text", String::from_utf8_lossy(&bytes[..e.valid_up_to()]).to_string()),
+        Err(_) => ("base64", base64::engine::general_purpose::STANDARD.encode(&bytes)),
+    };
+    Ok(serde_json::json!({
+        "success": true,
+        "path": path.to_string_lossy(),
+        "encoding": encoding,
+        "content": content,
+        "size": metadata.len(),
+        "truncated": truncated
+    }))
+}
+
+async fn write_file(policy: &FileSandboxPolicy, params: WriteFileParams) -> Result<serde_json::Value, String> {
+    let path = policy.resolve_for_write(&params.path)?;
+    let bytes = match params.encoding.unwrap_or_default() {
+        FileEncoding::Text => params.content.into_bytes(),
+        FileEncoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(params.content.trim())
+            .map_err(|e| format!("content isn't valid base64: {}", e))?,
+    };
+    if bytes.len() > MAX_WRITE_BYTES {
+        return Err(format!("Writes are limited to {}MB", MAX_WRITE_BYTES / (1024 * 1024)));
+    }
+    if path.is_dir() {
+        return Err(format!("{} is a directory", path.display()));
+    }
+    if let Some(parent) = path.parent().filter(|parent| !parent.exists()) {
+        if !params.create_dirs {
+            return Err(format!("{} doesn't exist; pass create_dirs to create it", parent.display()));
+        }
+        tokio::fs::create_dir_all(parent).await.map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let mode = params.mode.unwrap_or_default();
+    let mut options = tokio::fs::OpenOptions::new();
+    match mode {
+        WriteMode::Overwrite => options.write(true).create(true).truncate(true),
+        WriteMode::Append => options.append(true).create(true),
+        WriteMode::Create => options.write(true).create_new(true),
+    };
+    let mut file = options.open(&path).await.map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    tokio::io::AsyncWriteExt::write_all(&mut file, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "path": path.to_string_lossy(),
+        "bytes_written": bytes.len(),
+        "message": format!("Wrote {} bytes to {}", bytes.len(), path.display())
+    }))
+}
+
+async fn list_directory(policy: &FileSandboxPolicy, params: ListDirectoryParams) -> Result<serde_json::Value, String> {
+    let path = policy.resolve(params.path.as_deref().unwrap_or("."))?;
+    let max_entries = params.max_entries.unwrap_or(DEFAULT_MAX_ENTRIES).max(1);
+    let mut reader = tokio::fs::read_dir(&path).await.map_err(|e| format!("Failed to list {}: {}", path.display(), e))?;
+
+    let mut entries = Vec::new();
+    while let Some(entry) = reader.next_entry().await.map_err(|e| format!("Failed to list {}: {}", path.display(), e))? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !params.include_hidden && name.starts_with('.') {
+            continue;
+        }
+        let metadata = tokio::fs::symlink_metadata(entry.path()).await.ok();
+        let kind = match &metadata {
+            Some(metadata) if metadata.file_type().is_symlink() => "symlink",
+            Some(metadata) if metadata.is_dir() => "directory",
+            _ => "file",
+        };
+        entries.push(DirectoryEntry {
+            name,
+            kind,
+            size: metadata.as_ref().filter(|metadata| metadata.is_file()).map(|metadata| metadata.len()),
+            modified: metadata.as_ref().and_then(modified),
+        });
+    }
+    // Folders first, then by name
+    entries.sort_by(|a, b| (a.kind != "directory", a.name.to_lowercase()).cmp(&(b.kind != "directory", b.name.to_lowercase())));
+    let total = entries.len();
+    entries.truncate(max_entries);
+
+    Ok(serde_json::json!({
+        "success": true,
+        "path": path.to_string_lossy(),
+        "entries": entries,
+        "total": total,
+        "truncated": total > max_entries
+    }))
+}
+
+#[derive(Clone, Default)]
+pub struct ReadFileTool {
+    pub policy: FileSandboxPolicy,
+}
+
+#[async_trait]
+impl ComputerUseTool for ReadFileTool {
+    fn name(&self) -> &str { "read_file" }
+
+    fn description(&self) -> String {
+        "Read a file inside the allowed folders, as text or base64 for binary files".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Medium }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "File to read; relative paths start at the first allowed folder"
+                },
+                "max_bytes": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_READ_BYTES,
+                    "default": DEFAULT_MAX_READ_BYTES,
+                    "description": "Most bytes to return; longer files are cut off"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: ReadFileParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for read_file: {}", e))?;
+
+        log::info!("Session {}: Reading file {}", session_id, params.path);
+
+        let outcome = read_file(&self.policy, params).await;
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct WriteFileTool {
+    pub policy: FileSandboxPolicy,
+}
+
+#[async_trait]
+impl ComputerUseTool for WriteFileTool {
+    fn name(&self) -> &str { "write_file" }
+
+    fn description(&self) -> String {
+        "Write, append to or create a file inside the allowed folders (always asks for approval)".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::High }
+
+    fn always_requires_approval(&self) -> bool { true }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "File to write; relative paths start at the first allowed folder"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "What to write"
+                },
+                "encoding": {
+                    "type": "string",
+                    "enum": ["text", "base64"],
+                    "default": "text",
+                    "description": "How content is encoded"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["overwrite", "append", "create"],
+                    "default": "overwrite",
+                    "description": "Replace the file, add to its end, or only create a new one"
+                },
+                "create_dirs": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Create missing parent folders"
+                }
+            },
+            "required": ["path", "content"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: WriteFileParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for write_file: {}", e))?;
+
+        log::info!("Session {}: Writing file {} ({:?})", session_id, params.path, params.mode.unwrap_or_default());
+
+        let outcome = write_file(&self.policy, params).await;
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ListDirectoryTool {
+    pub policy: FileSandboxPolicy,
+}
+
+#[async_trait]
+impl ComputerUseTool for ListDirectoryTool {
+    fn name(&self) -> &str { "list_directory" }
+
+    fn description(&self) -> String {
+        "List the files and folders in a folder inside the allowed folders".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Medium }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Folder to list; defaults to the first allowed folder"
+                },
+                "include_hidden": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Include names starting with a dot"
+                },
+                "max_entries": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "default": DEFAULT_MAX_ENTRIES,
+                    "description": "Most entries to return"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: ListDirectoryParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for list_directory: {}", e))?;
+
+        log::info!("Session {}: Listing {}", session_id, params.path.as_deref().unwrap_or("."));
+
+        let outcome = list_directory(&self.policy, params).await;
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_confines_paths_to_roots() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("notes")).unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "x").unwrap();
+        let policy = FileSandboxPolicy {
+            allowed_roots: vec![root.path().to_string_lossy().to_string()],
+            read_only: false,
+        };
+        let canonical_root = std::fs::canonicalize(root.path()).unwrap();
+
+        // Relative paths start at the root, and new files may sit in new folders
+        assert_eq!(policy.resolve("notes").unwrap(), canonical_root.join("notes"));
+        assert_eq!(policy.resolve("notes/new/todo.md").unwrap(), canonical_root.join("notes/new/todo.md"));
+        assert!(policy.resolve("../escape.txt").is_err());
+        assert!(policy.resolve("notes/missing/../../../escape.txt").is_err());
+        assert!(policy.resolve(&outside.path().join("secret.txt").to_string_lossy()).is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(outside.path(), root.path().join("link")).unwrap();
+            assert!(policy.resolve("link/secret.txt").is_err());
+        }
+
+        assert!(FileSandboxPolicy { read_only: true, ..policy.clone() }.resolve_for_write("notes/a.txt").is_err());
+        assert!(FileSandboxPolicy::default().resolve("notes").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dangling_symlink_isnt_written_through() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let policy = FileSandboxPolicy {
+            allowed_roots: vec![root.path().to_string_lossy().to_string()],
+            read_only: false,
+        };
+        // A link to a file that doesn't exist yet would be created outside by a write
+        let target = outside.path().join("planted.txt");
+        std::os::unix::fs::symlink(&target, root.path().join("dangling.txt")).unwrap();
+        assert!(policy.resolve("dangling.txt").is_err());
+        assert!(policy.resolve("dangling.txt/child").is_err());
+
+        let params = WriteFileParams {
+            path: "dangling.txt".to_string(),
+            content: "x".to_string(),
+            encoding: None,
+            mode: None,
+            create_dirs: true,
+        };
+        assert!(write_file(&policy, params).await.is_err());
+        assert!(!target.exists());
+    }
+
+    #[tokio::test]
+    async fn test_read_stops_at_max_bytes() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("log.txt"), "0123456789").unwrap();
+        let policy = FileSandboxPolicy {
+            allowed_roots: vec![root.path().to_string_lossy().to_string()],
+            read_only: true,
+        };
+
+        let read = |max_bytes| read_file(&policy, ReadFileParams { path: "log.txt".to_string(), max_bytes: Some(max_bytes) });
+        let partial = read(4).await.unwrap();
+        assert_eq!((partial["content"].as_str(), partial["truncated"].as_bool(), partial["size"].as_u64()), (Some("0123"), Some(true), Some(10)));
+        let whole = read(10).await.unwrap();
+        assert_eq!((whole["content"].as_str(), whole["truncated"].as_bool()), (Some("0123456789"), Some(false)));
+    }
+}
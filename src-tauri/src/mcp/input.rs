@@ -0,0 +1,228 @@
+// src-tauri/src/mcp/input.rs
+// Mouse and keyboard input on macOS and Linux, through enigo. It posts CGEvents on macOS (the
+// app needs the Accessibility permission) and uses XTest on X11 or libei on Wayland. Windows
+// keeps its SendInput path in tools.rs.
+//
+// Every call runs on a blocking thread: enigo's connection isn't `Send` on every platform, and
+// typing with a per-character delay shouldn't hold up the runtime.
+use enigo::{Axis, Button, Coordinate, Direction, Enigo, Key, Keyboard, Mouse, Settings};
+use std::time::Duration;
+
+use crate::mcp::types::{KeyModifier, MouseButton, ScrollDirection, ScrollParams};
+
+fn connect() -> Result<Enigo, String> {
+    Enigo::new(&Settings::default()).map_err(|e| {
+        if cfg!(target_os = "macos") {
+            format!("Failed to create input events ({}); grant Accessibility access in System Settings", e)
+        } else {
+            format!("Failed to connect to the display server for input: {}", e)
+        }
+    })
+}
+
+async fn with_input<T, F>(action: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Enigo) -> Result<T, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || action(&mut connect()?))
+        .await
+        .map_err(|e| format!("Input task failed: {}", e))?
+}
+
+fn button_for(button: MouseButton) -> Button {
+    match button {
+        MouseButton::Left => Button::Left,
+        MouseButton::Right => Button::Right,
+        MouseButton::Middle => Button::Middle,
+    }
+}
+
+fn modifier_key(modifier: KeyModifier) -> Key {
+    match modifier {
+        KeyModifier::Ctrl => Key::Control,
+        KeyModifier::Alt => Key::Alt,
+        KeyModifier::Shift => Key::Shift,
+        KeyModifier::Meta => Key::Meta,
+    }
+}
+
+/// The key called `name`, accepting the same names as the Windows implementation
+fn key_for(name: &str) -> Result<Key, String> {
+    let key = match name.to_lowercase().as_str() {
+        "return" | "enter" => Key::Return,
+        "delete" | "del" => Key::Delete,
+        "backspace" | "back" => Key::Backspace,
+        "tab" => Key::Tab,
+        "escape" | "esc" => Key::Escape,
+        "space" => Key::Space,
+        "left" | "leftarrow" => Key::LeftArrow,
+        "right" | "rightarrow" => Key::RightArrow,
+        "up" | "uparrow" => Key::UpArrow,
+        "down" | "downarrow" => Key::DownArrow,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "ctrl" | "control" => Key::Control,
+        "alt" => Key::Alt,
+        "shift" => Key::Shift,
+        "meta" | "win" | "windows" | "cmd" | "command" => Key::Meta,
+        "f1" => Key::F1,
+        "f2" => Key::F2,
+        "f3" => Key::F3,
+        "f4" => Key::F4,
+        "f5" => Key::F5,
+        "f6" => Key::F6,
+        "f7" => Key::F7,
+        "f8" => Key::F8,
+        "f9" => Key::F9,
+        "f10" => Key::F10,
+        "f11" => Key::F11,
+        "f12" => Key::F12,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                // Lowercase, so a held Shift modifier is what decides the case
+                (Some(ch), None) if !ch.is_control() => Key::Unicode(ch.to_ascii_lowercase()),
+                _ => return Err(format!("Unsupported key: {}", name)),
+            }
+        }
+    };
+    Ok(key)
+}
+
+/// Wheel notches for a scroll; enigo counts down and right as positive
+fn scroll_steps(direction: ScrollDirection, amount: i32) -> (i32, Axis) {
+    match direction {
+        ScrollDirection::Up => (-amount, Axis::Vertical),
+        ScrollDirection::Down => (amount, Axis::Vertical),
+        ScrollDirection::Left => (-amount, Axis::Horizontal),
+        ScrollDirection::Right => (amount, Axis::Horizontal),
+    }
+}
+
+fn move_to(enigo: &mut Enigo, x: i32, y: i32) -> Result<(), String> {
+    let (x, y) = crate::coordinates::current_layout().map(|layout| layout.clamp(x, y)).unwrap_or((x, y));
+    enigo
+        .move_mouse(x, y, Coordinate::Abs)
+        .map_err(|e| format!("Failed to move cursor to ({}, {}): {}", x, y, e))
+}
+
+pub(crate) fn cursor_position() -> Result<(i32, i32), String> {
+    connect()?.location().map_err(|e| format!("Failed to get cursor position: {}", e))
+}
+
+pub(crate) async fn click(x: i32, y: i32, button: MouseButton, clicks: u32) -> Result<(), String> {
+    with_input(move |enigo| {
+        move_to(enigo, x, y)?;
+        std::thread::sleep(Duration::from_millis(10));
+        for click in 0..clicks {
+            if click > 0 {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            enigo
+                .button(button_for(button), Direction::Click)
+                .map_err(|e| format!("Failed to click: {}", e))?;
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Press `button` at the first point, move through the rest and release at the last
+pub(crate) async fn drag(path: Vec<(i32, i32)>, button: MouseButton, step_delay: Duration) -> Result<(), String> {
+    let (&(start_x, start_y), rest) = path.split_first().ok_or("A drag needs at least one point")?;
+    let rest = rest.to_vec();
+    with_input(move |enigo| {
+        move_to(enigo, start_x, start_y)?;
+        enigo
+            .button(button_for(button), Direction::Press)
+            .map_err(|e| format!("Failed to press mouse button: {}", e))?;
+        // Apps usually want a moment between the press and the first move to start a drag
+        std::thread::sleep(Duration::from_millis(50));
+        let moved = rest.iter().try_for_each(|&(x, y)| {
+            std::thread::sleep(step_delay);
+            move_to(enigo, x, y)
+        });
+        // Release even when a move failed, so the button doesn't stay held
+        let released = enigo
+            .button(button_for(button), Direction::Release)
+            .map_err(|e| format!("Failed to release mouse button: {}", e));
+        moved.and(released)
+    })
+    .await
+}
+
+pub(crate) async fn type_text(text: &str, delay_ms: u64) -> Result<(), String> {
+    let text = text.to_string();
+    with_input(move |enigo| {
+        if delay_ms == 0 {
+            return enigo.text(&text).map_err(|e| format!("Failed to type text: {}", e));
+        }
+        let mut buffer = [0u8; 4];
+        for ch in text.chars() {
+            enigo
+                .text(ch.encode_utf8(&mut buffer))
+                .map_err(|e| format!("Failed to type character '{}': {}", ch, e))?;
+            std::thread::sleep(Duration::from_millis(delay_ms));
+        }
+        Ok(())
+    })
+    .await
+}
+
+pub(crate) async fn scroll(params: ScrollParams) -> Result<(), String> {
+    with_input(move |enigo| {
+        if let (Some(x), Some(y)) = (params.x, params.y) {
+            let _ = move_to(enigo, x, y);
+        }
+        let (steps, axis) = scroll_steps(params.direction, params.amount.unwrap_or(3));
+        enigo.scroll(steps, axis).map_err(|e| format!("Failed to scroll: {}", e))
+    })
+    .await
+}
+
+pub(crate) async fn press_key(key: &str, modifiers: Vec<KeyModifier>) -> Result<(), String> {
+    let key = key_for(key)?;
+    with_input(move |enigo| {
+        let mut held = Vec::new();
+        let mut result = Ok(());
+        for modifier in modifiers.iter().map(|&modifier| modifier_key(modifier)) {
+            if let Err(e) = enigo.key(modifier, Direction::Press) {
+                result = Err(format!("Failed to press modifier: {}", e));
+                break;
+            }
+            held.push(modifier);
+        }
+        if result.is_ok() {
+            result = enigo.key(key, Direction::Click).map_err(|e| format!("Failed to press key: {}", e));
+        }
+        // Release whatever went down, even after a failure, so no modifier stays stuck
+        for modifier in held.into_iter().rev() {
+            let _ = enigo.key(modifier, Direction::Release);
+        }
+        result
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_names_and_scroll_directions() {
+        assert_eq!(key_for("Enter").unwrap(), Key::Return);
+        assert_eq!(key_for("DownArrow").unwrap(), Key::DownArrow);
+        assert_eq!(key_for("cmd").unwrap(), Key::Meta);
+        assert_eq!(key_for("F12").unwrap(), Key::F12);
+        assert_eq!(key_for("A").unwrap(), Key::Unicode('a'));
+        assert_eq!(key_for("/").unwrap(), Key::Unicode('/'));
+        assert!(key_for("hyper").is_err());
+        assert!(key_for("").is_err());
+
+        assert_eq!(scroll_steps(ScrollDirection::Up, 3), (-3, Axis::Vertical));
+        assert_eq!(scroll_steps(ScrollDirection::Right, 2), (2, Axis::Horizontal));
+    }
+}
@@ -0,0 +1,131 @@
+// src-tauri/src/mcp/limits.rs
+// Guards against a confused model - a per-session limit on tool calls and clicks per minute, and a
+// global emergency stop. The stop cancels every running tool and plan, denies every pending
+// approval and refuses new tool calls until `resume_mcp_activity`; the frontend fires it from the
+// transparency emergency hotkey, so the key that gets the window back also gets the mouse back.
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cancellation::OperationKind;
+use crate::mcp::commands::MCPSessionManager;
+use crate::mcp::types::RateLimits;
+
+const WINDOW: Duration = Duration::from_secs(60);
+const CLICK_TOOLS: &[&str] = &["click", "click_at", "click_on_text", "click_and_type", "drag"];
+
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+/// Recent calls of one session, for the sliding one-minute window
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    calls: VecDeque<Instant>,
+    clicks: VecDeque<Instant>,
+}
+
+fn prune(times: &mut VecDeque<Instant>, now: Instant) {
+    while times.front().is_some_and(|&at| now.duration_since(at) >= WINDOW) {
+        times.pop_front();
+    }
+}
+
+impl RateLimiter {
+    /// Count a call to `tool_name`, or `Err` if it would go over a limit; refused calls don't count
+    pub fn acquire(&mut self, limits: &RateLimits, tool_name: &str, now: Instant) -> Result<(), String> {
+        prune(&mut self.calls, now);
+        prune(&mut self.clicks, now);
+        let is_click = CLICK_TOOLS.contains(&tool_name);
+        if limits.max_calls_per_minute > 0 && self.calls.len() >= limits.max_calls_per_minute as usize {
+            return Err(format!("Rate limit reached: at most {} tool calls a minute", limits.max_calls_per_minute));
+        }
+        if is_click && limits.max_clicks_per_minute > 0 && self.clicks.len() >= limits.max_clicks_per_minute as usize {
+            return Err(format!("Rate limit reached: at most {} clicks a minute", limits.max_clicks_per_minute));
+        }
+        self.calls.push_back(now);
+        if is_click {
+            self.clicks.push_back(now);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct McpStopReport {
+    pub operations_cancelled: usize,
+    pub approvals_denied: usize,
+}
+
+/// `Err` while the emergency stop is on
+pub fn ensure_running() -> Result<(), String> {
+    if STOPPED.load(Ordering::SeqCst) {
+        Err("MCP activity was stopped; resume it before running tools".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+pub async fn stop_all(app_handle: &AppHandle) -> McpStopReport {
+    STOPPED.store(true, Ordering::SeqCst);
+    // Cancelled first: a running tool call holds the session list until it returns
+    let operations_cancelled = crate::cancellation::cancel_kinds(&[OperationKind::McpTool, OperationKind::McpPlan]);
+    let mut approvals_denied = 0;
+    if let Some(sessions) = app_handle.try_state::<MCPSessionManager>() {
+        let sessions: Vec<_> = sessions.lock().await.values().cloned().collect();
+        for session in sessions {
+            approvals_denied += session.deny_pending_approvals("Stopped by the emergency stop").await;
+        }
+    }
+    let report = McpStopReport { operations_cancelled, approvals_denied };
+    println!("🛑 MCP activity stopped: {} operations cancelled, {} approvals denied", operations_cancelled, approvals_denied);
+    let _ = app_handle.emit("mcp-activity-stopped", &report);
+    report
+}
+
+/// Emergency stop for every MCP session: cancel what's running and refuse new tool calls
+#[crate::command]
+pub async fn stop_all_mcp_activity(app_handle: AppHandle) -> Result<McpStopReport, String> {
+    Ok(stop_all(&app_handle).await)
+}
+
+#[crate::command]
+pub fn resume_mcp_activity(app_handle: AppHandle) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::McpExecution)?;
+    STOPPED.store(false, Ordering::SeqCst);
+    println!("▶️ MCP activity resumed");
+    let _ = app_handle.emit("mcp-activity-resumed", ());
+    Ok(())
+}
+
+#[crate::command]
+pub fn is_mcp_activity_stopped() -> Result<bool, String> {
+    Ok(STOPPED.load(Ordering::SeqCst))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_calls_and_clicks_per_minute() {
+        let limits = RateLimits { max_calls_per_minute: 4, max_clicks_per_minute: 2 };
+        let start = Instant::now();
+        let mut limiter = RateLimiter::default();
+        assert!(limiter.acquire(&limits, "click", start).is_ok());
+        assert!(limiter.acquire(&limits, "drag", start).is_ok());
+        assert!(limiter.acquire(&limits, "click_at", start).unwrap_err().contains("clicks"));
+        // Other tools still have room under the overall limit
+        assert!(limiter.acquire(&limits, "find_text", start).is_ok());
+        assert!(limiter.acquire(&limits, "take_screenshot", start).is_ok());
+        assert!(limiter.acquire(&limits, "find_text", start).unwrap_err().contains("tool calls"));
+
+        // A minute later the window has moved on
+        let later = start + WINDOW;
+        assert!(limiter.acquire(&limits, "click", later).is_ok());
+
+        let unlimited = RateLimits { max_calls_per_minute: 0, max_clicks_per_minute: 0 };
+        let mut limiter = RateLimiter::default();
+        assert!((0..100).all(|_| limiter.acquire(&unlimited, "click", start).is_ok()));
+    }
+}
@@ -3,8 +3,43 @@ pub mod types;
 pub mod server;
 pub mod tools;
 pub mod commands;
+pub mod ocr;
+pub mod ocr_cache;
+pub mod text_match;
+pub mod ocr_languages;
+pub mod content_policy;
+pub mod permissions;
+pub mod approval_context;
+pub mod limits;
+pub mod window_tools;
+pub mod ui_elements;
+pub mod clipboard_tools;
+pub mod drag_tool;
+pub mod file_tools;
+pub mod shell_tools;
+pub mod wait_tool;
+pub mod compare_tool;
+pub mod transcribe_tool;
+pub mod plan;
+pub mod recording;
+pub mod protocol;
+pub mod server_transport;
+pub mod client;
+#[cfg(not(target_os = "windows"))]
+pub mod input;
 
 // Re-export commonly used types and functions
 pub use types::*;
 pub use server::MCPSession;
-pub use commands::*;
\ No newline at end of file
+pub use commands::*;
+pub use ocr_languages::get_ocr_languages;
+pub use content_policy::{get_mcp_content_policy, set_mcp_content_policy, check_mcp_typed_text};
+pub use recording::{
+    get_mcp_recording_settings, save_mcp_recording_settings, list_mcp_recordings, get_mcp_recording,
+    export_mcp_recording, delete_mcp_recording, replay_mcp_recording,
+};
+pub use limits::{stop_all_mcp_activity, resume_mcp_activity, is_mcp_activity_stopped};
+pub use server_transport::{get_mcp_server_settings, save_mcp_server_settings, get_mcp_server_status};
+pub use client::{
+    list_external_mcp_servers, save_external_mcp_server, remove_external_mcp_server, reconnect_external_mcp_server,
+};
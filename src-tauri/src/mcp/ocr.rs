@@ -0,0 +1,308 @@
+// src-tauri/src/mcp/ocr.rs
+// OCR engines behind one trait - Windows.Media.Ocr on Windows, the Tesseract command-line tool
+// everywhere else - so find_text, click_on_text, click_and_type and debug_ocr work on every
+// platform. Engines only recognize words; matching and thresholds stay in tools.rs.
+//
+// Tesseract is run as `tesseract stdin stdout tsv`, found through `TESSERACT_PATH`, then `PATH`,
+// then the usual Homebrew and distro locations (apps started from the macOS Dock don't get the
+// shell's PATH). Its language data files use ISO 639-2 names (`eng`, `jpn`), so the BCP-47 tags
+// the tools take are mapped onto those.
+use async_trait::async_trait;
+
+/// A recognized word, in image pixels
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RecognizedWord {
+    pub text: String,
+    /// 0.0 - 1.0
+    pub confidence: f32,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[async_trait]
+pub(crate) trait OcrEngine: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Every word in `image` (PNG/JPEG bytes); `language` is a BCP-47 tag, `None` for the default
+    async fn recognize(&self, image: &[u8], language: Option<&str>) -> Result<Vec<RecognizedWord>, String>;
+}
+
+/// The platform's OCR engine
+pub(crate) fn engine() -> Box<dyn OcrEngine> {
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(WindowsOcr)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Box::new(TesseractOcr)
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) struct WindowsOcr;
+
+#[cfg(target_os = "windows")]
+#[async_trait]
+impl OcrEngine for WindowsOcr {
+    fn name(&self) -> &'static str { "windows" }
+
+    async fn recognize(&self, image: &[u8], language: Option<&str>) -> Result<Vec<RecognizedWord>, String> {
+        use windows::{
+            Storage::Streams::*,
+            Graphics::Imaging::*,
+        };
+
+        // Create OCR engine for the requested language, or the user's profile languages
+        let ocr_engine = super::ocr_languages::create_engine(language)?;
+
+        // Create memory stream from image data
+        let stream = InMemoryRandomAccessStream::new()
+            .map_err(|e| format!("Failed to create memory stream: {}", e))?;
+
+        let writer = stream.GetOutputStreamAt(0)
+            .map_err(|e| format!("Failed to get output stream: {}", e))?;
+
+        // Write image data to stream using DataWriter
+        let data_writer = DataWriter::CreateDataWriter(&writer)
+            .map_err(|e| format!("Failed to create data writer: {}", e))?;
+
+        data_writer.WriteBytes(image)
+            .map_err(|e| format!("Failed to write bytes: {}", e))?;
+
+        data_writer.StoreAsync()
+            .map_err(|e| format!("Failed to store data: {}", e))?
+            .get()
+            .map_err(|e| format!("Failed to complete store: {}", e))?;
+
+        writer.FlushAsync()
+            .map_err(|e| format!("Failed to flush stream: {}", e))?
+            .get()
+            .map_err(|e| format!("Failed to complete flush: {}", e))?;
+
+        // Create bitmap decoder
+        let decoder = BitmapDecoder::CreateAsync(&stream)
+            .map_err(|e| format!("Failed to create bitmap decoder: {}", e))?
+            .get()
+            .map_err(|e| format!("Failed to get bitmap decoder: {}", e))?;
+
+        // Get software bitmap
+        let bitmap = decoder.GetSoftwareBitmapAsync()
+            .map_err(|e| format!("Failed to get software bitmap: {}", e))?
+            .get()
+            .map_err(|e| format!("Failed to complete bitmap operation: {}", e))?;
+
+        // Perform OCR
+        let ocr_result = ocr_engine.RecognizeAsync(&bitmap)
+            .map_err(|e| format!("Failed to start OCR: {}", e))?
+            .get()
+            .map_err(|e| format!("Failed to complete OCR: {}", e))?;
+
+        let mut words = Vec::new();
+        let lines = ocr_result.Lines()
+            .map_err(|e| format!("Failed to get OCR lines: {}", e))?;
+        for line in lines {
+            let line_words = line.Words()
+                .map_err(|e| format!("Failed to get line words: {}", e))?;
+            for word in line_words {
+                let text = word.Text()
+                    .map_err(|e| format!("Failed to get word text: {}", e))?
+                    .to_string();
+                let bounding_rect = word.BoundingRect()
+                    .map_err(|e| format!("Failed to get bounding rect: {}", e))?;
+                words.push(RecognizedWord {
+                    text,
+                    // Windows OCR doesn't provide confidence per word, so we'll use a default high confidence
+                    confidence: 0.95,
+                    x: bounding_rect.X as i32,
+                    y: bounding_rect.Y as i32,
+                    width: bounding_rect.Width as i32,
+                    height: bounding_rect.Height as i32,
+                });
+            }
+        }
+        Ok(words)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) struct TesseractOcr;
+
+// BCP-47 primary language (and script, for Chinese) to Tesseract's traineddata name
+#[cfg(not(target_os = "windows"))]
+const TESSERACT_LANGUAGES: &[(&str, &str)] = &[
+    ("en", "eng"), ("de", "deu"), ("fr", "fra"), ("es", "spa"), ("it", "ita"), ("pt", "por"),
+    ("nl", "nld"), ("sv", "swe"), ("da", "dan"), ("nb", "nor"), ("no", "nor"), ("fi", "fin"),
+    ("pl", "pol"), ("cs", "ces"), ("hu", "hun"), ("tr", "tur"), ("el", "ell"), ("ru", "rus"),
+    ("uk", "ukr"), ("ar", "ara"), ("he", "heb"), ("hi", "hin"), ("th", "tha"), ("vi", "vie"),
+    ("ja", "jpn"), ("ko", "kor"), ("zh-hans", "chi_sim"), ("zh-hant", "chi_tra"),
+];
+
+/// Tesseract's name for a BCP-47 tag, e.g. `en-US` -> `eng`, `zh-TW` -> `chi_tra`
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn tesseract_language(tag: &str) -> Result<&'static str, String> {
+    let tag = super::ocr_languages::validate_language_tag(tag)?.to_lowercase();
+    let mut parts = tag.split('-');
+    let primary = parts.next().unwrap_or_default();
+    let key = if primary == "zh" {
+        match parts.next() {
+            Some("hant" | "tw" | "hk" | "mo") => "zh-hant",
+            _ => "zh-hans",
+        }
+    } else {
+        primary
+    };
+    TESSERACT_LANGUAGES
+        .iter()
+        .find(|(bcp47, _)| *bcp47 == key)
+        .map(|(_, name)| *name)
+        .ok_or_else(|| format!("Tesseract has no language data mapped for '{}'", tag))
+}
+
+/// The BCP-47 tag for one of Tesseract's language names, if it's one we map
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn bcp47_for_tesseract(name: &str) -> Option<&'static str> {
+    match name {
+        "chi_sim" => Some("zh-Hans"),
+        "chi_tra" => Some("zh-Hant"),
+        _ => TESSERACT_LANGUAGES.iter().find(|(_, tesseract)| *tesseract == name).map(|(bcp47, _)| *bcp47),
+    }
+}
+
+/// Words from Tesseract's TSV output; level 5 rows are words, confidence is 0 - 100 or -1
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn parse_tesseract_tsv(tsv: &str) -> Vec<RecognizedWord> {
+    tsv.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(12, '\t').collect();
+            if fields.len() < 12 || fields[0] != "5" {
+                return None;
+            }
+            let text = fields[11].trim();
+            let confidence: f32 = fields[10].parse().ok()?;
+            if text.is_empty() || confidence < 0.0 {
+                return None;
+            }
+            Some(RecognizedWord {
+                text: text.to_string(),
+                confidence: (confidence / 100.0).clamp(0.0, 1.0),
+                x: fields[6].parse().ok()?,
+                y: fields[7].parse().ok()?,
+                width: fields[8].parse().ok()?,
+                height: fields[9].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn tesseract_path() -> Result<std::path::PathBuf, String> {
+    if let Some(path) = std::env::var_os("TESSERACT_PATH").filter(|path| !path.is_empty()) {
+        return Ok(path.into());
+    }
+    let on_path = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).map(|dir| dir.join("tesseract")).collect::<Vec<_>>())
+        .unwrap_or_default();
+    on_path
+        .into_iter()
+        .chain(["/opt/homebrew/bin/tesseract", "/usr/local/bin/tesseract", "/usr/bin/tesseract"].map(Into::into))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            if cfg!(target_os = "macos") {
+                "Tesseract isn't installed; install it with `brew install tesseract`".to_string()
+            } else {
+                "Tesseract isn't installed; install the tesseract-ocr package for your distribution".to_string()
+            }
+        })
+}
+
+/// Language data files Tesseract has installed, `osd` excluded
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn tesseract_languages() -> Result<Vec<String>, String> {
+    let output = std::process::Command::new(tesseract_path()?)
+        .arg("--list-langs")
+        .output()
+        .map_err(|e| format!("Failed to run Tesseract: {}", e))?;
+    // Older releases print the list on stderr
+    let listing = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    Ok(listing
+        .lines()
+        .skip_while(|line| !line.starts_with("List of available languages"))
+        .skip(1)
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && *name != "osd")
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[async_trait]
+impl OcrEngine for TesseractOcr {
+    fn name(&self) -> &'static str { "tesseract" }
+
+    async fn recognize(&self, image: &[u8], language: Option<&str>) -> Result<Vec<RecognizedWord>, String> {
+        use std::process::Stdio;
+        use tokio::io::AsyncWriteExt;
+
+        let language = language.map(tesseract_language).transpose()?.unwrap_or("eng");
+        let mut child = tokio::process::Command::new(tesseract_path()?)
+            .args(["stdin", "stdout", "-l", language, "--psm", "11", "tsv"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start Tesseract: {}", e))?;
+
+        let mut stdin = child.stdin.take().ok_or("Failed to open Tesseract's input")?;
+        let image = image.to_vec();
+        // Written alongside reading the output so a large image can't fill both pipes
+        let writer = tokio::spawn(async move {
+            let _ = stdin.write_all(&image).await;
+        });
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| format!("Failed to run Tesseract: {}", e))?;
+        let _ = writer.await;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("Failed loading language") {
+                return Err(format!("Tesseract has no '{}' language data installed", language));
+            }
+            return Err(format!("Tesseract failed: {}", stderr.trim()));
+        }
+        Ok(parse_tesseract_tsv(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tesseract_languages_and_tsv_parsing() {
+        assert_eq!(tesseract_language("en-US").unwrap(), "eng");
+        assert_eq!(tesseract_language("ja").unwrap(), "jpn");
+        assert_eq!(tesseract_language("zh-TW").unwrap(), "chi_tra");
+        assert_eq!(tesseract_language("zh-Hans-CN").unwrap(), "chi_sim");
+        assert!(tesseract_language("tlh").is_err());
+        assert_eq!(bcp47_for_tesseract("deu"), Some("de"));
+        assert_eq!(bcp47_for_tesseract("chi_sim"), Some("zh-Hans"));
+
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t40\t12\t55\t18\t96.5\tSave\n\
+                   5\t1\t1\t1\t1\t2\t100\t12\t30\t18\t-1\t \n\
+                   5\t1\t1\t1\t1\t3\t140\t12\t70\t18\t41\tCancel";
+        let words = parse_tesseract_tsv(tsv);
+        assert_eq!(words.len(), 2);
+        assert_eq!((words[0].text.as_str(), words[0].x, words[0].y, words[0].width, words[0].height), ("Save", 40, 12, 55, 18));
+        assert!((words[0].confidence - 0.965).abs() < 1e-6);
+        assert_eq!(words[1].text, "Cancel");
+        assert!((words[1].confidence - 0.41).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,95 @@
+// src-tauri/src/mcp/ocr_cache.rs
+// Recent OCR results, keyed on a SHA-256 of the screenshot bytes and the language, so tools that
+// scan an unchanged screen one after another (find_text then click_on_text, or the retries of a
+// plan step) reuse the last scan instead of running full-screen OCR again.
+//
+// Identical bytes mean an identical picture, so a hit is always right; the short TTL only keeps
+// the cache small. The OCR worker process keeps its own cache, as it's the one running the scans.
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::mcp::ocr::RecognizedWord;
+
+const TTL: Duration = Duration::from_secs(10);
+const MAX_ENTRIES: usize = 8;
+
+lazy_static::lazy_static! {
+    static ref CACHE: Mutex<OcrCache> = Mutex::new(OcrCache::default());
+}
+
+struct Entry {
+    words: Vec<RecognizedWord>,
+    stored_at: Instant,
+}
+
+#[derive(Default)]
+struct OcrCache {
+    entries: HashMap<String, Entry>,
+}
+
+impl OcrCache {
+    fn prune(&mut self, now: Instant) {
+        self.entries.retain(|_, entry| now.duration_since(entry.stored_at) < TTL);
+    }
+
+    fn get(&mut self, key: &str, now: Instant) -> Option<Vec<RecognizedWord>> {
+        self.prune(now);
+        self.entries.get(key).map(|entry| entry.words.clone())
+    }
+
+    fn insert(&mut self, key: String, words: Vec<RecognizedWord>, now: Instant) {
+        self.prune(now);
+        if self.entries.len() >= MAX_ENTRIES && !self.entries.contains_key(&key) {
+            let oldest = self.entries.iter().min_by_key(|(_, entry)| entry.stored_at).map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, Entry { words, stored_at: now });
+    }
+}
+
+/// Cache key for OCR of `image` (PNG/JPEG bytes) in `language`
+pub(crate) fn key(image: &[u8], language: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(image);
+    format!("{:x}:{}", hasher.finalize(), language.unwrap_or(""))
+}
+
+pub(crate) fn get(key: &str) -> Option<Vec<RecognizedWord>> {
+    CACHE.lock().ok()?.get(key, Instant::now())
+}
+
+pub(crate) fn store(key: String, words: Vec<RecognizedWord>) {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.insert(key, words, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_expire_and_oldest_is_evicted() {
+        let word = |text: &str| RecognizedWord { text: text.to_string(), confidence: 0.9, x: 0, y: 0, width: 10, height: 10 };
+        assert_eq!(key(b"png", Some("en")), key(b"png", Some("en")));
+        assert_ne!(key(b"png", Some("en")), key(b"png", None));
+        assert_ne!(key(b"png", None), key(b"jpg", None));
+
+        let start = Instant::now();
+        let mut cache = OcrCache::default();
+        cache.insert("a".to_string(), vec![word("Save")], start);
+        assert_eq!(cache.get("a", start + Duration::from_secs(5)), Some(vec![word("Save")]));
+        assert_eq!(cache.get("a", start + TTL), None);
+
+        for index in 0..=MAX_ENTRIES {
+            cache.insert(index.to_string(), vec![], start + Duration::from_millis(index as u64));
+        }
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+        assert!(cache.get("0", start + Duration::from_secs(1)).is_none());
+        assert!(cache.get(&MAX_ENTRIES.to_string(), start + Duration::from_secs(1)).is_some());
+    }
+}
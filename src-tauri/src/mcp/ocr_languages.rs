@@ -0,0 +1,211 @@
+// OCR language packs - which languages Windows OCR (or Tesseract elsewhere) can read, engine
+// selection per call, and what to tell the user when the pack they need isn't installed.
+//
+// Windows only ships OCR for a language once its optional "Optical character recognition"
+// feature is installed, which often doesn't happen when a display language is added later.
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrLanguage {
+    /// BCP-47 tag, e.g. `en-US`
+    pub tag: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrLanguageReport {
+    /// Languages with an installed OCR pack
+    pub available: Vec<OcrLanguage>,
+    /// What `find_text` uses when no language is given, if any profile language has a pack
+    pub default_language: Option<String>,
+    /// Profile languages that have no OCR pack yet
+    pub missing_profile_languages: Vec<OcrLanguage>,
+    /// Steps to install the missing packs, when there are any
+    pub guidance: Option<String>,
+}
+
+/// Loose BCP-47 check so a bad tag fails with a readable error instead of a COM one
+pub fn validate_language_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    let valid = !tag.is_empty()
+        && tag.len() <= 35
+        && tag.split('-').all(|part| !part.is_empty() && part.len() <= 8 && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    if valid {
+        Ok(tag.to_string())
+    } else {
+        Err(format!("'{}' is not a language tag like \"en-US\" or \"ja\"", tag))
+    }
+}
+
+/// How to install the OCR pack for one or more language tags
+pub fn install_guidance(tags: &[String]) -> String {
+    let (languages, filter) = if tags.is_empty() {
+        ("any language".to_string(), "$_.Name -like 'Language.OCR*'".to_string())
+    } else {
+        let filter = tags
+            .iter()
+            .map(|tag| format!("$_.Name -like 'Language.OCR*{}*'", tag))
+            .collect::<Vec<_>>()
+            .join(" -or ");
+        (tags.join(", "), filter)
+    };
+    format!(
+        "Windows has no OCR pack for {}. Open Settings > Time & language > Language & region, choose \
+         the language (add it first if it's missing), then Language options > Optical character \
+         recognition > Download. An administrator can also run in PowerShell: \
+         Get-WindowsCapability -Online | Where-Object {{ {} }} | Add-WindowsCapability -Online",
+        languages,
+        filter
+    )
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use windows::core::HSTRING;
+    use windows::Globalization::Language;
+    use windows::Media::Ocr::OcrEngine;
+    use windows::System::UserProfile::GlobalizationPreferences;
+
+    fn describe(language: &Language) -> Option<OcrLanguage> {
+        Some(OcrLanguage {
+            tag: language.LanguageTag().ok()?.to_string(),
+            display_name: language.DisplayName().map(|name| name.to_string()).unwrap_or_default(),
+        })
+    }
+
+    fn create_language(tag: &str) -> Result<Language, String> {
+        Language::CreateLanguage(&HSTRING::from(tag))
+            .map_err(|e| format!("Unknown language '{}': {}", tag, e))
+    }
+
+    fn is_supported(language: &Language) -> bool {
+        OcrEngine::IsLanguageSupported(language).unwrap_or(false)
+    }
+
+    fn available() -> Vec<OcrLanguage> {
+        OcrEngine::AvailableRecognizerLanguages()
+            .map(|languages| languages.into_iter().filter_map(|language| describe(&language)).collect())
+            .unwrap_or_default()
+    }
+
+    fn profile_languages() -> Vec<String> {
+        GlobalizationPreferences::Languages()
+            .map(|tags| tags.into_iter().map(|tag| tag.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn report() -> Result<OcrLanguageReport, String> {
+        let available = available();
+        let missing_profile_languages: Vec<OcrLanguage> = profile_languages()
+            .iter()
+            .filter_map(|tag| create_language(tag).ok())
+            .filter(|language| !is_supported(language))
+            .filter_map(|language| describe(&language))
+            .collect();
+        let default_language = OcrEngine::TryCreateFromUserProfileLanguages()
+            .and_then(|engine| engine.RecognizerLanguage())
+            .ok()
+            .and_then(|language| describe(&language))
+            .map(|language| language.tag);
+
+        let guidance = if !missing_profile_languages.is_empty() {
+            let tags: Vec<String> = missing_profile_languages.iter().map(|language| language.tag.clone()).collect();
+            Some(install_guidance(&tags))
+        } else if available.is_empty() {
+            Some(install_guidance(&profile_languages()))
+        } else {
+            None
+        };
+
+        Ok(OcrLanguageReport { available, default_language, missing_profile_languages, guidance })
+    }
+
+    /// An engine for `tag`, or for the user's profile languages. Without a usable profile
+    /// language it falls back to any installed pack rather than failing outright.
+    pub fn create_engine(tag: Option<&str>) -> Result<OcrEngine, String> {
+        if let Some(tag) = tag {
+            let tag = validate_language_tag(tag)?;
+            let language = create_language(&tag)?;
+            if !is_supported(&language) {
+                return Err(install_guidance(&[tag]));
+            }
+            return OcrEngine::TryCreateFromLanguage(&language)
+                .map_err(|e| format!("Failed to create OCR engine for '{}': {}", tag, e));
+        }
+
+        if let Ok(engine) = OcrEngine::TryCreateFromUserProfileLanguages() {
+            return Ok(engine);
+        }
+        let fallback = OcrEngine::AvailableRecognizerLanguages()
+            .ok()
+            .and_then(|languages| languages.into_iter().next());
+        match fallback {
+            Some(language) => {
+                let tag = describe(&language).map(|language| language.tag).unwrap_or_default();
+                println!("🔤 No OCR pack for the profile languages, falling back to {}", tag);
+                OcrEngine::TryCreateFromLanguage(&language)
+                    .map_err(|e| format!("Failed to create OCR engine: {}", e))
+            }
+            None => Err(install_guidance(&profile_languages())),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) use platform::create_engine;
+
+/// Installed OCR languages, the default `find_text` uses and install guidance for missing packs
+#[crate::command]
+pub fn get_ocr_languages() -> Result<OcrLanguageReport, String> {
+    #[cfg(target_os = "windows")]
+    {
+        platform::report()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        tesseract_report()
+    }
+}
+
+// Tesseract's installed language data, under the tags the OCR tools take
+#[cfg(not(target_os = "windows"))]
+fn tesseract_report() -> Result<OcrLanguageReport, String> {
+    let available: Vec<OcrLanguage> = super::ocr::tesseract_languages()?
+        .iter()
+        .filter_map(|name| {
+            super::ocr::bcp47_for_tesseract(name).map(|tag| OcrLanguage { tag: tag.to_string(), display_name: name.clone() })
+        })
+        .collect();
+    let has_english = available.iter().any(|language| language.tag == "en");
+    let guidance = (!has_english).then(|| {
+        "Tesseract has no English language data; install the eng traineddata (tesseract-ocr-eng on most distributions)".to_string()
+    });
+    Ok(OcrLanguageReport {
+        default_language: has_english.then(|| "en".to_string()),
+        available,
+        missing_profile_languages: Vec::new(),
+        guidance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_tags_and_guidance() {
+        assert_eq!(validate_language_tag(" en-US ").unwrap(), "en-US");
+        assert_eq!(validate_language_tag("zh-Hans-CN").unwrap(), "zh-Hans-CN");
+        assert!(validate_language_tag("").is_err());
+        assert!(validate_language_tag("en_US").is_err());
+        assert!(validate_language_tag("en--US").is_err());
+
+        let guidance = install_guidance(&["ja".to_string(), "de-DE".to_string()]);
+        assert!(guidance.starts_with("Windows has no OCR pack for ja, de-DE."));
+        assert!(guidance.contains("{ $_.Name -like 'Language.OCR*ja*' -or $_.Name -like 'Language.OCR*de-DE*' }"));
+        assert!(install_guidance(&[]).starts_with("Windows has no OCR pack for any language."));
+    }
+}
@@ -0,0 +1,114 @@
+// src-tauri/src/mcp/permissions.rs
+// Permission profiles - a session can be limited to some tools and danger levels, so an agent
+// that only needs to look at the screen can't type or run commands even if the user would have
+// approved it. The check runs before the approval prompt: a refused tool never asks.
+//
+// Named profiles:
+// - "full": every tool (the default; approvals still apply)
+// - "read-only": tools that look at the screen, windows, clipboard and files without changing them
+// - "no-keyboard": everything except typing, key presses and writing the clipboard
+// - "screen-only": screenshots, OCR and screen details, nothing else
+use crate::mcp::types::{DangerLevel, PermissionProfile};
+
+const ALL_DANGER_LEVELS: [DangerLevel; 4] = [DangerLevel::Low, DangerLevel::Medium, DangerLevel::High, DangerLevel::Critical];
+
+const SCREEN_TOOLS: &[&str] = &[
+    "take_screenshot", "find_text", "debug_ocr", "wait_for", "get_screen_info", "get_cursor_position",
+];
+
+const READ_ONLY_TOOLS: &[&str] = &[
+    "take_screenshot", "find_text", "debug_ocr", "wait_for", "get_screen_info", "get_cursor_position",
+    "list_windows", "find_ui_elements", "clipboard_read", "read_file", "list_directory",
+];
+
+const KEYBOARD_TOOLS: &[&str] = &["type", "key_press", "click_and_type", "clipboard_write"];
+
+fn names(tools: &[&str]) -> Vec<String> {
+    tools.iter().map(|tool| tool.to_string()).collect()
+}
+
+impl Default for PermissionProfile {
+    fn default() -> Self {
+        Self {
+            name: "full".to_string(),
+            allowed_tools: vec!["*".to_string()],
+            denied_tools: Vec::new(),
+            allowed_danger_levels: ALL_DANGER_LEVELS.to_vec(),
+        }
+    }
+}
+
+impl PermissionProfile {
+    /// The profile called `name`
+    pub fn preset(name: &str) -> Result<Self, String> {
+        let profile = match name.trim().to_lowercase().as_str() {
+            "full" => Self::default(),
+            "read-only" => Self {
+                name: "read-only".to_string(),
+                allowed_tools: names(READ_ONLY_TOOLS),
+                ..Self::default()
+            },
+            "no-keyboard" => Self {
+                name: "no-keyboard".to_string(),
+                denied_tools: names(KEYBOARD_TOOLS),
+                ..Self::default()
+            },
+            "screen-only" => Self {
+                name: "screen-only".to_string(),
+                allowed_tools: names(SCREEN_TOOLS),
+                ..Self::default()
+            },
+            other => return Err(format!("Unknown permission profile: {}", other)),
+        };
+        Ok(profile)
+    }
+
+    pub fn allows(&self, tool_name: &str, danger_level: DangerLevel) -> bool {
+        self.check(tool_name, danger_level).is_ok()
+    }
+
+    /// `Err` saying why the profile refuses `tool_name`
+    pub fn check(&self, tool_name: &str, danger_level: DangerLevel) -> Result<(), String> {
+        let listed = |tools: &[String]| tools.iter().any(|tool| tool == "*" || tool == tool_name);
+        if !listed(&self.allowed_tools) || self.denied_tools.iter().any(|tool| tool == tool_name) {
+            return Err(format!("The \"{}\" permission profile doesn't allow {}", self.name, tool_name));
+        }
+        if !self.allowed_danger_levels.contains(&danger_level) {
+            return Err(format!(
+                "The \"{}\" permission profile doesn't allow {:?}-danger tools like {}",
+                self.name, danger_level, tool_name
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_limit_tools_and_danger_levels() {
+        let full = PermissionProfile::default();
+        assert!(full.allows("run_command", DangerLevel::Critical));
+
+        let read_only = PermissionProfile::preset("Read-Only").unwrap();
+        assert!(read_only.allows("find_text", DangerLevel::Low));
+        assert!(!read_only.allows("click", DangerLevel::Medium));
+        assert!(!read_only.allows("write_file", DangerLevel::High));
+
+        let no_keyboard = PermissionProfile::preset("no-keyboard").unwrap();
+        assert!(no_keyboard.allows("click", DangerLevel::Medium));
+        assert!(no_keyboard.check("type", DangerLevel::Medium).unwrap_err().contains("no-keyboard"));
+
+        let screen_only = PermissionProfile::preset("screen-only").unwrap();
+        assert!(screen_only.allows("take_screenshot", DangerLevel::Low));
+        assert!(!screen_only.allows("list_windows", DangerLevel::Low));
+
+        let low_only = PermissionProfile { allowed_danger_levels: vec![DangerLevel::Low], ..full };
+        assert!(low_only.allows("find_text", DangerLevel::Low));
+        assert!(!low_only.allows("click", DangerLevel::Medium));
+
+        assert!(PermissionProfile::preset("admin").is_err());
+    }
+}
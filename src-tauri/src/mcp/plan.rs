@@ -0,0 +1,309 @@
+// src-tauri/src/mcp/plan.rs
+// Execution plan engine - runs an approved plan's steps through the session, retrying failed
+// steps with backoff, following `on_failure` / `on_text_found` branches, and filling
+// `{{name.path}}` placeholders in a step's parameters from earlier steps' results.
+//
+// A placeholder names a step (by `save_as` or step id) and a dotted path into its result, with
+// numbers indexing arrays: `{{found.text_locations.0.center_x}}`. A string that is nothing but
+// one placeholder takes the value as-is (a number stays a number); placeholders inside longer
+// strings are written out as text. `{{name.success}}` is whether the step succeeded.
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::mcp::server::MCPSession;
+use crate::mcp::types::*;
+
+// Upper bound on steps run, so `go_to` loops end even if the plan never breaks out
+const MAX_STEPS_RUN: usize = 200;
+
+/// A plan kept on its session between creation, approval and execution
+#[derive(Debug, Clone)]
+pub struct StoredPlan {
+    pub plan: ToolExecutionPlan,
+    pub approval: Option<ExecutionPlanApproval>,
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `retry` (1 for the first retry)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = self.backoff_multiplier.max(1.0).powi(retry.saturating_sub(1) as i32);
+        let millis = (self.initial_backoff_ms as f64 * factor).min(self.max_backoff_ms as f64);
+        Duration::from_millis(millis as u64)
+    }
+}
+
+fn lookup<'a>(context: &'a HashMap<String, ContextData>, reference: &str) -> Option<serde_json::Value> {
+    let mut parts = reference.trim().split('.');
+    let data: &'a ContextData = context.get(parts.next()?)?;
+    let path: Vec<&str> = parts.collect();
+    if path == ["success"] {
+        return Some(serde_json::Value::Bool(data.success));
+    }
+    let mut value = &data.result;
+    for part in path {
+        value = match part.parse::<usize>() {
+            Ok(index) if value.is_array() => value.get(index)?,
+            _ => value.get(part)?,
+        };
+    }
+    Some(value.clone())
+}
+
+fn substitute_string(text: &str, context: &HashMap<String, ContextData>) -> Result<serde_json::Value, String> {
+    let trimmed = text.trim();
+    // A lone placeholder keeps the value's type
+    if let Some(reference) = trimmed.strip_prefix("{{").and_then(|rest| rest.strip_suffix("}}")) {
+        if !reference.contains("{{") {
+            return lookup(context, reference).ok_or_else(|| format!("Nothing found for {{{{{}}}}}", reference.trim()));
+        }
+    }
+    let mut output = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else { break };
+        let reference = &rest[start + 2..start + end];
+        let value = lookup(context, reference).ok_or_else(|| format!("Nothing found for {{{{{}}}}}", reference.trim()))?;
+        output.push_str(&rest[..start]);
+        match value {
+            serde_json::Value::String(value) => output.push_str(&value),
+            other => output.push_str(&other.to_string()),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    output.push_str(rest);
+    Ok(serde_json::Value::String(output))
+}
+
+/// `parameters` with every placeholder filled from earlier results
+pub fn substitute(parameters: &serde_json::Value, context: &HashMap<String, ContextData>) -> Result<serde_json::Value, String> {
+    Ok(match parameters {
+        serde_json::Value::String(text) if text.contains("{{") => substitute_string(text, context)?,
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items.iter().map(|item| substitute(item, context)).collect::<Result<_, _>>()?,
+        ),
+        serde_json::Value::Object(fields) => serde_json::Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), substitute(value, context)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// Whether `text` appears in any string (or key) of a result
+fn mentions(value: &serde_json::Value, text: &str) -> bool {
+    match value {
+        serde_json::Value::String(value) => value.to_lowercase().contains(text),
+        serde_json::Value::Array(items) => items.iter().any(|item| mentions(item, text)),
+        serde_json::Value::Object(fields) => fields.iter().any(|(key, value)| key.to_lowercase().contains(text) || mentions(value, text)),
+        _ => false,
+    }
+}
+
+/// The branch a finished step takes: `on_failure` when it failed, `on_text_found` when it
+/// succeeded and has one, otherwise carry on
+fn branch_for(step: &ToolStep, result: &ToolExecutionResult) -> StepBranch {
+    if !result.success {
+        return step.on_failure.clone().unwrap_or(StepBranch::Stop);
+    }
+    match &step.on_text_found {
+        Some(branch) if mentions(&result.result, &branch.text.to_lowercase()) => branch.then.clone(),
+        Some(branch) => branch.otherwise.clone().unwrap_or(StepBranch::Continue),
+        None => StepBranch::Continue,
+    }
+}
+
+/// Index of the step to run after `index`, or `None` to end the plan
+fn next_index(plan: &ToolExecutionPlan, index: usize, branch: &StepBranch) -> Result<Option<usize>, String> {
+    match branch {
+        StepBranch::Continue => Ok(Some(index + 1).filter(|&next| next < plan.steps.len())),
+        StepBranch::Stop => Ok(None),
+        StepBranch::GoTo { step_id } => plan
+            .steps
+            .iter()
+            .position(|step| &step.step_id == step_id)
+            .map(Some)
+            .ok_or_else(|| format!("Step {} jumps to unknown step {}", plan.steps[index].step_id, step_id)),
+    }
+}
+
+fn failed(step: &ToolStep, error: String) -> ToolExecutionResult {
+    ToolExecutionResult {
+        success: false,
+        result: serde_json::json!({ "error": error, "step_id": step.step_id }),
+        error: Some(error),
+        execution_time_ms: 0,
+        tool_name: step.tool_name.clone(),
+    }
+}
+
+/// Check a plan's branches point at real steps before anything runs
+pub fn validate(plan: &ToolExecutionPlan) -> Result<(), String> {
+    let ids: HashSet<&str> = plan.steps.iter().map(|step| step.step_id.as_str()).collect();
+    if ids.len() != plan.steps.len() {
+        return Err("Plan has duplicate step ids".to_string());
+    }
+    for step in &plan.steps {
+        let branches = [
+            step.on_failure.as_ref(),
+            step.on_text_found.as_ref().map(|branch| &branch.then),
+            step.on_text_found.as_ref().and_then(|branch| branch.otherwise.as_ref()),
+        ];
+        for branch in branches.into_iter().flatten() {
+            if let StepBranch::GoTo { step_id } = branch {
+                if !ids.contains(step_id.as_str()) {
+                    return Err(format!("Step {} jumps to unknown step {}", step.step_id, step_id));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run `plan` on `session`; steps left out of a partial approval are skipped. Returns the
+/// result of every attempt that ran, in order.
+pub async fn run(
+    session: &MCPSession,
+    plan: &ToolExecutionPlan,
+    approved_steps: Option<&HashSet<String>>,
+    token: &crate::cancellation::CancellationToken,
+) -> Result<Vec<ToolExecutionResult>, String> {
+    validate(plan)?;
+    let mut context: HashMap<String, ContextData> = HashMap::new();
+    let mut results = Vec::new();
+    let mut index = Some(0).filter(|_| !plan.steps.is_empty());
+    let mut steps_run = 0;
+
+    while let Some(current) = index {
+        token.check()?;
+        steps_run += 1;
+        if steps_run > MAX_STEPS_RUN {
+            return Err(format!("Plan stopped after {} steps; its branches may loop", MAX_STEPS_RUN));
+        }
+        let step = &plan.steps[current];
+        if approved_steps.is_some_and(|approved| !approved.contains(&step.step_id)) {
+            session.log(LogLevel::Info, format!("Skipping unapproved step: {}", step.description), Some(step.tool_name.clone())).await;
+            index = next_index(plan, current, &StepBranch::Continue)?;
+            continue;
+        }
+
+        let retry = step.retry.clone().unwrap_or(RetryPolicy { max_attempts: 1, ..Default::default() });
+        let attempts = retry.max_attempts.max(1);
+        let mut result = failed(step, "Step didn't run".to_string());
+        for attempt in 1..=attempts {
+            if attempt > 1 {
+                let wait = retry.backoff(attempt - 1);
+                session.log(
+                    LogLevel::Warning,
+                    format!("Retrying {} in {} ms (attempt {} of {})", step.tool_name, wait.as_millis(), attempt, attempts),
+                    Some(step.tool_name.clone()),
+                ).await;
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = token.cancelled() => return Err(crate::cancellation::CANCELLED.to_string()),
+                }
+            }
+            // Filled per attempt, so a retry sees the same inputs
+            result = match substitute(&step.parameters, &context) {
+                Ok(parameters) => {
+                    let outcome = token.run(session.execute_tool(&step.tool_name, parameters)).await;
+                    token.check()?;
+                    outcome.unwrap_or_else(|e| failed(step, e))
+                }
+                Err(e) => failed(step, e),
+            };
+            results.push(result.clone());
+            // A denied approval isn't worth asking again
+            if result.success || result.error.as_deref() == Some("User denied approval") {
+                break;
+            }
+        }
+
+        let data = ContextData {
+            step_id: step.step_id.clone(),
+            tool_name: step.tool_name.clone(),
+            result: result.result.clone(),
+            success: result.success,
+        };
+        if let Some(name) = step.save_as.as_deref().filter(|name| !name.is_empty()) {
+            context.insert(name.to_string(), data.clone());
+        }
+        context.insert(step.step_id.clone(), data);
+
+        index = next_index(plan, current, &branch_for(step, &result))?;
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str) -> ToolStep {
+        ToolStep {
+            step_id: id.to_string(),
+            tool_name: "find_text".to_string(),
+            description: String::new(),
+            parameters: serde_json::json!({}),
+            depends_on: None,
+            danger_level: DangerLevel::Low,
+            estimated_duration_ms: None,
+            save_as: None,
+            retry: None,
+            on_failure: None,
+            on_text_found: None,
+        }
+    }
+
+    fn outcome(success: bool, result: serde_json::Value) -> ToolExecutionResult {
+        ToolExecutionResult { success, result, error: None, execution_time_ms: 0, tool_name: "find_text".to_string() }
+    }
+
+    #[test]
+    fn test_placeholders_backoff_and_branches() {
+        let mut context = HashMap::new();
+        context.insert("found".to_string(), ContextData {
+            step_id: "s1".to_string(),
+            tool_name: "find_text".to_string(),
+            result: serde_json::json!({ "text_locations": [{ "center_x": 140, "text": "Save" }] }),
+            success: true,
+        });
+        let filled = substitute(&serde_json::json!({
+            "x": "{{found.text_locations.0.center_x}}",
+            "label": "Clicked {{ found.text_locations.0.text }} at {{found.text_locations.0.center_x}}",
+            "ok": "{{found.success}}",
+            "keep": 5
+        }), &context).unwrap();
+        assert_eq!(filled, serde_json::json!({ "x": 140, "label": "Clicked Save at 140", "ok": true, "keep": 5 }));
+        assert!(substitute(&serde_json::json!({ "x": "{{found.text_locations.3.center_x}}" }), &context).is_err());
+        assert!(substitute(&serde_json::json!({ "x": "{{missing.value}}" }), &context).is_err());
+
+        let retry = RetryPolicy { initial_backoff_ms: 100, backoff_multiplier: 3.0, max_backoff_ms: 500, ..Default::default() };
+        assert_eq!((retry.backoff(1), retry.backoff(2), retry.backoff(3)), (Duration::from_millis(100), Duration::from_millis(300), Duration::from_millis(500)));
+
+        let mut check = step("check");
+        check.on_failure = Some(StepBranch::GoTo { step_id: "fallback".to_string() });
+        check.on_text_found = Some(TextBranch { text: "Sign in".to_string(), then: StepBranch::Stop, otherwise: None });
+        assert_eq!(branch_for(&check, &outcome(false, serde_json::json!({}))), StepBranch::GoTo { step_id: "fallback".to_string() });
+        assert_eq!(branch_for(&check, &outcome(true, serde_json::json!({ "text": ["Please SIGN IN"] }))), StepBranch::Stop);
+        assert_eq!(branch_for(&check, &outcome(true, serde_json::json!({ "text": "Welcome" }))), StepBranch::Continue);
+        assert_eq!(branch_for(&step("plain"), &outcome(false, serde_json::json!({}))), StepBranch::Stop);
+
+        let plan = ToolExecutionPlan {
+            session_id: String::new(),
+            plan_id: String::new(),
+            user_request: String::new(),
+            steps: vec![check, step("next"), step("fallback")],
+            overall_risk: DangerLevel::Low,
+            requires_approval: false,
+            created_at: String::new(),
+        };
+        assert!(validate(&plan).is_ok());
+        assert_eq!(next_index(&plan, 0, &StepBranch::GoTo { step_id: "fallback".to_string() }).unwrap(), Some(2));
+        assert_eq!(next_index(&plan, 2, &StepBranch::Continue).unwrap(), None);
+        let mut broken = plan.clone();
+        broken.steps[1].on_failure = Some(StepBranch::GoTo { step_id: "nowhere".to_string() });
+        assert!(validate(&broken).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+// src-tauri/src/mcp/protocol.rs
+// Model Context Protocol messages - JSON-RPC 2.0 requests and responses, and the server side of
+// the protocol: `initialize`, `ping`, `tools/list` and `tools/call` answered from an MCP session,
+// so outside clients such as Claude Desktop can use the app's screenshot, OCR and transcription
+// tools. The transports that carry these messages live in server_transport.rs.
+//
+// Calls go through the session like any other tool call: its permission profile limits outside
+// clients to the tools in `EXPOSED_TOOLS`, and approvals, rate limits and recording all apply.
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::mcp::server::MCPSession;
+use crate::mcp::types::{PermissionProfile, ToolExecutionResult};
+
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Tools outside clients may call; none of them move the mouse or type
+pub const EXPOSED_TOOLS: &[&str] = &[
+    "take_screenshot", "find_text", "debug_ocr", "wait_for", "get_screen_info", "list_windows",
+    "find_ui_elements", "transcribe_audio", "compare_screenshots",
+];
+
+/// A request, or a notification when `id` is missing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<serde_json::Value>,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub params: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcRequest {
+    pub fn new(id: Option<serde_json::Value>, method: &str, params: serde_json::Value) -> Self {
+        Self { jsonrpc: "2.0".to_string(), id, method: method.to_string(), params }
+    }
+}
+
+impl JsonRpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+}
+
+impl JsonRpcResponse {
+    pub fn success(id: serde_json::Value, result: serde_json::Value) -> Self {
+        Self { jsonrpc: "2.0".to_string(), id, result: Some(result), error: None }
+    }
+
+    pub fn failure(id: serde_json::Value, error: JsonRpcError) -> Self {
+        Self { jsonrpc: "2.0".to_string(), id, result: None, error: Some(error) }
+    }
+}
+
+/// The permission profile of the session outside clients talk to
+pub fn exposed_profile() -> PermissionProfile {
+    PermissionProfile {
+        name: "mcp-server".to_string(),
+        allowed_tools: EXPOSED_TOOLS.iter().map(|tool| tool.to_string()).collect(),
+        ..PermissionProfile::default()
+    }
+}
+
+/// A tool result as MCP content: screenshots become image blocks, everything else JSON text
+pub fn tool_content(result: &ToolExecutionResult) -> serde_json::Value {
+    let mut content = Vec::new();
+    let mut details = result.result.clone();
+    if let Some(fields) = details.as_object_mut() {
+        if let Some(serde_json::Value::String(image)) = fields.remove("image_base64") {
+            let format = fields.get("format").and_then(|format| format.as_str()).unwrap_or("png");
+            content.push(serde_json::json!({
+                "type": "image",
+                "data": image,
+                "mimeType": format!("image/{}", format)
+            }));
+        }
+    }
+    let text = match (&result.error, details) {
+        (Some(error), _) if !result.success => error.clone(),
+        (_, serde_json::Value::String(text)) => text,
+        (_, details) => serde_json::to_string_pretty(&details).unwrap_or_default(),
+    };
+    content.push(serde_json::json!({ "type": "text", "text": text }));
+    serde_json::json!({ "content": content, "isError": !result.success })
+}
+
+/// Answers MCP requests from one session
+#[derive(Clone)]
+pub struct ProtocolServer {
+    session: Arc<MCPSession>,
+}
+
+impl ProtocolServer {
+    pub fn new(session: Arc<MCPSession>) -> Self {
+        Self { session }
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session.id
+    }
+
+    /// The JSON reply to one message, or `None` for a notification
+    pub async fn handle_text(&self, text: &str) -> Option<String> {
+        let response = match serde_json::from_str::<serde_json::Value>(text) {
+            Err(e) => Some(JsonRpcResponse::failure(serde_json::Value::Null, JsonRpcError::new(PARSE_ERROR, format!("Invalid JSON: {}", e)))),
+            Ok(value) => match serde_json::from_value::<JsonRpcRequest>(value) {
+                Ok(request) => self.handle(request).await,
+                Err(e) => Some(JsonRpcResponse::failure(serde_json::Value::Null, JsonRpcError::new(INVALID_REQUEST, format!("Invalid request: {}", e)))),
+            },
+        };
+        response.and_then(|response| serde_json::to_string(&response).ok())
+    }
+
+    pub async fn handle(&self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        let outcome = self.call(&request.method, request.params).await;
+        // Notifications get no reply, not even an error
+        let id = request.id?;
+        Some(match outcome {
+            Ok(result) => JsonRpcResponse::success(id, result),
+            Err(error) => JsonRpcResponse::failure(id, error),
+        })
+    }
+
+    async fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, JsonRpcError> {
+        match method {
+            "initialize" => {
+                log::info!("MCP client connected: {}", params["clientInfo"]["name"].as_str().unwrap_or("unknown"));
+                Ok(serde_json::json!({
+                    // We only speak one version; clients asking for another decide for themselves
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": { "tools": { "listChanged": false } },
+                    "serverInfo": {
+                        "name": self.session.config.server_name,
+                        "version": self.session.config.server_version
+                    }
+                }))
+            }
+            "ping" => Ok(serde_json::json!({})),
+            method if method.starts_with("notifications/") => Ok(serde_json::Value::Null),
+            "tools/list" => {
+                let mut tools = self.session.get_available_tools().await;
+                tools.sort_by(|a, b| a.name.cmp(&b.name));
+                let tools: Vec<serde_json::Value> = tools
+                    .into_iter()
+                    .map(|tool| serde_json::json!({
+                        "name": tool.name,
+                        "description": tool.description,
+                        "inputSchema": tool.parameters_schema
+                    }))
+                    .collect();
+                Ok(serde_json::json!({ "tools": tools }))
+            }
+            "tools/call" => {
+                let name = params["name"]
+                    .as_str()
+                    .ok_or_else(|| JsonRpcError::new(INVALID_PARAMS, "tools/call needs a tool name"))?;
+                let arguments = match &params["arguments"] {
+                    serde_json::Value::Null => serde_json::json!({}),
+                    arguments => arguments.clone(),
+                };
+                // A refused or failed call is a tool error the model can read, not a protocol error
+                let result = self.session.execute_tool(name, arguments).await.unwrap_or_else(|e| ToolExecutionResult {
+                    success: false,
+                    result: serde_json::json!({ "success": false, "error": e }),
+                    error: Some(e),
+                    execution_time_ms: 0,
+                    tool_name: name.to_string(),
+                });
+                Ok(tool_content(&result))
+            }
+            other => Err(JsonRpcError::new(METHOD_NOT_FOUND, format!("Method not found: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_and_tool_content() {
+        let request: JsonRpcRequest = serde_json::from_str(r#"{"jsonrpc":"2.0","id":7,"method":"tools/list"}"#).unwrap();
+        assert_eq!((request.id, request.method.as_str(), request.params), (Some(serde_json::json!(7)), "tools/list", serde_json::Value::Null));
+        let notification: JsonRpcRequest = serde_json::from_str(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).unwrap();
+        assert!(notification.id.is_none());
+
+        let failure = serde_json::to_value(JsonRpcResponse::failure(serde_json::json!(1), JsonRpcError::new(METHOD_NOT_FOUND, "nope"))).unwrap();
+        assert_eq!(failure, serde_json::json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32601, "message": "nope" } }));
+
+        let screenshot = ToolExecutionResult {
+            success: true,
+            result: serde_json::json!({ "image_base64": "aGk=", "format": "png", "width": 10 }),
+            error: None,
+            execution_time_ms: 5,
+            tool_name: "take_screenshot".to_string(),
+        };
+        let content = tool_content(&screenshot);
+        assert_eq!(content["isError"], false);
+        assert_eq!(content["content"][0], serde_json::json!({ "type": "image", "data": "aGk=", "mimeType": "image/png" }));
+        assert!(content["content"][1]["text"].as_str().unwrap().contains("\"width\": 10"));
+
+        let failed = ToolExecutionResult {
+            success: false,
+            result: serde_json::json!({ "success": false, "error": "Denied" }),
+            error: Some("Denied".to_string()),
+            execution_time_ms: 0,
+            tool_name: "find_text".to_string(),
+        };
+        assert_eq!(tool_content(&failed), serde_json::json!({ "content": [{ "type": "text", "text": "Denied" }], "isError": true }));
+
+        let profile = exposed_profile();
+        assert!(profile.allows("take_screenshot", crate::mcp::types::DangerLevel::Low));
+        assert!(!profile.allows("click", crate::mcp::types::DangerLevel::Medium));
+    }
+}
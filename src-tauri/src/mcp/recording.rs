@@ -0,0 +1,438 @@
+// src-tauri/src/mcp/recording.rs
+// Session recording - every tool call a session runs is kept in the mcp_recordings table with its
+// parameters, result and a screenshot from just before and just after, so an automation can be
+// audited afterwards, replayed step by step in a session, or exported as portable JSON.
+//
+// A recording is one session's calls, keyed by the session id. Screenshots are shrunk to JPEG
+// thumbnails before they're stored; turning them off makes recording nearly free.
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::mcp::commands::MCPSessionManager;
+use crate::mcp::types::*;
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+const EXPORT_FORMAT: &str = "enteract-mcp-recording";
+const EXPORT_VERSION: u32 = 1;
+// Wide enough to read a dialog, small enough that a long session doesn't fill the disk
+const SCREENSHOT_MAX_WIDTH: u32 = 1280;
+const SCREENSHOT_QUALITY: u8 = 70;
+// Longest pause kept between calls when a replay keeps the recorded timing
+const MAX_REPLAY_GAP_MS: i64 = 10_000;
+
+static DB_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<McpRecordingSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpRecordingSettings {
+    pub enabled: bool,
+    /// Keep a screenshot from before and after each call
+    pub capture_screenshots: bool,
+}
+
+impl Default for McpRecordingSettings {
+    fn default() -> Self {
+        Self { enabled: true, capture_screenshots: true }
+    }
+}
+
+/// One recorded tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedInvocation {
+    pub sequence: u32,
+    pub tool_name: String,
+    pub parameters: serde_json::Value,
+    pub success: bool,
+    pub result: serde_json::Value,
+    pub error: Option<String>,
+    pub execution_time_ms: u64,
+    /// Base64 JPEG
+    pub screenshot_before: Option<String>,
+    pub screenshot_after: Option<String>,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct McpRecordingSummary {
+    pub id: String,
+    pub invocations: u32,
+    pub failures: u32,
+    pub started_at: String,
+    pub ended_at: String,
+}
+
+/// The portable JSON form of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpRecordingExport {
+    pub format: String,
+    pub version: u32,
+    pub id: String,
+    pub exported_at: String,
+    pub invocations: Vec<RecordedInvocation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayedInvocation {
+    pub sequence: u32,
+    pub tool_name: String,
+    pub recorded_success: bool,
+    pub result: ToolExecutionResult,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct McpReplayReport {
+    pub recording_id: String,
+    pub session_id: String,
+    pub replayed: Vec<ReplayedInvocation>,
+    /// The first call whose outcome differed from the recording
+    pub diverged_at: Option<u32>,
+}
+
+const SETTINGS_FILE: &str = "mcp_recording.json";
+
+fn open() -> Result<Connection, String> {
+    let path = DB_PATH.get().ok_or("MCP recordings aren't available")?;
+    let conn = Connection::open(path).map_err(|e| format!("Failed to open MCP recordings: {}", e))?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS mcp_recordings (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            recording_id TEXT NOT NULL,
+            sequence INTEGER NOT NULL,
+            tool_name TEXT NOT NULL,
+            parameters TEXT NOT NULL,
+            success INTEGER NOT NULL,
+            result TEXT NOT NULL,
+            error TEXT,
+            execution_time_ms INTEGER NOT NULL,
+            screenshot_before TEXT,
+            screenshot_after TEXT,
+            recorded_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_mcp_recordings_recording ON mcp_recordings(recording_id, sequence);",
+    )
+    .map_err(|e| format!("Failed to create MCP recordings table: {}", e))?;
+    Ok(conn)
+}
+
+/// Re-encode a PNG screenshot as a smaller JPEG
+fn shrink(image_base64: &str) -> Result<String, String> {
+    use base64::Engine;
+    let engine = base64::engine::general_purpose::STANDARD;
+    let bytes = engine.decode(image_base64).map_err(|e| format!("Failed to decode screenshot: {}", e))?;
+    let mut image = image::load_from_memory(&bytes).map_err(|e| format!("Failed to read screenshot: {}", e))?;
+    if image.width() > SCREENSHOT_MAX_WIDTH {
+        let height = image.height() * SCREENSHOT_MAX_WIDTH / image.width();
+        image = image.resize(SCREENSHOT_MAX_WIDTH, height.max(1), image::imageops::FilterType::Triangle);
+    }
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, SCREENSHOT_QUALITY)
+        .encode_image(&image.to_rgb8())
+        .map_err(|e| format!("Failed to encode screenshot: {}", e))?;
+    Ok(engine.encode(jpeg))
+}
+
+/// A screenshot to store with the next call, or `None` when recording or screenshots are off
+pub(crate) async fn capture() -> Option<String> {
+    let settings = read_settings(&SETTINGS);
+    if !settings.enabled || !settings.capture_screenshots || DB_PATH.get().is_none() {
+        return None;
+    }
+    let screenshot = crate::mcp::tools::take_screenshot_full(Some("png".to_string()), None)
+        .await
+        .inspect_err(|e| log::warn!("Failed to capture screenshot for recording: {}", e))
+        .ok()?;
+    tokio::task::spawn_blocking(move || shrink(&screenshot.image_base64))
+        .await
+        .ok()?
+        .inspect_err(|e| log::warn!("{}", e))
+        .ok()
+}
+
+/// Append a finished call to `session_id`'s recording
+pub(crate) async fn record(
+    session_id: &str,
+    parameters: &serde_json::Value,
+    result: &ToolExecutionResult,
+    screenshot_before: Option<String>,
+) {
+    if !read_settings(&SETTINGS).enabled || DB_PATH.get().is_none() {
+        return;
+    }
+    let screenshot_after = match screenshot_before {
+        Some(_) => capture().await,
+        None => None,
+    };
+    let session_id = session_id.to_string();
+    let parameters = parameters.to_string();
+    let result = result.clone();
+    let stored = tokio::task::spawn_blocking(move || -> Result<(), String> {
+        let conn = open()?;
+        conn.execute(
+            "INSERT INTO mcp_recordings (recording_id, sequence, tool_name, parameters, success, result, error,
+                execution_time_ms, screenshot_before, screenshot_after, recorded_at)
+             VALUES (?1, (SELECT COALESCE(MAX(sequence), 0) + 1 FROM mcp_recordings WHERE recording_id = ?1),
+                ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                session_id,
+                result.tool_name,
+                parameters,
+                result.success,
+                result.result.to_string(),
+                result.error,
+                result.execution_time_ms as i64,
+                screenshot_before,
+                screenshot_after,
+                chrono::Utc::now().to_rfc3339(),
+            ],
+        )
+        .map_err(|e| format!("Failed to record tool call: {}", e))?;
+        Ok(())
+    })
+    .await;
+    match stored {
+        Ok(Err(e)) => log::warn!("{}", e),
+        Err(e) => log::warn!("Recording task failed: {}", e),
+        Ok(Ok(())) => {}
+    }
+}
+
+fn load(conn: &Connection, recording_id: &str, screenshots: bool) -> Result<Vec<RecordedInvocation>, String> {
+    let mut statement = conn
+        .prepare(
+            "SELECT sequence, tool_name, parameters, success, result, error, execution_time_ms,
+                screenshot_before, screenshot_after, recorded_at
+             FROM mcp_recordings WHERE recording_id = ?1 ORDER BY sequence",
+        )
+        .map_err(|e| format!("Failed to read recording: {}", e))?;
+    let rows = statement
+        .query_map(params![recording_id], |row| {
+            Ok(RecordedInvocation {
+                sequence: row.get(0)?,
+                tool_name: row.get(1)?,
+                parameters: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+                success: row.get(3)?,
+                result: serde_json::from_str(&row.get::<_, String>(4)?).unwrap_or_default(),
+                error: row.get(5)?,
+                execution_time_ms: row.get::<_, i64>(6)?.max(0) as u64,
+                screenshot_before: if screenshots { row.get(7)? } else { None },
+                screenshot_after: if screenshots { row.get(8)? } else { None },
+                recorded_at: row.get(9)?,
+            })
+        })
+        .map_err(|e| format!("Failed to read recording: {}", e))?;
+    let invocations = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read recording: {}", e))?;
+    if invocations.is_empty() {
+        return Err(format!("Recording not found: {}", recording_id));
+    }
+    Ok(invocations)
+}
+
+/// Pause before `next` that matches the recording: the time between `previous` finishing and
+/// `next` starting
+fn recorded_gap(previous: &RecordedInvocation, next: &RecordedInvocation) -> Duration {
+    let finished = |invocation: &RecordedInvocation| {
+        chrono::DateTime::parse_from_rfc3339(&invocation.recorded_at).map(|at| at.timestamp_millis()).ok()
+    };
+    let gap = match (finished(previous), finished(next)) {
+        (Some(previous_end), Some(next_end)) => next_end - next.execution_time_ms as i64 - previous_end,
+        _ => 0,
+    };
+    Duration::from_millis(gap.clamp(0, MAX_REPLAY_GAP_MS) as u64)
+}
+
+pub fn init(app_handle: &AppHandle) {
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = DB_PATH.set(dir.join("mcp_recordings.db"));
+        }
+        Err(e) => eprintln!("⚠️ MCP recordings unavailable: {}", e),
+    }
+}
+
+#[crate::command]
+pub fn get_mcp_recording_settings() -> Result<McpRecordingSettings, String> {
+    Ok(read_settings(&SETTINGS))
+}
+
+#[crate::command]
+pub fn save_mcp_recording_settings(settings: McpRecordingSettings) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    save_json_settings(SETTINGS_FILE, "MCP recording settings", &settings)?;
+
+    *SETTINGS
+        .write()
+        .map_err(|_| "Failed to update MCP recording settings".to_string())? = settings;
+    Ok(())
+}
+
+#[crate::command]
+pub async fn list_mcp_recordings() -> Result<Vec<McpRecordingSummary>, String> {
+    tokio::task::spawn_blocking(|| {
+        let conn = open()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT recording_id, COUNT(*), SUM(success = 0), MIN(recorded_at), MAX(recorded_at)
+                 FROM mcp_recordings GROUP BY recording_id ORDER BY MAX(recorded_at) DESC",
+            )
+            .map_err(|e| format!("Failed to list recordings: {}", e))?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok(McpRecordingSummary {
+                    id: row.get(0)?,
+                    invocations: row.get(1)?,
+                    failures: row.get(2)?,
+                    started_at: row.get(3)?,
+                    ended_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| format!("Failed to list recordings: {}", e))?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to list recordings: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Recording task failed: {}", e))?
+}
+
+/// Every call in a recording, screenshots included, for auditing
+#[crate::command]
+pub async fn get_mcp_recording(id: String) -> Result<Vec<RecordedInvocation>, String> {
+    tokio::task::spawn_blocking(move || load(&open()?, &id, true))
+        .await
+        .map_err(|e| format!("Recording task failed: {}", e))?
+}
+
+/// The recording as portable JSON, for the frontend to save
+#[crate::command]
+pub async fn export_mcp_recording(id: String, include_screenshots: Option<bool>) -> Result<String, String> {
+    let screenshots = include_screenshots.unwrap_or(true);
+    let recording_id = id.clone();
+    let invocations = tokio::task::spawn_blocking(move || load(&open()?, &recording_id, screenshots))
+        .await
+        .map_err(|e| format!("Recording task failed: {}", e))??;
+    let export = McpRecordingExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        id,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        invocations,
+    };
+    serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize recording: {}", e))
+}
+
+#[crate::command]
+pub async fn delete_mcp_recording(id: String) -> Result<usize, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::DataDeletion)?;
+    tokio::task::spawn_blocking(move || {
+        open()?
+            .execute("DELETE FROM mcp_recordings WHERE recording_id = ?1", params![id])
+            .map_err(|e| format!("Failed to delete recording: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Recording task failed: {}", e))?
+}
+
+/// Run a recording's calls again, in order and with the same parameters, in `session_id`.
+/// Approvals still apply. Stops at the first call whose outcome differs from the recording
+/// unless `continue_on_divergence` is set; cancellable as `mcp-replay:<id>`.
+#[crate::command]
+pub async fn replay_mcp_recording(
+    id: String,
+    session_id: String,
+    keep_timing: Option<bool>,
+    continue_on_divergence: Option<bool>,
+    sessions: State<'_, MCPSessionManager>,
+) -> Result<McpReplayReport, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::McpExecution)?;
+    let session = sessions
+        .lock()
+        .await
+        .get(&session_id)
+        .cloned()
+        .ok_or(format!("Session not found: {}", session_id))?;
+    let recording_id = id.clone();
+    let invocations = tokio::task::spawn_blocking(move || load(&open()?, &recording_id, false))
+        .await
+        .map_err(|e| format!("Recording task failed: {}", e))??;
+
+    let operation = crate::cancellation::start(format!("mcp-replay:{}", id), crate::cancellation::OperationKind::McpPlan, "replay");
+    session.log(LogLevel::Info, format!("Replaying recording {} ({} calls)", id, invocations.len()), None).await;
+
+    let mut report = McpReplayReport { recording_id: id, session_id, replayed: Vec::new(), diverged_at: None };
+    for (index, invocation) in invocations.iter().enumerate() {
+        operation.token().check()?;
+        if keep_timing.unwrap_or(false) && index > 0 {
+            let gap = recorded_gap(&invocations[index - 1], invocation);
+            operation.run(async { tokio::time::sleep(gap).await; Ok::<_, String>(()) }).await?;
+        }
+        let result = operation
+            .run(session.execute_tool(&invocation.tool_name, invocation.parameters.clone()))
+            .await?;
+        let diverged = result.success != invocation.success;
+        report.replayed.push(ReplayedInvocation {
+            sequence: invocation.sequence,
+            tool_name: invocation.tool_name.clone(),
+            recorded_success: invocation.success,
+            result,
+        });
+        if diverged && report.diverged_at.is_none() {
+            report.diverged_at = Some(invocation.sequence);
+            if !continue_on_divergence.unwrap_or(false) {
+                break;
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invocation(recorded_at: &str, execution_time_ms: u64) -> RecordedInvocation {
+        RecordedInvocation {
+            sequence: 1,
+            tool_name: "click".to_string(),
+            parameters: serde_json::json!({ "x": 10, "y": 20 }),
+            success: true,
+            result: serde_json::json!({ "success": true }),
+            error: None,
+            execution_time_ms,
+            screenshot_before: None,
+            screenshot_after: None,
+            recorded_at: recorded_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_replay_gap_and_export_round_trip() {
+        let first = invocation("2026-01-01T10:00:00+00:00", 100);
+        let second = invocation("2026-01-01T10:00:03+00:00", 500);
+        assert_eq!(recorded_gap(&first, &second), Duration::from_millis(2500));
+        // Overlapping or unreadable times don't wait, and long pauses are capped
+        assert_eq!(recorded_gap(&second, &first), Duration::ZERO);
+        assert_eq!(recorded_gap(&first, &invocation("not a time", 0)), Duration::ZERO);
+        let much_later = invocation("2026-01-01T11:00:00+00:00", 0);
+        assert_eq!(recorded_gap(&first, &much_later), Duration::from_millis(MAX_REPLAY_GAP_MS as u64));
+
+        let export = McpRecordingExport {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            id: "session".to_string(),
+            exported_at: "2026-01-01T12:00:00+00:00".to_string(),
+            invocations: vec![first, second],
+        };
+        let json = serde_json::to_string(&export).unwrap();
+        let parsed: McpRecordingExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.invocations.len(), 2);
+        assert_eq!(parsed.invocations[0].parameters, serde_json::json!({ "x": 10, "y": 20 }));
+    }
+}
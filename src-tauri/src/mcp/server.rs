@@ -20,6 +20,8 @@ pub struct MCPSession {
     pub log_entries: Arc<Mutex<Vec<MCPLogEntry>>>,
     pub status: Arc<Mutex<SessionStatus>>,
     pub tools: Arc<Mutex<HashMap<String, Box<dyn ComputerUseTool + Send + Sync>>>>,
+    pub plans: Arc<Mutex<HashMap<String, crate::mcp::plan::StoredPlan>>>,
+    pub rate_limiter: Arc<Mutex<crate::mcp::limits::RateLimiter>>,
 }
 
 impl MCPSession {
@@ -29,25 +31,8 @@ impl MCPSession {
         
         log::info!("🚀 Creating new MCP session: {}", session_id);
         
-        let mut tools: HashMap<String, Box<dyn ComputerUseTool + Send + Sync>> = HashMap::new();
-        
-        // Register computer use tools
-        tools.insert("click".to_string(), Box::new(crate::mcp::tools::ClickTool));
-        tools.insert("type".to_string(), Box::new(crate::mcp::tools::TypeTool));
-        tools.insert("scroll".to_string(), Box::new(crate::mcp::tools::ScrollTool));
-        tools.insert("key_press".to_string(), Box::new(crate::mcp::tools::KeyPressTool));
-        tools.insert("get_cursor_position".to_string(), Box::new(crate::mcp::tools::GetCursorPositionTool));
-        tools.insert("get_screen_info".to_string(), Box::new(crate::mcp::tools::GetScreenInfoTool));
-        tools.insert("take_screenshot".to_string(), Box::new(crate::mcp::tools::ScreenshotTool));
-        
-        // Register new atomic OCR tools
-        tools.insert("find_text".to_string(), Box::new(crate::mcp::tools::FindTextTool));
-        tools.insert("click_at".to_string(), Box::new(crate::mcp::tools::ClickAtTool));
-        tools.insert("debug_ocr".to_string(), Box::new(crate::mcp::tools::DebugOcrTool));
-        
-        // Register compound tools (require approval)
-        tools.insert("click_on_text".to_string(), Box::new(crate::mcp::tools::ClickOnTextTool));
-        tools.insert("click_and_type".to_string(), Box::new(crate::mcp::tools::ClickAndTypeTool));
+        let tools = crate::mcp::tools::session_tools(&config, Some(&app_handle));
+        
         Self {
             id: session_id,
             config,
@@ -57,6 +42,8 @@ impl MCPSession {
             log_entries: Arc::new(Mutex::new(Vec::new())),
             status: Arc::new(Mutex::new(SessionStatus::Initializing)),
             tools: Arc::new(Mutex::new(tools)),
+            plans: Arc::new(Mutex::new(HashMap::new())),
+            rate_limiter: Arc::new(Mutex::new(Default::default())),
         }
     }
     
@@ -157,16 +144,21 @@ impl MCPSession {
         tool_description: &str,
         parameters: &serde_json::Value,
         danger_level: DangerLevel,
+        policy_violations: Vec<crate::mcp::content_policy::PolicyViolation>,
+        mandatory: bool,
     ) -> Result<bool, String> {
-        if !self.config.require_approval {
+        // Content policy violations, and tools that always ask, are always put to the user
+        let flagged = !policy_violations.is_empty();
+        if !self.config.require_approval && !flagged && !mandatory {
             return Ok(true);
         }
         
         // Check if tool requires approval based on danger level
-        let requires_approval = matches!(danger_level, DangerLevel::Medium | DangerLevel::High | DangerLevel::Critical);
-        if !requires_approval {
+        let requires_approval = mandatory || matches!(danger_level, DangerLevel::Medium | DangerLevel::High | DangerLevel::Critical);
+        if !requires_approval && !flagged {
             return Ok(true);
         }
+        let danger_level = if flagged && !matches!(danger_level, DangerLevel::Critical) { DangerLevel::High } else { danger_level };
         
         // Update session status
         {
@@ -184,6 +176,10 @@ impl MCPSession {
             parameters: parameters.clone(),
             timestamp: Utc::now().to_rfc3339(),
             danger_level,
+            policy_violations,
+            risk_summary: crate::mcp::approval_context::risk_summary(tool_name, parameters, danger_level, flagged),
+            // Shows the user what the call would click or type into
+            context: crate::mcp::approval_context::gather(tool_name, parameters).await,
         };
         
         // Store pending approval
@@ -198,6 +194,24 @@ impl MCPSession {
         // Emit approval request to frontend
         self.app_handle.emit("mcp_approval_request", &request)
             .map_err(|e| format!("Failed to emit approval request: {}", e))?;
+        crate::accessibility::announce(
+            &self.app_handle,
+            crate::accessibility::AnnouncementCategory::ApprovalRequired,
+            crate::accessibility::AnnouncementPriority::Assertive,
+            &if flagged {
+                format!("Approval needed: the text {} would type breaks the content policy", tool_name)
+            } else {
+                format!("Approval needed to run {}", tool_name)
+            },
+        );
+        if flagged {
+            let details: Vec<&str> = request.policy_violations.iter().map(|v| v.detail.as_str()).collect();
+            self.log(
+                LogLevel::Warning,
+                format!("Content policy flagged {}: {}", tool_name, details.join("; ")),
+                Some(tool_name.to_string()),
+            ).await;
+        }
         
         self.log(
             LogLevel::Info,
@@ -261,6 +275,23 @@ impl MCPSession {
         }
     }
     
+    /// Deny every approval still waiting for an answer; returns how many there were
+    pub async fn deny_pending_approvals(&self, reason: &str) -> usize {
+        let pending: Vec<PendingApproval> = self.pending_approvals.lock().await.drain().map(|(_, pending)| pending).collect();
+        let denied = pending.len();
+        for pending_approval in pending {
+            let _ = pending_approval.response_sender.send(ToolApprovalResponse {
+                session_id: self.id.clone(),
+                approved: false,
+                reason: Some(reason.to_string()),
+            });
+        }
+        if denied > 0 {
+            self.log(LogLevel::Warning, format!("Denied {} pending approvals: {}", denied, reason), None).await;
+        }
+        denied
+    }
+    
     pub async fn handle_approval_response(&self, response: ToolApprovalResponse) -> Result<(), String> {
         let mut pending = self.pending_approvals.lock().await;
         
@@ -296,29 +327,61 @@ impl MCPSession {
         };
         
         if let Some(tool) = tool {
-            // Request approval if required
+            // Tools outside the session's permission profile are refused without asking, as are
+            // calls while the app is locked, over the rate limit or after the emergency stop.
+            // Outside clients reach this over the MCP server without going through a command.
+            let allowed = crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::McpExecution)
+                .and_then(|_| crate::mcp::limits::ensure_running())
+                .and_then(|_| self.config.permissions.check(tool_name, tool.danger_level()));
+            let allowed = match allowed {
+                Ok(()) => self.rate_limiter.lock().await.acquire(&self.config.rate_limits, tool_name, std::time::Instant::now()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = allowed {
+                self.log(LogLevel::Warning, e.clone(), Some(tool_name.to_string())).await;
+                return Err(e);
+            }
+            
+            // Request approval if required, or if the text to type breaks the content policy
             let approved = self.request_approval(
                 tool_name,
                 &tool.description(),
                 &parameters,
                 tool.danger_level(),
+                crate::mcp::content_policy::check_tool_call(tool_name, &parameters),
+                tool.always_requires_approval(),
             ).await?;
             
             if !approved {
-                return Ok(ToolExecutionResult {
+                let denied = ToolExecutionResult {
                     success: false,
                     result: serde_json::json!({"error": "User denied approval"}),
                     error: Some("User denied approval".to_string()),
                     execution_time_ms: 0,
                     tool_name: tool_name.to_string(),
-                });
+                };
+                self.audit(&parameters, &denied);
+                return Ok(denied);
             }
             
-            // Execute tool
-            let result = tool.execute(parameters, &self.id).await;
+            // Execute tool, recording the call with screenshots from either side of it
+            let screenshot_before = crate::mcp::recording::capture().await;
+            let result = tool.execute(parameters.clone(), &self.id).await;
             
             // Log the result
+            if let Err(ref e) = result {
+                self.audit(&parameters, &ToolExecutionResult {
+                    success: false,
+                    result: serde_json::Value::Null,
+                    error: Some(e.clone()),
+                    execution_time_ms: 0,
+                    tool_name: tool_name.to_string(),
+                });
+            }
             if let Ok(ref exec_result) = result {
+                self.audit(&parameters, exec_result);
+                crate::mcp::recording::record(&self.id, &parameters, exec_result, screenshot_before).await;
+
                 let log_entry = MCPLogEntry {
                     session_id: self.id.clone(),
                     timestamp: Utc::now().to_rfc3339(),
@@ -340,11 +403,26 @@ impl MCPSession {
         }
     }
     
+    /// Add a denied or executed call to the tamper-evident audit log
+    fn audit(&self, parameters: &serde_json::Value, result: &ToolExecutionResult) {
+        let entry = crate::data::audit::AuditEntry::tool_call(
+            &self.id,
+            &result.tool_name,
+            parameters,
+            result.success,
+            &result.result,
+            result.error.as_deref(),
+            result.execution_time_ms,
+        );
+        crate::data::audit::record(&self.app_handle, entry);
+    }
+    
     pub async fn get_available_tools(&self) -> Vec<ToolInfo> {
         let tools_guard = self.tools.lock().await;
         let mut tool_infos = Vec::new();
         
-        for (name, tool) in tools_guard.iter() {
+        // Only what the permission profile allows, so plans aren't built on refused tools
+        for (name, tool) in tools_guard.iter().filter(|(name, tool)| self.config.permissions.allows(name, tool.danger_level())) {
             tool_infos.push(ToolInfo {
                 name: name.clone(),
                 description: tool.description(),
@@ -384,6 +462,10 @@ impl MCPSession {
                     depends_on: None,
                     danger_level: DangerLevel::Low,
                     estimated_duration_ms: Some(2000),
+                    save_as: Some("found".to_string()),
+                    retry: Some(RetryPolicy::default()),
+                    on_failure: None,
+                    on_text_found: None,
                 });
             }
         }
@@ -394,10 +476,22 @@ impl MCPSession {
                     step_id: Uuid::new_v4().to_string(),
                     tool_name: "click".to_string(),
                     description: "Click on the found text location".to_string(),
-                    parameters: serde_json::json!({}),
+                    // Reads the first match when a find_text step ran before it
+                    parameters: if steps.is_empty() {
+                        serde_json::json!({})
+                    } else {
+                        serde_json::json!({
+                            "x": "{{found.text_locations.0.center_x}}",
+                            "y": "{{found.text_locations.0.center_y}}"
+                        })
+                    },
                     depends_on: steps.last().map(|s| s.step_id.clone()),
                     danger_level: DangerLevel::Medium,
                     estimated_duration_ms: Some(500),
+                    save_as: None,
+                    retry: None,
+                    on_failure: None,
+                    on_text_found: None,
                 });
             }
         }
@@ -412,6 +506,10 @@ impl MCPSession {
                     depends_on: None,
                     danger_level: DangerLevel::Low,
                     estimated_duration_ms: Some(1000),
+                    save_as: None,
+                    retry: None,
+                    on_failure: None,
+                    on_text_found: None,
                 });
             }
         }
@@ -0,0 +1,506 @@
+// src-tauri/src/mcp/server_transport.rs
+// Transports for the MCP server in protocol.rs. The app listens on localhost only, with:
+// - `GET /sse` and `POST /messages?session_id=...`: the MCP SSE transport; replies arrive as
+//   `message` events on the stream that opened the session
+// - `POST /mcp`: one JSON-RPC message per request, answered in the response body
+//
+// Clients that speak stdio (Claude Desktop's usual setup) start `enteract --mcp-stdio`, a small
+// bridge process that relays stdin lines to `/mcp` on the running app and prints the replies,
+// so tool calls still show their approval prompts in the app window.
+//
+// Every request needs the token from the settings, as a bearer header or a `token` query
+// parameter, and requests from web pages other than localhost are refused.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinSet;
+
+use crate::cancellation::CancellationToken;
+
+use crate::mcp::commands::MCPSessionManager;
+use crate::mcp::protocol::{JsonRpcError, JsonRpcResponse, ProtocolServer, INTERNAL_ERROR};
+use crate::mcp::server::MCPSession;
+use crate::mcp::types::MCPSessionConfig;
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+pub const STDIO_FLAG: &str = "--mcp-stdio";
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+// Audio for transcribe_audio comes in the body, base64-encoded
+const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<McpServerSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref RUNNING: std::sync::Mutex<Option<RunningServer>> = std::sync::Mutex::new(None);
+}
+
+struct RunningServer {
+    task: tauri::async_runtime::JoinHandle<()>,
+    /// Stops the accept loop, every open connection and tool calls still in flight
+    shutdown: CancellationToken,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Shared secret clients send; made up on first start when empty
+    pub token: String,
+}
+
+impl Default for McpServerSettings {
+    fn default() -> Self {
+        Self { enabled: false, port: 3917, token: String::new() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct McpServerStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub sse_url: String,
+    /// What to put in an MCP client's config to connect over stdio
+    pub stdio_command: Vec<String>,
+}
+
+const SETTINGS_FILE: &str = "mcp_server.json";
+
+fn write_settings(settings: &McpServerSettings) -> Result<(), String> {
+    save_json_settings(SETTINGS_FILE, "MCP server settings", settings)?;
+    *SETTINGS.write().map_err(|_| "Failed to update MCP server settings".to_string())? = settings.clone();
+    Ok(())
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' if index + 2 < bytes.len() && bytes[index + 1].is_ascii_hexdigit() && bytes[index + 2].is_ascii_hexdigit() => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap_or("00");
+                decoded.push(u8::from_str_radix(hex, 16).unwrap_or(0));
+                index += 3;
+                continue;
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Method, path, query and lowercased headers from a request head
+fn parse_head(head: &str) -> Result<HttpRequest, String> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Err("Malformed request line".to_string());
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    Ok(HttpRequest { method: method.to_uppercase(), path: path.to_string(), query, headers, body: Vec::new() })
+}
+
+async fn read_request(reader: &mut BufReader<TcpStream>) -> Result<HttpRequest, String> {
+    let mut head = String::new();
+    loop {
+        let mut line = String::new();
+        let read = reader.read_line(&mut line).await.map_err(|e| format!("Failed to read request: {}", e))?;
+        if read == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push_str(&line);
+        if head.len() > MAX_HEAD_BYTES {
+            return Err("Request headers too large".to_string());
+        }
+    }
+    let mut request = parse_head(head.trim_end())?;
+    let length: usize = request.headers.get("content-length").and_then(|length| length.parse().ok()).unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err("Request body too large".to_string());
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body).await.map_err(|e| format!("Failed to read request body: {}", e))?;
+    Ok(request)
+}
+
+/// The token matches, compared without stopping at the first difference
+fn authorized(request: &HttpRequest, token: &str) -> bool {
+    let sent = request
+        .headers
+        .get("authorization")
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .or_else(|| request.query.get("token").map(String::as_str))
+        .unwrap_or_default();
+    !token.is_empty()
+        && sent.len() == token.len()
+        && sent.bytes().zip(token.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// No Origin (a native client), or a page served from this machine
+fn origin_allowed(request: &HttpRequest) -> bool {
+    let Some(origin) = request.headers.get("origin") else { return true };
+    let authority = origin.split_once("://").map_or(origin.as_str(), |(_, rest)| rest).split('/').next().unwrap_or_default();
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1" | "tauri.localhost") || origin.starts_with("tauri://")
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> Result<(), String> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await.map_err(|e| e.to_string())?;
+    stream.write_all(body).await.map_err(|e| e.to_string())?;
+    stream.flush().await.map_err(|e| e.to_string())
+}
+
+struct ServerState {
+    protocol: ProtocolServer,
+    token: String,
+    streams: Mutex<HashMap<String, mpsc::UnboundedSender<String>>>,
+    shutdown: CancellationToken,
+}
+
+async fn serve_sse(state: Arc<ServerState>, mut stream: TcpStream) -> Result<(), String> {
+    let stream_id = uuid::Uuid::new_v4().to_string();
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    state.streams.lock().await.insert(stream_id.clone(), sender);
+
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+    let endpoint = format!("event: endpoint\ndata: /messages?session_id={}&token={}\n\n", stream_id, state.token);
+    let mut outcome = async {
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(endpoint.as_bytes()).await?;
+        stream.flush().await
+    }
+    .await;
+
+    let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+    while outcome.is_ok() {
+        let event = tokio::select! {
+            message = receiver.recv() => match message {
+                Some(message) => format!("event: message\ndata: {}\n\n", message),
+                None => break,
+            },
+            _ = keepalive.tick() => ": keepalive\n\n".to_string(),
+        };
+        outcome = async {
+            stream.write_all(event.as_bytes()).await?;
+            stream.flush().await
+        }
+        .await;
+    }
+    state.streams.lock().await.remove(&stream_id);
+    Ok(())
+}
+
+async fn handle_connection(state: Arc<ServerState>, stream: TcpStream) -> Result<(), String> {
+    let mut reader = BufReader::new(stream);
+    let request = read_request(&mut reader).await;
+    let mut stream = reader.into_inner();
+    let request = match request {
+        Ok(request) => request,
+        Err(e) => return respond(&mut stream, "400 Bad Request", "text/plain", e.as_bytes()).await,
+    };
+    if !origin_allowed(&request) {
+        return respond(&mut stream, "403 Forbidden", "text/plain", b"Origin not allowed").await;
+    }
+    if !authorized(&request, &state.token) {
+        return respond(&mut stream, "401 Unauthorized", "text/plain", b"Missing or wrong token").await;
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/sse") => serve_sse(state, stream).await,
+        ("POST", "/messages") => {
+            let sender = match request.query.get("session_id") {
+                Some(id) => state.streams.lock().await.get(id).cloned(),
+                None => None,
+            };
+            let Some(sender) = sender else {
+                return respond(&mut stream, "404 Not Found", "text/plain", b"Unknown session").await;
+            };
+            let body = String::from_utf8_lossy(&request.body).into_owned();
+            // Answered on the event stream; tool calls can wait on approvals for minutes
+            // so they outlive this connection and stop with the server instead
+            tokio::spawn(async move {
+                let reply = tokio::select! {
+                    _ = state.shutdown.cancelled() => None,
+                    reply = state.protocol.handle_text(&body) => reply,
+                };
+                if let Some(reply) = reply {
+                    let _ = sender.send(reply);
+                }
+            });
+            respond(&mut stream, "202 Accepted", "text/plain", b"Accepted").await
+        }
+        ("POST", "/mcp") => {
+            let body = String::from_utf8_lossy(&request.body).into_owned();
+            match state.protocol.handle_text(&body).await {
+                Some(reply) => respond(&mut stream, "200 OK", "application/json", reply.as_bytes()).await,
+                None => respond(&mut stream, "202 Accepted", "text/plain", b"").await,
+            }
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", b"Not found").await,
+    }
+}
+
+/// The session outside clients use, shown in the app's session list so its approvals appear
+async fn server_session(app_handle: &AppHandle) -> Result<Arc<MCPSession>, String> {
+    let config = MCPSessionConfig {
+        server_name: "enteract".to_string(),
+        permissions: crate::mcp::protocol::exposed_profile(),
+        ..MCPSessionConfig::default()
+    };
+    let session = Arc::new(MCPSession::new(config, app_handle.clone()));
+    session.initialize().await?;
+    if let Some(sessions) = app_handle.try_state::<MCPSessionManager>() {
+        sessions.lock().await.insert(session.id.clone(), session.clone());
+    }
+    Ok(session)
+}
+
+async fn run_server(
+    app_handle: AppHandle,
+    settings: McpServerSettings,
+    shutdown: CancellationToken,
+    previous: Option<tauri::async_runtime::JoinHandle<()>>,
+) {
+    // The previous server lets go of the port and its session first
+    if let Some(previous) = previous {
+        let _ = previous.await;
+    }
+    if shutdown.is_cancelled() {
+        return;
+    }
+    let listener = match TcpListener::bind(("127.0.0.1", settings.port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("❌ MCP server couldn't listen on port {}: {}", settings.port, e);
+            return;
+        }
+    };
+    let session = match server_session(&app_handle).await {
+        Ok(session) => session,
+        Err(e) => {
+            eprintln!("❌ MCP server session failed to start: {}", e);
+            return;
+        }
+    };
+    println!("🔌 MCP server listening on http://127.0.0.1:{} (session {})", settings.port, session.id);
+    let state = Arc::new(ServerState {
+        protocol: ProtocolServer::new(session.clone()),
+        token: settings.token,
+        streams: Mutex::new(HashMap::new()),
+        shutdown: shutdown.clone(),
+    });
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let state = state.clone();
+                    connections.spawn(async move {
+                        if let Err(e) = handle_connection(state, stream).await {
+                            log::warn!("MCP server connection failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => log::warn!("MCP server failed to accept a connection: {}", e),
+            },
+        }
+    }
+
+    // Open connections, SSE streams included, still hold the old token and session
+    connections.shutdown().await;
+    session.deny_pending_approvals("The MCP server was restarted").await;
+    if let Some(sessions) = app_handle.try_state::<MCPSessionManager>() {
+        sessions.lock().await.remove(&session.id);
+    }
+    println!("🔌 MCP server on port {} stopped (session {})", settings.port, session.id);
+}
+
+/// Start the server with the current settings, stopping any earlier one
+fn restart() -> Result<(), String> {
+    let mut running = RUNNING.lock().map_err(|_| "Failed to restart the MCP server".to_string())?;
+    let previous = running.take().map(|server| {
+        server.shutdown.cancel();
+        server.task
+    });
+    let mut settings = read_settings(&SETTINGS);
+    if !settings.enabled {
+        return Ok(());
+    }
+    if settings.token.is_empty() {
+        settings.token = uuid::Uuid::new_v4().simple().to_string();
+        write_settings(&settings)?;
+    }
+    let app_handle = APP_HANDLE.get().cloned().ok_or("The MCP server isn't initialized")?;
+    let shutdown = CancellationToken::new();
+    let task = tauri::async_runtime::spawn(run_server(app_handle, settings, shutdown.clone(), previous));
+    *running = Some(RunningServer { task, shutdown });
+    Ok(())
+}
+
+pub fn init(app_handle: &AppHandle) -> Result<(), String> {
+    let _ = APP_HANDLE.set(app_handle.clone());
+    restart()
+}
+
+/// Entry point of `enteract --mcp-stdio`: relay stdin to the running app and its replies to
+/// stdout; returns the exit code
+pub fn run_stdio_bridge() -> i32 {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start the MCP bridge: {}", e);
+            return 1;
+        }
+    };
+    runtime.block_on(async {
+        let settings: McpServerSettings = load_json_settings(SETTINGS_FILE);
+        let url = format!("http://127.0.0.1:{}/mcp", settings.port);
+        let client = reqwest::Client::new();
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let sent = client
+                .post(&url)
+                .bearer_auth(&settings.token)
+                .header("Content-Type", "application/json")
+                .body(line.clone())
+                .send()
+                .await;
+            let reply = match sent {
+                Ok(response) if response.status().as_u16() == 202 => None,
+                Ok(response) if response.status().is_success() => response.text().await.ok(),
+                Ok(response) => bridge_error(&line, &format!("Enteract refused the request ({})", response.status())),
+                Err(e) => bridge_error(&line, &format!("Enteract isn't running or its MCP server is off: {}", e)),
+            };
+            if let Some(reply) = reply {
+                let written = async {
+                    stdout.write_all(reply.trim_end().as_bytes()).await?;
+                    stdout.write_all(b"\n").await?;
+                    stdout.flush().await
+                }
+                .await;
+                if written.is_err() {
+                    break;
+                }
+            }
+        }
+        0
+    })
+}
+
+/// An error reply to `line` when it was a request; notifications get nothing
+fn bridge_error(line: &str, message: &str) -> Option<String> {
+    eprintln!("{}", message);
+    let id = serde_json::from_str::<serde_json::Value>(line).ok()?.get("id").cloned()?;
+    serde_json::to_string(&JsonRpcResponse::failure(id, JsonRpcError::new(INTERNAL_ERROR, message))).ok()
+}
+
+#[crate::command]
+pub fn get_mcp_server_settings() -> Result<McpServerSettings, String> {
+    Ok(read_settings(&SETTINGS))
+}
+
+#[crate::command]
+pub fn save_mcp_server_settings(settings: McpServerSettings) -> Result<McpServerStatus, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    if settings.port < 1024 {
+        return Err("Pick a port from 1024 up".to_string());
+    }
+    write_settings(&settings)?;
+    restart()?;
+    get_mcp_server_status()
+}
+
+#[crate::command]
+pub fn get_mcp_server_status() -> Result<McpServerStatus, String> {
+    let settings = read_settings(&SETTINGS);
+    let running = RUNNING
+        .lock()
+        .map(|running| running.as_ref().is_some_and(|server| !server.task.inner().is_finished()))
+        .unwrap_or(false);
+    let executable = std::env::current_exe().map(|path| path.to_string_lossy().into_owned()).unwrap_or_default();
+    Ok(McpServerStatus {
+        enabled: settings.enabled,
+        running,
+        sse_url: format!("http://127.0.0.1:{}/sse?token={}", settings.port, settings.token),
+        stdio_command: vec![executable, STDIO_FLAG.to_string()],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_head_token_and_origin() {
+        let mut request = parse_head(
+            "POST /messages?session_id=abc&token=s%3Dcret HTTP/1.1\r\nHost: 127.0.0.1:3917\r\nContent-Length: 12\r\nOrigin: http://localhost:5173",
+        )
+        .unwrap();
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/messages"));
+        assert_eq!(request.query.get("session_id").map(String::as_str), Some("abc"));
+        assert_eq!(request.headers.get("content-length").map(String::as_str), Some("12"));
+        assert!(authorized(&request, "s=cret"));
+        assert!(!authorized(&request, "s=cres"));
+        assert!(!authorized(&request, ""));
+        assert!(origin_allowed(&request));
+
+        request.query.clear();
+        request.headers.insert("authorization".to_string(), "Bearer s=cret".to_string());
+        assert!(authorized(&request, "s=cret"));
+
+        request.headers.insert("origin".to_string(), "https://evil.example".to_string());
+        assert!(!origin_allowed(&request));
+        request.headers.insert("origin".to_string(), "http://localhost.evil.example".to_string());
+        assert!(!origin_allowed(&request));
+        request.headers.remove("origin");
+        assert!(origin_allowed(&request));
+
+        assert!(parse_head("").is_err());
+        assert_eq!(percent_decode("a%20b+c%zz"), "a b c%zz");
+    }
+}
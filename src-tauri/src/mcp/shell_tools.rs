@@ -0,0 +1,347 @@
+// src-tauri/src/mcp/shell_tools.rs
+// Shell command tool - runs one command line through the platform shell (`sh -c`, or `cmd /C`
+// on Windows), limited by the session's `ShellCommandPolicy`, with every call put to the user.
+//
+// Each program in the line (every segment between `;`, `&&`, `||`, `|` and `&`) has to be on
+// the allowlist as a bare name, so it's the one PATH finds rather than a lookalike elsewhere.
+// Leading `VAR=value` assignments need their own allowlist entry, since `PATH=...` or
+// `LD_PRELOAD=...` change what an allowed program runs. Command substitution and redirection
+// are refused: one runs programs the check never saw, the other reads or writes any file.
+// Output is streamed line by line as `mcp-tool-output` events while it runs, and the result
+// keeps up to the policy's byte cap of each stream.
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::mcp::tools::{tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+
+const MAX_TIMEOUT_SECONDS: u64 = 600;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunCommandParams {
+    pub command: String,
+    /// Folder to run in; must be inside the file sandbox when one is set
+    pub working_dir: Option<String>,
+    /// Shorter than the policy's timeout, never longer
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct CapturedOutput {
+    text: String,
+    bytes: usize,
+    truncated: bool,
+}
+
+impl CapturedOutput {
+    fn push(&mut self, line: &str, cap: usize) {
+        self.bytes += line.len();
+        if self.truncated {
+            return;
+        }
+        let room = cap.saturating_sub(self.text.len());
+        if line.len() <= room {
+            self.text.push_str(line);
+        } else {
+            let cut = (0..=room).rev().find(|&i| line.is_char_boundary(i)).unwrap_or(0);
+            self.text.push_str(&line[..cut]);
+            self.truncated = true;
+        }
+    }
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+// The leading words of every segment of a command line, up to and including its program:
+// `A=1 git log` gives `a=1` and `git`. Program names lose `.exe` but keep any path.
+fn programs(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for segment in command.split(['\n', ';', '|', '&']) {
+        for word in segment.split_whitespace() {
+            let word = word.trim_matches(|c| c == '"' || c == '\'' || c == '(' || c == ')').to_lowercase();
+            if word.is_empty() {
+                continue;
+            }
+            if is_assignment(&word) {
+                words.push(word);
+                continue;
+            }
+            words.push(word.strip_suffix(".exe").map(str::to_string).unwrap_or(word));
+            break;
+        }
+    }
+    words
+}
+
+impl ShellCommandPolicy {
+    /// Why `command` may not run, if it may not
+    pub(crate) fn check(&self, command: &str) -> Result<(), String> {
+        let command = command.trim();
+        if command.is_empty() {
+            return Err("Command can't be empty".to_string());
+        }
+        if command.contains('`') || command.contains("$(") || command.contains("<(") || command.contains(">(") {
+            return Err("Command substitution isn't allowed".to_string());
+        }
+        if command.contains(['<', '>']) {
+            return Err("Redirection isn't allowed".to_string());
+        }
+        let lower = command.to_lowercase();
+        if let Some(denied) = self.denylist.iter().map(|entry| entry.trim()).find(|entry| !entry.is_empty() && lower.contains(&entry.to_lowercase())) {
+            return Err(format!("The command contains \"{}\", which is denied", denied));
+        }
+        if self.allowlist.iter().any(|entry| entry.trim() == "*") {
+            return Ok(());
+        }
+        if self.allowlist.iter().all(|entry| entry.trim().is_empty()) {
+            return Err("No programs are allowed; add some to the session's shell allowlist".to_string());
+        }
+        let allowed: Vec<String> = self.allowlist.iter().map(|entry| entry.trim().to_lowercase()).collect();
+        for word in programs(command) {
+            if is_assignment(&word) {
+                if !allowed.contains(&word) {
+                    return Err(format!("Setting {} isn't on the allowlist", word));
+                }
+            } else if word.contains(['/', '\\']) {
+                return Err(format!("{} must be a bare program name found through PATH", word));
+            } else if !allowed.contains(&word) {
+                return Err(format!("{} isn't on the allowlist", word));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn shell_command(command: &str) -> tokio::process::Command {
+    #[cfg(windows)]
+    {
+        let mut shell = tokio::process::Command::new("cmd");
+        shell.arg("/C").arg(command);
+        // cmd looks in the working folder before PATH unless told not to
+        shell.env("NoDefaultCurrentDirectoryInExePath", "1");
+        shell
+    }
+    #[cfg(not(windows))]
+    {
+        let mut shell = tokio::process::Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    }
+}
+
+// Read `reader` to the end, capturing and announcing each line
+async fn pump(
+    reader: Option<impl AsyncRead + Unpin>,
+    stream: &str,
+    captured: &mut CapturedOutput,
+    cap: usize,
+    emit: &(impl Fn(&str, &str) + Sync),
+) {
+    let Some(reader) = reader else { return };
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let text = String::from_utf8_lossy(&line);
+                captured.push(&text, cap);
+                emit(stream, &text);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RunCommandTool {
+    pub policy: ShellCommandPolicy,
+    pub sandbox: FileSandboxPolicy,
+    pub app_handle: Option<AppHandle>,
+}
+
+impl RunCommandTool {
+    async fn run(&self, params: RunCommandParams, session_id: &str) -> Result<serde_json::Value, String> {
+        self.policy.check(&params.command)?;
+        let working_dir = match params.working_dir.as_deref() {
+            Some(dir) => Some(self.sandbox.resolve(dir)?),
+            None if !self.sandbox.allowed_roots.is_empty() => Some(self.sandbox.resolve(".")?),
+            None => None,
+        };
+        let limit = Duration::from_secs(
+            params
+                .timeout_seconds
+                .unwrap_or(self.policy.timeout_seconds)
+                .clamp(1, self.policy.timeout_seconds.clamp(1, MAX_TIMEOUT_SECONDS)),
+        );
+
+        let mut command = shell_command(&params.command);
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(dir) = &working_dir {
+            command.current_dir(dir);
+        }
+        let mut child = command.spawn().map_err(|e| format!("Failed to start command: {}", e))?;
+
+        let emit = |stream: &str, text: &str| {
+            if let Some(app_handle) = &self.app_handle {
+                let _ = app_handle.emit("mcp-tool-output", serde_json::json!({
+                    "session_id": session_id,
+                    "tool_name": "run_command",
+                    "stream": stream,
+                    "text": text
+                }));
+            }
+        };
+        let cap = self.policy.max_output_bytes;
+        let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+        let mut out = CapturedOutput::default();
+        let mut err = CapturedOutput::default();
+        let started = Instant::now();
+        let finished = tokio::time::timeout(limit, async {
+            let (_, _, status) = tokio::join!(
+                pump(stdout, "stdout", &mut out, cap, &emit),
+                pump(stderr, "stderr", &mut err, cap, &emit),
+                child.wait(),
+            );
+            status
+        })
+        .await;
+
+        let (exit_code, timed_out) = match finished {
+            Ok(status) => (status.map_err(|e| format!("Failed to wait for command: {}", e))?.code(), false),
+            Err(_) => {
+                let _ = child.kill().await;
+                (None, true)
+            }
+        };
+        Ok(serde_json::json!({
+            "success": !timed_out && exit_code == Some(0),
+            "exit_code": exit_code,
+            "timed_out": timed_out,
+            "stdout": out.text,
+            "stderr": err.text,
+            "stdout_truncated": out.truncated,
+            "stderr_truncated": err.truncated,
+            "output_bytes": out.bytes + err.bytes,
+            "duration_ms": started.elapsed().as_millis() as u64,
+            "working_dir": working_dir.map(|dir| dir.to_string_lossy().to_string())
+        }))
+    }
+}
+
+#[async_trait]
+impl ComputerUseTool for RunCommandTool {
+    fn name(&self) -> &str { "run_command" }
+
+    fn description(&self) -> String {
+        "Run a shell command whose programs are on the allowlist (always asks for approval)".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Critical }
+
+    fn always_requires_approval(&self) -> bool { true }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "Command line to run in the system shell"
+                },
+                "working_dir": {
+                    "type": "string",
+                    "description": "Folder to run in; defaults to the first allowed folder"
+                },
+                "timeout_seconds": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_TIMEOUT_SECONDS,
+                    "description": "Stop the command after this long; capped by the session's policy"
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: RunCommandParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for run_command: {}", e))?;
+
+        log::info!("Session {}: Running command: {}", session_id, params.command);
+
+        let outcome = self.run(params, session_id).await;
+        // A command that ran but failed still reports its output
+        let mut result = tool_result(self.name(), start_time, outcome);
+        if result.success && !result.result["success"].as_bool().unwrap_or(false) {
+            result.success = false;
+            result.error = Some(match result.result["exit_code"].as_i64() {
+                _ if result.result["timed_out"].as_bool().unwrap_or(false) => "Command timed out".to_string(),
+                Some(code) => format!("Command exited with status {}", code),
+                None => "Command was terminated".to_string(),
+            });
+        }
+        Ok(result)
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_checks_every_program_in_the_line() {
+        let policy = ShellCommandPolicy {
+            allowlist: vec!["git".to_string(), "ls".to_string(), "grep".to_string(), "GIT_PAGER=cat".to_string()],
+            denylist: vec!["push --force".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.check("git status").is_ok());
+        assert!(policy.check("ls -la | grep notes && GIT_PAGER=cat git log -1").is_ok());
+        assert!(policy.check("git.exe status").is_ok());
+        assert!(policy.check("ls; curl http://example.com").is_err());
+        assert!(policy.check("ls $(whoami)").is_err());
+        assert!(policy.check("echo `id`").is_err());
+        assert!(policy.check("   ").is_err());
+        // Only the programs PATH finds, not lookalikes at a path
+        assert!(policy.check("./git status").is_err());
+        assert!(policy.check("/tmp/x/git status").is_err());
+        assert!(policy.check("ls && \"/tmp/x/grep\" notes").is_err());
+        assert!(policy.check("..\\tools\\git.exe status").is_err());
+        // Assignments that change what a program runs need their own entry
+        assert!(policy.check("LD_PRELOAD=/tmp/evil.so ls").is_err());
+        assert!(policy.check("PATH=/tmp ls").is_err());
+        assert!(policy.check("GIT_PAGER=cat PATH=/tmp git log").is_err());
+        // Redirection would read or write files outside the sandbox
+        assert!(policy.check("ls > /etc/profile").is_err());
+        assert!(policy.check("ls >> ~/.bashrc").is_err());
+        assert!(policy.check("grep key < ~/.ssh/id_rsa").is_err());
+        // The denylist applies even to allowed programs
+        assert!(policy.check("git push --force").is_err());
+
+        assert!(ShellCommandPolicy::default().check("ls").is_err());
+        assert!(ShellCommandPolicy { allowlist: vec!["*".to_string()], ..Default::default() }.check("curl example.com").is_ok());
+
+        let mut captured = CapturedOutput::default();
+        captured.push("héllo\n", 3);
+        captured.push("more\n", 3);
+        assert_eq!((captured.text.as_str(), captured.truncated, captured.bytes), ("hé", true, 12));
+    }
+}
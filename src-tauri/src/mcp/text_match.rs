@@ -0,0 +1,225 @@
+// src-tauri/src/mcp/text_match.rs
+// Matching OCR words against the text a tool looks for. Exact matching keeps the old behavior:
+// a word containing the text. Fuzzy matching forgives what OCR gets wrong on buttons - "Sign  ln"
+// for "Sign in", "Sign-in", "Résumé" read as "Resume" - by comparing normalized text (no
+// punctuation or diacritics, single spaces) by normalized Levenshtein similarity.
+//
+// OCR engines report single words, so fuzzy matching joins runs of neighbouring words on a line
+// and compares those too, which lets multi-word targets match at all; the match's box covers the
+// whole run.
+use serde::{Deserialize, Serialize};
+
+use crate::mcp::ocr::RecognizedWord;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextMatchOptions {
+    pub case_sensitive: bool,
+    pub fuzzy: bool,
+    /// 0.0 - 1.0; how similar fuzzy matches must be
+    pub similarity_threshold: f64,
+}
+
+impl Default for TextMatchOptions {
+    fn default() -> Self {
+        Self { case_sensitive: false, fuzzy: false, similarity_threshold: 0.8 }
+    }
+}
+
+/// The letter without its accent, for the Latin letters OCR most often gets half right
+fn fold_diacritic(ch: char) -> char {
+    match ch {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => 'e',
+        'ğ' => 'g',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => 'i',
+        'ĺ' | 'ľ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => 'o',
+        'ŕ' | 'ř' => 'r',
+        'ś' | 'š' | 'ş' | 'ș' => 's',
+        'ť' | 'ţ' | 'ț' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        other => other,
+    }
+}
+
+/// Text as fuzzy matching compares it
+pub(crate) fn normalize(text: &str, case_sensitive: bool) -> String {
+    let folded: String = text
+        .chars()
+        .map(|ch| {
+            let lower = ch.to_lowercase().next().unwrap_or(ch);
+            let folded = fold_diacritic(lower);
+            if case_sensitive && ch.is_uppercase() {
+                folded.to_uppercase().next().unwrap_or(folded)
+            } else if case_sensitive {
+                if folded == lower { ch } else { folded }
+            } else {
+                folded
+            }
+        })
+        .filter(|ch| ch.is_alphanumeric() || ch.is_whitespace())
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// 1.0 for equal strings down to 0.0; spacing is ignored, since OCR splits and joins words freely
+pub(crate) fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().filter(|ch| !ch.is_whitespace()).collect();
+    let b: Vec<char> = b.chars().filter(|ch| !ch.is_whitespace()).collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / longest as f64
+}
+
+/// Words grouped into lines, each left to right
+pub(crate) fn lines(words: &[RecognizedWord]) -> Vec<Vec<&RecognizedWord>> {
+    let mut sorted: Vec<&RecognizedWord> = words.iter().collect();
+    sorted.sort_by_key(|word| (word.y + word.height / 2, word.x));
+    let mut lines: Vec<Vec<&RecognizedWord>> = Vec::new();
+    for word in sorted {
+        let center = word.y + word.height / 2;
+        // Same line when the word's middle is level with the line's last word
+        match lines.iter_mut().find(|line| line.last().is_some_and(|last| center >= last.y && center <= last.y + last.height)) {
+            Some(line) => line.push(word),
+            None => lines.push(vec![word]),
+        }
+    }
+    for line in &mut lines {
+        line.sort_by_key(|word| word.x);
+    }
+    lines
+}
+
+/// A run of words as one word, its box covering them all
+fn join(run: &[&RecognizedWord]) -> RecognizedWord {
+    let left = run.iter().map(|word| word.x).min().unwrap_or(0);
+    let top = run.iter().map(|word| word.y).min().unwrap_or(0);
+    let right = run.iter().map(|word| word.x + word.width).max().unwrap_or(0);
+    let bottom = run.iter().map(|word| word.y + word.height).max().unwrap_or(0);
+    RecognizedWord {
+        text: run.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" "),
+        confidence: run.iter().map(|word| word.confidence).fold(1.0, f32::min),
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    }
+}
+
+/// Words (or runs of words) matching `target`, with their similarity; best first for fuzzy matches
+pub(crate) fn find_matches(
+    words: Vec<RecognizedWord>,
+    target: &str,
+    confidence_threshold: f64,
+    options: &TextMatchOptions,
+) -> Vec<(RecognizedWord, f64)> {
+    if !options.fuzzy {
+        let search_text = if options.case_sensitive { target.to_string() } else { target.to_lowercase() };
+        return words
+            .into_iter()
+            .filter(|word| {
+                let found_text = if options.case_sensitive { word.text.clone() } else { word.text.to_lowercase() };
+                found_text.contains(&search_text) && word.confidence >= confidence_threshold as f32
+            })
+            .map(|word| (word, 1.0))
+            .collect();
+    }
+
+    let target = normalize(target, options.case_sensitive);
+    if target.is_empty() {
+        return Vec::new();
+    }
+    let target_words = target.split(' ').count();
+    // (line, first word, word count, similarity)
+    let lines = lines(&words);
+    let mut candidates: Vec<(usize, usize, usize, f64)> = Vec::new();
+    for (line_index, line) in lines.iter().enumerate() {
+        for start in 0..line.len() {
+            for count in target_words.saturating_sub(1).max(1)..=target_words + 1 {
+                let Some(run) = line.get(start..start + count) else { break };
+                if run.iter().any(|word| word.confidence < confidence_threshold as f32) {
+                    continue;
+                }
+                let text = normalize(&run.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" "), options.case_sensitive);
+                // A single word containing the text matched before fuzzy matching, so it still does
+                let score = if count == 1 && text.contains(&target) { 1.0 } else { similarity(&text, &target) };
+                if score >= options.similarity_threshold {
+                    candidates.push((line_index, start, count, score));
+                }
+            }
+        }
+    }
+
+    // Best first, tighter runs before looser ones; a word belongs to one match at most
+    candidates.sort_by(|a, b| b.3.partial_cmp(&a.3).unwrap_or(std::cmp::Ordering::Equal).then(a.2.cmp(&b.2)));
+    let mut used: Vec<(usize, usize)> = Vec::new();
+    let mut matches = Vec::new();
+    for (line_index, start, count, score) in candidates {
+        if (start..start + count).any(|index| used.contains(&(line_index, index))) {
+            continue;
+        }
+        used.extend((start..start + count).map(|index| (line_index, index)));
+        matches.push((join(&lines[line_index][start..start + count]), score));
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, x: i32, y: i32) -> RecognizedWord {
+        RecognizedWord { text: text.to_string(), confidence: 0.95, x, y, width: 10 * text.len() as i32, height: 20 }
+    }
+
+    #[test]
+    fn test_fuzzy_matching_forgives_ocr_mistakes() {
+        assert_eq!(normalize("  Sign-in,   Résumé! ", false), "signin resume");
+        assert_eq!(normalize("Écran", true), "Ecran");
+        assert!((similarity("sign ln", "sign in") - 5.0 / 6.0).abs() < 1e-9);
+        assert_eq!(similarity("signin", "sign in"), 1.0);
+
+        // "Sign ln" on one line, "Cancel" and "in" elsewhere
+        let words = vec![
+            word("Sign", 100, 50),
+            word("ln", 145, 52),
+            word("Cancel", 300, 50),
+            word("in", 100, 200),
+        ];
+        let exact = TextMatchOptions::default();
+        assert!(find_matches(words.clone(), "Sign in", 0.8, &exact).is_empty());
+        assert_eq!(find_matches(words.clone(), "cancel", 0.8, &exact).len(), 1);
+
+        let fuzzy = TextMatchOptions { fuzzy: true, ..TextMatchOptions::default() };
+        let matches = find_matches(words.clone(), "Sign in", 0.8, &fuzzy);
+        assert_eq!(matches.len(), 1);
+        let (found, score) = &matches[0];
+        assert_eq!((found.text.as_str(), found.x, found.y, found.width, found.height), ("Sign ln", 100, 50, 65, 22));
+        assert!(*score > 0.8 && *score < 1.0);
+
+        // Too different, or below the OCR confidence threshold
+        assert!(find_matches(words.clone(), "Sign out", 0.8, &fuzzy).is_empty());
+        assert!(find_matches(words, "Sign in", 0.99, &fuzzy).is_empty());
+    }
+}
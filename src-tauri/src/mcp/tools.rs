@@ -1,7 +1,21 @@
 // src-tauri/src/mcp/tools.rs
 use async_trait::async_trait;
 use crate::mcp::types::*;
+use crate::mcp::clipboard_tools::{ClipboardReadTool, ClipboardWriteTool};
+use crate::mcp::drag_tool::DragTool;
+use crate::mcp::file_tools::{ListDirectoryTool, ReadFileTool, WriteFileTool};
+use crate::mcp::shell_tools::RunCommandTool;
+use crate::mcp::transcribe_tool::TranscribeAudioTool;
+use crate::mcp::compare_tool::CompareScreenshotsTool;
+use crate::mcp::text_match::{find_matches, TextMatchOptions};
+use crate::mcp::ui_elements::UiElementsTool;
+use crate::mcp::wait_tool::WaitForTool;
+use crate::mcp::window_tools::{FocusWindowTool, MoveResizeWindowTool, WindowListTool};
+use std::collections::HashMap;
 use std::time::Instant;
+use crate::action_registry::{descriptor, param, to_value, ActionKind, RegisteredAction};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
 
 // Base trait for computer use tools
 #[async_trait]
@@ -10,13 +24,107 @@ pub trait ComputerUseTool: Send + Sync {
     fn description(&self) -> String;
     fn danger_level(&self) -> DangerLevel;
     fn requires_approval(&self) -> bool {
-        matches!(self.danger_level(), DangerLevel::Medium | DangerLevel::High | DangerLevel::Critical)
+        self.always_requires_approval() || matches!(self.danger_level(), DangerLevel::Medium | DangerLevel::High | DangerLevel::Critical)
+    }
+    /// Put every call to the user, even in sessions that skip approvals
+    fn always_requires_approval(&self) -> bool {
+        false
     }
     fn parameters_schema(&self) -> serde_json::Value;
     async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String>;
     fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync>;
 }
 
+/// A tool's outcome as a `ToolExecutionResult`, with the error repeated in `result` the way
+/// the built-in tools report failures
+pub(crate) fn tool_result(name: &str, start_time: Instant, outcome: Result<serde_json::Value, String>) -> ToolExecutionResult {
+    let execution_time_ms = start_time.elapsed().as_millis() as u64;
+    match outcome {
+        Ok(result) => ToolExecutionResult {
+            success: true,
+            result,
+            error: None,
+            execution_time_ms,
+            tool_name: name.to_string(),
+        },
+        Err(e) => ToolExecutionResult {
+            success: false,
+            result: serde_json::json!({"success": false, "error": e}),
+            error: Some(e),
+            execution_time_ms,
+            tool_name: name.to_string(),
+        },
+    }
+}
+
+/// Every computer use tool, keyed by name. Each MCP session gets its own set, and the
+/// action registry lists them for the command palette.
+pub fn builtin_tools() -> HashMap<String, Box<dyn ComputerUseTool + Send + Sync>> {
+    session_tools(&MCPSessionConfig::default(), None)
+}
+
+/// The built-in tools, with the file and shell tools held to the session's policies. Tools
+/// that stream output as they run emit it through `app_handle`.
+pub fn session_tools(
+    config: &MCPSessionConfig,
+    app_handle: Option<&tauri::AppHandle>,
+) -> HashMap<String, Box<dyn ComputerUseTool + Send + Sync>> {
+    let mut tools: HashMap<String, Box<dyn ComputerUseTool + Send + Sync>> = HashMap::new();
+    
+    // Register computer use tools
+    tools.insert("click".to_string(), Box::new(ClickTool));
+    tools.insert("type".to_string(), Box::new(TypeTool));
+    tools.insert("scroll".to_string(), Box::new(ScrollTool));
+    tools.insert("drag".to_string(), Box::new(DragTool));
+    tools.insert("key_press".to_string(), Box::new(KeyPressTool));
+    tools.insert("get_cursor_position".to_string(), Box::new(GetCursorPositionTool));
+    tools.insert("get_screen_info".to_string(), Box::new(GetScreenInfoTool));
+    tools.insert("take_screenshot".to_string(), Box::new(ScreenshotTool));
+    
+    // Register new atomic OCR tools
+    tools.insert("find_text".to_string(), Box::new(FindTextTool));
+    tools.insert("click_at".to_string(), Box::new(ClickAtTool));
+    tools.insert("debug_ocr".to_string(), Box::new(DebugOcrTool));
+    tools.insert("wait_for".to_string(), Box::new(WaitForTool));
+    tools.insert("compare_screenshots".to_string(), Box::new(CompareScreenshotsTool));
+    
+    // Register compound tools (require approval)
+    tools.insert("click_on_text".to_string(), Box::new(ClickOnTextTool));
+    tools.insert("click_and_type".to_string(), Box::new(ClickAndTypeTool));
+
+    // Register window management tools
+    tools.insert("list_windows".to_string(), Box::new(WindowListTool));
+    tools.insert("focus_window".to_string(), Box::new(FocusWindowTool));
+    tools.insert("move_resize_window".to_string(), Box::new(MoveResizeWindowTool));
+
+    // Register accessibility tree lookup
+    tools.insert("find_ui_elements".to_string(), Box::new(UiElementsTool));
+
+    // Register clipboard tools
+    tools.insert("clipboard_read".to_string(), Box::new(ClipboardReadTool));
+    tools.insert("clipboard_write".to_string(), Box::new(ClipboardWriteTool));
+
+    // Register file system tools
+    let policy = config.file_sandbox.clone();
+    tools.insert("read_file".to_string(), Box::new(ReadFileTool { policy: policy.clone() }));
+    tools.insert("write_file".to_string(), Box::new(WriteFileTool { policy: policy.clone() }));
+    tools.insert("list_directory".to_string(), Box::new(ListDirectoryTool { policy }));
+
+    // Register shell tool
+    tools.insert("run_command".to_string(), Box::new(RunCommandTool {
+        policy: config.shell.clone(),
+        sandbox: config.file_sandbox.clone(),
+        app_handle: app_handle.cloned(),
+    }));
+
+    // Register speech-to-text
+    tools.insert("transcribe_audio".to_string(), Box::new(TranscribeAudioTool { app_handle: app_handle.cloned() }));
+
+    // Tools of connected external MCP servers
+    tools.extend(crate::mcp::client::external_tools());
+    tools
+}
+
 // Click tool implementation
 #[derive(Clone)]
 pub struct ClickTool;
@@ -43,6 +151,11 @@ impl ComputerUseTool for ClickTool {
                     "type": "integer", 
                     "description": "Y coordinate (optional, uses current position if not provided)"
                 },
+                "monitor": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Index into get_screen_info's monitors; makes x/y relative to that monitor's top-left corner"
+                },
                 "button": {
                     "type": "string",
                     "enum": ["left", "right", "middle"],
@@ -60,8 +173,9 @@ impl ComputerUseTool for ClickTool {
             .map_err(|e| format!("Invalid parameters for click: {}", e))?;
         
         // Get current cursor position if not specified
-        let (click_x, click_y) = match (click_params.x, click_params.y) {
-            (Some(x), Some(y)) => (x, y),
+        let (click_x, click_y) = match (click_params.x, click_params.y, click_params.monitor) {
+            (Some(x), Some(y), Some(monitor)) => crate::coordinates::current_layout()?.from_monitor(monitor, x, y)?,
+            (Some(x), Some(y), None) => (x, y),
             _ => get_cursor_position()?,
         };
         
@@ -412,7 +526,7 @@ impl ComputerUseTool for GetScreenInfoTool {
     fn name(&self) -> &str { "get_screen_info" }
     
     fn description(&self) -> String {
-        "Get screen information (width, height, scale factor)".to_string()
+        "Get screen information: the primary monitor's width, height and scale factor, and every monitor's position on the desktop. Other tools' `monitor` parameter is an index into `monitors`".to_string()
     }
     
     fn danger_level(&self) -> DangerLevel { DangerLevel::Low }
@@ -495,7 +609,12 @@ impl ComputerUseTool for ScreenshotTool {
                         "width": {"type": "integer"},
                         "height": {"type": "integer"}
                     },
-                    "description": "Region to capture (full screen if not specified)"
+                    "description": "Region to capture in desktop coordinates (full screen if not specified)"
+                },
+                "monitor": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Monitor to capture when there's no region, an index into get_screen_info's monitors (primary if not specified)"
                 }
             }
         })
@@ -509,6 +628,7 @@ impl ComputerUseTool for ScreenshotTool {
                 format: Some("png".to_string()),
                 quality: Some(90),
                 region: None,
+                monitor: None,
             });
         
         log::info!("Session {}: Taking screenshot", session_id);
@@ -516,7 +636,7 @@ impl ComputerUseTool for ScreenshotTool {
         let result = if let Some(region) = screenshot_params.region {
             take_screenshot_region(region, screenshot_params.format, screenshot_params.quality).await
         } else {
-            take_screenshot_full(screenshot_params.format, screenshot_params.quality).await
+            take_screenshot_monitor(screenshot_params.monitor, screenshot_params.format, screenshot_params.quality).await
         };
         
         let execution_time = start_time.elapsed().as_millis() as u64;
@@ -554,15 +674,13 @@ impl ComputerUseTool for ScreenshotTool {
 #[cfg(target_os = "windows")]
 async fn perform_click(x: i32, y: i32, button: MouseButton) -> Result<(), String> {
     use winapi::um::winuser::{
-        SetCursorPos, mouse_event, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
+        mouse_event, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
         MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP
     };
     
+    crate::coordinates::move_cursor(x, y)?;
+    
     unsafe {
-        if SetCursorPos(x, y) == 0 {
-            return Err("Failed to move cursor".to_string());
-        }
-        
         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
         
         let (down_event, up_event) = match button {
@@ -581,17 +699,7 @@ async fn perform_click(x: i32, y: i32, button: MouseButton) -> Result<(), String
 
 #[cfg(target_os = "windows")]
 fn get_cursor_position() -> Result<(i32, i32), String> {
-    use winapi::um::winuser::GetCursorPos;
-    use winapi::shared::windef::POINT;
-    
-    unsafe {
-        let mut point = POINT { x: 0, y: 0 };
-        if GetCursorPos(&mut point) != 0 {
-            Ok((point.x, point.y))
-        } else {
-            Err("Failed to get cursor position".to_string())
-        }
-    }
+    crate::coordinates::cursor_position()
 }
 
 #[cfg(target_os = "windows")]
@@ -761,10 +869,7 @@ async fn perform_scroll(params: ScrollParams) -> Result<(), String> {
     
     // Move to position if specified
     if let (Some(x), Some(y)) = (params.x, params.y) {
-        use winapi::um::winuser::SetCursorPos;
-        unsafe {
-            let _ = SetCursorPos(x, y);
-        }
+        let _ = crate::coordinates::move_cursor(x, y);
     }
     
     let amount = params.amount.unwrap_or(3);
@@ -918,38 +1023,38 @@ async fn press_key(key: &str, modifiers: Vec<KeyModifier>) -> Result<(), String>
     Ok(())
 }
 
-#[cfg(target_os = "windows")]
+// The primary monitor's size and scale, plus the whole layout for multi-monitor setups
 fn get_screen_info() -> Result<ScreenInfo, String> {
-    use winapi::um::winuser::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
-    
-    unsafe {
-        let width = GetSystemMetrics(SM_CXSCREEN) as u32;
-        let height = GetSystemMetrics(SM_CYSCREEN) as u32;
-        
-        Ok(ScreenInfo {
-            width,
-            height,
-            scale_factor: 1.0, // Would need proper DPI detection
-        })
-    }
+    let layout = crate::coordinates::current_layout()?;
+    let primary = *layout.primary();
+    Ok(ScreenInfo {
+        width: primary.width,
+        height: primary.height,
+        scale_factor: primary.scale_factor as f64,
+        monitors: layout.monitors,
+    })
 }
 
-#[cfg(target_os = "windows")]
-async fn take_screenshot_full(_format: Option<String>, _quality: Option<u8>) -> Result<ScreenshotResult, String> {
+pub(crate) async fn take_screenshot_full(format: Option<String>, quality: Option<u8>) -> Result<ScreenshotResult, String> {
+    take_screenshot_monitor(None, format, quality).await
+}
+
+/// Screenshot of monitor `monitor` (an index into get_screen_info's monitors), the primary one when `None`
+pub(crate) async fn take_screenshot_monitor(monitor: Option<usize>, _format: Option<String>, _quality: Option<u8>) -> Result<ScreenshotResult, String> {
     // Use existing screenshot implementation from screenshot.rs
-    match crate::screenshot::capture_screenshot().await {
+    match crate::screenshot::capture_screenshot(monitor).await {
         Ok(result) => Ok(ScreenshotResult {
             image_base64: result.image_base64,
             width: result.width,
             height: result.height,
             format: result.format,
+            mapping: result.mapping,
         }),
         Err(e) => Err(e),
     }
 }
 
-#[cfg(target_os = "windows")]
-async fn take_screenshot_region(region: ScreenRegion, _format: Option<String>, _quality: Option<u8>) -> Result<ScreenshotResult, String> {
+pub(crate) async fn take_screenshot_region(region: ScreenRegion, _format: Option<String>, _quality: Option<u8>) -> Result<ScreenshotResult, String> {
     // Use existing screenshot implementation from screenshot.rs
     match crate::screenshot::capture_screenshot_area(region.x, region.y, region.width, region.height).await {
         Ok(result) => Ok(ScreenshotResult {
@@ -957,58 +1062,36 @@ async fn take_screenshot_region(region: ScreenRegion, _format: Option<String>, _
             width: result.width,
             height: result.height,
             format: result.format,
+            mapping: result.mapping,
         }),
         Err(e) => Err(e),
     }
 }
 
-// Fallback implementations for non-Windows platforms
+// macOS and Linux go through enigo (see input.rs)
 #[cfg(not(target_os = "windows"))]
 async fn perform_click(x: i32, y: i32, button: MouseButton) -> Result<(), String> {
-    log::info!("Simulated click at ({}, {}) with {:?} button - not implemented for this platform", x, y, button);
-    Ok(())
+    crate::mcp::input::click(x, y, button, 1).await
 }
 
 #[cfg(not(target_os = "windows"))]
 fn get_cursor_position() -> Result<(i32, i32), String> {
-    Ok((800, 600)) // Return center of screen as fallback
+    crate::mcp::input::cursor_position()
 }
 
 #[cfg(not(target_os = "windows"))]
 async fn type_text(text: &str, delay_ms: u64) -> Result<(), String> {
-    log::info!("Simulated typing: '{}' - not implemented for this platform", text);
-    Ok(())
+    crate::mcp::input::type_text(text, delay_ms).await
 }
 
 #[cfg(not(target_os = "windows"))]
 async fn perform_scroll(params: ScrollParams) -> Result<(), String> {
-    log::info!("Simulated scroll {:?} - not implemented for this platform", params.direction);
-    Ok(())
-}
-
-#[cfg(not(target_os = "windows"))]
-async fn press_key(_key: &str, _modifiers: Vec<KeyModifier>) -> Result<(), String> {
-    log::info!("Simulated key press: '{}' with modifiers: {:?} - not implemented for this platform", _key, _modifiers);
-    Ok(())
-}
-
-#[cfg(not(target_os = "windows"))]
-fn get_screen_info() -> Result<ScreenInfo, String> {
-    Ok(ScreenInfo {
-        width: 1920,
-        height: 1080,
-        scale_factor: 1.0,
-    })
+    crate::mcp::input::scroll(params).await
 }
 
 #[cfg(not(target_os = "windows"))]
-async fn take_screenshot_full(_format: Option<String>, _quality: Option<u8>) -> Result<ScreenshotResult, String> {
-    Err("Screenshot not implemented for this platform".to_string())
-}
-
-#[cfg(not(target_os = "windows"))]
-async fn take_screenshot_region(_region: ScreenRegion, _format: Option<String>, _quality: Option<u8>) -> Result<ScreenshotResult, String> {
-    Err("Screenshot not implemented for this platform".to_string())
+async fn press_key(key: &str, modifiers: Vec<KeyModifier>) -> Result<(), String> {
+    crate::mcp::input::press_key(key, modifiers).await
 }
 
 // ========== NEW ATOMIC OCR TOOLS ==========
@@ -1043,6 +1126,38 @@ impl ComputerUseTool for FindTextTool {
                     "type": "boolean",
                     "default": false,
                     "description": "Whether to perform case-sensitive matching"
+                },
+                "language": {
+                    "type": "string",
+                    "description": "OCR language tag such as \"en-US\" or \"ja\"; defaults to the user's profile languages"
+                },
+                "region": {
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "integer" },
+                        "y": { "type": "integer" },
+                        "width": { "type": "integer" },
+                        "height": { "type": "integer" }
+                    },
+                    "required": ["x", "y", "width", "height"],
+                    "description": "Only search this part of the screen (desktop coordinates); faster than the whole screen"
+                },
+                "monitor": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Search this monitor instead of the primary one, an index into get_screen_info's monitors"
+                },
+                "fuzzy": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Forgive OCR mistakes, punctuation and accents, e.g. match \"Sign in\" when OCR reads \"Sign ln\""
+                },
+                "similarity_threshold": {
+                    "type": "number",
+                    "minimum": 0.0,
+                    "maximum": 1.0,
+                    "default": 0.8,
+                    "description": "How similar a fuzzy match must be"
                 }
             },
             "required": ["text"]
@@ -1055,13 +1170,30 @@ impl ComputerUseTool for FindTextTool {
         let text_to_find = params["text"].as_str()
             .ok_or("Missing required parameter: text")?;
         let confidence_threshold = params["confidence_threshold"].as_f64().unwrap_or(0.8);
-        let case_sensitive = params["case_sensitive"].as_bool().unwrap_or(false);
+        let matching = TextMatchOptions {
+            case_sensitive: params["case_sensitive"].as_bool().unwrap_or(false),
+            fuzzy: params["fuzzy"].as_bool().unwrap_or(false),
+            similarity_threshold: params["similarity_threshold"].as_f64().unwrap_or(0.8).clamp(0.0, 1.0),
+        };
+        let language = params["language"].as_str()
+            .map(super::ocr_languages::validate_language_tag)
+            .transpose()?;
+        let region: Option<ScreenRegion> = match &params["region"] {
+            serde_json::Value::Null => None,
+            region => Some(serde_json::from_value(region.clone()).map_err(|e| format!("Invalid region: {}", e))?),
+        };
         
-        // Take screenshot first
-        let screenshot_result = take_screenshot_full(Some("png".to_string()), Some(80)).await?;
+        let monitor = params["monitor"].as_u64().map(|index| index as usize);
+        
+        // Take screenshot first, of the region when there is one
+        let screenshot_result = match region {
+            Some(region) => take_screenshot_region(region, Some("png".to_string()), Some(80)).await?,
+            None => take_screenshot_monitor(monitor, Some("png".to_string()), Some(80)).await?,
+        };
         
-        // Perform OCR on the screenshot
-        let text_locations = find_text_in_image(&screenshot_result.image_base64, text_to_find, confidence_threshold, case_sensitive).await?;
+        // Perform OCR on the screenshot, then move the matches from image pixels to the desktop
+        let text_locations = find_text_in_image(&screenshot_result.image_base64, text_to_find, confidence_threshold, &matching, language.as_deref()).await?;
+        let text_locations = locations_on_desktop(text_locations, &screenshot_result.mapping);
         
         let execution_time = start_time.elapsed().as_millis() as u64;
         
@@ -1071,6 +1203,8 @@ impl ComputerUseTool for FindTextTool {
                 "text_locations": text_locations,
                 "search_text": text_to_find,
                 "confidence_threshold": confidence_threshold,
+                "fuzzy": matching.fuzzy,
+                "language": language,
                 "matches_found": text_locations.len()
             }),
             error: None,
@@ -1103,11 +1237,16 @@ impl ComputerUseTool for ClickAtTool {
             "properties": {
                 "x": {
                     "type": "integer",
-                    "description": "X coordinate to click"
+                    "description": "Desktop X coordinate to click, as find_text and get_screen_info report"
                 },
                 "y": {
                     "type": "integer",
-                    "description": "Y coordinate to click"
+                    "description": "Desktop Y coordinate to click, as find_text and get_screen_info report"
+                },
+                "monitor": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Index into get_screen_info's monitors; makes x/y relative to that monitor's top-left corner"
                 },
                 "button": {
                     "type": "string",
@@ -1130,6 +1269,10 @@ impl ComputerUseTool for ClickAtTool {
         
         let x = params["x"].as_i64().ok_or("Missing required parameter: x")? as i32;
         let y = params["y"].as_i64().ok_or("Missing required parameter: y")? as i32;
+        let (x, y) = match params["monitor"].as_u64() {
+            Some(monitor) => crate::coordinates::current_layout()?.from_monitor(monitor as usize, x, y)?,
+            None => (x, y),
+        };
         let button = params["button"].as_str().unwrap_or("left");
         let double_click = params["double_click"].as_bool().unwrap_or(false);
         
@@ -1185,6 +1328,15 @@ impl ComputerUseTool for ClickOnTextTool {
                     "default": 0.8,
                     "description": "Minimum confidence level for text recognition"
                 },
+                "fuzzy": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Forgive OCR mistakes in the text, as find_text does"
+                },
+                "region": {
+                    "type": "object",
+                    "description": "Only search this part of the screen, as find_text does"
+                },
                 "button": {
                     "type": "string",
                     "enum": ["left", "right", "middle"],
@@ -1312,6 +1464,7 @@ impl ComputerUseTool for DebugOcrTool {
         
         // Perform OCR to get all text on screen
         let all_text_locations = debug_ocr_scan(&screenshot_result.image_base64, confidence_threshold, show_all).await?;
+        let all_text_locations = locations_on_desktop(all_text_locations, &screenshot_result.mapping);
         
         let execution_time = start_time.elapsed().as_millis() as u64;
         
@@ -1411,6 +1564,11 @@ impl ComputerUseTool for ClickAndTypeTool {
                     "default": 0.8,
                     "description": "Minimum confidence level for text recognition"
                 },
+                "fuzzy": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Forgive OCR mistakes in click_target, as find_text does"
+                },
                 "press_enter": {
                     "type": "boolean",
                     "default": false,
@@ -1449,6 +1607,9 @@ impl ComputerUseTool for ClickAndTypeTool {
         let click_params = serde_json::json!({
             "text": click_target,
             "confidence_threshold": confidence_threshold,
+            "fuzzy": params["fuzzy"],
+            "similarity_threshold": params["similarity_threshold"],
+            "region": params["region"],
             "button": "left"
         });
         
@@ -1475,8 +1636,9 @@ impl ComputerUseTool for ClickAndTypeTool {
         
         // Step 2: Clear existing text if requested
         if clear_existing {
-            // Ctrl+A to select all, then Delete to clear
-            let select_all_result = press_key("a", vec![KeyModifier::Ctrl]).await;
+            // Select all (Cmd+A on macOS, Ctrl+A elsewhere), then Delete to clear
+            let select_all = if cfg!(target_os = "macos") { KeyModifier::Meta } else { KeyModifier::Ctrl };
+            let select_all_result = press_key("a", vec![select_all]).await;
             if let Err(e) = select_all_result {
                 log::warn!("Failed to select all text: {}", e);
             } else {
@@ -1543,301 +1705,213 @@ impl ComputerUseTool for ClickAndTypeTool {
 // ========== OCR HELPER FUNCTIONS ==========
 
 #[derive(serde::Serialize, serde::Deserialize)]
-struct TextLocation {
-    text: String,
-    confidence: f32,
-    bounding_box: TextBoundingBox,
-    center_x: i32,
-    center_y: i32,
+pub(crate) struct TextLocation {
+    pub text: String,
+    pub confidence: f32,
+    pub bounding_box: TextBoundingBox,
+    pub center_x: i32,
+    pub center_y: i32,
+    /// How close a fuzzy match came to the searched text, 0.0 - 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
-struct TextBoundingBox {
-    x: i32,
-    y: i32,
-    width: i32,
-    height: i32,
+pub(crate) struct TextBoundingBox {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+// OCR reports image pixels; clicks need desktop coordinates
+pub(crate) fn locations_on_desktop(locations: Vec<TextLocation>, mapping: &crate::coordinates::ImageMapping) -> Vec<TextLocation> {
+    locations
+        .into_iter()
+        .map(|location| {
+            let b = &location.bounding_box;
+            let (x, y, width, height) = mapping.rect_to_desktop(b.x, b.y, b.width, b.height);
+            let (center_x, center_y) = mapping.to_desktop(location.center_x, location.center_y);
+            TextLocation {
+                bounding_box: TextBoundingBox { x, y, width, height },
+                center_x,
+                center_y,
+                ..location
+            }
+        })
+        .collect()
 }
 
-async fn find_text_in_image(
+// OCR runs in the OCR worker process when isolation is on, so an engine crash can't end the app.
+// Either way it stops when the tool call's operation is cancelled.
+pub(crate) async fn find_text_in_image(
     base64_image: &str,
     target_text: &str,
     confidence_threshold: f64,
-    case_sensitive: bool,
+    matching: &TextMatchOptions,
+    language: Option<&str>,
 ) -> Result<Vec<TextLocation>, String> {
-    #[cfg(target_os = "windows")]
-    {
-        windows_ocr_find_text(base64_image, target_text, confidence_threshold, case_sensitive).await
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("OCR is only supported on Windows currently".to_string())
-    }
+    if crate::worker_process::is_isolated(crate::worker_process::WorkerKind::Ocr) {
+        let locations = crate::worker_process::ocr(crate::worker_process::protocol::WorkerRequest::FindText {
+            image_base64: base64_image.to_string(),
+            target_text: target_text.to_string(),
+            confidence_threshold,
+            case_sensitive: matching.case_sensitive,
+            fuzzy: matching.fuzzy,
+            similarity_threshold: Some(matching.similarity_threshold),
+            language: language.map(str::to_string),
+        }).await?;
+        return serde_json::from_value(locations).map_err(|e| format!("Invalid OCR worker result: {}", e));
+    }
+    crate::cancellation::cancellable(find_text_locally(base64_image, target_text, confidence_threshold, matching, language)).await
 }
 
-async fn debug_ocr_scan(
+pub(crate) async fn debug_ocr_scan(
     base64_image: &str,
     confidence_threshold: f64,
     show_all: bool,
 ) -> Result<Vec<TextLocation>, String> {
-    #[cfg(target_os = "windows")]
-    {
-        windows_ocr_debug_scan(base64_image, confidence_threshold, show_all).await
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("OCR is only supported on Windows currently".to_string())
-    }
+    if crate::worker_process::is_isolated(crate::worker_process::WorkerKind::Ocr) {
+        let locations = crate::worker_process::ocr(crate::worker_process::protocol::WorkerRequest::ScanText {
+            image_base64: base64_image.to_string(),
+            confidence_threshold,
+            show_all,
+        }).await?;
+        return serde_json::from_value(locations).map_err(|e| format!("Invalid OCR worker result: {}", e));
+    }
+    crate::cancellation::cancellable(scan_text_locally(base64_image, confidence_threshold, show_all)).await
 }
 
-#[cfg(target_os = "windows")]
-async fn windows_ocr_find_text(
+/// All the text in an image (PNG or JPEG bytes), line by line in reading order - for documents
+/// and photos rather than screens
+pub(crate) async fn ocr_image_text(image: &[u8]) -> Result<String, String> {
+    use base64::Engine;
+
+    let base64_image = base64::engine::general_purpose::STANDARD.encode(image);
+    let words: Vec<super::ocr::RecognizedWord> = debug_ocr_scan(&base64_image, 0.0, true)
+        .await?
+        .into_iter()
+        .map(|location| super::ocr::RecognizedWord {
+            text: location.text,
+            confidence: location.confidence,
+            x: location.bounding_box.x,
+            y: location.bounding_box.y,
+            width: location.bounding_box.width,
+            height: location.bounding_box.height,
+        })
+        .collect();
+    Ok(super::text_match::lines(&words)
+        .iter()
+        .map(|line| line.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// OCR entry points for the OCR worker process
+pub(crate) async fn find_text_in_process(
     base64_image: &str,
     target_text: &str,
     confidence_threshold: f64,
-    case_sensitive: bool,
+    matching: &TextMatchOptions,
+    language: Option<&str>,
+) -> Result<serde_json::Value, String> {
+    let locations = find_text_locally(base64_image, target_text, confidence_threshold, matching, language).await?;
+    serde_json::to_value(locations).map_err(|e| e.to_string())
+}
+
+pub(crate) async fn scan_text_in_process(
+    base64_image: &str,
+    confidence_threshold: f64,
+    show_all: bool,
+) -> Result<serde_json::Value, String> {
+    let locations = scan_text_locally(base64_image, confidence_threshold, show_all).await?;
+    serde_json::to_value(locations).map_err(|e| e.to_string())
+}
+
+async fn find_text_locally(
+    base64_image: &str,
+    target_text: &str,
+    confidence_threshold: f64,
+    matching: &TextMatchOptions,
+    language: Option<&str>,
 ) -> Result<Vec<TextLocation>, String> {
-    use base64::Engine;
-    use windows::{
-        Media::Ocr::*,
-        Storage::Streams::*,
-        Graphics::Imaging::*,
-    };
-    
-    // Decode base64 image
-    let image_data = base64::engine::general_purpose::STANDARD
-        .decode(base64_image)
-        .map_err(|e| format!("Failed to decode base64 image: {}", e))?;
-    
-    // Create OCR engine
-    let ocr_engine = OcrEngine::TryCreateFromUserProfileLanguages()
-        .map_err(|e| format!("Failed to create OCR engine: {}", e))?;
-    
-    // Create memory stream from image data
-    let stream = InMemoryRandomAccessStream::new()
-        .map_err(|e| format!("Failed to create memory stream: {}", e))?;
-    
-    let writer = stream.GetOutputStreamAt(0)
-        .map_err(|e| format!("Failed to get output stream: {}", e))?;
-    
-    // Write image data to stream using DataWriter
-    let data_writer = windows::Storage::Streams::DataWriter::CreateDataWriter(&writer)
-        .map_err(|e| format!("Failed to create data writer: {}", e))?;
-    
-    data_writer.WriteBytes(&image_data)
-        .map_err(|e| format!("Failed to write bytes: {}", e))?;
-    
-    data_writer.StoreAsync()
-        .map_err(|e| format!("Failed to store data: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to complete store: {}", e))?;
-    
-    writer.FlushAsync()
-        .map_err(|e| format!("Failed to flush stream: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to complete flush: {}", e))?;
-    
-    // Create bitmap decoder
-    let decoder = BitmapDecoder::CreateAsync(&stream)
-        .map_err(|e| format!("Failed to create bitmap decoder: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to get bitmap decoder: {}", e))?;
-    
-    // Get software bitmap
-    let bitmap = decoder.GetSoftwareBitmapAsync()
-        .map_err(|e| format!("Failed to get software bitmap: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to complete bitmap operation: {}", e))?;
-    
-    // Perform OCR
-    let ocr_result = ocr_engine.RecognizeAsync(&bitmap)
-        .map_err(|e| format!("Failed to start OCR: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to complete OCR: {}", e))?;
-    
-    // Extract text and positions
-    let mut results = Vec::new();
-    let search_text = if case_sensitive { target_text.to_string() } else { target_text.to_lowercase() };
-    
-    let lines = ocr_result.Lines()
-        .map_err(|e| format!("Failed to get OCR lines: {}", e))?;
-    
-    for line in lines {
-        let words = line.Words()
-            .map_err(|e| format!("Failed to get line words: {}", e))?;
-        
-        for word in words {
-            let text = word.Text()
-                .map_err(|e| format!("Failed to get word text: {}", e))?
-                .to_string();
-            
-            let found_text = if case_sensitive { text.clone() } else { text.to_lowercase() };
-            
-            // Check if this word contains our target text
-            if found_text.contains(&search_text) {
-                let bounding_rect = word.BoundingRect()
-                    .map_err(|e| format!("Failed to get bounding rect: {}", e))?;
-                
-                // Windows OCR doesn't provide confidence per word, so we'll use a default high confidence
-                let confidence = 0.95_f32; // High confidence for Windows OCR
-                
-                if confidence >= confidence_threshold as f32 {
-                    let x = bounding_rect.X as i32;
-                    let y = bounding_rect.Y as i32;
-                    let width = bounding_rect.Width as i32;
-                    let height = bounding_rect.Height as i32;
-                    
-                    let center_x = x + width / 2;
-                    let center_y = y + height / 2;
-                    
-                    results.push(TextLocation {
-                        text: text.clone(),
-                        confidence,
-                        bounding_box: TextBoundingBox { x, y, width, height },
-                        center_x,
-                        center_y,
-                    });
-                }
-            }
-        }
+    let words = recognize_words(base64_image, language).await?;
+    let mut results: Vec<TextLocation> = find_matches(words, target_text, confidence_threshold, matching)
+        .into_iter()
+        .map(|(word, similarity)| TextLocation {
+            similarity: matching.fuzzy.then_some(similarity),
+            ..text_location(word)
+        })
+        .collect();
+    sort_locations(&mut results);
+    // Closest fuzzy match first; the sort is stable, so confidence still breaks ties
+    if matching.fuzzy {
+        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
     }
-    
-    // Sort by confidence (highest first) and then by position (top to bottom, left to right)
-    results.sort_by(|a, b| {
-        b.confidence.partial_cmp(&a.confidence)
-            .unwrap_or(std::cmp::Ordering::Equal)
-            .then_with(|| a.bounding_box.y.cmp(&b.bounding_box.y))
-            .then_with(|| a.bounding_box.x.cmp(&b.bounding_box.x))
-    });
-    
     Ok(results)
 }
 
-#[cfg(target_os = "windows")]
-async fn windows_ocr_debug_scan(
+async fn scan_text_locally(
     base64_image: &str,
     confidence_threshold: f64,
     show_all: bool,
 ) -> Result<Vec<TextLocation>, String> {
+    let words = recognize_words(base64_image, None).await?;
+    // Include all text if show_all is true, or only text above threshold
+    let mut results: Vec<TextLocation> = words
+        .into_iter()
+        .filter(|word| !word.text.trim().is_empty())
+        .filter(|word| show_all || word.confidence >= confidence_threshold as f32)
+        .map(text_location)
+        .collect();
+    sort_locations(&mut results);
+
+    log::info!("🔍 OCR Debug: Found {} text elements total", results.len());
+    for (i, result) in results.iter().take(10).enumerate() {
+        log::info!("  {}. \"{}\" at ({}, {}) confidence: {:.3}", 
+                  i + 1, result.text, result.center_x, result.center_y, result.confidence);
+    }
+    Ok(results)
+}
+
+async fn recognize_words(base64_image: &str, language: Option<&str>) -> Result<Vec<super::ocr::RecognizedWord>, String> {
     use base64::Engine;
-    use windows::{
-        Media::Ocr::*,
-        Storage::Streams::*,
-        Graphics::Imaging::*,
-    };
-    
-    // Decode base64 image
+
     let image_data = base64::engine::general_purpose::STANDARD
         .decode(base64_image)
         .map_err(|e| format!("Failed to decode base64 image: {}", e))?;
-    
-    // Create OCR engine
-    let ocr_engine = OcrEngine::TryCreateFromUserProfileLanguages()
-        .map_err(|e| format!("Failed to create OCR engine: {}", e))?;
-    
-    // Create memory stream from image data
-    let stream = InMemoryRandomAccessStream::new()
-        .map_err(|e| format!("Failed to create memory stream: {}", e))?;
-    
-    let writer = stream.GetOutputStreamAt(0)
-        .map_err(|e| format!("Failed to get output stream: {}", e))?;
-    
-    // Write image data to stream using DataWriter
-    let data_writer = windows::Storage::Streams::DataWriter::CreateDataWriter(&writer)
-        .map_err(|e| format!("Failed to create data writer: {}", e))?;
-    
-    data_writer.WriteBytes(&image_data)
-        .map_err(|e| format!("Failed to write bytes: {}", e))?;
-    
-    data_writer.StoreAsync()
-        .map_err(|e| format!("Failed to store data: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to complete store: {}", e))?;
-    
-    writer.FlushAsync()
-        .map_err(|e| format!("Failed to flush stream: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to complete flush: {}", e))?;
-    
-    // Create bitmap decoder
-    let decoder = BitmapDecoder::CreateAsync(&stream)
-        .map_err(|e| format!("Failed to create bitmap decoder: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to get bitmap decoder: {}", e))?;
-    
-    // Get software bitmap
-    let bitmap = decoder.GetSoftwareBitmapAsync()
-        .map_err(|e| format!("Failed to get software bitmap: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to complete bitmap operation: {}", e))?;
-    
-    // Perform OCR
-    let ocr_result = ocr_engine.RecognizeAsync(&bitmap)
-        .map_err(|e| format!("Failed to start OCR: {}", e))?
-        .get()
-        .map_err(|e| format!("Failed to complete OCR: {}", e))?;
-    
-    // Extract ALL text and positions (for debugging)
-    let mut results = Vec::new();
-    
-    let lines = ocr_result.Lines()
-        .map_err(|e| format!("Failed to get OCR lines: {}", e))?;
-    
-    for line in lines {
-        let words = line.Words()
-            .map_err(|e| format!("Failed to get line words: {}", e))?;
-        
-        for word in words {
-            let text = word.Text()
-                .map_err(|e| format!("Failed to get word text: {}", e))?
-                .to_string();
-            
-            if text.trim().is_empty() {
-                continue; // Skip empty text
-            }
-            
-            let bounding_rect = word.BoundingRect()
-                .map_err(|e| format!("Failed to get bounding rect: {}", e))?;
-            
-            // Windows OCR doesn't provide confidence per word, so we'll use a default high confidence
-            let confidence = 0.95_f32; // High confidence for Windows OCR
-            
-            // Include all text if show_all is true, or only text above threshold
-            if show_all || confidence >= confidence_threshold as f32 {
-                let x = bounding_rect.X as i32;
-                let y = bounding_rect.Y as i32;
-                let width = bounding_rect.Width as i32;
-                let height = bounding_rect.Height as i32;
-                
-                let center_x = x + width / 2;
-                let center_y = y + height / 2;
-                
-                results.push(TextLocation {
-                    text: text.clone(),
-                    confidence,
-                    bounding_box: TextBoundingBox { x, y, width, height },
-                    center_x,
-                    center_y,
-                });
-            }
-        }
+    // A screen that hasn't changed since the last scan gives the same bytes
+    let cache_key = super::ocr_cache::key(&image_data, language);
+    if let Some(words) = super::ocr_cache::get(&cache_key) {
+        log::debug!("OCR cache hit: {} words", words.len());
+        return Ok(words);
+    }
+    let engine = super::ocr::engine();
+    let words = engine.recognize(&image_data, language).await?;
+    log::debug!("{} OCR recognized {} words", engine.name(), words.len());
+    super::ocr_cache::store(cache_key, words.clone());
+    Ok(words)
+}
+
+fn text_location(word: super::ocr::RecognizedWord) -> TextLocation {
+    TextLocation {
+        center_x: word.x + word.width / 2,
+        center_y: word.y + word.height / 2,
+        bounding_box: TextBoundingBox { x: word.x, y: word.y, width: word.width, height: word.height },
+        confidence: word.confidence,
+        text: word.text,
+        similarity: None,
     }
-    
-    // Sort by confidence (highest first) and then by position (top to bottom, left to right)
+}
+
+// Sort by confidence (highest first) and then by position (top to bottom, left to right)
+fn sort_locations(results: &mut [TextLocation]) {
     results.sort_by(|a, b| {
         b.confidence.partial_cmp(&a.confidence)
             .unwrap_or(std::cmp::Ordering::Equal)
             .then_with(|| a.bounding_box.y.cmp(&b.bounding_box.y))
             .then_with(|| a.bounding_box.x.cmp(&b.bounding_box.x))
     });
-    
-    log::info!("🔍 OCR Debug: Found {} text elements total", results.len());
-    for (i, result) in results.iter().take(10).enumerate() {
-        log::info!("  {}. \"{}\" at ({}, {}) confidence: {:.3}", 
-                  i + 1, result.text, result.center_x, result.center_y, result.confidence);
-    }
-    
-    Ok(results)
 }
 
 async fn click_at_coordinates(x: i32, y: i32, button: &str, double_click: bool) -> Result<(), String> {
@@ -1849,18 +1923,23 @@ async fn click_at_coordinates(x: i32, y: i32, button: &str, double_click: bool)
     }
     #[cfg(not(target_os = "windows"))]
     {
-        Err("Click not implemented for this platform".to_string())
+        let button = match button {
+            "right" => MouseButton::Right,
+            "middle" => MouseButton::Middle,
+            _ => MouseButton::Left,
+        };
+        crate::mcp::input::click(x, y, button, if double_click { 2 } else { 1 }).await
     }
 }
 
 #[cfg(target_os = "windows")]
 async fn windows_click_at(x: i32, y: i32, button: &str, double_click: bool) -> Result<(), String> {
-    use winapi::um::winuser::{SetCursorPos, mouse_event, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP};
+    use winapi::um::winuser::{mouse_event, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP};
+    
+    // Move cursor to position
+    crate::coordinates::move_cursor(x, y)?;
     
     unsafe {
-        // Move cursor to position
-        SetCursorPos(x, y);
-        
         // Small delay to ensure cursor movement
         tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
         
@@ -1886,4 +1965,60 @@ async fn windows_click_at(x: i32, y: i32, button: &str, double_click: bool) -> R
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Command palette actions, one per tool. Params come from each tool's JSON schema, plus
+/// the MCP session that approves and logs the call.
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    let mut tools: Vec<_> = builtin_tools().into_iter().collect();
+    tools.sort_by(|a, b| a.0.cmp(&b.0));
+
+    tools
+        .into_iter()
+        .map(|(name, tool)| {
+            let schema = tool.parameters_schema();
+            let required: Vec<&str> = schema["required"]
+                .as_array()
+                .map(|names| names.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let mut params = vec![param("session_id", "string", true, "MCP session to run the tool in")];
+            if let Some(properties) = schema["properties"].as_object() {
+                for (key, property) in properties {
+                    params.push(param(
+                        key,
+                        property["type"].as_str().unwrap_or("string"),
+                        required.contains(&key.as_str()),
+                        property["description"].as_str().unwrap_or(""),
+                    ));
+                }
+            }
+            RegisteredAction {
+                descriptor: descriptor(&format!("tool.{}", name), ActionKind::Tool, &name, &tool.description(), params, &["mcp"]),
+                handler: |app, params| Box::pin(invoke_tool(app, params)),
+            }
+        })
+        .collect()
+}
+
+async fn invoke_tool(app_handle: AppHandle, mut params: Value) -> Result<Value, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::McpExecution)?;
+    let tool_name = params["__tool"].as_str().unwrap_or_default().to_string();
+    let session_id = params["session_id"]
+        .as_str()
+        .ok_or("session_id is required to run a tool")?
+        .to_string();
+    if let Some(object) = params.as_object_mut() {
+        object.remove("__tool");
+        object.remove("session_id");
+    }
+
+    let session = {
+        let sessions = app_handle.state::<crate::mcp::MCPSessionManager>();
+        let sessions = sessions.lock().await;
+        sessions
+            .get(&session_id)
+            .cloned()
+            .ok_or(format!("Session not found: {}", session_id))?
+    };
+    to_value(session.execute_tool(&tool_name, params).await?)
+}
@@ -0,0 +1,160 @@
+// src-tauri/src/mcp/transcribe_tool.rs
+// Audio transcription as a tool - runs the app's speech-to-text (local Whisper or the configured
+// provider) on audio a caller passes in, so plans and outside MCP clients can transcribe a clip
+// without going through the recording UI.
+//
+// Whisper wants 16kHz mono: the audio is either raw 16-bit little-endian PCM at that rate, or a
+// WAV file in that format. Other WAV formats are refused rather than resampled here.
+use async_trait::async_trait;
+use base64::Engine;
+use serde::Deserialize;
+use std::time::Instant;
+
+use crate::mcp::tools::{tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+
+const SAMPLE_RATE: u32 = 16_000;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscribeAudioParams {
+    /// Base64 of 16kHz mono 16-bit PCM, or of a WAV file in that format
+    pub audio_base64: String,
+    pub model: Option<String>,
+    pub language: Option<String>,
+}
+
+/// Raw little-endian PCM from `bytes`, unwrapping a WAV file if that's what it is
+fn pcm_bytes(bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    if !bytes.starts_with(b"RIFF") {
+        return Ok(bytes);
+    }
+    let reader = hound::WavReader::new(std::io::Cursor::new(bytes)).map_err(|e| format!("Failed to read WAV audio: {}", e))?;
+    let spec = reader.spec();
+    if spec.channels != 1 || spec.sample_rate != SAMPLE_RATE || spec.bits_per_sample != 16 || spec.sample_format != hound::SampleFormat::Int {
+        return Err(format!(
+            "WAV audio must be 16kHz mono 16-bit; got {} Hz, {} channels, {} bits",
+            spec.sample_rate, spec.channels, spec.bits_per_sample
+        ));
+    }
+    reader
+        .into_samples::<i16>()
+        .map(|sample| sample.map(i16::to_le_bytes))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|samples| samples.concat())
+        .map_err(|e| format!("Failed to read WAV audio: {}", e))
+}
+
+#[derive(Clone)]
+pub struct TranscribeAudioTool {
+    pub app_handle: Option<tauri::AppHandle>,
+}
+
+impl TranscribeAudioTool {
+    async fn transcribe(&self, params: TranscribeAudioParams) -> Result<serde_json::Value, String> {
+        let app_handle = self.app_handle.clone().ok_or("Transcription needs the app to be running")?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(params.audio_base64.trim())
+            .map_err(|e| format!("Failed to decode audio: {}", e))?;
+        let pcm = pcm_bytes(bytes)?;
+        if pcm.len() < 2 {
+            return Err("No audio to transcribe".to_string());
+        }
+        let duration_ms = pcm.len() as u64 / 2 * 1000 / SAMPLE_RATE as u64;
+
+        let config = crate::speech::WhisperModelConfig {
+            modelSize: params.model.unwrap_or_else(|| "small".to_string()),
+            language: params.language.clone(),
+            enableVad: false,
+            silenceThreshold: 0.01,
+            maxSegmentLength: 30,
+            source: Some("mcp".to_string()),
+            deviceId: None,
+            languages: params.language.into_iter().collect(),
+            operationId: None,
+        };
+        let audio_base64 = base64::engine::general_purpose::STANDARD.encode(&pcm);
+        let transcription = crate::speech::transcribe_audio_base64(app_handle, audio_base64, config).await?;
+        Ok(serde_json::json!({
+            "success": true,
+            "text": transcription.text.trim(),
+            "language": transcription.language,
+            "confidence": transcription.confidence,
+            "segments": transcription.segments,
+            "duration_ms": duration_ms,
+            "message": format!("Transcribed {} ms of audio", duration_ms)
+        }))
+    }
+}
+
+#[async_trait]
+impl ComputerUseTool for TranscribeAudioTool {
+    fn name(&self) -> &str { "transcribe_audio" }
+
+    fn description(&self) -> String {
+        "Transcribe speech from 16kHz mono 16-bit audio (raw PCM or WAV, base64-encoded)".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Low }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "audio_base64": {
+                    "type": "string",
+                    "description": "Base64 of 16kHz mono 16-bit little-endian PCM, or of a WAV file in that format"
+                },
+                "model": {
+                    "type": "string",
+                    "default": "small",
+                    "description": "Whisper model size, e.g. tiny, base, small"
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Language code such as \"en\"; detected when left out"
+                }
+            },
+            "required": ["audio_base64"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: TranscribeAudioParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for transcribe_audio: {}", e))?;
+
+        log::info!("Session {}: Transcribing {} bytes of base64 audio", session_id, params.audio_base64.len());
+
+        let outcome = self.transcribe(params).await;
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(sample_rate: u32, channels: u16) -> Vec<u8> {
+        let spec = hound::WavSpec { channels, sample_rate, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for sample in [1i16, -2, 300] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_wav_is_unwrapped_to_pcm() {
+        let raw = vec![1, 0, 2, 0];
+        assert_eq!(pcm_bytes(raw.clone()).unwrap(), raw);
+        assert_eq!(pcm_bytes(wav(16_000, 1)).unwrap(), [1i16, -2, 300].iter().flat_map(|s| s.to_le_bytes()).collect::<Vec<_>>());
+        assert!(pcm_bytes(wav(44_100, 1)).unwrap_err().contains("44100 Hz"));
+        assert!(pcm_bytes(wav(16_000, 2)).is_err());
+    }
+}
@@ -2,6 +2,8 @@
 use serde::{Deserialize, Serialize};
 use tokio::sync::oneshot;
 
+pub use crate::shared_types::tools::ToolExecutionResult;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MCPSessionConfig {
     pub require_approval: bool,
@@ -9,6 +11,17 @@ pub struct MCPSessionConfig {
     pub enable_logging: bool,
     pub server_name: String,
     pub server_version: String,
+    /// Where the file tools may read and write
+    #[serde(default)]
+    pub file_sandbox: FileSandboxPolicy,
+    /// Which commands `run_command` may run, and for how long
+    #[serde(default)]
+    pub shell: ShellCommandPolicy,
+    /// Which tools the session may run at all, checked before any approval prompt
+    #[serde(default)]
+    pub permissions: PermissionProfile,
+    #[serde(default)]
+    pub rate_limits: RateLimits,
 }
 
 impl Default for MCPSessionConfig {
@@ -19,10 +32,82 @@ impl Default for MCPSessionConfig {
             enable_logging: true,
             server_name: "enteract-mcp-server".to_string(),
             server_version: "1.0.0".to_string(),
+            file_sandbox: FileSandboxPolicy::default(),
+            shell: ShellCommandPolicy::default(),
+            permissions: PermissionProfile::default(),
+            rate_limits: RateLimits::default(),
         }
     }
 }
 
+/// Directories the file tools are confined to. With no roots every path is refused.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileSandboxPolicy {
+    pub allowed_roots: Vec<String>,
+    /// Refuse writes even inside the roots
+    pub read_only: bool,
+}
+
+/// What the shell tool may run. With an empty allowlist every command is refused; `*` allows
+/// any program, though the denylist still applies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellCommandPolicy {
+    /// Program names, without path or `.exe`, and exact `VAR=value` assignments allowed before them
+    pub allowlist: Vec<String>,
+    /// Case-insensitive substrings that refuse a command wherever they appear
+    pub denylist: Vec<String>,
+    pub timeout_seconds: u64,
+    /// Most bytes kept of each of stdout and stderr
+    pub max_output_bytes: usize,
+}
+
+impl Default for ShellCommandPolicy {
+    fn default() -> Self {
+        Self {
+            allowlist: Vec::new(),
+            denylist: [
+                "rm -rf /", "rm -rf ~", "--no-preserve-root", "mkfs", "dd if=", "shutdown", "reboot",
+                "format c:", "del /s", "rd /s", ":(){",
+            ]
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect(),
+            timeout_seconds: 30,
+            max_output_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Tools and danger levels a session is limited to. See `PermissionProfile::preset` for the
+/// named profiles; the default allows everything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionProfile {
+    pub name: String,
+    /// Tool names the session may run; `*` allows any tool
+    pub allowed_tools: Vec<String>,
+    /// Refused even when `allowed_tools` has them
+    pub denied_tools: Vec<String>,
+    pub allowed_danger_levels: Vec<DangerLevel>,
+}
+
+/// Most tool calls a session may make in any one minute; 0 turns a limit off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimits {
+    pub max_calls_per_minute: u32,
+    /// Clicks and drags, counted on top of the overall limit
+    pub max_clicks_per_minute: u32,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self { max_calls_per_minute: 60, max_clicks_per_minute: 30 }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolApprovalRequest {
     pub session_id: String,
@@ -31,6 +116,27 @@ pub struct ToolApprovalRequest {
     pub parameters: serde_json::Value,
     pub timestamp: String,
     pub danger_level: DangerLevel,
+    /// Why the content policy wants this call approved, when it does
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy_violations: Vec<crate::mcp::content_policy::PolicyViolation>,
+    /// What the call would do and how risky that is, in plain English
+    #[serde(default)]
+    pub risk_summary: String,
+    /// The screen around the point the call acts on, when it acts on one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<ApprovalContext>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalContext {
+    /// Desktop point the call acts on
+    pub target_x: i32,
+    pub target_y: i32,
+    /// Desktop area the screenshot shows
+    pub region: ScreenRegion,
+    pub screenshot_base64: Option<String>,
+    /// Text OCR found in the screenshot, in reading order
+    pub ocr_text: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,15 +165,6 @@ pub enum SessionStatus {
     Error(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ToolExecutionResult {
-    pub success: bool,
-    pub result: serde_json::Value,
-    pub error: Option<String>,
-    pub execution_time_ms: u64,
-    pub tool_name: String,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolInfo {
     pub name: String,
@@ -77,7 +174,7 @@ pub struct ToolInfo {
     pub parameters_schema: serde_json::Value,
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DangerLevel {
     Low,      // Reading data, getting cursor position
     Medium,   // Clicking, typing, scrolling
@@ -106,6 +203,63 @@ pub struct ToolStep {
     pub depends_on: Option<String>, // Previous step ID
     pub danger_level: DangerLevel,
     pub estimated_duration_ms: Option<u64>,
+    /// Name later steps use to read this step's result in `{{name.path}}` placeholders; the
+    /// step id works too
+    #[serde(default)]
+    pub save_as: Option<String>,
+    /// How often to retry a failed step, and how long to wait in between
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    /// Where to go once the step has failed every attempt; stops the plan when unset
+    #[serde(default)]
+    pub on_failure: Option<StepBranch>,
+    /// Where to go depending on whether the step's result mentions some text
+    #[serde(default)]
+    pub on_text_found: Option<TextBranch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first included
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    /// Each wait is this much longer than the one before
+    pub backoff_multiplier: f64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            backoff_multiplier: 2.0,
+            max_backoff_ms: 10_000,
+        }
+    }
+}
+
+/// What a plan does after a step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StepBranch {
+    /// Carry on with the next step
+    Continue,
+    /// End the plan here
+    Stop,
+    /// Jump to another step, earlier or later
+    GoTo { step_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextBranch {
+    /// Looked for, case-insensitively, anywhere in the step's result
+    pub text: String,
+    pub then: StepBranch,
+    /// When the text isn't there; carries on when unset
+    #[serde(default)]
+    pub otherwise: Option<StepBranch>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,6 +308,9 @@ pub struct ClickParams {
     pub x: Option<i32>,
     pub y: Option<i32>,
     pub button: Option<MouseButton>,
+    /// Makes x/y relative to this monitor (an index into get_screen_info's monitors)
+    #[serde(default)]
+    pub monitor: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -178,8 +335,12 @@ pub struct KeyPressParams {
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum MouseButton {
+    // The tool schemas advertise lowercase names
+    #[serde(alias = "left")]
     Left,
+    #[serde(alias = "right")]
     Right,
+    #[serde(alias = "middle")]
     Middle,
 }
 
@@ -210,6 +371,8 @@ pub struct ScreenInfo {
     pub width: u32,
     pub height: u32,
     pub scale_factor: f64,
+    /// Every monitor in desktop coordinates, the ones the input tools take
+    pub monitors: Vec<crate::coordinates::MonitorGeometry>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -217,9 +380,12 @@ pub struct ScreenshotParams {
     pub format: Option<String>, // "png", "jpeg"
     pub quality: Option<u8>,    // 1-100 for jpeg
     pub region: Option<ScreenRegion>,
+    /// Monitor to capture, an index into get_screen_info's monitors; the primary by default
+    #[serde(default)]
+    pub monitor: Option<usize>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScreenRegion {
     pub x: i32,
     pub y: i32,
@@ -233,4 +399,5 @@ pub struct ScreenshotResult {
     pub width: u32,
     pub height: u32,
     pub format: String,
+    pub mapping: crate::coordinates::ImageMapping,
 }
\ No newline at end of file
@@ -0,0 +1,538 @@
+// src-tauri/src/mcp/ui_elements.rs
+// UI element detection through the platform accessibility APIs - UI Automation on Windows,
+// AXUIElement on macOS - so the agent can find a control by role and name ("the Save button")
+// and click its centre, instead of relying only on OCR finding the right word on screen.
+//
+// Both trees are walked depth-first from a window (the foreground one unless another is picked)
+// down to `max_depth`, stopping after `max_elements`. Roles from both platforms are mapped onto
+// one set of names, and bounding boxes are desktop coordinates like every other input tool.
+// Linux would need AT-SPI over D-Bus, which isn't wired up yet.
+#![cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::mcp::tools::{tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+
+const MAX_DEPTH: u32 = 25;
+const MAX_ELEMENTS: usize = 2000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UiElement {
+    /// Platform-neutral role such as `button`, `text_field` or `menu_item`
+    pub role: String,
+    /// The platform's own role name, for roles that map to `other`
+    pub native_role: String,
+    pub name: String,
+    /// UIA AutomationId or AXIdentifier, when the app sets one
+    pub identifier: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub center_x: i32,
+    pub center_y: i32,
+    pub enabled: bool,
+    pub focused: bool,
+    /// 0 for the window itself
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UiElementQuery {
+    /// Window id from list_windows (Windows)
+    pub window_id: Option<i64>,
+    /// Application process id (macOS)
+    pub process_id: Option<i32>,
+    /// Only elements with this role
+    pub role: Option<String>,
+    /// Case-insensitive substring of the element's name
+    pub name: Option<String>,
+    pub max_depth: Option<u32>,
+    pub max_elements: Option<usize>,
+    /// Keep elements with no name and no size (layout containers); off by default
+    #[serde(default)]
+    pub include_unnamed: bool,
+}
+
+/// Windows control type id (UIA_*ControlTypeId) to a role name
+pub(crate) fn uia_role(control_type: i32) -> &'static str {
+    match control_type {
+        50000 => "button",
+        50002 => "checkbox",
+        50003 => "combo_box",
+        50004 => "text_field",
+        50005 => "link",
+        50006 => "image",
+        50007 => "list_item",
+        50008 => "list",
+        50009 => "menu",
+        50010 => "menu_bar",
+        50011 => "menu_item",
+        50012 => "progress_bar",
+        50013 => "radio_button",
+        50014 => "scroll_bar",
+        50015 => "slider",
+        50018 => "tab",
+        50019 => "tab_item",
+        50020 => "text",
+        50021 => "toolbar",
+        50023 => "tree",
+        50024 => "tree_item",
+        50026 => "group",
+        50028 | 50036 => "table",
+        50029 => "row",
+        50030 => "document",
+        50031 => "button",
+        50032 => "window",
+        _ => "other",
+    }
+}
+
+/// macOS AXRole to a role name
+pub(crate) fn ax_role(role: &str) -> &'static str {
+    match role {
+        "AXButton" | "AXMenuButton" | "AXPopUpButton" => "button",
+        "AXCheckBox" => "checkbox",
+        "AXComboBox" => "combo_box",
+        "AXTextField" | "AXTextArea" | "AXSearchField" | "AXSecureTextField" => "text_field",
+        "AXLink" => "link",
+        "AXImage" => "image",
+        "AXList" | "AXOutline" => "list",
+        "AXMenu" => "menu",
+        "AXMenuBar" => "menu_bar",
+        "AXMenuItem" | "AXMenuBarItem" => "menu_item",
+        "AXProgressIndicator" => "progress_bar",
+        "AXRadioButton" => "radio_button",
+        "AXScrollBar" => "scroll_bar",
+        "AXSlider" => "slider",
+        "AXTabGroup" => "tab",
+        "AXStaticText" => "text",
+        "AXToolbar" => "toolbar",
+        "AXGroup" | "AXSplitGroup" | "AXScrollArea" => "group",
+        "AXTable" => "table",
+        "AXRow" => "row",
+        "AXCell" => "cell",
+        "AXWebArea" => "document",
+        "AXWindow" | "AXSheet" | "AXDrawer" => "window",
+        _ => "other",
+    }
+}
+
+impl UiElementQuery {
+    fn matches(&self, element: &UiElement) -> bool {
+        if !self.include_unnamed && element.name.trim().is_empty() && (element.width <= 0 || element.height <= 0) {
+            return false;
+        }
+        if let Some(role) = self.role.as_deref().map(str::trim).filter(|role| !role.is_empty()) {
+            if !element.role.eq_ignore_ascii_case(role) && !element.native_role.eq_ignore_ascii_case(role) {
+                return false;
+            }
+        }
+        match self.name.as_deref().map(str::trim).filter(|name| !name.is_empty()) {
+            Some(name) => element.name.to_lowercase().contains(&name.to_lowercase()),
+            None => true,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn element(role: &str, native_role: String, name: String, identifier: Option<String>, bounds: (i32, i32, i32, i32), enabled: bool, focused: bool, depth: u32) -> UiElement {
+    let (x, y, width, height) = bounds;
+    UiElement {
+        role: role.to_string(),
+        native_role,
+        name,
+        identifier: identifier.filter(|identifier| !identifier.is_empty()),
+        x,
+        y,
+        width,
+        height,
+        center_x: x + width / 2,
+        center_y: y + height / 2,
+        enabled,
+        focused,
+        depth,
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+    use windows::Win32::UI::Accessibility::{CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationTreeWalker};
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    fn describe(node: &IUIAutomationElement, depth: u32) -> Option<UiElement> {
+        unsafe {
+            let control_type = node.CurrentControlType().ok()?.0;
+            let rect = node.CurrentBoundingRectangle().unwrap_or_default();
+            Some(element(
+                uia_role(control_type),
+                format!("UIA_{}", control_type),
+                node.CurrentName().map(|name| name.to_string()).unwrap_or_default(),
+                node.CurrentAutomationId().ok().map(|id| id.to_string()),
+                (rect.left, rect.top, rect.right - rect.left, rect.bottom - rect.top),
+                node.CurrentIsEnabled().map(|enabled| enabled.as_bool()).unwrap_or(true),
+                node.CurrentHasKeyboardFocus().map(|focused| focused.as_bool()).unwrap_or(false),
+                depth,
+            ))
+        }
+    }
+
+    fn walk(walker: &IUIAutomationTreeWalker, node: &IUIAutomationElement, depth: u32, query: &UiElementQuery, max_depth: u32, limit: usize, found: &mut Vec<UiElement>) {
+        if found.len() >= limit {
+            return;
+        }
+        if let Some(described) = describe(node, depth) {
+            if query.matches(&described) {
+                found.push(described);
+            }
+        }
+        if depth >= max_depth {
+            return;
+        }
+        // A missing child or sibling comes back as an error
+        let mut child = unsafe { walker.GetFirstChildElement(node) }.ok();
+        while let Some(current) = child {
+            if found.len() >= limit {
+                return;
+            }
+            walk(walker, &current, depth + 1, query, max_depth, limit, found);
+            child = unsafe { walker.GetNextSiblingElement(&current) }.ok();
+        }
+    }
+
+    pub fn elements(query: &UiElementQuery, max_depth: u32, limit: usize) -> Result<Vec<UiElement>, String> {
+        use winapi::shared::windef::DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2;
+        use winapi::um::winuser::SetThreadDpiAwarenessContext;
+
+        unsafe {
+            // Already initialized on this thread (in any mode) is fine
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            // Rects in physical pixels, matching the desktop coordinates the input tools use
+            let previous = SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+            let result = (|| {
+                let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+                    .map_err(|e| format!("UI Automation unavailable: {}", e))?;
+                let hwnd = match query.window_id {
+                    Some(id) => HWND(id as isize),
+                    None => GetForegroundWindow(),
+                };
+                let root = automation
+                    .ElementFromHandle(hwnd)
+                    .map_err(|e| format!("Failed to read the window's UI elements: {}", e))?;
+                let walker = automation
+                    .ControlViewWalker()
+                    .map_err(|e| format!("Failed to walk UI elements: {}", e))?;
+                let mut found = Vec::new();
+                walk(&walker, &root, 0, query, max_depth, limit, &mut found);
+                Ok(found)
+            })();
+            if !previous.is_null() {
+                SetThreadDpiAwarenessContext(previous);
+            }
+            result
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use std::ffi::c_void;
+
+    type CFTypeRef = *const c_void;
+    type CFStringRef = *const c_void;
+    type AXUIElementRef = *const c_void;
+
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const K_AX_VALUE_CG_POINT_TYPE: u32 = 1;
+    const K_AX_VALUE_CG_SIZE_TYPE: u32 = 2;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGPoint { x: f64, y: f64 }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGSize { width: f64, height: f64 }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithBytes(alloc: *const c_void, bytes: *const u8, length: isize, encoding: u32, external: u8) -> CFStringRef;
+        fn CFStringGetLength(string: CFStringRef) -> isize;
+        fn CFStringGetCString(string: CFStringRef, buffer: *mut i8, size: isize, encoding: u32) -> u8;
+        fn CFStringGetTypeID() -> usize;
+        fn CFBooleanGetTypeID() -> usize;
+        fn CFBooleanGetValue(boolean: CFTypeRef) -> u8;
+        fn CFArrayGetCount(array: CFTypeRef) -> isize;
+        fn CFArrayGetValueAtIndex(array: CFTypeRef, index: isize) -> CFTypeRef;
+        fn CFGetTypeID(value: CFTypeRef) -> usize;
+        fn CFRelease(value: CFTypeRef);
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> u8;
+        fn AXUIElementCreateApplication(pid: i32) -> AXUIElementRef;
+        fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+        fn AXUIElementCopyAttributeValue(element: AXUIElementRef, attribute: CFStringRef, value: *mut CFTypeRef) -> i32;
+        fn AXUIElementGetPid(element: AXUIElementRef, pid: *mut i32) -> i32;
+        fn AXValueGetValue(value: CFTypeRef, value_type: u32, out: *mut c_void) -> u8;
+    }
+
+    /// A CF object released when dropped
+    struct Owned(CFTypeRef);
+
+    impl Drop for Owned {
+        fn drop(&mut self) {
+            if !self.0.is_null() {
+                unsafe { CFRelease(self.0) };
+            }
+        }
+    }
+
+    fn cf_string(value: &str) -> Owned {
+        Owned(unsafe { CFStringCreateWithBytes(std::ptr::null(), value.as_ptr(), value.len() as isize, K_CF_STRING_ENCODING_UTF8, 0) })
+    }
+
+    fn attribute(node: AXUIElementRef, name: &str) -> Option<Owned> {
+        let name = cf_string(name);
+        let mut value: CFTypeRef = std::ptr::null();
+        let status = unsafe { AXUIElementCopyAttributeValue(node, name.0, &mut value) };
+        (status == 0 && !value.is_null()).then(|| Owned(value))
+    }
+
+    fn string_attribute(node: AXUIElementRef, name: &str) -> Option<String> {
+        let value = attribute(node, name)?;
+        unsafe {
+            if CFGetTypeID(value.0) != CFStringGetTypeID() {
+                return None;
+            }
+            // UTF-8 needs up to 4 bytes per UTF-16 unit, plus the terminator
+            let size = CFStringGetLength(value.0) * 4 + 1;
+            let mut buffer = vec![0i8; size as usize];
+            if CFStringGetCString(value.0, buffer.as_mut_ptr(), size, K_CF_STRING_ENCODING_UTF8) == 0 {
+                return None;
+            }
+            Some(std::ffi::CStr::from_ptr(buffer.as_ptr()).to_string_lossy().to_string())
+        }
+    }
+
+    fn bool_attribute(node: AXUIElementRef, name: &str) -> Option<bool> {
+        let value = attribute(node, name)?;
+        unsafe { (CFGetTypeID(value.0) == CFBooleanGetTypeID()).then(|| CFBooleanGetValue(value.0) != 0) }
+    }
+
+    fn bounds(node: AXUIElementRef) -> (i32, i32, i32, i32) {
+        let mut point = CGPoint::default();
+        let mut size = CGSize::default();
+        unsafe {
+            if let Some(value) = attribute(node, "AXPosition") {
+                AXValueGetValue(value.0, K_AX_VALUE_CG_POINT_TYPE, &mut point as *mut CGPoint as *mut c_void);
+            }
+            if let Some(value) = attribute(node, "AXSize") {
+                AXValueGetValue(value.0, K_AX_VALUE_CG_SIZE_TYPE, &mut size as *mut CGSize as *mut c_void);
+            }
+        }
+        (point.x.round() as i32, point.y.round() as i32, size.width.round() as i32, size.height.round() as i32)
+    }
+
+    fn describe(node: AXUIElementRef, depth: u32) -> UiElement {
+        let native_role = string_attribute(node, "AXRole").unwrap_or_default();
+        // Buttons carry their label in AXTitle, icons and fields often only in AXDescription
+        let name = string_attribute(node, "AXTitle")
+            .filter(|title| !title.is_empty())
+            .or_else(|| string_attribute(node, "AXDescription"))
+            .unwrap_or_default();
+        element(
+            ax_role(&native_role),
+            native_role.clone(),
+            name,
+            string_attribute(node, "AXIdentifier"),
+            bounds(node),
+            bool_attribute(node, "AXEnabled").unwrap_or(true),
+            bool_attribute(node, "AXFocused").unwrap_or(false),
+            depth,
+        )
+    }
+
+    fn walk(node: AXUIElementRef, depth: u32, query: &UiElementQuery, max_depth: u32, limit: usize, found: &mut Vec<UiElement>) {
+        if found.len() >= limit {
+            return;
+        }
+        let described = describe(node, depth);
+        if query.matches(&described) {
+            found.push(described);
+        }
+        if depth >= max_depth {
+            return;
+        }
+        let Some(children) = attribute(node, "AXChildren") else { return };
+        let count = unsafe { CFArrayGetCount(children.0) };
+        for index in 0..count {
+            if found.len() >= limit {
+                return;
+            }
+            // Borrowed from the array, which outlives the walk below
+            let child = unsafe { CFArrayGetValueAtIndex(children.0, index) };
+            walk(child, depth + 1, query, max_depth, limit, found);
+        }
+    }
+
+    fn frontmost_pid() -> Result<i32, String> {
+        let system = Owned(unsafe { AXUIElementCreateSystemWide() });
+        let application = attribute(system.0, "AXFocusedApplication").ok_or("No application has focus")?;
+        let mut pid = 0;
+        if unsafe { AXUIElementGetPid(application.0, &mut pid) } != 0 {
+            return Err("Failed to identify the focused application".to_string());
+        }
+        Ok(pid)
+    }
+
+    pub fn elements(query: &UiElementQuery, max_depth: u32, limit: usize) -> Result<Vec<UiElement>, String> {
+        if unsafe { AXIsProcessTrusted() } == 0 {
+            return Err("Reading UI elements needs Accessibility access; grant it in System Settings > Privacy & Security > Accessibility".to_string());
+        }
+        let pid = match query.process_id {
+            Some(pid) => pid,
+            None => frontmost_pid()?,
+        };
+        let application = Owned(unsafe { AXUIElementCreateApplication(pid) });
+        // Start from the focused window; an app without one is walked from the top
+        let window = attribute(application.0, "AXFocusedWindow");
+        let root = window.as_ref().map_or(application.0, |window| window.0);
+        let mut found = Vec::new();
+        walk(root, 0, query, max_depth, limit, &mut found);
+        Ok(found)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    pub fn elements(_query: &UiElementQuery, _max_depth: u32, _limit: usize) -> Result<Vec<UiElement>, String> {
+        Err("UI element detection isn't available on this platform yet".to_string())
+    }
+}
+
+#[derive(Clone)]
+pub struct UiElementsTool;
+
+#[async_trait]
+impl ComputerUseTool for UiElementsTool {
+    fn name(&self) -> &str { "find_ui_elements" }
+
+    fn description(&self) -> String {
+        "List UI elements (buttons, fields, menu items...) in a window through the accessibility API, with roles, names and positions".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Low }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "window_id": {
+                    "type": "integer",
+                    "description": "Window id from list_windows (Windows); defaults to the foreground window"
+                },
+                "process_id": {
+                    "type": "integer",
+                    "description": "Application process id (macOS); defaults to the focused application"
+                },
+                "role": {
+                    "type": "string",
+                    "description": "Only this role: button, checkbox, combo_box, text_field, link, menu_item, tab_item, list_item, text, ..."
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Part of the element's name, case-insensitive"
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_DEPTH,
+                    "default": 12
+                },
+                "max_elements": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_ELEMENTS,
+                    "default": 200
+                },
+                "include_unnamed": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Also list unnamed, zero-size layout containers"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let query: UiElementQuery = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for find_ui_elements: {}", e))?;
+        let max_depth = query.max_depth.unwrap_or(12).clamp(1, MAX_DEPTH);
+        let limit = query.max_elements.unwrap_or(200).clamp(1, MAX_ELEMENTS);
+
+        log::info!("Session {}: Listing UI elements (role {:?}, name {:?})", session_id, query.role, query.name);
+
+        // The accessibility APIs block, sometimes for a while on a busy app
+        let outcome = tokio::task::spawn_blocking(move || platform::elements(&query, max_depth, limit))
+            .await
+            .map_err(|e| format!("UI element lookup failed: {}", e))
+            .and_then(|result| result)
+            .map(|elements| serde_json::json!({
+                "success": true,
+                "count": elements.len(),
+                "truncated": elements.len() >= limit,
+                "elements": elements
+            }));
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_map_across_platforms_and_filter() {
+        assert_eq!(uia_role(50000), "button");
+        assert_eq!(ax_role("AXButton"), "button");
+        assert_eq!(uia_role(50004), ax_role("AXTextField"));
+        assert_eq!(uia_role(50011), ax_role("AXMenuItem"));
+        assert_eq!(uia_role(12345), "other");
+        assert_eq!(ax_role("AXUnknownThing"), "other");
+
+        let save = element("button", "AXButton".to_string(), "Save".to_string(), Some(String::new()), (100, 40, 80, 24), true, false, 3);
+        assert_eq!((save.center_x, save.center_y, save.identifier.clone()), (140, 52, None));
+        let pane = element("group", "AXGroup".to_string(), String::new(), None, (0, 0, 0, 0), true, false, 1);
+
+        let query = |role: Option<&str>, name: Option<&str>| UiElementQuery {
+            window_id: None,
+            process_id: None,
+            role: role.map(str::to_string),
+            name: name.map(str::to_string),
+            max_depth: None,
+            max_elements: None,
+            include_unnamed: false,
+        };
+        assert!(query(Some("button"), Some("sav")).matches(&save));
+        assert!(query(Some("AXButton"), None).matches(&save));
+        assert!(!query(Some("link"), None).matches(&save));
+        assert!(!query(None, Some("cancel")).matches(&save));
+        assert!(!query(None, None).matches(&pane));
+        assert!(UiElementQuery { include_unnamed: true, ..query(None, None) }.matches(&pane));
+    }
+}
@@ -0,0 +1,266 @@
+// src-tauri/src/mcp/wait_tool.rs
+// Wait for the screen - poll screenshots of the desktop (or one region of it) until some text
+// shows up through OCR, or until the picture stops changing, so a plan can give a slow UI time
+// to load between steps instead of clicking into a half-drawn window.
+//
+// "Stopped changing" compares small grayscale thumbnails of consecutive screenshots: once the
+// mean pixel difference stays under the threshold for `stable_ms`, the screen counts as settled.
+// A blinking caret or a spinner in a corner moves the mean very little, so they don't hold it up.
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+use crate::mcp::tools::{find_text_in_image, locations_on_desktop, take_screenshot_full, take_screenshot_region, tool_result, ComputerUseTool};
+use crate::mcp::text_match::TextMatchOptions;
+use crate::mcp::types::*;
+
+const MAX_TIMEOUT_MS: u64 = 120_000;
+const MIN_POLL_INTERVAL_MS: u64 = 100;
+// Thumbnails this size still notice a dialog opening but not a caret blinking
+const THUMBNAIL_SIZE: u32 = 64;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaitForParams {
+    /// Wait for this text to appear; without it, wait for the screen to settle
+    pub text: Option<String>,
+    pub region: Option<ScreenRegion>,
+    pub timeout_ms: Option<u64>,
+    pub poll_interval_ms: Option<u64>,
+    /// How long the screen has to stay unchanged to count as settled
+    pub stable_ms: Option<u64>,
+    /// Mean pixel difference (0.0 - 1.0) below which two screenshots count as the same
+    pub change_threshold: Option<f64>,
+    pub case_sensitive: Option<bool>,
+    pub confidence_threshold: Option<f64>,
+}
+
+fn thumbnail(image_base64: &str) -> Result<image::GrayImage, String> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(image_base64)
+        .map_err(|e| format!("Failed to decode screenshot: {}", e))?;
+    let image = image::load_from_memory(&bytes).map_err(|e| format!("Failed to read screenshot: {}", e))?;
+    Ok(image::imageops::thumbnail(&image.to_luma8(), THUMBNAIL_SIZE, THUMBNAIL_SIZE))
+}
+
+/// Mean absolute difference between two thumbnails, 0.0 (same) to 1.0; different sizes count as 1.0
+fn difference(a: &image::GrayImage, b: &image::GrayImage) -> f64 {
+    if a.dimensions() != b.dimensions() || a.is_empty() {
+        return 1.0;
+    }
+    let total: u64 = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .map(|(&x, &y)| x.abs_diff(y) as u64)
+        .sum();
+    total as f64 / (a.as_raw().len() as f64 * 255.0)
+}
+
+/// Tracks how long consecutive screenshots have stayed the same
+#[derive(Default)]
+struct Settling {
+    previous: Option<image::GrayImage>,
+    unchanged_since: Option<Instant>,
+}
+
+impl Settling {
+    /// The thumbnail's difference from the previous one, and whether the screen has now been
+    /// still for `stable_for`
+    fn observe(&mut self, current: image::GrayImage, now: Instant, threshold: f64, stable_for: Duration) -> (f64, bool) {
+        let change = self.previous.as_ref().map_or(1.0, |previous| difference(previous, &current));
+        self.previous = Some(current);
+        if change > threshold {
+            self.unchanged_since = Some(now);
+            return (change, false);
+        }
+        let since = *self.unchanged_since.get_or_insert(now);
+        (change, now.duration_since(since) >= stable_for)
+    }
+}
+
+#[derive(Clone)]
+pub struct WaitForTool;
+
+impl WaitForTool {
+    async fn screenshot(region: Option<&ScreenRegion>) -> Result<ScreenshotResult, String> {
+        match region {
+            Some(region) => take_screenshot_region(region.clone(), Some("png".to_string()), None).await,
+            None => take_screenshot_full(Some("png".to_string()), None).await,
+        }
+    }
+
+    async fn wait(&self, params: WaitForParams) -> Result<serde_json::Value, String> {
+        let timeout = Duration::from_millis(params.timeout_ms.unwrap_or(10_000).min(MAX_TIMEOUT_MS));
+        let poll_interval = Duration::from_millis(params.poll_interval_ms.unwrap_or(500).max(MIN_POLL_INTERVAL_MS));
+        let stable_for = Duration::from_millis(params.stable_ms.unwrap_or(1_000));
+        let threshold = params.change_threshold.unwrap_or(0.01).clamp(0.0, 1.0);
+        let text = params.text.as_deref().map(str::trim).filter(|text| !text.is_empty());
+
+        let started = Instant::now();
+        let mut settling = Settling::default();
+        let mut polls = 0u32;
+        loop {
+            polls += 1;
+            let screenshot = Self::screenshot(params.region.as_ref()).await?;
+            match text {
+                Some(text) => {
+                    let matches = find_text_in_image(
+                        &screenshot.image_base64,
+                        text,
+                        params.confidence_threshold.unwrap_or(0.8),
+                        &TextMatchOptions { case_sensitive: params.case_sensitive.unwrap_or(false), ..TextMatchOptions::default() },
+                        None,
+                    )
+                    .await?;
+                    if !matches.is_empty() {
+                        let matches = locations_on_desktop(matches, &screenshot.mapping);
+                        return Ok(serde_json::json!({
+                            "success": true,
+                            "condition": "text_found",
+                            "text": text,
+                            "matches": matches,
+                            "elapsed_ms": started.elapsed().as_millis() as u64,
+                            "polls": polls,
+                            "message": format!("'{}' appeared after {} ms", text, started.elapsed().as_millis())
+                        }));
+                    }
+                }
+                None => {
+                    let thumbnail = thumbnail(&screenshot.image_base64)?;
+                    let (change, settled) = settling.observe(thumbnail, Instant::now(), threshold, stable_for);
+                    if settled {
+                        return Ok(serde_json::json!({
+                            "success": true,
+                            "condition": "stable",
+                            "last_change": change,
+                            "elapsed_ms": started.elapsed().as_millis() as u64,
+                            "polls": polls,
+                            "message": format!("Screen settled after {} ms", started.elapsed().as_millis())
+                        }));
+                    }
+                }
+            }
+
+            if started.elapsed() + poll_interval > timeout {
+                return Err(match text {
+                    Some(text) => format!("Timed out after {} ms waiting for '{}' to appear", timeout.as_millis(), text),
+                    None => format!("Timed out after {} ms waiting for the screen to settle", timeout.as_millis()),
+                });
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+#[async_trait]
+impl ComputerUseTool for WaitForTool {
+    fn name(&self) -> &str { "wait_for" }
+
+    fn description(&self) -> String {
+        "Wait until text appears on screen (via OCR) or the screen stops changing, with a timeout".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Low }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "Text to wait for; leave out to wait for the screen to stop changing"
+                },
+                "region": {
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "integer" },
+                        "y": { "type": "integer" },
+                        "width": { "type": "integer" },
+                        "height": { "type": "integer" }
+                    },
+                    "required": ["x", "y", "width", "height"],
+                    "description": "Only watch this part of the screen"
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "maximum": MAX_TIMEOUT_MS,
+                    "default": 10000,
+                    "description": "Give up after this long"
+                },
+                "poll_interval_ms": {
+                    "type": "integer",
+                    "minimum": MIN_POLL_INTERVAL_MS,
+                    "default": 500,
+                    "description": "Time between screenshots"
+                },
+                "stable_ms": {
+                    "type": "integer",
+                    "default": 1000,
+                    "description": "How long the screen must stay unchanged to count as settled"
+                },
+                "change_threshold": {
+                    "type": "number",
+                    "minimum": 0.0,
+                    "maximum": 1.0,
+                    "default": 0.01,
+                    "description": "Mean pixel difference below which the screen counts as unchanged"
+                },
+                "case_sensitive": {
+                    "type": "boolean",
+                    "default": false
+                },
+                "confidence_threshold": {
+                    "type": "number",
+                    "minimum": 0.0,
+                    "maximum": 1.0,
+                    "default": 0.8,
+                    "description": "Minimum OCR confidence for the text to count"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: WaitForParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for wait_for: {}", e))?;
+
+        match &params.text {
+            Some(text) => log::info!("Session {}: Waiting for '{}' to appear", session_id, text),
+            None => log::info!("Session {}: Waiting for the screen to settle", session_id),
+        }
+
+        let outcome = self.wait(params).await;
+        Ok(tool_result(self.name(), start_time, outcome))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_settles_after_staying_unchanged() {
+        let dark = image::GrayImage::from_pixel(8, 8, image::Luma([0]));
+        let light = image::GrayImage::from_pixel(8, 8, image::Luma([255]));
+        assert_eq!(difference(&dark, &dark), 0.0);
+        assert_eq!(difference(&dark, &light), 1.0);
+        assert_eq!(difference(&dark, &image::GrayImage::new(4, 4)), 1.0);
+
+        let stable_for = Duration::from_millis(1000);
+        let start = Instant::now();
+        let mut settling = Settling::default();
+        // The first screenshot has nothing to compare with
+        assert!(!settling.observe(dark.clone(), start, 0.01, stable_for).1);
+        assert!(!settling.observe(dark.clone(), start + Duration::from_millis(500), 0.01, stable_for).1);
+        // A change restarts the clock
+        assert!(!settling.observe(light.clone(), start + Duration::from_millis(1000), 0.01, stable_for).1);
+        assert!(!settling.observe(light.clone(), start + Duration::from_millis(1500), 0.01, stable_for).1);
+        assert!(settling.observe(light, start + Duration::from_millis(2600), 0.01, stable_for).1);
+    }
+}
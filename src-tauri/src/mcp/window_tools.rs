@@ -0,0 +1,521 @@
+// src-tauri/src/mcp/window_tools.rs
+// Window management tools - list the open top-level windows, bring one to the foreground, and
+// move or resize it, so the agent can get an app in front of it before clicking instead of
+// clicking blindly at coordinates.
+//
+// Windows are picked by the `id` `list_windows` reports, or by a title substring and/or
+// process name; when several match, the topmost wins. Positions and sizes are desktop
+// coordinates, the same units the input tools and `get_screen_info` use.
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::mcp::tools::{tool_result, ComputerUseTool};
+use crate::mcp::types::*;
+
+// Smallest size the agent may shrink a window to
+const MIN_WINDOW_SIZE: i32 = 100;
+// How much of the title bar has to stay on some monitor after a move
+const TITLE_BAR_GRIP: i32 = 40;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowInfo {
+    /// Window handle, valid until the window closes
+    pub id: i64,
+    pub title: String,
+    pub process_id: u32,
+    pub process_name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowQuery {
+    pub id: Option<i64>,
+    /// Case-insensitive substring of the title
+    pub title: Option<String>,
+    /// Executable name, with or without `.exe`
+    pub process: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveResizeParams {
+    #[serde(flatten)]
+    pub window: WindowQuery,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
+
+fn without_exe(name: &str) -> &str {
+    let trimmed = name.trim();
+    match trimmed.len().checked_sub(4) {
+        Some(stem) if trimmed[stem..].eq_ignore_ascii_case(".exe") => &trimmed[..stem],
+        _ => trimmed,
+    }
+}
+
+impl WindowQuery {
+    fn is_empty(&self) -> bool {
+        self.id.is_none()
+            && self.title.as_deref().map_or(true, |title| title.trim().is_empty())
+            && self.process.as_deref().map_or(true, |process| process.trim().is_empty())
+    }
+
+    fn matches(&self, window: &WindowInfo) -> bool {
+        if let Some(id) = self.id {
+            return window.id == id;
+        }
+        let title_ok = self
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map_or(true, |title| window.title.to_lowercase().contains(&title.to_lowercase()));
+        let process_ok = self
+            .process
+            .as_deref()
+            .filter(|process| !process.trim().is_empty())
+            .map_or(true, |process| {
+                window
+                    .process_name
+                    .as_deref()
+                    .is_some_and(|name| without_exe(name).eq_ignore_ascii_case(without_exe(process)))
+            });
+        title_ok && process_ok
+    }
+
+    fn describe(&self) -> String {
+        if let Some(id) = self.id {
+            return format!("id {}", id);
+        }
+        let mut parts = Vec::new();
+        if let Some(title) = self.title.as_deref().filter(|title| !title.trim().is_empty()) {
+            parts.push(format!("title containing \"{}\"", title.trim()));
+        }
+        if let Some(process) = self.process.as_deref().filter(|process| !process.trim().is_empty()) {
+            parts.push(format!("process {}", process.trim()));
+        }
+        parts.join(" and ")
+    }
+}
+
+/// The topmost window matching `query`; `windows` is in Z order, as `list_windows` returns it
+fn find_window(query: &WindowQuery, windows: Vec<WindowInfo>) -> Result<WindowInfo, String> {
+    if query.is_empty() {
+        return Err("Say which window: pass an id from list_windows, a title, or a process name".to_string());
+    }
+    windows
+        .into_iter()
+        .find(|window| query.matches(window))
+        .ok_or_else(|| format!("No open window with {}", query.describe()))
+}
+
+/// Where `window` would end up after applying `params`, checked to stay usable
+fn target_bounds(window: &WindowInfo, params: &MoveResizeParams) -> Result<(i32, i32, i32, i32), String> {
+    if params.x.is_none() && params.y.is_none() && params.width.is_none() && params.height.is_none() {
+        return Err("Nothing to change: pass x/y to move the window or width/height to resize it".to_string());
+    }
+    let width = params.width.unwrap_or(window.width);
+    let height = params.height.unwrap_or(window.height);
+    if width < MIN_WINDOW_SIZE || height < MIN_WINDOW_SIZE {
+        return Err(format!("Windows can't be made smaller than {}x{}", MIN_WINDOW_SIZE, MIN_WINDOW_SIZE));
+    }
+    Ok((params.x.unwrap_or(window.x), params.y.unwrap_or(window.y), width, height))
+}
+
+// A window dragged entirely off-screen can't be reached with the mouse, so some of its title
+// bar has to stay on a monitor
+fn title_bar_visible(layout: &crate::coordinates::DesktopLayout, x: i32, y: i32, width: i32) -> bool {
+    let grip = TITLE_BAR_GRIP.min(width / 2);
+    [x + grip, x + width / 2, x + width - grip]
+        .iter()
+        .any(|&point_x| layout.monitor_at(point_x, y + grip / 2).is_some())
+}
+
+fn query_schema() -> serde_json::Map<String, serde_json::Value> {
+    let schema = serde_json::json!({
+        "id": {
+            "type": "integer",
+            "description": "Window id from list_windows"
+        },
+        "title": {
+            "type": "string",
+            "description": "Part of the window title, case-insensitive"
+        },
+        "process": {
+            "type": "string",
+            "description": "Executable name, e.g. notepad or chrome.exe"
+        }
+    });
+    schema.as_object().cloned().unwrap_or_default()
+}
+
+#[derive(Clone)]
+pub struct WindowListTool;
+
+#[async_trait]
+impl ComputerUseTool for WindowListTool {
+    fn name(&self) -> &str { "list_windows" }
+
+    fn description(&self) -> String {
+        "List open windows with their id, title, process, position and size, topmost first. Optionally filter by title or process".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Low }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut properties = query_schema();
+        properties.remove("id");
+        serde_json::json!({
+            "type": "object",
+            "properties": properties
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let query: WindowQuery = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for list_windows: {}", e))?;
+        let query = WindowQuery { id: None, ..query };
+
+        log::info!("Session {}: Listing windows", session_id);
+
+        let outcome = platform::list_windows().map(|windows| {
+            let windows: Vec<WindowInfo> = windows.into_iter().filter(|window| query.matches(window)).collect();
+            serde_json::json!({
+                "success": true,
+                "count": windows.len(),
+                "windows": windows
+            })
+        });
+        Ok(tool_result(self.name(), start_time, outcome.map_err(|e| format!("Failed to list windows: {}", e))))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct FocusWindowTool;
+
+#[async_trait]
+impl ComputerUseTool for FocusWindowTool {
+    fn name(&self) -> &str { "focus_window" }
+
+    fn description(&self) -> String {
+        "Bring a window to the foreground, restoring it if minimized. Pick it by id, title or process".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Medium }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": query_schema()
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let query: WindowQuery = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for focus_window: {}", e))?;
+
+        let outcome = async {
+            let window = find_window(&query, platform::list_windows()?)?;
+            log::info!("Session {}: Focusing window {} \"{}\"", session_id, window.id, window.title);
+            platform::focus_window(window.id).await?;
+            Ok::<_, String>(serde_json::json!({
+                "success": true,
+                "window": window,
+                "message": format!("Brought \"{}\" to the foreground", window.title)
+            }))
+        }
+        .await;
+        Ok(tool_result(self.name(), start_time, outcome.map_err(|e| format!("Failed to focus window: {}", e))))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct MoveResizeWindowTool;
+
+#[async_trait]
+impl ComputerUseTool for MoveResizeWindowTool {
+    fn name(&self) -> &str { "move_resize_window" }
+
+    fn description(&self) -> String {
+        "Move and/or resize a window, in desktop coordinates. Pick it by id, title or process; omitted position or size values are kept".to_string()
+    }
+
+    fn danger_level(&self) -> DangerLevel { DangerLevel::Medium }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut properties = query_schema();
+        for (field, description) in [
+            ("x", "New left edge"),
+            ("y", "New top edge"),
+            ("width", "New width"),
+            ("height", "New height"),
+        ] {
+            properties.insert(field.to_string(), serde_json::json!({
+                "type": "integer",
+                "description": description
+            }));
+        }
+        serde_json::json!({
+            "type": "object",
+            "properties": properties
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, session_id: &str) -> Result<ToolExecutionResult, String> {
+        let start_time = Instant::now();
+        let params: MoveResizeParams = serde_json::from_value(params)
+            .map_err(|e| format!("Invalid parameters for move_resize_window: {}", e))?;
+
+        let outcome = (|| -> Result<serde_json::Value, String> {
+            let window = find_window(&params.window, platform::list_windows()?)?;
+            let (x, y, width, height) = target_bounds(&window, &params)?;
+            let layout = crate::coordinates::current_layout()?;
+            if !title_bar_visible(&layout, x, y, width) {
+                return Err(format!("({}, {}) would put \"{}\" off-screen", x, y, window.title));
+            }
+            log::info!("Session {}: Moving window {} to ({}, {}) {}x{}", session_id, window.id, x, y, width, height);
+            platform::set_window_bounds(window.id, x, y, width, height)?;
+            Ok(serde_json::json!({
+                "success": true,
+                "id": window.id,
+                "title": window.title,
+                "x": x,
+                "y": y,
+                "width": width,
+                "height": height
+            }))
+        })();
+        Ok(tool_result(self.name(), start_time, outcome.map_err(|e| format!("Failed to move window: {}", e))))
+    }
+
+    fn clone_box(&self) -> Box<dyn ComputerUseTool + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::WindowInfo;
+    use winapi::shared::minwindef::{BOOL, LPARAM, TRUE};
+    use winapi::shared::windef::{DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, HWND, RECT};
+    use winapi::um::dwmapi::DwmGetWindowAttribute;
+    use winapi::um::dwmapi::DWMWA_CLOAKED;
+    use winapi::um::winuser::*;
+
+    // Rects in physical pixels, matching the desktop coordinates the input tools use
+    fn per_monitor_aware<T>(f: impl FnOnce() -> T) -> T {
+        unsafe {
+            let previous = SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+            let result = f();
+            if !previous.is_null() {
+                SetThreadDpiAwarenessContext(previous);
+            }
+            result
+        }
+    }
+
+    fn handle(id: i64) -> Result<HWND, String> {
+        let hwnd = id as isize as HWND;
+        if unsafe { IsWindow(hwnd) } == 0 {
+            return Err(format!("Window {} has closed", id));
+        }
+        Ok(hwnd)
+    }
+
+    // Only what shows on the taskbar: visible, titled, unowned, not a tool window, and not
+    // cloaked (suspended Store apps are "visible" but never drawn)
+    unsafe fn describe(hwnd: HWND, foreground: HWND) -> Option<WindowInfo> {
+        if IsWindowVisible(hwnd) == 0 || !GetWindow(hwnd, GW_OWNER).is_null() {
+            return None;
+        }
+        if GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW != 0 {
+            return None;
+        }
+        let mut cloaked: u32 = 0;
+        let cloaked_size = std::mem::size_of::<u32>() as u32;
+        if DwmGetWindowAttribute(hwnd, DWMWA_CLOAKED, &mut cloaked as *mut u32 as *mut _, cloaked_size) == 0 && cloaked != 0 {
+            return None;
+        }
+        let length = GetWindowTextLengthW(hwnd);
+        if length <= 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; length as usize + 1];
+        let copied = GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32);
+        let title = String::from_utf16_lossy(&buffer[..copied.max(0) as usize]);
+
+        let mut rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
+        if GetWindowRect(hwnd, &mut rect) == 0 {
+            return None;
+        }
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut process_id);
+        Some(WindowInfo {
+            id: hwnd as isize as i64,
+            title,
+            process_id,
+            process_name: crate::audio_loopback::windows::audio_sessions::process_name(process_id),
+            x: rect.left,
+            y: rect.top,
+            width: rect.right - rect.left,
+            height: rect.bottom - rect.top,
+            minimized: IsIconic(hwnd) != 0,
+            maximized: IsZoomed(hwnd) != 0,
+            focused: hwnd == foreground,
+        })
+    }
+
+    struct Collected {
+        foreground: HWND,
+        windows: Vec<WindowInfo>,
+    }
+
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let collected = &mut *(lparam as *mut Collected);
+        if let Some(window) = describe(hwnd, collected.foreground) {
+            collected.windows.push(window);
+        }
+        TRUE
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        per_monitor_aware(|| unsafe {
+            let mut collected = Collected { foreground: GetForegroundWindow(), windows: Vec::new() };
+            if EnumWindows(Some(collect), &mut collected as *mut Collected as LPARAM) == 0 {
+                return Err("EnumWindows failed".to_string());
+            }
+            Ok(collected.windows)
+        })
+    }
+
+    pub async fn focus_window(id: i64) -> Result<(), String> {
+        request_focus(id)?;
+        // Focus changes asynchronously
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        if unsafe { GetForegroundWindow() } as isize as i64 == id {
+            Ok(())
+        } else {
+            Err("Windows refused to bring the window to the foreground".to_string())
+        }
+    }
+
+    // Kept apart from `focus_window` so no HWND is held across its await
+    fn request_focus(id: i64) -> Result<(), String> {
+        let hwnd = handle(id)?;
+        unsafe {
+            if IsIconic(hwnd) != 0 {
+                ShowWindow(hwnd, SW_RESTORE);
+            }
+            if SetForegroundWindow(hwnd) == 0 {
+                // Windows only lets the app that last had input change the foreground window; a
+                // synthetic Alt press counts as input, so the retry is allowed
+                keybd_event(VK_MENU as u8, 0, 0, 0);
+                keybd_event(VK_MENU as u8, 0, KEYEVENTF_KEYUP, 0);
+                SetForegroundWindow(hwnd);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_window_bounds(id: i64, x: i32, y: i32, width: i32, height: i32) -> Result<(), String> {
+        let hwnd = handle(id)?;
+        per_monitor_aware(|| unsafe {
+            // A maximized or minimized window ignores SetWindowPos until restored
+            if IsZoomed(hwnd) != 0 || IsIconic(hwnd) != 0 {
+                ShowWindow(hwnd, SW_RESTORE);
+            }
+            if SetWindowPos(hwnd, std::ptr::null_mut(), x, y, width, height, SWP_NOZORDER | SWP_NOACTIVATE) == 0 {
+                return Err("SetWindowPos failed".to_string());
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::WindowInfo;
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        Err("Window management not implemented for this platform".to_string())
+    }
+
+    pub async fn focus_window(_id: i64) -> Result<(), String> {
+        Err("Window management not implemented for this platform".to_string())
+    }
+
+    pub fn set_window_bounds(_id: i64, _x: i32, _y: i32, _width: i32, _height: i32) -> Result<(), String> {
+        Err("Window management not implemented for this platform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: i64, title: &str, process: &str) -> WindowInfo {
+        WindowInfo {
+            id,
+            title: title.to_string(),
+            process_id: id as u32,
+            process_name: Some(process.to_string()),
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+            minimized: false,
+            maximized: false,
+            focused: false,
+        }
+    }
+
+    #[test]
+    fn test_finds_topmost_matching_window_and_checks_bounds() {
+        let windows = vec![
+            window(1, "Inbox - Outlook", "OUTLOOK.EXE"),
+            window(2, "notes.txt - Notepad", "notepad.exe"),
+            window(3, "todo.txt - Notepad", "notepad.exe"),
+        ];
+        let query = |title: Option<&str>, process: Option<&str>| WindowQuery {
+            id: None,
+            title: title.map(str::to_string),
+            process: process.map(str::to_string),
+        };
+
+        // Process names match with or without .exe; the topmost match wins
+        assert_eq!(find_window(&query(None, Some("Notepad")), windows.clone()).unwrap().id, 2);
+        assert_eq!(find_window(&query(Some("TODO"), Some("notepad.exe")), windows.clone()).unwrap().id, 3);
+        assert_eq!(find_window(&WindowQuery { id: Some(1), ..query(Some("Notepad"), None) }, windows.clone()).unwrap().id, 1);
+        assert!(find_window(&query(Some("todo"), Some("outlook")), windows.clone()).is_err());
+        assert!(find_window(&query(Some("  "), None), windows.clone()).is_err());
+
+        let params = |x, width| MoveResizeParams { window: WindowQuery::default(), x, y: None, width, height: None };
+        assert_eq!(target_bounds(&windows[0], &params(Some(50), None)), Ok((50, 0, 800, 600)));
+        assert!(target_bounds(&windows[0], &params(None, Some(20))).is_err());
+        assert!(target_bounds(&windows[0], &params(None, None)).is_err());
+
+        let monitor = crate::coordinates::MonitorGeometry { id: 1, x: 0, y: 0, width: 1920, height: 1080, scale_factor: 1.0, is_primary: true };
+        let layout = crate::coordinates::DesktopLayout::new(vec![monitor]).unwrap();
+        assert!(title_bar_visible(&layout, -700, 10, 800));
+        assert!(!title_bar_visible(&layout, 3000, 10, 800));
+        assert!(!title_bar_visible(&layout, 100, -200, 800));
+    }
+}
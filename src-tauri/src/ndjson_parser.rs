@@ -0,0 +1,197 @@
+// Tolerant incremental NDJSON parser for streamed Ollama responses
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+// A fragment longer than this without ever parsing is treated as garbage
+const MAX_PENDING_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct StreamDiagnostics {
+    pub parsed_lines: usize,
+    /// Lines that only parsed after being joined with a previous fragment
+    pub repaired_lines: usize,
+    /// Lines that held more than one JSON object
+    pub merged_lines: usize,
+    pub unrecoverable_lines: usize,
+}
+
+enum SegmentResult<T> {
+    Complete(Vec<T>),
+    /// Parsed values, followed by a truncated object that may continue on the next line
+    Incomplete(Vec<T>, String),
+    Invalid(Vec<T>, serde_json::Error),
+}
+
+/// Splits a byte stream into newline-delimited JSON values. Bytes are buffered
+/// until a newline so chunk boundaries (including inside UTF-8 sequences) are
+/// harmless, truncated objects are carried over and joined with the next line,
+/// and several objects run together on one line are split apart.
+pub struct NdjsonStreamParser<T> {
+    buffer: Vec<u8>,
+    pending: String,
+    diagnostics: StreamDiagnostics,
+    _marker: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> NdjsonStreamParser<T> {
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            pending: String::new(),
+            diagnostics: StreamDiagnostics::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn diagnostics(&self) -> &StreamDiagnostics {
+        &self.diagnostics
+    }
+
+    /// Feed a chunk of bytes and return every value completed by it
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<T> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut values = Vec::new();
+        while let Some(newline_pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line = self.buffer.drain(..=newline_pos).collect::<Vec<u8>>();
+            let line = String::from_utf8_lossy(&line[..line.len() - 1]).to_string();
+            self.process_line(&line, &mut values);
+        }
+        values
+    }
+
+    /// Flush whatever is left once the stream has ended
+    pub fn finish(&mut self) -> Vec<T> {
+        let mut values = Vec::new();
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).to_string();
+            self.process_line(&line, &mut values);
+        }
+        if !self.pending.trim().is_empty() {
+            eprintln!("Dropping incomplete streaming response: {}", self.pending);
+            self.diagnostics.unrecoverable_lines += 1;
+        }
+        self.pending.clear();
+        values
+    }
+
+    fn process_line(&mut self, line: &str, values: &mut Vec<T>) {
+        if line.trim().is_empty() {
+            return;
+        }
+
+        if self.pending.is_empty() {
+            self.process_segment(line, false, values);
+            return;
+        }
+
+        let joined = format!("{}{}", std::mem::take(&mut self.pending), line);
+        match parse_segment::<T>(&joined) {
+            SegmentResult::Invalid(parsed, _) if parsed.is_empty() => {
+                // The fragment didn't belong to this line; give up on it and retry the line alone
+                eprintln!("Dropping unrepairable streaming fragment before: {}", line);
+                self.diagnostics.unrecoverable_lines += 1;
+                self.process_segment(line, false, values);
+            }
+            _ => self.process_segment(&joined, true, values),
+        }
+    }
+
+    fn process_segment(&mut self, text: &str, repaired: bool, values: &mut Vec<T>) {
+        let (parsed, rest) = match parse_segment::<T>(text) {
+            SegmentResult::Complete(parsed) => (parsed, None),
+            SegmentResult::Incomplete(parsed, rest) => (parsed, Some(rest)),
+            SegmentResult::Invalid(parsed, e) => {
+                eprintln!("Failed to parse streaming response: {} - Line: {}", e, text);
+                self.diagnostics.unrecoverable_lines += 1;
+                (parsed, None)
+            }
+        };
+
+        if !parsed.is_empty() {
+            self.diagnostics.parsed_lines += 1;
+            if repaired {
+                self.diagnostics.repaired_lines += 1;
+            }
+            if parsed.len() > 1 {
+                self.diagnostics.merged_lines += 1;
+            }
+        }
+        values.extend(parsed);
+
+        if let Some(rest) = rest {
+            if rest.len() > MAX_PENDING_BYTES {
+                eprintln!("Dropping oversized streaming fragment ({} bytes)", rest.len());
+                self.diagnostics.unrecoverable_lines += 1;
+            } else {
+                self.pending = rest;
+            }
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Default for NdjsonStreamParser<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_segment<T: DeserializeOwned>(text: &str) -> SegmentResult<T> {
+    let mut values = Vec::new();
+    let mut stream = serde_json::Deserializer::from_str(text).into_iter::<T>();
+    loop {
+        let offset = stream.byte_offset();
+        match stream.next() {
+            None => return SegmentResult::Complete(values),
+            Some(Ok(value)) => values.push(value),
+            Some(Err(e)) if e.is_eof() => {
+                return SegmentResult::Incomplete(values, text[offset..].trim_start().to_string())
+            }
+            Some(Err(e)) => return SegmentResult::Invalid(values, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Chunk {
+        response: String,
+        done: bool,
+    }
+
+    fn texts(chunks: &[Chunk]) -> Vec<&str> {
+        chunks.iter().map(|c| c.response.as_str()).collect()
+    }
+
+    #[test]
+    fn test_repairs_split_and_merged_lines() {
+        let mut parser = NdjsonStreamParser::<Chunk>::new();
+        let mut chunks = parser.push(b"{\"response\":\"a\",\"done\":false}\n{\"response\":\"b\",");
+        // A stray newline inside the object splits it across two lines
+        chunks.extend(parser.push(b"\n\"done\":false}\n"));
+        chunks.extend(parser.push(b"{\"response\":\"c\",\"done\":false}{\"response\":\"d\",\"done\":false}\n"));
+        chunks.extend(parser.push("{\"response\":\"\u{e9}\",\"done\":true}".as_bytes()));
+        chunks.extend(parser.finish());
+
+        assert_eq!(texts(&chunks), vec!["a", "b", "c", "d", "\u{e9}"]);
+        assert_eq!(parser.diagnostics().repaired_lines, 1);
+        assert_eq!(parser.diagnostics().merged_lines, 1);
+        assert_eq!(parser.diagnostics().unrecoverable_lines, 0);
+    }
+
+    #[test]
+    fn test_counts_unrecoverable_lines() {
+        let mut parser = NdjsonStreamParser::<Chunk>::new();
+        let mut chunks = parser.push(b"not json\n{\"response\":\"trunc");
+        chunks.extend(parser.push(b"\n{\"response\":\"ok\",\"done\":true}\n"));
+        chunks.extend(parser.finish());
+
+        assert_eq!(texts(&chunks), vec!["ok"]);
+        assert_eq!(parser.diagnostics().unrecoverable_lines, 2);
+    }
+}
@@ -0,0 +1,90 @@
+// src-tauri/src/near_duplicate.rs
+// Near-duplicate documents - MinHash signatures over word shingles, so a re-uploaded PDF with a
+// few edits is recognised as a new version of one already stored.
+//
+// Each document's text is lowercased into words and cut into overlapping five-word shingles.
+// The signature keeps, for each of 64 seeded hash functions, the smallest hash over all
+// shingles; the share of positions two signatures agree on estimates the Jaccard similarity of
+// their shingle sets. Hashing is FNV-1a plus a fixed mix so stored signatures stay comparable
+// across builds.
+
+pub const SIGNATURE_LEN: usize = 64;
+const SHINGLE_WORDS: usize = 5;
+/// Estimated similarity from which an upload counts as a version of a stored document
+pub const NEAR_DUPLICATE_THRESHOLD: f64 = 0.8;
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+// splitmix64 finalizer, turning one shingle hash into independent-looking per-seed hashes
+fn mix(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+/// MinHash signature of `text`; `None` when it has no words
+pub fn signature(text: &str) -> Option<Vec<u64>> {
+    let words: Vec<String> = text
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    let mut minimums = vec![u64::MAX; SIGNATURE_LEN];
+    // Texts shorter than a shingle are one shingle
+    for shingle in words.windows(SHINGLE_WORDS.min(words.len())) {
+        let hash = fnv1a(&shingle.join(" "));
+        for (seed, minimum) in minimums.iter_mut().enumerate() {
+            let seeded = mix(hash ^ mix(seed as u64 + 1));
+            if seeded < *minimum {
+                *minimum = seeded;
+            }
+        }
+    }
+    Some(minimums)
+}
+
+/// Estimated Jaccard similarity of the texts two signatures came from, 0.0 to 1.0
+pub fn similarity(a: &[u64], b: &[u64]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    a.iter().zip(b).filter(|(x, y)| x == y).count() as f64 / a.len() as f64
+}
+
+pub fn to_bytes(signature: &[u64]) -> Vec<u8> {
+    signature.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Vec<u64> {
+    bytes
+        .chunks_exact(8)
+        .map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_near_duplicates_score_high() {
+        let original = (0..300).map(|i| format!("word{}", i)).collect::<Vec<_>>().join(" ");
+        // A handful of edits in a long text
+        let edited = original.replace("word10 ", "changed ").replace("word200 ", "edited ");
+        let unrelated = (0..300).map(|i| format!("other{}", i)).collect::<Vec<_>>().join(" ");
+
+        let a = signature(&original).unwrap();
+        let b = signature(&edited).unwrap();
+        let c = signature(&unrelated).unwrap();
+        assert!(similarity(&a, &b) >= NEAR_DUPLICATE_THRESHOLD, "{}", similarity(&a, &b));
+        assert!(similarity(&a, &c) < 0.2);
+        assert_eq!(similarity(&a, &signature(&original.to_uppercase()).unwrap()), 1.0);
+        assert_eq!(from_bytes(&to_bytes(&a)), a);
+        assert_eq!(signature(" ... "), None);
+    }
+}
@@ -0,0 +1,259 @@
+// src-tauri/src/offline_mode.rs
+// Offline mode - whether features that need the internet should run, detected by a connectivity
+// monitor or set by hand
+//
+// Remote LLM backends, cloud transcription, model downloads and web page imports call
+// `require_online` first and fail with a readable error while offline. Local transcription, RAG
+// and an Ollama server on this machine or the local network keep working; a cloud transcription
+// provider falls back to local Whisper. The monitor probes a few well-known hosts over TCP and
+// emits `offline-mode-changed` whenever the effective state flips.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+// Any one answering means we're online; DNS isn't needed for the IP addresses
+const PROBE_HOSTS: &[&str] = &["1.1.1.1:443", "8.8.8.8:53", "dns.google:443"];
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<OfflineSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref MONITOR: Mutex<ConnectivityMonitor> = Mutex::new(ConnectivityMonitor::default());
+    static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+}
+
+/// Something that can't work without the internet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteFeature {
+    RemoteLlm,
+    CloudTranscription,
+    ModelDownload,
+    WebIngestion,
+}
+
+impl RemoteFeature {
+    fn label(self) -> &'static str {
+        match self {
+            RemoteFeature::RemoteLlm => "Remote AI backends",
+            RemoteFeature::CloudTranscription => "Cloud transcription",
+            RemoteFeature::ModelDownload => "Model downloads",
+            RemoteFeature::WebIngestion => "Web page imports",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OfflineReason {
+    /// Turned on by the user
+    Manual,
+    /// None of the probe hosts answered
+    NoConnectivity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OfflineSettings {
+    /// `Some(true)` forces offline mode, `Some(false)` forces online; `None` follows the monitor
+    pub manual_override: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineStatus {
+    pub offline: bool,
+    pub reason: Option<OfflineReason>,
+    pub manual_override: Option<bool>,
+    /// Result of the last probe; `None` before the first one
+    pub reachable: Option<bool>,
+    /// Unix ms of the last probe
+    pub last_checked: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct ConnectivityMonitor {
+    reachable: Option<bool>,
+    last_checked_ms: Option<u64>,
+    /// Last state announced, so only changes are emitted
+    announced: Option<OfflineReason>,
+}
+
+impl ConnectivityMonitor {
+    fn evaluate(&self, settings: &OfflineSettings) -> Option<OfflineReason> {
+        match settings.manual_override {
+            Some(true) => Some(OfflineReason::Manual),
+            Some(false) => None,
+            // Unknown counts as online, so nothing is refused before the first probe
+            None => (self.reachable == Some(false)).then_some(OfflineReason::NoConnectivity),
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+const SETTINGS_FILE: &str = "offline_mode.json";
+
+/// Whether `url` points at this machine or the local network, which stays reachable offline
+pub fn is_local_url(url: &str) -> bool {
+    let Ok(parsed) = reqwest::Url::parse(url.trim()) else { return false };
+    let Some(host) = parsed.host_str() else { return false };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.eq_ignore_ascii_case("localhost") || host.ends_with(".local") || host.ends_with(".lan") {
+        return true;
+    }
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        Ok(IpAddr::V6(ip)) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00 || (ip.segments()[0] & 0xffc0) == 0xfe80,
+        Err(_) => false,
+    }
+}
+
+fn probe() -> bool {
+    PROBE_HOSTS.iter().any(|host| {
+        host.to_socket_addrs()
+            .ok()
+            .into_iter()
+            .flatten()
+            .take(2)
+            .any(|address: SocketAddr| TcpStream::connect_timeout(&address, PROBE_TIMEOUT).is_ok())
+    })
+}
+
+fn status_from(monitor: &ConnectivityMonitor, settings: &OfflineSettings) -> OfflineStatus {
+    let reason = monitor.evaluate(settings);
+    OfflineStatus {
+        offline: reason.is_some(),
+        reason,
+        manual_override: settings.manual_override,
+        reachable: monitor.reachable,
+        last_checked: monitor.last_checked_ms,
+    }
+}
+
+// Emits "offline-mode-changed" only when going offline or online, or the reason changes
+fn reevaluate() {
+    let settings = read_settings(&SETTINGS);
+    let status = {
+        let Ok(mut monitor) = MONITOR.lock() else { return };
+        let reason = monitor.evaluate(&settings);
+        if reason == monitor.announced {
+            return;
+        }
+        monitor.announced = reason;
+        status_from(&monitor, &settings)
+    };
+    println!("🌐 {} ({:?})", if status.offline { "Offline mode on" } else { "Back online" }, status.reason);
+    if let Some(app_handle) = APP_HANDLE.lock().ok().and_then(|handle| handle.clone()) {
+        let _ = app_handle.emit("offline-mode-changed", &status);
+    }
+}
+
+fn check_connectivity() {
+    let reachable = probe();
+    if let Ok(mut monitor) = MONITOR.lock() {
+        monitor.reachable = Some(reachable);
+        monitor.last_checked_ms = Some(now_ms());
+    }
+    reevaluate();
+}
+
+pub fn is_offline() -> bool {
+    let settings = read_settings(&SETTINGS);
+    MONITOR.lock().map(|monitor| monitor.evaluate(&settings).is_some()).unwrap_or(false)
+}
+
+/// Fails with a readable error while offline
+pub fn require_online(feature: RemoteFeature) -> Result<(), String> {
+    let settings = read_settings(&SETTINGS);
+    let reason = MONITOR.lock().ok().and_then(|monitor| monitor.evaluate(&settings));
+    match reason {
+        None => Ok(()),
+        Some(OfflineReason::Manual) => Err(format!("{} are unavailable in offline mode", feature.label())),
+        Some(OfflineReason::NoConnectivity) => Err(format!("{} are unavailable: no internet connection", feature.label())),
+    }
+}
+
+/// Keep the app handle for events and start probing connectivity
+pub fn init(app_handle: &AppHandle) {
+    if let Ok(mut handle) = APP_HANDLE.lock() {
+        *handle = Some(app_handle.clone());
+    }
+    std::thread::spawn(|| loop {
+        // A forced state doesn't need the network checked
+        if read_settings(&SETTINGS).manual_override.is_none() {
+            check_connectivity();
+        }
+        std::thread::sleep(PROBE_INTERVAL);
+    });
+}
+
+#[crate::command]
+pub fn get_offline_status() -> Result<OfflineStatus, String> {
+    let monitor = MONITOR.lock().map_err(|_| "Failed to read offline status".to_string())?;
+    Ok(status_from(&monitor, &read_settings(&SETTINGS)))
+}
+
+/// Force offline mode on (`true`) or off (`false`); `None` hands control back to the monitor
+#[crate::command]
+pub async fn set_offline_mode(manual_override: Option<bool>) -> Result<OfflineStatus, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    let settings = OfflineSettings { manual_override };
+    save_json_settings(SETTINGS_FILE, "offline settings", &settings)?;
+    *SETTINGS
+        .write()
+        .map_err(|_| "Failed to update offline settings".to_string())? = settings;
+
+    if manual_override.is_none() {
+        // Probing blocks for up to a few seconds
+        tokio::task::spawn_blocking(check_connectivity)
+            .await
+            .map_err(|e| format!("Failed to check connectivity: {}", e))?;
+    } else {
+        reevaluate();
+    }
+    get_offline_status()
+}
+
+/// Probe now instead of waiting for the next check
+#[crate::command]
+pub async fn check_connectivity_now() -> Result<OfflineStatus, String> {
+    tokio::task::spawn_blocking(check_connectivity)
+        .await
+        .map_err(|e| format!("Failed to check connectivity: {}", e))?;
+    get_offline_status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_and_probe_decide_offline_state() {
+        let mut monitor = ConnectivityMonitor::default();
+        let auto = OfflineSettings::default();
+        // Before the first probe nothing is refused
+        assert_eq!(monitor.evaluate(&auto), None);
+        monitor.reachable = Some(false);
+        assert_eq!(monitor.evaluate(&auto), Some(OfflineReason::NoConnectivity));
+        assert_eq!(monitor.evaluate(&OfflineSettings { manual_override: Some(false) }), None);
+        monitor.reachable = Some(true);
+        assert_eq!(monitor.evaluate(&OfflineSettings { manual_override: Some(true) }), Some(OfflineReason::Manual));
+
+        assert!(is_local_url("http://localhost:11434"));
+        assert!(is_local_url("http://192.168.1.20:8000/v1"));
+        assert!(is_local_url("http://[::1]:1234"));
+        assert!(is_local_url("http://gpu-box.local:8080"));
+        assert!(!is_local_url("https://api.openai.com/v1"));
+        assert!(!is_local_url("https://8.8.8.8"));
+        assert!(!is_local_url("not a url"));
+    }
+}
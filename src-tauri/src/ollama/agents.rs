@@ -0,0 +1,250 @@
+// src-tauri/src/ollama/agents.rs
+// Agent registry - model, sampling and system prompt for each built-in agent. The defaults here are
+// what the agents shipped with; settings store only the fields a user changed.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::backend::AGENT_TYPES;
+use crate::system_prompts::{
+    CODING_AGENT_PROMPT, CONVERSATIONAL_AI_PROMPT, DEEP_RESEARCH_PROMPT, ENTERACT_AGENT_PROMPT,
+    CONTEXT_SUMMARY_PROMPT, MCP_AGENT_PROMPT, RAG_AGENT_PROMPT, VISION_ANALYSIS_PROMPT,
+};
+use crate::json_settings::{load_json_settings, save_json_settings};
+
+const MAX_NUM_PREDICT: u32 = 32768;
+const MAX_FALLBACK_MODELS: usize = 8;
+
+lazy_static::lazy_static! {
+    static ref AGENT_OVERRIDES: RwLock<HashMap<String, AgentOverrides>> = RwLock::new(load_json_settings(SETTINGS_FILE));
+}
+
+/// Effective settings for one agent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentProfile {
+    pub agent: String,
+    /// Empty for the MCP agent, which uses whichever model the caller asks for
+    pub model: String,
+    pub temperature: f64,
+    /// Maximum tokens to generate; `None` leaves it to the server
+    pub num_predict: Option<u32>,
+    pub system_prompt: String,
+    /// Tried in order when `model` isn't installed or fails to load; a name without a tag
+    /// matches any installed tag of that model
+    pub fallback_models: Vec<String>,
+}
+
+/// Fields a user changed; anything left as `None` uses the built-in value
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentOverrides {
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    pub num_predict: Option<u32>,
+    pub system_prompt: Option<String>,
+    /// An empty list turns fallback off for the agent
+    pub fallback_models: Option<Vec<String>>,
+}
+
+impl AgentOverrides {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // Blank strings from a cleared form field mean "use the default"
+    fn normalized(self) -> Self {
+        let non_blank = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+        Self {
+            model: non_blank(self.model).map(|model| model.trim().to_string()),
+            system_prompt: non_blank(self.system_prompt),
+            fallback_models: self.fallback_models.map(|models| {
+                let mut unique: Vec<String> = Vec::new();
+                for model in models.iter().map(|model| model.trim()).filter(|model| !model.is_empty()) {
+                    if !unique.iter().any(|m| m == model) {
+                        unique.push(model.to_string());
+                    }
+                }
+                unique
+            }),
+            ..self
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(temperature) = self.temperature {
+            if !temperature.is_finite() || !(0.0..=2.0).contains(&temperature) {
+                return Err("Temperature must be between 0 and 2".to_string());
+            }
+        }
+        if let Some(num_predict) = self.num_predict {
+            if num_predict == 0 || num_predict > MAX_NUM_PREDICT {
+                return Err(format!("Max tokens must be between 1 and {}", MAX_NUM_PREDICT));
+            }
+        }
+        if self.fallback_models.as_ref().is_some_and(|models| models.len() > MAX_FALLBACK_MODELS) {
+            return Err(format!("An agent can have at most {} fallback models", MAX_FALLBACK_MODELS));
+        }
+        Ok(())
+    }
+
+    fn apply(&self, mut profile: AgentProfile) -> AgentProfile {
+        if let Some(model) = &self.model {
+            profile.model = model.clone();
+        }
+        if let Some(temperature) = self.temperature {
+            profile.temperature = temperature;
+        }
+        if self.num_predict.is_some() {
+            profile.num_predict = self.num_predict;
+        }
+        if let Some(system_prompt) = &self.system_prompt {
+            profile.system_prompt = system_prompt.clone();
+        }
+        if let Some(fallback_models) = &self.fallback_models {
+            profile.fallback_models = fallback_models.clone();
+        }
+        profile
+    }
+}
+
+/// An agent as shown in settings
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentConfig {
+    pub profile: AgentProfile,
+    pub defaults: AgentProfile,
+    pub overrides: AgentOverrides,
+}
+
+fn builtin(agent: &str) -> Option<AgentProfile> {
+    // Small general models most installs have at least one of
+    const GENERAL: &[&str] = &["gemma3", "llama3.2", "phi3"];
+    let (model, temperature, num_predict, system_prompt, fallback_models): (_, _, _, _, &[&str]) = match agent {
+        "enteract" => ("gemma3:1b-it-qat", 0.7, Some(1024), ENTERACT_AGENT_PROMPT, GENERAL),
+        "vision" => ("qwen2.5vl:3b", 0.5, Some(1024), VISION_ANALYSIS_PROMPT, &["qwen2.5vl", "llava", "llama3.2-vision"]),
+        "coding" => ("qwen2.5-coder:1.5b", 0.2, Some(1024), CODING_AGENT_PROMPT, &["qwen2.5-coder", "gemma3", "llama3.2"]),
+        "research" => ("deepseek-r1:1.5b", 0.7, Some(1024), DEEP_RESEARCH_PROMPT, &["deepseek-r1", "gemma3", "llama3.2"]),
+        "conversational_ai" => ("gemma3:1b-it-qat", 0.7, Some(2048), CONVERSATIONAL_AI_PROMPT, GENERAL),
+        "mcp" => ("", 0.7, None, MCP_AGENT_PROMPT, &[]),
+        "rag" => ("gemma3:1b-it-qat", 0.3, Some(1024), RAG_AGENT_PROMPT, GENERAL),
+        "summarizer" => ("gemma3:1b-it-qat", 0.2, Some(512), CONTEXT_SUMMARY_PROMPT, GENERAL),
+        _ => return None,
+    };
+    Some(AgentProfile {
+        agent: agent.to_string(),
+        model: model.to_string(),
+        temperature,
+        num_predict,
+        system_prompt: system_prompt.to_string(),
+        fallback_models: fallback_models.iter().map(|model| model.to_string()).collect(),
+    })
+}
+
+const SETTINGS_FILE: &str = "agents.json";
+
+fn overrides_for(agent: &str) -> AgentOverrides {
+    AGENT_OVERRIDES
+        .read()
+        .ok()
+        .and_then(|overrides| overrides.get(agent).cloned())
+        .unwrap_or_default()
+}
+
+/// Built-in settings with the user's overrides, before any prompt template
+pub(super) fn configured_profile(agent: &str) -> AgentProfile {
+    let defaults = builtin(agent).unwrap_or_else(|| builtin("enteract").expect("enteract is a built-in agent"));
+    overrides_for(agent).apply(defaults)
+}
+
+/// Settings an agent runs with. Unknown agent types get the Enteract agent's settings; the
+/// system prompt comes from the agent's template file when it has one.
+pub fn profile(agent: &str) -> AgentProfile {
+    let mut profile = configured_profile(agent);
+    profile.system_prompt = super::templates::resolve(agent, profile.system_prompt);
+    profile
+}
+
+fn agent_config(agent: &str) -> Result<AgentConfig, String> {
+    let defaults = builtin(agent).ok_or_else(|| format!("Unknown agent: {}", agent))?;
+    let overrides = overrides_for(agent);
+    Ok(AgentConfig {
+        profile: overrides.apply(defaults.clone()),
+        defaults,
+        overrides,
+    })
+}
+
+fn store_overrides(agent: &str, overrides: AgentOverrides) -> Result<(), String> {
+    let mut stored = AGENT_OVERRIDES
+        .write()
+        .map_err(|_| "Failed to update agent settings".to_string())?;
+    let mut updated = stored.clone();
+    if overrides.is_empty() {
+        updated.remove(agent);
+    } else {
+        updated.insert(agent.to_string(), overrides);
+    }
+
+    save_json_settings(SETTINGS_FILE, "agent settings", &updated)?;
+    *stored = updated;
+    Ok(())
+}
+
+#[crate::command]
+pub fn get_agent_registry() -> Result<Vec<AgentConfig>, String> {
+    AGENT_TYPES.iter().map(|agent| agent_config(agent)).collect()
+}
+
+/// Replace an agent's overrides. A backend selection that names its own model still takes precedence.
+#[crate::command]
+pub fn update_agent_config(agent: String, overrides: AgentOverrides) -> Result<AgentConfig, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    if builtin(&agent).is_none() {
+        return Err(format!("Unknown agent: {}", agent));
+    }
+    let overrides = overrides.normalized();
+    overrides.validate()?;
+    store_overrides(&agent, overrides)?;
+    agent_config(&agent)
+}
+
+#[crate::command]
+pub fn reset_agent_config(agent: String) -> Result<AgentConfig, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    if builtin(&agent).is_none() {
+        return Err(format!("Unknown agent: {}", agent));
+    }
+    store_overrides(&agent, AgentOverrides::default())?;
+    agent_config(&agent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_layer_over_builtin_defaults() {
+        assert!(AGENT_TYPES.iter().all(|agent| builtin(agent).is_some()));
+
+        let overrides = AgentOverrides {
+            model: Some("  qwen2.5-coder:7b ".to_string()),
+            temperature: Some(0.1),
+            num_predict: None,
+            system_prompt: Some("   ".to_string()),
+            fallback_models: Some(vec![" qwen2.5-coder ".to_string(), "".to_string(), "qwen2.5-coder".to_string()]),
+        }
+        .normalized();
+        assert!(overrides.validate().is_ok());
+
+        let profile = overrides.apply(builtin("coding").unwrap());
+        assert_eq!(profile.model, "qwen2.5-coder:7b");
+        assert_eq!(profile.temperature, 0.1);
+        assert_eq!(profile.num_predict, Some(1024));
+        assert_eq!(profile.system_prompt, CODING_AGENT_PROMPT);
+        assert_eq!(profile.fallback_models, vec!["qwen2.5-coder".to_string()]);
+
+        assert!(AgentOverrides { temperature: Some(3.0), ..Default::default() }.validate().is_err());
+        assert!(AgentOverrides { num_predict: Some(0), ..Default::default() }.validate().is_err());
+    }
+}
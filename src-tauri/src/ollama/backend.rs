@@ -0,0 +1,823 @@
+// src-tauri/src/ollama/backend.rs
+// LLM backends - the local Ollama server, or any OpenAI-compatible /v1/chat/completions endpoint
+// (LM Studio, vLLM, llama.cpp server, OpenRouter), selectable per agent
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use super::{ChatMessage, ChatRequest, ChatResponse, ToolCall, ToolCallFunction};
+use crate::ndjson_parser::{NdjsonStreamParser, StreamDiagnostics};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+pub const OLLAMA_BACKEND_ID: &str = "ollama";
+const OLLAMA_DEFAULT_URL: &str = "http://localhost:11434";
+
+/// Agents whose backend can be chosen in settings
+pub const AGENT_TYPES: &[&str] = &["enteract", "vision", "coding", "research", "conversational_ai", "mcp", "rag", "summarizer"];
+
+lazy_static::lazy_static! {
+    static ref BACKEND_SETTINGS: RwLock<LlmBackendSettings> = RwLock::new(load_settings());
+    // Clients that trust a backend's own certificate, keyed by certificate path
+    static ref TLS_CLIENTS: Mutex<HashMap<String, reqwest::Client>> = Mutex::new(HashMap::new());
+}
+
+/// One piece of generated text, normalized across backends
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerationChunk {
+    pub text: String,
+    /// Tool calls the model asked for; these arrive complete, never split across chunks
+    pub tool_calls: Vec<ToolCall>,
+    pub done: bool,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// Turns a backend's streamed response bytes into chunks
+pub trait ChunkDecoder: Send + Sync {
+    fn push(&mut self, bytes: &[u8]) -> Vec<GenerationChunk>;
+    /// Flush whatever is left once the stream has ended
+    fn finish(&mut self) -> Vec<GenerationChunk>;
+    fn diagnostics(&self) -> &StreamDiagnostics;
+}
+
+/// Sends generation requests to a model server
+#[async_trait]
+pub trait LLMBackend: Send + Sync {
+    /// Backend id from settings
+    fn name(&self) -> &str;
+    fn kind(&self) -> BackendKind;
+    /// Post a generation request; `request.stream` decides whether the reply is streamed
+    async fn send(&self, client: &reqwest::Client, request: &ChatRequest) -> Result<reqwest::Response, String>;
+    /// Decoder for a streamed reply from `send`
+    fn decoder(&self) -> Box<dyn ChunkDecoder>;
+    /// Read a non-streamed reply from `send`
+    async fn read_response(&self, response: reqwest::Response) -> Result<GenerationChunk, String>;
+    /// Free the model's memory now instead of when the server decides to; a no-op by default
+    async fn unload(&self, _client: &reqwest::Client, _model: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum BackendKind {
+    #[default]
+    Ollama,
+    OpenAiCompatible,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackendConfig {
+    pub id: String,
+    pub name: String,
+    pub kind: BackendKind,
+    /// Server root; for OpenAI-compatible servers this includes the `/v1` prefix
+    pub base_url: String,
+    /// Never returned to the frontend; `None` on save keeps the stored key
+    pub api_key: Option<String>,
+    #[serde(skip_deserializing)]
+    pub api_key_set: bool,
+    /// Used by agents assigned to this backend that don't name a model
+    pub default_model: Option<String>,
+    /// PEM certificate to trust, for a server with a self-signed certificate
+    pub ca_certificate_path: Option<String>,
+}
+
+impl BackendConfig {
+    fn local_ollama() -> Self {
+        Self {
+            id: OLLAMA_BACKEND_ID.to_string(),
+            name: "Ollama".to_string(),
+            kind: BackendKind::Ollama,
+            // OLLAMA_BASE_URL only sets the starting point; set_llm_endpoint overrides it
+            base_url: std::env::var("OLLAMA_BASE_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .unwrap_or_else(|| OLLAMA_DEFAULT_URL.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn redacted(&self) -> Self {
+        Self {
+            api_key: None,
+            api_key_set: self.api_key.as_deref().map_or(false, |key| !key.is_empty()),
+            ..self.clone()
+        }
+    }
+
+    fn url(&self) -> String {
+        self.base_url.trim_end_matches('/').to_string()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Backend id is required".to_string());
+        }
+        let url = reqwest::Url::parse(self.base_url.trim())
+            .map_err(|e| format!("Backend '{}' URL is invalid: {}", self.id, e))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(format!("Backend '{}' URL must start with http:// or https://", self.id));
+        }
+        // Compare the parsed host so "http://localhost.example.com" doesn't pass as local
+        let local = url.scheme() == "http"
+            && matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+        if self.api_key.is_some() && url.scheme() != "https" && !local {
+            return Err(format!("Backend '{}' has an API key, so its URL must use https (or http to localhost)", self.id));
+        }
+        if let Some(path) = &self.ca_certificate_path {
+            if url.scheme() != "https" {
+                return Err(format!("Backend '{}' has a certificate, so its URL must use https", self.id));
+            }
+            tls_client(path).map_err(|e| format!("Backend '{}': {}", self.id, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn endpoint(&self) -> Endpoint {
+        let client = self.ca_certificate_path.as_deref().and_then(|path| {
+            tls_client(path)
+                .map_err(|e| eprintln!("⚠️ Using system certificates for backend '{}': {}", self.id, e))
+                .ok()
+        });
+        Endpoint {
+            base_url: self.url(),
+            api_key: self.api_key.clone().filter(|key| !key.is_empty()),
+            client,
+        }
+    }
+
+    fn backend(&self) -> Box<dyn LLMBackend> {
+        let id = self.id.clone();
+        let endpoint = self.endpoint();
+        match self.kind {
+            BackendKind::Ollama => Box::new(OllamaBackend { id, endpoint }),
+            BackendKind::OpenAiCompatible => Box::new(OpenAiCompatibleBackend { id, endpoint }),
+        }
+    }
+}
+
+fn tls_client(certificate_path: &str) -> Result<reqwest::Client, String> {
+    let mut clients = TLS_CLIENTS.lock().map_err(|_| "TLS client cache unavailable".to_string())?;
+    if let Some(client) = clients.get(certificate_path) {
+        return Ok(client.clone());
+    }
+    let pem = std::fs::read(certificate_path)
+        .map_err(|e| format!("Failed to read certificate {}: {}", certificate_path, e))?;
+    let certificate = reqwest::Certificate::from_pem(&pem)
+        .map_err(|e| format!("Invalid certificate {}: {}", certificate_path, e))?;
+    let client = reqwest::Client::builder()
+        .add_root_certificate(certificate)
+        .pool_idle_timeout(Duration::from_secs(60))
+        .tcp_keepalive(Some(Duration::from_secs(60)))
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    clients.insert(certificate_path.to_string(), client.clone());
+    Ok(client)
+}
+
+/// Where a backend lives and how to authenticate with it
+#[derive(Clone)]
+pub struct Endpoint {
+    base_url: String,
+    api_key: Option<String>,
+    /// Set when the backend has its own certificate; otherwise the caller's shared client is used
+    client: Option<reqwest::Client>,
+}
+
+impl Endpoint {
+    /// Request to `path` under the base URL, sent with the bearer token if there is one
+    pub fn request(&self, shared: &reqwest::Client, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let builder = self
+            .client
+            .as_ref()
+            .unwrap_or(shared)
+            .request(method, format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(api_key) => builder.bearer_auth(api_key),
+            None => builder,
+        }
+    }
+
+    /// Backends on this machine or the local network stay usable in offline mode
+    fn require_reachable(&self) -> Result<(), String> {
+        if crate::offline_mode::is_local_url(&self.base_url) {
+            return Ok(());
+        }
+        crate::offline_mode::require_online(crate::offline_mode::RemoteFeature::RemoteLlm)
+    }
+}
+
+/// Backend (and optionally model) an agent runs on
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentBackend {
+    pub backend: String,
+    /// Overrides the agent's built-in Ollama model
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LlmBackendSettings {
+    pub backends: Vec<BackendConfig>,
+    /// Keyed by agent type; agents not listed use Ollama with their built-in model
+    pub agents: HashMap<String, AgentBackend>,
+}
+
+impl Default for LlmBackendSettings {
+    fn default() -> Self {
+        Self {
+            backends: vec![BackendConfig::local_ollama()],
+            agents: HashMap::new(),
+        }
+    }
+}
+
+impl LlmBackendSettings {
+    fn find(&self, id: &str) -> Option<&BackendConfig> {
+        self.backends.iter().find(|backend| backend.id == id)
+    }
+
+    fn ollama(&self) -> BackendConfig {
+        self.find(OLLAMA_BACKEND_ID).cloned().unwrap_or_else(BackendConfig::local_ollama)
+    }
+
+    fn redacted(&self) -> Self {
+        Self {
+            backends: self.backends.iter().map(BackendConfig::redacted).collect(),
+            agents: self.agents.clone(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for backend in &self.backends {
+            backend.validate()?;
+            if !seen.insert(backend.id.as_str()) {
+                return Err(format!("Duplicate backend id: {}", backend.id));
+            }
+        }
+        if self.find(OLLAMA_BACKEND_ID).map_or(true, |backend| backend.kind != BackendKind::Ollama) {
+            return Err("The built-in Ollama backend can't be removed".to_string());
+        }
+        for (agent, selection) in &self.agents {
+            if !AGENT_TYPES.contains(&agent.as_str()) {
+                return Err(format!("Unknown agent: {}", agent));
+            }
+            let backend = self
+                .find(&selection.backend)
+                .ok_or_else(|| format!("Agent '{}' uses unknown backend '{}'", agent, selection.backend))?;
+            // Built-in model names are Ollama tags, which other servers won't know
+            if backend.kind == BackendKind::OpenAiCompatible && selection.model.is_none() && backend.default_model.is_none() {
+                return Err(format!("Agent '{}' needs a model for backend '{}'", agent, backend.id));
+            }
+        }
+        Ok(())
+    }
+}
+
+const SETTINGS_FILE: &str = "llm_backends.json";
+
+fn load_settings() -> LlmBackendSettings {
+    let mut settings: LlmBackendSettings = load_json_settings(SETTINGS_FILE);
+    if settings.find(OLLAMA_BACKEND_ID).is_none() {
+        settings.backends.insert(0, BackendConfig::local_ollama());
+    }
+    settings
+}
+
+/// The Ollama server, for model management
+pub fn ollama_endpoint() -> Endpoint {
+    read_settings(&BACKEND_SETTINGS).ollama().endpoint()
+}
+
+/// The Ollama backend, for requests that name an Ollama model directly
+pub fn ollama_backend() -> Box<dyn LLMBackend> {
+    read_settings(&BACKEND_SETTINGS).ollama().backend()
+}
+
+/// Backend and model for an agent; `default_model` is the agent's built-in Ollama model
+pub fn resolve(agent_type: &str, default_model: String) -> (Box<dyn LLMBackend>, String) {
+    let settings = read_settings(&BACKEND_SETTINGS);
+    let selection = settings.agents.get(agent_type);
+    let config = selection
+        .and_then(|selection| settings.find(&selection.backend).cloned())
+        .unwrap_or_else(|| settings.ollama());
+    let model = selection
+        .and_then(|selection| selection.model.clone())
+        .or_else(|| config.default_model.clone().filter(|_| config.kind == BackendKind::OpenAiCompatible))
+        .unwrap_or(default_model);
+    (config.backend(), model)
+}
+
+async fn error_text(response: reqwest::Response) -> String {
+    response.text().await.unwrap_or_else(|_| "Unknown error".to_string())
+}
+
+pub struct OllamaBackend {
+    id: String,
+    endpoint: Endpoint,
+}
+
+fn ollama_chunk(response: ChatResponse) -> GenerationChunk {
+    let message = response.message.unwrap_or_else(|| ChatMessage::new("assistant", String::new()));
+    GenerationChunk {
+        text: message.content,
+        tool_calls: message.tool_calls,
+        done: response.done,
+        prompt_tokens: response.prompt_eval_count.unwrap_or(0) as u64,
+        completion_tokens: response.eval_count.unwrap_or(0) as u64,
+    }
+}
+
+struct OllamaDecoder(NdjsonStreamParser<ChatResponse>);
+
+impl ChunkDecoder for OllamaDecoder {
+    fn push(&mut self, bytes: &[u8]) -> Vec<GenerationChunk> {
+        self.0.push(bytes).into_iter().map(ollama_chunk).collect()
+    }
+
+    fn finish(&mut self) -> Vec<GenerationChunk> {
+        self.0.finish().into_iter().map(ollama_chunk).collect()
+    }
+
+    fn diagnostics(&self) -> &StreamDiagnostics {
+        self.0.diagnostics()
+    }
+}
+
+#[async_trait]
+impl LLMBackend for OllamaBackend {
+    fn name(&self) -> &str { &self.id }
+    fn kind(&self) -> BackendKind { BackendKind::Ollama }
+
+    async fn send(&self, client: &reqwest::Client, request: &ChatRequest) -> Result<reqwest::Response, String> {
+        self.endpoint.require_reachable()?;
+        self.endpoint
+            .request(client, reqwest::Method::POST, "/api/chat")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama: {}", e))
+    }
+
+    fn decoder(&self) -> Box<dyn ChunkDecoder> {
+        Box::new(OllamaDecoder(NdjsonStreamParser::new()))
+    }
+
+    async fn read_response(&self, response: reqwest::Response) -> Result<GenerationChunk, String> {
+        if !response.status().is_success() {
+            return Err(format!("Generation failed: {}", error_text(response).await));
+        }
+        response
+            .json::<ChatResponse>()
+            .await
+            .map(ollama_chunk)
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    }
+
+    async fn unload(&self, client: &reqwest::Client, model: &str) -> Result<(), String> {
+        // A request without a prompt and a zero keep-alive unloads the model
+        let response = self
+            .endpoint
+            .request(client, reqwest::Method::POST, "/api/generate")
+            .json(&serde_json::json!({ "model": model, "keep_alive": 0 }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_text(response).await)
+        }
+    }
+}
+
+pub struct OpenAiCompatibleBackend {
+    id: String,
+    endpoint: Endpoint,
+}
+
+// Ollama option names that have an OpenAI equivalent; GPU and thread hints are dropped
+const OPTION_MAP: &[(&str, &str)] = &[
+    ("temperature", "temperature"),
+    ("top_p", "top_p"),
+    ("num_predict", "max_tokens"),
+    ("seed", "seed"),
+];
+
+// Ollama's message shape in OpenAI's: images become content parts and tool call arguments a JSON string
+fn openai_message(message: &ChatMessage) -> Value {
+    let content = match &message.images {
+        Some(images) if !images.is_empty() => {
+            let mut parts = vec![serde_json::json!({ "type": "text", "text": message.content })];
+            parts.extend(images.iter().map(|image| serde_json::json!({
+                "type": "image_url",
+                "image_url": { "url": format!("data:image/png;base64,{}", image) },
+            })));
+            Value::Array(parts)
+        }
+        _ => Value::String(message.content.clone()),
+    };
+    let mut body = serde_json::json!({ "role": message.role, "content": content });
+    if !message.tool_calls.is_empty() {
+        body["tool_calls"] = message.tool_calls.iter().map(|call| serde_json::json!({
+            "id": call.id,
+            "type": "function",
+            "function": { "name": call.function.name, "arguments": call.function.arguments.to_string() },
+        })).collect();
+    }
+    if let Some(tool_call_id) = &message.tool_call_id {
+        body["tool_call_id"] = Value::String(tool_call_id.clone());
+    }
+    body
+}
+
+fn chat_request(request: &ChatRequest) -> Value {
+    let messages: Vec<Value> = request.messages.iter().map(openai_message).collect();
+    let stream = request.stream.unwrap_or(false);
+    let mut body = serde_json::json!({ "model": request.model, "messages": messages, "stream": stream });
+    if stream {
+        body["stream_options"] = serde_json::json!({ "include_usage": true });
+    }
+    if let Some(tools) = &request.tools {
+        body["tools"] = Value::Array(tools.clone());
+    }
+    match &request.format {
+        Some(Value::String(format)) if format == "json" => {
+            body["response_format"] = serde_json::json!({ "type": "json_object" });
+        }
+        Some(schema @ Value::Object(_)) => {
+            body["response_format"] = serde_json::json!({
+                "type": "json_schema",
+                "json_schema": { "name": "response", "schema": schema },
+            });
+        }
+        _ => {}
+    }
+    if let Some(options) = &request.options {
+        for (from, to) in OPTION_MAP {
+            if let Some(value) = options.get(*from) {
+                body[*to] = value.clone();
+            }
+        }
+    }
+    body
+}
+
+// OpenAI sends arguments as a JSON string; anything unparseable is passed through as a string
+fn parse_arguments(arguments: &str) -> Value {
+    if arguments.trim().is_empty() {
+        return serde_json::json!({});
+    }
+    serde_json::from_str(arguments).unwrap_or_else(|_| Value::String(arguments.to_string()))
+}
+
+fn openai_tool_calls(calls: &Value) -> Vec<ToolCall> {
+    calls
+        .as_array()
+        .map(|calls| calls.iter().map(|call| ToolCall {
+            id: call["id"].as_str().map(str::to_string),
+            function: ToolCallFunction {
+                name: call["function"]["name"].as_str().unwrap_or_default().to_string(),
+                arguments: parse_arguments(call["function"]["arguments"].as_str().unwrap_or_default()),
+            },
+        }).collect())
+        .unwrap_or_default()
+}
+
+fn usage(body: &Value) -> (u64, u64) {
+    (
+        body["usage"]["prompt_tokens"].as_u64().unwrap_or(0),
+        body["usage"]["completion_tokens"].as_u64().unwrap_or(0),
+    )
+}
+
+/// Server-sent events from `/chat/completions` with `stream: true`. Token usage arrives in a
+/// chunk of its own near the end, so it's carried over to the final `[DONE]` chunk. Tool calls
+/// stream as fragments keyed by index and are assembled onto that chunk too.
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+    usage: (u64, u64),
+    // (id, name, argument text) per tool call index
+    tool_calls: Vec<(Option<String>, String, String)>,
+    finished: bool,
+    done: bool,
+    diagnostics: StreamDiagnostics,
+}
+
+impl SseDecoder {
+    fn process_line(&mut self, line: &str, chunks: &mut Vec<GenerationChunk>) {
+        let Some(data) = line.trim().strip_prefix("data:") else { return };
+        let data = data.trim();
+        if self.done || data.is_empty() {
+            return;
+        }
+        if data == "[DONE]" {
+            chunks.push(self.done_chunk());
+            return;
+        }
+
+        let body: Value = match serde_json::from_str(data) {
+            Ok(body) => body,
+            Err(_) => {
+                self.diagnostics.unrecoverable_lines += 1;
+                return;
+            }
+        };
+        self.diagnostics.parsed_lines += 1;
+        if body["usage"].is_object() {
+            self.usage = usage(&body);
+        }
+        let choice = &body["choices"][0];
+        if !choice["finish_reason"].is_null() {
+            self.finished = true;
+        }
+        if let Some(text) = choice["delta"]["content"].as_str().filter(|text| !text.is_empty()) {
+            chunks.push(GenerationChunk { text: text.to_string(), ..Default::default() });
+        }
+        for fragment in choice["delta"]["tool_calls"].as_array().into_iter().flatten() {
+            let index = fragment["index"].as_u64().unwrap_or(0) as usize;
+            if self.tool_calls.len() <= index {
+                self.tool_calls.resize(index + 1, (None, String::new(), String::new()));
+            }
+            let call = &mut self.tool_calls[index];
+            if let Some(id) = fragment["id"].as_str() {
+                call.0 = Some(id.to_string());
+            }
+            call.1.push_str(fragment["function"]["name"].as_str().unwrap_or_default());
+            call.2.push_str(fragment["function"]["arguments"].as_str().unwrap_or_default());
+        }
+    }
+
+    fn done_chunk(&mut self) -> GenerationChunk {
+        self.done = true;
+        let tool_calls = std::mem::take(&mut self.tool_calls)
+            .into_iter()
+            .filter(|(_, name, _)| !name.is_empty())
+            .map(|(id, name, arguments)| ToolCall {
+                id,
+                function: ToolCallFunction { name, arguments: parse_arguments(&arguments) },
+            })
+            .collect();
+        GenerationChunk {
+            text: String::new(),
+            tool_calls,
+            done: true,
+            prompt_tokens: self.usage.0,
+            completion_tokens: self.usage.1,
+        }
+    }
+}
+
+impl ChunkDecoder for SseDecoder {
+    fn push(&mut self, bytes: &[u8]) -> Vec<GenerationChunk> {
+        self.buffer.extend_from_slice(bytes);
+        let mut chunks = Vec::new();
+        while let Some(newline_pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line = self.buffer.drain(..=newline_pos).collect::<Vec<u8>>();
+            self.process_line(&String::from_utf8_lossy(&line), &mut chunks);
+        }
+        chunks
+    }
+
+    fn finish(&mut self) -> Vec<GenerationChunk> {
+        let mut chunks = Vec::new();
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).to_string();
+            self.process_line(&line, &mut chunks);
+        }
+        // Some servers close the stream without sending [DONE]
+        if self.finished && !self.done {
+            chunks.push(self.done_chunk());
+        }
+        chunks
+    }
+
+    fn diagnostics(&self) -> &StreamDiagnostics {
+        &self.diagnostics
+    }
+}
+
+#[async_trait]
+impl LLMBackend for OpenAiCompatibleBackend {
+    fn name(&self) -> &str { &self.id }
+    fn kind(&self) -> BackendKind { BackendKind::OpenAiCompatible }
+
+    async fn send(&self, client: &reqwest::Client, request: &ChatRequest) -> Result<reqwest::Response, String> {
+        self.endpoint.require_reachable()?;
+        self.endpoint
+            .request(client, reqwest::Method::POST, "/chat/completions")
+            .json(&chat_request(request))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to backend '{}': {}", self.id, e))
+    }
+
+    fn decoder(&self) -> Box<dyn ChunkDecoder> {
+        Box::new(SseDecoder::default())
+    }
+
+    async fn read_response(&self, response: reqwest::Response) -> Result<GenerationChunk, String> {
+        if !response.status().is_success() {
+            return Err(format!("Generation failed: {}", error_text(response).await));
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        let (prompt_tokens, completion_tokens) = usage(&body);
+        Ok(GenerationChunk {
+            text: body["choices"][0]["message"]["content"].as_str().unwrap_or_default().to_string(),
+            tool_calls: openai_tool_calls(&body["choices"][0]["message"]["tool_calls"]),
+            done: true,
+            prompt_tokens,
+            completion_tokens,
+        })
+    }
+}
+
+#[crate::command]
+pub fn get_llm_backends() -> Result<LlmBackendSettings, String> {
+    Ok(read_settings(&BACKEND_SETTINGS).redacted())
+}
+
+/// Save backends and per-agent selections. API keys left out of the payload keep their stored value.
+#[crate::command]
+pub fn save_llm_backends(settings: LlmBackendSettings) -> Result<LlmBackendSettings, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+
+    let stored = read_settings(&BACKEND_SETTINGS);
+    let mut updated = settings;
+    for backend in updated.backends.iter_mut() {
+        backend.id = backend.id.trim().to_string();
+        backend.default_model = backend.default_model.take().filter(|model| !model.trim().is_empty());
+        backend.ca_certificate_path = backend.ca_certificate_path.take().filter(|path| !path.trim().is_empty());
+        // An omitted key keeps the stored one, an empty key clears it
+        backend.api_key = match backend.api_key.take() {
+            Some(key) => Some(key.trim().to_string()).filter(|key| !key.is_empty()),
+            None => stored.find(&backend.id).and_then(|stored| stored.api_key.clone()),
+        };
+    }
+    for selection in updated.agents.values_mut() {
+        selection.model = selection.model.take().filter(|model| !model.trim().is_empty());
+    }
+    store_settings(updated)?;
+    get_llm_backends()
+}
+
+fn store_settings(updated: LlmBackendSettings) -> Result<(), String> {
+    updated.validate()?;
+
+    save_json_settings(SETTINGS_FILE, "LLM backend settings", &updated)?;
+
+    *BACKEND_SETTINGS
+        .write()
+        .map_err(|_| "Failed to update LLM backend settings".to_string())? = updated;
+    Ok(())
+}
+
+/// Point the built-in Ollama backend at another server, e.g. Ollama on a home server behind
+/// TLS. `api_key` is sent as a bearer token; `None` keeps the stored one and an empty string
+/// clears it. `ca_certificate_path` trusts a self-signed certificate.
+#[crate::command]
+pub fn set_llm_endpoint(
+    base_url: String,
+    api_key: Option<String>,
+    ca_certificate_path: Option<String>,
+) -> Result<BackendConfig, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+
+    let mut updated = read_settings(&BACKEND_SETTINGS);
+    let ollama = updated
+        .backends
+        .iter_mut()
+        .find(|backend| backend.id == OLLAMA_BACKEND_ID)
+        .ok_or("The built-in Ollama backend is missing")?;
+    ollama.base_url = base_url.trim().to_string();
+    if let Some(key) = api_key {
+        ollama.api_key = Some(key.trim().to_string()).filter(|key| !key.is_empty());
+    }
+    ollama.ca_certificate_path = ca_certificate_path.filter(|path| !path.trim().is_empty());
+    store_settings(updated)?;
+
+    println!("🔗 Ollama endpoint set to {}", base_url.trim());
+    Ok(read_settings(&BACKEND_SETTINGS).ollama().redacted())
+}
+
+/// Model names a backend offers, for filling in per-agent model pickers
+#[crate::command]
+pub async fn list_backend_models(backend_id: String) -> Result<Vec<String>, String> {
+    let config = read_settings(&BACKEND_SETTINGS)
+        .find(&backend_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown backend: {}", backend_id))?;
+
+    let path = match config.kind {
+        BackendKind::Ollama => "/api/tags",
+        BackendKind::OpenAiCompatible => "/models",
+    };
+    let response = config
+        .endpoint()
+        .request(&reqwest::Client::new(), reqwest::Method::GET, path)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to backend '{}': {}", backend_id, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to list models: {}", error_text(response).await));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse models response: {}", e))?;
+
+    let (list, key) = match config.kind {
+        BackendKind::Ollama => (&body["models"], "name"),
+        BackendKind::OpenAiCompatible => (&body["data"], "id"),
+    };
+    Ok(list
+        .as_array()
+        .map(|models| models.iter().filter_map(|model| model[key].as_str().map(str::to_string)).collect())
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_decoder_streams_text_and_usage() {
+        let mut decoder = SseDecoder::default();
+        let mut chunks = decoder.push(b"data: {\"choices\":[{\"delta\":{\"role\":\"assistant\",\"content\":\"Hel\"}}]}\n\ndata: {\"choices\":[{\"delta\":{\"con");
+        chunks.extend(decoder.push(b"tent\":\"lo\"},\"finish_reason\":null}]}\n\n: keep-alive\n\n"));
+        chunks.extend(decoder.push(b"data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n"));
+        chunks.extend(decoder.push(b"data: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":2}}\n\ndata: [DONE]\n\n"));
+        chunks.extend(decoder.finish());
+
+        let text: String = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(text, "Hello");
+        let last = chunks.last().unwrap();
+        assert!(last.done);
+        assert_eq!((last.prompt_tokens, last.completion_tokens), (12, 2));
+        assert_eq!(chunks.iter().filter(|chunk| chunk.done).count(), 1);
+        assert_eq!(decoder.diagnostics().unrecoverable_lines, 0);
+    }
+
+    #[test]
+    fn test_sse_decoder_assembles_tool_calls() {
+        let mut decoder = SseDecoder::default();
+        let mut chunks = decoder.push(b"data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_a\",\"function\":{\"name\":\"click\",\"arguments\":\"{\\\"x\\\":\"}}]}}]}\n\n");
+        chunks.extend(decoder.push(b"data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"10}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n"));
+        chunks.extend(decoder.finish());
+
+        let last = chunks.last().unwrap();
+        assert!(last.done);
+        assert_eq!(last.tool_calls.len(), 1);
+        assert_eq!(last.tool_calls[0].id.as_deref(), Some("call_a"));
+        assert_eq!(last.tool_calls[0].function.name, "click");
+        assert_eq!(last.tool_calls[0].function.arguments, serde_json::json!({ "x": 10 }));
+    }
+
+    #[test]
+    fn test_remote_endpoint_requires_tls_for_credentials() {
+        let mut config = BackendConfig {
+            base_url: "http://192.168.1.20:11434".to_string(),
+            ..BackendConfig::local_ollama()
+        };
+        assert!(config.validate().is_ok());
+
+        config.api_key = Some("secret".to_string());
+        assert!(config.validate().is_err());
+        config.base_url = "https://ollama.home.example:443/".to_string();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.url(), "https://ollama.home.example:443");
+
+        config.base_url = "http://192.168.1.20:11434".to_string();
+        config.api_key = None;
+        config.ca_certificate_path = Some("/nonexistent/home-ca.pem".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_only_loopback_hosts_count_as_local() {
+        let mut config = BackendConfig {
+            api_key: Some("secret".to_string()),
+            ..BackendConfig::local_ollama()
+        };
+        for url in ["http://localhost:11434", "http://127.0.0.1:11434", "http://[::1]:11434"] {
+            config.base_url = url.to_string();
+            assert!(config.validate().is_ok(), "{}", url);
+        }
+        for url in ["http://localhost.evil.com", "http://127.0.0.1.nip.io", "http://localhost@evil.com", "localhost:11434"] {
+            config.base_url = url.to_string();
+            assert!(config.validate().is_err(), "{}", url);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// src-tauri/src/ollama/compare.rs
+// Model comparison - the same prompt sent to 2-4 local models at once, each streamed on its own
+// channel, with latency and token counts recorded so models can be A/B tested side by side.
+//
+// Model `i` of comparison `id` streams to `ollama-stream-<id>-<i>` with the usual chunk events,
+// so the chat view's stream handling works for each column. Every request still takes a
+// `REQUEST_SEMAPHORE` permit, so a comparison waits its turn like any other generation.
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::time::timeout;
+
+use super::backend::{self, GenerationChunk};
+use super::{resolve_seed, with_seed, ChatMessage, ChatRequest, HTTP_CLIENT, REQUEST_SEMAPHORE};
+use crate::data::conversation::DIRECT_AGENT;
+
+const MIN_MODELS: usize = 2;
+const MAX_MODELS: usize = 4;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_RUN_DURATION: Duration = Duration::from_secs(300);
+const MAX_STORED: usize = 20;
+
+lazy_static::lazy_static! {
+    // Recent comparisons, newest last
+    static ref COMPARISONS: Mutex<VecDeque<ModelComparison>> = Mutex::new(VecDeque::new());
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRunStats {
+    pub model: String,
+    /// Session id the model streamed under (`ollama-stream-<channel>`)
+    pub channel: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Request sent to first non-empty chunk, including time spent waiting for a permit
+    pub time_to_first_token_ms: Option<u64>,
+    pub total_ms: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Completion tokens over the time after the first token
+    pub tokens_per_second: Option<f64>,
+    pub response: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelComparison {
+    pub id: String,
+    pub prompt: String,
+    pub created_at: String,
+    pub seed: i64,
+    pub results: Vec<ModelRunStats>,
+}
+
+fn validate_models(models: Vec<String>) -> Result<Vec<String>, String> {
+    let mut unique: Vec<String> = Vec::new();
+    for model in models.into_iter().map(|model| model.trim().to_string()) {
+        if model.is_empty() {
+            return Err("Model names can't be empty".to_string());
+        }
+        if !unique.contains(&model) {
+            unique.push(model);
+        }
+    }
+    if !(MIN_MODELS..=MAX_MODELS).contains(&unique.len()) {
+        return Err(format!("Compare between {} and {} different models", MIN_MODELS, MAX_MODELS));
+    }
+    Ok(unique)
+}
+
+fn tokens_per_second(completion_tokens: u64, generating: Duration) -> Option<f64> {
+    let seconds = generating.as_secs_f64();
+    (completion_tokens > 0 && seconds > 0.0).then(|| completion_tokens as f64 / seconds)
+}
+
+#[derive(Default)]
+struct RunProgress {
+    text: String,
+    first_token: Option<Duration>,
+    usage: Option<GenerationChunk>,
+}
+
+async fn stream_model(app_handle: &AppHandle, channel: &str, request: &ChatRequest, started: Instant, progress: &mut RunProgress) -> Result<(), String> {
+    let _permit = REQUEST_SEMAPHORE.acquire().await.map_err(|e| format!("Failed to acquire semaphore: {}", e))?;
+    let backend = backend::ollama_backend();
+    let response = timeout(CONNECT_TIMEOUT, backend.send(&HTTP_CLIENT, request))
+        .await
+        .map_err(|_| "Request timeout".to_string())??;
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Generation failed: {}", error_text));
+    }
+
+    let event = format!("ollama-stream-{}", channel);
+    let mut stream = response.bytes_stream();
+    let mut decoder = backend.decoder();
+    loop {
+        let (chunks, ended) = match timeout(CHUNK_TIMEOUT, stream.next()).await {
+            Ok(Some(Ok(bytes))) => (decoder.push(&bytes), false),
+            Ok(Some(Err(e))) => return Err(format!("Stream error: {}", e)),
+            Ok(None) => (decoder.finish(), true),
+            Err(_) => return Err(format!("Chunk read timeout after {:?}", CHUNK_TIMEOUT)),
+        };
+        for chunk in chunks {
+            if !chunk.text.is_empty() {
+                progress.first_token.get_or_insert_with(|| started.elapsed());
+                progress.text.push_str(&chunk.text);
+            }
+            if chunk.text.is_empty() && !chunk.done {
+                continue;
+            }
+            let _ = app_handle.emit(&event, serde_json::json!({
+                "type": "chunk",
+                "text": chunk.text,
+                "done": chunk.done
+            }));
+            if chunk.done {
+                super::record_usage(app_handle, "", DIRECT_AGENT, &request.model, &chunk);
+                progress.usage = Some(chunk);
+                super::emit_complete(app_handle, channel, decoder.diagnostics()).await;
+                return Ok(());
+            }
+        }
+        if ended {
+            super::emit_complete(app_handle, channel, decoder.diagnostics()).await;
+            return Ok(());
+        }
+    }
+}
+
+async fn run_model(
+    app_handle: &AppHandle,
+    operation: &crate::cancellation::Operation,
+    channel: String,
+    request: ChatRequest,
+) -> ModelRunStats {
+    let _ = app_handle.emit(&format!("ollama-stream-{}", channel), serde_json::json!({
+        "type": "start",
+        "model": request.model
+    }));
+    let started = Instant::now();
+    let mut progress = RunProgress::default();
+    let outcome = operation.run(async {
+        timeout(MAX_RUN_DURATION, stream_model(app_handle, &channel, &request, started, &mut progress))
+            .await
+            .map_err(|_| format!("Stopped after {}s", MAX_RUN_DURATION.as_secs()))?
+    }).await;
+    let total = started.elapsed();
+
+    if let Err(e) = &outcome {
+        let event = if e == crate::cancellation::CANCELLED {
+            serde_json::json!({ "type": "cancelled", "message": "Comparison cancelled by user" })
+        } else {
+            serde_json::json!({ "type": "error", "error": e })
+        };
+        let _ = app_handle.emit(&format!("ollama-stream-{}", channel), event);
+    }
+    let (prompt_tokens, completion_tokens) = progress
+        .usage
+        .as_ref()
+        .map(|usage| (usage.prompt_tokens, usage.completion_tokens))
+        .unwrap_or((0, 0));
+    ModelRunStats {
+        model: request.model,
+        channel,
+        success: outcome.is_ok(),
+        error: outcome.err(),
+        time_to_first_token_ms: progress.first_token.map(|first| first.as_millis() as u64),
+        total_ms: total.as_millis() as u64,
+        prompt_tokens,
+        completion_tokens,
+        tokens_per_second: progress.first_token.and_then(|first| tokens_per_second(completion_tokens, total - first)),
+        response: progress.text,
+    }
+}
+
+/// Send `prompt` to each of `models` (2-4 Ollama models) concurrently. Returns once all of them
+/// finish; `comparison_id` names the stream channels and cancels the whole comparison.
+#[crate::command]
+pub async fn compare_models(
+    app_handle: AppHandle,
+    models: Vec<String>,
+    prompt: String,
+    system_prompt: Option<String>,
+    comparison_id: Option<String>,
+    seed: Option<i64>,
+) -> Result<ModelComparison, String> {
+    let models = validate_models(models)?;
+    if prompt.trim().is_empty() {
+        return Err("Nothing to compare: the prompt is empty".to_string());
+    }
+    let id = comparison_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let operation = crate::cancellation::start(&id, crate::cancellation::OperationKind::AiResponse, "compare");
+    println!("⚖️ Comparing {} models for {}: {}", models.len(), id, models.join(", "));
+
+    // The same sampling options for every model, seed included, so the runs are comparable;
+    // only the GPU layer split is per model
+    let gpu_layers = futures_util::future::join_all(models.iter().map(|model| super::gpu_layers::gpu_layers_for(model))).await;
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = system_prompt.filter(|prompt| !prompt.trim().is_empty()) {
+        messages.push(ChatMessage::new("system", system_prompt));
+    }
+    messages.push(ChatMessage::new("user", prompt.clone()));
+    let seed = resolve_seed(seed);
+
+    let runs = models.into_iter().zip(gpu_layers).enumerate().map(|(index, (model, gpu_layers))| {
+        let options = (gpu_layers > 0).then(|| serde_json::json!({ "num_gpu": gpu_layers, "num_thread": 4 }));
+        let request = ChatRequest {
+            model,
+            messages: messages.clone(),
+            stream: Some(true),
+            tools: None,
+            format: None,
+            options: with_seed(options, seed),
+        };
+        run_model(&app_handle, &operation, format!("{}-{}", id, index), request)
+    });
+    let results = futures_util::future::join_all(runs).await;
+
+    let comparison = ModelComparison {
+        id,
+        prompt,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        seed,
+        results,
+    };
+    if let Ok(mut stored) = COMPARISONS.lock() {
+        if stored.len() >= MAX_STORED {
+            stored.pop_front();
+        }
+        stored.push_back(comparison.clone());
+    }
+    Ok(comparison)
+}
+
+/// Recent comparisons, newest first
+#[crate::command]
+pub fn get_model_comparisons() -> Result<Vec<ModelComparison>, String> {
+    COMPARISONS
+        .lock()
+        .map(|stored| stored.iter().rev().cloned().collect())
+        .map_err(|_| "Failed to read model comparisons".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validates_model_list_and_rates() {
+        let models = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            validate_models(models(&["llama3.2", " qwen2.5 ", "llama3.2"])).unwrap(),
+            models(&["llama3.2", "qwen2.5"])
+        );
+        // Duplicates don't count towards the minimum, and five is too many
+        assert!(validate_models(models(&["llama3.2", "llama3.2"])).is_err());
+        assert!(validate_models(models(&["a", "b", "c", "d", "e"])).is_err());
+        assert!(validate_models(models(&["a", ""])).is_err());
+
+        assert_eq!(tokens_per_second(50, Duration::from_secs(2)), Some(25.0));
+        assert_eq!(tokens_per_second(0, Duration::from_secs(2)), None);
+        assert_eq!(tokens_per_second(50, Duration::ZERO), None);
+    }
+}
@@ -0,0 +1,268 @@
+// src-tauri/src/ollama/context.rs
+// Rolling context compression - once a chat's history passes the token budget, the older turns
+// are summarized by the small summarizer agent and replaced with that summary.
+//
+// Summaries are cached by the turns they cover, so a chat that keeps growing only summarizes the
+// turns that newly fell out of the window, on top of the previous summary.
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::time::timeout;
+
+use super::{agents, ChatContextMessage, ChatMessage, ChatRequest, HTTP_CLIENT};
+
+/// Estimated tokens of history sent verbatim before older turns are summarized
+const CONTEXT_TOKEN_BUDGET: usize = 3072;
+// The newest turns always go through as they are, however long
+const KEEP_RECENT_MESSAGES: usize = 4;
+// Role and formatting tokens each chat turn costs on top of its text
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+const SUMMARY_TIMEOUT: Duration = Duration::from_secs(45);
+const MAX_CACHED: usize = 64;
+
+lazy_static::lazy_static! {
+    // Rolling summaries keyed by a fingerprint of the turns they cover
+    static ref SUMMARY_CACHE: Mutex<HashMap<u64, String>> = Mutex::new(HashMap::new());
+    static ref CONTEXT_STATS: Mutex<HashMap<String, ContextStats>> = Mutex::new(HashMap::new());
+}
+
+/// What the last request of a session sent as history
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextStats {
+    pub session_id: String,
+    pub budget_tokens: usize,
+    pub original_messages: usize,
+    pub original_tokens: usize,
+    /// Older turns folded into the summary
+    pub summarized_messages: usize,
+    pub summary: Option<String>,
+    pub compressed_tokens: usize,
+    /// The history as sent, the summary first
+    pub messages: Vec<ChatContextMessage>,
+}
+
+/// Rough token count - about four characters per token for English text
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+fn message_tokens(message: &ChatContextMessage) -> usize {
+    estimate_tokens(&message.content) + MESSAGE_OVERHEAD_TOKENS
+}
+
+fn history_tokens(history: &[ChatContextMessage]) -> usize {
+    history.iter().map(message_tokens).sum()
+}
+
+/// Index of the first turn kept verbatim. Everything before it gets summarized; the kept turns
+/// get three quarters of the budget so the summary has room.
+fn split_point(history: &[ChatContextMessage], budget: usize) -> usize {
+    if history_tokens(history) <= budget {
+        return 0;
+    }
+    let window = budget * 3 / 4;
+    let mut kept_tokens = 0;
+    let mut split = history.len();
+    for (i, message) in history.iter().enumerate().rev() {
+        let tokens = message_tokens(message);
+        let kept = history.len() - i;
+        if kept > KEEP_RECENT_MESSAGES && kept_tokens + tokens > window {
+            break;
+        }
+        kept_tokens += tokens;
+        split = i;
+    }
+    split
+}
+
+// Fingerprints of every prefix of `turns`, so the longest already-summarized prefix can be found
+fn prefix_fingerprints(turns: &[ChatContextMessage]) -> Vec<u64> {
+    let mut hasher = DefaultHasher::new();
+    turns
+        .iter()
+        .map(|turn| {
+            turn.role.hash(&mut hasher);
+            turn.content.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+fn summary_message(summary: &str) -> ChatContextMessage {
+    ChatContextMessage {
+        role: "system".to_string(),
+        content: format!("Summary of the earlier conversation:\n{}", summary),
+    }
+}
+
+async fn summarize(app_handle: &AppHandle, previous: Option<&str>, turns: &[ChatContextMessage]) -> Result<String, String> {
+    let profile = agents::profile("summarizer");
+    let (backend, model) = super::fallback::resolve(app_handle, "summarizer", profile.model).await?;
+
+    let mut transcript = String::new();
+    if let Some(previous) = previous {
+        transcript.push_str(&format!("Summary so far:\n{}\n\n", previous));
+    }
+    transcript.push_str("Conversation to add:\n");
+    for turn in turns {
+        transcript.push_str(&format!("{}: {}\n", turn.role, turn.content.trim()));
+    }
+
+    let mut options = serde_json::json!({ "temperature": profile.temperature });
+    if let Some(num_predict) = profile.num_predict {
+        options["num_predict"] = serde_json::json!(num_predict);
+    }
+    let request = ChatRequest {
+        model,
+        messages: vec![
+            ChatMessage::new("system", profile.system_prompt),
+            ChatMessage::new("user", transcript),
+        ],
+        stream: Some(false),
+        tools: None,
+        format: None,
+        options: Some(options),
+    };
+
+    let generated = timeout(SUMMARY_TIMEOUT, async {
+        let response = backend.send(&HTTP_CLIENT, &request).await?;
+        backend.read_response(response).await
+    })
+    .await
+    .map_err(|_| "Summarizing the conversation timed out".to_string())??;
+    super::record_usage(app_handle, "", "summarizer", &request.model, &generated);
+
+    let summary = generated.text.trim().to_string();
+    if summary.is_empty() {
+        return Err("The summarizer returned nothing".to_string());
+    }
+    Ok(summary)
+}
+
+// Summary of `older`, reusing the cached summary of its longest already-covered prefix
+async fn rolling_summary(app_handle: &AppHandle, older: &[ChatContextMessage]) -> Result<String, String> {
+    let fingerprints = prefix_fingerprints(older);
+    let full = *fingerprints.last().ok_or("Nothing to summarize")?;
+    let cached = {
+        let cache = SUMMARY_CACHE.lock().map_err(|_| "Summary cache is unavailable".to_string())?;
+        if let Some(summary) = cache.get(&full) {
+            return Ok(summary.clone());
+        }
+        fingerprints
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, fingerprint)| cache.get(fingerprint).map(|summary| (i + 1, summary.clone())))
+    };
+
+    let summary = match cached {
+        Some((covered, previous)) => summarize(app_handle, Some(&previous), &older[covered..]).await?,
+        None => summarize(app_handle, None, older).await?,
+    };
+    if let Ok(mut cache) = SUMMARY_CACHE.lock() {
+        if cache.len() >= MAX_CACHED {
+            cache.clear();
+        }
+        cache.insert(full, summary.clone());
+    }
+    Ok(summary)
+}
+
+fn store_stats(stats: ContextStats) {
+    if let Ok(mut all) = CONTEXT_STATS.lock() {
+        if all.len() >= MAX_CACHED && !all.contains_key(&stats.session_id) {
+            all.clear();
+        }
+        all.insert(stats.session_id.clone(), stats);
+    }
+}
+
+/// History to send for `session_id`, compressed when it's over budget. If summarizing fails the
+/// older turns are dropped instead, so the request still fits.
+pub(super) async fn compress_context(
+    app_handle: &AppHandle,
+    session_id: &str,
+    context: Option<Vec<ChatContextMessage>>,
+) -> Option<Vec<ChatContextMessage>> {
+    let history = context?;
+    let original_messages = history.len();
+    let original_tokens = history_tokens(&history);
+    let split = split_point(&history, CONTEXT_TOKEN_BUDGET);
+
+    let (messages, summary) = if split == 0 {
+        (history, None)
+    } else {
+        let summary = match rolling_summary(app_handle, &history[..split]).await {
+            Ok(summary) => Some(summary),
+            Err(e) => {
+                eprintln!("⚠️ Context summary failed for session {}, dropping {} older turns: {}", session_id, split, e);
+                None
+            }
+        };
+        let mut messages: Vec<ChatContextMessage> = summary.as_deref().map(summary_message).into_iter().collect();
+        messages.extend(history.into_iter().skip(split));
+        (messages, summary)
+    };
+
+    if split > 0 {
+        println!("🗜️ Compressed context for session {}: {} turns summarized, ~{} -> ~{} tokens",
+                 session_id, split, original_tokens, history_tokens(&messages));
+    }
+    store_stats(ContextStats {
+        session_id: session_id.to_string(),
+        budget_tokens: CONTEXT_TOKEN_BUDGET,
+        original_messages,
+        original_tokens,
+        summarized_messages: split,
+        summary,
+        compressed_tokens: history_tokens(&messages),
+        messages: messages.clone(),
+    });
+    Some(messages)
+}
+
+/// Token estimates and the compressed history of a session's latest request
+#[crate::command]
+pub fn get_context_stats(session_id: String) -> Result<Option<ContextStats>, String> {
+    CONTEXT_STATS
+        .lock()
+        .map(|all| all.get(&session_id).cloned())
+        .map_err(|_| "Failed to read context stats".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(role: &str, chars: usize) -> ChatContextMessage {
+        ChatContextMessage { role: role.to_string(), content: "x".repeat(chars) }
+    }
+
+    #[test]
+    fn test_split_keeps_recent_turns_within_budget() {
+        assert_eq!(estimate_tokens("abcde"), 2);
+
+        // 20 turns of ~104 tokens each is ~2080 tokens: under a 3072 budget, nothing is summarized
+        let history: Vec<ChatContextMessage> = (0..20)
+            .map(|i| turn(if i % 2 == 0 { "user" } else { "assistant" }, 400))
+            .collect();
+        assert_eq!(split_point(&history, 3072), 0);
+
+        // With a 1000 token budget the newest turns fill 750 tokens: 7 turns of 104
+        assert_eq!(split_point(&history, 1000), 13);
+
+        // The newest turns are kept even when they alone are over the budget
+        let long = vec![turn("user", 8000), turn("assistant", 8000), turn("user", 8000), turn("assistant", 8000), turn("user", 8000)];
+        assert_eq!(split_point(&long, 1000), 1);
+
+        // Prefix fingerprints only depend on the turns up to each point
+        let a = prefix_fingerprints(&history[..5]);
+        let b = prefix_fingerprints(&history[..8]);
+        assert_eq!(a[..], b[..5]);
+    }
+}
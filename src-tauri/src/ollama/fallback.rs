@@ -0,0 +1,230 @@
+// src-tauri/src/ollama/fallback.rs
+// Model fallback - before an agent runs, its model is checked against what Ollama has installed,
+// and a missing model (or one that recently failed to load) is swapped for the first available
+// entry of the agent's fallback chain. Each substitution is sent to the frontend as a
+// `model-fallback` event so the user knows which model answered.
+//
+// Only Ollama models are checked; an OpenAI-compatible server is trusted to know its own models.
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use super::agents;
+use super::backend::{self, BackendKind, LLMBackend, AGENT_TYPES};
+use crate::accessibility::{announce, AnnouncementCategory, AnnouncementPriority};
+
+// Installed models are re-listed at most this often
+const INSTALLED_TTL: Duration = Duration::from_secs(30);
+// A model that failed to load is skipped for this long
+const FAILURE_COOLDOWN: Duration = Duration::from_secs(300);
+
+lazy_static::lazy_static! {
+    static ref INSTALLED: Mutex<Option<(Instant, Vec<String>)>> = Mutex::new(None);
+    static ref FAILED: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FallbackReason {
+    NotInstalled,
+    LoadFailed,
+}
+
+/// Payload of the `model-fallback` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelFallback {
+    pub agent: String,
+    pub requested: String,
+    pub substituted: String,
+    pub reason: FallbackReason,
+    pub message: String,
+}
+
+/// What an agent would run with right now
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentModelHealth {
+    pub agent: String,
+    pub backend: String,
+    pub requested: String,
+    pub installed: bool,
+    /// `None` when neither the model nor any fallback is available
+    pub effective: Option<String>,
+    pub fallback_models: Vec<String>,
+}
+
+/// The installed model `wanted` refers to: the same name, or any tag of it when `wanted` has no tag
+fn find_installed<'a>(wanted: &str, installed: &'a [String]) -> Option<&'a String> {
+    installed.iter().find(|name| *name == wanted).or_else(|| {
+        if wanted.contains(':') {
+            return None;
+        }
+        let tagged = format!("{}:", wanted);
+        installed.iter().find(|name| name.starts_with(&tagged))
+    })
+}
+
+/// First model of `chain` that is installed and not in `skip`
+fn pick<'a>(chain: &[String], installed: &'a [String], skip: impl Fn(&str) -> bool) -> Option<&'a String> {
+    chain
+        .iter()
+        .filter_map(|wanted| find_installed(wanted, installed))
+        .find(|name| !skip(name))
+}
+
+/// Whether an error reply means the model itself is unusable rather than the request
+pub(super) fn is_model_failure(status: reqwest::StatusCode, error_text: &str) -> bool {
+    let text = error_text.to_lowercase();
+    status == reqwest::StatusCode::NOT_FOUND
+        || text.contains("not found")
+        || text.contains("failed to load")
+        || text.contains("requires more system memory")
+        || text.contains("llama runner process has terminated")
+}
+
+async fn installed_models(refresh: bool) -> Result<Vec<String>, String> {
+    if !refresh {
+        if let Some((at, models)) = INSTALLED.lock().ok().and_then(|cached| cached.clone()) {
+            if at.elapsed() < INSTALLED_TTL {
+                return Ok(models);
+            }
+        }
+    }
+    let models: Vec<String> = super::get_ollama_models().await?.into_iter().map(|model| model.name).collect();
+    if let Ok(mut cached) = INSTALLED.lock() {
+        *cached = Some((Instant::now(), models.clone()));
+    }
+    Ok(models)
+}
+
+/// Drop the cached model list, e.g. after a pull or delete
+pub(super) fn forget_installed() {
+    if let Ok(mut cached) = INSTALLED.lock() {
+        *cached = None;
+    }
+}
+
+fn recently_failed(model: &str) -> bool {
+    FAILED
+        .lock()
+        .ok()
+        .and_then(|failed| failed.get(model).map(|at| at.elapsed() < FAILURE_COOLDOWN))
+        .unwrap_or(false)
+}
+
+fn chain(agent: &str, model: &str) -> Vec<String> {
+    let mut chain = vec![model.to_string()];
+    chain.extend(agents::configured_profile(agent).fallback_models);
+    chain
+}
+
+fn notify(app_handle: &AppHandle, agent: &str, requested: &str, substituted: &str, reason: FallbackReason) {
+    let message = match reason {
+        FallbackReason::NotInstalled => format!("{} isn't installed, using {} instead", requested, substituted),
+        FallbackReason::LoadFailed => format!("{} failed to load, using {} instead", requested, substituted),
+    };
+    println!("🔀 {} agent: {}", agent, message);
+    announce(app_handle, AnnouncementCategory::Custom, AnnouncementPriority::Polite, &message);
+    let _ = app_handle.emit("model-fallback", ModelFallback {
+        agent: agent.to_string(),
+        requested: requested.to_string(),
+        substituted: substituted.to_string(),
+        reason,
+        message,
+    });
+}
+
+/// `backend::resolve`, with a missing Ollama model replaced by the agent's first available
+/// fallback. When Ollama can't be reached the model is left alone so the request reports that.
+pub(super) async fn resolve(app_handle: &AppHandle, agent: &str, default_model: String) -> Result<(Box<dyn LLMBackend>, String), String> {
+    let (backend, model) = backend::resolve(agent, default_model);
+    if backend.kind() != BackendKind::Ollama || model.is_empty() {
+        return Ok((backend, model));
+    }
+    let Ok(installed) = installed_models(false).await else {
+        return Ok((backend, model));
+    };
+    let chain = chain(agent, &model);
+    let chosen = pick(&chain, &installed, recently_failed)
+        // Every candidate failed recently; retrying one beats refusing outright
+        .or_else(|| pick(&chain, &installed, |_| false))
+        .cloned();
+    match chosen {
+        Some(chosen) => {
+            if find_installed(&model, &installed) != Some(&chosen) {
+                let reason = if find_installed(&model, &installed).is_some() { FallbackReason::LoadFailed } else { FallbackReason::NotInstalled };
+                notify(app_handle, agent, &model, &chosen, reason);
+            }
+            Ok((backend, chosen))
+        }
+        None if chain.len() > 1 => Err(format!(
+            "Model '{}' isn't installed and neither is any of its fallbacks ({}). Pull one of them to continue.",
+            model,
+            chain[1..].join(", ")
+        )),
+        None => Err(format!("Model '{}' isn't installed. Pull it to continue.", model)),
+    }
+}
+
+/// After `failed` couldn't serve a request: remember that, and pick the next model to try,
+/// skipping any in `tried`
+pub(super) async fn next_after_failure(app_handle: &AppHandle, agent: &str, failed: &str, tried: &[String]) -> Option<String> {
+    if let Ok(mut failures) = FAILED.lock() {
+        failures.insert(failed.to_string(), Instant::now());
+    }
+    let installed = installed_models(true).await.ok()?;
+    let chain = chain(agent, tried.first().map(String::as_str).unwrap_or(failed));
+    let next = pick(&chain, &installed, |name| tried.iter().any(|t| t == name) || recently_failed(name))?.clone();
+    let reason = if find_installed(failed, &installed).is_some() { FallbackReason::LoadFailed } else { FallbackReason::NotInstalled };
+    notify(app_handle, agent, failed, &next, reason);
+    Some(next)
+}
+
+/// Check every agent's model against what Ollama has installed
+#[crate::command]
+pub async fn check_agent_models() -> Result<Vec<AgentModelHealth>, String> {
+    let installed = installed_models(true).await?;
+    Ok(AGENT_TYPES
+        .iter()
+        .map(|agent| {
+            let profile = agents::configured_profile(agent);
+            let (backend, model) = backend::resolve(agent, profile.model);
+            let ollama = backend.kind() == BackendKind::Ollama;
+            let chain = chain(agent, &model);
+            AgentModelHealth {
+                agent: agent.to_string(),
+                backend: backend.name().to_string(),
+                installed: !ollama || find_installed(&model, &installed).is_some(),
+                effective: if ollama { pick(&chain, &installed, recently_failed).cloned() } else { Some(model.clone()) },
+                requested: model,
+                fallback_models: profile.fallback_models,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_picks_first_installed_model() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let installed = names(&["llama3.2:latest", "phi3:mini", "gemma3:4b"]);
+        let chain = names(&["gemma3:1b-it-qat", "llama3.2", "phi3"]);
+
+        // A tagged name must match exactly; an untagged one matches any tag
+        assert_eq!(find_installed("gemma3:1b-it-qat", &installed), None);
+        assert_eq!(find_installed("gemma3", &installed).map(String::as_str), Some("gemma3:4b"));
+        assert_eq!(pick(&chain, &installed, |_| false).map(String::as_str), Some("llama3.2:latest"));
+        assert_eq!(pick(&chain, &installed, |name| name == "llama3.2:latest").map(String::as_str), Some("phi3:mini"));
+        assert_eq!(pick(&chain, &names(&["mistral:7b"]), |_| false), None);
+
+        assert!(is_model_failure(reqwest::StatusCode::NOT_FOUND, ""));
+        assert!(is_model_failure(reqwest::StatusCode::INTERNAL_SERVER_ERROR, "model requires more system memory (5.1 GiB)"));
+        assert!(!is_model_failure(reqwest::StatusCode::BAD_REQUEST, "invalid options"));
+    }
+}
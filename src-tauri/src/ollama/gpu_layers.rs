@@ -0,0 +1,226 @@
+// src-tauri/src/ollama/gpu_layers.rs
+// GPU offload per model - `num_gpu` is how many of a model's layers Ollama keeps in VRAM, and
+// the right number depends on the model: a 1B model fits whole where a 7B one on the same card
+// has to leave layers on the CPU. The model's layer count (from /api/show) and weight size
+// (from /api/tags) are weighed against the VRAM free right now.
+//
+// Each model's result is cached and recomputed once free VRAM has moved by more than
+// `PRESSURE_MARGIN_MB` since, e.g. when a game or another model takes memory. Without a
+// free-VRAM reading (no nvidia-smi) the card-size estimate of `detect_gpu_layers` is used.
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::backend;
+use super::HTTP_CLIENT;
+use crate::system_info::{detect_gpu_layers, free_vram_mb};
+
+// nvidia-smi is re-run at most this often
+const FREE_VRAM_TTL: Duration = Duration::from_secs(10);
+// A change in free VRAM this large invalidates cached layer counts
+const PRESSURE_MARGIN_MB: u64 = 1024;
+// Kept free for the CUDA context and the KV cache at the default context length
+const RESERVED_MB: u64 = 768;
+const MB: u64 = 1024 * 1024;
+
+lazy_static::lazy_static! {
+    static ref FREE_VRAM: Mutex<Option<(Instant, Option<u64>)>> = Mutex::new(None);
+    static ref SHAPES: Mutex<HashMap<String, ModelShape>> = Mutex::new(HashMap::new());
+    static ref PLANS: Mutex<HashMap<String, GpuLayerPlan>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ModelShape {
+    /// Transformer blocks; Ollama counts one more layer for the output
+    blocks: u32,
+    weights_mb: u64,
+}
+
+impl ModelShape {
+    fn offloadable_layers(&self) -> u32 {
+        self.blocks + 1
+    }
+}
+
+/// How a model's layers are split between GPU and CPU
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuLayerPlan {
+    pub model: String,
+    /// The `num_gpu` sent with requests
+    pub layers: i32,
+    /// `None` when the model's shape couldn't be read and the card-size estimate was used
+    pub total_layers: Option<u32>,
+    pub weights_mb: Option<u64>,
+    /// Free VRAM when the plan was made; `None` for the card-size estimate
+    pub available_vram_mb: Option<u64>,
+}
+
+/// Layers of a model with `shape` that fit in `available_mb` of VRAM
+fn layers_that_fit(shape: ModelShape, available_mb: u64) -> i32 {
+    let total = shape.offloadable_layers();
+    let per_layer_mb = (shape.weights_mb / total as u64).max(1);
+    let usable_mb = available_mb.saturating_sub(RESERVED_MB);
+    (usable_mb / per_layer_mb).min(total as u64) as i32
+}
+
+async fn current_free_vram_mb() -> Option<u64> {
+    if let Some((at, free)) = FREE_VRAM.lock().ok().and_then(|cached| *cached) {
+        if at.elapsed() < FREE_VRAM_TTL {
+            return free;
+        }
+    }
+    let free = tokio::task::spawn_blocking(free_vram_mb).await.ok().flatten();
+    if let Ok(mut cached) = FREE_VRAM.lock() {
+        *cached = Some((Instant::now(), free));
+    }
+    free
+}
+
+fn block_count(show: &serde_json::Value) -> Option<u32> {
+    show.get("model_info")?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.ends_with(".block_count"))
+        .and_then(|(_, value)| value.as_u64())
+        .map(|blocks| blocks as u32)
+}
+
+async fn model_shape(model: &str) -> Option<ModelShape> {
+    if let Some(shape) = SHAPES.lock().ok().and_then(|shapes| shapes.get(model).copied()) {
+        return Some(shape);
+    }
+    let show: serde_json::Value = backend::ollama_endpoint()
+        .request(&HTTP_CLIENT, reqwest::Method::POST, "/api/show")
+        .json(&serde_json::json!({ "name": model }))
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    let blocks = block_count(&show)?;
+    let tagged = format!("{}:latest", model);
+    let weights = super::get_ollama_models()
+        .await
+        .ok()?
+        .into_iter()
+        .find(|installed| installed.name == model || installed.name == tagged)?
+        .size;
+    let shape = ModelShape { blocks, weights_mb: weights / MB };
+    if let Ok(mut shapes) = SHAPES.lock() {
+        shapes.insert(model.to_string(), shape);
+    }
+    Some(shape)
+}
+
+// VRAM the model already holds if it's loaded; that memory is its to use
+async fn loaded_vram_mb(model: &str) -> u64 {
+    let running: Option<serde_json::Value> = async {
+        backend::ollama_endpoint()
+            .request(&HTTP_CLIENT, reqwest::Method::GET, "/api/ps")
+            .send()
+            .await
+            .ok()?
+            .json()
+            .await
+            .ok()
+    }
+    .await;
+    running
+        .as_ref()
+        .and_then(|running| running.get("models")?.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .find(|loaded| loaded.get("name").and_then(|name| name.as_str()).is_some_and(|name| name == model || name == format!("{}:latest", model)))
+        .and_then(|loaded| loaded.get("size_vram")?.as_u64())
+        .map_or(0, |bytes| bytes / MB)
+}
+
+fn card_size_plan(model: &str) -> GpuLayerPlan {
+    GpuLayerPlan {
+        model: model.to_string(),
+        layers: detect_gpu_layers(),
+        total_layers: None,
+        weights_mb: None,
+        available_vram_mb: None,
+    }
+}
+
+async fn plan(model: &str) -> GpuLayerPlan {
+    let Some(free_mb) = current_free_vram_mb().await else {
+        return card_size_plan(model);
+    };
+    let cached = PLANS.lock().ok().and_then(|plans| plans.get(model).cloned());
+    if let Some(cached) = cached {
+        // Made with about the same free VRAM as now
+        if cached.available_vram_mb.is_some_and(|available| available.abs_diff(free_mb) <= PRESSURE_MARGIN_MB) {
+            return cached;
+        }
+    }
+    let Some(shape) = model_shape(model).await else {
+        return card_size_plan(model);
+    };
+    let available_mb = free_mb + loaded_vram_mb(model).await;
+    let plan = GpuLayerPlan {
+        model: model.to_string(),
+        layers: layers_that_fit(shape, available_mb),
+        total_layers: Some(shape.offloadable_layers()),
+        weights_mb: Some(shape.weights_mb),
+        // Keyed on the free reading so the next check compares like with like
+        available_vram_mb: Some(free_mb),
+    };
+    println!(
+        "🎮 {}: {} of {} layers on GPU ({}MB of weights, {}MB VRAM available)",
+        model, plan.layers, shape.offloadable_layers(), shape.weights_mb, available_mb
+    );
+    if let Ok(mut plans) = PLANS.lock() {
+        plans.insert(model.to_string(), plan.clone());
+    }
+    plan
+}
+
+/// The `num_gpu` to send with a request for `model`
+pub(super) async fn gpu_layers_for(model: &str) -> i32 {
+    plan(model).await.layers
+}
+
+/// Drop what's known about `model`, after it is pulled again or deleted
+pub(super) fn forget(model: &str) {
+    if let Ok(mut shapes) = SHAPES.lock() {
+        shapes.remove(model);
+    }
+    if let Ok(mut plans) = PLANS.lock() {
+        plans.remove(model);
+    }
+}
+
+/// How many of `model`'s layers would go to the GPU right now, and why
+#[crate::command]
+pub async fn get_model_gpu_layers(model: String) -> Result<GpuLayerPlan, String> {
+    Ok(plan(model.trim()).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_scale_with_model_size_and_free_vram() {
+        // Roughly a 1B and an 8B model at 4-bit
+        let small = ModelShape { blocks: 16, weights_mb: 800 };
+        let large = ModelShape { blocks: 32, weights_mb: 4700 };
+
+        // The same 3GB card holds all of the small one but under half of the large one
+        assert_eq!(layers_that_fit(small, 3000), 17);
+        assert_eq!(layers_that_fit(large, 3000), 15);
+        assert_eq!(layers_that_fit(large, 12000), 33);
+        // Nothing left after the reserve
+        assert_eq!(layers_that_fit(large, 700), 0);
+
+        let show = serde_json::json!({ "model_info": { "general.architecture": "llama", "llama.block_count": 32 } });
+        assert_eq!(block_count(&show), Some(32));
+        assert_eq!(block_count(&serde_json::json!({})), None);
+    }
+}
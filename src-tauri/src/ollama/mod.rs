@@ -0,0 +1,1712 @@
+use serde::{Deserialize, Serialize};
+use serde_json;
+use reqwest;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use futures_util::StreamExt;
+use lazy_static::lazy_static;
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+use crate::system_info::{detect_gpu_layers, get_gpu_info};
+use crate::ndjson_parser::StreamDiagnostics;
+use crate::accessibility::{announce, AnnouncementCategory, AnnouncementPriority};
+use crate::app_lock::{require_unlocked, CommandGroup};
+use crate::data::chat::ResponseJournal;
+use crate::data::conversation::DIRECT_AGENT;
+use crate::action_registry::{args, descriptor, param, to_value, ActionKind, ActionParam, RegisteredAction, SessionArgs};
+
+mod backend;
+pub use backend::{get_llm_backends, save_llm_backends, list_backend_models, set_llm_endpoint};
+mod agents;
+pub use agents::{get_agent_registry, update_agent_config, reset_agent_config};
+pub(crate) use agents::profile as agent_profile;
+#[cfg(feature = "enhanced-rag")]
+mod rag;
+#[cfg(feature = "enhanced-rag")]
+pub use rag::generate_rag_response;
+mod context;
+pub use context::get_context_stats;
+mod templates;
+pub use templates::{
+    list_prompt_templates, get_prompt_template, save_prompt_template, delete_prompt_template,
+    preview_prompt_template, set_prompt_variable,
+};
+pub(crate) use templates::init as init_prompt_templates;
+mod scheduled;
+pub use scheduled::run_scheduled_task_now;
+pub(crate) use scheduled::init as init_scheduler;
+mod structured;
+pub use structured::generate_structured_response;
+mod compare;
+pub use compare::{compare_models, get_model_comparisons};
+mod pull;
+pub use pull::{pull_ollama_model, cancel_pull};
+pub(crate) use pull::pull_model_with_progress;
+mod fallback;
+pub use fallback::check_agent_models;
+mod gpu_layers;
+pub use gpu_layers::get_model_gpu_layers;
+mod response_cache;
+pub use response_cache::{get_response_cache_settings, save_response_cache_settings, clear_response_cache};
+pub(crate) use response_cache::init as init_response_cache;
+use backend::{GenerationChunk, LLMBackend};
+
+// Shared HTTP client for better connection pooling and memory efficiency
+lazy_static! {
+    static ref HTTP_CLIENT: Arc<reqwest::Client> = Arc::new(
+        reqwest::Client::builder()
+            .pool_max_idle_per_host(16)  // More idle connections for faster reuse
+            .pool_idle_timeout(Duration::from_secs(60))
+            .tcp_keepalive(Some(Duration::from_secs(60)))
+            .timeout(Duration::from_secs(60))  // Shorter timeout to fail fast
+            .build()
+            .expect("Failed to create HTTP client")
+    );
+    
+    // Semaphore to limit concurrent AI model requests (memory safety)
+    static ref REQUEST_SEMAPHORE: Arc<Semaphore> = Arc::new(Semaphore::new(4)); // Slightly higher concurrency
+}
+
+/// A request to the configured Ollama server's API, through the shared client
+#[cfg(feature = "enhanced-rag")]
+pub(crate) fn ollama_request(method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+    backend::ollama_endpoint().request(&HTTP_CLIENT, method, path)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaModel {
+    pub name: String,
+    pub modified_at: String,
+    pub size: u64,
+    pub digest: String,
+    pub details: Option<ModelDetails>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelDetails {
+    pub format: String,
+    pub family: String,
+    pub families: Option<Vec<String>>,
+    pub parameter_size: String,
+    pub quantization_level: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaModelsResponse {
+    pub models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaStatus {
+    pub status: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub name: String,
+    pub insecure: Option<bool>,
+    pub stream: Option<bool>,
+}
+
+// Chat context structures for frontend communication
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatContextMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// One turn of a chat request, in Ollama's /api/chat shape
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub images: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// On `tool` messages, the tool that produced the result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// On `tool` messages, the call being answered (OpenAI-compatible servers need it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    pub fn new(role: &str, content: impl Into<String>) -> Self {
+        Self { role: role.to_string(), content: content.into(), ..Default::default() }
+    }
+}
+
+/// A function call the model asked for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolCall {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub function: ToolCallFunction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolCallFunction {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub stream: Option<bool>,
+    /// Function definitions (`{"type": "function", "function": {...}}`) the model may call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<serde_json::Value>>,
+    /// `"json"`, or a JSON schema the reply must follow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<serde_json::Value>,
+    pub options: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatResponse {
+    pub model: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub message: Option<ChatMessage>,
+    pub done: bool,
+    pub total_duration: Option<u64>,
+    pub load_duration: Option<u64>,
+    pub prompt_eval_count: Option<u32>,
+    pub prompt_eval_duration: Option<u64>,
+    pub eval_count: Option<u32>,
+    pub eval_duration: Option<u64>,
+}
+
+// Every response gets a seed, picked at random when the caller has none, so the one reported
+// in the start event can be saved with the response and reproduce it later
+fn resolve_seed(seed: Option<i64>) -> i64 {
+    seed.unwrap_or_else(|| i64::from(rand::random::<u32>()))
+}
+
+// Fixing the sampling seed makes a response reproducible for a given model and prompt
+fn with_seed(options: Option<serde_json::Value>, seed: i64) -> Option<serde_json::Value> {
+    let mut opts = options.unwrap_or_else(|| serde_json::json!({}));
+    opts["seed"] = serde_json::json!(seed);
+    Some(opts)
+}
+
+// Only Ollama takes `num_gpu`; other backends place layers themselves
+async fn backend_gpu_layers(backend: &dyn LLMBackend, model: &str) -> i32 {
+    if backend.kind() == backend::BackendKind::Ollama {
+        gpu_layers::gpu_layers_for(model).await
+    } else {
+        0
+    }
+}
+
+// Stream state tracking for timeouts and pattern detection
+#[derive(Debug)]
+struct StreamState {
+    start_time: Instant,
+    last_chunk_time: Instant,
+    chunk_count: usize,
+    last_chunk_text: String,
+    repeat_count: usize,
+    consecutive_empty_count: usize, // Changed: track consecutive empty chunks
+    total_empty_count: usize,       // Added: track total for debugging
+}
+
+#[derive(Debug)]
+enum ChunkResult {
+    Continue,
+    Exit(String), // Exit with message
+}
+
+impl StreamState {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            start_time: now,
+            last_chunk_time: now,
+            chunk_count: 0,
+            last_chunk_text: String::new(),
+            repeat_count: 0,
+            consecutive_empty_count: 0,
+            total_empty_count: 0,
+        }
+    }
+
+    fn update_chunk(&mut self, chunk_text: &str) -> ChunkResult {
+        self.last_chunk_time = Instant::now();
+        self.chunk_count += 1;
+
+        // Track empty chunks
+        if chunk_text.trim().is_empty() {
+            self.consecutive_empty_count += 1;
+            self.total_empty_count += 1;
+            
+            // Optional: Log excessive consecutive empty chunks for debugging
+            if self.consecutive_empty_count > 10 {
+                println!("⚠️ {} consecutive empty chunks received", self.consecutive_empty_count);
+            }
+            
+            return ChunkResult::Continue;
+        } else {
+            // Reset consecutive empty count when we get content
+            self.consecutive_empty_count = 0;
+        }
+
+        // Check for consecutive identical chunks (dynamic repetition)
+        if !self.last_chunk_text.is_empty() && self.last_chunk_text == chunk_text {
+            self.repeat_count += 1;
+            println!("⚠️ Consecutive repeat detected: '{}' (count: {})", 
+                     chunk_text.chars().take(50).collect::<String>(), self.repeat_count);
+            
+            // More aggressive detection for very short or UI-related chunks
+            if chunk_text.trim().len() <= 20 && self.repeat_count > 3 {
+                println!("🛑 Exiting stream due to short repeat (likely UI artifact)");
+                return ChunkResult::Exit(format!("Stream terminated: Detected repetitive short chunk '{}' ({} times) - possible AI confusion", 
+                    chunk_text.chars().take(20).collect::<String>(), self.repeat_count));
+            }
+            
+            // Standard detection for longer content
+            if chunk_text.trim().len() > 20 && self.repeat_count > 8 {
+                println!("🛑 Exiting stream due to excessive content repeat");
+                return ChunkResult::Exit(format!("Stream terminated: Excessive repetition of content '{}' ({} times)", 
+                    chunk_text.chars().take(30).collect::<String>(), self.repeat_count));
+            }
+            
+            // Continue but keep counting - don't update last_chunk_text since it's the same
+            return ChunkResult::Continue;
+        } else {
+            // FIXED: Only reset counter and update text when we have DIFFERENT content
+            if !chunk_text.trim().is_empty() {
+                self.repeat_count = 0; // Reset counter for different text
+                self.last_chunk_text = chunk_text.to_string(); // Store new text
+            }
+        }
+
+        ChunkResult::Continue
+    }
+
+    fn should_timeout(&self, max_total_duration: Duration, max_chunk_gap: Duration) -> Option<String> {
+        let now = Instant::now();
+        
+        // Check total stream timeout
+        if now.duration_since(self.start_time) > max_total_duration {
+            return Some(format!("Total stream timeout after {:?}", max_total_duration));
+        }
+        
+        // Check gap between chunks timeout
+        if now.duration_since(self.last_chunk_time) > max_chunk_gap {
+            return Some(format!("Chunk gap timeout after {:?}", max_chunk_gap));
+        }
+        
+        None
+    }
+
+    fn should_terminate_patterns(&self, max_repeats: usize, max_consecutive_empty_chunks: usize) -> Option<String> {
+        // Only terminate on very excessive repetition (adjusted thresholds)
+        if self.repeat_count >= max_repeats * 3 {  // Triple the threshold for termination
+            return Some(format!("Too many consecutive repeats: {}", self.repeat_count));
+        }
+        
+        // Check consecutive empty chunks instead of total
+        if self.consecutive_empty_count > max_consecutive_empty_chunks {
+            return Some(format!("Too many consecutive empty chunks: {} (total empty: {})", 
+                self.consecutive_empty_count, self.total_empty_count));
+        }
+        
+        None
+    }
+}
+
+// Base streaming configuration
+pub struct StreamConfig {
+    max_total_duration: Duration,
+    max_chunk_gap: Duration,
+    chunk_timeout: Duration,
+    max_consecutive_repeats: usize,
+    max_consecutive_empty_chunks: usize, 
+}
+
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            max_total_duration: Duration::from_secs(300), // 5 minutes total
+            max_chunk_gap: Duration::from_secs(30),       // 30 seconds between chunks
+            chunk_timeout: Duration::from_secs(10),       // 10 seconds per chunk read
+            max_consecutive_repeats: 5,                   // Max 5 consecutive identical chunks
+            max_consecutive_empty_chunks: 25,              // Max 25 consecutive empty chunks (increased)
+        }
+    }
+}
+
+// Chat history from the frontend becomes real chat turns rather than one pasted-together prompt
+fn build_messages(
+    system_prompt: Option<String>,
+    context: Option<Vec<ChatContextMessage>>,
+    prompt: String,
+    images: Option<Vec<String>>,
+) -> Vec<ChatMessage> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = system_prompt {
+        messages.push(ChatMessage::new("system", system_prompt));
+    }
+    let history = context.unwrap_or_default();
+    println!("📊 Building chat request with {} context messages", history.len());
+    for message in history {
+        match message.role.as_str() {
+            "user" | "assistant" | "system" => messages.push(ChatMessage::new(&message.role, message.content)),
+            // Chat servers only know the standard roles
+            _ => messages.push(ChatMessage::new("user", format!("{}: {}", message.role, message.content))),
+        }
+    }
+    messages.push(ChatMessage { images, ..ChatMessage::new("user", prompt) });
+    messages
+}
+
+// Get GPU acceleration status
+#[crate::command]
+pub fn get_gpu_acceleration_status() -> serde_json::Value {
+    let gpu_layers = detect_gpu_layers();
+    let gpus = get_gpu_info().unwrap_or_else(|_| vec![]);
+    
+    serde_json::json!({
+        "enabled": gpu_layers > 0,
+        "layers": gpu_layers,
+        "gpus": gpus.iter().map(|gpu| {
+            serde_json::json!({
+                "name": gpu.name,
+                "vendor": gpu.vendor,
+                "memory_mb": gpu.memory_mb,
+                "driver_version": gpu.driver_version
+            })
+        }).collect::<Vec<_>>()
+    })
+}
+
+// Cancel a streaming session; the same as `cancel_operation` with the session id
+#[crate::command]
+pub fn cancel_ai_response(session_id: String) -> Result<(), String> {
+    crate::cancellation::cancel_operation(session_id).map(|_| ())
+}
+
+// Tell the UI a response was cancelled and unload the model. Dropping the connection already
+// stops Ollama generating; unloading frees its GPU memory now rather than after the keep-alive.
+async fn finish_cancelled(app_handle: &AppHandle, session_id: &str, backend: &dyn LLMBackend, model: &str) {
+    println!("🛑 Session cancelled: {}", session_id);
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "cancelled",
+        "message": "Response cancelled by user"
+    })) {
+        eprintln!("Failed to emit cancellation event: {}", e);
+    }
+    if backend.kind() != backend::BackendKind::Ollama || model.is_empty() {
+        return;
+    }
+    match timeout(Duration::from_secs(5), backend.unload(&HTTP_CLIENT, model)).await {
+        Ok(Ok(())) => println!("🧹 Unloaded {} after cancellation", model),
+        Ok(Err(e)) => eprintln!("Failed to unload {} after cancellation: {}", model, e),
+        Err(_) => eprintln!("Timed out unloading {} after cancellation", model),
+    }
+}
+
+// Enhanced streaming logic with timeout and pattern detection
+async fn stream_ollama_response_enhanced(
+    app_handle: AppHandle,
+    backend: Box<dyn LLMBackend>,
+    request: ChatRequest,
+    session_id: String,
+    agent: &str,
+    config: StreamConfig,
+) -> Result<(), String> {
+    // Cancellable by session id until the stream ends
+    let operation = crate::cancellation::start(&session_id, crate::cancellation::OperationKind::AiResponse, agent);
+
+    let client = Arc::clone(&HTTP_CLIENT);
+
+    // Keyed on the model asked for, before any fallback
+    let cache_key = response_cache::key_for(&request);
+    if let Some(cached) = cache_key.as_deref().and_then(response_cache::lookup) {
+        println!("🗄️ Replaying cached response for session: {}", session_id);
+        emit_cached(&app_handle, &session_id, &cached).await;
+        return Ok(());
+    }
+    
+    // A model that turns out to be missing or fails to load moves on to the agent's next fallback
+    let mut request = request;
+    let mut tried = vec![request.model.clone()];
+    let response = loop {
+        // Make request with timeout; cancelling while the model loads drops the request too
+        let sent = operation.run(async {
+            timeout(Duration::from_secs(30), backend.send(&client, &request))
+                .await
+                .map_err(|_| "Request timeout".to_string())?
+        }).await;
+        let response = match sent {
+            Err(e) if e == crate::cancellation::CANCELLED => {
+                finish_cancelled(&app_handle, &session_id, backend.as_ref(), &request.model).await;
+                return Ok(());
+            }
+            sent => sent?,
+        };
+        if response.status().is_success() {
+            break response;
+        }
+
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if backend.kind() == backend::BackendKind::Ollama && fallback::is_model_failure(status, &error_text) {
+            if let Some(next) = fallback::next_after_failure(&app_handle, agent, &request.model, &tried).await {
+                tried.push(next.clone());
+                // The fallback model gets its own layer split
+                if let Some(options) = request.options.as_mut().filter(|options| options.get("num_gpu").is_some()) {
+                    options["num_gpu"] = serde_json::json!(gpu_layers::gpu_layers_for(&next).await);
+                }
+                request.model = next;
+                continue;
+            }
+        }
+        let error_msg = format!("Generation failed: {}", error_text);
+        
+        emit_error(&app_handle, &session_id, &error_msg).await;
+        return Err(error_msg);
+    };
+
+    announce(&app_handle, AnnouncementCategory::ModelResponding, AnnouncementPriority::Polite, "Assistant is responding");
+
+    let mut stream = response.bytes_stream();
+    let mut parser = backend.decoder();
+    let mut state = StreamState::new();
+    // Kept on disk until this function returns, so a crash mid-stream leaves the partial text
+    let mut journal = ResponseJournal::start(&app_handle, &session_id, agent, &request.model);
+    let mut full_text = String::new();
+
+    // Emit a tiny nudge to UI so it can render quickly even before first chunk
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "chunk",
+        "text": "",
+        "done": false
+    })) {
+        eprintln!("Failed to emit priming chunk: {}", e);
+    }
+
+    loop {
+        // Check for cancellation first
+        if operation.is_cancelled() {
+            break;
+        }
+
+        // Check timeouts
+        if let Some(timeout_reason) = state.should_timeout(config.max_total_duration, config.max_chunk_gap) {
+            println!("⏰ Stream timeout: {}", timeout_reason);
+            emit_timeout(&app_handle, &session_id, &timeout_reason).await;
+            emit_complete(&app_handle, &session_id, parser.diagnostics()).await;
+            return Err(timeout_reason);
+        }
+
+        // Check problematic patterns
+        if let Some(pattern_reason) = state.should_terminate_patterns(config.max_consecutive_repeats, config.max_consecutive_empty_chunks) {
+            println!("🔁 Pattern termination: {}", pattern_reason);
+            emit_error(&app_handle, &session_id, &pattern_reason).await;
+            emit_complete(&app_handle, &session_id, parser.diagnostics()).await;
+            return Err(pattern_reason);
+        }
+
+        // Read next chunk with timeout, giving up on it as soon as the response is cancelled
+        let chunk_result = tokio::select! {
+            biased;
+            _ = operation.token().cancelled() => break,
+            result = timeout(config.chunk_timeout, stream.next()) => result,
+        };
+        
+        let (responses, stream_ended) = match chunk_result {
+            Ok(Some(Ok(chunk))) => (parser.push(&chunk), false),
+            Ok(Some(Err(e))) => {
+                let error_msg = format!("Stream error: {}", e);
+                eprintln!("{}", error_msg);
+
+                emit_error(&app_handle, &session_id, &error_msg).await;
+                return Err(error_msg);
+            }
+            // Stream ended naturally; flush any line that wasn't newline-terminated
+            Ok(None) => (parser.finish(), true),
+            Err(_) => {
+                let error_msg = format!("Chunk read timeout after {:?}", config.chunk_timeout);
+                println!("⏰ {}", error_msg);
+                emit_timeout(&app_handle, &session_id, &error_msg).await;
+                emit_complete(&app_handle, &session_id, parser.diagnostics()).await;
+                return Err(error_msg);
+            }
+        };
+
+        for response_chunk in responses {
+            // Check patterns and update state
+            match state.update_chunk(&response_chunk.text) {
+                ChunkResult::Continue => { 
+                    // Process chunk normally
+                }
+                ChunkResult::Exit(reason) => {
+                // 1. Send termination event with details
+                emit_termination(&app_handle, &session_id, &reason, state.chunk_count, state.repeat_count).await;
+                
+                // 2. Send completion event to reset UI state  
+                emit_complete(&app_handle, &session_id, parser.diagnostics()).await;
+                
+                // 3. Clean up session
+                
+                return Ok(());
+                }
+            }
+
+            // Skip empty chunks to reduce UI overhead but still emit important ones
+            if response_chunk.text.is_empty() && !response_chunk.done {
+                continue;
+            }
+            journal.push(&response_chunk.text);
+            full_text.push_str(&response_chunk.text);
+
+            if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+                "type": "chunk",
+                "text": response_chunk.text,
+                "done": response_chunk.done,
+                "chunk_count": state.chunk_count,
+                "repeat_count": state.repeat_count
+            })) {
+                eprintln!("Failed to emit chunk event: {}", e);
+            }
+
+            if response_chunk.done {
+                record_usage(&app_handle, &session_id, agent, &request.model, &response_chunk);
+                if let Some(key) = &cache_key {
+                    response_cache::store(key, &request.model, &full_text);
+                }
+                println!("✅ Agent streaming completed for session: {} (chunks: {}, repeats: {})", 
+                         session_id, state.chunk_count, state.repeat_count);
+                emit_complete(&app_handle, &session_id, parser.diagnostics()).await;
+                return Ok(());
+            }
+        }
+
+        if stream_ended {
+            println!("✅ Stream completed naturally for session: {}", session_id);
+            if let Some(key) = &cache_key {
+                response_cache::store(key, &request.model, &full_text);
+            }
+            emit_complete(&app_handle, &session_id, parser.diagnostics()).await;
+            return Ok(());
+        }
+    }
+
+    // Cancelled: close the connection before unloading so the server stops generating first
+    drop(stream);
+    finish_cancelled(&app_handle, &session_id, backend.as_ref(), &request.model).await;
+    Ok(())
+}
+
+// Shared streaming logic (backwards compatibility)
+async fn stream_ollama_response(
+    app_handle: AppHandle,
+    request: ChatRequest,
+    session_id: String,
+) -> Result<(), String> {
+    stream_ollama_response_enhanced(app_handle, backend::ollama_backend(), request, session_id, DIRECT_AGENT, StreamConfig::default()).await
+}
+
+// Use enhanced streaming with default config - remove any old stream_ollama_response calls
+// All streaming now goes through stream_ollama_response_enhanced
+
+// Helper emit functions
+// Attribute the final chunk's token counts to the active meeting and the agent's ledger
+fn record_usage(app_handle: &AppHandle, session_id: &str, agent: &str, model: &str, response: &GenerationChunk) {
+    crate::data::conversation::usage::record_generation(
+        app_handle,
+        model,
+        response.prompt_tokens,
+        response.completion_tokens,
+    );
+    crate::data::conversation::record_tokens(
+        app_handle,
+        session_id,
+        agent,
+        model,
+        response.prompt_tokens,
+        response.completion_tokens,
+    );
+}
+
+async fn emit_error(app_handle: &AppHandle, session_id: &str, error: &str) {
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "error",
+        "error": error
+    })) {
+        eprintln!("Failed to emit error: {}", e);
+    }
+}
+
+// A cached answer arrives as one final chunk, flagged so the UI can say it wasn't regenerated
+async fn emit_cached(app_handle: &AppHandle, session_id: &str, text: &str) {
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "chunk",
+        "text": text,
+        "done": true,
+        "cached": true
+    })) {
+        eprintln!("Failed to emit cached response: {}", e);
+    }
+    emit_complete(app_handle, session_id, &StreamDiagnostics::default()).await;
+}
+
+async fn emit_timeout(app_handle: &AppHandle, session_id: &str, reason: &str) {
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "timeout",
+        "reason": reason
+    })) {
+        eprintln!("Failed to emit timeout: {}", e);
+    }
+}
+
+async fn emit_complete(app_handle: &AppHandle, session_id: &str, diagnostics: &StreamDiagnostics) {
+    if diagnostics.unrecoverable_lines > 0 {
+        println!("⚠️ {} unrecoverable stream lines for session: {}", diagnostics.unrecoverable_lines, session_id);
+    }
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "complete",
+        "diagnostics": diagnostics
+    })) {
+        eprintln!("Failed to emit complete: {}", e);
+    }
+}
+
+async fn emit_termination(app_handle: &AppHandle, session_id: &str, reason: &str, chunk_count: usize, repeat_count: usize) {
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "terminated",
+        "reason": reason,
+        "chunk_count": chunk_count,
+        "repeat_count": repeat_count
+    })) {
+        eprintln!("Failed to emit termination: {}", e);
+    }
+}
+
+// All your existing Tauri commands remain the same...
+
+#[crate::command]
+pub async fn get_ollama_models() -> Result<Vec<OllamaModel>, String> {
+    let client = Arc::clone(&HTTP_CLIENT);
+    
+    match backend::ollama_endpoint().request(&client, reqwest::Method::GET, "/api/tags").send().await {
+        Ok(response) => {
+            if response.status().is_success() {
+                match response.json::<OllamaModelsResponse>().await {
+                    Ok(models_response) => Ok(models_response.models),
+                    Err(e) => Err(format!("Failed to parse models response: {}", e)),
+                }
+            } else {
+                Err(format!("Ollama API error: {}", response.status()))
+            }
+        }
+        Err(e) => Err(format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e)),
+    }
+}
+
+#[crate::command]
+pub async fn get_ollama_status() -> Result<OllamaStatus, String> {
+    let client = Arc::clone(&HTTP_CLIENT);
+    
+    match backend::ollama_endpoint().request(&client, reqwest::Method::GET, "/api/version").send().await {
+        Ok(response) => {
+            if response.status().is_success() {
+                match response.json::<HashMap<String, String>>().await {
+                    Ok(version_info) => Ok(OllamaStatus {
+                        status: "running".to_string(),
+                        version: version_info.get("version").cloned(),
+                    }),
+                    Err(_) => Ok(OllamaStatus {
+                        status: "running".to_string(),
+                        version: None,
+                    }),
+                }
+            } else {
+                Err(format!("Ollama API error: {}", response.status()))
+            }
+        }
+        Err(_) => Ok(OllamaStatus {
+            status: "not_running".to_string(),
+            version: None,
+        }),
+    }
+}
+
+#[crate::command]
+pub async fn delete_ollama_model(model_name: String) -> Result<String, String> {
+    let client = Arc::clone(&HTTP_CLIENT);
+    
+    let request = serde_json::json!({
+        "name": model_name
+    });
+    
+    match backend::ollama_endpoint().request(&client, reqwest::Method::DELETE, "/api/delete").json(&request).send().await {
+        Ok(response) => {
+            if response.status().is_success() {
+                fallback::forget_installed();
+                gpu_layers::forget(&model_name);
+                Ok(format!("Successfully deleted model: {}", model_name))
+            } else {
+                let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+                Err(format!("Failed to delete model: {}", error_text))
+            }
+        }
+        Err(e) => Err(format!("Failed to connect to Ollama: {}", e)),
+    }
+}
+
+#[crate::command]
+pub async fn generate_ollama_response(app_handle: AppHandle, model: String, prompt: String, seed: Option<i64>) -> Result<String, String> {
+    let client = Arc::clone(&HTTP_CLIENT);
+    let backend = backend::ollama_backend();
+    
+    // Offload as many of this model's layers as free VRAM allows
+    let gpu_layers = gpu_layers::gpu_layers_for(&model).await;
+    let options = if gpu_layers > 0 {
+        Some(serde_json::json!({
+            "num_gpu": gpu_layers,
+            "num_thread": 4
+        }))
+    } else {
+        None
+    };
+    
+    let seed = resolve_seed(seed);
+    let request = ChatRequest {
+        model,
+        messages: vec![ChatMessage::new("user", prompt)],
+        stream: Some(false),
+        tools: None,
+        format: None,
+        options: with_seed(options, seed),
+    };
+    
+    let response = backend.send(&client, &request).await?;
+    let generated = backend.read_response(response).await?;
+    record_usage(&app_handle, "", DIRECT_AGENT, &request.model, &generated);
+    Ok(generated.text)
+}
+
+#[crate::command]
+pub async fn generate_ollama_response_stream(
+    app_handle: AppHandle,
+    model: String,
+    prompt: String,
+    session_id: String,
+    seed: Option<i64>,
+) -> Result<(), String> {
+    // Offload as many of this model's layers as free VRAM allows
+    let gpu_layers = gpu_layers::gpu_layers_for(&model).await;
+    let options = if gpu_layers > 0 {
+        Some(serde_json::json!({
+            "num_gpu": gpu_layers,
+            "num_thread": 4
+        }))
+    } else {
+        None
+    };
+    
+    let seed = resolve_seed(seed);
+    let request = ChatRequest {
+        model: model.clone(),
+        messages: vec![ChatMessage::new("user", prompt.clone())],
+        stream: Some(true),
+        tools: None,
+        format: None,
+        options: with_seed(options, seed),
+    };
+    
+    println!("🚀 Starting streaming generation for session: {}", session_id);
+    
+    // Emit start event
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "start",
+        "model": model,
+        "prompt": prompt,
+        "seed": seed
+    })) {
+        return Err(format!("Failed to emit start event: {}", e));
+    }
+    
+    // Use enhanced streaming with default config
+    stream_ollama_response_enhanced(app_handle, backend::ollama_backend(), request, session_id, DIRECT_AGENT, StreamConfig::default()).await
+}
+
+#[crate::command]
+pub async fn generate_enteract_agent_response(
+    app_handle: AppHandle,
+    prompt: String,
+    context: Option<Vec<ChatContextMessage>>,
+    session_id: String,
+    seed: Option<i64>,
+) -> Result<(), String> {
+    let profile = agents::profile("enteract");
+    generate_agent_response_stream(app_handle, profile.model, prompt, profile.system_prompt, context, session_id, "enteract".to_string(), seed).await
+}
+
+#[crate::command]
+pub async fn generate_vision_analysis(
+    app_handle: AppHandle,
+    prompt: String,
+    image_base64: String,
+    session_id: String,
+    seed: Option<i64>,
+) -> Result<(), String> {
+    let profile = agents::profile("vision");
+    let full_prompt = format!("Screenshot Analysis Request:\n\n{}", prompt);
+    
+    generate_agent_response_stream_with_image(
+        app_handle, 
+        profile.model, 
+        full_prompt, 
+        profile.system_prompt,
+        image_base64,
+        None, // Vision analysis doesn't use chat context
+        session_id,
+        "vision".to_string(),
+        seed
+    ).await
+}
+
+#[crate::command]
+pub async fn generate_coding_agent_response(
+    app_handle: AppHandle,
+    prompt: String,
+    context: Option<Vec<ChatContextMessage>>,
+    session_id: String,
+    seed: Option<i64>,
+) -> Result<(), String> {
+    let profile = agents::profile("coding");
+    let full_prompt = format!("Coding Request:\n\n{}", prompt);
+    
+    println!("💻 CODING AGENT: Using model {} for session {}", profile.model, session_id);
+    generate_agent_response_stream(app_handle, profile.model, full_prompt, profile.system_prompt, context, session_id, "coding".to_string(), seed).await
+}
+
+#[crate::command]
+pub async fn generate_deep_research(
+    app_handle: AppHandle,
+    prompt: String,
+    context: Option<Vec<ChatContextMessage>>,
+    session_id: String,
+    seed: Option<i64>,
+) -> Result<(), String> {
+    let profile = agents::profile("research");
+    let full_prompt = format!("Deep Research Query:\n\n{}", prompt);
+    
+    println!("🧠 DEEP RESEARCH: Using model {} for session {}", profile.model, session_id);
+    generate_agent_response_stream(app_handle, profile.model, full_prompt, profile.system_prompt, context, session_id, "research".to_string(), seed).await
+}
+
+#[crate::command]
+pub async fn generate_conversational_ai(
+    app_handle: AppHandle,
+    conversation_context: String,
+    session_id: String,
+    _custom_system_prompt: Option<String>, // Prefixed with underscore to indicate intentionally unused
+    seed: Option<i64>,
+    languages: Option<Vec<String>>,
+) -> Result<(), String> {
+    // Defaults to a fast 1B model for instant responses (quantized)
+    let profile = agents::profile("conversational_ai");
+    let model = profile.model;
+    
+    // Simplified prompt - just provide the conversation context
+    let full_prompt = format!("Conversation:\n{}\n\nProvide a brief summary and helpful next steps.", conversation_context);
+    
+    // Always use the simplified system prompt, told which languages the conversation uses
+    let languages = match languages {
+        Some(languages) => crate::data::conversation::normalize_languages(languages)?,
+        None => crate::data::conversation::active_conversation_languages(&app_handle).await,
+    };
+    let mut system_prompt = match crate::data::conversation::describe_for_prompt(&languages) {
+        Some(language_note) => format!("{}\n\n## LANGUAGE\n{}", profile.system_prompt, language_note),
+        None => profile.system_prompt,
+    };
+    // Liked and disliked insights steer what the model suggests next
+    if let Some(feedback) = crate::data::conversation::feedback_prompt_section(&app_handle).await {
+        system_prompt = format!("{}\n\n{}", system_prompt, feedback);
+    }
+    
+    println!("💬 CONVERSATIONAL AI: Using model {} for insights, session {}", model, session_id);
+    
+    generate_agent_response_stream(app_handle, model, full_prompt, system_prompt, None, session_id, "conversational_ai".to_string(), seed).await
+}
+
+// Helper function for streaming with system prompt
+async fn generate_agent_response_stream(
+    app_handle: AppHandle,
+    model: String,
+    prompt: String,
+    system_prompt: String,
+    context: Option<Vec<ChatContextMessage>>,
+    session_id: String,
+    agent_type: String,
+    seed: Option<i64>,
+) -> Result<(), String> {
+    // Acquire semaphore permit for memory safety (limits concurrent model loads)
+    let _permit = REQUEST_SEMAPHORE.acquire().await.map_err(|e| format!("Failed to acquire semaphore: {}", e))?;
+    
+    println!("🔒 Acquired request semaphore for {} agent (session: {})", agent_type, session_id);
+    
+    // Settings may move the agent to another backend or model, and a missing model falls back
+    let (backend, model) = fallback::resolve(&app_handle, &agent_type, model).await?;
+    
+    // Long histories are summarized down to the context budget
+    let context = context::compress_context(&app_handle, &session_id, context).await;
+    let messages = build_messages(Some(system_prompt), context, prompt, None);
+    
+    // Offload as many of this model's layers as free VRAM allows
+    let gpu_layers = backend_gpu_layers(backend.as_ref(), &model).await;
+    
+    // Temperature and length come from the agent registry
+    let options = {
+        let profile = agents::profile(&agent_type);
+        // Conversation coaching repeats key points on purpose, so it's penalized less
+        let repeat_penalty = if agent_type == "conversational_ai" { 1.05 } else { 1.1 };
+        let mut opts = serde_json::json!({
+            "temperature": profile.temperature,
+            "top_p": 0.9,
+            "repeat_penalty": repeat_penalty
+        });
+        if let Some(num_predict) = profile.num_predict {
+            opts["num_predict"] = serde_json::json!(num_predict);
+        }
+        if gpu_layers > 0 {
+            opts["num_gpu"] = serde_json::json!(gpu_layers);
+            opts["num_thread"] = serde_json::json!(4); // Reduce CPU threads when using GPU
+        }
+        Some(opts)
+    };
+
+    let seed = resolve_seed(seed);
+    let request = ChatRequest {
+        model: model.clone(),
+        messages,
+        stream: Some(true),
+        tools: None,
+        format: None,
+        options: with_seed(options, seed),
+    };
+    
+    println!("🤖 Starting {} agent ({} on {}) streaming for session: {}", agent_type, model, backend.name(), session_id);
+    
+    // Emit start event with correct agent type
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "start",
+        "model": model,
+        "backend": backend.name(),
+        "agent_type": agent_type,
+        "seed": seed
+    })) {
+        return Err(format!("Failed to emit start event: {}", e));
+    }
+    
+    // Use enhanced streaming with tighter config for agents
+    let agent_config = StreamConfig {
+        max_total_duration: Duration::from_secs(180), // 3 minutes for agents
+        max_chunk_gap: Duration::from_secs(20),       // 20 seconds between chunks
+        chunk_timeout: Duration::from_secs(8),        // 8 seconds per chunk
+        max_consecutive_repeats: 3,                   // Max 3 consecutive repeats for agents
+        max_consecutive_empty_chunks: 30,               // Max 30 consecutive empty chunks (increased)
+    };
+
+    
+    let result = stream_ollama_response_enhanced(app_handle, backend, request, session_id.clone(), &agent_type, agent_config).await;
+    
+    // Semaphore is automatically released when _permit goes out of scope
+    println!("🔓 Released request semaphore for {} agent (session: {})", agent_type, session_id);
+    
+    result
+}
+
+// Helper function for streaming with image
+async fn generate_agent_response_stream_with_image(
+    app_handle: AppHandle,
+    model: String,
+    prompt: String,
+    system_prompt: String,
+    image_base64: String,
+    context: Option<Vec<ChatContextMessage>>,
+    session_id: String,
+    agent_type: String,
+    seed: Option<i64>,
+) -> Result<(), String> {
+    // Acquire semaphore permit for memory safety (limits concurrent model loads)
+    let _permit = REQUEST_SEMAPHORE.acquire().await.map_err(|e| format!("Failed to acquire semaphore: {}", e))?;
+    
+    println!("🔒 Acquired request semaphore for {} agent with image (session: {})", agent_type, session_id);
+    
+    let (backend, model) = fallback::resolve(&app_handle, &agent_type, model).await?;
+    let gpu_layers = backend_gpu_layers(backend.as_ref(), &model).await;
+    
+    let seed = resolve_seed(seed);
+    let request = ChatRequest {
+        model: model.clone(),
+        messages: build_messages(Some(system_prompt), context, prompt, Some(vec![image_base64])),
+        stream: Some(true),
+        tools: None,
+        format: None,
+        options: with_seed({
+            let profile = agents::profile(&agent_type);
+            let mut opts = serde_json::json!({
+                "temperature": profile.temperature,
+                "top_p": 0.9
+            });
+            if let Some(num_predict) = profile.num_predict {
+                opts["num_predict"] = serde_json::json!(num_predict);
+            }
+            if gpu_layers > 0 {
+                opts["num_gpu"] = serde_json::json!(gpu_layers);
+                opts["num_thread"] = serde_json::json!(4);
+            }
+            Some(opts)
+        }, seed),
+    };
+    
+    println!("👁️ Starting {} vision analysis ({} on {}) for session: {}", agent_type, model, backend.name(), session_id);
+    
+    // Emit start event with correct agent type
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "start",
+        "model": model,
+        "backend": backend.name(),
+        "agent_type": agent_type,
+        "seed": seed
+    })) {
+        return Err(format!("Failed to emit start event: {}", e));
+    }
+    
+    // Use enhanced streaming with vision-specific config
+    let vision_config = StreamConfig {
+        max_total_duration: Duration::from_secs(120), // 2 minutes for vision
+        max_chunk_gap: Duration::from_secs(25),       // 25 seconds between chunks
+        chunk_timeout: Duration::from_secs(10),       // 10 seconds per chunk
+        max_consecutive_repeats: 4,                   // Max 4 consecutive repeats for vision
+        max_consecutive_empty_chunks: 25,              // Max 25 consecutive empty chunks (increased)
+    };
+
+    let result = stream_ollama_response_enhanced(app_handle, backend, request, session_id.clone(), &agent_type, vision_config).await;
+    
+    // Semaphore is automatically released when _permit goes out of scope
+    println!("🔓 Released request semaphore for {} agent (session: {})", agent_type, session_id);
+    
+    result
+}
+
+#[crate::command]
+pub async fn get_ollama_model_info(model_name: String) -> Result<serde_json::Value, String> {
+    let client = Arc::clone(&HTTP_CLIENT);
+    
+    let request = serde_json::json!({
+        "name": model_name
+    });
+    
+    match backend::ollama_endpoint().request(&client, reqwest::Method::POST, "/api/show").json(&request).send().await {
+        Ok(response) => {
+            if response.status().is_success() {
+                match response.json::<serde_json::Value>().await {
+                    Ok(model_info) => Ok(model_info),
+                    Err(e) => Err(format!("Failed to parse model info response: {}", e)),
+                }
+            } else {
+                let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+                Err(format!("Failed to get model info: {}", error_text))
+            }
+        }
+        Err(e) => Err(format!("Failed to connect to Ollama: {}", e)),
+    }
+}
+
+// Additional helper function for custom timeout streaming (for specific use cases)
+#[crate::command]
+pub async fn generate_with_custom_timeouts(
+    app_handle: AppHandle,
+    model: String,
+    prompt: String,
+    session_id: String,
+    total_timeout_secs: u64,
+    chunk_gap_secs: u64,
+    max_repeats: usize,
+    seed: Option<i64>,
+) -> Result<(), String> {
+    let gpu_layers = gpu_layers::gpu_layers_for(&model).await;
+    let options = if gpu_layers > 0 {
+        Some(serde_json::json!({
+            "num_gpu": gpu_layers,
+            "num_thread": 4
+        }))
+    } else {
+        None
+    };
+    
+    let seed = resolve_seed(seed);
+    let request = ChatRequest {
+        model: model.clone(),
+        messages: vec![ChatMessage::new("user", prompt)],
+        stream: Some(true),
+        tools: None,
+        format: None,
+        options: with_seed(options, seed),
+    };
+    
+    println!("🚀 Starting custom timeout streaming for session: {} (total: {}s, gap: {}s, repeats: {})", 
+             session_id, total_timeout_secs, chunk_gap_secs, max_repeats);
+    
+    // Emit start event
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "start",
+        "model": model,
+        "seed": seed
+    })) {
+        return Err(format!("Failed to emit start event: {}", e));
+    }
+    
+    let custom_config = StreamConfig {
+        max_total_duration: Duration::from_secs(total_timeout_secs),
+        max_chunk_gap: Duration::from_secs(chunk_gap_secs),
+        chunk_timeout: Duration::from_secs(10),
+        max_consecutive_repeats: max_repeats,
+        max_consecutive_empty_chunks: 25,
+    };
+    
+    stream_ollama_response_enhanced(app_handle, backend::ollama_backend(), request, session_id, DIRECT_AGENT, custom_config).await
+}
+
+
+
+
+
+// ADDED MCP FUNCTIONALITY (only built with the `mcp` feature)
+
+
+// Add these imports to the top of your ollama.rs file
+#[cfg(feature = "mcp")]
+use crate::mcp::commands::MCPSessionManager;
+#[cfg(feature = "mcp")]
+use crate::mcp::types::MCPSessionConfig;
+
+// Add this new command for MCP-enabled AI responses
+#[cfg(feature = "mcp")]
+#[crate::command]
+pub async fn generate_mcp_enabled_response(
+    app_handle: AppHandle,
+    model: String,
+    prompt: String,
+    context: Option<Vec<ChatContextMessage>>,
+    session_id: String,
+    mcp_session_id: Option<String>,
+    seed: Option<i64>,
+    mcp_sessions: tauri::State<'_, MCPSessionManager>,
+) -> Result<(), String> {
+    require_unlocked(CommandGroup::McpExecution)?;
+    let profile = agents::profile("mcp");
+    // The registry only picks the model when one is configured; otherwise the caller's choice stands
+    let model = if profile.model.is_empty() { model } else { profile.model.clone() };
+    let (backend, model) = fallback::resolve(&app_handle, "mcp", model).await?;
+    
+    // The session's tools go to the model as native function definitions
+    let (system_prompt, tools) = build_mcp_tools(mcp_session_id.clone(), &mcp_sessions, profile.system_prompt).await?;
+    let context = context::compress_context(&app_handle, &session_id, context).await;
+    
+    // Offload as many of this model's layers as free VRAM allows
+    let gpu_layers = backend_gpu_layers(backend.as_ref(), &model).await;
+    let mut opts = serde_json::json!({
+        "temperature": profile.temperature,
+        "top_p": 0.9,
+        "repeat_penalty": 1.1
+    });
+    if let Some(num_predict) = profile.num_predict {
+        opts["num_predict"] = serde_json::json!(num_predict);
+    }
+    if gpu_layers > 0 {
+        opts["num_gpu"] = serde_json::json!(gpu_layers);
+        opts["num_thread"] = serde_json::json!(4);
+    }
+    let options = Some(opts);
+    
+    let seed = resolve_seed(seed);
+    let request = ChatRequest {
+        model: model.clone(),
+        messages: build_messages(Some(system_prompt), context, prompt, None),
+        stream: Some(true),
+        tools,
+        format: None,
+        options: with_seed(options, seed),
+    };
+    
+    println!("🤖 Starting MCP-enabled streaming for session: {} (MCP: {:?})", session_id, mcp_session_id);
+    
+    // Emit start event
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "start",
+        "model": model,
+        "mcp_enabled": mcp_session_id.is_some(),
+        "mcp_session_id": mcp_session_id,
+        "seed": seed
+    })) {
+        return Err(format!("Failed to emit start event: {}", e));
+    }
+    
+    // Use enhanced streaming with MCP tool execution
+    stream_ollama_response_with_mcp(app_handle, backend, request, session_id, mcp_session_id, mcp_sessions).await
+}
+
+// System prompt and function definitions for an MCP session's tools
+#[cfg(feature = "mcp")]
+async fn build_mcp_tools(
+    mcp_session_id: Option<String>,
+    mcp_sessions: &tauri::State<'_, MCPSessionManager>,
+    system_prompt: String,
+) -> Result<(String, Option<Vec<serde_json::Value>>), String> {
+    if let Some(session_id) = mcp_session_id {
+        let sessions_guard = mcp_sessions.lock().await;
+        if let Some(session) = sessions_guard.get(&session_id) {
+            let tools = session.get_available_tools().await;
+            let definitions = tools
+                .iter()
+                .map(|tool| {
+                    let parameters = if tool.parameters_schema.is_object() {
+                        tool.parameters_schema.clone()
+                    } else {
+                        serde_json::json!({ "type": "object", "properties": {} })
+                    };
+                    serde_json::json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": format!("{} (risk: {:?})", tool.description, tool.danger_level),
+                            "parameters": parameters,
+                        }
+                    })
+                })
+                .collect();
+            
+            return Ok((system_prompt, Some(definitions)));
+        }
+    }
+    
+    Ok(("You are a helpful AI assistant.".to_string(), None))
+}
+
+// Model turns per request; each turn may call tools whose results feed the next one
+#[cfg(feature = "mcp")]
+const MAX_TOOL_ROUNDS: usize = 6;
+
+// One streamed model turn that reached its final chunk
+#[cfg(feature = "mcp")]
+struct McpTurn {
+    text: String,
+    tool_calls: Vec<ToolCall>,
+    decoder: Box<dyn backend::ChunkDecoder>,
+}
+
+// Enhanced streaming function that can execute MCP tools
+#[cfg(feature = "mcp")]
+async fn stream_ollama_response_with_mcp(
+    app_handle: AppHandle,
+    backend: Box<dyn LLMBackend>,
+    mut request: ChatRequest,
+    session_id: String,
+    mcp_session_id: Option<String>,
+    mcp_sessions: tauri::State<'_, MCPSessionManager>,
+) -> Result<(), String> {
+    // Cancellable by session id until the last round ends, tool calls included
+    let operation = crate::cancellation::start(&session_id, crate::cancellation::OperationKind::AiResponse, "mcp");
+
+    announce(&app_handle, AnnouncementCategory::ModelResponding, AnnouncementPriority::Polite, "Assistant is responding");
+
+    let mut state = StreamState::new();
+    for _ in 0..MAX_TOOL_ROUNDS {
+        let Some(turn) = stream_mcp_turn(&app_handle, backend.as_ref(), &request, &session_id, mcp_session_id.is_some(), &mut state, &operation).await? else {
+            return Ok(());
+        };
+        
+        let mcp_id = match mcp_session_id.as_deref() {
+            Some(mcp_id) if !turn.tool_calls.is_empty() => mcp_id,
+            _ => {
+                if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+                    "type": "chunk",
+                    "text": "",
+                    "done": true,
+                    "mcp_enabled": mcp_session_id.is_some()
+                })) {
+                    eprintln!("Failed to emit chunk event: {}", e);
+                }
+                emit_complete(&app_handle, &session_id, turn.decoder.diagnostics()).await;
+                return Ok(());
+            }
+        };
+        
+        // Hand the calls and their results back to the model for its next turn
+        let mut tool_calls = turn.tool_calls;
+        let results = run_tool_calls(&mut tool_calls, mcp_id, &mcp_sessions, &app_handle, &session_id, &operation).await;
+        if operation.is_cancelled() {
+            println!("🛑 Session cancelled during tool calls: {}", session_id);
+            let _ = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+                "type": "cancelled",
+                "message": "Response cancelled by user"
+            }));
+            return Ok(());
+        }
+        request.messages.push(ChatMessage { tool_calls, ..ChatMessage::new("assistant", turn.text) });
+        request.messages.extend(results);
+    }
+    
+    let reason = format!("Stopped after {} rounds of tool calls", MAX_TOOL_ROUNDS);
+    emit_error(&app_handle, &session_id, &reason).await;
+    emit_complete(&app_handle, &session_id, &StreamDiagnostics::default()).await;
+    Err(reason)
+}
+
+// Stream one model turn to the frontend. `None` means the stream was stopped (cancelled or
+// terminated) and already cleaned up; errors are likewise reported before returning.
+#[cfg(feature = "mcp")]
+async fn stream_mcp_turn(
+    app_handle: &AppHandle,
+    backend: &dyn LLMBackend,
+    request: &ChatRequest,
+    session_id: &str,
+    mcp_enabled: bool,
+    state: &mut StreamState,
+    operation: &crate::cancellation::Operation,
+) -> Result<Option<McpTurn>, String> {
+    let client = Arc::clone(&HTTP_CLIENT);
+    
+    // Make request with timeout
+    let sent = tokio::select! {
+        biased;
+        _ = operation.token().cancelled() => None,
+        result = timeout(Duration::from_secs(30), backend.send(&client, request)) => Some(result),
+    };
+    let response = match sent {
+        None => {
+            finish_cancelled(app_handle, session_id, backend, &request.model).await;
+            return Ok(None);
+        }
+        Some(Ok(Ok(response))) => response,
+        Some(Ok(Err(e))) => {
+            emit_error(app_handle, session_id, &e).await;
+            return Err(e);
+        }
+        Some(Err(_)) => {
+            emit_error(app_handle, session_id, "Request timeout").await;
+            return Err("Request timeout".to_string());
+        }
+    };
+
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        let error_msg = format!("Generation failed: {}", error_text);
+        
+        emit_error(app_handle, session_id, &error_msg).await;
+        return Err(error_msg);
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut parser = backend.decoder();
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+
+    loop {
+        // Check for cancellation
+        if operation.is_cancelled() {
+            break;
+        }
+
+        // Check timeouts and patterns
+        if let Some(timeout_reason) = state.should_timeout(Duration::from_secs(300), Duration::from_secs(30)) {
+            emit_timeout(app_handle, session_id, &timeout_reason).await;
+            emit_complete(app_handle, session_id, parser.diagnostics()).await;
+            return Err(timeout_reason);
+        }
+
+        // Read next chunk
+        let chunk_result = tokio::select! {
+            biased;
+            _ = operation.token().cancelled() => break,
+            result = timeout(Duration::from_secs(10), stream.next()) => result,
+        };
+        
+        let (responses, stream_ended) = match chunk_result {
+            Ok(Some(Ok(chunk))) => (parser.push(&chunk), false),
+            Ok(Some(Err(e))) => {
+                let error_msg = format!("Stream error: {}", e);
+                emit_error(app_handle, session_id, &error_msg).await;
+                return Err(error_msg);
+            }
+            Ok(None) => (parser.finish(), true),
+            Err(_) => {
+                emit_timeout(app_handle, session_id, "Chunk read timeout").await;
+                emit_complete(app_handle, session_id, parser.diagnostics()).await;
+                return Err("Chunk read timeout".to_string());
+            }
+        };
+
+        for response_chunk in responses {
+            // Tool calls arrive as structured data, usually in a chunk with no text
+            tool_calls.extend(response_chunk.tool_calls.iter().cloned());
+            if response_chunk.text.is_empty() && !response_chunk.done {
+                continue;
+            }
+
+            match state.update_chunk(&response_chunk.text) {
+                ChunkResult::Continue => {},
+                ChunkResult::Exit(reason) => {
+                    emit_termination(app_handle, session_id, &reason, state.chunk_count, state.repeat_count).await;
+                    emit_complete(app_handle, session_id, parser.diagnostics()).await;
+                    return Ok(None);
+                }
+            }
+
+            text.push_str(&response_chunk.text);
+            // `done` is sent once the whole exchange, including tool rounds, is finished
+            if !response_chunk.text.is_empty() {
+                if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+                    "type": "chunk",
+                    "text": response_chunk.text,
+                    "done": false,
+                    "mcp_enabled": mcp_enabled
+                })) {
+                    eprintln!("Failed to emit chunk event: {}", e);
+                }
+            }
+
+            if response_chunk.done {
+                record_usage(app_handle, session_id, "mcp", &request.model, &response_chunk);
+                return Ok(Some(McpTurn { text, tool_calls, decoder: parser }));
+            }
+        }
+
+        if stream_ended {
+            return Ok(Some(McpTurn { text, tool_calls, decoder: parser }));
+        }
+    }
+
+    drop(stream);
+    finish_cancelled(app_handle, session_id, backend, &request.model).await;
+    Ok(None)
+}
+
+// Execute the model's tool calls through the MCP session, returning one `tool` message per call
+#[cfg(feature = "mcp")]
+async fn run_tool_calls(
+    tool_calls: &mut [ToolCall],
+    mcp_session_id: &str,
+    mcp_sessions: &tauri::State<'_, MCPSessionManager>,
+    app_handle: &AppHandle,
+    session_id: &str,
+    operation: &crate::cancellation::Operation,
+) -> Vec<ChatMessage> {
+    let mut results = Vec::new();
+    for (index, call) in tool_calls.iter_mut().enumerate() {
+        // Calls after a cancel aren't started; the caller stops before the next round
+        if operation.is_cancelled() {
+            break;
+        }
+        // Ollama doesn't number its calls, but OpenAI-compatible servers match results by id
+        let call_id = call.id.get_or_insert_with(|| format!("call_{}", index)).clone();
+        let tool_name = call.function.name.clone();
+        println!("🔧 Model called tool: {} {}", tool_name, call.function.arguments);
+        let _ = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+            "type": "tool_call",
+            "tool_name": tool_name,
+            "arguments": call.function.arguments
+        }));
+        
+        let content = {
+            let sessions_guard = mcp_sessions.lock().await;
+            match sessions_guard.get(mcp_session_id) {
+                None => format!("MCP session {} not found", mcp_session_id),
+                // Run under the session's token so OCR and worker calls inside the tool stop too
+                Some(session) => match operation.run(session.execute_tool(&tool_name, call.function.arguments.clone())).await {
+                    Ok(result) => {
+                        // Emit tool execution result to frontend
+                        let _ = app_handle.emit(&format!("mcp-tool-result-{}", session_id), serde_json::json!({
+                            "tool_name": tool_name,
+                            "result": &result,
+                            "session_id": session_id
+                        }));
+                        serde_json::json!({
+                            "success": result.success,
+                            "result": result.result,
+                            "error": result.error,
+                        }).to_string()
+                    }
+                    Err(e) => format!("Tool execution error: {}", e),
+                },
+            }
+        };
+        
+        results.push(ChatMessage {
+            tool_name: Some(tool_name),
+            tool_call_id: Some(call_id),
+            ..ChatMessage::new("tool", content)
+        });
+    }
+    results
+}
+
+// Add MCP session management commands for the frontend
+#[cfg(feature = "mcp")]
+#[crate::command]
+pub async fn create_mcp_session_for_ai(
+    app_handle: AppHandle,
+    mcp_sessions: tauri::State<'_, MCPSessionManager>,
+) -> Result<String, String> {
+    let config = MCPSessionConfig {
+        require_approval: true,
+        session_timeout_seconds: 300,
+        enable_logging: true,
+        server_name: "enteract-ai-mcp".to_string(),
+        server_version: "1.0.0".to_string(),
+        file_sandbox: Default::default(),
+        shell: Default::default(),
+        permissions: Default::default(),
+        rate_limits: Default::default(),
+    };
+    
+    let session_info = crate::mcp::commands::start_mcp_session(
+        Some(config),
+        None,
+        app_handle,
+        mcp_sessions,
+    ).await?;
+    
+    Ok(session_info.id)
+}
+
+#[cfg(feature = "mcp")]
+#[crate::command]
+pub async fn get_mcp_session_for_ai(
+    mcp_session_id: String,
+    mcp_sessions: tauri::State<'_, MCPSessionManager>,
+) -> Result<crate::mcp::types::MCPSessionInfo, String> {
+    crate::mcp::commands::get_mcp_session_info(mcp_session_id, mcp_sessions).await
+}
+
+#[derive(Deserialize)]
+struct AgentArgs {
+    prompt: String,
+    session_id: String,
+    #[serde(default)]
+    context: Option<Vec<ChatContextMessage>>,
+    #[serde(default)]
+    seed: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct VisionArgs {
+    prompt: String,
+    session_id: String,
+    image_base64: String,
+    #[serde(default)]
+    seed: Option<i64>,
+}
+
+fn agent_params() -> Vec<ActionParam> {
+    vec![
+        param("prompt", "string", true, "What to ask the agent"),
+        param("session_id", "string", true, "Chat session the streamed reply belongs to"),
+        param("context", "object", false, "Earlier chat messages as {role, content}"),
+        param("seed", "number", false, "Sampling seed for reproducible replies"),
+    ]
+}
+
+/// Command palette actions for Ollama and the agents
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    vec![
+        RegisteredAction {
+            descriptor: descriptor("ollama.status", ActionKind::Command, "Ollama Status",
+                "Check whether Ollama is running", vec![], &["models", "llm"]),
+            handler: |_, _| Box::pin(async move { to_value(get_ollama_status().await?) }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("ollama.list_models", ActionKind::Command, "List Ollama Models",
+                "List models installed in Ollama", vec![], &["llm"]),
+            handler: |_, _| Box::pin(async move { to_value(get_ollama_models().await?) }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("ai.cancel", ActionKind::Command, "Cancel AI Response",
+                "Stop a streaming response", vec![param("session_id", "string", true, "Chat session to cancel")], &["stop", "abort"]),
+            handler: |_, params| Box::pin(async move {
+                let SessionArgs { session_id } = args(params)?;
+                cancel_ai_response(session_id)?;
+                Ok(serde_json::Value::Null)
+            }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("agent.enteract", ActionKind::Agent, "Ask Enteract",
+                "General assistant", agent_params(), &["chat", "assistant"]),
+            handler: |app, params| Box::pin(async move {
+                let a: AgentArgs = args(params)?;
+                generate_enteract_agent_response(app, a.prompt, a.context, a.session_id, a.seed).await?;
+                Ok(serde_json::Value::Null)
+            }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("agent.coding", ActionKind::Agent, "Coding Agent",
+                "Help with code", agent_params(), &["programming", "code"]),
+            handler: |app, params| Box::pin(async move {
+                let a: AgentArgs = args(params)?;
+                generate_coding_agent_response(app, a.prompt, a.context, a.session_id, a.seed).await?;
+                Ok(serde_json::Value::Null)
+            }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("agent.research", ActionKind::Agent, "Deep Research",
+                "Step-by-step research with reasoning", agent_params(), &["think", "reasoning"]),
+            handler: |app, params| Box::pin(async move {
+                let a: AgentArgs = args(params)?;
+                generate_deep_research(app, a.prompt, a.context, a.session_id, a.seed).await?;
+                Ok(serde_json::Value::Null)
+            }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("agent.vision", ActionKind::Agent, "Vision Analysis",
+                "Describe or answer questions about a screenshot",
+                vec![
+                    param("prompt", "string", true, "What to ask about the image"),
+                    param("session_id", "string", true, "Chat session the streamed reply belongs to"),
+                    param("image_base64", "string", true, "PNG screenshot, base64 encoded"),
+                    param("seed", "number", false, "Sampling seed for reproducible replies"),
+                ],
+                &["image", "screenshot"]),
+            handler: |app, params| Box::pin(async move {
+                let a: VisionArgs = args(params)?;
+                generate_vision_analysis(app, a.prompt, a.image_base64, a.session_id, a.seed).await?;
+                Ok(serde_json::Value::Null)
+            }),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_request_gets_a_reportable_seed() {
+        assert_eq!(resolve_seed(Some(42)), 42);
+
+        // Without a seed one is generated and sent, so the start event can report it
+        let seed = resolve_seed(None);
+        assert!(seed >= 0);
+        let options = with_seed(Some(serde_json::json!({ "num_gpu": 20 })), seed).unwrap();
+        assert_eq!(options["seed"], serde_json::json!(seed));
+        assert_eq!(options["num_gpu"], serde_json::json!(20));
+        assert_eq!(with_seed(None, 7), Some(serde_json::json!({ "seed": 7 })));
+    }
+}
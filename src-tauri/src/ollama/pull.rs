@@ -0,0 +1,252 @@
+// src-tauri/src/ollama/pull.rs
+// Model pulls - Ollama's streamed /api/pull progress turned into `ollama-pull-progress-<model>`
+// events with a percentage and download speed per layer, cancellable with `cancel_pull`.
+//
+// Event names only allow letters, digits, `-`, `/`, `:` and `_`, so other characters in the
+// model name become `_`: pulling `llama3.2:3b` reports on `ollama-pull-progress-llama3_2:3b`.
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use super::{backend, PullRequest};
+
+// Ollama reports many times a second; the UI doesn't need more than this
+const EMIT_INTERVAL: Duration = Duration::from_millis(150);
+// Speed is measured over at least this long and smoothed, or it jumps with every line
+const SPEED_WINDOW: Duration = Duration::from_millis(500);
+const SPEED_SMOOTHING: f64 = 0.3;
+
+// One line of a streamed /api/pull reply
+#[derive(Debug, Deserialize)]
+struct PullProgressLine {
+    #[serde(default)]
+    status: String,
+    digest: Option<String>,
+    total: Option<u64>,
+    completed: Option<u64>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullProgress {
+    pub model: String,
+    /// Ollama's status, e.g. "pulling manifest", "verifying sha256 digest" or "success"
+    pub status: String,
+    /// Layer being downloaded
+    pub digest: Option<String>,
+    pub completed: Option<u64>,
+    pub total: Option<u64>,
+    /// Of the current layer, 0-100
+    pub percent: Option<f64>,
+    pub bytes_per_second: Option<f64>,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+fn percent(completed: Option<u64>, total: Option<u64>) -> Option<f64> {
+    match (completed, total) {
+        (Some(completed), Some(total)) if total > 0 => Some((completed as f64 / total as f64 * 100.0).min(100.0)),
+        _ => None,
+    }
+}
+
+/// Download speed of the current layer, reset when the layer changes
+#[derive(Default)]
+struct SpeedMeter {
+    digest: Option<String>,
+    sample: Option<(Instant, u64)>,
+    rate: Option<f64>,
+}
+
+impl SpeedMeter {
+    fn update(&mut self, digest: Option<&str>, completed: Option<u64>, now: Instant) -> Option<f64> {
+        if digest != self.digest.as_deref() {
+            *self = SpeedMeter { digest: digest.map(|digest| digest.to_string()), ..SpeedMeter::default() };
+        }
+        let completed = completed?;
+        match self.sample {
+            None => self.sample = Some((now, completed)),
+            Some((at, bytes)) => {
+                let elapsed = now.duration_since(at);
+                if elapsed >= SPEED_WINDOW {
+                    let current = completed.saturating_sub(bytes) as f64 / elapsed.as_secs_f64();
+                    self.rate = Some(match self.rate {
+                        Some(rate) => rate + SPEED_SMOOTHING * (current - rate),
+                        None => current,
+                    });
+                    self.sample = Some((now, completed));
+                }
+            }
+        }
+        self.rate
+    }
+}
+
+fn progress_event(model_name: &str) -> String {
+    let name: String = model_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_') { c } else { '_' })
+        .collect();
+    format!("ollama-pull-progress-{}", name)
+}
+
+fn operation_id(model_name: &str) -> String {
+    format!("pull:{}", model_name)
+}
+
+/// Pull a model and wait for it to finish, reporting progress as it goes. The pull is
+/// cancellable as `pull:<model>`, which also stops the download in Ollama.
+pub(crate) async fn pull_model_with_progress<F>(model_name: &str, mut on_progress: F) -> Result<(), String>
+where
+    F: FnMut(&PullProgress),
+{
+    crate::offline_mode::require_online(crate::offline_mode::RemoteFeature::ModelDownload)?;
+    let operation = crate::cancellation::start(operation_id(model_name), crate::cancellation::OperationKind::ModelPull, model_name);
+    // The shared client's 60s timeout would cut off any real download
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let request = PullRequest {
+        name: model_name.to_string(),
+        insecure: Some(false),
+        stream: Some(true),
+    };
+
+    operation.run(async {
+        let response = backend::ollama_endpoint()
+            .request(&client, reqwest::Method::POST, "/api/pull")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Failed to pull model: {}", error_text));
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut parser = crate::ndjson_parser::NdjsonStreamParser::<PullProgressLine>::new();
+        let mut speed = SpeedMeter::default();
+        let mut stream_ended = false;
+        while !stream_ended {
+            let lines = match stream.next().await {
+                Some(Ok(chunk)) => parser.push(&chunk),
+                Some(Err(e)) => return Err(format!("Pull stream error: {}", e)),
+                None => {
+                    stream_ended = true;
+                    parser.finish()
+                }
+            };
+            for line in lines {
+                if let Some(error) = line.error {
+                    return Err(format!("Failed to pull model: {}", error));
+                }
+                let done = line.status == "success";
+                on_progress(&PullProgress {
+                    model: model_name.to_string(),
+                    percent: percent(line.completed, line.total),
+                    bytes_per_second: speed.update(line.digest.as_deref(), line.completed, Instant::now()),
+                    status: line.status,
+                    digest: line.digest,
+                    completed: line.completed,
+                    total: line.total,
+                    done,
+                    error: None,
+                });
+                if done {
+                    return Ok(());
+                }
+            }
+        }
+        Err(format!("Pull of {} ended before it finished", model_name))
+    }).await
+}
+
+/// Pull a model, streaming progress as `ollama-pull-progress-<model>` events (see the top of
+/// this file for how the name is written). Returns once the model is installed.
+#[crate::command]
+pub async fn pull_ollama_model(app_handle: AppHandle, model_name: String) -> Result<String, String> {
+    let model_name = model_name.trim().to_string();
+    if model_name.is_empty() {
+        return Err("Model name is required".to_string());
+    }
+    let event = progress_event(&model_name);
+    println!("⬇️ Pulling model {} (progress on {})", model_name, event);
+
+    let mut last_emit: Option<(Instant, String)> = None;
+    let result = pull_model_with_progress(&model_name, |progress| {
+        // Status changes always go out, byte counts at most every EMIT_INTERVAL
+        let due = match &last_emit {
+            Some((at, status)) => progress.done || *status != progress.status || at.elapsed() >= EMIT_INTERVAL,
+            None => true,
+        };
+        if due {
+            let _ = app_handle.emit(&event, progress);
+            last_emit = Some((Instant::now(), progress.status.clone()));
+        }
+    })
+    .await;
+
+    match result {
+        Ok(()) => {
+            super::fallback::forget_installed();
+            super::gpu_layers::forget(&model_name);
+            Ok(format!("Successfully pulled model: {}", model_name))
+        }
+        Err(e) => {
+            let status = if e == crate::cancellation::CANCELLED { "cancelled" } else { "error" };
+            let _ = app_handle.emit(&event, PullProgress {
+                model: model_name.clone(),
+                status: status.to_string(),
+                digest: None,
+                completed: None,
+                total: None,
+                percent: None,
+                bytes_per_second: None,
+                done: true,
+                error: Some(e.clone()),
+            });
+            Err(e)
+        }
+    }
+}
+
+/// Stop a model pull started by `pull_ollama_model` or setup; false if it isn't running
+#[crate::command]
+pub fn cancel_pull(model_name: String) -> Result<bool, String> {
+    let found = crate::cancellation::cancel(&operation_id(model_name.trim()));
+    if found {
+        println!("🛑 Cancelling pull of {}", model_name.trim());
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_percent_speed_and_event_name() {
+        assert_eq!(percent(Some(50), Some(200)), Some(25.0));
+        assert_eq!(percent(Some(10), None), None);
+        assert_eq!(percent(Some(10), Some(0)), None);
+
+        let start = Instant::now();
+        let mut speed = SpeedMeter::default();
+        assert_eq!(speed.update(Some("sha256:a"), Some(0), start), None);
+        // Too soon after the first sample to measure
+        assert_eq!(speed.update(Some("sha256:a"), Some(100), start + Duration::from_millis(100)), None);
+        assert_eq!(speed.update(Some("sha256:a"), Some(1000), start + Duration::from_secs(1)), Some(1000.0));
+        // Smoothed towards the new rate rather than jumping to it
+        let smoothed = speed.update(Some("sha256:a"), Some(3000), start + Duration::from_secs(2)).unwrap();
+        assert!((smoothed - 1300.0).abs() < 1e-6);
+        // A new layer starts over
+        assert_eq!(speed.update(Some("sha256:b"), Some(0), start + Duration::from_secs(3)), None);
+
+        assert_eq!(progress_event("llama3.2:3b"), "ollama-pull-progress-llama3_2:3b");
+        assert_eq!(progress_event("library/qwen2.5"), "ollama-pull-progress-library/qwen2_5");
+    }
+}
@@ -0,0 +1,172 @@
+// src-tauri/src/ollama/rag.rs
+// Retrieval-augmented chat - search the enhanced RAG index, answer from the top chunks and tell
+// the UI which chunks each [n] citation refers to.
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, State};
+
+use super::{agents, generate_agent_response_stream, ChatContextMessage};
+use crate::enhanced_rag_commands::EnhancedRagSystemState;
+use crate::chunking_service::ChunkLocation;
+use crate::enhanced_rag_system::{EnhancedDocumentChunk, RelevantChunks};
+
+const RAG_TOP_K: usize = 6;
+// Keeps the prompt inside small local models' context windows
+const MAX_SOURCE_CHARS: usize = 1500;
+
+/// A chunk the answer may cite as `[index]`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RagSource {
+    pub index: usize,
+    pub chunk_id: String,
+    pub document_id: String,
+    pub document_name: String,
+    pub chunk_index: i32,
+    /// Where in the document the chunk is, e.g. "page 12, §3.2 Installation"
+    pub location: Option<String>,
+    pub score: Option<f32>,
+}
+
+fn source_excerpt(content: &str) -> String {
+    let content = content.trim();
+    if content.chars().count() > MAX_SOURCE_CHARS {
+        let cut: String = content.chars().take(MAX_SOURCE_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        content.to_string()
+    }
+}
+
+/// Number the top chunks and build the prompt that quotes them
+fn build_cited_prompt(
+    question: &str,
+    found: &RelevantChunks,
+    document_names: &HashMap<String, String>,
+) -> (String, Vec<RagSource>) {
+    let mut sources = Vec::new();
+    let mut prompt = String::from("Sources:\n\n");
+    for (i, chunk) in found.chunks.iter().take(RAG_TOP_K).enumerate() {
+        let document_name = document_names
+            .get(&chunk.document_id)
+            .cloned()
+            .unwrap_or_else(|| chunk.document_id.clone());
+        let location = ChunkLocation::from_metadata(chunk.metadata.as_deref()).and_then(|location| location.label());
+        prompt.push_str(&format!(
+            "[{}] {} ({})\n{}\n\n",
+            i + 1,
+            document_name,
+            location.clone().unwrap_or_else(|| format!("part {}", chunk.chunk_index + 1)),
+            source_excerpt(&chunk.content)
+        ));
+        sources.push(RagSource {
+            index: i + 1,
+            chunk_id: chunk.id.clone(),
+            document_id: chunk.document_id.clone(),
+            document_name,
+            chunk_index: chunk.chunk_index,
+            location,
+            score: chunk.similarity_score,
+        });
+    }
+    if sources.is_empty() {
+        prompt = String::from("Sources: none of the selected documents matched this question.\n\n");
+        if found.insufficient_context {
+            prompt.push_str(
+                "The documents don't contain the answer. Say that you couldn't find it in them; don't cite sources or guess.\n\n",
+            );
+        }
+    }
+    prompt.push_str(&format!("Question: {}", question));
+    (prompt, sources)
+}
+
+/// Answer `prompt` from the selected documents (all documents when `document_ids` is empty),
+/// only those in `collection_id` when given. Streams on `ollama-stream-{session_id}` like the other agents, with a `sources` event first.
+#[crate::command]
+pub async fn generate_rag_response(
+    app_handle: AppHandle,
+    prompt: String,
+    document_ids: Vec<String>,
+    collection_id: Option<String>,
+    session_id: String,
+    context: Option<Vec<ChatContextMessage>>,
+    seed: Option<i64>,
+    rag_state: State<'_, EnhancedRagSystemState>,
+) -> Result<(), String> {
+    let system = {
+        let rag_state = rag_state.0.lock().map_err(|e| e.to_string())?;
+        match &*rag_state {
+            Some(sys) => Ok(sys.clone()),
+            None => Err("Enhanced RAG system not initialized".to_string())
+        }
+    }?;
+
+    let found = system
+        .search_relevant(&prompt, document_ids, collection_id.as_deref())
+        .await
+        .map_err(|e| format!("Document search failed: {}", e))?;
+    let document_names: HashMap<String, String> = system
+        .get_all_documents()
+        .map(|documents| documents.into_iter().map(|doc| (doc.id, doc.file_name)).collect())
+        .unwrap_or_default();
+    let (full_prompt, sources) = build_cited_prompt(&prompt, &found, &document_names);
+
+    println!("📚 RAG: {} sources for session {}", sources.len(), session_id);
+    if let Err(e) = app_handle.emit(&format!("ollama-stream-{}", session_id), serde_json::json!({
+        "type": "sources",
+        "sources": sources,
+        "insufficientContext": found.insufficient_context,
+    })) {
+        eprintln!("Failed to emit sources event: {}", e);
+    }
+
+    let profile = agents::profile("rag");
+    generate_agent_response_stream(app_handle, profile.model, full_prompt, profile.system_prompt, context, session_id, "rag".to_string(), seed).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &str, document_id: &str, chunk_index: i32, content: &str) -> EnhancedDocumentChunk {
+        EnhancedDocumentChunk {
+            id: id.to_string(),
+            document_id: document_id.to_string(),
+            chunk_index,
+            content: content.to_string(),
+            start_char: 0,
+            end_char: content.len() as i32,
+            token_count: 0,
+            embedding: None,
+            similarity_score: Some(0.5),
+            bm25_score: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_cited_prompt_numbers_sources_in_rank_order() {
+        let names = HashMap::from([("doc-1".to_string(), "handbook.pdf".to_string())]);
+        let mut chunks: Vec<EnhancedDocumentChunk> = (0..8)
+            .map(|i| chunk(&format!("c{}", i), if i == 0 { "doc-1" } else { "doc-2" }, i, "Refunds take 14 days."))
+            .collect();
+        chunks[2].metadata = Some(r#"{"page":12,"heading":"3.2 Installation"}"#.to_string());
+        let found = RelevantChunks { chunks, insufficient_context: false, below_threshold: 0 };
+
+        let (prompt, sources) = build_cited_prompt("How long do refunds take?", &found, &names);
+        assert_eq!(sources.len(), RAG_TOP_K);
+        assert_eq!((sources[0].index, sources[0].chunk_id.as_str()), (1, "c0"));
+        assert_eq!(sources[1].document_name, "doc-2");
+        assert_eq!(sources[2].location.as_deref(), Some("page 12, §3.2 Installation"));
+        assert!(prompt.starts_with("Sources:\n\n[1] handbook.pdf (part 1)\nRefunds take 14 days.\n\n[2] doc-2 (part 2)"));
+        assert!(prompt.contains("[3] doc-2 (page 12, §3.2 Installation)\n"));
+        assert!(prompt.ends_with("Question: How long do refunds take?"));
+
+        let nothing = RelevantChunks { chunks: Vec::new(), insufficient_context: true, below_threshold: 3 };
+        let (prompt, sources) = build_cited_prompt("Anything?", &nothing, &names);
+        assert!(sources.is_empty());
+        assert!(prompt.contains("none of the selected documents matched"));
+        assert!(prompt.contains("couldn't find it"));
+    }
+}
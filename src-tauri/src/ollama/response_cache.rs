@@ -0,0 +1,226 @@
+// src-tauri/src/ollama/response_cache.rs
+// Prompt/response cache - a finished streamed answer is kept in SQLite under the SHA-256 of its
+// model, system prompt and prompt turns (images included), so asking the vision agent about the
+// same screenshot again, or repeating a RAG question, replays the answer instead of regenerating.
+//
+// Off by default: the same prompt then always gets the same answer, which isn't what everyone
+// wants from a chat. Entries older than the TTL are ignored and swept on startup.
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use tauri::{AppHandle, Manager};
+
+use super::ChatRequest;
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+const MAX_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+static DB_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<ResponseCacheSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResponseCacheSettings {
+    pub enabled: bool,
+    /// How long a cached answer is reused
+    pub ttl_secs: u64,
+}
+
+impl Default for ResponseCacheSettings {
+    fn default() -> Self {
+        Self { enabled: false, ttl_secs: 24 * 60 * 60 }
+    }
+}
+
+impl ResponseCacheSettings {
+    fn validate(&self) -> Result<(), String> {
+        if self.ttl_secs == 0 || self.ttl_secs > MAX_TTL_SECS {
+            return Err(format!("Cache TTL must be between 1 second and {} days", MAX_TTL_SECS / 86400));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearedResponseCache {
+    pub entries_removed: usize,
+}
+
+const SETTINGS_FILE: &str = "response_cache.json";
+
+fn open() -> Option<Connection> {
+    let conn = Connection::open(DB_PATH.get()?)
+        .inspect_err(|e| eprintln!("Failed to open response cache: {}", e))
+        .ok()?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS response_cache (
+            key TEXT PRIMARY KEY,
+            model TEXT NOT NULL,
+            response TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            hits INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )
+    .inspect_err(|e| eprintln!("Failed to create response cache table: {}", e))
+    .ok()?;
+    Some(conn)
+}
+
+/// SHA-256 over the model, the system prompt and every other turn with its images
+fn cache_key(request: &ChatRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.model.as_bytes());
+    hasher.update(b"\0");
+    for message in request.messages.iter().filter(|message| message.role == "system") {
+        hasher.update(message.content.as_bytes());
+        hasher.update(b"\0");
+    }
+    hasher.update(b"\x01");
+    for message in request.messages.iter().filter(|message| message.role != "system") {
+        hasher.update(message.role.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(message.content.as_bytes());
+        hasher.update(b"\0");
+        for image in message.images.iter().flatten() {
+            hasher.update(image.as_bytes());
+            hasher.update(b"\0");
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// The key `request`'s answer is cached under, or `None` while the cache is off
+pub(super) fn key_for(request: &ChatRequest) -> Option<String> {
+    (read_settings(&SETTINGS).enabled && DB_PATH.get().is_some()).then(|| cache_key(request))
+}
+
+/// A cached answer younger than the TTL
+pub(super) fn lookup(key: &str) -> Option<String> {
+    let conn = open()?;
+    let oldest = chrono::Utc::now().timestamp() - read_settings(&SETTINGS).ttl_secs as i64;
+    let response: Option<String> = conn
+        .query_row(
+            "SELECT response FROM response_cache WHERE key = ?1 AND created_at >= ?2",
+            params![key, oldest],
+            |row| row.get(0),
+        )
+        .optional()
+        .ok()
+        .flatten();
+    if response.is_some() {
+        let _ = conn.execute("UPDATE response_cache SET hits = hits + 1 WHERE key = ?1", params![key]);
+    }
+    response
+}
+
+pub(super) fn store(key: &str, model: &str, response: &str) {
+    if response.trim().is_empty() {
+        return;
+    }
+    let Some(conn) = open() else { return };
+    if let Err(e) = conn.execute(
+        "INSERT OR REPLACE INTO response_cache (key, model, response, created_at, hits) VALUES (?1, ?2, ?3, ?4, 0)",
+        params![key, model, response, chrono::Utc::now().timestamp()],
+    ) {
+        eprintln!("Failed to cache response: {}", e);
+    }
+}
+
+fn purge_expired() {
+    let Some(conn) = open() else { return };
+    let oldest = chrono::Utc::now().timestamp() - read_settings(&SETTINGS).ttl_secs as i64;
+    match conn.execute("DELETE FROM response_cache WHERE created_at < ?1", params![oldest]) {
+        Ok(removed) if removed > 0 => println!("🗄️ Removed {} expired cached responses", removed),
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to purge response cache: {}", e),
+    }
+}
+
+pub fn init(app_handle: &AppHandle) {
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = DB_PATH.set(dir.join("response_cache.db"));
+        }
+        Err(e) => eprintln!("⚠️ Response cache unavailable: {}", e),
+    }
+    if read_settings(&SETTINGS).enabled {
+        std::thread::spawn(purge_expired);
+    }
+}
+
+#[crate::command]
+pub fn get_response_cache_settings() -> Result<ResponseCacheSettings, String> {
+    Ok(read_settings(&SETTINGS))
+}
+
+#[crate::command]
+pub fn save_response_cache_settings(settings: ResponseCacheSettings) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    settings.validate()?;
+    save_json_settings(SETTINGS_FILE, "response cache settings", &settings)?;
+
+    *SETTINGS
+        .write()
+        .map_err(|_| "Failed to update response cache settings".to_string())? = settings;
+    Ok(())
+}
+
+/// Forget every cached answer
+#[crate::command]
+pub fn clear_response_cache() -> Result<ClearedResponseCache, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::DataDeletion)?;
+    let Some(conn) = open() else {
+        return Ok(ClearedResponseCache { entries_removed: 0 });
+    };
+    let entries_removed = conn
+        .execute("DELETE FROM response_cache", [])
+        .map_err(|e| format!("Failed to clear response cache: {}", e))?;
+    println!("🗄️ Cleared {} cached responses", entries_removed);
+    Ok(ClearedResponseCache { entries_removed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ollama::ChatMessage;
+
+    fn request(model: &str, system: &str, prompt: &str, image: Option<&str>) -> ChatRequest {
+        ChatRequest {
+            model: model.to_string(),
+            messages: vec![
+                ChatMessage::new("system", system),
+                ChatMessage { images: image.map(|image| vec![image.to_string()]), ..ChatMessage::new("user", prompt) },
+            ],
+            stream: Some(true),
+            tools: None,
+            format: None,
+            options: Some(serde_json::json!({ "num_gpu": 20 })),
+        }
+    }
+
+    #[test]
+    fn test_key_covers_model_system_prompt_and_images() {
+        let base = cache_key(&request("qwen2.5vl:3b", "Describe", "What is this?", Some("aGVsbG8=")));
+        assert_eq!(base.len(), 64);
+        // Options such as the GPU split don't change the answer's key
+        let mut moved = request("qwen2.5vl:3b", "Describe", "What is this?", Some("aGVsbG8="));
+        moved.options = None;
+        assert_eq!(cache_key(&moved), base);
+
+        assert_ne!(cache_key(&request("llava", "Describe", "What is this?", Some("aGVsbG8="))), base);
+        assert_ne!(cache_key(&request("qwen2.5vl:3b", "Summarize", "What is this?", Some("aGVsbG8="))), base);
+        assert_ne!(cache_key(&request("qwen2.5vl:3b", "Describe", "What is that?", Some("aGVsbG8="))), base);
+        assert_ne!(cache_key(&request("qwen2.5vl:3b", "Describe", "What is this?", Some("d29ybGQ="))), base);
+        assert_ne!(cache_key(&request("qwen2.5vl:3b", "Describe", "What is this?", None)), base);
+
+        assert!(ResponseCacheSettings::default().validate().is_ok());
+        assert!(ResponseCacheSettings { ttl_secs: 0, ..Default::default() }.validate().is_err());
+    }
+}
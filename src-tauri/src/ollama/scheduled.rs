@@ -0,0 +1,313 @@
+// src-tauri/src/ollama/scheduled.rs
+// Background runner for scheduled agent tasks - looks for due tasks every half minute, runs them
+// one at a time and saves each result as a chat session, announced with `scheduled-task-completed`.
+//
+// A task that came due while the app was closed runs once on the first check after startup.
+use chrono::{Local, Utc};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::time::timeout;
+
+use super::{agents, ChatMessage, ChatRequest, HTTP_CLIENT, REQUEST_SEMAPHORE};
+use crate::data::chat::{next_run_at, ScheduledTask, ScheduledTaskKind};
+use crate::data::types::{ChatMessage as StoredMessage, ChatSession, MessageMetadata};
+use crate::data::worker;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const RUN_TIMEOUT: Duration = Duration::from_secs(300);
+// Keeps a digest prompt inside small local models' context windows
+const MAX_DIGEST_DOCUMENTS: usize = 8;
+const MAX_DOCUMENT_CHARS: usize = 3000;
+
+lazy_static::lazy_static! {
+    // One task at a time, so a long research run can't pile up behind itself
+    static ref RUN_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// A document a digest covers
+struct DigestDocument {
+    name: String,
+    content: String,
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// Whether a document carries `tag`, either in its metadata's `tags` list or as `#tag` in its name
+fn has_tag(file_name: &str, metadata: Option<&str>, tag: &str) -> bool {
+    let tag = normalize_tag(tag);
+    let in_metadata = metadata
+        .and_then(|metadata| serde_json::from_str::<serde_json::Value>(metadata).ok())
+        .and_then(|metadata| metadata.get("tags").and_then(|tags| tags.as_array()).cloned())
+        .map(|tags| tags.iter().filter_map(|t| t.as_str()).any(|t| normalize_tag(t) == tag))
+        .unwrap_or(false);
+    in_metadata || file_name.to_lowercase().contains(&format!("#{}", tag))
+}
+
+fn excerpt(content: &str) -> String {
+    let content = content.trim();
+    if content.chars().count() > MAX_DOCUMENT_CHARS {
+        let cut: String = content.chars().take(MAX_DOCUMENT_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        content.to_string()
+    }
+}
+
+fn digest_prompt(tag: &str, documents: &[DigestDocument]) -> String {
+    let mut prompt = format!("New documents tagged #{}:\n\n", normalize_tag(tag));
+    for document in documents.iter().take(MAX_DIGEST_DOCUMENTS) {
+        prompt.push_str(&format!("## {}\n{}\n\n", document.name, excerpt(&document.content)));
+    }
+    if documents.len() > MAX_DIGEST_DOCUMENTS {
+        prompt.push_str(&format!("({} more documents were left out)\n\n", documents.len() - MAX_DIGEST_DOCUMENTS));
+    }
+    prompt.push_str("Summarize each document in a few bullet points, then list anything that needs a decision or follow-up.");
+    prompt
+}
+
+// Enhanced RAG documents tagged `tag` added after `since` (milliseconds), oldest first
+#[cfg(feature = "enhanced-rag")]
+fn tagged_documents(app_handle: &AppHandle, tag: &str, since: Option<i64>) -> Result<Vec<DigestDocument>, String> {
+    use tauri::Manager;
+    let state = app_handle
+        .try_state::<crate::enhanced_rag_commands::EnhancedRagSystemState>()
+        .ok_or("Enhanced RAG is unavailable")?;
+    let system = state
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("Enhanced RAG system not initialized")?;
+    let mut documents: Vec<_> = system
+        .get_all_documents()
+        .map_err(|e| format!("Failed to list documents: {}", e))?
+        .into_iter()
+        .filter(|document| has_tag(&document.file_name, document.metadata.as_deref(), tag))
+        .filter_map(|document| {
+            let created = chrono::DateTime::parse_from_rfc3339(&document.created_at).ok()?.timestamp_millis();
+            (!matches!(since, Some(since) if created <= since)).then_some((created, document))
+        })
+        .collect();
+    documents.sort_by_key(|(created, _)| *created);
+    Ok(documents
+        .into_iter()
+        .map(|(_, document)| DigestDocument { name: document.file_name, content: document.content })
+        .collect())
+}
+
+#[cfg(not(feature = "enhanced-rag"))]
+fn tagged_documents(_app_handle: &AppHandle, _tag: &str, _since: Option<i64>) -> Result<Vec<DigestDocument>, String> {
+    Err("Document digests need the enhanced RAG feature".to_string())
+}
+
+// One non-streamed generation with the agent's profile and backend
+async fn generate(app_handle: &AppHandle, session_id: &str, agent: &str, prompt: String) -> Result<(String, String), String> {
+    let _permit = REQUEST_SEMAPHORE.acquire().await.map_err(|e| format!("Failed to acquire semaphore: {}", e))?;
+    let profile = agents::profile(agent);
+    let (backend, model) = super::fallback::resolve(app_handle, agent, profile.model).await?;
+
+    let mut options = serde_json::json!({ "temperature": profile.temperature });
+    if let Some(num_predict) = profile.num_predict {
+        options["num_predict"] = serde_json::json!(num_predict);
+    }
+    let request = ChatRequest {
+        model,
+        messages: vec![
+            ChatMessage::new("system", profile.system_prompt),
+            ChatMessage::new("user", prompt),
+        ],
+        stream: Some(false),
+        tools: None,
+        format: None,
+        options: Some(options),
+    };
+
+    let response = backend.send(&HTTP_CLIENT, &request).await?;
+    let generated = backend.read_response(response).await?;
+    super::record_usage(app_handle, session_id, agent, &request.model, &generated);
+    let text = generated.text.trim().to_string();
+    if text.is_empty() {
+        return Err(format!("The {} agent returned nothing", agent));
+    }
+    Ok((text, request.model))
+}
+
+fn stored_message(sender: &str, text: String, metadata: Option<MessageMetadata>) -> StoredMessage {
+    StoredMessage {
+        id: 0,
+        text,
+        sender: sender.to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        is_interim: None,
+        confidence: None,
+        source: Some("scheduled_task".to_string()),
+        attachments: None,
+        thinking: None,
+        message_type: None,
+        metadata,
+    }
+}
+
+// The run's chat session, or `Ok(None)` when there was nothing to do
+async fn execute(app_handle: &AppHandle, task: &ScheduledTask) -> Result<Option<ChatSession>, String> {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let (agent, request, prompt) = match &task.kind {
+        ScheduledTaskKind::DocumentDigest { tag } => {
+            let documents = tagged_documents(app_handle, tag, task.last_run_at)?;
+            if documents.is_empty() {
+                return Ok(None);
+            }
+            let request = format!("Digest of {} new document(s) tagged #{}", documents.len(), normalize_tag(tag));
+            ("enteract", request, digest_prompt(tag, &documents))
+        }
+        ScheduledTaskKind::Research { topic } => {
+            ("research", topic.clone(), format!("Deep Research Query:\n\n{}", topic))
+        }
+    };
+
+    let started = std::time::Instant::now();
+    let (answer, model) = generate(app_handle, &session_id, agent, prompt).await?;
+    let now = Utc::now().to_rfc3339();
+    let metadata = MessageMetadata {
+        agent_type: Some(agent.to_string()),
+        model: Some(model.clone()),
+        tokens: None,
+        processing_time: Some(started.elapsed().as_secs_f64()),
+        analysis_type: None,
+        search_queries: None,
+        sources: None,
+        seed: None,
+    };
+    Ok(Some(ChatSession {
+        id: session_id,
+        title: format!("{} · {}", task.name, Local::now().format("%b %-d, %H:%M")),
+        history: vec![
+            stored_message("user", request, None),
+            stored_message("assistant", answer, Some(metadata)),
+        ],
+        created_at: now.clone(),
+        updated_at: now,
+        model_id: Some(model),
+    }))
+}
+
+// Run one task, save its session and schedule the next run
+async fn run_task(app_handle: &AppHandle, task: ScheduledTask) -> Result<ScheduledTask, String> {
+    println!("⏰ Running scheduled task '{}' ({})", task.name, task.id);
+    let outcome = timeout(RUN_TIMEOUT, execute(app_handle, &task))
+        .await
+        .unwrap_or_else(|_| Err("Timed out".to_string()));
+
+    let ran_at = Utc::now().timestamp_millis();
+    let (status, session) = match outcome {
+        Ok(Some(session)) => {
+            let saved = session.clone();
+            match worker::write(app_handle, move |db| {
+                db.chats()?.insert_chat_session(saved).map_err(|e| e.to_string())
+            }).await {
+                Ok(()) => ("completed".to_string(), Some(session)),
+                Err(e) => (format!("failed: couldn't save the result: {}", e), None),
+            }
+        }
+        Ok(None) => ("skipped: nothing new".to_string(), None),
+        Err(e) => (format!("failed: {}", e), None),
+    };
+    if let Some(error) = status.strip_prefix("failed: ") {
+        eprintln!("❌ Scheduled task '{}' failed: {}", task.name, error);
+    }
+
+    // A failed run leaves `last_run_at` alone, so a digest still covers what it missed
+    let last_run_at = if status.starts_with("failed") { task.last_run_at } else { Some(ran_at) };
+    let next = if task.enabled { next_run_at(&task.schedule, ran_at)? } else { task.next_run_at };
+    let session_id = session.as_ref().map(|session| session.id.clone());
+    {
+        let (id, status, session_id) = (task.id.clone(), status.clone(), session_id.clone());
+        worker::write(app_handle, move |db| {
+            db.chats()?
+                .record_scheduled_run(&id, last_run_at, next, &status, session_id.as_deref())
+                .map_err(|e| format!("Failed to record scheduled run: {}", e))
+        }).await?;
+    }
+
+    let task = ScheduledTask {
+        last_run_at,
+        next_run_at: next,
+        last_status: Some(status),
+        last_session_id: session_id.or(task.last_session_id),
+        ..task
+    };
+    if let Err(e) = app_handle.emit("scheduled-task-completed", serde_json::json!({
+        "task": task,
+        "session": session,
+    })) {
+        eprintln!("Failed to emit scheduled task event: {}", e);
+    }
+    Ok(task)
+}
+
+async fn run_due_tasks(app_handle: &AppHandle) {
+    // A manual run in progress; the next check picks up anything still due
+    let Ok(_guard) = RUN_LOCK.try_lock() else { return };
+    let now = Utc::now().timestamp_millis();
+    let due = match worker::read(app_handle, move |db| {
+        db.chats()?.due_scheduled_tasks(now).map_err(|e| e.to_string())
+    }).await {
+        Ok(due) => due,
+        Err(e) => {
+            eprintln!("❌ Failed to check scheduled tasks: {}", e);
+            return;
+        }
+    };
+    for task in due {
+        if let Err(e) = run_task(app_handle, task).await {
+            eprintln!("❌ Scheduled task bookkeeping failed: {}", e);
+        }
+    }
+}
+
+pub fn init(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            run_due_tasks(&app_handle).await;
+        }
+    });
+}
+
+/// Run a task right away, whatever its schedule; waits for a run already in progress
+#[crate::command]
+pub async fn run_scheduled_task_now(app_handle: AppHandle, id: String) -> Result<ScheduledTask, String> {
+    let _guard = RUN_LOCK.lock().await;
+    let task = worker::read(&app_handle, move |db| {
+        db.chats()?
+            .load_scheduled_task(&id)
+            .map_err(|e| format!("Failed to load scheduled task: {}", e))?
+            .ok_or_else(|| format!("Scheduled task {} not found", id))
+    }).await?;
+    run_task(&app_handle, task).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_tags_and_prompt() {
+        assert!(has_tag("notes.md", Some(r##"{"tags": ["#Inbox", "work"]}"##), "inbox"));
+        assert!(has_tag("Q3 plan #inbox.pdf", None, "#inbox"));
+        assert!(!has_tag("inbox.pdf", Some(r#"{"tags": ["archive"]}"#), "inbox"));
+        assert!(!has_tag("notes.md", Some("not json"), "inbox"));
+
+        let documents: Vec<DigestDocument> = (0..10)
+            .map(|i| DigestDocument { name: format!("doc-{}", i), content: "x".repeat(if i == 0 { 5000 } else { 10 }) })
+            .collect();
+        let prompt = digest_prompt("#Inbox", &documents);
+        assert!(prompt.starts_with("New documents tagged #inbox:\n\n## doc-0\n"));
+        assert!(prompt.contains(&format!("{}…\n\n## doc-1", "x".repeat(MAX_DOCUMENT_CHARS))));
+        assert!(!prompt.contains("## doc-8"));
+        assert!(prompt.contains("(2 more documents were left out)"));
+    }
+}
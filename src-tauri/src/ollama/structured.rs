@@ -0,0 +1,208 @@
+// src-tauri/src/ollama/structured.rs
+// Structured output - the model is asked for JSON (held to the caller's schema through Ollama's
+// `format` when there is one), the reply is validated against that schema, and a failing reply
+// is sent back with a repair prompt listing what was wrong.
+use serde::Serialize;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::time::timeout;
+
+use super::backend::AGENT_TYPES;
+use super::{agents, ChatMessage, ChatRequest, HTTP_CLIENT, REQUEST_SEMAPHORE};
+
+const DEFAULT_RETRIES: u32 = 2;
+const MAX_RETRIES: u32 = 5;
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(90);
+// The repair prompt lists at most this many problems; the rest usually follow from them
+const MAX_REPORTED_ERRORS: usize = 8;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredResponse {
+    /// The parsed reply, valid against the schema
+    pub value: Value,
+    /// The model's text as it came back
+    pub raw: String,
+    pub model: String,
+    /// 1 when the first reply was valid
+    pub attempts: u32,
+}
+
+/// The JSON in a reply, tolerating code fences and text around the object
+fn extract_json(text: &str) -> Result<Value, String> {
+    let trimmed = text.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.trim_end().strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+    let parse_error = match serde_json::from_str(unfenced) {
+        Ok(value) => return Ok(value),
+        Err(e) => e.to_string(),
+    };
+    // The outermost object or array, if the model wrapped it in prose
+    for (open, close) in [('{', '}'), ('[', ']')] {
+        if let (Some(start), Some(end)) = (unfenced.find(open), unfenced.rfind(close)) {
+            if start < end {
+                if let Ok(value) = serde_json::from_str(&unfenced[start..=end]) {
+                    return Ok(value);
+                }
+            }
+        }
+    }
+    Err(format!("The reply isn't valid JSON: {}", parse_error))
+}
+
+/// What's wrong with `value`, one line per problem; empty when it's valid
+fn validation_errors(validator: Option<&jsonschema::Validator>, value: &Value) -> Vec<String> {
+    let Some(validator) = validator else {
+        return Vec::new();
+    };
+    validator
+        .iter_errors(value)
+        .map(|error| {
+            let path = error.instance_path.to_string();
+            if path.is_empty() {
+                error.to_string()
+            } else {
+                format!("{}: {}", path, error)
+            }
+        })
+        .collect()
+}
+
+fn repair_prompt(errors: &[String]) -> String {
+    let mut prompt = String::from("Your reply doesn't match the required JSON schema:\n");
+    for error in errors.iter().take(MAX_REPORTED_ERRORS) {
+        prompt.push_str(&format!("- {}\n", error));
+    }
+    if errors.len() > MAX_REPORTED_ERRORS {
+        prompt.push_str(&format!("- and {} more\n", errors.len() - MAX_REPORTED_ERRORS));
+    }
+    prompt.push_str("Reply again with only the corrected JSON, no other text.");
+    prompt
+}
+
+fn with_json_instructions(base: String, schema: Option<&Value>) -> String {
+    match schema {
+        Some(schema) => format!(
+            "{}\n\nReply with a single JSON value matching this JSON schema, and nothing else:\n{}",
+            base, schema
+        ),
+        None => format!("{}\n\nReply with a single JSON value and nothing else.", base),
+    }
+}
+
+/// Generate a reply the frontend can parse: JSON, valid against `schema` when one is given.
+/// Invalid replies are retried up to `max_retries` times with the validation errors attached.
+/// `session_id` attributes usage and makes the request cancellable through `cancel_operation`.
+#[crate::command]
+pub async fn generate_structured_response(
+    app_handle: AppHandle,
+    prompt: String,
+    schema: Option<Value>,
+    agent: Option<String>,
+    system_prompt: Option<String>,
+    max_retries: Option<u32>,
+    session_id: Option<String>,
+) -> Result<StructuredResponse, String> {
+    let agent = agent.unwrap_or_else(|| "enteract".to_string());
+    if !AGENT_TYPES.contains(&agent.as_str()) {
+        return Err(format!("Unknown agent: {}", agent));
+    }
+    let validator = match &schema {
+        Some(schema) => Some(jsonschema::validator_for(schema).map_err(|e| format!("Invalid JSON schema: {}", e))?),
+        None => None,
+    };
+    let session_id = session_id.unwrap_or_else(|| format!("structured:{}", uuid::Uuid::new_v4()));
+    let operation = crate::cancellation::start(&session_id, crate::cancellation::OperationKind::AiResponse, agent.as_str());
+
+    let profile = agents::profile(&agent);
+    let (backend, model) = super::fallback::resolve(&app_handle, &agent, profile.model).await?;
+    let mut options = serde_json::json!({ "temperature": profile.temperature });
+    if let Some(num_predict) = profile.num_predict {
+        options["num_predict"] = serde_json::json!(num_predict);
+    }
+    let base_prompt = system_prompt.unwrap_or(profile.system_prompt);
+    let mut request = ChatRequest {
+        model,
+        messages: vec![
+            ChatMessage::new("system", with_json_instructions(base_prompt, schema.as_ref())),
+            ChatMessage::new("user", prompt),
+        ],
+        stream: Some(false),
+        tools: None,
+        format: Some(schema.clone().unwrap_or_else(|| Value::String("json".to_string()))),
+        options: Some(options),
+    };
+
+    let attempts = max_retries.unwrap_or(DEFAULT_RETRIES).min(MAX_RETRIES) + 1;
+    let mut errors = Vec::new();
+    for attempt in 1..=attempts {
+        let generated = operation.run(async {
+            let _permit = REQUEST_SEMAPHORE.acquire().await.map_err(|e| format!("Failed to acquire semaphore: {}", e))?;
+            timeout(ATTEMPT_TIMEOUT, async {
+                let response = backend.send(&HTTP_CLIENT, &request).await?;
+                backend.read_response(response).await
+            })
+            .await
+            .map_err(|_| "Structured generation timed out".to_string())?
+        }).await?;
+        super::record_usage(&app_handle, &session_id, &agent, &request.model, &generated);
+
+        let raw = generated.text;
+        errors = match extract_json(&raw) {
+            Ok(value) => {
+                let problems = validation_errors(validator.as_ref(), &value);
+                if problems.is_empty() {
+                    return Ok(StructuredResponse { value, raw, model: request.model, attempts: attempt });
+                }
+                problems
+            }
+            Err(e) => vec![e],
+        };
+        println!("🔁 Structured reply attempt {}/{} was invalid: {}", attempt, attempts, errors.join("; "));
+        request.messages.push(ChatMessage::new("assistant", raw));
+        request.messages.push(ChatMessage::new("user", repair_prompt(&errors)));
+    }
+    Err(format!(
+        "The model's reply didn't match the schema after {} attempts: {}",
+        attempts,
+        errors.into_iter().take(MAX_REPORTED_ERRORS).collect::<Vec<_>>().join("; ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_and_validates_replies() {
+        let expected = serde_json::json!({ "title": "Plan", "steps": 3 });
+        assert_eq!(extract_json(r#"{"title": "Plan", "steps": 3}"#).unwrap(), expected);
+        assert_eq!(extract_json("```json\n{\"title\": \"Plan\", \"steps\": 3}\n```").unwrap(), expected);
+        assert_eq!(extract_json("Sure! Here it is: {\"title\": \"Plan\", \"steps\": 3} Hope that helps.").unwrap(), expected);
+        assert!(extract_json("no json here").is_err());
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string" },
+                "steps": { "type": "integer", "minimum": 1 }
+            },
+            "required": ["title", "steps"]
+        });
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        assert!(validation_errors(Some(&validator), &expected).is_empty());
+
+        let errors = validation_errors(Some(&validator), &serde_json::json!({ "steps": 0 }));
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|error| error.starts_with("/steps:")));
+        assert!(repair_prompt(&errors).contains("- /steps:"));
+
+        // Without a schema any JSON goes
+        assert!(validation_errors(None, &serde_json::json!([1, 2])).is_empty());
+    }
+}
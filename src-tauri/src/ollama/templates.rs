@@ -0,0 +1,283 @@
+// src-tauri/src/ollama/templates.rs
+// Prompt templates - an agent's system prompt can come from `prompt_templates/<agent>.md` in app
+// data, with `{{variables}}` filled in every time the agent runs.
+//
+// Files are re-read whenever their modification time changes, so edits made in the settings
+// screen or in any text editor apply to the next request without a restart. Built-in and
+// settings prompts are rendered the same way, so they can use variables too.
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+
+use super::agents;
+use super::backend::AGENT_TYPES;
+
+// Large clipboard contents would crowd out the conversation
+const MAX_CLIPBOARD_CHARS: usize = 2000;
+const MAX_TEMPLATE_BYTES: usize = 64 * 1024;
+
+/// Variables every template can use; others can be set with `set_prompt_variable`
+const BUILTIN_VARIABLES: &[&str] = &["user_name", "active_document", "clipboard", "date", "time", "agent"];
+
+lazy_static::lazy_static! {
+    static ref TEMPLATE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+    // Last read of each template file, keyed by agent
+    static ref TEMPLATE_CACHE: Mutex<HashMap<String, (SystemTime, String)>> = Mutex::new(HashMap::new());
+    // Values the frontend set, e.g. `active_document`; these win over the built-in ones
+    static ref VARIABLES: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    pub agent: String,
+    /// The template file's text, or the agent's current prompt when there is no file
+    pub content: String,
+    pub customized: bool,
+    /// What the agent uses without a template file
+    pub default_content: String,
+    /// Variables the content refers to
+    pub variables: Vec<String>,
+    /// Built-in variables plus any the frontend set
+    pub available_variables: Vec<String>,
+    pub path: Option<String>,
+}
+
+/// Replace each `{{ name }}` with `lookup(name)`; unknown names are left in place so they show up
+pub fn render(template: &str, mut lookup: impl FnMut(&str) -> Option<String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            return rendered;
+        };
+        let name = after[..end].trim();
+        match lookup(name) {
+            Some(value) if is_variable_name(name) => rendered.push_str(&value),
+            _ => rendered.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Distinct variable names a template refers to, in order of first use
+pub fn referenced_variables(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    render(template, |name| {
+        if is_variable_name(name) && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        None
+    });
+    names
+}
+
+fn clipboard_text() -> Option<String> {
+    let text = arboard::Clipboard::new().ok()?.get_text().ok()?;
+    if text.chars().count() > MAX_CLIPBOARD_CHARS {
+        let cut: String = text.chars().take(MAX_CLIPBOARD_CHARS).collect();
+        Some(format!("{}…", cut))
+    } else {
+        Some(text)
+    }
+}
+
+fn variable(agent: &str, name: &str) -> Option<String> {
+    if let Some(value) = VARIABLES.read().ok().and_then(|variables| variables.get(name).cloned()) {
+        return Some(value);
+    }
+    let now = chrono::Local::now();
+    match name {
+        "user_name" => std::env::var("USERNAME").or_else(|_| std::env::var("USER")).ok(),
+        // Nothing open reads better than a leftover placeholder
+        "active_document" => Some("none".to_string()),
+        "clipboard" => Some(clipboard_text().unwrap_or_default()),
+        "date" => Some(now.format("%A, %B %-d, %Y").to_string()),
+        "time" => Some(now.format("%H:%M").to_string()),
+        "agent" => Some(agent.to_string()),
+        _ => None,
+    }
+}
+
+fn available_variables() -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN_VARIABLES.iter().map(|name| name.to_string()).collect();
+    if let Ok(variables) = VARIABLES.read() {
+        let mut custom: Vec<String> = variables.keys().filter(|name| !names.contains(name)).cloned().collect();
+        custom.sort();
+        names.extend(custom);
+    }
+    names
+}
+
+fn template_path(agent: &str) -> Option<PathBuf> {
+    TEMPLATE_DIR.read().ok()?.as_ref().map(|dir| dir.join(format!("{}.md", agent)))
+}
+
+// The template file's text, re-read only when the file changed
+fn template_file(agent: &str) -> Option<String> {
+    let path = template_path(agent)?;
+    let modified = std::fs::metadata(&path).and_then(|meta| meta.modified());
+    let mut cache = TEMPLATE_CACHE.lock().ok()?;
+    let Ok(modified) = modified else {
+        cache.remove(agent);
+        return None;
+    };
+    if let Some((cached_at, text)) = cache.get(agent) {
+        if *cached_at == modified {
+            return Some(text.clone());
+        }
+    }
+    let text = std::fs::read_to_string(&path).ok()?;
+    println!("📝 Loaded prompt template for {} from {}", agent, path.display());
+    cache.insert(agent.to_string(), (modified, text.clone()));
+    Some(text)
+}
+
+/// The system prompt an agent runs with: its template file if there is one, else `configured`,
+/// with variables filled in
+pub fn resolve(agent: &str, configured: String) -> String {
+    let template = template_file(agent).filter(|text| !text.trim().is_empty()).unwrap_or(configured);
+    if !template.contains("{{") {
+        return template;
+    }
+    render(&template, |name| variable(agent, name))
+}
+
+pub fn init(app_handle: &AppHandle) {
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => {
+            if let Ok(mut template_dir) = TEMPLATE_DIR.write() {
+                *template_dir = Some(dir.join("prompt_templates"));
+            }
+        }
+        Err(e) => eprintln!("⚠️ Prompt templates unavailable: {}", e),
+    }
+}
+
+fn known_agent(agent: &str) -> Result<(), String> {
+    if AGENT_TYPES.contains(&agent) {
+        Ok(())
+    } else {
+        Err(format!("Unknown agent: {}", agent))
+    }
+}
+
+fn prompt_template(agent: &str) -> Result<PromptTemplate, String> {
+    known_agent(agent)?;
+    let default_content = agents::configured_profile(agent).system_prompt;
+    let file = template_file(agent);
+    let content = file.clone().unwrap_or_else(|| default_content.clone());
+    Ok(PromptTemplate {
+        agent: agent.to_string(),
+        variables: referenced_variables(&content),
+        available_variables: available_variables(),
+        customized: file.is_some(),
+        content,
+        default_content,
+        path: template_path(agent).map(|path| path.to_string_lossy().to_string()),
+    })
+}
+
+#[crate::command]
+pub fn list_prompt_templates() -> Result<Vec<PromptTemplate>, String> {
+    AGENT_TYPES.iter().map(|agent| prompt_template(agent)).collect()
+}
+
+#[crate::command]
+pub fn get_prompt_template(agent: String) -> Result<PromptTemplate, String> {
+    prompt_template(&agent)
+}
+
+/// Write an agent's template file; it takes effect on the agent's next request
+#[crate::command]
+pub fn save_prompt_template(agent: String, content: String) -> Result<PromptTemplate, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    known_agent(&agent)?;
+    if content.trim().is_empty() {
+        return Err("A prompt template can't be empty; delete it to use the default".to_string());
+    }
+    if content.len() > MAX_TEMPLATE_BYTES {
+        return Err(format!("Prompt templates are limited to {} KB", MAX_TEMPLATE_BYTES / 1024));
+    }
+    let path = template_path(&agent).ok_or("Prompt templates are unavailable")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create template directory: {}", e))?;
+    }
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write prompt template: {}", e))?;
+    prompt_template(&agent)
+}
+
+/// Remove an agent's template file, going back to its configured prompt
+#[crate::command]
+pub fn delete_prompt_template(agent: String) -> Result<PromptTemplate, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    known_agent(&agent)?;
+    if let Some(path) = template_path(&agent).filter(|path| path.exists()) {
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete prompt template: {}", e))?;
+    }
+    if let Ok(mut cache) = TEMPLATE_CACHE.lock() {
+        cache.remove(&agent);
+    }
+    prompt_template(&agent)
+}
+
+/// `content` (or the agent's current template) as the agent would see it right now
+#[crate::command]
+pub fn preview_prompt_template(agent: String, content: Option<String>) -> Result<String, String> {
+    known_agent(&agent)?;
+    let content = content.unwrap_or_else(|| prompt_template(&agent).map(|t| t.content).unwrap_or_default());
+    Ok(render(&content, |name| variable(&agent, name)))
+}
+
+/// Set a template variable such as `active_document`; `None` goes back to the built-in value
+#[crate::command]
+pub fn set_prompt_variable(name: String, value: Option<String>) -> Result<(), String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    let name = name.trim().to_string();
+    if !is_variable_name(&name) {
+        return Err(format!("'{}' isn't a valid variable name; use letters, digits and _", name));
+    }
+    let mut variables = VARIABLES.write().map_err(|_| "Failed to update prompt variables".to_string())?;
+    match value {
+        Some(value) => variables.insert(name, value),
+        None => variables.remove(&name),
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_fills_known_variables_only() {
+        let values = HashMap::from([("user_name", "Sam"), ("active_document", "plan.md")]);
+        let lookup = |name: &str| values.get(name).map(|v| v.to_string());
+
+        assert_eq!(
+            render("Hi {{user_name}}, you're reading {{ active_document }}.", lookup),
+            "Hi Sam, you're reading plan.md."
+        );
+        // Unknown names, odd spacing inside names and unclosed braces stay as written
+        assert_eq!(render("{{missing}} and {{user name}}", lookup), "{{missing}} and {{user name}}");
+        assert_eq!(render("Tail {{user_name", lookup), "Tail {{user_name");
+        assert_eq!(render("No variables", lookup), "No variables");
+
+        assert_eq!(
+            referenced_variables("{{clipboard}} {{ date }} {{clipboard}} {{not valid}}"),
+            vec!["clipboard".to_string(), "date".to_string()]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::simple_embedding_service::{normalize_embedding, EmbeddingConfig, EmbeddingProvider};
+
+/// Used when the config still names the built-in model
+pub const DEFAULT_OLLAMA_EMBEDDING_MODEL: &str = "nomic-embed-text";
+const BUILTIN_MODEL_NAME: &str = "simple-text-embedding";
+// Past this many cached vectors the cache is cleared rather than tracked per entry
+const MAX_CACHED: usize = 1024;
+
+/// Embeddings from the local Ollama server's `/api/embeddings`, e.g. nomic-embed-text (768
+/// dimensions) or mxbai-embed-large (1024). The model has to be pulled first; the dimension is
+/// whatever it returns on first use.
+#[derive(Clone)]
+pub struct OllamaEmbeddingProvider {
+    model: String,
+    normalize: bool,
+    dimension: Arc<Mutex<Option<usize>>>,
+    cache: Arc<Mutex<HashMap<String, Vec<f32>>>>,
+    initialized: Arc<Mutex<bool>>,
+}
+
+/// The vector in an `/api/embeddings` response
+fn parse_embedding(body: &serde_json::Value, model: &str, normalize: bool) -> Result<Vec<f32>> {
+    let Some(values) = body.get("embedding").and_then(|embedding| embedding.as_array()) else {
+        return match body.get("error").and_then(|error| error.as_str()) {
+            Some(error) => Err(anyhow!("Ollama couldn't embed with {}: {}", model, error)),
+            None => Err(anyhow!("Ollama returned no embedding for {}", model)),
+        };
+    };
+    let mut embedding = values
+        .iter()
+        .map(|value| value.as_f64().map(|value| value as f32))
+        .collect::<Option<Vec<f32>>>()
+        .ok_or_else(|| anyhow!("Ollama returned a malformed embedding for {}", model))?;
+    if embedding.is_empty() {
+        return Err(anyhow!("{} returned an empty embedding; is it an embedding model?", model));
+    }
+    if normalize {
+        normalize_embedding(&mut embedding);
+    }
+    Ok(embedding)
+}
+
+impl OllamaEmbeddingProvider {
+    pub fn new(config: &EmbeddingConfig) -> Self {
+        let model = match config.model_name.trim() {
+            "" | BUILTIN_MODEL_NAME => DEFAULT_OLLAMA_EMBEDDING_MODEL.to_string(),
+            name => name.to_string(),
+        };
+        Self {
+            model,
+            normalize: config.normalize_embeddings,
+            dimension: Arc::new(Mutex::new(None)),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            initialized: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        if let Some(cached) = self.cache.lock().ok().and_then(|cache| cache.get(text).cloned()) {
+            return Ok(cached);
+        }
+
+        let response = crate::ollama::ollama_request(reqwest::Method::POST, "/api/embeddings")
+            .json(&serde_json::json!({ "model": self.model, "prompt": text }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach Ollama for embeddings: {}", e))?;
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to read Ollama embedding response ({}): {}", status, e))?;
+        let embedding = parse_embedding(&body, &self.model, self.normalize)?;
+
+        // Vectors of different sizes can't be compared, so a model swapped underneath is an error
+        let mut dimension = self.dimension.lock().map_err(|e| anyhow!("Mutex lock failed: {}", e))?;
+        match *dimension {
+            Some(expected) if expected != embedding.len() => {
+                return Err(anyhow!(
+                    "{} returned {} dimensions, expected {}; re-embed documents after changing models",
+                    self.model,
+                    embedding.len(),
+                    expected
+                ));
+            }
+            Some(_) => {}
+            None => *dimension = Some(embedding.len()),
+        }
+        drop(dimension);
+
+        if let Ok(mut cache) = self.cache.lock() {
+            if cache.len() >= MAX_CACHED {
+                cache.clear();
+            }
+            cache.insert(text.to_string(), embedding.clone());
+        }
+        Ok(embedding)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    /// Probe the model once. The provider counts as initialized even if the probe fails, so
+    /// embedding jobs report Ollama's error instead of waiting forever.
+    async fn initialize(&self) -> Result<()> {
+        let probe = self.embed("embedding probe").await;
+        if let Ok(mut initialized) = self.initialized.lock() {
+            *initialized = true;
+        }
+        let embedding = probe?;
+        println!("Ollama embedding provider initialized ({}, dimension: {})", self.model, embedding.len());
+        Ok(())
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.initialized.lock().map(|initialized| *initialized).unwrap_or(false)
+    }
+
+    async fn embed_documents(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            embeddings.push(self.embed(&text).await?);
+        }
+        Ok(embeddings)
+    }
+
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        self.embed(query).await
+    }
+
+    fn model_id(&self) -> String {
+        format!("ollama:{}", self.model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_ollama_embeddings_and_picks_model() {
+        let body = serde_json::json!({ "embedding": [3.0, 4.0] });
+        assert_eq!(parse_embedding(&body, "nomic-embed-text", true).unwrap(), vec![0.6, 0.8]);
+        assert_eq!(parse_embedding(&body, "nomic-embed-text", false).unwrap(), vec![3.0, 4.0]);
+
+        let missing = serde_json::json!({ "error": "model \"mxbai-embed-large\" not found" });
+        assert!(parse_embedding(&missing, "mxbai-embed-large", true).unwrap_err().to_string().contains("not found"));
+        assert!(parse_embedding(&serde_json::json!({ "embedding": [] }), "llama3.2", true).is_err());
+
+        // The built-in model name means "pick a sensible Ollama model"
+        let provider = OllamaEmbeddingProvider::new(&EmbeddingConfig::default());
+        assert_eq!(provider.model, DEFAULT_OLLAMA_EMBEDDING_MODEL);
+        let config = EmbeddingConfig { model_name: "mxbai-embed-large".to_string(), ..EmbeddingConfig::default() };
+        assert_eq!(OllamaEmbeddingProvider::new(&config).model, "mxbai-embed-large");
+        assert_eq!(OllamaEmbeddingProvider::new(&config).model_id(), "ollama:mxbai-embed-large");
+        assert!(!provider.is_initialized());
+    }
+}
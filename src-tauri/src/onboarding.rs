@@ -0,0 +1,365 @@
+// src-tauri/src/onboarding.rs
+// First-run setup - an ordered set of steps, each backed by a real check, that the frontend wizard
+// walks through. Progress goes out as "setup-progress" events and the state is stored, so a wizard
+// closed halfway resumes where it left off.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, RwLock};
+use tauri::{AppHandle, Emitter};
+
+use crate::audio_loopback::settings::{read_audio_settings, write_audio_settings};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+/// Agents whose models are pulled unless the wizard picks others
+const DEFAULT_MODEL_AGENTS: &[&str] = &["enteract", "vision"];
+pub const DEFAULT_WHISPER_MODEL: &str = "base";
+
+lazy_static::lazy_static! {
+    static ref SETUP_STATE: RwLock<SetupState> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref RUNNING_STEP: Mutex<Option<SetupStep>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SetupStep {
+    CheckOllama,
+    PullModels,
+    SelectAudioDevice,
+    Permissions,
+    TestCapture,
+    WhisperModel,
+}
+
+impl SetupStep {
+    pub const ALL: [SetupStep; 6] = [
+        SetupStep::CheckOllama,
+        SetupStep::PullModels,
+        SetupStep::SelectAudioDevice,
+        SetupStep::Permissions,
+        SetupStep::TestCapture,
+        SetupStep::WhisperModel,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|step| *step == self).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StepStatus {
+    #[default]
+    Pending,
+    Running,
+    Done,
+    Skipped,
+    Failed,
+}
+
+impl StepStatus {
+    fn is_settled(self) -> bool {
+        matches!(self, StepStatus::Done | StepStatus::Skipped)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepState {
+    pub step: SetupStep,
+    pub status: StepStatus,
+    pub message: Option<String>,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SetupState {
+    pub completed: bool,
+    pub completed_at: Option<i64>,
+    pub steps: Vec<StepState>,
+    pub selected_device: Option<String>,
+    pub installed_models: Vec<String>,
+    pub whisper_model: Option<String>,
+}
+
+impl Default for SetupState {
+    fn default() -> Self {
+        Self {
+            completed: false,
+            completed_at: None,
+            steps: SetupStep::ALL
+                .iter()
+                .map(|step| StepState { step: *step, status: StepStatus::Pending, message: None, updated_at: None })
+                .collect(),
+            selected_device: None,
+            installed_models: Vec::new(),
+            whisper_model: None,
+        }
+    }
+}
+
+impl SetupState {
+    fn status(&self, step: SetupStep) -> StepStatus {
+        self.steps.iter().find(|s| s.step == step).map(|s| s.status).unwrap_or_default()
+    }
+
+    fn set(&mut self, step: SetupStep, status: StepStatus, message: Option<String>) {
+        let updated_at = Some(chrono::Utc::now().timestamp_millis());
+        match self.steps.iter_mut().find(|s| s.step == step) {
+            Some(state) => {
+                state.status = status;
+                state.message = message;
+                state.updated_at = updated_at;
+            }
+            None => self.steps.push(StepState { step, status, message, updated_at }),
+        }
+        self.steps.sort_by_key(|s| s.step.index());
+    }
+
+    /// First step that still needs doing, `None` once everything is done or skipped
+    pub fn next_step(&self) -> Option<SetupStep> {
+        SetupStep::ALL.into_iter().find(|step| !self.status(*step).is_settled())
+    }
+
+    // Steps build on each other, so a step only runs once everything before it is settled
+    fn can_run(&self, step: SetupStep) -> Result<(), String> {
+        match SetupStep::ALL[..step.index()].iter().find(|earlier| !self.status(**earlier).is_settled()) {
+            Some(blocking) => Err(format!("Finish or skip the {:?} step first", blocking)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Choices the wizard can pass to a step; anything left out uses the defaults
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SetupStepOptions {
+    pub models: Option<Vec<String>>,
+    pub device_id: Option<String>,
+    pub whisper_model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetupProgress<'a> {
+    step: SetupStep,
+    status: StepStatus,
+    message: Option<&'a str>,
+    /// 0.0 to 1.0 for steps that download something
+    progress: Option<f64>,
+}
+
+fn emit_progress(app_handle: &AppHandle, step: SetupStep, status: StepStatus, message: Option<&str>, progress: Option<f64>) {
+    let _ = app_handle.emit("setup-progress", SetupProgress { step, status, message, progress });
+}
+
+const SETTINGS_FILE: &str = "onboarding.json";
+
+fn update_state<F: FnOnce(&mut SetupState)>(f: F) -> Result<SetupState, String> {
+    let mut state = SETUP_STATE.write().map_err(|_| "Failed to update setup state".to_string())?;
+    f(&mut state);
+    save_json_settings(SETTINGS_FILE, "setup state", &*state)?;
+    Ok(state.clone())
+}
+
+async fn check_ollama() -> Result<String, String> {
+    let status = crate::ollama::get_ollama_status().await?;
+    if status.status != "running" {
+        return Err("Ollama isn't running. Install it from ollama.com or start it, then check again.".to_string());
+    }
+    Ok(match status.version {
+        Some(version) => format!("Ollama {} is running", version),
+        None => "Ollama is running".to_string(),
+    })
+}
+
+async fn pull_models(app_handle: &AppHandle, models: Vec<String>) -> Result<String, String> {
+    let installed: Vec<String> = crate::ollama::get_ollama_models()
+        .await?
+        .into_iter()
+        .map(|model| model.name)
+        .collect();
+    let missing: Vec<String> = models.iter().filter(|model| !installed.contains(model)).cloned().collect();
+
+    for (index, model) in missing.iter().enumerate() {
+        let step_share = 1.0 / missing.len() as f64;
+        crate::ollama::pull_model_with_progress(model, |pull| {
+            let fraction = pull.percent.map(|percent| percent / 100.0).unwrap_or(0.0);
+            let message = format!("{}: {}", model, pull.status);
+            let progress = (index as f64 + fraction) * step_share;
+            emit_progress(app_handle, SetupStep::PullModels, StepStatus::Running, Some(&message), Some(progress));
+        }).await?;
+    }
+    update_state(|state| state.installed_models = models.clone())?;
+
+    Ok(if missing.is_empty() {
+        "Models are already installed".to_string()
+    } else {
+        format!("Installed {}", missing.join(", "))
+    })
+}
+
+async fn select_audio_device(device_id: Option<String>) -> Result<String, String> {
+    let device_id = match device_id {
+        Some(device_id) => device_id,
+        None => crate::audio_loopback::auto_select_best_device()
+            .await?
+            .map(|device| device.id)
+            .ok_or("No audio device that supports capture was found")?,
+    };
+
+    let mut settings = read_audio_settings()?.unwrap_or_default();
+    settings.selectedLoopbackDevice = Some(device_id.clone());
+    write_audio_settings(&settings)?;
+    update_state(|state| state.selected_device = Some(device_id.clone()))?;
+    Ok(format!("Using {}", crate::audio_loopback::device_aliases::describe_device(&device_id)))
+}
+
+#[cfg(target_os = "macos")]
+fn check_permissions() -> Result<String, String> {
+    use crate::audio_loopback::macos::screen_capture_kit;
+    // Core Audio taps on older releases don't need screen recording access
+    if !screen_capture_kit::is_screen_capture_kit_available() {
+        return Ok("No extra permissions needed on this macOS version".to_string());
+    }
+    // Asking for shareable content shows the system prompt the first time
+    if screen_capture_kit::has_screen_recording_permission() {
+        return Ok("Screen recording access granted".to_string());
+    }
+    let _ = crate::permissions::open_permission_settings(crate::permissions::PermissionKind::ScreenRecording);
+    Err("Allow Enteract under Screen Recording in System Settings, then check again.".to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn check_permissions() -> Result<String, String> {
+    Ok("No extra permissions needed on this platform".to_string())
+}
+
+async fn test_capture() -> Result<String, String> {
+    let device_id = read_settings(&SETUP_STATE)
+        .selected_device
+        .or_else(|| read_audio_settings().ok().flatten().and_then(|settings| settings.selectedLoopbackDevice))
+        .ok_or("Select an audio device first")?;
+    if crate::audio_loopback::test_audio_device(device_id.clone()).await? {
+        Ok("Audio capture works".to_string())
+    } else {
+        Err(format!(
+            "Couldn't capture from {}; pick another device",
+            crate::audio_loopback::device_aliases::describe_device(&device_id)
+        ))
+    }
+}
+
+async fn download_whisper_model(app_handle: &AppHandle, model_id: String) -> Result<String, String> {
+    // Download progress goes out on "whisper-model-download-progress"
+    crate::whisper_models::ensure_model(Some(app_handle), &model_id).await?;
+    update_state(|state| state.whisper_model = Some(model_id.clone()))?;
+    Ok(format!("Whisper model '{}' is ready", model_id))
+}
+
+async fn perform_step(app_handle: &AppHandle, step: SetupStep, options: SetupStepOptions) -> Result<String, String> {
+    match step {
+        SetupStep::CheckOllama => check_ollama().await,
+        SetupStep::PullModels => {
+            let models = options.models.unwrap_or_else(|| {
+                let mut models: Vec<String> = DEFAULT_MODEL_AGENTS
+                    .iter()
+                    .map(|agent| crate::ollama::agent_profile(agent).model)
+                    .collect();
+                models.dedup();
+                models
+            });
+            pull_models(app_handle, models).await
+        }
+        SetupStep::SelectAudioDevice => select_audio_device(options.device_id).await,
+        SetupStep::Permissions => check_permissions(),
+        SetupStep::TestCapture => test_capture().await,
+        SetupStep::WhisperModel => {
+            let model_id = options.whisper_model.unwrap_or_else(|| DEFAULT_WHISPER_MODEL.to_string());
+            download_whisper_model(app_handle, model_id).await
+        }
+    }
+}
+
+#[crate::command]
+pub fn get_setup_state() -> Result<SetupState, String> {
+    Ok(read_settings(&SETUP_STATE))
+}
+
+/// Run one step and record the outcome. A failed step can simply be run again.
+#[crate::command]
+pub async fn run_setup_step(app_handle: AppHandle, step: SetupStep, options: Option<SetupStepOptions>) -> Result<StepState, String> {
+    read_settings(&SETUP_STATE).can_run(step)?;
+    {
+        let mut running = RUNNING_STEP.lock().map_err(|_| "Failed to lock setup state".to_string())?;
+        if let Some(other) = *running {
+            return Err(format!("The {:?} step is still running", other));
+        }
+        *running = Some(step);
+    }
+
+    update_state(|state| state.set(step, StepStatus::Running, None))?;
+    emit_progress(&app_handle, step, StepStatus::Running, None, None);
+    let outcome = perform_step(&app_handle, step, options.unwrap_or_default()).await;
+    if let Ok(mut running) = RUNNING_STEP.lock() {
+        *running = None;
+    }
+
+    let (status, message) = match outcome {
+        Ok(message) => (StepStatus::Done, message),
+        Err(message) => (StepStatus::Failed, message),
+    };
+    emit_progress(&app_handle, step, status, Some(&message), None);
+    let state = update_state(|state| state.set(step, status, Some(message)))?;
+    state.steps.into_iter().find(|s| s.step == step).ok_or_else(|| "Setup step missing".to_string())
+}
+
+#[crate::command]
+pub fn skip_setup_step(step: SetupStep) -> Result<SetupState, String> {
+    read_settings(&SETUP_STATE).can_run(step)?;
+    update_state(|state| state.set(step, StepStatus::Skipped, None))
+}
+
+/// Mark setup finished so the wizard isn't shown again; every step must be done or skipped
+#[crate::command]
+pub fn complete_setup() -> Result<SetupState, String> {
+    if let Some(step) = read_settings(&SETUP_STATE).next_step() {
+        return Err(format!("The {:?} step hasn't been finished or skipped", step));
+    }
+    update_state(|state| {
+        state.completed = true;
+        state.completed_at = Some(chrono::Utc::now().timestamp_millis());
+    })
+}
+
+/// Start the wizard over, e.g. from settings
+#[crate::command]
+pub fn reset_setup() -> Result<SetupState, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    update_state(|state| *state = SetupState::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_run_in_order() {
+        let mut state = SetupState::default();
+        assert_eq!(state.next_step(), Some(SetupStep::CheckOllama));
+        assert!(state.can_run(SetupStep::PullModels).is_err());
+
+        state.set(SetupStep::CheckOllama, StepStatus::Done, None);
+        state.set(SetupStep::PullModels, StepStatus::Failed, Some("offline".to_string()));
+        assert!(state.can_run(SetupStep::PullModels).is_ok());
+        assert!(state.can_run(SetupStep::SelectAudioDevice).is_err());
+        assert_eq!(state.next_step(), Some(SetupStep::PullModels));
+
+        for step in SetupStep::ALL {
+            if !state.status(step).is_settled() {
+                state.set(step, StepStatus::Skipped, None);
+            }
+        }
+        assert_eq!(state.next_step(), None);
+    }
+}
@@ -0,0 +1,242 @@
+// Privacy permission preflight - whether the app may use the microphone, record the screen
+// (ScreenCaptureKit needs this for system audio) and use accessibility, with a deep link to the
+// System Settings pane that grants each one.
+//
+// Only macOS gates these; elsewhere every permission reports `notRequired`. Capture paths call
+// `require` first so a missing permission is reported by name instead of as silence.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionKind {
+    Microphone,
+    ScreenRecording,
+    Accessibility,
+}
+
+const ALL_PERMISSIONS: [PermissionKind; 3] = [
+    PermissionKind::Microphone,
+    PermissionKind::ScreenRecording,
+    PermissionKind::Accessibility,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+    /// Blocked by a device management profile or parental controls
+    Restricted,
+    /// Never asked; the system prompts on first use
+    NotDetermined,
+    /// This platform doesn't gate it
+    NotRequired,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionInfo {
+    pub kind: PermissionKind,
+    pub status: PermissionStatus,
+    /// What stops working without it
+    pub needed_for: String,
+    /// What to do about it; `None` when nothing needs doing
+    pub message: Option<String>,
+}
+
+impl PermissionKind {
+    fn label(self) -> &'static str {
+        match self {
+            PermissionKind::Microphone => "Microphone",
+            PermissionKind::ScreenRecording => "Screen Recording",
+            PermissionKind::Accessibility => "Accessibility",
+        }
+    }
+
+    fn needed_for(self) -> &'static str {
+        match self {
+            PermissionKind::Microphone => "microphone transcription and the wake word",
+            PermissionKind::ScreenRecording => "system audio capture",
+            PermissionKind::Accessibility => "tools that click and type in other apps",
+        }
+    }
+
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn settings_url(self) -> &'static str {
+        match self {
+            PermissionKind::Microphone => "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone",
+            PermissionKind::ScreenRecording => "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture",
+            PermissionKind::Accessibility => "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility",
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::PermissionStatus;
+    use objc::runtime::{Class, Object};
+    use objc::{msg_send, sel, sel_impl};
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: *mut Object;
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+        fn CGRequestScreenCaptureAccess() -> bool;
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> u8;
+    }
+
+    pub fn microphone() -> PermissionStatus {
+        let Some(class) = Class::get("AVCaptureDevice") else {
+            return PermissionStatus::NotDetermined;
+        };
+        // AVAuthorizationStatus
+        let status: isize = unsafe { msg_send![class, authorizationStatusForMediaType: AVMediaTypeAudio] };
+        match status {
+            3 => PermissionStatus::Granted,
+            2 => PermissionStatus::Denied,
+            1 => PermissionStatus::Restricted,
+            _ => PermissionStatus::NotDetermined,
+        }
+    }
+
+    // The preflight can't tell "never asked" from "denied"
+    pub fn screen_recording() -> PermissionStatus {
+        if unsafe { CGPreflightScreenCaptureAccess() } {
+            PermissionStatus::Granted
+        } else {
+            PermissionStatus::Denied
+        }
+    }
+
+    /// Shows the system prompt the first time; afterwards only reports the current answer
+    pub fn request_screen_recording() -> bool {
+        unsafe { CGRequestScreenCaptureAccess() }
+    }
+
+    pub fn accessibility() -> PermissionStatus {
+        if unsafe { AXIsProcessTrusted() } != 0 {
+            PermissionStatus::Granted
+        } else {
+            PermissionStatus::Denied
+        }
+    }
+}
+
+pub fn status(kind: PermissionKind) -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    {
+        match kind {
+            PermissionKind::Microphone => macos::microphone(),
+            PermissionKind::ScreenRecording => macos::screen_recording(),
+            PermissionKind::Accessibility => macos::accessibility(),
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = kind;
+        PermissionStatus::NotRequired
+    }
+}
+
+fn message(kind: PermissionKind, status: PermissionStatus) -> Option<String> {
+    match status {
+        PermissionStatus::Denied => Some(format!(
+            "Enteract doesn't have {} access, so {} won't work. Allow it in System Settings > Privacy & Security > {}.",
+            kind.label(),
+            kind.needed_for(),
+            kind.label()
+        )),
+        PermissionStatus::Restricted => Some(format!(
+            "{} access is blocked by a device management profile or parental controls, so {} won't work.",
+            kind.label(),
+            kind.needed_for()
+        )),
+        PermissionStatus::NotDetermined => Some(format!("macOS will ask for {} access the first time it's needed.", kind.label())),
+        PermissionStatus::Granted | PermissionStatus::NotRequired => None,
+    }
+}
+
+// Undecided permissions are fine: using them is what brings up the prompt
+fn check(kind: PermissionKind, status: PermissionStatus) -> Result<(), String> {
+    match status {
+        PermissionStatus::Denied | PermissionStatus::Restricted => Err(message(kind, status).unwrap_or_default()),
+        PermissionStatus::Granted | PermissionStatus::NotDetermined | PermissionStatus::NotRequired => Ok(()),
+    }
+}
+
+/// `Err` with what to do if `kind` is missing; call before starting whatever needs it
+pub fn require(kind: PermissionKind) -> Result<(), String> {
+    let status = status(kind);
+    #[cfg(target_os = "macos")]
+    {
+        // Screen Recording only prompts when asked for explicitly
+        if kind == PermissionKind::ScreenRecording && status == PermissionStatus::Denied && macos::request_screen_recording() {
+            return Ok(());
+        }
+    }
+    check(kind, status)
+}
+
+fn permission_info(kind: PermissionKind) -> PermissionInfo {
+    let status = status(kind);
+    PermissionInfo {
+        kind,
+        status,
+        needed_for: kind.needed_for().to_string(),
+        message: message(kind, status),
+    }
+}
+
+#[crate::command]
+pub fn get_permission_statuses() -> Result<Vec<PermissionInfo>, String> {
+    Ok(ALL_PERMISSIONS.iter().map(|kind| permission_info(*kind)).collect())
+}
+
+#[crate::command]
+pub fn get_permission_status(kind: PermissionKind) -> Result<PermissionInfo, String> {
+    Ok(permission_info(kind))
+}
+
+/// Open the System Settings pane that grants `kind`
+#[crate::command]
+pub fn open_permission_settings(kind: PermissionKind) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg(kind.settings_url())
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to open System Settings: {}", e))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        Err(format!("{} access doesn't need granting on this platform", kind.label()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_refused_permissions_block() {
+        assert!(check(PermissionKind::Microphone, PermissionStatus::Granted).is_ok());
+        assert!(check(PermissionKind::Microphone, PermissionStatus::NotDetermined).is_ok());
+        assert!(check(PermissionKind::Microphone, PermissionStatus::NotRequired).is_ok());
+
+        let error = check(PermissionKind::ScreenRecording, PermissionStatus::Denied).unwrap_err();
+        assert!(error.contains("Screen Recording"));
+        assert!(error.contains("system audio capture"));
+        assert!(check(PermissionKind::Accessibility, PermissionStatus::Restricted).is_err());
+
+        assert_eq!(message(PermissionKind::Microphone, PermissionStatus::Granted), None);
+    }
+}
@@ -0,0 +1,300 @@
+// Gaze-contingent privacy mode - blurs or hides sensitive panels when someone else may be reading the screen
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+// How often time-based conditions (gaze gone for N seconds) are re-checked
+const EVALUATE_INTERVAL: Duration = Duration::from_millis(500);
+// Presence reports older than this no longer count as "a face is there"
+const PRESENCE_STALE_MS: u64 = 3000;
+
+lazy_static::lazy_static! {
+    static ref PRIVACY_SETTINGS: RwLock<PrivacySettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref MONITOR: Mutex<PrivacyMonitor> = Mutex::new(PrivacyMonitor::default());
+    static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PrivacyAction {
+    #[default]
+    Blur,
+    Hide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PrivacyReason {
+    /// The camera sees a face but no gaze is tracked - the user may have stepped away
+    FaceWithoutGaze,
+    /// The user has been looking away from the screen
+    GazeOffScreen,
+    /// More than one face in front of the camera
+    AdditionalFace,
+    /// Turned on by the user
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrivacySettings {
+    pub enabled: bool,
+    pub action: PrivacyAction,
+    /// Seconds of a face without gaze before panels are protected
+    pub no_gaze_seconds: f32,
+    /// Seconds of off-screen gaze before panels are protected
+    pub off_screen_seconds: f32,
+    pub protect_on_additional_face: bool,
+    /// Gaze below this confidence counts as no gaze
+    pub min_gaze_confidence: f32,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: PrivacyAction::Blur,
+            no_gaze_seconds: 2.0,
+            off_screen_seconds: 5.0,
+            protect_on_additional_face: true,
+            min_gaze_confidence: 0.3,
+        }
+    }
+}
+
+impl PrivacySettings {
+    fn validate(&self) -> Result<(), String> {
+        if !(0.5..=120.0).contains(&self.no_gaze_seconds) || !(0.5..=120.0).contains(&self.off_screen_seconds) {
+            return Err("Privacy delays must be between 0.5 and 120 seconds".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_gaze_confidence) {
+            return Err("Minimum gaze confidence must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyModeStatus {
+    pub active: bool,
+    pub reason: Option<PrivacyReason>,
+    pub action: PrivacyAction,
+    /// Unix ms when privacy mode last turned on
+    pub since: Option<u64>,
+    pub manual_override: Option<bool>,
+    pub settings: PrivacySettings,
+}
+
+/// Tracks the latest gaze and presence observations and decides whether panels need protecting
+#[derive(Debug, Default)]
+struct PrivacyMonitor {
+    last_on_screen_gaze_ms: Option<u64>,
+    off_screen_since_ms: Option<u64>,
+    last_presence_ms: Option<u64>,
+    faces: u32,
+    /// `Some(true)` forces privacy on, `Some(false)` forces it off
+    manual_override: Option<bool>,
+    active: Option<PrivacyReason>,
+    since_ms: Option<u64>,
+}
+
+impl PrivacyMonitor {
+    fn observe_gaze(&mut self, on_screen: bool, now_ms: u64) {
+        if on_screen {
+            self.last_on_screen_gaze_ms = Some(now_ms);
+            self.off_screen_since_ms = None;
+        } else if self.off_screen_since_ms.is_none() {
+            self.off_screen_since_ms = Some(now_ms);
+        }
+    }
+
+    fn observe_presence(&mut self, faces: u32, now_ms: u64) {
+        self.faces = faces;
+        self.last_presence_ms = Some(now_ms);
+    }
+
+    fn evaluate(&self, settings: &PrivacySettings, now_ms: u64) -> Option<PrivacyReason> {
+        match self.manual_override {
+            Some(true) => return Some(PrivacyReason::Manual),
+            Some(false) => return None,
+            None => {}
+        }
+        if !settings.enabled {
+            return None;
+        }
+
+        let face_present = self.faces > 0
+            && self.last_presence_ms.map_or(false, |at| now_ms.saturating_sub(at) <= PRESENCE_STALE_MS);
+        if settings.protect_on_additional_face && face_present && self.faces > 1 {
+            return Some(PrivacyReason::AdditionalFace);
+        }
+
+        let elapsed = |since: u64| now_ms.saturating_sub(since) as f32 / 1000.0;
+        if let Some(since) = self.off_screen_since_ms {
+            if elapsed(since) >= settings.off_screen_seconds {
+                return Some(PrivacyReason::GazeOffScreen);
+            }
+        }
+        // Both the "no gaze" clock and the presence camera have to agree
+        let gaze_missing_for = self.last_on_screen_gaze_ms.map_or(f32::MAX, elapsed);
+        if face_present && self.off_screen_since_ms.is_none() && gaze_missing_for >= settings.no_gaze_seconds {
+            return Some(PrivacyReason::FaceWithoutGaze);
+        }
+        None
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+const SETTINGS_FILE: &str = "privacy_mode.json";
+
+/// Keep the app handle for events and start re-checking time-based conditions
+pub fn init(app_handle: &AppHandle) {
+    if let Ok(mut handle) = APP_HANDLE.lock() {
+        *handle = Some(app_handle.clone());
+    }
+    std::thread::spawn(|| loop {
+        std::thread::sleep(EVALUATE_INTERVAL);
+        reevaluate();
+    });
+}
+
+/// Feed a gaze sample from the eye tracker. Low-confidence samples count as no gaze.
+#[cfg_attr(not(feature = "eye-tracking"), allow(dead_code))]
+pub fn observe_gaze(x: f64, y: f64, confidence: f32, screen_width: u32, screen_height: u32) {
+    let settings = read_settings(&PRIVACY_SETTINGS);
+    if confidence < settings.min_gaze_confidence {
+        return;
+    }
+    let on_screen = (0.0..=screen_width as f64).contains(&x) && (0.0..=screen_height as f64).contains(&y);
+    if let Ok(mut monitor) = MONITOR.lock() {
+        monitor.observe_gaze(on_screen, now_ms());
+    }
+    reevaluate();
+}
+
+/// Feed the number of faces the presence camera currently sees
+#[cfg_attr(not(feature = "eye-tracking"), allow(dead_code))]
+pub fn observe_presence(faces: u32) {
+    if let Ok(mut monitor) = MONITOR.lock() {
+        monitor.observe_presence(faces, now_ms());
+    }
+    reevaluate();
+}
+
+/// Forget gaze and presence history, e.g. when eye tracking stops. A manual override is kept.
+#[cfg_attr(not(feature = "eye-tracking"), allow(dead_code))]
+pub fn reset_observations() {
+    if let Ok(mut monitor) = MONITOR.lock() {
+        let manual_override = monitor.manual_override;
+        *monitor = PrivacyMonitor { manual_override, active: monitor.active, since_ms: monitor.since_ms, ..Default::default() };
+    }
+    reevaluate();
+}
+
+fn status_from(monitor: &PrivacyMonitor, settings: PrivacySettings) -> PrivacyModeStatus {
+    PrivacyModeStatus {
+        active: monitor.active.is_some(),
+        reason: monitor.active,
+        action: settings.action,
+        since: monitor.since_ms,
+        manual_override: monitor.manual_override,
+        settings,
+    }
+}
+
+// Emits "privacy-mode-changed" only when the protected state or its reason changes
+fn reevaluate() {
+    let settings = read_settings(&PRIVACY_SETTINGS);
+    let now = now_ms();
+    let status = {
+        let Ok(mut monitor) = MONITOR.lock() else { return };
+        let reason = monitor.evaluate(&settings, now);
+        if reason == monitor.active {
+            return;
+        }
+        if monitor.active.is_none() {
+            monitor.since_ms = Some(now);
+        }
+        if reason.is_none() {
+            monitor.since_ms = None;
+        }
+        monitor.active = reason;
+        status_from(&monitor, settings)
+    };
+
+    println!("🕶️ Privacy mode {} ({:?})", if status.active { "on" } else { "off" }, status.reason);
+    if let Some(app_handle) = APP_HANDLE.lock().ok().and_then(|handle| handle.clone()) {
+        let _ = app_handle.emit("privacy-mode-changed", &status);
+    }
+}
+
+#[crate::command]
+pub fn get_privacy_mode() -> Result<PrivacyModeStatus, String> {
+    let monitor = MONITOR.lock().map_err(|_| "Failed to read privacy mode".to_string())?;
+    Ok(status_from(&monitor, read_settings(&PRIVACY_SETTINGS)))
+}
+
+#[crate::command]
+pub fn save_privacy_settings(settings: PrivacySettings) -> Result<PrivacyModeStatus, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    settings.validate()?;
+    save_json_settings(SETTINGS_FILE, "privacy settings", &settings)?;
+
+    *PRIVACY_SETTINGS
+        .write()
+        .map_err(|_| "Failed to update privacy settings".to_string())? = settings;
+    reevaluate();
+    get_privacy_mode()
+}
+
+/// Force privacy mode on (`true`) or off (`false`); `None` hands control back to eye tracking
+#[crate::command]
+pub fn set_privacy_override(active: Option<bool>) -> Result<PrivacyModeStatus, String> {
+    MONITOR
+        .lock()
+        .map_err(|_| "Failed to update privacy mode".to_string())?
+        .manual_override = active;
+    reevaluate();
+    get_privacy_mode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privacy_triggers() {
+        let settings = PrivacySettings { enabled: true, ..Default::default() };
+        let mut monitor = PrivacyMonitor::default();
+
+        // Face in view and gaze on screen: nothing to hide
+        monitor.observe_presence(1, 0);
+        monitor.observe_gaze(true, 0);
+        assert_eq!(monitor.evaluate(&settings, 1000), None);
+
+        // Face still there, gaze lost for longer than no_gaze_seconds
+        monitor.observe_presence(1, 2500);
+        assert_eq!(monitor.evaluate(&settings, 2500), Some(PrivacyReason::FaceWithoutGaze));
+
+        // Looking away long enough
+        monitor.observe_gaze(false, 3000);
+        assert_eq!(monitor.evaluate(&settings, 7000), None);
+        assert_eq!(monitor.evaluate(&settings, 8000), Some(PrivacyReason::GazeOffScreen));
+
+        monitor.observe_gaze(true, 8000);
+        monitor.observe_presence(2, 8000);
+        assert_eq!(monitor.evaluate(&settings, 8000), Some(PrivacyReason::AdditionalFace));
+
+        monitor.manual_override = Some(false);
+        assert_eq!(monitor.evaluate(&settings, 8000), None);
+    }
+}
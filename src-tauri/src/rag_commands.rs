@@ -5,12 +5,31 @@ use std::sync::Mutex;
 use tauri::State;
 
 use std::sync::Arc;
+use crate::app_lock::{require_unlocked, CommandGroup};
 
 // Global RAG system instance
 #[derive(Clone)]
 pub struct RagSystemState(pub Arc<Mutex<Option<RagSystem>>>);
 
-#[tauri::command]
+// Run `f` on the RAG system, creating it first if this is the first command to need it
+fn with_system<T>(
+    app_handle: &tauri::AppHandle,
+    state: &RagSystemState,
+    f: impl FnOnce(&RagSystem) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut rag_state = state.0.lock().map_err(|e| e.to_string())?;
+    if rag_state.is_none() {
+        let system = crate::startup::lazy_step("legacy_rag", || RagSystem::new(app_handle))
+            .map_err(|e| format!("Failed to initialize RAG system: {}", e))?;
+        *rag_state = Some(system);
+    }
+    match &*rag_state {
+        Some(system) => f(system),
+        None => Err("RAG system not initialized".to_string()),
+    }
+}
+
+#[crate::command]
 pub async fn initialize_rag_system(
     app_handle: tauri::AppHandle,
     state: State<'_, RagSystemState>,
@@ -26,123 +45,95 @@ pub async fn initialize_rag_system(
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn upload_document(
+    app_handle: tauri::AppHandle,
     file_name: String,
     file_content: Vec<u8>,
     file_type: String,
     state: State<'_, RagSystemState>,
 ) -> Result<Document, String> {
     // Clone the system reference to avoid holding the lock across await
-    let system = {
-        let rag_state = state.0.lock().map_err(|e| e.to_string())?;
-        match &*rag_state {
-            Some(sys) => Ok(sys.clone()),
-            None => Err("RAG system not initialized".to_string())
-        }
-    }?;
+    let system = with_system(&app_handle, &state, |sys| Ok(sys.clone()))?;
+    crate::disk_space::preflight(crate::disk_space::Subsystem::Rag, file_content.len() as u64)?;
     
     system.upload_document(file_name, file_content, file_type)
         .await
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_all_documents(
+    app_handle: tauri::AppHandle,
     state: State<'_, RagSystemState>,
 ) -> Result<Vec<Document>, String> {
-    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
-    
-    match &*rag_state {
-        Some(system) => {
-            system.get_all_documents()
-                .map_err(|e| e.to_string())
-        }
-        None => Err("RAG system not initialized".to_string())
-    }
+    with_system(&app_handle, &state, |system| {
+        system.get_all_documents()
+            .map_err(|e| e.to_string())
+    })
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn delete_document(
+    app_handle: tauri::AppHandle,
     document_id: String,
     state: State<'_, RagSystemState>,
 ) -> Result<String, String> {
-    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
-    
-    match &*rag_state {
-        Some(system) => {
-            system.delete_document(&document_id)
-                .map_err(|e| e.to_string())?;
-            Ok(format!("Document {} deleted successfully", document_id))
-        }
-        None => Err("RAG system not initialized".to_string())
-    }
+    require_unlocked(CommandGroup::DataDeletion)?;
+    with_system(&app_handle, &state, |system| {
+        system.delete_document(&document_id)
+            .map_err(|e| e.to_string())?;
+        Ok(format!("Document {} deleted successfully", document_id))
+    })
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn search_documents(
+    app_handle: tauri::AppHandle,
     query: String,
     context_document_ids: Vec<String>,
     state: State<'_, RagSystemState>,
 ) -> Result<Vec<DocumentChunk>, String> {
-    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
-    
-    match &*rag_state {
-        Some(system) => {
-            system.search_documents(&query, context_document_ids)
-                .map_err(|e| e.to_string())
-        }
-        None => Err("RAG system not initialized".to_string())
-    }
+    with_system(&app_handle, &state, |system| {
+        system.search_documents(&query, context_document_ids)
+            .map_err(|e| e.to_string())
+    })
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn update_rag_settings(
+    app_handle: tauri::AppHandle,
     settings: RagSettings,
     state: State<'_, RagSystemState>,
 ) -> Result<String, String> {
-    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
-    
-    match &*rag_state {
-        Some(system) => {
-            system.update_settings(settings)
-                .map_err(|e| e.to_string())?;
-            Ok("Settings updated successfully".to_string())
-        }
-        None => Err("RAG system not initialized".to_string())
-    }
+    require_unlocked(CommandGroup::SettingsChanges)?;
+    with_system(&app_handle, &state, |system| {
+        system.update_settings(settings)
+            .map_err(|e| e.to_string())?;
+        Ok("Settings updated successfully".to_string())
+    })
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_rag_settings(
+    app_handle: tauri::AppHandle,
     state: State<'_, RagSystemState>,
 ) -> Result<RagSettings, String> {
-    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
-    
-    match &*rag_state {
-        Some(system) => {
-            Ok(system.get_settings())
-        }
-        None => Err("RAG system not initialized".to_string())
-    }
+    with_system(&app_handle, &state, |system| Ok(system.get_settings()))
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_storage_stats(
+    app_handle: tauri::AppHandle,
     state: State<'_, RagSystemState>,
 ) -> Result<HashMap<String, Value>, String> {
-    let rag_state = state.0.lock().map_err(|e| e.to_string())?;
-    
-    match &*rag_state {
-        Some(system) => {
-            system.get_storage_stats()
-                .map_err(|e| e.to_string())
-        }
-        None => Err("RAG system not initialized".to_string())
-    }
+    with_system(&app_handle, &state, |system| {
+        system.get_storage_stats()
+            .map_err(|e| e.to_string())
+    })
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn generate_embeddings(
     document_id: String,
     _state: State<'_, RagSystemState>,
@@ -152,10 +143,11 @@ pub async fn generate_embeddings(
     Ok(format!("Embeddings for document {} will be generated", document_id))
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn clear_embedding_cache(
     _state: State<'_, RagSystemState>,
 ) -> Result<String, String> {
+    require_unlocked(CommandGroup::DataDeletion)?;
     // TODO: Implement cache clearing
     Ok("Embedding cache cleared".to_string())
 }
\ No newline at end of file
@@ -2,6 +2,8 @@ use xcap::Monitor;
 use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
+use crate::coordinates::{ImageMapping, MonitorGeometry};
+use crate::action_registry::{descriptor, to_value, ActionKind, RegisteredAction};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScreenshotResult {
@@ -9,21 +11,43 @@ pub struct ScreenshotResult {
     pub width: u32,
     pub height: u32,
     pub format: String,
+    /// Where the image sits on the desktop, for turning its pixels into click coordinates
+    pub mapping: ImageMapping,
 }
 
-#[tauri::command]
-pub async fn capture_screenshot() -> Result<ScreenshotResult, String> {
+fn image_mapping(monitor: &Monitor, region: Option<(i32, i32, u32, u32)>, width: u32, height: u32) -> Result<ImageMapping, String> {
+    let layout = crate::coordinates::current_layout()?;
+    let id = monitor.id().map_err(|e| format!("Failed to identify monitor: {}", e))?;
+    let geometry = layout.monitor(id).ok_or("Captured monitor is no longer connected")?;
+    Ok(ImageMapping::for_capture(geometry, region, width, height))
+}
+
+/// Monitor `index` in the layout `list_monitors` reports, or the primary one
+fn pick_monitor(index: Option<usize>) -> Result<Monitor, String> {
+    let layout = crate::coordinates::current_layout()?;
+    let geometry = match index {
+        Some(index) => layout.monitor_by_index(index)?,
+        None => layout.primary(),
+    };
+    Monitor::all()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?
+        .into_iter()
+        .find(|monitor| monitor.id().ok() == Some(geometry.id))
+        .ok_or_else(|| "Monitor is no longer connected".to_string())
+}
+
+/// Every monitor in desktop coordinates; `capture_screenshot` takes an index into this list
+#[crate::command]
+pub async fn list_monitors() -> Result<Vec<MonitorGeometry>, String> {
+    Ok(crate::coordinates::current_layout()?.monitors)
+}
+
+/// Capture a whole monitor - `monitor` indexes `list_monitors`, the primary one by default
+#[crate::command]
+pub async fn capture_screenshot(monitor: Option<usize>) -> Result<ScreenshotResult, String> {
     println!("📸 Capturing screenshot...");
     
-    // Get all monitors
-    let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
-    
-    // Use the primary monitor or first one if no primary found
-    let monitor = monitors
-        .into_iter()
-        .find(|m| m.is_primary().unwrap_or(false))
-        .or_else(|| Monitor::all().ok()?.into_iter().next())
-        .ok_or("No monitors found")?;
+    let monitor = pick_monitor(monitor)?;
     
     println!("📸 Found monitor: {}x{}", 
         monitor.width().unwrap_or(0), 
@@ -38,6 +62,7 @@ pub async fn capture_screenshot() -> Result<ScreenshotResult, String> {
     let height = image.height();
     
     println!("📸 Captured image: {}x{}", width, height);
+    let mapping = image_mapping(&monitor, None, width, height)?;
     
     // Convert to PNG bytes
     let mut png_data = Vec::new();
@@ -54,10 +79,11 @@ pub async fn capture_screenshot() -> Result<ScreenshotResult, String> {
         width,
         height,
         format: "png".to_string(),
+        mapping,
     })
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn capture_screenshot_area(x: i32, y: i32, width: u32, height: u32) -> Result<ScreenshotResult, String> {
     println!("📸 Capturing screenshot area: {}x{} at ({}, {})", width, height, x, y);
     
@@ -98,6 +124,8 @@ pub async fn capture_screenshot_area(x: i32, y: i32, width: u32, height: u32) ->
     let captured_height = image.height();
     
     println!("📸 Captured region: {}x{}", captured_width, captured_height);
+    let region = (monitor_x + relative_x.max(0), monitor_y + relative_y.max(0), width, height);
+    let mapping = image_mapping(&monitor, Some(region), captured_width, captured_height)?;
     
     // Convert to PNG bytes
     let mut png_data = Vec::new();
@@ -115,5 +143,15 @@ pub async fn capture_screenshot_area(x: i32, y: i32, width: u32, height: u32) ->
         width: captured_width,
         height: captured_height,
         format: "png".to_string(),
+        mapping,
     })
-}
\ No newline at end of file
+}
+
+/// Command palette actions for screen capture
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    vec![RegisteredAction {
+        descriptor: descriptor("screenshot.capture", ActionKind::Command, "Capture Screenshot",
+            "Capture the primary screen", vec![], &["screen", "image"]),
+        handler: |_, _| Box::pin(async move { to_value(capture_screenshot(None).await?) }),
+    }]
+}
@@ -1,19 +1,25 @@
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use tantivy::collector::TopDocs;
 use tantivy::query::QueryParser;
 use tantivy::schema::{Schema, STORED, TEXT, FAST, Field, Value};
 use tantivy::{Index, IndexWriter, IndexReader};
 
+use crate::vector_index::VectorIndex;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SearchConfig {
     pub bm25_weight: f32,
     pub vector_weight: f32,
     pub max_results: usize,
+    /// Minimum cosine similarity between the query and a chunk for the chunk to count as relevant
     pub min_score_threshold: f32,
+    /// Minimum BM25 score for a keyword match to count as relevant on its own
+    pub min_bm25_score: f32,
 }
 
 impl Default for SearchConfig {
@@ -22,11 +28,20 @@ impl Default for SearchConfig {
             bm25_weight: 0.7,
             vector_weight: 0.3,
             max_results: 50,
-            min_score_threshold: 0.1,
+            min_score_threshold: 0.35,
+            min_bm25_score: 1.0,
         }
     }
 }
 
+impl SearchConfig {
+    /// Whether a match clears either threshold; `similarity` is `None` when there was no
+    /// embedding to compare
+    pub fn is_relevant(&self, bm25_score: f32, similarity: Option<f32>) -> bool {
+        bm25_score >= self.min_bm25_score || similarity.is_some_and(|similarity| similarity >= self.min_score_threshold)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub chunk_id: String,
@@ -38,6 +53,20 @@ pub struct SearchResult {
     pub metadata: Option<String>,
 }
 
+/// Size and shape of the BM25 index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchIndexStats {
+    pub segment_count: usize,
+    /// Chunks searchable by BM25
+    pub doc_count: u64,
+    /// Deleted chunks still taking space until their segments are merged
+    pub deleted_doc_count: u64,
+    pub size_bytes: u64,
+    pub vector_count: usize,
+    /// Whether the index was found corrupted at startup and recreated
+    pub recovered: bool,
+}
+
 #[derive(Clone)]
 pub struct SearchService {
     index: Arc<Index>,
@@ -46,6 +75,12 @@ pub struct SearchService {
     schema: Schema,
     fields: SearchFields,
     config: SearchConfig,
+    // Chunk embeddings for vector search, saved to `vector_path` on commit
+    vectors: Arc<RwLock<VectorIndex>>,
+    vector_path: PathBuf,
+    index_dir: PathBuf,
+    // The index was recreated empty and needs refilling by the owner
+    recovered: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -89,16 +124,32 @@ impl SearchService {
             let _ = std::fs::remove_file(&lock_file); // Ignore errors, might be in use
         }
         
-        let index = if index_dir.join("meta.json").exists() {
-            Index::open_in_dir(&index_dir)?
+        let (index, reader, recovered) = if index_dir.join("meta.json").exists() {
+            match Self::open_index(&index_dir) {
+                Ok((index, reader)) => (index, reader, false),
+                Err(e) => {
+                    eprintln!("Search index is corrupted, recreating it: {}", e);
+                    Self::remove_index_files(&index_dir)?;
+                    let index = Index::create_in_dir(&index_dir, schema.clone())?;
+                    let reader = index.reader_builder().try_into()?;
+                    (index, reader, true)
+                }
+            }
         } else {
-            Index::create_in_dir(&index_dir, schema.clone())?
+            let index = Index::create_in_dir(&index_dir, schema.clone())?;
+            let reader = index.reader_builder().try_into()?;
+            (index, reader, false)
         };
         
-        // Set up reader with auto-reload
-        let reader = index
-            .reader_builder()
-            .try_into()?;
+        let vector_path = index_dir.join("vectors.hnsw");
+        let vectors = if vector_path.exists() {
+            VectorIndex::load(&vector_path).unwrap_or_else(|e| {
+                eprintln!("Failed to load vector index, it needs rebuilding: {}", e);
+                VectorIndex::default()
+            })
+        } else {
+            VectorIndex::default()
+        };
         
         Ok(Self {
             index: Arc::new(index),
@@ -107,9 +158,46 @@ impl SearchService {
             schema,
             fields,
             config,
+            vectors: Arc::new(RwLock::new(vectors)),
+            vector_path,
+            index_dir,
+            recovered,
         })
     }
     
+    // Open an existing index, failing when it can't be read: missing or unreadable segment
+    // files fail opening the reader, damaged ones their checksums
+    fn open_index(index_dir: &Path) -> Result<(Index, IndexReader)> {
+        let index = Index::open_in_dir(index_dir)?;
+        let damaged = index.validate_checksum()?;
+        if !damaged.is_empty() {
+            return Err(anyhow!("{} index files failed their checksums", damaged.len()));
+        }
+        let reader = index.reader_builder().try_into()?;
+        Ok((index, reader))
+    }
+    
+    // Everything in the index directory but the vector index, which is checked on its own
+    fn remove_index_files(index_dir: &Path) -> Result<()> {
+        for entry in std::fs::read_dir(index_dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|name| name == "vectors.hnsw") {
+                continue;
+            }
+            if path.is_dir() {
+                std::fs::remove_dir_all(&path)?;
+            } else {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Whether the index was corrupted at startup and recreated empty, so its chunks need adding again
+    pub fn recovered(&self) -> bool {
+        self.recovered
+    }
+    
     pub fn initialize_writer(&self) -> Result<()> {
         let mut writer_guard = self.writer.lock().map_err(|e| anyhow!("Mutex lock failed: {}", e))?;
         
@@ -136,6 +224,9 @@ impl SearchService {
             doc.add_text(self.fields.content, &chunk.content);
             
             if let Some(embedding) = chunk.embedding {
+                if let Err(e) = self.vectors.write().map_err(|e| anyhow!("Lock failed: {}", e))?.insert(&chunk.id, &chunk.document_id, &embedding) {
+                    eprintln!("Chunk {} left out of the vector index: {}", chunk.id, e);
+                }
                 let embedding_bytes = embedding_to_bytes(&embedding);
                 doc.add_bytes(self.fields.embedding, embedding_bytes);
             }
@@ -155,7 +246,38 @@ impl SearchService {
         if let Some(writer) = writer_guard.as_mut() {
             writer.commit()?;
         }
-        Ok(())
+        self.save_vectors()
+    }
+    
+    fn save_vectors(&self) -> Result<()> {
+        let mut vectors = self.vectors.write().map_err(|e| anyhow!("Lock failed: {}", e))?;
+        if vectors.needs_compaction() {
+            *vectors = vectors.compacted();
+        }
+        vectors.save(&self.vector_path)
+    }
+    
+    /// Replace the vector index with one built from `chunks` (chunk id, document id, embedding)
+    /// and save it. Returns how many chunks went in; ones with another embedding size are skipped.
+    pub fn rebuild_vectors(&self, chunks: impl IntoIterator<Item = (String, String, Vec<f32>)>) -> Result<usize> {
+        let mut rebuilt = VectorIndex::default();
+        let mut skipped = 0;
+        for (chunk_id, document_id, embedding) in chunks {
+            if rebuilt.insert(&chunk_id, &document_id, &embedding).is_err() {
+                skipped += 1;
+            }
+        }
+        if skipped > 0 {
+            eprintln!("{} chunks have embeddings of another size than the first and were skipped; re-embed them", skipped);
+        }
+        let count = rebuilt.len();
+        *self.vectors.write().map_err(|e| anyhow!("Lock failed: {}", e))? = rebuilt;
+        self.save_vectors()?;
+        Ok(count)
+    }
+    
+    pub fn vector_count(&self) -> usize {
+        self.vectors.read().map(|vectors| vectors.len()).unwrap_or(0)
     }
     
     pub fn search_bm25(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
@@ -206,11 +328,22 @@ impl SearchService {
         Ok(results)
     }
     
-    pub fn search_vector(&self, _query_embedding: &[f32], _limit: usize) -> Result<Vec<SearchResult>> {
-        // For now, return empty results since vector search is complex with current Tantivy API
-        // This can be implemented later with proper HNSW index
-        println!("Vector search not yet implemented - falling back to BM25");
-        Ok(Vec::new())
+    /// Nearest chunks by embedding. Only ids and scores are filled in; callers load the chunk text.
+    pub fn search_vector(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        let vectors = self.vectors.read().map_err(|e| anyhow!("Lock failed: {}", e))?;
+        Ok(vectors
+            .search(query_embedding, limit)
+            .into_iter()
+            .map(|found| SearchResult {
+                chunk_id: found.chunk_id,
+                document_id: found.document_id,
+                content: String::new(),
+                score: found.similarity,
+                bm25_score: 0.0,
+                vector_score: found.similarity,
+                metadata: None,
+            })
+            .collect())
     }
     
     pub fn hybrid_search(&self, query: &str, query_embedding: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
@@ -272,20 +405,79 @@ impl SearchService {
         
         let term = tantivy::Term::from_field_text(self.fields.document_id, document_id);
         writer.delete_term(term);
+        self.vectors.write().map_err(|e| anyhow!("Lock failed: {}", e))?.remove_document(document_id);
         
         Ok(())
     }
     
+    pub fn delete_chunk(&self, chunk_id: &str) -> Result<()> {
+        let mut writer_guard = self.writer.lock().map_err(|e| anyhow!("Mutex lock failed: {}", e))?;
+        let writer = writer_guard.as_mut().ok_or_else(|| anyhow!("Writer not initialized"))?;
+        
+        let term = tantivy::Term::from_field_text(self.fields.chunk_id, chunk_id);
+        writer.delete_term(term);
+        self.vectors.write().map_err(|e| anyhow!("Lock failed: {}", e))?.remove_chunk(chunk_id);
+        
+        Ok(())
+    }
+    
+    /// Take a document's chunks out of the vector index only; they stay searchable by BM25
+    pub fn unload_vectors(&self, document_id: &str) -> Result<()> {
+        self.vectors.write().map_err(|e| anyhow!("Lock failed: {}", e))?.remove_document(document_id);
+        Ok(())
+    }
+    
     pub fn clear_index(&self) -> Result<()> {
         let mut writer_guard = self.writer.lock().map_err(|e| anyhow!("Mutex lock failed: {}", e))?;
         let writer = writer_guard.as_mut().ok_or_else(|| anyhow!("Writer not initialized"))?;
         
         writer.delete_all_documents()?;
         writer.commit()?;
+        *self.vectors.write().map_err(|e| anyhow!("Lock failed: {}", e))? = VectorIndex::default();
+        self.save_vectors()?;
         
         Ok(())
     }
     
+    /// Merge all segments into one, dropping deleted chunks, and compact the vector index
+    pub fn compact(&self) -> Result<SearchIndexStats> {
+        {
+            let mut writer_guard = self.writer.lock().map_err(|e| anyhow!("Mutex lock failed: {}", e))?;
+            let writer = writer_guard.as_mut().ok_or_else(|| anyhow!("Writer not initialized"))?;
+            writer.commit()?;
+            let segment_ids = self.index.searchable_segment_ids()?;
+            if !segment_ids.is_empty() {
+                writer.merge(&segment_ids).wait()?;
+            }
+            writer.garbage_collect_files().wait()?;
+        }
+        self.reader.reload()?;
+        {
+            let mut vectors = self.vectors.write().map_err(|e| anyhow!("Lock failed: {}", e))?;
+            *vectors = vectors.compacted();
+            vectors.save(&self.vector_path)?;
+        }
+        self.stats()
+    }
+    
+    pub fn stats(&self) -> Result<SearchIndexStats> {
+        let segments = self.index.searchable_segment_metas()?;
+        let size_bytes = segments
+            .iter()
+            .flat_map(|segment| segment.list_files())
+            .filter_map(|file| std::fs::metadata(self.index_dir.join(file)).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        Ok(SearchIndexStats {
+            segment_count: segments.len(),
+            doc_count: segments.iter().map(|segment| segment.num_docs() as u64).sum(),
+            deleted_doc_count: segments.iter().map(|segment| segment.num_deleted_docs() as u64).sum(),
+            size_bytes,
+            vector_count: self.vector_count(),
+            recovered: self.recovered,
+        })
+    }
+    
     pub fn close_writer(&self) -> Result<()> {
         let mut writer_guard = self.writer.lock().map_err(|e| anyhow!("Mutex lock failed: {}", e))?;
         if let Some(mut writer) = writer_guard.take() {
@@ -360,10 +552,70 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_relevance_needs_either_score_over_threshold() {
+        let config = SearchConfig::default();
+        assert!(config.is_relevant(4.2, None));
+        assert!(config.is_relevant(0.2, Some(0.6)));
+        assert!(!config.is_relevant(0.2, Some(0.1)));
+        assert!(!config.is_relevant(0.0, None));
+
+        // Settings saved before the thresholds existed get the defaults
+        let stored: SearchConfig = serde_json::from_str(r#"{"bm25_weight":0.5,"vector_weight":0.5,"max_results":10,"min_score_threshold":0.2}"#).unwrap();
+        assert_eq!(stored.min_bm25_score, 1.0);
+        assert_eq!(stored.min_score_threshold, 0.2);
+    }
+
     #[test]
     fn test_search_service_creation() {
         let temp_dir = tempdir().unwrap();
         let service = SearchService::new(temp_dir.path().to_path_buf(), None);
         assert!(service.is_ok());
     }
+    
+    #[test]
+    fn test_corrupted_index_is_recreated_and_compaction_merges_segments() {
+        let temp_dir = tempdir().unwrap();
+        let chunk = |id: &str, content: &str| DocumentChunk {
+            id: id.to_string(),
+            document_id: "doc".to_string(),
+            content: content.to_string(),
+            embedding: None,
+            metadata: None,
+        };
+        {
+            let service = SearchService::new(temp_dir.path().to_path_buf(), None).unwrap();
+            service.initialize_writer().unwrap();
+            service.add_documents(vec![chunk("c1", "pricing decision")]).unwrap();
+            service.commit().unwrap();
+            service.add_documents(vec![chunk("c2", "launch plan"), chunk("c3", "hiring")]).unwrap();
+            service.commit().unwrap();
+            service.delete_chunk("c2").unwrap();
+            service.commit().unwrap();
+            
+            let stats = service.stats().unwrap();
+            assert_eq!((stats.segment_count, stats.doc_count, stats.deleted_doc_count), (2, 2, 1));
+            let compacted = service.compact().unwrap();
+            assert_eq!((compacted.segment_count, compacted.doc_count, compacted.deleted_doc_count), (1, 2, 0));
+            assert!(!compacted.recovered);
+            service.close_writer().unwrap();
+        }
+        
+        // Damage every segment file
+        for entry in std::fs::read_dir(temp_dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            if !name.ends_with(".json") && !name.starts_with('.') && name != "vectors.hnsw" {
+                std::fs::write(&path, b"not a segment").unwrap();
+            }
+        }
+        let service = SearchService::new(temp_dir.path().to_path_buf(), None).unwrap();
+        assert!(service.recovered());
+        assert_eq!(service.stats().unwrap().doc_count, 0);
+        service.initialize_writer().unwrap();
+        service.add_documents(vec![chunk("c1", "pricing decision")]).unwrap();
+        service.commit().unwrap();
+        service.reader.reload().unwrap();
+        assert_eq!(service.search_bm25("pricing", 5).unwrap().len(), 1);
+    }
 }
\ No newline at end of file
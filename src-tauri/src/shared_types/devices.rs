@@ -0,0 +1,39 @@
+// src-tauri/src/shared_types/devices.rs
+// Audio devices as device enumeration reports them; `audio_loopback::types` re-exports these.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct AudioLoopbackDevice {
+    pub id: String,
+    // Stable across reboots and re-plugging (the Core Audio UID on macOS); aliases are keyed by it
+    #[serde(default)]
+    pub uid: String,
+    pub name: String,
+    #[serde(default)]
+    pub alias: Option<String>,
+    // Alias if one is set, otherwise the system name
+    #[serde(default)]
+    pub display_name: String,
+    pub is_default: bool,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub format: String,
+    pub device_type: DeviceType,
+    pub loopback_method: LoopbackMethod,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub enum DeviceType {
+    Render,
+    Capture,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub enum LoopbackMethod {
+    RenderLoopback,
+    CaptureDevice,
+    StereoMix,
+}
@@ -0,0 +1,43 @@
+// src-tauri/src/shared_types/documents.rs
+// Documents and chunks of the enhanced RAG store; `enhanced_rag_system` re-exports these.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct EnhancedDocument {
+    pub id: String,
+    pub file_name: String,
+    pub file_path: String,
+    pub file_type: String,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub file_size: i64,
+    pub content: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub access_count: i32,
+    pub last_accessed: Option<String>,
+    pub is_cached: bool,
+    pub embedding_status: String, // "pending", "processing", "completed", "failed", "cancelled"
+    pub chunk_count: i32,
+    pub metadata: Option<String>,
+    pub content_hash: Option<String>,
+    /// The collection the document is filed in; `None` for none
+    #[serde(default)]
+    pub collection_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct EnhancedDocumentChunk {
+    pub id: String,
+    pub document_id: String,
+    pub chunk_index: i32,
+    pub content: String,
+    pub start_char: i32,
+    pub end_char: i32,
+    pub token_count: i32,
+    pub embedding: Option<Vec<f32>>,
+    pub similarity_score: Option<f32>,
+    pub bm25_score: Option<f32>,
+    pub metadata: Option<String>,
+}
@@ -0,0 +1,264 @@
+// src-tauri/src/shared_types/messages.rs
+// Chat and conversation types - what chat and conversation storage keep, and what their commands
+// send and receive. `crate::data::types` re-exports all of them.
+
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// CHAT SESSION TYPES (Main Claude Chat)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ChatMessage {
+    pub id: i32,
+    pub text: String,
+    pub sender: String, // 'user' | 'assistant' | 'transcription' | 'system'
+    pub timestamp: String, // ISO 8601 string
+    pub is_interim: Option<bool>,
+    pub confidence: Option<f64>,
+    pub source: Option<String>,
+    pub attachments: Option<Vec<MessageAttachment>>,
+    pub thinking: Option<ThinkingProcess>,
+    #[serde(rename = "messageType")]
+    pub message_type: Option<String>,
+    pub metadata: Option<MessageMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct MessageAttachment {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub attachment_type: String,
+    pub name: String,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub size: i64,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub url: Option<String>,
+    #[serde(rename = "base64Data")]
+    pub base64_data: Option<String>,
+    pub thumbnail: Option<String>,
+    #[serde(rename = "extractedText")]
+    pub extracted_text: Option<String>,
+    pub dimensions: Option<FileDimensions>,
+    #[serde(rename = "uploadProgress")]
+    pub upload_progress: Option<i32>,
+    #[serde(rename = "uploadStatus")]
+    pub upload_status: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct FileDimensions {
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ThinkingProcess {
+    #[serde(rename = "isVisible")]
+    pub is_visible: bool,
+    pub content: String,
+    #[serde(rename = "isStreaming")]
+    pub is_streaming: bool,
+    pub steps: Option<Vec<ThinkingStep>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ThinkingStep {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub timestamp: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct MessageMetadata {
+    #[serde(rename = "agentType")]
+    pub agent_type: Option<String>,
+    pub model: Option<String>,
+    pub tokens: Option<i32>,
+    #[serde(rename = "processingTime")]
+    pub processing_time: Option<f64>,
+    #[serde(rename = "analysisType")]
+    pub analysis_type: Option<Vec<String>>,
+    #[serde(rename = "searchQueries")]
+    pub search_queries: Option<Vec<String>>,
+    pub sources: Option<Vec<String>>,
+    // Sampling seed the response was generated with, for reproducing it later
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    pub seed: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ChatSession {
+    pub id: String,
+    pub title: String,
+    pub history: Vec<ChatMessage>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    #[serde(rename = "modelId")]
+    pub model_id: Option<String>,
+}
+
+// Request/Response types for chat operations
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct SaveChatsPayload {
+    pub chats: Vec<ChatSession>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct LoadChatsResponse {
+    pub chats: Vec<ChatSession>,
+}
+
+// ============================================================================
+// CONVERSATION TYPES (Audio Conversations)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ConversationMessage {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub message_type: String, // 'user' | 'system'
+    pub source: String, // 'microphone' | 'loopback'
+    pub content: String,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub timestamp: i64,
+    pub confidence: Option<f64>,
+    // Additional fields for frontend compatibility
+    #[serde(rename = "isPreview", skip_serializing_if = "Option::is_none")]
+    pub is_preview: Option<bool>,
+    #[serde(rename = "isTyping", skip_serializing_if = "Option::is_none")]
+    pub is_typing: Option<bool>,
+    #[serde(rename = "persistenceState", skip_serializing_if = "Option::is_none")]
+    pub persistence_state: Option<String>,
+    #[serde(rename = "retryCount", skip_serializing_if = "Option::is_none")]
+    pub retry_count: Option<i32>,
+    #[serde(rename = "lastSaveAttempt", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    pub last_save_attempt: Option<i64>,
+    #[serde(rename = "saveError", skip_serializing_if = "Option::is_none")]
+    pub save_error: Option<String>,
+    /// Word timings from Whisper, for transcribed messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<TranscriptTimings>,
+}
+
+/// One recognised word; times are seconds from the start of the transcribed audio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct TranscriptWord {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct TranscriptSegment {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: f32,
+    #[serde(default)]
+    pub words: Vec<TranscriptWord>,
+}
+
+/// Segment and word timings kept with a message for highlighting and click-to-seek
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct TranscriptTimings {
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+    #[serde(rename = "languageProbability", default)]
+    pub language_probability: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ConversationInsight {
+    pub id: String,
+    pub text: String,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub timestamp: i64,
+    #[serde(rename = "contextLength")]
+    pub context_length: i32,
+    #[serde(rename = "type")]
+    pub insight_type: String, // 'insight' | 'welcome' | 'question' | 'answer'
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ConversationSession {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "startTime")]
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub start_time: i64,
+    #[serde(rename = "endTime")]
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    pub end_time: Option<i64>,
+    pub messages: Vec<ConversationMessage>,
+    #[serde(rename = "isActive")]
+    pub is_active: bool,
+    #[serde(default)]
+    pub insights: Vec<ConversationInsight>,
+    /// Spoken language codes ("en", "es"); empty means auto-detect
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+// Request/Response types for conversation operations
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct SaveConversationsPayload {
+    pub conversations: Vec<ConversationSession>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct LoadConversationsResponse {
+    pub conversations: Vec<ConversationSession>,
+}
+
+// Update structures for granular operations
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ConversationMessageUpdate {
+    pub content: Option<String>,
+    pub confidence: Option<f64>,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    pub timestamp: Option<i64>,
+    #[serde(default)]
+    pub timings: Option<TranscriptTimings>,
+}
+
+// ============================================================================
+// BACKUP AND UTILITY TYPES
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct BackupInfo {
+    pub filename: String,
+    pub backup_type: String,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub size: u64,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub modified: i64,
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+// src-tauri/src/shared_types/mod.rs
+// Serde types that cross the command boundary - messages, devices, documents and tool results -
+// kept in one place so the frontend's definitions are generated from them rather than kept in
+// sync by hand. The modules that use them re-export them, so their old paths still work.
+//
+// With the `ts-bindings` feature every type here derives `ts_rs::TS`, and
+// `cargo test --features ts-bindings export_bindings` writes one `.ts` file per type to
+// `src/types/generated` in the frontend (set by `TS_RS_EXPORT_DIR` in `.cargo/config.toml`).
+// 64-bit integers are exported as `number`; timestamps and sizes fit well within its range.
+pub mod messages;
+pub mod devices;
+#[cfg(feature = "enhanced-rag")]
+pub mod documents;
+#[cfg(feature = "mcp")]
+pub mod tools;
@@ -0,0 +1,14 @@
+// src-tauri/src/shared_types/tools.rs
+// The outcome of an MCP tool call; `mcp::types` re-exports it.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct ToolExecutionResult {
+    pub success: bool,
+    pub result: serde_json::Value,
+    pub error: Option<String>,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub execution_time_ms: u64,
+    pub tool_name: String,
+}
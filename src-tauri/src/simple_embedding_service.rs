@@ -1,11 +1,25 @@
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use std::path::PathBuf;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where embeddings come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderKind {
+    /// The built-in feature-hashing embeddings below
+    #[default]
+    Simple,
+    /// The local Ollama server, with `model_name` as the embedding model
+    Ollama,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EmbeddingConfig {
+    #[serde(default)]
+    pub provider: EmbeddingProviderKind,
     pub model_name: String,
     pub max_length: usize,
     pub normalize_embeddings: bool,
@@ -15,6 +29,7 @@ pub struct EmbeddingConfig {
 impl Default for EmbeddingConfig {
     fn default() -> Self {
         Self {
+            provider: EmbeddingProviderKind::Simple,
             model_name: "simple-text-embedding".to_string(),
             max_length: 512,
             normalize_embeddings: true,
@@ -23,6 +38,17 @@ impl Default for EmbeddingConfig {
     }
 }
 
+/// What the RAG system embeds chunks and queries with
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    async fn initialize(&self) -> Result<()>;
+    fn is_initialized(&self) -> bool;
+    async fn embed_documents(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>>;
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>>;
+    /// Identifies the vector space; embeddings from providers with different ids can't be compared
+    fn model_id(&self) -> String;
+}
+
 /// Simple embedding service that generates deterministic embeddings based on text features
 /// This is a placeholder that can be replaced with a real embedding model later
 #[derive(Clone)]
@@ -209,6 +235,30 @@ impl SimpleEmbeddingService {
     }
 }
 
+#[async_trait]
+impl EmbeddingProvider for SimpleEmbeddingService {
+    async fn initialize(&self) -> Result<()> {
+        SimpleEmbeddingService::initialize(self).await
+    }
+
+    fn is_initialized(&self) -> bool {
+        SimpleEmbeddingService::is_initialized(self)
+    }
+
+    async fn embed_documents(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        SimpleEmbeddingService::embed_documents(self, texts)
+    }
+
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        SimpleEmbeddingService::embed_query(self, query)
+    }
+
+    // Hashed features don't depend on the model name, only on how many buckets there are
+    fn model_id(&self) -> String {
+        format!("simple:{}", self.config.embedding_dimension)
+    }
+}
+
 // Utility functions
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
@@ -268,6 +318,12 @@ mod tests {
         let different_text = "Goodbye world";
         let different_embedding = service.generate_embedding(different_text).unwrap();
         assert_ne!(embedding, different_embedding);
+        
+        // Another dimension is another vector space
+        let config = EmbeddingConfig { embedding_dimension: 256, ..EmbeddingConfig::default() };
+        let smaller = SimpleEmbeddingService::new(temp_dir.path().to_path_buf(), Some(config));
+        assert_eq!(service.model_id(), "simple:384");
+        assert_ne!(smaller.model_id(), service.model_id());
     }
     
     #[test]
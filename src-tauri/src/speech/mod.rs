@@ -0,0 +1,495 @@
+use std::sync::{Arc, Mutex};
+
+// Whisper-rs imports for transcription
+use std::fs;
+use base64::{Engine as _, engine::general_purpose};
+use tempfile::NamedTempFile;
+use anyhow::Result;
+use whisper_rs::{WhisperContext, FullParams, SamplingStrategy};
+use crate::data::types::{TranscriptSegment, TranscriptWord};
+
+mod provider;
+pub use provider::{get_transcription_provider, set_transcription_provider};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AudioConfig {
+    pub sample_rate: u32,
+    pub chunk_size: usize,
+    pub silence_threshold: f32,
+    pub silence_duration: f32,
+    pub max_recording_duration: f32,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            chunk_size: 1024,
+            silence_threshold: 0.01,
+            silence_duration: 2.0,
+            max_recording_duration: 30.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpeechTranscription {
+    pub text: String,
+    pub confidence: f32,
+    pub duration: f32,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SpeechState {
+    pub is_listening: bool,
+    pub is_recording: bool,
+    pub last_transcription: Option<SpeechTranscription>,
+}
+
+impl Default for SpeechState {
+    fn default() -> Self {
+        Self {
+            is_listening: false,
+            is_recording: false,
+            last_transcription: None,
+        }
+    }
+}
+
+// Whisper-rs structures for transcription
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WhisperModelConfig {
+    pub modelSize: String,
+    pub language: Option<String>,
+    pub enableVad: bool,
+    pub silenceThreshold: f32,
+    pub maxSegmentLength: u32,
+    // "microphone" (the default for frontend recordings) or "loopback"
+    #[serde(default)]
+    pub source: Option<String>,
+    // Microphone device id used to pick the per-device DSP chain
+    #[serde(default)]
+    pub deviceId: Option<String>,
+    // Languages the conversation may use; overrides `language` and defaults to the active conversation's
+    #[serde(default)]
+    pub languages: Vec<String>,
+    // Id to cancel the transcription with through `cancel_operation`
+    #[serde(default)]
+    pub operationId: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TranscriptionResult {
+    pub text: String,
+    pub confidence: f32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub language: Option<String>,
+    /// Segments with word-level timestamps and confidence
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+    /// How sure language detection was, when it ran
+    #[serde(default)]
+    pub language_probability: Option<f32>,
+}
+
+// Global whisper context
+lazy_static::lazy_static! {
+    pub static ref WHISPER_CONTEXT: Arc<Mutex<Option<WhisperContext>>> = Arc::new(Mutex::new(None));
+}
+
+// Whisper-rs commands for frontend
+#[crate::command]
+pub async fn initialize_whisper_model(app_handle: tauri::AppHandle, config: WhisperModelConfig) -> Result<String, String> {
+    if crate::worker_process::is_isolated(crate::worker_process::WorkerKind::Transcription) {
+        // The worker loads the model itself; just make sure it's on disk
+        crate::whisper_models::ensure_model(Some(&app_handle), &config.modelSize).await?;
+        return Ok(format!("Whisper model '{}' is ready for the transcription worker", config.modelSize));
+    }
+    load_whisper_model(Some(&app_handle), &config.modelSize).await
+}
+
+async fn load_whisper_model(app_handle: Option<&tauri::AppHandle>, model_size: &str) -> Result<String, String> {
+    let model_path = crate::whisper_models::ensure_model(app_handle, model_size).await?;
+    
+    let (ctx, backend) = crate::whisper_backend::create_context(&model_path)?;
+    
+    let mut whisper_ctx = WHISPER_CONTEXT.lock().unwrap();
+    *whisper_ctx = Some(ctx);
+    crate::whisper_models::mark_loaded(model_size);
+    
+    Ok(format!("Whisper model '{}' initialized successfully on {:?}", model_size, backend))
+}
+
+/// Load the model for `config` unless one is loaded already. The app passes its handle for
+/// download progress; the transcription worker has none.
+pub(crate) async fn ensure_whisper_context(app_handle: Option<&tauri::AppHandle>, config: &WhisperModelConfig) -> Result<(), String> {
+    let needs_init = {
+        let whisper_ctx = WHISPER_CONTEXT.lock().unwrap();
+        whisper_ctx.is_none()
+    };
+    
+    if needs_init {
+        load_whisper_model(app_handle, &config.modelSize).await?;
+    }
+    Ok(())
+}
+
+#[crate::command]
+pub async fn transcribe_audio_base64(app_handle: tauri::AppHandle, audioData: String, config: WhisperModelConfig) -> Result<TranscriptionResult, String> {
+    // Decode base64 audio data
+    let audio_bytes = general_purpose::STANDARD
+        .decode(&audioData)
+        .map_err(|e| format!("Failed to decode base64 audio: {}", e))?;
+    
+    // Create temporary file for audio - using .pcm extension for raw PCM data
+    let temp_file = NamedTempFile::with_suffix(".pcm")
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    
+    fs::write(temp_file.path(), audio_bytes)
+        .map_err(|e| format!("Failed to write audio to temp file: {}", e))?;
+    
+    transcribe_audio_file(app_handle, temp_file.path().to_string_lossy().to_string(), config).await
+}
+
+#[crate::command]
+pub async fn transcribe_audio_file(app_handle: tauri::AppHandle, file_path: String, config: WhisperModelConfig) -> Result<TranscriptionResult, String> {
+    // Load and preprocess audio
+    let mut audio_data = load_audio_file(&file_path)?;
+    
+    // Loopback audio already went through its DSP stages while streaming
+    if config.source.as_deref().unwrap_or("microphone") == "microphone" {
+        crate::audio_loopback::dsp::process_microphone(config.deviceId.as_deref(), &mut audio_data);
+        crate::audio_loopback::session_recorder::push_microphone(&audio_data);
+    }
+    
+    let candidates = if config.languages.is_empty() {
+        crate::data::conversation::active_conversation_languages(&app_handle).await
+    } else {
+        crate::data::conversation::normalize_languages(config.languages.clone())?
+    };
+    
+    let operation = crate::cancellation::start(
+        config.operationId.clone().unwrap_or_else(|| format!("transcription:{}", uuid::Uuid::new_v4())),
+        crate::cancellation::OperationKind::Transcription,
+        config.source.clone().unwrap_or_else(|| "microphone".to_string()),
+    );
+    let provider = provider::active_provider();
+    let result = operation.run(provider.transcribe(&app_handle, &audio_data, &config, &candidates)).await?;
+    
+    // Whisper input is 16kHz mono
+    crate::data::conversation::usage::record_transcription(
+        &app_handle,
+        &provider.usage_model(&config),
+        audio_data.len() as u64 * 1000 / 16000,
+    );
+    
+    Ok(result)
+}
+
+/// Transcribe 16kHz mono audio with the local Whisper model, in the transcription worker
+/// when it's enabled
+pub(crate) async fn transcribe_with_whisper(
+    app_handle: &tauri::AppHandle,
+    audio_data: &[f32],
+    config: &WhisperModelConfig,
+    candidates: &[String],
+) -> Result<TranscriptionResult, String> {
+    if crate::worker_process::is_isolated(crate::worker_process::WorkerKind::Transcription) {
+        // Downloading here keeps progress events flowing to the UI
+        crate::whisper_models::ensure_model(Some(app_handle), &config.modelSize).await?;
+        return crate::worker_process::transcribe(audio_data, config, candidates).await;
+    }
+    
+    ensure_whisper_context(Some(app_handle), config).await?;
+    run_whisper(audio_data, config, candidates)
+}
+
+/// Run the loaded Whisper model over 16kHz mono audio
+pub(crate) fn run_whisper(
+    audio_data: &[f32],
+    config: &WhisperModelConfig,
+    candidates: &[String],
+) -> Result<TranscriptionResult, String> {
+    // Get Whisper context
+    let whisper_ctx = WHISPER_CONTEXT.lock().unwrap();
+    let ctx = whisper_ctx.as_ref().ok_or("Whisper context not initialized")?;
+    let mut state = ctx.create_state().map_err(|e| format!("Failed to create state: {}", e))?;
+    let (language, language_probability) =
+        choose_language(&mut state, audio_data, candidates, config.language.as_deref());
+    
+    // Set up transcription parameters - MATCHING PYTHON SCRIPT
+    // Python uses: beam_size=1, best_of=1, temperature=0.0
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    
+    // None auto-detects like Python
+    params.set_language(language.as_deref());
+    
+    // Match Python settings
+    params.set_translate(false);
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_suppress_blank(true);      // Python: suppress_blank=True
+    params.set_single_segment(false);     // Allow multiple segments
+    params.set_no_context(true);          // Python: condition_on_previous_text=False
+    params.set_temperature(0.0);          // Python: temperature=0.0
+    params.set_no_timestamps(true);       // Python: without_timestamps=True
+    params.set_token_timestamps(true);    // Word timings for highlighting and seeking
+    
+    // Inference blocks the task, so a cancel reaches it through whisper's abort callback
+    let cancellation = crate::cancellation::current();
+    if let Some(token) = cancellation.clone() {
+        params.set_abort_callback_safe(move || token.is_cancelled());
+    }
+    
+    // Run transcription
+    let transcribed = state.full(params, audio_data);
+    if let Some(token) = &cancellation {
+        token.check()?;
+    }
+    transcribed.map_err(|e| format!("Transcription failed: {}", e))?;
+    
+    // Extract results
+    let num_segments = state.full_n_segments()
+        .map_err(|e| format!("Failed to get segment count: {}", e))?;
+    
+    let mut full_text = String::new();
+    let mut start_time: f32 = f32::MAX;
+    let mut end_time: f32 = 0.0;
+    let mut segments = Vec::new();
+    let eot = ctx.token_eot();
+    
+    for i in 0..num_segments {
+        let segment_text = state.full_get_segment_text(i)
+            .map_err(|e| format!("Failed to get segment text: {}", e))?;
+        
+        let segment_start = state.full_get_segment_t0(i)
+            .map_err(|e| format!("Failed to get segment start time: {}", e))? as f32 / 100.0;
+        
+        let segment_end = state.full_get_segment_t1(i)
+            .map_err(|e| format!("Failed to get segment end time: {}", e))? as f32 / 100.0;
+        
+        // Token times are in centiseconds like segment times; special tokens sit at or above EOT
+        let mut tokens = Vec::new();
+        let n_tokens = state.full_n_tokens(i)
+            .map_err(|e| format!("Failed to get token count: {}", e))?;
+        for j in 0..n_tokens {
+            let Ok(data) = state.full_get_token_data(i, j) else { continue };
+            if data.id >= eot {
+                continue;
+            }
+            // Tokens that split a multi-byte character aren't valid UTF-8 on their own
+            let Ok(text) = state.full_get_token_text(i, j) else { continue };
+            tokens.push((text, data.t0 as f32 / 100.0, data.t1 as f32 / 100.0, data.p));
+        }
+        
+        full_text.push_str(&segment_text);
+        start_time = start_time.min(segment_start);
+        end_time = end_time.max(segment_end);
+        segments.push(TranscriptSegment {
+            text: segment_text.trim().to_string(),
+            start: segment_start,
+            end: segment_end,
+            confidence: mean_confidence(tokens.iter().map(|token| token.3)),
+            words: group_words(&tokens),
+        });
+    }
+    
+    let avg_confidence = mean_confidence(
+        segments.iter().flat_map(|segment| segment.words.iter().map(|word| word.confidence))
+    );
+    
+    Ok(TranscriptionResult {
+        text: full_text.trim().to_string(),
+        confidence: avg_confidence,
+        start_time,
+        end_time,
+        language,
+        segments,
+        language_probability,
+    })
+}
+
+fn mean_confidence(values: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), p| (sum + p, count + 1));
+    if count > 0 { sum / count as f32 } else { 0.0 }
+}
+
+// Characters of scripts written without spaces; each one is timed as its own word
+fn is_unspaced_char(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{0E00}'..='\u{0E7F}')
+}
+
+/// Join Whisper's sub-word tokens `(text, start, end, probability)` into words.
+/// A token starting with a space begins a new word; punctuation stays with the word before it.
+fn group_words(tokens: &[(String, f32, f32, f32)]) -> Vec<TranscriptWord> {
+    let mut words: Vec<TranscriptWord> = Vec::new();
+    let mut probabilities: Vec<Vec<f32>> = Vec::new();
+    
+    for (text, start, end, probability) in tokens {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let first = trimmed.chars().next().unwrap_or(' ');
+        let continues_word = !text.starts_with(char::is_whitespace)
+            && !is_unspaced_char(first)
+            && words.last().map_or(false, |word| !word.text.ends_with(is_unspaced_char));
+        let is_punctuation = trimmed.chars().all(|c| c.is_ascii_punctuation());
+        
+        match words.last_mut() {
+            Some(word) if continues_word || is_punctuation => {
+                word.text.push_str(trimmed);
+                word.end = word.end.max(*end);
+                if let Some(word_probabilities) = probabilities.last_mut() {
+                    word_probabilities.push(*probability);
+                }
+            }
+            _ => {
+                words.push(TranscriptWord {
+                    text: trimmed.to_string(),
+                    start: *start,
+                    end: *end,
+                    confidence: 0.0,
+                });
+                probabilities.push(vec![*probability]);
+            }
+        }
+    }
+    
+    for (word, word_probabilities) in words.iter_mut().zip(probabilities) {
+        word.confidence = mean_confidence(word_probabilities.into_iter());
+    }
+    words
+}
+
+/// Pick the Whisper language hint and, when detection ran, its probability. One declared
+/// language is forced; with several, detection is limited to them so a bilingual meeting
+/// can't drift into a third language.
+fn choose_language(
+    state: &mut whisper_rs::WhisperState,
+    audio: &[f32],
+    candidates: &[String],
+    fallback: Option<&str>,
+) -> (Option<String>, Option<f32>) {
+    match candidates {
+        [] => {
+            if let Some(lang) = fallback.filter(|lang| *lang != "auto" && !lang.is_empty()) {
+                return (Some(lang.to_string()), None);
+            }
+            // Detect up front rather than inside full() so the probability can be reported
+            match detect_language_probabilities(state, audio) {
+                Ok(probabilities) => probabilities
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .and_then(|(id, p)| Some((whisper_rs::get_lang_str(id as i32)?.to_string(), *p)))
+                    .map_or((None, None), |(code, p)| (Some(code), Some(p))),
+                Err(e) => {
+                    eprintln!("[WHISPER] Language detection failed, letting Whisper decide: {}", e);
+                    (None, None)
+                }
+            }
+        }
+        [only] => (Some(only.clone()), None),
+        [first, ..] => match detect_language_probabilities(state, audio) {
+            Ok(probabilities) => candidates
+                .iter()
+                .filter_map(|code| {
+                    let id = whisper_rs::get_lang_id(code)? as usize;
+                    Some((code, *probabilities.get(id)?))
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or((Some(first.clone()), None), |(code, p)| (Some(code.clone()), Some(p))),
+            Err(e) => {
+                eprintln!("[WHISPER] Language detection failed, using {}: {}", first, e);
+                (Some(first.clone()), None)
+            }
+        },
+    }
+}
+
+fn detect_language_probabilities(
+    state: &mut whisper_rs::WhisperState,
+    audio: &[f32],
+) -> Result<Vec<f32>, whisper_rs::WhisperError> {
+    state.pcm_to_mel(audio, 1)?;
+    state.lang_detect(0, 1)
+}
+
+#[crate::command]
+pub async fn check_whisper_model_availability(modelSize: String) -> Result<bool, String> {
+    let installed = crate::whisper_models::list_whisper_models()?
+        .into_iter()
+        .any(|model| model.id == modelSize && model.installed);
+    Ok(installed)
+}
+
+/// Download a model into the model manager, resuming a partial download and
+/// verifying its checksum. An already verified model is left as it is.
+#[crate::command]
+pub async fn download_whisper_model(app_handle: tauri::AppHandle, modelSize: String) -> Result<String, String> {
+    crate::whisper_models::ensure_model(Some(&app_handle), &modelSize).await?;
+    Ok(format!("Model '{}' downloaded successfully", modelSize))
+}
+
+#[crate::command]
+pub async fn list_available_models() -> Result<Vec<String>, String> {
+    Ok(crate::whisper_models::catalog_ids())
+}
+
+fn load_audio_file(file_path: &str) -> Result<Vec<f32>, String> {
+    let audio_bytes = fs::read(file_path)
+        .map_err(|e| format!("Failed to read audio file: {}", e))?;
+    
+    println!("[WHISPER] Loading audio file: {} bytes from {}", audio_bytes.len(), file_path);
+    
+    let mut audio_f32 = Vec::new();
+    for chunk in audio_bytes.chunks(2) {
+        if chunk.len() == 2 {
+            let sample = i16::from_le_bytes([chunk[0], chunk[1]]) as f32 / 32768.0;
+            audio_f32.push(sample);
+        }
+    }
+    
+    println!("[WHISPER] Converted to {} f32 samples", audio_f32.len());
+    
+    // Check if audio is silent
+    let rms = (audio_f32.iter().map(|&x| x * x).sum::<f32>() / audio_f32.len() as f32).sqrt();
+    println!("[WHISPER] Audio RMS: {:.6}", rms);
+    
+    Ok(audio_f32)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, start: f32, end: f32, p: f32) -> (String, f32, f32, f32) {
+        (text.to_string(), start, end, p)
+    }
+
+    #[test]
+    fn test_group_words_joins_subword_tokens() {
+        let tokens = vec![
+            token(" Hel", 0.0, 0.2, 0.8),
+            token("lo", 0.2, 0.4, 0.6),
+            token(",", 0.4, 0.4, 1.0),
+            token(" world", 0.5, 0.9, 0.9),
+        ];
+        let words = group_words(&tokens);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello,");
+        assert_eq!((words[0].start, words[0].end), (0.0, 0.4));
+        assert!((words[0].confidence - 0.8).abs() < 1e-6);
+        assert_eq!(words[1].text, "world");
+
+        // Unspaced scripts get one timed word per character token
+        let words = group_words(&[token("你", 0.0, 0.1, 0.9), token("好", 0.1, 0.2, 0.9)]);
+        assert_eq!(words.len(), 2);
+    }
+}
@@ -0,0 +1,494 @@
+// src-tauri/src/speech/provider.rs
+// Speech-to-text providers - local Whisper, or an HTTP API for machines too slow to run it
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::{TranscriptionResult, WhisperModelConfig};
+use crate::data::types::{TranscriptSegment, TranscriptWord};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+const OPENAI_DEFAULT_URL: &str = "https://api.openai.com/v1";
+const OPENAI_DEFAULT_MODEL: &str = "whisper-1";
+const DEEPGRAM_DEFAULT_URL: &str = "https://api.deepgram.com/v1";
+const DEEPGRAM_DEFAULT_MODEL: &str = "nova-2";
+const SAMPLE_RATE: u32 = 16000;
+
+lazy_static::lazy_static! {
+    static ref PROVIDER_SETTINGS: RwLock<TranscriptionProviderSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref HTTP_CLIENT: Arc<reqwest::Client> = Arc::new(
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .expect("Failed to create HTTP client")
+    );
+}
+
+/// Turns 16kHz mono audio into a transcription
+#[async_trait]
+pub trait TranscriptionProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Model name usage and cost are recorded under
+    fn usage_model(&self, config: &WhisperModelConfig) -> String;
+    async fn transcribe(
+        &self,
+        app_handle: &AppHandle,
+        audio: &[f32],
+        config: &WhisperModelConfig,
+        languages: &[String],
+    ) -> Result<TranscriptionResult, String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    #[default]
+    Local,
+    OpenAi,
+    Deepgram,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpProviderSettings {
+    /// Never returned to the frontend; `None` on save keeps the stored key
+    pub api_key: Option<String>,
+    #[serde(skip_deserializing)]
+    pub api_key_set: bool,
+    pub model: Option<String>,
+    /// For self-hosted servers speaking the same API
+    pub base_url: Option<String>,
+}
+
+impl HttpProviderSettings {
+    fn redacted(&self) -> Self {
+        Self {
+            api_key: None,
+            api_key_set: self.api_key.as_deref().map_or(false, |key| !key.is_empty()),
+            ..self.clone()
+        }
+    }
+
+    // An omitted key keeps the stored one, an empty key clears it
+    fn merge(&mut self, incoming: HttpProviderSettings) {
+        if let Some(key) = incoming.api_key {
+            self.api_key = Some(key.trim().to_string()).filter(|key| !key.is_empty());
+        }
+        self.model = incoming.model.filter(|model| !model.trim().is_empty());
+        self.base_url = incoming.base_url.filter(|url| !url.trim().is_empty());
+    }
+
+    fn validate(&self, label: &str) -> Result<(), String> {
+        if let Some(url) = &self.base_url {
+            let parsed = reqwest::Url::parse(url.trim())
+                .map_err(|e| format!("{} URL is invalid: {}", label, e))?;
+            // Compare the parsed host so "http://localhost.example.com" doesn't pass as local
+            let local = parsed.scheme() == "http"
+                && matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+            if parsed.scheme() != "https" && !local {
+                return Err(format!("{} URL must use https (or http to localhost)", label));
+            }
+        }
+        Ok(())
+    }
+
+    fn api_key(&self, label: &str) -> Result<&str, String> {
+        self.api_key
+            .as_deref()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| format!("No {} API key configured", label))
+    }
+
+    fn url(&self, default: &str) -> String {
+        self.base_url.as_deref().unwrap_or(default).trim_end_matches('/').to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranscriptionProviderSettings {
+    pub provider: ProviderKind,
+    pub openai: HttpProviderSettings,
+    pub deepgram: HttpProviderSettings,
+}
+
+const SETTINGS_FILE: &str = "transcription_provider.json";
+
+/// The provider selected in settings
+pub fn active_provider() -> Box<dyn TranscriptionProvider> {
+    let settings = read_settings(&PROVIDER_SETTINGS);
+    // Cloud providers fall back to local Whisper while offline; self-hosted ones on the LAN don't
+    let remote = match settings.provider {
+        ProviderKind::Local => None,
+        ProviderKind::OpenAi => Some(settings.openai.url(OPENAI_DEFAULT_URL)),
+        ProviderKind::Deepgram => Some(settings.deepgram.url(DEEPGRAM_DEFAULT_URL)),
+    };
+    if remote.map_or(false, |url| !crate::offline_mode::is_local_url(&url) && crate::offline_mode::is_offline()) {
+        return Box::new(LocalWhisperProvider);
+    }
+    match settings.provider {
+        ProviderKind::Local => Box::new(LocalWhisperProvider),
+        ProviderKind::OpenAi => Box::new(OpenAiProvider { settings: settings.openai }),
+        ProviderKind::Deepgram => Box::new(DeepgramProvider { settings: settings.deepgram }),
+    }
+}
+
+pub struct LocalWhisperProvider;
+
+#[async_trait]
+impl TranscriptionProvider for LocalWhisperProvider {
+    fn name(&self) -> &'static str { "local" }
+
+    fn usage_model(&self, config: &WhisperModelConfig) -> String {
+        format!("whisper-{}", config.modelSize)
+    }
+
+    async fn transcribe(
+        &self,
+        app_handle: &AppHandle,
+        audio: &[f32],
+        config: &WhisperModelConfig,
+        languages: &[String],
+    ) -> Result<TranscriptionResult, String> {
+        super::transcribe_with_whisper(app_handle, audio, config, languages).await
+    }
+}
+
+/// OpenAI's `/audio/transcriptions` endpoint, or any server compatible with it
+pub struct OpenAiProvider {
+    settings: HttpProviderSettings,
+}
+
+#[async_trait]
+impl TranscriptionProvider for OpenAiProvider {
+    fn name(&self) -> &'static str { "openai" }
+
+    fn usage_model(&self, _config: &WhisperModelConfig) -> String {
+        format!("openai-{}", self.settings.model.as_deref().unwrap_or(OPENAI_DEFAULT_MODEL))
+    }
+
+    async fn transcribe(
+        &self,
+        _app_handle: &AppHandle,
+        audio: &[f32],
+        config: &WhisperModelConfig,
+        languages: &[String],
+    ) -> Result<TranscriptionResult, String> {
+        let api_key = self.settings.api_key("OpenAI")?;
+        let file = reqwest::multipart::Part::bytes(encode_wav(audio)?)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| format!("Failed to build upload: {}", e))?;
+        let mut form = reqwest::multipart::Form::new()
+            .part("file", file)
+            .text("model", self.settings.model.clone().unwrap_or_else(|| OPENAI_DEFAULT_MODEL.to_string()))
+            .text("response_format", "verbose_json")
+            .text("timestamp_granularities[]", "word")
+            .text("timestamp_granularities[]", "segment");
+        // The API takes one language hint; with several, let it detect
+        if let Some(language) = language_hint(config, languages) {
+            form = form.text("language", language);
+        }
+
+        let response = HTTP_CLIENT
+            .post(format!("{}/audio/transcriptions", self.settings.url(OPENAI_DEFAULT_URL)))
+            .bearer_auth(api_key)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| format!("OpenAI transcription request failed: {}", e))?;
+        let body = read_json(response, "OpenAI").await?;
+        Ok(parse_openai_response(&body))
+    }
+}
+
+pub struct DeepgramProvider {
+    settings: HttpProviderSettings,
+}
+
+#[async_trait]
+impl TranscriptionProvider for DeepgramProvider {
+    fn name(&self) -> &'static str { "deepgram" }
+
+    fn usage_model(&self, _config: &WhisperModelConfig) -> String {
+        format!("deepgram-{}", self.settings.model.as_deref().unwrap_or(DEEPGRAM_DEFAULT_MODEL))
+    }
+
+    async fn transcribe(
+        &self,
+        _app_handle: &AppHandle,
+        audio: &[f32],
+        config: &WhisperModelConfig,
+        languages: &[String],
+    ) -> Result<TranscriptionResult, String> {
+        let api_key = self.settings.api_key("Deepgram")?;
+        let model = self.settings.model.as_deref().unwrap_or(DEEPGRAM_DEFAULT_MODEL);
+        let mut query = vec![
+            ("model", model.to_string()),
+            ("smart_format", "true".to_string()),
+            ("punctuate", "true".to_string()),
+        ];
+        match language_hint(config, languages) {
+            Some(language) => query.push(("language", language)),
+            None => query.push(("detect_language", "true".to_string())),
+        }
+
+        let response = HTTP_CLIENT
+            .post(format!("{}/listen", self.settings.url(DEEPGRAM_DEFAULT_URL)))
+            .header("Authorization", format!("Token {}", api_key))
+            .header("Content-Type", "audio/wav")
+            .query(&query)
+            .body(encode_wav(audio)?)
+            .send()
+            .await
+            .map_err(|e| format!("Deepgram transcription request failed: {}", e))?;
+        let body = read_json(response, "Deepgram").await?;
+        Ok(parse_deepgram_response(&body))
+    }
+}
+
+fn language_hint(config: &WhisperModelConfig, languages: &[String]) -> Option<String> {
+    match languages {
+        [only] => Some(only.clone()),
+        [] => config.language.clone().filter(|lang| lang != "auto" && !lang.is_empty()),
+        _ => None,
+    }
+}
+
+fn encode_wav(audio: &[f32]) -> Result<Vec<u8>, String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut bytes = Vec::new();
+    {
+        let mut writer = hound::WavWriter::new(std::io::Cursor::new(&mut bytes), spec)
+            .map_err(|e| format!("Failed to encode audio: {}", e))?;
+        for sample in audio {
+            writer
+                .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                .map_err(|e| format!("Failed to encode audio: {}", e))?;
+        }
+        writer.finalize().map_err(|e| format!("Failed to encode audio: {}", e))?;
+    }
+    Ok(bytes)
+}
+
+async fn read_json(response: reqwest::Response, label: &str) -> Result<Value, String> {
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {} response: {}", label, e))?;
+    if !status.is_success() {
+        return Err(format!("{} transcription failed ({}): {}", label, status, text));
+    }
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {} response: {}", label, e))
+}
+
+fn f32_field(value: &Value, key: &str) -> f32 {
+    value[key].as_f64().unwrap_or(0.0) as f32
+}
+
+fn result_from_segments(
+    text: String,
+    segments: Vec<TranscriptSegment>,
+    language: Option<String>,
+    language_probability: Option<f32>,
+) -> TranscriptionResult {
+    let words: Vec<&TranscriptWord> = segments.iter().flat_map(|segment| &segment.words).collect();
+    let confidence = if words.is_empty() {
+        super::mean_confidence(segments.iter().map(|segment| segment.confidence))
+    } else {
+        super::mean_confidence(words.iter().map(|word| word.confidence))
+    };
+    TranscriptionResult {
+        text: text.trim().to_string(),
+        confidence,
+        start_time: segments.first().map_or(0.0, |segment| segment.start),
+        end_time: segments.last().map_or(0.0, |segment| segment.end),
+        language,
+        segments,
+        language_probability,
+    }
+}
+
+// verbose_json lists words separately from segments and has no per-word confidence
+fn parse_openai_response(body: &Value) -> TranscriptionResult {
+    let words: Vec<TranscriptWord> = body["words"]
+        .as_array()
+        .map(|words| {
+            words
+                .iter()
+                .map(|word| TranscriptWord {
+                    text: word["word"].as_str().unwrap_or_default().trim().to_string(),
+                    start: f32_field(word, "start"),
+                    end: f32_field(word, "end"),
+                    confidence: 1.0,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut segments: Vec<TranscriptSegment> = body["segments"]
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .map(|segment| {
+                    let start = f32_field(segment, "start");
+                    let end = f32_field(segment, "end");
+                    let confidence = segment["avg_logprob"].as_f64().map_or(1.0, |p| p.exp() as f32);
+                    TranscriptSegment {
+                        text: segment["text"].as_str().unwrap_or_default().trim().to_string(),
+                        start,
+                        end,
+                        confidence,
+                        words: words
+                            .iter()
+                            .filter(|word| word.start >= start && word.start < end)
+                            .map(|word| TranscriptWord { confidence, ..word.clone() })
+                            .collect(),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let text = body["text"].as_str().unwrap_or_default().to_string();
+    if segments.is_empty() && !words.is_empty() {
+        segments.push(TranscriptSegment {
+            text: text.trim().to_string(),
+            start: words[0].start,
+            end: words[words.len() - 1].end,
+            confidence: 1.0,
+            words,
+        });
+    }
+
+    let language = body["language"]
+        .as_str()
+        .map(|name| crate::data::conversation::language_code(name).map(str::to_string).unwrap_or_else(|| name.to_string()));
+    result_from_segments(text, segments, language, None)
+}
+
+fn parse_deepgram_response(body: &Value) -> TranscriptionResult {
+    let channel = &body["results"]["channels"][0];
+    let alternative = &channel["alternatives"][0];
+    let words: Vec<TranscriptWord> = alternative["words"]
+        .as_array()
+        .map(|words| {
+            words
+                .iter()
+                .map(|word| TranscriptWord {
+                    text: word["punctuated_word"]
+                        .as_str()
+                        .or_else(|| word["word"].as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    start: f32_field(word, "start"),
+                    end: f32_field(word, "end"),
+                    confidence: f32_field(word, "confidence"),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let text = alternative["transcript"].as_str().unwrap_or_default().to_string();
+    let segments = if words.is_empty() {
+        Vec::new()
+    } else {
+        vec![TranscriptSegment {
+            text: text.clone(),
+            start: words[0].start,
+            end: words[words.len() - 1].end,
+            confidence: f32_field(alternative, "confidence"),
+            words,
+        }]
+    };
+
+    let language = channel["detected_language"].as_str().map(str::to_string);
+    let language_probability = channel["language_confidence"].as_f64().map(|p| p as f32);
+    result_from_segments(text, segments, language, language_probability)
+}
+
+#[crate::command]
+pub fn get_transcription_provider() -> Result<TranscriptionProviderSettings, String> {
+    let settings = read_settings(&PROVIDER_SETTINGS);
+    Ok(TranscriptionProviderSettings {
+        provider: settings.provider,
+        openai: settings.openai.redacted(),
+        deepgram: settings.deepgram.redacted(),
+    })
+}
+
+/// Pick the speech-to-text provider. API keys left out of the payload keep their stored value.
+#[crate::command]
+pub fn set_transcription_provider(settings: TranscriptionProviderSettings) -> Result<TranscriptionProviderSettings, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+
+    let mut updated = read_settings(&PROVIDER_SETTINGS);
+    updated.provider = settings.provider;
+    updated.openai.merge(settings.openai);
+    updated.deepgram.merge(settings.deepgram);
+    updated.openai.validate("OpenAI")?;
+    updated.deepgram.validate("Deepgram")?;
+    match updated.provider {
+        ProviderKind::Local => {}
+        ProviderKind::OpenAi => { updated.openai.api_key("OpenAI")?; }
+        ProviderKind::Deepgram => { updated.deepgram.api_key("Deepgram")?; }
+    }
+
+    save_json_settings(SETTINGS_FILE, "transcription provider settings", &updated)?;
+
+    *PROVIDER_SETTINGS
+        .write()
+        .map_err(|_| "Failed to update transcription provider settings".to_string())? = updated;
+    println!("[WHISPER] Transcription provider set to {}", active_provider().name());
+    get_transcription_provider()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deepgram_response() {
+        let body = serde_json::json!({
+            "results": {"channels": [{
+                "detected_language": "es",
+                "language_confidence": 0.93,
+                "alternatives": [{
+                    "transcript": "hola mundo",
+                    "confidence": 0.9,
+                    "words": [
+                        {"word": "hola", "punctuated_word": "Hola", "start": 0.1, "end": 0.4, "confidence": 0.8},
+                        {"word": "mundo", "start": 0.5, "end": 0.9, "confidence": 1.0}
+                    ]
+                }]
+            }]}
+        });
+        let result = parse_deepgram_response(&body);
+        assert_eq!(result.text, "hola mundo");
+        assert_eq!(result.language.as_deref(), Some("es"));
+        assert_eq!(result.segments[0].words[0].text, "Hola");
+        assert!((result.confidence - 0.9).abs() < 1e-6);
+        assert_eq!((result.start_time, result.end_time), (0.1, 0.9));
+    }
+
+    #[test]
+    fn test_plain_http_is_only_allowed_to_loopback_hosts() {
+        let settings = |url: &str| HttpProviderSettings { base_url: Some(url.to_string()), ..Default::default() };
+        for url in ["https://api.example.com", "http://localhost:8080", "http://127.0.0.1/v1", "http://[::1]:9000"] {
+            assert!(settings(url).validate("Test").is_ok(), "{}", url);
+        }
+        for url in ["http://localhost.evil.com", "http://127.0.0.1.evil.com", "http://localhost@evil.com", "not a url"] {
+            assert!(settings(url).validate("Test").is_err(), "{}", url);
+        }
+    }
+}
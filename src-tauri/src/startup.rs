@@ -0,0 +1,209 @@
+// Staged startup - what runs before the window shows, what waits, and how long each took.
+//
+// `setup_step`s run inside Tauri's setup hook and hold up the window, so only cheap work
+// (loading settings, storing the app handle) belongs there. `defer`red steps run in the
+// background once setup has returned, and `lazy_step`s the first time a command needs them.
+// Each step is announced with a `startup-phase` event and listed by `get_startup_report`.
+use serde::Serialize;
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StartupPhase {
+    /// In the setup hook, before the window shows
+    Setup,
+    /// In the background right after setup
+    Deferred,
+    /// On first use
+    Lazy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupStep {
+    pub name: String,
+    pub phase: StartupPhase,
+    /// Milliseconds after launch
+    pub started_ms: u64,
+    /// `None` while the step is still running
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    /// Launch to the end of the setup hook, roughly when the window can show
+    pub setup_ms: Option<u64>,
+    /// Launch to the last deferred step finishing
+    pub deferred_ms: Option<u64>,
+    pub steps: Vec<StartupStep>,
+}
+
+type DeferredStep = (&'static str, Pin<Box<dyn Future<Output = Option<String>> + Send>>);
+
+lazy_static::lazy_static! {
+    static ref LAUNCHED_AT: Instant = Instant::now();
+    static ref STEPS: Mutex<Vec<StartupStep>> = Mutex::new(Vec::new());
+    static ref MILESTONES: Mutex<(Option<u64>, Option<u64>)> = Mutex::new((None, None));
+    static ref DEFERRED: Mutex<Vec<DeferredStep>> = Mutex::new(Vec::new());
+    static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+}
+
+/// What a step returns; errors are kept in the report
+pub trait StepOutcome {
+    fn error(&self) -> Option<String>;
+}
+
+impl StepOutcome for () {
+    fn error(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<T, E: Display> StepOutcome for Result<T, E> {
+    fn error(&self) -> Option<String> {
+        self.as_ref().err().map(|e| e.to_string())
+    }
+}
+
+fn elapsed_ms() -> u64 {
+    LAUNCHED_AT.elapsed().as_millis() as u64
+}
+
+fn emit(event: &str, payload: serde_json::Value) {
+    let app_handle = APP_HANDLE.lock().ok().and_then(|handle| handle.clone());
+    if let Some(app_handle) = app_handle {
+        let _ = app_handle.emit(event, payload);
+    }
+}
+
+// Record a step as started; returns its index for `finish_step`
+fn start_step(name: &str, phase: StartupPhase) -> Option<usize> {
+    let mut steps = STEPS.lock().ok()?;
+    steps.push(StartupStep {
+        name: name.to_string(),
+        phase,
+        started_ms: elapsed_ms(),
+        duration_ms: None,
+        error: None,
+    });
+    Some(steps.len() - 1)
+}
+
+fn finish_step(index: Option<usize>, started: Instant, error: Option<String>) {
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let step = index.and_then(|index| {
+        let mut steps = STEPS.lock().ok()?;
+        let step = steps.get_mut(index)?;
+        step.duration_ms = Some(duration_ms);
+        step.error = error;
+        Some(step.clone())
+    });
+    if let Some(step) = step {
+        match &step.error {
+            Some(e) => eprintln!("[STARTUP] {} failed after {}ms: {}", step.name, duration_ms, e),
+            None => println!("[STARTUP] {} took {}ms", step.name, duration_ms),
+        }
+        emit("startup-phase", serde_json::json!({ "type": "step", "step": step }));
+    }
+}
+
+/// Mark the launch time; call first thing so step offsets are from launch
+pub fn begin() {
+    lazy_static::initialize(&LAUNCHED_AT);
+}
+
+/// Run a step inside the setup hook, timing it
+pub fn setup_step<T: StepOutcome>(app_handle: &AppHandle, name: &str, step: impl FnOnce() -> T) -> T {
+    if let Ok(mut handle) = APP_HANDLE.lock() {
+        handle.get_or_insert_with(|| app_handle.clone());
+    }
+    let index = start_step(name, StartupPhase::Setup);
+    let started = Instant::now();
+    let outcome = step();
+    finish_step(index, started, outcome.error());
+    outcome
+}
+
+/// Queue a step to run in the background once setup is done
+pub fn defer<T: StepOutcome>(name: &'static str, step: impl Future<Output = T> + Send + 'static) {
+    if let Ok(mut deferred) = DEFERRED.lock() {
+        deferred.push((name, Box::pin(async move { step.await.error() })));
+    }
+}
+
+/// Initialize something on first use, timing it as a lazy step. Call it only when the
+/// subsystem isn't set up yet.
+pub fn lazy_step<T: StepOutcome>(name: &str, step: impl FnOnce() -> T) -> T {
+    let index = start_step(name, StartupPhase::Lazy);
+    let started = Instant::now();
+    let outcome = step();
+    finish_step(index, started, outcome.error());
+    outcome
+}
+
+/// End of the setup hook: record how long it took and start the deferred steps
+pub fn finish_setup(app_handle: &AppHandle) {
+    let setup_ms = elapsed_ms();
+    if let Ok(mut milestones) = MILESTONES.lock() {
+        milestones.0 = Some(setup_ms);
+    }
+    println!("[STARTUP] Setup finished after {}ms", setup_ms);
+    let _ = app_handle.emit("startup-phase", serde_json::json!({ "type": "setupComplete", "elapsedMs": setup_ms }));
+
+    let deferred = DEFERRED.lock().map(|mut deferred| std::mem::take(&mut *deferred)).unwrap_or_default();
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let runs = deferred.into_iter().map(|(name, step)| async move {
+            let index = start_step(name, StartupPhase::Deferred);
+            let started = Instant::now();
+            let error = step.await;
+            finish_step(index, started, error);
+        });
+        futures_util::future::join_all(runs).await;
+
+        let deferred_ms = elapsed_ms();
+        if let Ok(mut milestones) = MILESTONES.lock() {
+            milestones.1 = Some(deferred_ms);
+        }
+        println!("[STARTUP] Deferred initialization finished after {}ms", deferred_ms);
+        let _ = app_handle.emit("startup-phase", serde_json::json!({ "type": "deferredComplete", "elapsedMs": deferred_ms }));
+    });
+}
+
+/// Every startup step so far, in the order they started, with their timings
+#[crate::command]
+pub fn get_startup_report() -> Result<StartupReport, String> {
+    let (setup_ms, deferred_ms) = *MILESTONES.lock().map_err(|_| "Failed to read startup report".to_string())?;
+    let mut steps = STEPS.lock().map_err(|_| "Failed to read startup report".to_string())?.clone();
+    steps.sort_by_key(|step| step.started_ms);
+    Ok(StartupReport { setup_ms, deferred_ms, steps })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_steps_are_timed_with_their_errors() {
+        begin();
+        let value = lazy_step("test_ok", || Ok::<_, String>(7));
+        assert_eq!(value, Ok(7));
+        let failed: Result<(), String> = lazy_step("test_failed", || Err("no disk".to_string()));
+        assert!(failed.is_err());
+
+        let report = get_startup_report().unwrap();
+        let ok = report.steps.iter().find(|step| step.name == "test_ok").unwrap();
+        assert_eq!(ok.phase, StartupPhase::Lazy);
+        assert!(ok.duration_ms.is_some());
+        assert_eq!(ok.error, None);
+        let failed = report.steps.iter().find(|step| step.name == "test_failed").unwrap();
+        assert_eq!(failed.error.as_deref(), Some("no disk"));
+    }
+}
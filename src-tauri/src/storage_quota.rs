@@ -0,0 +1,136 @@
+// src-tauri/src/storage_quota.rs
+// Storage limits for the enhanced RAG store - the collection size quota uploads are checked
+// against, and least-recently-used eviction of embeddings beyond `max_cached_documents`.
+//
+// Documents are ranked by when search last used them (upload time for ones never searched),
+// then by how often; the least recently used go first. Evicting a document drops its chunk
+// embeddings and vectors but leaves its text in the BM25 index, so it stays searchable by
+// keyword and is re-embedded when it's next selected for search. With archiving on, its
+// original file is also compressed into a zip beside it.
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// A document as ranked for eviction
+#[derive(Debug, Clone)]
+pub struct CachedDocument {
+    pub id: String,
+    pub last_accessed: Option<String>,
+    pub created_at: String,
+    pub access_count: i64,
+}
+
+/// Sort least recently used first
+pub fn rank_by_use(documents: &mut [CachedDocument]) {
+    // RFC 3339 timestamps in UTC sort by time as text
+    documents.sort_by(|a, b| {
+        let used = |doc: &CachedDocument| doc.last_accessed.clone().unwrap_or_else(|| doc.created_at.clone());
+        used(a).cmp(&used(b)).then(a.access_count.cmp(&b.access_count))
+    });
+}
+
+/// The documents to evict so that at most `limit` keep their embeddings, least recently used first
+pub fn eviction_order(mut documents: Vec<CachedDocument>, limit: usize) -> Vec<CachedDocument> {
+    if documents.len() <= limit {
+        return Vec::new();
+    }
+    rank_by_use(&mut documents);
+    let excess = documents.len() - limit;
+    documents.truncate(excess);
+    documents
+}
+
+pub fn quota_bytes(max_collection_size_gb: f64) -> u64 {
+    (max_collection_size_gb.max(0.0) * BYTES_PER_GB) as u64
+}
+
+/// Bytes taken by the files under `path`
+pub fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Where the archived copy of an original file goes
+pub fn archive_path(file_path: &Path) -> PathBuf {
+    let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".zip");
+    file_path.with_file_name(name)
+}
+
+/// Compress `file_path` into its archive and remove it; returns the bytes saved
+pub fn archive_file(file_path: &Path) -> Result<u64> {
+    let content = fs::read(file_path)?;
+    let name = file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let archive = archive_path(file_path);
+    let mut writer = zip::ZipWriter::new(fs::File::create(&archive)?);
+    writer.start_file(name, zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated))?;
+    writer.write_all(&content)?;
+    writer.finish()?;
+    let archived = fs::metadata(&archive)?.len();
+    fs::remove_file(file_path)?;
+    Ok((content.len() as u64).saturating_sub(archived))
+}
+
+/// Sent as `enhanced-rag-documents-evicted` when documents past `max_cached_documents` lose
+/// their embeddings, or have their originals archived to make room for an upload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvictionEvent {
+    pub document_ids: Vec<String>,
+    /// "cache_limit" when embeddings were unloaded, "quota" when only originals were archived
+    pub reason: &'static str,
+    /// Original files compressed into archives
+    pub archived_files: usize,
+}
+
+/// Sent as `enhanced-rag-storage-quota` when an upload doesn't fit in the quota
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaEvent {
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+    pub requested_bytes: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(id: &str, last_accessed: Option<&str>, created_at: &str, access_count: i64) -> CachedDocument {
+        CachedDocument {
+            id: id.to_string(),
+            last_accessed: last_accessed.map(str::to_string),
+            created_at: created_at.to_string(),
+            access_count,
+        }
+    }
+
+    #[test]
+    fn test_eviction_order_is_least_recently_used_first() {
+        let documents = vec![
+            document("recent", Some("2026-10-14T09:00:00+00:00"), "2026-01-01T00:00:00+00:00", 1),
+            document("stale", Some("2026-03-01T00:00:00+00:00"), "2026-01-01T00:00:00+00:00", 40),
+            // Never searched: ranked by upload time
+            document("unused", None, "2026-02-01T00:00:00+00:00", 0),
+            document("tied-popular", Some("2026-05-01T00:00:00+00:00"), "2026-01-01T00:00:00+00:00", 9),
+            document("tied-rare", Some("2026-05-01T00:00:00+00:00"), "2026-01-01T00:00:00+00:00", 2),
+        ];
+        let ids = |evicted: Vec<CachedDocument>| evicted.into_iter().map(|doc| doc.id).collect::<Vec<_>>();
+        assert_eq!(ids(eviction_order(documents.clone(), 2)), ["unused", "stale", "tied-rare"]);
+        assert!(eviction_order(documents, 5).is_empty());
+
+        assert_eq!(archive_path(Path::new("/store/doc/report.pdf")), Path::new("/store/doc/report.pdf.zip"));
+        assert_eq!(quota_bytes(2.0), 2 * 1024 * 1024 * 1024);
+    }
+}
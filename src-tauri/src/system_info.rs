@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::process::Command;
+use crate::action_registry::{descriptor, to_value, ActionKind, RegisteredAction};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpuInfo {
@@ -156,7 +157,91 @@ fn get_nvidia_info() -> Result<Vec<GpuInfo>, String> {
     Ok(gpus)
 }
 
-#[tauri::command]
+/// Free VRAM in MB across NVIDIA GPUs right now; `None` without nvidia-smi
+pub fn free_vram_mb() -> Option<u64> {
+    let output = Command::new("nvidia-smi")
+        .args(&["--query-gpu=memory.free", "--format=csv,noheader,nounits"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let free: Vec<u64> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().parse::<u64>().ok())
+        .collect();
+    (!free.is_empty()).then(|| free.iter().sum())
+}
+
+// Detect GPU and determine optimal layer count for GPU acceleration
+pub fn detect_gpu_layers() -> i32 {
+    // Try to get GPU info
+    match get_gpu_info() {
+        Ok(gpus) => {
+            for gpu in gpus {
+                // Check for NVIDIA GPUs (best Ollama support)
+                if gpu.vendor == "NVIDIA" {
+                    if let Some(memory_mb) = gpu.memory_mb {
+                        println!("🎮 Detected NVIDIA GPU: {} with {}MB VRAM", gpu.name, memory_mb);
+                        
+                        // Calculate layers based on VRAM
+                        // Conservative estimates to prevent OOM
+                        let layers = if memory_mb >= 24000 {
+                            99  // Full GPU offload for 24GB+ cards (RTX 4090, A5000)
+                        } else if memory_mb >= 16000 {
+                            80  // RTX 4080, A4000
+                        } else if memory_mb >= 12000 {
+                            60  // RTX 4070 Ti, RTX 3080 Ti
+                        } else if memory_mb >= 10000 {
+                            50  // RTX 3080, RTX 4070
+                        } else if memory_mb >= 8000 {
+                            40  // RTX 3070, RTX 4060 Ti
+                        } else if memory_mb >= 6000 {
+                            30  // RTX 3060, RTX 4060
+                        } else if memory_mb >= 4000 {
+                            20  // GTX 1650, older cards
+                        } else {
+                            0   // Too little VRAM, use CPU
+                        };
+                        
+                        println!("🚀 GPU acceleration enabled with {} layers", layers);
+                        return layers;
+                    }
+                }
+                
+                // AMD GPUs (experimental Ollama support)
+                if gpu.vendor == "AMD" && gpu.name.contains("Radeon") {
+                    if let Some(memory_mb) = gpu.memory_mb {
+                        println!("🎮 Detected AMD GPU: {} with {}MB VRAM", gpu.name, memory_mb);
+                        
+                        // Conservative for AMD due to less mature support
+                        let layers = if memory_mb >= 16000 {
+                            40
+                        } else if memory_mb >= 8000 {
+                            20
+                        } else {
+                            0
+                        };
+                        
+                        if layers > 0 {
+                            println!("⚠️ AMD GPU support is experimental, using {} layers", layers);
+                        }
+                        return layers;
+                    }
+                }
+            }
+            
+            println!("⚠️ No supported GPU found for acceleration, using CPU");
+            0
+        }
+        Err(e) => {
+            println!("⚠️ Could not detect GPU: {}, using CPU", e);
+            0
+        }
+    }
+}
+
+#[crate::command]
 pub fn get_system_info() -> Result<SystemInfo, String> {
     let gpus = get_gpu_info().unwrap_or_else(|_| vec![]);
     
@@ -208,4 +293,37 @@ pub fn get_system_info() -> Result<SystemInfo, String> {
         memory_gb,
         os,
     })
-}
\ No newline at end of file
+}
+
+/// Optional subsystems compiled into this build, so the UI can hide features that aren't there
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildCapabilities {
+    pub version: String,
+    pub eye_tracking: bool,
+    pub mcp: bool,
+    pub enhanced_rag: bool,
+    pub wake_word: bool,
+    pub whisper_backends: Vec<crate::whisper_backend::WhisperBackend>,
+}
+
+#[crate::command]
+pub fn get_build_capabilities() -> BuildCapabilities {
+    BuildCapabilities {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        eye_tracking: cfg!(feature = "eye-tracking"),
+        mcp: cfg!(feature = "mcp"),
+        enhanced_rag: cfg!(feature = "enhanced-rag"),
+        wake_word: cfg!(feature = "wake-word"),
+        whisper_backends: crate::whisper_backend::available_backends(),
+    }
+}
+
+/// Command palette actions for hardware details
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    vec![RegisteredAction {
+        descriptor: descriptor("system.info", ActionKind::Command, "System Information",
+            "Show CPU, memory and GPU details", vec![], &["hardware", "gpu"]),
+        handler: |_, _| Box::pin(async move { to_value(get_system_info()?) }),
+    }]
+}
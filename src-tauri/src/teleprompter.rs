@@ -0,0 +1,346 @@
+// Gaze-following teleprompter - scrolls a prepared script as the reader's eyes move down it
+//
+// The script is wrapped into lines here, so the overlay renders exactly the lines the scroll
+// position counts in. Gaze samples inside the overlay's screen rectangle mark the line being
+// read; once the eyes have stayed in the lower part of the overlay for `dwell_ms`, the script
+// scrolls so that line sits at the anchor near the top. Looking away holds the position.
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+const DEFAULT_CHARS_PER_LINE: usize = 42;
+// Weight of each new sample in the smoothed gaze height
+const GAZE_SMOOTHING: f64 = 0.3;
+
+lazy_static::lazy_static! {
+    static ref TELEPROMPTER: Mutex<Option<Teleprompter>> = Mutex::new(None);
+    static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+}
+
+/// Where the overlay shows the script, in the same screen coordinates as gaze samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TeleprompterLayout {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub line_height: f64,
+    /// Fraction of the height where the line being read is scrolled to
+    pub anchor: f64,
+    /// Fraction of the height below which reading advances the script
+    pub advance_zone: f64,
+    pub dwell_ms: u64,
+    pub min_confidence: f32,
+}
+
+impl Default for TeleprompterLayout {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 800.0,
+            height: 300.0,
+            line_height: 40.0,
+            anchor: 0.25,
+            advance_zone: 0.6,
+            dwell_ms: 400,
+            min_confidence: 0.4,
+        }
+    }
+}
+
+impl TeleprompterLayout {
+    fn validate(&self) -> Result<(), String> {
+        if self.width <= 0.0 || self.height <= 0.0 || self.line_height <= 0.0 || self.line_height > self.height {
+            return Err("The teleprompter needs a positive size and a line height no taller than it".to_string());
+        }
+        if !(0.0..1.0).contains(&self.anchor) || !(0.0..1.0).contains(&self.advance_zone) || self.anchor >= self.advance_zone {
+            return Err("The anchor must sit above the advance zone, both fractions of the height".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err("Minimum gaze confidence must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+
+    fn visible_lines(&self) -> usize {
+        ((self.height / self.line_height) as usize).max(1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeleprompterState {
+    /// First line shown at the top of the overlay
+    pub position: usize,
+    /// Line the reader is looking at
+    pub current_line: usize,
+    pub total_lines: usize,
+    /// Share of the script read, 0-1
+    pub progress: f32,
+    pub paused: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeleprompterSession {
+    pub lines: Vec<String>,
+    pub layout: TeleprompterLayout,
+    pub state: TeleprompterState,
+}
+
+#[derive(Debug)]
+struct Teleprompter {
+    lines: Vec<String>,
+    layout: TeleprompterLayout,
+    position: usize,
+    current_line: usize,
+    paused: bool,
+    smoothed_y: Option<f64>,
+    in_advance_zone_since: Option<u64>,
+}
+
+impl Teleprompter {
+    fn new(lines: Vec<String>, layout: TeleprompterLayout) -> Self {
+        Self { lines, layout, position: 0, current_line: 0, paused: false, smoothed_y: None, in_advance_zone_since: None }
+    }
+
+    fn last_position(&self) -> usize {
+        self.lines.len().saturating_sub(self.layout.visible_lines())
+    }
+
+    fn state(&self) -> TeleprompterState {
+        TeleprompterState {
+            position: self.position,
+            current_line: self.current_line,
+            total_lines: self.lines.len(),
+            progress: if self.lines.len() > 1 { self.current_line as f32 / (self.lines.len() - 1) as f32 } else { 1.0 },
+            paused: self.paused,
+        }
+    }
+
+    fn scroll_to(&mut self, position: usize) {
+        self.position = position.min(self.last_position());
+        // The text just moved under the reader's eyes
+        self.smoothed_y = None;
+        self.in_advance_zone_since = None;
+    }
+
+    /// Take a gaze sample; the new state when the position or the line being read changed
+    fn observe(&mut self, x: f64, y: f64, confidence: f32, now_ms: u64) -> Option<TeleprompterState> {
+        let layout = &self.layout;
+        if self.paused || self.lines.is_empty() || confidence < layout.min_confidence {
+            return None;
+        }
+        let inside = (layout.x..=layout.x + layout.width).contains(&x) && (layout.y..=layout.y + layout.height).contains(&y);
+        if !inside {
+            self.in_advance_zone_since = None;
+            return None;
+        }
+
+        let smoothed = self.smoothed_y.map_or(y, |previous| previous + GAZE_SMOOTHING * (y - previous));
+        self.smoothed_y = Some(smoothed);
+        let offset = smoothed - layout.y;
+        let row = ((offset / layout.line_height) as usize).min(layout.visible_lines() - 1);
+        let reading = (self.position + row).min(self.lines.len() - 1);
+        let before = (self.position, self.current_line);
+        self.current_line = reading;
+
+        if offset / layout.height >= layout.advance_zone {
+            let since = *self.in_advance_zone_since.get_or_insert(now_ms);
+            if now_ms.saturating_sub(since) >= layout.dwell_ms {
+                let anchor_row = (layout.anchor * layout.visible_lines() as f64) as usize;
+                self.scroll_to(reading.saturating_sub(anchor_row));
+            }
+        } else {
+            self.in_advance_zone_since = None;
+        }
+
+        ((self.position, self.current_line) != before).then(|| self.state())
+    }
+}
+
+/// Word-wrap `script` into lines of at most `chars_per_line` characters, keeping its line breaks
+fn wrap_script(script: &str, chars_per_line: usize) -> Vec<String> {
+    let width = chars_per_line.max(1);
+    let mut lines = Vec::new();
+    for paragraph in script.trim().lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            // Words longer than a line are broken up
+            while word.len() > width {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..width).collect());
+            }
+            let word: String = word.into_iter().collect();
+            if word.is_empty() {
+                continue;
+            }
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        // Blank lines between paragraphs stay as pauses
+        lines.push(line);
+    }
+    lines
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn emit_state(state: &TeleprompterState) {
+    if let Some(app_handle) = APP_HANDLE.lock().ok().and_then(|handle| handle.clone()) {
+        let _ = app_handle.emit("teleprompter-scroll", state);
+    }
+}
+
+/// Feed a gaze sample from the eye tracker; does nothing unless a teleprompter is running
+pub fn observe_gaze(x: f64, y: f64, confidence: f32) {
+    let changed = {
+        let Ok(mut teleprompter) = TELEPROMPTER.lock() else { return };
+        let Some(teleprompter) = teleprompter.as_mut() else { return };
+        teleprompter.observe(x, y, confidence, now_ms())
+    };
+    if let Some(state) = changed {
+        emit_state(&state);
+    }
+}
+
+fn session(teleprompter: &Teleprompter) -> TeleprompterSession {
+    TeleprompterSession {
+        lines: teleprompter.lines.clone(),
+        layout: teleprompter.layout.clone(),
+        state: teleprompter.state(),
+    }
+}
+
+// Apply `change` to the running teleprompter and announce the result
+fn update(change: impl FnOnce(&mut Teleprompter)) -> Result<TeleprompterState, String> {
+    let state = {
+        let mut teleprompter = TELEPROMPTER.lock().map_err(|_| "Failed to access the teleprompter".to_string())?;
+        let teleprompter = teleprompter.as_mut().ok_or("The teleprompter isn't running")?;
+        change(teleprompter);
+        teleprompter.state()
+    };
+    emit_state(&state);
+    Ok(state)
+}
+
+/// Start following `script` with the reader's gaze. Eye tracking has to be running for the
+/// script to advance on its own; it can always be moved with `set_teleprompter_position`.
+#[crate::command]
+pub fn start_teleprompter(
+    app_handle: AppHandle,
+    script: String,
+    chars_per_line: Option<usize>,
+    layout: Option<TeleprompterLayout>,
+) -> Result<TeleprompterSession, String> {
+    let layout = layout.unwrap_or_default();
+    layout.validate()?;
+    let lines = wrap_script(&script, chars_per_line.unwrap_or(DEFAULT_CHARS_PER_LINE));
+    if lines.iter().all(|line| line.is_empty()) {
+        return Err("The script is empty".to_string());
+    }
+    if let Ok(mut handle) = APP_HANDLE.lock() {
+        *handle = Some(app_handle);
+    }
+
+    let teleprompter = Teleprompter::new(lines, layout);
+    let started = session(&teleprompter);
+    *TELEPROMPTER.lock().map_err(|_| "Failed to access the teleprompter".to_string())? = Some(teleprompter);
+    println!("📜 Teleprompter started with {} lines", started.lines.len());
+    Ok(started)
+}
+
+#[crate::command]
+pub fn stop_teleprompter() -> Result<(), String> {
+    TELEPROMPTER.lock().map_err(|_| "Failed to access the teleprompter".to_string())?.take();
+    Ok(())
+}
+
+#[crate::command]
+pub fn get_teleprompter() -> Result<Option<TeleprompterSession>, String> {
+    let teleprompter = TELEPROMPTER.lock().map_err(|_| "Failed to access the teleprompter".to_string())?;
+    Ok(teleprompter.as_ref().map(session))
+}
+
+/// Scroll by hand, e.g. to jump back a paragraph; `line` becomes the top line
+#[crate::command]
+pub fn set_teleprompter_position(line: usize) -> Result<TeleprompterState, String> {
+    update(|teleprompter| {
+        teleprompter.scroll_to(line);
+        teleprompter.current_line = teleprompter.position;
+    })
+}
+
+/// Hold the script where it is while the reader ad-libs
+#[crate::command]
+pub fn set_teleprompter_paused(paused: bool) -> Result<TeleprompterState, String> {
+    update(|teleprompter| {
+        teleprompter.paused = paused;
+        teleprompter.in_advance_zone_since = None;
+    })
+}
+
+/// Change where the overlay sits, after it is moved or resized
+#[crate::command]
+pub fn set_teleprompter_layout(layout: TeleprompterLayout) -> Result<TeleprompterState, String> {
+    layout.validate()?;
+    update(|teleprompter| {
+        teleprompter.layout = layout;
+        let position = teleprompter.position;
+        teleprompter.scroll_to(position);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_and_gaze_driven_scrolling() {
+        let lines = wrap_script("Good morning everyone.\n\nToday: supercalifragilistic", 12);
+        assert_eq!(lines, vec!["Good morning", "everyone.", "", "Today:", "supercalifra", "gilistic"]);
+
+        // 300px tall with 40px lines shows 7 lines; the anchor is row 1
+        let layout = TeleprompterLayout { x: 100.0, y: 100.0, ..Default::default() };
+        let script: Vec<String> = (0..20).map(|i| format!("line {}", i)).collect();
+        let mut teleprompter = Teleprompter::new(script, layout);
+
+        // Reading the third row moves the highlight but not the script
+        let state = teleprompter.observe(300.0, 190.0, 0.9, 0).unwrap();
+        assert_eq!((state.position, state.current_line), (0, 2));
+        // Low confidence and off-overlay samples are ignored
+        assert_eq!(teleprompter.observe(300.0, 350.0, 0.1, 10), None);
+        assert_eq!(teleprompter.observe(50.0, 350.0, 0.9, 10), None);
+
+        // Eyes in the lower part: the script scrolls once the dwell time has passed
+        teleprompter.smoothed_y = None;
+        let state = teleprompter.observe(300.0, 350.0, 0.9, 100).unwrap();
+        assert_eq!((state.position, state.current_line), (0, 6));
+        assert_eq!(teleprompter.observe(300.0, 350.0, 0.9, 300), None);
+        let state = teleprompter.observe(300.0, 350.0, 0.9, 500).unwrap();
+        assert_eq!((state.position, state.current_line), (5, 6));
+
+        // Scrolling stops with the last line on screen
+        teleprompter.scroll_to(100);
+        assert_eq!(teleprompter.position, 13);
+
+        teleprompter.paused = true;
+        assert_eq!(teleprompter.observe(300.0, 190.0, 0.9, 1000), None);
+    }
+}
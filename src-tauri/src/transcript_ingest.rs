@@ -0,0 +1,146 @@
+// src-tauri/src/transcript_ingest.rs
+// Finished audio conversations as RAG documents, so chat can answer "what did we decide about
+// pricing last Tuesday" from what was actually said.
+//
+// A transcript opens with the conversation's name and date, then has one line per turn with its
+// clock time and speaker; consecutive messages from the same side are merged into one turn, and
+// previews that were never finalised are left out. The date is in the file name as well, so every
+// cited chunk carries it, not only the first one.
+use chrono::{DateTime, TimeZone};
+use serde_json::Value;
+use std::fmt::Display;
+
+use crate::data::conversation::Speaker;
+use crate::data::types::ConversationSession;
+
+fn speaker_label(speaker: Speaker) -> &'static str {
+    match speaker {
+        Speaker::You => "You",
+        Speaker::Others => "Others",
+    }
+}
+
+fn local_time<Tz: TimeZone>(timestamp_ms: i64, tz: &Tz) -> Option<DateTime<Tz>> {
+    tz.timestamp_millis_opt(timestamp_ms).single()
+}
+
+fn render<Tz: TimeZone>(session: &ConversationSession, tz: &Tz) -> String
+where
+    Tz::Offset: Display,
+{
+    let mut turns: Vec<(Speaker, i64, Vec<&str>)> = Vec::new();
+    for message in &session.messages {
+        let content = message.content.trim();
+        if content.is_empty() || message.is_preview == Some(true) || message.is_typing == Some(true) {
+            continue;
+        }
+        let speaker = Speaker::of(message);
+        match turns.last_mut() {
+            Some((last, _, lines)) if *last == speaker => lines.push(content),
+            _ => turns.push((speaker, message.timestamp, vec![content])),
+        }
+    }
+    if turns.is_empty() {
+        return String::new();
+    }
+
+    let mut text = match local_time(session.start_time, tz) {
+        Some(start) => format!("{} - {}\n", session.name, start.format("%A, %-d %B %Y")),
+        None => format!("{}\n", session.name),
+    };
+    for (speaker, timestamp, lines) in turns {
+        let time = local_time(timestamp, tz).map(|time| time.format("%H:%M").to_string()).unwrap_or_default();
+        text.push_str(&format!("[{}] {}: {}\n", time, speaker_label(speaker), lines.join(" ")));
+    }
+    text
+}
+
+/// The conversation as plain text; empty when nothing final was said
+pub fn transcript_text(session: &ConversationSession) -> String {
+    render(session, &chrono::Local)
+}
+
+/// A file name for the stored transcript: the conversation name and its date
+pub fn file_name(session: &ConversationSession) -> String {
+    let name: String = session
+        .name
+        .chars()
+        .map(|ch| if ch.is_alphanumeric() || matches!(ch, ' ' | '-' | '_' | '.') { ch } else { '_' })
+        .take(80)
+        .collect();
+    let name = if name.trim().is_empty() { "Conversation" } else { name.trim() };
+    match local_time(session.start_time, &chrono::Local) {
+        Some(start) => format!("{} {}.txt", name, start.format("%Y-%m-%d")),
+        None => format!("{}.txt", name),
+    }
+}
+
+/// Where the transcript came from, merged into the document's metadata
+pub fn metadata(session: &ConversationSession) -> serde_json::Map<String, Value> {
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("source".to_string(), Value::from("conversation"));
+    metadata.insert("conversationId".to_string(), Value::from(session.id.clone()));
+    metadata.insert("startTime".to_string(), Value::from(session.start_time));
+    if let Some(end_time) = session.end_time {
+        metadata.insert("endTime".to_string(), Value::from(end_time));
+    }
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::types::ConversationMessage;
+
+    fn message(source: &str, content: &str, timestamp: i64, is_preview: Option<bool>) -> ConversationMessage {
+        ConversationMessage {
+            id: format!("{}-{}", source, timestamp),
+            message_type: if source == "microphone" { "user" } else { "system" }.to_string(),
+            source: source.to_string(),
+            content: content.to_string(),
+            timestamp,
+            confidence: None,
+            is_preview,
+            is_typing: None,
+            persistence_state: None,
+            retry_count: None,
+            last_save_attempt: None,
+            save_error: None,
+            timings: None,
+        }
+    }
+
+    #[test]
+    fn test_transcript_merges_turns_and_skips_previews() {
+        // Tuesday 6 October 2026, 14:05 UTC
+        let start = 1_791_295_500_000;
+        let session = ConversationSession {
+            id: "s1".to_string(),
+            name: "Pricing sync".to_string(),
+            start_time: start,
+            end_time: Some(start + 600_000),
+            messages: vec![
+                message("loopback", "Should we raise the Pro tier?", start + 60_000, None),
+                message("microphone", "Yes, to 12 dollars.", start + 120_000, None),
+                message("microphone", "Starting next month.", start + 130_000, None),
+                message("loopback", "Let's", start + 140_000, Some(true)),
+                message("loopback", "  ", start + 150_000, None),
+                message("loopback", "Agreed.", start + 180_000, None),
+            ],
+            is_active: false,
+            insights: Vec::new(),
+            languages: Vec::new(),
+        };
+        assert_eq!(
+            render(&session, &chrono::Utc),
+            "Pricing sync - Tuesday, 6 October 2026\n\
+             [14:06] Others: Should we raise the Pro tier?\n\
+             [14:07] You: Yes, to 12 dollars. Starting next month.\n\
+             [14:08] Others: Agreed.\n"
+        );
+        assert_eq!(metadata(&session)["conversationId"], "s1");
+
+        let silent = ConversationSession { messages: vec![message("loopback", "Hm", start, Some(true))], ..session };
+        assert_eq!(render(&silent, &chrono::Utc), "");
+    }
+}
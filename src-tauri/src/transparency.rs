@@ -1,6 +1,7 @@
-use tauri::Window;
+use tauri::{AppHandle, Manager, Window};
+use crate::action_registry::{args, descriptor, param, ActionKind, RegisteredAction};
 
-#[tauri::command]
+#[crate::command]
 pub async fn set_window_transparency(window: Window, alpha: f64) -> Result<(), String> {
     // Clamp alpha between 0.0 and 1.0
     let clamped_alpha = alpha.clamp(0.0, 1.0);
@@ -71,7 +72,7 @@ pub async fn set_window_transparency(window: Window, alpha: f64) -> Result<(), S
     Ok(())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn emergency_restore_window(window: Window) -> Result<(), String> {
     println!("🔧 TRANSPARENCY: Emergency restore called");
     
@@ -92,7 +93,7 @@ pub async fn emergency_restore_window(window: Window) -> Result<(), String> {
     Ok(())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn toggle_transparency(window: Window, current_alpha: f64) -> Result<f64, String> {
     println!("🔧 TRANSPARENCY: Toggle called with current_alpha: {}", current_alpha);
     let new_alpha = if current_alpha > 0.5 { 0.3 } else { 1.0 };
@@ -102,7 +103,7 @@ pub async fn toggle_transparency(window: Window, current_alpha: f64) -> Result<f
     Ok(new_alpha)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn initialize_window_transparency(window: Window) -> Result<(), String> {
     println!("🔧 TRANSPARENCY: Initializing window transparency");
     
@@ -121,4 +122,39 @@ pub async fn initialize_window_transparency(window: Window) -> Result<(), String
     println!("🔧 TRANSPARENCY: Window transparency initialized");
     Ok(())
 }
- 
\ No newline at end of file
+ 
+
+fn main_window(app_handle: &AppHandle) -> Result<Window, String> {
+    app_handle
+        .get_webview_window("main")
+        .map(|window| window.as_ref().window())
+        .ok_or_else(|| "Main window not found".to_string())
+}
+
+#[derive(serde::Deserialize)]
+struct AlphaArgs {
+    alpha: f64,
+}
+
+/// Command palette actions for the main window
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    vec![
+        RegisteredAction {
+            descriptor: descriptor("window.emergency_restore", ActionKind::Command, "Restore Window",
+                "Make the main window fully opaque and clickable again", vec![], &["transparency", "reset"]),
+            handler: |app, _| Box::pin(async move {
+                emergency_restore_window(main_window(&app)?).await?;
+                Ok(serde_json::Value::Null)
+            }),
+        },
+        RegisteredAction {
+            descriptor: descriptor("window.set_transparency", ActionKind::Command, "Set Window Transparency",
+                "Set the main window opacity", vec![param("alpha", "number", true, "Opacity from 0.0 to 1.0")], &["opacity"]),
+            handler: |app, params| Box::pin(async move {
+                let AlphaArgs { alpha } = args(params)?;
+                set_window_transparency(main_window(&app)?, alpha).await?;
+                Ok(serde_json::Value::Null)
+            }),
+        },
+    ]
+}
@@ -0,0 +1,403 @@
+// Approximate nearest-neighbour index over chunk embeddings - a hierarchical navigable small world
+// (HNSW) graph, so vector search touches a few hundred chunks instead of every stored embedding.
+//
+// Vectors are normalized on insert, making distance 1 - dot product (cosine distance). Removed
+// chunks stay in the graph as tombstones so it stays connected; `compacted` rebuilds without them.
+// The graph is saved next to the Tantivy index in a small binary format and loaded at startup.
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::Path;
+
+// Links per node above layer 0, and on layer 0
+const M: usize = 16;
+const M0: usize = 2 * M;
+const EF_CONSTRUCTION: usize = 100;
+const EF_SEARCH: usize = 64;
+const MAGIC: &[u8; 6] = b"EHNSW1";
+
+struct Node {
+    chunk_id: String,
+    document_id: String,
+    vector: Vec<f32>,
+    /// Neighbours on each layer the node is on, layer 0 first
+    links: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+// (distance, node) ordered by distance
+#[derive(Clone, Copy, PartialEq)]
+struct Candidate(f32, usize);
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// A chunk found by `VectorIndex::search`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorMatch {
+    pub chunk_id: String,
+    pub document_id: String,
+    /// Cosine similarity to the query
+    pub similarity: f32,
+}
+
+#[derive(Default)]
+pub struct VectorIndex {
+    dimensions: usize,
+    nodes: Vec<Node>,
+    ids: HashMap<String, usize>,
+    entry_point: Option<usize>,
+    deleted: usize,
+}
+
+fn normalized(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|x| x / norm).collect()
+}
+
+impl VectorIndex {
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn distance(&self, a: &[f32], node: usize) -> f32 {
+        1.0 - a.iter().zip(&self.nodes[node].vector).map(|(x, y)| x * y).sum::<f32>()
+    }
+
+    fn level(&self, node: usize) -> usize {
+        self.nodes[node].links.len() - 1
+    }
+
+    fn random_level() -> usize {
+        let uniform: f64 = rand::thread_rng().gen_range(f64::EPSILON..1.0);
+        (-uniform.ln() / (M as f64).ln()).floor() as usize
+    }
+
+    // The `ef` nodes closest to `query` on `layer`, searching out from `entry_points`; closest first
+    fn search_layer(&self, query: &[f32], entry_points: &[usize], ef: usize, layer: usize) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entry_points.iter().copied().collect();
+        let mut candidates: BinaryHeap<Reverse<Candidate>> = BinaryHeap::new();
+        let mut found: BinaryHeap<Candidate> = BinaryHeap::new();
+        for &node in entry_points {
+            let candidate = Candidate(self.distance(query, node), node);
+            candidates.push(Reverse(candidate));
+            found.push(candidate);
+        }
+        while let Some(Reverse(Candidate(distance, node))) = candidates.pop() {
+            if found.len() >= ef && found.peek().is_some_and(|furthest| distance > furthest.0) {
+                break;
+            }
+            for &neighbour in self.nodes[node].links.get(layer).into_iter().flatten() {
+                let neighbour = neighbour as usize;
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let distance = self.distance(query, neighbour);
+                if found.len() < ef || found.peek().is_some_and(|furthest| distance < furthest.0) {
+                    candidates.push(Reverse(Candidate(distance, neighbour)));
+                    found.push(Candidate(distance, neighbour));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+        found.into_sorted_vec()
+    }
+
+    // Greedy descent from the entry point to `layer`
+    fn descend(&self, query: &[f32], layer: usize) -> Option<usize> {
+        let mut current = self.entry_point?;
+        for level in (layer + 1..=self.level(current)).rev() {
+            current = self.search_layer(query, &[current], 1, level)[0].1;
+        }
+        Some(current)
+    }
+
+    /// Add or replace a chunk's embedding. Embeddings of another size than the index's are refused.
+    pub fn insert(&mut self, chunk_id: &str, document_id: &str, vector: &[f32]) -> Result<()> {
+        if self.dimensions == 0 {
+            self.dimensions = vector.len();
+        }
+        if vector.len() != self.dimensions || vector.is_empty() {
+            return Err(anyhow!("Embedding has {} dimensions, the vector index {}", vector.len(), self.dimensions));
+        }
+        self.remove_chunk(chunk_id);
+
+        let vector = normalized(vector);
+        let level = Self::random_level();
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            chunk_id: chunk_id.to_string(),
+            document_id: document_id.to_string(),
+            vector: vector.clone(),
+            links: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.ids.insert(chunk_id.to_string(), index);
+
+        let Some(entry_point) = self.entry_point else {
+            self.entry_point = Some(index);
+            return Ok(());
+        };
+        let top = self.level(entry_point);
+        let mut entry = self.descend(&vector, level.min(top)).unwrap_or(entry_point);
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&vector, &[entry], EF_CONSTRUCTION, layer);
+            let max_links = if layer == 0 { M0 } else { M };
+            let neighbours: Vec<u32> = found.iter().filter(|c| c.1 != index).take(M).map(|c| c.1 as u32).collect();
+            for &neighbour in &neighbours {
+                let neighbour = neighbour as usize;
+                self.nodes[neighbour].links[layer].push(index as u32);
+                if self.nodes[neighbour].links[layer].len() > max_links {
+                    // Keep the neighbour's closest links
+                    let own = self.nodes[neighbour].vector.clone();
+                    let mut links: Vec<Candidate> = self.nodes[neighbour].links[layer]
+                        .iter()
+                        .map(|&link| Candidate(self.distance(&own, link as usize), link as usize))
+                        .collect();
+                    links.sort();
+                    self.nodes[neighbour].links[layer] = links.into_iter().take(max_links).map(|c| c.1 as u32).collect();
+                }
+            }
+            self.nodes[index].links[layer] = neighbours;
+            entry = found[0].1;
+        }
+        if level > top {
+            self.entry_point = Some(index);
+        }
+        Ok(())
+    }
+
+    pub fn remove_chunk(&mut self, chunk_id: &str) {
+        if let Some(index) = self.ids.remove(chunk_id) {
+            self.nodes[index].deleted = true;
+            self.deleted += 1;
+        }
+    }
+
+    pub fn remove_document(&mut self, document_id: &str) {
+        let chunk_ids: Vec<String> = self
+            .nodes
+            .iter()
+            .filter(|node| !node.deleted && node.document_id == document_id)
+            .map(|node| node.chunk_id.clone())
+            .collect();
+        for chunk_id in chunk_ids {
+            self.remove_chunk(&chunk_id);
+        }
+    }
+
+    /// The `limit` chunks closest to `query`, most similar first
+    pub fn search(&self, query: &[f32], limit: usize) -> Vec<VectorMatch> {
+        if query.len() != self.dimensions || limit == 0 {
+            return Vec::new();
+        }
+        let query = normalized(query);
+        let Some(entry) = self.descend(&query, 0) else { return Vec::new() };
+        // Tombstones take up places in the candidate list, so look further when there are many
+        let ef = EF_SEARCH.max(limit) + self.deleted.min(limit * 4);
+        self.search_layer(&query, &[entry], ef, 0)
+            .into_iter()
+            .filter(|candidate| !self.nodes[candidate.1].deleted)
+            .take(limit)
+            .map(|Candidate(distance, node)| VectorMatch {
+                chunk_id: self.nodes[node].chunk_id.clone(),
+                document_id: self.nodes[node].document_id.clone(),
+                similarity: 1.0 - distance,
+            })
+            .collect()
+    }
+
+    /// Whether enough of the graph is tombstones to be worth rebuilding
+    pub fn needs_compaction(&self) -> bool {
+        self.deleted > 1000 && self.deleted > self.nodes.len() / 2
+    }
+
+    /// The same chunks in a fresh graph, without tombstones
+    pub fn compacted(&self) -> Self {
+        let mut index = Self::default();
+        for node in self.nodes.iter().filter(|node| !node.deleted) {
+            // Same dimensions as before, so this can't fail
+            let _ = index.insert(&node.chunk_id, &node.document_id, &node.vector);
+        }
+        index
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fn string(out: &mut Vec<u8>, value: &str) {
+            out.extend((value.len() as u32).to_le_bytes());
+            out.extend(value.as_bytes());
+        }
+
+        let mut out = Vec::with_capacity(self.nodes.len() * (self.dimensions * 4 + 200));
+        out.extend(MAGIC);
+        out.extend((self.dimensions as u32).to_le_bytes());
+        out.extend((self.nodes.len() as u32).to_le_bytes());
+        out.extend(self.entry_point.map_or(u32::MAX, |entry| entry as u32).to_le_bytes());
+        for node in &self.nodes {
+            out.push(node.deleted as u8);
+            string(&mut out, &node.chunk_id);
+            string(&mut out, &node.document_id);
+            for value in &node.vector {
+                out.extend(value.to_le_bytes());
+            }
+            out.push(node.links.len() as u8);
+            for links in &node.links {
+                out.extend((links.len() as u32).to_le_bytes());
+                for link in links {
+                    out.extend(link.to_le_bytes());
+                }
+            }
+        }
+        // Written aside and renamed, so a crash mid-write leaves the old index
+        let temp = path.with_extension("tmp");
+        std::fs::File::create(&temp)?.write_all(&out)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut data = Vec::new();
+        std::fs::File::open(path)?.read_to_end(&mut data)?;
+        if !data.starts_with(MAGIC) {
+            return Err(anyhow!("Not a vector index file"));
+        }
+
+        let mut position = MAGIC.len();
+        let read_u32 = |position: &mut usize| -> Result<u32> {
+            let bytes = data.get(*position..*position + 4).ok_or_else(|| anyhow!("Vector index file is truncated"))?;
+            *position += 4;
+            Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+        let read_bytes = |position: &mut usize, count: usize| -> Result<&[u8]> {
+            let bytes = data.get(*position..*position + count).ok_or_else(|| anyhow!("Vector index file is truncated"))?;
+            *position += count;
+            Ok(bytes)
+        };
+        let read_string = |position: &mut usize| -> Result<String> {
+            let length = read_u32(position)? as usize;
+            Ok(String::from_utf8(read_bytes(position, length)?.to_vec())?)
+        };
+
+        let dimensions = read_u32(&mut position)? as usize;
+        let count = read_u32(&mut position)? as usize;
+        let entry_point = match read_u32(&mut position)? {
+            u32::MAX => None,
+            entry => Some(entry as usize),
+        };
+        let mut index = Self { dimensions, entry_point, ..Self::default() };
+        for node_index in 0..count {
+            let deleted = read_bytes(&mut position, 1)?[0] != 0;
+            let chunk_id = read_string(&mut position)?;
+            let document_id = read_string(&mut position)?;
+            let vector = read_bytes(&mut position, dimensions * 4)?
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            let levels = read_bytes(&mut position, 1)?[0] as usize;
+            let mut links = Vec::with_capacity(levels);
+            for _ in 0..levels {
+                let length = read_u32(&mut position)? as usize;
+                let layer: Vec<u32> = (0..length).map(|_| read_u32(&mut position)).collect::<Result<_>>()?;
+                if layer.iter().any(|&link| link as usize >= count) {
+                    return Err(anyhow!("Vector index file is corrupt"));
+                }
+                links.push(layer);
+            }
+            if links.is_empty() {
+                return Err(anyhow!("Vector index file is corrupt"));
+            }
+            if deleted {
+                index.deleted += 1;
+            } else {
+                index.ids.insert(chunk_id.clone(), node_index);
+            }
+            index.nodes.push(Node { chunk_id, document_id, vector, links, deleted });
+        }
+        if index.entry_point.is_some_and(|entry| entry >= count) {
+            return Err(anyhow!("Vector index file is corrupt"));
+        }
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_search_finds_nearest_chunks_and_survives_reload() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let vectors: Vec<Vec<f32>> = (0..600).map(|_| (0..24).map(|_| rng.gen_range(-1.0..1.0)).collect()).collect();
+        let mut index = VectorIndex::default();
+        for (i, vector) in vectors.iter().enumerate() {
+            index.insert(&format!("chunk-{}", i), &format!("doc-{}", i % 10), vector).unwrap();
+        }
+        assert_eq!(index.len(), 600);
+        assert!(index.insert("other", "doc", &[1.0, 2.0]).is_err());
+
+        // Recall of the 10 nearest against a linear scan
+        let exact = |query: &[f32]| {
+            let query = normalized(query);
+            let mut all: Vec<(f32, usize)> = vectors
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (normalized(v).iter().zip(&query).map(|(a, b)| a * b).sum::<f32>(), i))
+                .collect();
+            all.sort_by(|a, b| b.0.total_cmp(&a.0));
+            all.into_iter().take(10).map(|(_, i)| format!("chunk-{}", i)).collect::<HashSet<_>>()
+        };
+        let mut hits = 0;
+        for query in vectors.iter().take(50) {
+            let found = index.search(query, 10);
+            hits += found.iter().filter(|m| exact(query).contains(&m.chunk_id)).count();
+        }
+        assert!(hits >= 450, "recall {} of 500", hits);
+        let top = &index.search(&vectors[3], 1)[0];
+        assert_eq!((top.chunk_id.as_str(), top.document_id.as_str()), ("chunk-3", "doc-3"));
+        assert!((top.similarity - 1.0).abs() < 1e-5);
+
+        index.remove_chunk("chunk-3");
+        index.remove_document("doc-4");
+        assert_eq!(index.len(), 539);
+        let found = index.search(&vectors[3], 20);
+        assert!(found.iter().all(|m| m.chunk_id != "chunk-3" && m.document_id != "doc-4"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectors.hnsw");
+        index.save(&path).unwrap();
+        let loaded = VectorIndex::load(&path).unwrap();
+        assert_eq!(loaded.len(), 539);
+        assert_eq!(loaded.search(&vectors[5], 5), index.search(&vectors[5], 5));
+        assert_eq!(index.compacted().search(&vectors[5], 1)[0].chunk_id, "chunk-5");
+
+        std::fs::write(&path, b"EHNSW1\x10").unwrap();
+        assert!(VectorIndex::load(&path).is_err());
+    }
+}
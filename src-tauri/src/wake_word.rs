@@ -0,0 +1,600 @@
+// src-tauri/src/wake_word.rs
+// Always-on wake-word listener for hands-free activation. An energy gate picks out short utterances
+// from the microphone and the local Whisper model checks them for the wake phrase, so nothing leaves the device.
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+const FRAME_MS: u32 = 20;
+const PRE_ROLL_MS: u32 = 200;
+const MIN_UTTERANCE_MS: u32 = 300;
+// Ignore further wake phrases right after a detection so one utterance doesn't fire twice
+const DETECTION_COOLDOWN: Duration = Duration::from_secs(2);
+// How long to wait for the user to start speaking a command after the wake phrase
+const COMMAND_WAIT: Duration = Duration::from_secs(8);
+
+lazy_static::lazy_static! {
+    static ref WAKE_WORD_SETTINGS: RwLock<WakeWordSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref LISTENER: Mutex<Option<Listener>> = Mutex::new(None);
+    static ref LAST_DETECTION_MS: Mutex<Option<u64>> = Mutex::new(None);
+    static ref LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WakeWordSettings {
+    /// Listen whenever the app runs
+    pub enabled: bool,
+    pub phrase: String,
+    /// 0-1; higher accepts looser matches of the phrase
+    pub sensitivity: f32,
+    /// Input device name; `None` uses the system default microphone
+    pub device_name: Option<String>,
+    /// RMS level a frame needs to count as speech
+    pub speech_threshold: f32,
+    /// Whisper model used if none is loaded yet
+    pub model_size: String,
+    pub language: String,
+    /// Transcribe the command after the wake phrase and send it to an agent
+    pub auto_start_session: bool,
+    /// Agent action from the action registry that receives the command
+    pub agent_action: String,
+}
+
+impl Default for WakeWordSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            phrase: "hey enteract".to_string(),
+            sensitivity: 0.5,
+            device_name: None,
+            speech_threshold: 0.01,
+            model_size: "tiny".to_string(),
+            language: "en".to_string(),
+            auto_start_session: false,
+            agent_action: "agent.enteract".to_string(),
+        }
+    }
+}
+
+impl WakeWordSettings {
+    fn validate(&self) -> Result<(), String> {
+        if normalize(&self.phrase).is_empty() {
+            return Err("Wake phrase must contain at least one word".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.sensitivity) {
+            return Err("Sensitivity must be between 0 and 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.speech_threshold) {
+            return Err("Speech threshold must be between 0 and 1".to_string());
+        }
+        if !self.agent_action.starts_with("agent.") {
+            return Err("The wake word session must use an agent action".to_string());
+        }
+        Ok(())
+    }
+
+    // Similarity the transcript needs to count as the wake phrase
+    fn match_threshold(&self) -> f32 {
+        1.0 - 0.3 * self.sensitivity
+    }
+
+    fn whisper_config(&self) -> crate::speech::WhisperModelConfig {
+        crate::speech::WhisperModelConfig {
+            modelSize: self.model_size.clone(),
+            language: Some(self.language.clone()),
+            enableVad: false,
+            silenceThreshold: self.speech_threshold,
+            maxSegmentLength: 30,
+            source: Some("wake-word".to_string()),
+            deviceId: None,
+            languages: Vec::new(),
+            operationId: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeWordStatus {
+    pub listening: bool,
+    /// Unix ms of the last detection
+    pub last_detection: Option<u64>,
+    /// Why the listener last stopped on its own
+    pub error: Option<String>,
+    pub settings: WakeWordSettings,
+}
+
+struct Listener {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// Splits a mono stream into utterances: runs of speech-level frames ended by enough silence
+struct UtteranceGate {
+    frame_len: usize,
+    threshold: f32,
+    end_silence: usize,
+    max_len: usize,
+    min_len: usize,
+    pending: Vec<f32>,
+    pre_roll: VecDeque<f32>,
+    pre_roll_len: usize,
+    speech: Vec<f32>,
+    silence_run: usize,
+}
+
+impl UtteranceGate {
+    fn new(sample_rate: u32, threshold: f32, end_silence_ms: u32, max_ms: u32) -> Self {
+        let samples = |ms: u32| (sample_rate as u64 * ms as u64 / 1000) as usize;
+        Self {
+            frame_len: samples(FRAME_MS).max(1),
+            threshold,
+            end_silence: samples(end_silence_ms),
+            max_len: samples(max_ms),
+            min_len: samples(MIN_UTTERANCE_MS),
+            pending: Vec::new(),
+            pre_roll: VecDeque::new(),
+            pre_roll_len: samples(PRE_ROLL_MS),
+            speech: Vec::new(),
+            silence_run: 0,
+        }
+    }
+
+    fn in_speech(&self) -> bool {
+        !self.speech.is_empty()
+    }
+
+    /// Feed mono samples; returns any utterances that finished
+    fn push(&mut self, samples: &[f32]) -> Vec<Vec<f32>> {
+        self.pending.extend_from_slice(samples);
+        let mut finished = Vec::new();
+        while self.pending.len() >= self.frame_len {
+            let frame: Vec<f32> = self.pending.drain(..self.frame_len).collect();
+            let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+            let loud = rms >= self.threshold;
+
+            if !self.in_speech() {
+                if loud {
+                    self.speech.extend(self.pre_roll.drain(..));
+                    self.speech.extend_from_slice(&frame);
+                    self.silence_run = 0;
+                } else {
+                    self.pre_roll.extend(frame);
+                    while self.pre_roll.len() > self.pre_roll_len {
+                        self.pre_roll.pop_front();
+                    }
+                }
+                continue;
+            }
+
+            self.speech.extend_from_slice(&frame);
+            self.silence_run = if loud { 0 } else { self.silence_run + frame.len() };
+            if self.silence_run >= self.end_silence || self.speech.len() >= self.max_len {
+                let utterance = std::mem::take(&mut self.speech);
+                self.silence_run = 0;
+                if utterance.len() >= self.min_len {
+                    finished.push(utterance);
+                }
+            }
+        }
+        finished
+    }
+}
+
+enum ListenMode {
+    Wake,
+    Command { deadline: Instant },
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+const SETTINGS_FILE: &str = "wake_word.json";
+
+// Lowercase words with punctuation stripped
+fn normalize(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f32 / longest as f32
+}
+
+/// Look for the wake phrase in a transcript. Returns the match score and whatever was said after it.
+/// Spacing is ignored so "enter act" still matches "enteract".
+fn find_wake_phrase(transcript: &str, phrase: &str, threshold: f32) -> Option<(f32, String)> {
+    let phrase = normalize(phrase).concat();
+    let original: Vec<&str> = transcript.split_whitespace().collect();
+    let words: Vec<(usize, String)> = original
+        .iter()
+        .enumerate()
+        .map(|(index, word)| (index, normalize(word).concat()))
+        .filter(|(_, word)| !word.is_empty())
+        .collect();
+
+    let mut best: Option<(f32, usize)> = None;
+    for start in 0..words.len() {
+        let mut joined = String::new();
+        for end in start..words.len() {
+            joined.push_str(&words[end].1);
+            let score = similarity(&joined, &phrase);
+            if score >= threshold && best.map_or(true, |(best_score, _)| score > best_score) {
+                best = Some((score, words[end].0 + 1));
+            }
+            if joined.chars().count() > phrase.chars().count() * 2 {
+                break;
+            }
+        }
+    }
+
+    best.map(|(score, rest_from)| {
+        let rest = original[rest_from..].join(" ");
+        (score, rest.trim_start_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace()).to_string())
+    })
+}
+
+fn resample_to_whisper(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    if sample_rate == WHISPER_SAMPLE_RATE || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let step = sample_rate as f64 / WHISPER_SAMPLE_RATE as f64;
+    let len = (samples.len() as f64 / step) as usize;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position as usize;
+            let next = samples.get(index + 1).copied().unwrap_or(samples[index]);
+            let fraction = (position - index as f64) as f32;
+            samples[index] + (next - samples[index]) * fraction
+        })
+        .collect()
+}
+
+fn transcribe(app_handle: &AppHandle, utterance: &[f32], sample_rate: u32, settings: &WakeWordSettings) -> Result<String, String> {
+    let audio = resample_to_whisper(utterance, sample_rate);
+    let config = settings.whisper_config();
+    let result = tauri::async_runtime::block_on(crate::speech::transcribe_with_whisper(app_handle, &audio, &config, &[]))?;
+    Ok(result.text.trim().to_string())
+}
+
+fn open_input_stream(device_name: Option<&str>, sender: mpsc::Sender<Vec<f32>>) -> Result<(cpal::Stream, u32), String> {
+    crate::permissions::require(crate::permissions::PermissionKind::Microphone)?;
+    let host = cpal::default_host();
+    let device = match device_name {
+        Some(name) => host
+            .input_devices()
+            .map_err(|e| format!("Failed to list microphones: {}", e))?
+            .find(|device| device.name().map_or(false, |device_name| device_name == name)),
+        None => host.default_input_device(),
+    }
+    .ok_or_else(|| format!("Microphone not found: {}", device_name.unwrap_or("default")))?;
+
+    let supported = device
+        .default_input_config()
+        .map_err(|e| format!("Failed to read microphone format: {}", e))?;
+    let channels = supported.channels().max(1) as usize;
+    let sample_rate = supported.sample_rate().0;
+    let config: cpal::StreamConfig = supported.config();
+
+    // Downmix in the callback so the channel carries mono audio
+    let mono = move |samples: Vec<f32>| -> Vec<f32> {
+        samples.chunks(channels).map(|frame| frame.iter().sum::<f32>() / frame.len() as f32).collect()
+    };
+    let on_error = |e: cpal::StreamError| eprintln!("[WAKE] Microphone stream error: {}", e);
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| { let _ = sender.send(mono(data.to_vec())); },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &config,
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                let _ = sender.send(mono(data.iter().map(|&s| s as f32 / 32768.0).collect()));
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::U16 => device.build_input_stream(
+            &config,
+            move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                let _ = sender.send(mono(data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0).collect()));
+            },
+            on_error,
+            None,
+        ),
+        other => return Err(format!("Unsupported microphone sample format: {:?}", other)),
+    }
+    .map_err(|e| format!("Failed to open microphone: {}", e))?;
+
+    stream.play().map_err(|e| format!("Failed to start microphone: {}", e))?;
+    Ok((stream, sample_rate))
+}
+
+fn start_session(app_handle: &AppHandle, settings: &WakeWordSettings, prompt: String) {
+    let session_id = format!("wake-{}", uuid::Uuid::new_v4());
+    println!("[WAKE] Starting {} session {}", settings.agent_action, session_id);
+    let _ = app_handle.emit("wake-word-session-started", serde_json::json!({
+        "sessionId": session_id,
+        "prompt": prompt,
+        "action": settings.agent_action,
+    }));
+
+    let app_handle = app_handle.clone();
+    let action = settings.agent_action.clone();
+    tauri::async_runtime::spawn(async move {
+        let params = serde_json::json!({ "prompt": prompt, "session_id": session_id });
+        if let Err(e) = crate::action_registry::invoke_action(app_handle.clone(), action, Some(params)).await {
+            eprintln!("[WAKE] Failed to start session: {}", e);
+            let _ = app_handle.emit("wake-word-error", serde_json::json!({ "error": e }));
+        }
+    });
+}
+
+// Runs on the listener thread until `stop` is set. Transcription blocks this thread;
+// the microphone callback keeps queueing audio meanwhile, so nothing said after the wake phrase is lost.
+fn listen(app_handle: AppHandle, stop: Arc<AtomicBool>, receiver: mpsc::Receiver<Vec<f32>>, sample_rate: u32) {
+    let settings = read_settings(&WAKE_WORD_SETTINGS);
+    let threshold = settings.match_threshold();
+    let wake_gate = || UtteranceGate::new(sample_rate, settings.speech_threshold, 500, 3000);
+    let command_gate = || UtteranceGate::new(sample_rate, settings.speech_threshold, 1200, 20000);
+
+    let mut gate = wake_gate();
+    let mut mode = ListenMode::Wake;
+    let mut cooldown_until: Option<Instant> = None;
+
+    while !stop.load(Ordering::SeqCst) {
+        let samples = match receiver.recv_timeout(Duration::from_millis(100)) {
+            Ok(samples) => samples,
+            Err(mpsc::RecvTimeoutError::Timeout) => Vec::new(),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+
+        if let ListenMode::Command { deadline } = mode {
+            if !gate.in_speech() && Instant::now() >= deadline {
+                println!("[WAKE] No command heard, listening for the wake phrase again");
+                let _ = app_handle.emit("wake-word-command-timeout", ());
+                gate = wake_gate();
+                mode = ListenMode::Wake;
+            }
+        }
+
+        for utterance in gate.push(&samples) {
+            let text = match transcribe(&app_handle, &utterance, sample_rate, &settings) {
+                Ok(text) => text,
+                Err(e) => {
+                    eprintln!("[WAKE] Transcription failed: {}", e);
+                    continue;
+                }
+            };
+
+            match mode {
+                ListenMode::Command { .. } => {
+                    gate = wake_gate();
+                    mode = ListenMode::Wake;
+                    if !text.is_empty() {
+                        start_session(&app_handle, &settings, text);
+                    }
+                    break;
+                }
+                ListenMode::Wake => {
+                    if cooldown_until.map_or(false, |until| Instant::now() < until) {
+                        continue;
+                    }
+                    let Some((score, rest)) = find_wake_phrase(&text, &settings.phrase, threshold) else { continue };
+                    cooldown_until = Some(Instant::now() + DETECTION_COOLDOWN);
+                    let timestamp = now_ms();
+                    if let Ok(mut last) = LAST_DETECTION_MS.lock() {
+                        *last = Some(timestamp);
+                    }
+
+                    println!("[WAKE] Wake phrase detected ({:.2}): {}", score, text);
+                    let _ = app_handle.emit("wake-word-detected", serde_json::json!({
+                        "phrase": settings.phrase,
+                        "transcript": text,
+                        "score": score,
+                        "timestamp": timestamp,
+                        "autoStart": settings.auto_start_session,
+                    }));
+
+                    if settings.auto_start_session {
+                        // "Hey Enteract, what's on my calendar" carries its command along
+                        if rest.is_empty() {
+                            gate = command_gate();
+                            mode = ListenMode::Command { deadline: Instant::now() + COMMAND_WAIT };
+                            break;
+                        }
+                        start_session(&app_handle, &settings, rest);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn start_listener(app_handle: &AppHandle) -> Result<(), String> {
+    let mut listener = LISTENER.lock().map_err(|_| "Failed to read wake word state".to_string())?;
+    if listener.as_ref().map_or(false, |listener| !listener.thread.is_finished()) {
+        return Ok(());
+    }
+
+    let settings = read_settings(&WAKE_WORD_SETTINGS);
+    let stop = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+    let thread_stop = stop.clone();
+    let app = app_handle.clone();
+
+    // cpal streams aren't Send on every platform, so the stream lives on the listener thread
+    let thread = std::thread::spawn(move || {
+        let (sender, receiver) = mpsc::channel();
+        let (stream, sample_rate) = match open_input_stream(settings.device_name.as_deref(), sender) {
+            Ok(opened) => opened,
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        let _ = ready_tx.send(Ok(()));
+        println!("[WAKE] Listening for \"{}\" at {} Hz", settings.phrase, sample_rate);
+
+        listen(app.clone(), thread_stop.clone(), receiver, sample_rate);
+        drop(stream);
+
+        if !thread_stop.load(Ordering::SeqCst) {
+            let error = "Microphone stream ended".to_string();
+            if let Ok(mut last) = LAST_ERROR.lock() {
+                *last = Some(error.clone());
+            }
+            let _ = app.emit("wake-word-error", serde_json::json!({ "error": error }));
+        }
+    });
+
+    let ready = ready_rx
+        .recv_timeout(Duration::from_secs(5))
+        .map_err(|_| "Timed out opening the microphone".to_string())
+        .and_then(|opened| opened);
+    if let Err(e) = ready {
+        stop.store(true, Ordering::SeqCst);
+        return Err(e);
+    }
+    if let Ok(mut last) = LAST_ERROR.lock() {
+        *last = None;
+    }
+    *listener = Some(Listener { stop, thread });
+    Ok(())
+}
+
+fn stop_listener() {
+    let listener = LISTENER.lock().ok().and_then(|mut listener| listener.take());
+    if let Some(listener) = listener {
+        listener.stop.store(true, Ordering::SeqCst);
+        let _ = listener.thread.join();
+    }
+}
+
+/// Start listening at launch when the wake word is enabled
+pub fn init(app_handle: &AppHandle) {
+    if !read_settings(&WAKE_WORD_SETTINGS).enabled {
+        return;
+    }
+    if let Err(e) = start_listener(app_handle) {
+        eprintln!("[WAKE] Could not start wake word listener: {}", e);
+        if let Ok(mut last) = LAST_ERROR.lock() {
+            *last = Some(e);
+        }
+    }
+}
+
+#[crate::command]
+pub fn get_wake_word_status() -> Result<WakeWordStatus, String> {
+    let listening = LISTENER
+        .lock()
+        .map_err(|_| "Failed to read wake word state".to_string())?
+        .as_ref()
+        .map_or(false, |listener| !listener.thread.is_finished());
+    Ok(WakeWordStatus {
+        listening,
+        last_detection: LAST_DETECTION_MS.lock().ok().and_then(|last| *last),
+        error: LAST_ERROR.lock().ok().and_then(|last| last.clone()),
+        settings: read_settings(&WAKE_WORD_SETTINGS),
+    })
+}
+
+/// Save wake word settings and start or stop the listener to match `enabled`
+#[crate::command]
+pub fn save_wake_word_settings(app_handle: AppHandle, settings: WakeWordSettings) -> Result<WakeWordStatus, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    settings.validate()?;
+    save_json_settings(SETTINGS_FILE, "wake word settings", &settings)?;
+
+    let enabled = settings.enabled;
+    *WAKE_WORD_SETTINGS
+        .write()
+        .map_err(|_| "Failed to update wake word settings".to_string())? = settings;
+    // Restart so a running listener picks up the new phrase and device
+    stop_listener();
+    if enabled {
+        start_listener(&app_handle)?;
+    }
+    get_wake_word_status()
+}
+
+#[crate::command]
+pub fn start_wake_word(app_handle: AppHandle) -> Result<WakeWordStatus, String> {
+    start_listener(&app_handle)?;
+    get_wake_word_status()
+}
+
+#[crate::command]
+pub fn stop_wake_word() -> Result<WakeWordStatus, String> {
+    stop_listener();
+    get_wake_word_status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_wake_phrase() {
+        let threshold = WakeWordSettings::default().match_threshold();
+        let (score, rest) = find_wake_phrase("Hey, Enteract!", "hey enteract", threshold).unwrap();
+        assert_eq!(score, 1.0);
+        assert_eq!(rest, "");
+
+        // Whisper splitting or misspelling the name still counts, and the command after it is kept
+        let (_, rest) = find_wake_phrase("hey enter act, what's the time?", "hey enteract", threshold).unwrap();
+        assert_eq!(rest, "what's the time?");
+        assert!(find_wake_phrase("Hey, Enterract open notes", "hey enteract", threshold).is_some());
+
+        assert!(find_wake_phrase("they interact with it", "hey enteract", threshold).is_none());
+        assert!(find_wake_phrase("good morning everyone", "hey enteract", threshold).is_none());
+    }
+
+    #[test]
+    fn test_utterance_gate_splits_on_silence() {
+        let mut gate = UtteranceGate::new(16000, 0.01, 500, 3000);
+        let speech = vec![0.1; 16000];
+        let silence = vec![0.0; 16000];
+
+        assert!(gate.push(&silence).is_empty());
+        assert!(gate.push(&speech).is_empty());
+        let utterances = gate.push(&silence);
+        assert_eq!(utterances.len(), 1);
+        // Speech plus pre-roll and the trailing silence that ended it
+        assert_eq!(utterances[0].len(), 16000 + 3200 + 8000);
+        assert!(!gate.in_speech());
+    }
+}
@@ -0,0 +1,225 @@
+// src-tauri/src/web_ingest.rs
+// Web pages as RAG documents - fetching a URL and keeping the article, not the page around it.
+//
+// Extraction is readability-style but deliberately small: boilerplate elements (scripts, styles,
+// navigation, headers, footers, sidebars, forms) are dropped, the largest <article> or <main> is
+// preferred over the whole body, and what's left is split into blocks. Blocks that are mostly
+// link text (menus, tag clouds, "related" lists) or too short to be prose are dropped; headings
+// are kept so chunks still carry their section titles.
+use regex::Regex;
+use std::time::Duration;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+// Blocks shorter than this are kept only when they read like a sentence or are headings
+const MIN_BLOCK_CHARS: usize = 40;
+const MAX_LINK_DENSITY: f64 = 0.5;
+// Markers for link text and headings while tags are stripped; control characters never in pages
+const LINK_START: char = '\u{1}';
+const LINK_END: char = '\u{2}';
+const HEADING: char = '\u{3}';
+
+lazy_static::lazy_static! {
+    static ref COMMENTS: Regex = Regex::new(r"(?s)<!--.*?-->").unwrap();
+    static ref BOILERPLATE: Vec<Regex> = [
+        "script", "style", "noscript", "svg", "nav", "header", "footer", "aside", "form", "iframe", "template", "button", "select",
+    ]
+    .iter()
+    .map(|tag| Regex::new(&format!(r"(?is)<{0}\b[^>]*>.*?</{0}\s*>", tag)).unwrap())
+    .collect();
+    static ref TITLE: Regex = Regex::new(r"(?is)<title[^>]*>(.*?)</title\s*>").unwrap();
+    static ref OG_TITLE: Regex = Regex::new(r#"(?is)<meta[^>]+property\s*=\s*["']og:title["'][^>]*content\s*=\s*["']([^"']*)["']"#).unwrap();
+    static ref ARTICLE: Regex = Regex::new(r"(?is)<(article|main)\b[^>]*>(.*?)</(?:article|main)\s*>").unwrap();
+    static ref BODY: Regex = Regex::new(r"(?is)<body\b[^>]*>(.*)</body\s*>").unwrap();
+    static ref LINK: Regex = Regex::new(r"(?is)<a\b[^>]*>(.*?)</a\s*>").unwrap();
+    static ref HEADING_OPEN: Regex = Regex::new(r"(?i)<h[1-6]\b[^>]*>").unwrap();
+    static ref BLOCK: Regex = Regex::new(r"(?i)</?(p|div|section|h[1-6]|li|ul|ol|pre|blockquote|td|tr|table|br|hr|dd|dt|figcaption)\b[^>]*>").unwrap();
+    static ref TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+    static ref ENTITY: Regex = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap();
+}
+
+/// The readable part of a page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Article {
+    pub title: Option<String>,
+    pub text: String,
+}
+
+fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "mdash" => Some('—'),
+                "ndash" => Some('–'),
+                "hellip" => Some('…'),
+                "rsquo" => Some('’'),
+                "lsquo" => Some('‘'),
+                "rdquo" => Some('”'),
+                "ldquo" => Some('“'),
+                _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                    u32::from_str_radix(&entity[2..], 16).ok().and_then(char::from_u32)
+                }
+                _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+                _ => None,
+            };
+            decoded.map(String::from).unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The article in `html`, boilerplate stripped
+pub fn extract_article(html: &str) -> Article {
+    let title = OG_TITLE
+        .captures(html)
+        .or_else(|| TITLE.captures(html))
+        .map(|caps| collapse_whitespace(&decode_entities(&TAG.replace_all(&caps[1], ""))))
+        .filter(|title| !title.is_empty());
+
+    let mut page = COMMENTS.replace_all(html, "").into_owned();
+    for boilerplate in BOILERPLATE.iter() {
+        page = boilerplate.replace_all(&page, "\n").into_owned();
+    }
+    // The biggest <article> or <main> when the page marks one, else the body
+    let scope = ARTICLE
+        .captures_iter(&page)
+        .map(|caps| caps.get(2).map_or("", |m| m.as_str()).to_string())
+        .max_by_key(|content| TAG.replace_all(content, "").len())
+        .filter(|content| TAG.replace_all(content, "").trim().len() >= MIN_BLOCK_CHARS)
+        .or_else(|| BODY.captures(&page).map(|caps| caps[1].to_string()))
+        .unwrap_or(page);
+
+    let marked = LINK.replace_all(&scope, |caps: &regex::Captures| format!("{}{}{}", LINK_START, &caps[1], LINK_END));
+    let marked = HEADING_OPEN.replace_all(&marked, format!("\n{}", HEADING));
+    let blocks = BLOCK.replace_all(&marked, "\n");
+    let text = decode_entities(&TAG.replace_all(&blocks, ""));
+
+    let mut kept = Vec::new();
+    for line in text.lines() {
+        let heading = line.contains(HEADING);
+        let mut link_chars = 0;
+        let mut in_link = false;
+        for ch in line.chars() {
+            match ch {
+                LINK_START => in_link = true,
+                LINK_END => in_link = false,
+                ch if in_link && !ch.is_whitespace() => link_chars += 1,
+                _ => {}
+            }
+        }
+        let line = collapse_whitespace(&line.replace([LINK_START, LINK_END, HEADING], ""));
+        let chars = line.chars().filter(|ch| !ch.is_whitespace()).count();
+        if chars == 0 || link_chars as f64 / chars as f64 > MAX_LINK_DENSITY {
+            continue;
+        }
+        let sentence = line.ends_with(['.', '!', '?', ':', '"', '”']);
+        if heading || sentence || line.chars().count() >= MIN_BLOCK_CHARS {
+            kept.push(line);
+        }
+    }
+    Article { title, text: kept.join("\n") }
+}
+
+/// Fetch a page's HTML, at most `max_bytes` of it. Returns the final URL after redirects too.
+pub async fn fetch(url: &str, max_bytes: usize) -> Result<(String, String), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only http and https URLs can be imported".to_string());
+    }
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(concat!("Enteract/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut response = client
+        .get(parsed)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to fetch page: {}", e))?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/html")
+        .to_lowercase();
+    if !(content_type.contains("html") || content_type.starts_with("text/")) {
+        return Err(format!("Not a web page: {}", content_type));
+    }
+    let final_url = response.url().to_string();
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Failed to read page: {}", e))? {
+        body.extend_from_slice(&chunk);
+        if body.len() > max_bytes {
+            return Err(format!("Page is larger than {} MB", max_bytes / (1024 * 1024)));
+        }
+    }
+    Ok((final_url, String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// A file name for the stored article
+pub fn file_name(title: Option<&str>, url: &str) -> String {
+    let base = title
+        .filter(|title| !title.trim().is_empty())
+        .map(str::to_string)
+        .or_else(|| reqwest::Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string)))
+        .unwrap_or_else(|| "web page".to_string());
+    let safe: String = base
+        .chars()
+        .map(|ch| if ch.is_alphanumeric() || matches!(ch, ' ' | '-' | '_' | '.') { ch } else { '_' })
+        .take(80)
+        .collect();
+    format!("{}.txt", safe.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_article_drops_boilerplate() {
+        let html = r#"<html><head><title>Ignored</title>
+            <meta property="og:title" content="Rust &amp; You">
+            <style>body { color: red }</style></head>
+            <body>
+              <header><a href="/">Home</a> <a href="/blog">Blog</a></header>
+              <nav><ul><li><a href="/a">Menu item</a></li></ul></nav>
+              <article>
+                <h1>Why ownership matters</h1>
+                <p>Ownership lets the compiler free memory without a garbage collector &mdash; at no runtime cost.</p>
+                <!-- tracking pixel -->
+                <p>See <a href="/docs">the book</a> for a longer explanation of borrowing rules.</p>
+                <ul><li><a href="/x">Related: lifetimes</a></li><li><a href="/y">Related: traits</a></li></ul>
+                <script>track();</script>
+              </article>
+              <aside>Subscribe to our newsletter for weekly updates about everything.</aside>
+              <footer>&copy; 2024</footer>
+            </body></html>"#;
+        let article = extract_article(html);
+        assert_eq!(article.title.as_deref(), Some("Rust & You"));
+        assert_eq!(
+            article.text,
+            "Why ownership matters\n\
+             Ownership lets the compiler free memory without a garbage collector — at no runtime cost.\n\
+             See the book for a longer explanation of borrowing rules."
+        );
+
+        // No <article>: the body is used, and &#NNN; entities decode
+        let article = extract_article("<body><div>Caf&#233; opening hours are posted at the door each week.</div></body>");
+        assert_eq!(article.title, None);
+        assert_eq!(article.text, "Café opening hours are posted at the door each week.");
+
+        assert_eq!(file_name(Some("A/B: notes?"), "https://example.com"), "A_B_ notes_.txt");
+        assert_eq!(file_name(None, "https://example.com/page"), "example.com.txt");
+    }
+}
@@ -0,0 +1,298 @@
+// Whisper inference backend selection - CPU or a GPU build of whisper.cpp (CUDA, Metal)
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+use std::time::Instant;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+// Length of the synthetic clip each backend transcribes when benchmarked
+const BENCHMARK_SECONDS: usize = 5;
+const WHISPER_SAMPLE_RATE: usize = 16000;
+
+lazy_static::lazy_static! {
+    static ref BACKEND_SETTINGS: RwLock<BackendSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref ACTIVE_BACKEND: Mutex<Option<WhisperBackend>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhisperBackend {
+    Cpu,
+    Cuda,
+    Metal,
+}
+
+impl WhisperBackend {
+    fn is_gpu(self) -> bool {
+        self != WhisperBackend::Cpu
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub backend: WhisperBackend,
+    pub model: String,
+    pub load_ms: u64,
+    pub transcribe_ms: u64,
+    /// Processing time divided by audio length; below 1.0 is faster than real time
+    pub real_time_factor: f64,
+    pub error: Option<String>,
+    pub measured_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct BackendSettings {
+    /// User override; `None` picks automatically
+    backend_override: Option<WhisperBackend>,
+    gpu_device: i32,
+    benchmarks: Vec<BenchmarkResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperBackendStatus {
+    pub selected: WhisperBackend,
+    pub reason: String,
+    /// Backend the loaded model is actually running on, if one is loaded
+    pub active: Option<WhisperBackend>,
+    pub available: Vec<WhisperBackend>,
+    pub backend_override: Option<WhisperBackend>,
+    pub gpu_device: i32,
+    pub gpu_layers: i32,
+    pub benchmarks: Vec<BenchmarkResult>,
+}
+
+const SETTINGS_FILE: &str = "whisper_backend.json";
+
+fn save_settings(settings: &BackendSettings) -> Result<(), String> {
+    save_json_settings(SETTINGS_FILE, "Whisper backend settings", settings)
+}
+
+/// Backends this binary was built with; GPU ones come from the `whisper-*` cargo features
+pub fn available_backends() -> Vec<WhisperBackend> {
+    let mut backends = vec![WhisperBackend::Cpu];
+    if cfg!(feature = "whisper-cuda") {
+        backends.push(WhisperBackend::Cuda);
+    }
+    if cfg!(feature = "whisper-metal") {
+        backends.push(WhisperBackend::Metal);
+    }
+    backends
+}
+
+fn primary_gpu_vendor() -> Option<String> {
+    crate::system_info::get_gpu_info()
+        .ok()?
+        .into_iter()
+        .map(|gpu| gpu.vendor)
+        .find(|vendor| vendor == "NVIDIA" || vendor == "AMD")
+}
+
+fn pick_backend(
+    backend_override: Option<WhisperBackend>,
+    available: &[WhisperBackend],
+    gpu_layers: i32,
+    gpu_vendor: Option<&str>,
+    is_macos: bool,
+) -> (WhisperBackend, String) {
+    if let Some(backend) = backend_override {
+        if available.contains(&backend) {
+            return (backend, "Selected in settings".to_string());
+        }
+    }
+
+    // Every Mac that runs the app has a Metal GPU; detect_gpu_layers only knows NVIDIA and AMD
+    if is_macos && available.contains(&WhisperBackend::Metal) {
+        return (WhisperBackend::Metal, "Apple GPU".to_string());
+    }
+
+    if gpu_layers > 0 {
+        if gpu_vendor == Some("NVIDIA") && available.contains(&WhisperBackend::Cuda) {
+            return (WhisperBackend::Cuda, "NVIDIA GPU detected".to_string());
+        }
+        return (WhisperBackend::Cpu, "GPU detected, but this build has no matching GPU backend".to_string());
+    }
+
+    let reason = match backend_override {
+        Some(backend) => format!("{:?} isn't available in this build", backend),
+        None => "No capable GPU detected".to_string(),
+    };
+    (WhisperBackend::Cpu, reason)
+}
+
+/// The backend `initialize_whisper_model` will use, and why
+pub fn select_backend() -> (WhisperBackend, String) {
+    let settings = read_settings(&BACKEND_SETTINGS);
+    pick_backend(
+        settings.backend_override,
+        &available_backends(),
+        crate::system_info::detect_gpu_layers(),
+        primary_gpu_vendor().as_deref(),
+        cfg!(target_os = "macos"),
+    )
+}
+
+fn load_context(model_path: &Path, backend: WhisperBackend, gpu_device: i32) -> Result<WhisperContext, String> {
+    let mut params = WhisperContextParameters::default();
+    params.use_gpu(backend.is_gpu()).gpu_device(gpu_device);
+    WhisperContext::new_with_params(model_path.to_str().ok_or("Invalid model path")?, params)
+        .map_err(|e| format!("Failed to initialize Whisper context: {}", e))
+}
+
+/// Load a model on the selected backend, falling back to the CPU if the GPU can't take it
+pub fn create_context(model_path: &Path) -> Result<(WhisperContext, WhisperBackend), String> {
+    let (backend, reason) = select_backend();
+    println!("[WHISPER] Using {:?} backend ({})", backend, reason);
+
+    let result = match load_context(model_path, backend, read_settings(&BACKEND_SETTINGS).gpu_device) {
+        Ok(ctx) => Ok((ctx, backend)),
+        Err(e) if backend.is_gpu() => {
+            eprintln!("[WHISPER] {:?} backend failed, falling back to CPU: {}", backend, e);
+            load_context(model_path, WhisperBackend::Cpu, 0).map(|ctx| (ctx, WhisperBackend::Cpu))
+        }
+        Err(e) => Err(e),
+    };
+
+    if let Ok((_, backend)) = &result {
+        if let Ok(mut active) = ACTIVE_BACKEND.lock() {
+            *active = Some(*backend);
+        }
+    }
+    result
+}
+
+fn run_benchmark(model_path: &Path, model: &str, backend: WhisperBackend, gpu_device: i32) -> BenchmarkResult {
+    let mut result = BenchmarkResult {
+        backend,
+        model: model.to_string(),
+        load_ms: 0,
+        transcribe_ms: 0,
+        real_time_factor: 0.0,
+        error: None,
+        measured_at: chrono::Utc::now().timestamp_millis(),
+    };
+
+    let started = Instant::now();
+    let ctx = match load_context(model_path, backend, gpu_device) {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    result.load_ms = started.elapsed().as_millis() as u64;
+
+    // A quiet tone keeps the decoder busy without depending on a bundled audio file
+    let audio: Vec<f32> = (0..BENCHMARK_SECONDS * WHISPER_SAMPLE_RATE)
+        .map(|i| (i as f32 * 220.0 * std::f32::consts::TAU / WHISPER_SAMPLE_RATE as f32).sin() * 0.05)
+        .collect();
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some("en"));
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+
+    let started = Instant::now();
+    let outcome = ctx
+        .create_state()
+        .and_then(|mut state| state.full(params, &audio).map(|_| ()));
+    result.transcribe_ms = started.elapsed().as_millis() as u64;
+    result.real_time_factor = result.transcribe_ms as f64 / (BENCHMARK_SECONDS * 1000) as f64;
+    if let Err(e) = outcome {
+        result.error = Some(format!("Benchmark transcription failed: {}", e));
+    }
+    result
+}
+
+#[crate::command]
+pub fn get_whisper_backend() -> Result<WhisperBackendStatus, String> {
+    let settings = read_settings(&BACKEND_SETTINGS);
+    let (selected, reason) = select_backend();
+    Ok(WhisperBackendStatus {
+        selected,
+        reason,
+        active: ACTIVE_BACKEND.lock().ok().and_then(|active| *active),
+        available: available_backends(),
+        backend_override: settings.backend_override,
+        gpu_device: settings.gpu_device,
+        gpu_layers: crate::system_info::detect_gpu_layers(),
+        benchmarks: settings.benchmarks,
+    })
+}
+
+/// Override the automatic choice (`None` restores it). The loaded model is dropped so
+/// the next transcription reloads it on the new backend.
+#[crate::command]
+pub fn set_whisper_backend(backend: Option<WhisperBackend>, gpu_device: Option<i32>) -> Result<WhisperBackendStatus, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    if let Some(backend) = backend {
+        if !available_backends().contains(&backend) {
+            return Err(format!("{:?} support isn't compiled into this build", backend));
+        }
+    }
+
+    {
+        let mut settings = BACKEND_SETTINGS
+            .write()
+            .map_err(|_| "Failed to update Whisper backend settings".to_string())?;
+        settings.backend_override = backend;
+        if let Some(gpu_device) = gpu_device {
+            settings.gpu_device = gpu_device.max(0);
+        }
+        save_settings(&settings)?;
+    }
+
+    *crate::speech::WHISPER_CONTEXT.lock().map_err(|_| "Whisper context lock poisoned".to_string())? = None;
+    if let Ok(mut active) = ACTIVE_BACKEND.lock() {
+        *active = None;
+    }
+    get_whisper_backend()
+}
+
+/// Time model load and a short transcription on every available backend
+#[crate::command]
+pub async fn benchmark_whisper_backends(app_handle: tauri::AppHandle, model_size: String) -> Result<Vec<BenchmarkResult>, String> {
+    let model_path = crate::whisper_models::ensure_model(Some(&app_handle), &model_size).await?;
+    let gpu_device = read_settings(&BACKEND_SETTINGS).gpu_device;
+
+    let results = tokio::task::spawn_blocking(move || {
+        available_backends()
+            .into_iter()
+            .map(|backend| run_benchmark(&model_path, &model_size, backend, gpu_device))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Benchmark task failed: {}", e))?;
+
+    let mut settings = BACKEND_SETTINGS
+        .write()
+        .map_err(|_| "Failed to update Whisper backend settings".to_string())?;
+    // Keep only the latest result per backend and model
+    settings.benchmarks.retain(|old| !results.iter().any(|new| new.backend == old.backend && new.model == old.model));
+    settings.benchmarks.extend(results.iter().cloned());
+    save_settings(&settings)?;
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use WhisperBackend::*;
+
+    #[test]
+    fn test_pick_backend() {
+        let all = [Cpu, Cuda, Metal];
+        assert_eq!(pick_backend(None, &all, 40, Some("NVIDIA"), false).0, Cuda);
+        // No AMD backend is built
+        assert_eq!(pick_backend(None, &all, 20, Some("AMD"), false).0, Cpu);
+        assert_eq!(pick_backend(None, &all, 0, None, true).0, Metal);
+        assert_eq!(pick_backend(None, &all, 0, None, false).0, Cpu);
+        // Overrides win, but only for backends the build actually has
+        assert_eq!(pick_backend(Some(Cpu), &all, 40, Some("NVIDIA"), false).0, Cpu);
+        assert_eq!(pick_backend(Some(Cuda), &[Cpu], 40, Some("NVIDIA"), false).0, Cpu);
+    }
+}
@@ -0,0 +1,539 @@
+// Whisper model manager - quantized variants, resumable verified downloads and a models.json registry
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::action_registry::{descriptor, to_value, ActionKind, RegisteredAction};
+
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+const REGISTRY_FILE: &str = "models.json";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+static MODELS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+    static ref DOWNLOADS_IN_PROGRESS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    static ref LOADED_MODEL: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// A downloadable model file. `id` is what the frontend passes as `modelSize`.
+struct ModelSpec {
+    id: &'static str,
+    base: &'static str,
+    quantization: Option<&'static str>,
+    file_stem: &'static str,
+    approx_size_mb: u32,
+}
+
+const CATALOG: &[ModelSpec] = &[
+    ModelSpec { id: "tiny", base: "tiny", quantization: None, file_stem: "tiny", approx_size_mb: 75 },
+    ModelSpec { id: "tiny-q5_1", base: "tiny", quantization: Some("q5_1"), file_stem: "tiny-q5_1", approx_size_mb: 31 },
+    ModelSpec { id: "tiny-q8_0", base: "tiny", quantization: Some("q8_0"), file_stem: "tiny-q8_0", approx_size_mb: 42 },
+    ModelSpec { id: "base", base: "base", quantization: None, file_stem: "base", approx_size_mb: 142 },
+    ModelSpec { id: "base-q5_1", base: "base", quantization: Some("q5_1"), file_stem: "base-q5_1", approx_size_mb: 57 },
+    ModelSpec { id: "base-q8_0", base: "base", quantization: Some("q8_0"), file_stem: "base-q8_0", approx_size_mb: 78 },
+    ModelSpec { id: "small", base: "small", quantization: None, file_stem: "small", approx_size_mb: 466 },
+    ModelSpec { id: "small-q5_1", base: "small", quantization: Some("q5_1"), file_stem: "small-q5_1", approx_size_mb: 181 },
+    ModelSpec { id: "small-q8_0", base: "small", quantization: Some("q8_0"), file_stem: "small-q8_0", approx_size_mb: 252 },
+    ModelSpec { id: "medium", base: "medium", quantization: None, file_stem: "medium", approx_size_mb: 1500 },
+    ModelSpec { id: "medium-q5_0", base: "medium", quantization: Some("q5_0"), file_stem: "medium-q5_0", approx_size_mb: 514 },
+    ModelSpec { id: "medium-q8_0", base: "medium", quantization: Some("q8_0"), file_stem: "medium-q8_0", approx_size_mb: 785 },
+    // There is no plain ggml-large.bin upstream; "large" means the latest large release
+    ModelSpec { id: "large", base: "large", quantization: None, file_stem: "large-v3", approx_size_mb: 2900 },
+    ModelSpec { id: "large-q5_0", base: "large", quantization: Some("q5_0"), file_stem: "large-v3-q5_0", approx_size_mb: 1080 },
+];
+
+fn find_spec(model_id: &str) -> Result<&'static ModelSpec, String> {
+    CATALOG
+        .iter()
+        .find(|spec| spec.id == model_id)
+        .ok_or_else(|| format!("Unknown Whisper model '{}'", model_id))
+}
+
+fn file_name(spec: &ModelSpec) -> String {
+    format!("ggml-{}.bin", spec.file_stem)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledModel {
+    file_name: String,
+    size_bytes: u64,
+    sha256: String,
+    downloaded_at: i64,
+    last_used_at: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ModelRegistry {
+    models: HashMap<String, InstalledModel>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperModelInfo {
+    pub id: String,
+    pub base: String,
+    pub quantization: Option<String>,
+    pub approx_size_mb: u32,
+    pub installed: bool,
+    pub size_bytes: u64,
+    /// Bytes of an interrupted download that the next download will resume from
+    pub partial_bytes: u64,
+    pub last_used_at: Option<i64>,
+    pub loaded: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDiskUsage {
+    pub models_dir: String,
+    pub installed_bytes: u64,
+    pub partial_bytes: u64,
+    pub installed_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadProgress<'a> {
+    model_id: &'a str,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+}
+
+/// Point the manager at app_data_dir, adopting models cached by older builds in the temp dir
+pub fn init(app_handle: &AppHandle) {
+    let dir = match app_handle.path().app_data_dir() {
+        Ok(dir) => dir.join("whisper_models"),
+        Err(e) => {
+            eprintln!("[WHISPER_MODELS] No app data directory, using temp dir: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = fs::create_dir_all(&dir) {
+        eprintln!("[WHISPER_MODELS] Failed to create models directory: {}", e);
+        return;
+    }
+
+    let legacy_dir = legacy_models_dir();
+    if let Ok(entries) = fs::read_dir(&legacy_dir) {
+        for entry in entries.flatten() {
+            let target = dir.join(entry.file_name());
+            let is_model = entry.file_name().to_string_lossy().ends_with(".bin");
+            // A rename across filesystems fails; such models are simply downloaded again
+            if is_model && !target.exists() && fs::rename(entry.path(), &target).is_ok() {
+                println!("[WHISPER_MODELS] Moved {:?} into {:?}", entry.file_name(), dir);
+            }
+        }
+    }
+
+    let _ = MODELS_DIR.set(dir);
+}
+
+fn legacy_models_dir() -> PathBuf {
+    std::env::temp_dir().join("enteract").join("whisper_models")
+}
+
+fn models_dir() -> PathBuf {
+    MODELS_DIR.get().cloned().unwrap_or_else(legacy_models_dir)
+}
+
+fn registry_path() -> PathBuf {
+    models_dir().join(REGISTRY_FILE)
+}
+
+fn load_registry() -> ModelRegistry {
+    fs::read_to_string(registry_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_registry(registry: &ModelRegistry) -> Result<(), String> {
+    let path = registry_path();
+    let json = serde_json::to_string_pretty(registry)
+        .map_err(|e| format!("Failed to serialize model registry: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json).map_err(|e| format!("Failed to write model registry: {}", e))?;
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to write model registry: {}", e))
+}
+
+fn update_registry<F: FnOnce(&mut ModelRegistry)>(f: F) -> Result<(), String> {
+    let _guard = REGISTRY_LOCK.lock().map_err(|_| "Model registry lock poisoned".to_string())?;
+    let mut registry = load_registry();
+    f(&mut registry);
+    save_registry(&registry)
+}
+
+fn part_path(model_path: &Path) -> PathBuf {
+    let mut name = model_path.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Where a model's file lives, whether or not it has been downloaded
+pub fn model_path(model_id: &str) -> Result<PathBuf, String> {
+    Ok(models_dir().join(file_name(find_spec(model_id)?)))
+}
+
+/// Remember which model the Whisper context holds so it isn't deleted underneath it
+pub fn mark_loaded(model_id: &str) {
+    if let Ok(mut loaded) = LOADED_MODEL.lock() {
+        *loaded = Some(model_id.to_string());
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let _ = update_registry(|registry| {
+        if let Some(model) = registry.models.get_mut(model_id) {
+            model.last_used_at = now;
+        }
+    });
+}
+
+fn loaded_model() -> Option<String> {
+    LOADED_MODEL.lock().ok().and_then(|loaded| loaded.clone())
+}
+
+/// Return a verified local copy of the model, downloading (or resuming) it if needed
+pub async fn ensure_model(app_handle: Option<&AppHandle>, model_id: &str) -> Result<PathBuf, String> {
+    let spec = find_spec(model_id)?;
+    let path = models_dir().join(file_name(spec));
+
+    if let Some(installed) = load_registry().models.get(model_id) {
+        if file_len(&path) == installed.size_bytes {
+            return Ok(path);
+        }
+        println!("[WHISPER_MODELS] '{}' doesn't match its registry entry, downloading again", model_id);
+    } else if path.exists() {
+        // Cached before the registry existed; verify it against upstream before trusting it
+        let expected = fetch_expected(&model_url(spec)).await.ok();
+        let actual = hash_file(path.clone()).await?;
+        if expected.as_ref().and_then(|e| e.sha256.as_ref()).map_or(true, |sha| *sha == actual) {
+            register(model_id, spec, file_len(&path), actual)?;
+            return Ok(path);
+        }
+        let _ = fs::remove_file(&path);
+    }
+
+    {
+        let mut downloads = DOWNLOADS_IN_PROGRESS.lock().map_err(|_| "Download lock poisoned".to_string())?;
+        if !downloads.insert(model_id.to_string()) {
+            return Err(format!("Model '{}' is already downloading", model_id));
+        }
+    }
+    let result = download(app_handle, model_id, spec, &path).await;
+    if let Ok(mut downloads) = DOWNLOADS_IN_PROGRESS.lock() {
+        downloads.remove(model_id);
+    }
+    result.map(|_| path)
+}
+
+fn model_url(spec: &ModelSpec) -> String {
+    format!("{}/{}", MODEL_BASE_URL, file_name(spec))
+}
+
+struct ExpectedFile {
+    sha256: Option<String>,
+    size: Option<u64>,
+}
+
+/// Hugging Face reports an LFS file's SHA-256 and size on the redirect response
+async fn fetch_expected(url: &str) -> Result<ExpectedFile, String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = client.head(url).send().await
+        .map_err(|e| format!("Failed to query model metadata: {}", e))?;
+    if response.status().is_client_error() || response.status().is_server_error() {
+        return Err(format!("Failed to query model metadata: HTTP {}", response.status()));
+    }
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim_matches('"').to_string())
+    };
+    Ok(ExpectedFile {
+        sha256: header("x-linked-etag").filter(|sha| sha.len() == 64),
+        size: header("x-linked-size").and_then(|size| size.parse().ok()),
+    })
+}
+
+async fn download(app_handle: Option<&AppHandle>, model_id: &str, spec: &ModelSpec, path: &Path) -> Result<(), String> {
+    crate::offline_mode::require_online(crate::offline_mode::RemoteFeature::ModelDownload)?;
+    fs::create_dir_all(models_dir()).map_err(|e| format!("Failed to create models directory: {}", e))?;
+    let url = model_url(spec);
+    let expected = fetch_expected(&url).await?;
+    let part = part_path(path);
+
+    let mut offset = file_len(&part);
+    if expected.size.map_or(false, |size| offset > size) {
+        offset = 0;
+    }
+    let needed = expected
+        .size
+        .map(|size| size - offset)
+        .unwrap_or(spec.approx_size_mb as u64 * 1024 * 1024);
+    crate::disk_space::preflight(crate::disk_space::Subsystem::Models, needed)?;
+
+    println!("Downloading Whisper model '{}' from: {} (resuming at {} bytes)", model_id, url, offset);
+    let mut request = reqwest::Client::new().get(&url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let response = request.send().await.map_err(|e| format!("Failed to download model: {}", e))?;
+
+    let status = response.status();
+    let range_done = status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && Some(offset) == expected.size;
+    if !status.is_success() && !range_done {
+        return Err(format!("Failed to download model: HTTP {}", status));
+    }
+
+    if !range_done {
+        // A plain 200 means the server ignored the range and is sending the whole file
+        let resume = status == reqwest::StatusCode::PARTIAL_CONTENT;
+        if !resume {
+            offset = 0;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resume)
+            .truncate(!resume)
+            .open(&part)
+            .map_err(|e| format!("Failed to open partial model file: {}", e))?;
+
+        let total = expected.size.or_else(|| response.content_length().map(|len| len + offset));
+        let mut downloaded = offset;
+        let mut last_progress = Instant::now();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("Model download interrupted: {}", e))?;
+            file.write_all(&chunk).map_err(|e| format!("Failed to save model: {}", e))?;
+            downloaded += chunk.len() as u64;
+
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                last_progress = Instant::now();
+                if let Some(app_handle) = app_handle {
+                    let _ = app_handle.emit("whisper-model-download-progress", DownloadProgress {
+                        model_id,
+                        downloaded_bytes: downloaded,
+                        total_bytes: total,
+                    });
+                }
+            }
+        }
+        file.flush().map_err(|e| format!("Failed to save model: {}", e))?;
+    }
+
+    let size = file_len(&part);
+    if let Some(expected_size) = expected.size {
+        if size != expected_size {
+            return Err(format!("Model download incomplete: {} of {} bytes", size, expected_size));
+        }
+    }
+
+    let sha256 = hash_file(part.clone()).await?;
+    if let Some(expected_sha) = &expected.sha256 {
+        if *expected_sha != sha256 {
+            let _ = fs::remove_file(&part);
+            return Err(format!("Checksum mismatch for model '{}'; the download was discarded", model_id));
+        }
+    }
+
+    fs::rename(&part, path).map_err(|e| format!("Failed to save model: {}", e))?;
+    register(model_id, spec, size, sha256)?;
+    println!("Successfully downloaded Whisper model '{}' to: {:?}", model_id, path);
+    Ok(())
+}
+
+fn register(model_id: &str, spec: &ModelSpec, size_bytes: u64, sha256: String) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    update_registry(|registry| {
+        registry.models.insert(model_id.to_string(), InstalledModel {
+            file_name: file_name(spec),
+            size_bytes,
+            sha256,
+            downloaded_at: now,
+            last_used_at: now,
+        });
+    })
+}
+
+async fn hash_file(path: PathBuf) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut file = fs::File::open(&path).map_err(|e| format!("Failed to open model file: {}", e))?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 1 << 20];
+        loop {
+            let read = file.read(&mut buffer).map_err(|e| format!("Failed to read model file: {}", e))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(|e| format!("Checksum task failed: {}", e))?
+}
+
+fn remove_model_files(model_id: &str) -> Result<u64, String> {
+    let path = model_path(model_id)?;
+    let part = part_path(&path);
+    let freed = file_len(&path) + file_len(&part);
+    for file in [&path, &part] {
+        if file.exists() {
+            fs::remove_file(file).map_err(|e| format!("Failed to delete {:?}: {}", file, e))?;
+        }
+    }
+    update_registry(|registry| {
+        registry.models.remove(model_id);
+    })?;
+    Ok(freed)
+}
+
+/// Least recently used models to delete until the total fits in `max_bytes`
+fn eviction_candidates(registry: &ModelRegistry, max_bytes: u64, keep: Option<&str>) -> Vec<String> {
+    let mut total: u64 = registry.models.values().map(|m| m.size_bytes).sum();
+    let mut by_age: Vec<(&String, &InstalledModel)> = registry
+        .models
+        .iter()
+        .filter(|(id, _)| Some(id.as_str()) != keep)
+        .collect();
+    by_age.sort_by_key(|(_, model)| model.last_used_at);
+
+    let mut evicted = Vec::new();
+    for (id, model) in by_age {
+        if total <= max_bytes {
+            break;
+        }
+        total -= model.size_bytes;
+        evicted.push(id.clone());
+    }
+    evicted
+}
+
+#[crate::command]
+pub fn list_whisper_models() -> Result<Vec<WhisperModelInfo>, String> {
+    let registry = load_registry();
+    let loaded = loaded_model();
+    let dir = models_dir();
+
+    Ok(CATALOG
+        .iter()
+        .map(|spec| {
+            let installed = registry.models.get(spec.id);
+            WhisperModelInfo {
+                id: spec.id.to_string(),
+                base: spec.base.to_string(),
+                quantization: spec.quantization.map(str::to_string),
+                approx_size_mb: spec.approx_size_mb,
+                installed: installed.is_some(),
+                size_bytes: installed.map(|m| m.size_bytes).unwrap_or(0),
+                partial_bytes: file_len(&part_path(&dir.join(file_name(spec)))),
+                last_used_at: installed.map(|m| m.last_used_at),
+                loaded: loaded.as_deref() == Some(spec.id),
+            }
+        })
+        .collect())
+}
+
+#[crate::command]
+pub fn get_whisper_models_disk_usage() -> Result<ModelDiskUsage, String> {
+    let registry = load_registry();
+    let dir = models_dir();
+    let partial_bytes = CATALOG
+        .iter()
+        .map(|spec| file_len(&part_path(&dir.join(file_name(spec)))))
+        .sum();
+
+    Ok(ModelDiskUsage {
+        models_dir: dir.to_string_lossy().to_string(),
+        installed_bytes: registry.models.values().map(|m| m.size_bytes).sum(),
+        partial_bytes,
+        installed_count: registry.models.len(),
+    })
+}
+
+/// Delete a model and any partial download of it; returns the bytes freed
+#[crate::command]
+pub fn delete_whisper_model(model_id: String) -> Result<u64, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::DataDeletion)?;
+    if loaded_model().as_deref() == Some(model_id.as_str()) {
+        return Err(format!("Model '{}' is in use by the transcription engine", model_id));
+    }
+    remove_model_files(&model_id)
+}
+
+/// Delete least recently used models until they fit in `max_bytes`; the loaded model is kept
+#[crate::command]
+pub fn evict_whisper_models(max_bytes: u64) -> Result<Vec<String>, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::DataDeletion)?;
+    let evicted = eviction_candidates(&load_registry(), max_bytes, loaded_model().as_deref());
+    for model_id in &evicted {
+        remove_model_files(model_id)?;
+    }
+    Ok(evicted)
+}
+
+pub fn catalog_ids() -> Vec<String> {
+    CATALOG.iter().map(|spec| spec.id.to_string()).collect()
+}
+
+/// Command palette actions for transcription models
+pub(crate) fn actions() -> Vec<RegisteredAction> {
+    vec![RegisteredAction {
+        descriptor: descriptor("whisper.list_models", ActionKind::Command, "List Whisper Models",
+            "Show installed and available transcription models", vec![], &["speech", "transcription"]),
+        handler: |_, _| Box::pin(async move { to_value(list_whisper_models()?) }),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(size_bytes: u64, last_used_at: i64) -> InstalledModel {
+        InstalledModel {
+            file_name: String::new(),
+            size_bytes,
+            sha256: String::new(),
+            downloaded_at: 0,
+            last_used_at,
+        }
+    }
+
+    #[test]
+    fn test_catalog_variants() {
+        assert_eq!(file_name(find_spec("large").unwrap()), "ggml-large-v3.bin");
+        assert_eq!(find_spec("small-q8_0").unwrap().quantization, Some("q8_0"));
+        assert!(find_spec("../large").is_err());
+    }
+
+    #[test]
+    fn test_eviction_keeps_loaded_and_recent_models() {
+        let mut registry = ModelRegistry::default();
+        registry.models.insert("tiny".to_string(), installed(100, 1));
+        registry.models.insert("base".to_string(), installed(200, 2));
+        registry.models.insert("small".to_string(), installed(500, 0));
+
+        // "small" is the oldest but loaded, so the next oldest go first
+        assert_eq!(eviction_candidates(&registry, 550, Some("small")), vec!["tiny", "base"]);
+        assert_eq!(eviction_candidates(&registry, 300, None), vec!["small"]);
+        assert!(eviction_candidates(&registry, 1000, None).is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use tauri::Window;
 use tauri::{PhysicalPosition, PhysicalSize};
 
-#[tauri::command]
+#[crate::command]
 pub async fn move_window_to_position(window: Window, x: i32, y: i32) -> Result<(), String> {
     let position = PhysicalPosition::new(x, y);
     window.set_position(position).map_err(|e| e.to_string())?;
@@ -9,19 +9,19 @@ pub async fn move_window_to_position(window: Window, x: i32, y: i32) -> Result<(
     Ok(())
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_window_position(window: Window) -> Result<(i32, i32), String> {
     let position = window.outer_position().map_err(|e| e.to_string())?;
     Ok((position.x, position.y))
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_window_size(window: Window) -> Result<(u32, u32), String> {
     let size = window.outer_size().map_err(|e| e.to_string())?;
     Ok((size.width, size.height))
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_screen_size() -> Result<(u32, u32), String> {
     // Get primary monitor size
     #[cfg(target_os = "windows")]
@@ -66,7 +66,7 @@ pub struct MonitorInfo {
     pub name: String,
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_monitor_layout() -> Result<Vec<MonitorInfo>, String> {
     let mut monitors = Vec::new();
     
@@ -122,7 +122,7 @@ pub async fn get_monitor_layout() -> Result<Vec<MonitorInfo>, String> {
     Ok(monitors)
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn get_virtual_desktop_size() -> Result<(u32, u32), String> {
     // Get full virtual desktop size (all monitors combined)
     #[cfg(target_os = "windows")]
@@ -170,7 +170,7 @@ pub async fn get_virtual_desktop_size() -> Result<(u32, u32), String> {
     }
 }
 
-#[tauri::command]
+#[crate::command]
 pub async fn set_window_bounds(window: Window, x: i32, y: i32, width: u32, height: u32) -> Result<(), String> {
     let position = PhysicalPosition::new(x, y);
     let size = PhysicalSize::new(width, height);
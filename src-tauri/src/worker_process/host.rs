@@ -0,0 +1,69 @@
+// The worker side: a copy of the app started with `--worker <kind>` that serves requests from stdin
+// until the app closes the pipe. A native crash here ends only this process.
+use std::io::{BufRead, Write};
+
+use super::protocol::{decode_audio, decode_line, encode_line, RequestEnvelope, ResponseEnvelope, WorkerRequest, WorkerResponse};
+use super::WorkerKind;
+
+/// Serve requests until stdin closes; returns the process exit code
+pub fn run(kind: WorkerKind) -> i32 {
+    let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("[WORKER] Failed to start runtime: {}", e);
+            return 1;
+        }
+    };
+    eprintln!("[WORKER] {} worker started (pid {})", kind.label(), std::process::id());
+
+    let stdin = std::io::stdin();
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else { break };
+        let Some(envelope) = decode_line::<RequestEnvelope>(&line) else { continue };
+        let response = match envelope {
+            Ok(envelope) => ResponseEnvelope {
+                id: envelope.id,
+                result: runtime.block_on(handle(kind, envelope.request)),
+            },
+            Err(e) => ResponseEnvelope { id: 0, result: Err(e) },
+        };
+        let written = encode_line(&response).and_then(|line| {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(line.as_bytes()).and_then(|_| stdout.flush()).map_err(|e| e.to_string())
+        });
+        if let Err(e) = written {
+            eprintln!("[WORKER] Failed to write response: {}", e);
+            return 1;
+        }
+    }
+    0
+}
+
+async fn handle(kind: WorkerKind, request: WorkerRequest) -> Result<WorkerResponse, String> {
+    match (kind, request) {
+        (_, WorkerRequest::Ping) => Ok(WorkerResponse::Pong),
+        (WorkerKind::Transcription, WorkerRequest::Transcribe { audio, config, candidates }) => {
+            let audio = decode_audio(&audio)?;
+            crate::speech::ensure_whisper_context(None, &config).await?;
+            crate::speech::run_whisper(&audio, &config, &candidates).map(WorkerResponse::Transcription)
+        }
+        #[cfg(feature = "mcp")]
+        (WorkerKind::Ocr, WorkerRequest::FindText { image_base64, target_text, confidence_threshold, case_sensitive, fuzzy, similarity_threshold, language }) => {
+            let matching = crate::mcp::text_match::TextMatchOptions {
+                case_sensitive,
+                fuzzy,
+                similarity_threshold: similarity_threshold.unwrap_or(0.8),
+            };
+            crate::mcp::tools::find_text_in_process(&image_base64, &target_text, confidence_threshold, &matching, language.as_deref())
+                .await
+                .map(WorkerResponse::TextLocations)
+        }
+        #[cfg(feature = "mcp")]
+        (WorkerKind::Ocr, WorkerRequest::ScanText { image_base64, confidence_threshold, show_all }) => {
+            crate::mcp::tools::scan_text_in_process(&image_base64, confidence_threshold, show_all)
+                .await
+                .map(WorkerResponse::TextLocations)
+        }
+        (kind, request) => Err(format!("The {} worker can't handle {}", kind.label(), request.method())),
+    }
+}
@@ -0,0 +1,432 @@
+// Worker processes for crash-prone native code - Whisper transcription and OCR
+//
+// When isolation is on for a subsystem, its requests go to a copy of this executable started with
+// `--worker <kind>`. A fault in whisper.cpp or the OCR engine then fails the request in flight
+// instead of taking down the app, and the worker is started again straight away. Workers that keep
+// crashing are left stopped for a while rather than respawned in a loop.
+pub mod protocol;
+mod host;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+
+use crate::speech::{TranscriptionResult, WhisperModelConfig};
+use protocol::{decode_line, encode_audio, encode_line, RequestEnvelope, ResponseEnvelope, WorkerRequest, WorkerResponse};
+use crate::json_settings::{load_json_settings, read_settings, save_json_settings};
+
+pub const WORKER_FLAG: &str = "--worker";
+
+const START_TIMEOUT: Duration = Duration::from_secs(15);
+const CRASH_WINDOW: Duration = Duration::from_secs(120);
+// This many crashes inside the window stops respawning until the oldest one ages out
+const MAX_CRASHES_IN_WINDOW: usize = 3;
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<WorkerProcessSettings> = RwLock::new(load_json_settings(SETTINGS_FILE));
+    static ref WORKERS: HashMap<WorkerKind, tokio::sync::Mutex<Option<RunningWorker>>> = WorkerKind::ALL
+        .iter()
+        .map(|kind| (*kind, tokio::sync::Mutex::new(None)))
+        .collect();
+    static ref HEALTH: Mutex<HashMap<WorkerKind, WorkerHealth>> = Mutex::new(HashMap::new());
+    static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkerKind {
+    Transcription,
+    Ocr,
+}
+
+impl WorkerKind {
+    pub const ALL: [WorkerKind; 2] = [WorkerKind::Transcription, WorkerKind::Ocr];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WorkerKind::Transcription => "transcription",
+            WorkerKind::Ocr => "ocr",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.label() == label)
+    }
+
+    // Long recordings can take a while on CPU; anything past this is treated as a hang
+    fn request_timeout(self) -> Duration {
+        match self {
+            WorkerKind::Transcription => Duration::from_secs(180),
+            WorkerKind::Ocr => Duration::from_secs(30),
+        }
+    }
+}
+
+/// Which subsystems run out of process. Both default to in-process.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkerProcessSettings {
+    pub transcription: bool,
+    pub ocr: bool,
+}
+
+impl WorkerProcessSettings {
+    fn isolates(&self, kind: WorkerKind) -> bool {
+        match kind {
+            WorkerKind::Transcription => self.transcription,
+            WorkerKind::Ocr => self.ocr,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct WorkerHealth {
+    pid: Option<u32>,
+    starts: u32,
+    crashes: VecDeque<Instant>,
+    last_crash: Option<String>,
+    last_crash_at: Option<i64>,
+}
+
+impl WorkerHealth {
+    fn respawn_pause(&mut self, now: Instant) -> Option<Duration> {
+        while self.crashes.front().map_or(false, |at| now.duration_since(*at) >= CRASH_WINDOW) {
+            self.crashes.pop_front();
+        }
+        if self.crashes.len() < MAX_CRASHES_IN_WINDOW {
+            return None;
+        }
+        self.crashes.front().map(|oldest| CRASH_WINDOW - now.duration_since(*oldest))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerProcessStatus {
+    pub kind: WorkerKind,
+    pub isolated: bool,
+    pub running: bool,
+    pub pid: Option<u32>,
+    /// Starts after the first one
+    pub restarts: u32,
+    pub recent_crashes: usize,
+    pub last_crash: Option<String>,
+    pub last_crash_at: Option<i64>,
+    /// Set while respawning is paused after repeated crashes
+    pub respawn_paused_seconds: Option<u64>,
+}
+
+struct RunningWorker {
+    kind: WorkerKind,
+    // Dropping the worker kills the process
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    next_id: u64,
+}
+
+impl RunningWorker {
+    async fn spawn(kind: WorkerKind) -> Result<Self, String> {
+        let executable = std::env::current_exe().map_err(|e| format!("Failed to locate the app executable: {}", e))?;
+        let mut child = tokio::process::Command::new(executable)
+            .arg(WORKER_FLAG)
+            .arg(kind.label())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start {} worker: {}", kind.label(), e))?;
+        let stdin = child.stdin.take().ok_or("Worker stdin unavailable")?;
+        let stdout = child.stdout.take().ok_or("Worker stdout unavailable")?;
+
+        let mut worker = Self { kind, child, stdin, stdout: BufReader::new(stdout).lines(), next_id: 1 };
+        // Make sure it came up before it's handed real work
+        worker.round_trip(WorkerRequest::Ping, START_TIMEOUT).await??;
+        Ok(worker)
+    }
+
+    /// The outer error means the worker itself failed (crash, hang, garbled output); the inner
+    /// result is the request's own outcome
+    async fn round_trip(&mut self, request: WorkerRequest, limit: Duration) -> Result<Result<WorkerResponse, String>, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let line = encode_line(&RequestEnvelope { id, request })?;
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+        self.stdin.flush().await.map_err(|e| format!("Failed to send request: {}", e))?;
+
+        let kind = self.kind;
+        let stdout = &mut self.stdout;
+        let response = async move {
+            loop {
+                let line = match stdout.next_line().await {
+                    Ok(Some(line)) => line,
+                    Ok(None) => return Err("the process exited".to_string()),
+                    Err(e) => return Err(format!("failed to read its output: {}", e)),
+                };
+                match decode_line::<ResponseEnvelope>(&line) {
+                    // Native libraries log to stdout; pass that through
+                    None => println!("[WORKER:{}] {}", kind.label(), line),
+                    Some(Ok(envelope)) if envelope.id == id => return Ok(envelope.result),
+                    // A reply to a request that already timed out
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(e),
+                }
+            }
+        };
+        tokio::time::timeout(limit, response)
+            .await
+            .map_err(|_| format!("no response within {}s", limit.as_secs()))?
+    }
+
+    async fn shut_down(&mut self) -> String {
+        let _ = self.child.start_kill();
+        match tokio::time::timeout(Duration::from_secs(2), self.child.wait()).await {
+            Ok(Ok(status)) => status.to_string(),
+            _ => "exit status unknown".to_string(),
+        }
+    }
+}
+
+const SETTINGS_FILE: &str = "worker_processes.json";
+
+/// Keep the app handle for crash events
+pub fn init(app_handle: &AppHandle) {
+    if let Ok(mut handle) = APP_HANDLE.lock() {
+        *handle = Some(app_handle.clone());
+    }
+}
+
+/// The worker kind when this process was started as a worker
+pub fn worker_from_args() -> Option<WorkerKind> {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some(WORKER_FLAG) => args.next().as_deref().and_then(WorkerKind::from_label),
+        _ => None,
+    }
+}
+
+/// Entry point of a worker process; returns its exit code
+pub fn run_worker(kind: WorkerKind) -> i32 {
+    host::run(kind)
+}
+
+pub fn is_isolated(kind: WorkerKind) -> bool {
+    read_settings(&SETTINGS).isolates(kind)
+}
+
+fn with_health<T>(kind: WorkerKind, f: impl FnOnce(&mut WorkerHealth) -> T) -> Option<T> {
+    HEALTH.lock().ok().map(|mut health| f(health.entry(kind).or_default()))
+}
+
+fn record_crash(kind: WorkerKind, reason: &str) {
+    eprintln!("❌ {} worker failed: {}", kind.label(), reason);
+    let paused = with_health(kind, |health| {
+        health.pid = None;
+        health.crashes.push_back(Instant::now());
+        health.last_crash = Some(reason.to_string());
+        health.last_crash_at = Some(chrono::Utc::now().timestamp_millis());
+        health.respawn_pause(Instant::now()).is_some()
+    })
+    .unwrap_or(false);
+
+    if let Some(app_handle) = APP_HANDLE.lock().ok().and_then(|handle| handle.clone()) {
+        let _ = app_handle.emit("worker-process-crashed", serde_json::json!({
+            "kind": kind,
+            "reason": reason,
+            "respawnPaused": paused,
+        }));
+    }
+    if !paused {
+        // Bring it back now so the next request doesn't pay for the start-up
+        tauri::async_runtime::spawn(async move {
+            let mut slot = WORKERS[&kind].lock().await;
+            let _ = start_if_needed(kind, &mut slot).await;
+        });
+    }
+}
+
+async fn start_if_needed(kind: WorkerKind, slot: &mut Option<RunningWorker>) -> Result<(), String> {
+    if slot.is_some() {
+        return Ok(());
+    }
+    if let Some(wait) = with_health(kind, |health| health.respawn_pause(Instant::now())).flatten() {
+        return Err(format!(
+            "The {} worker keeps crashing; it will be retried in {}s",
+            kind.label(),
+            wait.as_secs().max(1)
+        ));
+    }
+    match RunningWorker::spawn(kind).await {
+        Ok(worker) => {
+            let pid = worker.child.id();
+            with_health(kind, |health| {
+                health.pid = pid;
+                health.starts += 1;
+            });
+            *slot = Some(worker);
+            Ok(())
+        }
+        Err(e) => {
+            record_crash(kind, &e);
+            Err(e)
+        }
+    }
+}
+
+/// Send a request to a worker, starting it first if needed. A worker that crashes or hangs is
+/// replaced, and the request fails with an error instead of bringing the app down.
+///
+/// Cancelling the calling operation stops the worker mid-request; it's started again for the
+/// next one without counting as a crash.
+pub async fn call(kind: WorkerKind, request: WorkerRequest) -> Result<WorkerResponse, String> {
+    let mut slot = WORKERS[&kind].lock().await;
+    start_if_needed(kind, &mut slot).await?;
+    let worker = slot.as_mut().ok_or("Worker unavailable")?;
+    let cancellation = crate::cancellation::current();
+    let response = match &cancellation {
+        Some(token) => token.run(async { Ok(worker.round_trip(request, kind.request_timeout()).await) }).await,
+        None => Ok(worker.round_trip(request, kind.request_timeout()).await),
+    };
+    let Ok(response) = response else {
+        println!("🛑 Stopping the {} worker for a cancelled request", kind.label());
+        worker.shut_down().await;
+        *slot = None;
+        drop(slot);
+        with_health(kind, |health| health.pid = None);
+        return Err(crate::cancellation::CANCELLED.to_string());
+    };
+    match response {
+        Ok(result) => result,
+        Err(e) => {
+            let reason = format!("{} ({})", e, worker.shut_down().await);
+            *slot = None;
+            drop(slot);
+            record_crash(kind, &reason);
+            Err(format!("The {} worker stopped: {}. It's being restarted.", kind.label(), reason))
+        }
+    }
+}
+
+/// Transcribe in the transcription worker; the model must already be downloaded
+pub async fn transcribe(
+    audio: &[f32],
+    config: &WhisperModelConfig,
+    candidates: &[String],
+) -> Result<TranscriptionResult, String> {
+    let request = WorkerRequest::Transcribe {
+        audio: encode_audio(audio),
+        config: config.clone(),
+        candidates: candidates.to_vec(),
+    };
+    match call(WorkerKind::Transcription, request).await? {
+        WorkerResponse::Transcription(result) => Ok(result),
+        other => Err(format!("Unexpected transcription worker response: {:?}", other)),
+    }
+}
+
+/// Run an OCR request (`FindText` or `ScanText`) in the OCR worker
+#[cfg_attr(not(feature = "mcp"), allow(dead_code))]
+pub async fn ocr(request: WorkerRequest) -> Result<serde_json::Value, String> {
+    match call(WorkerKind::Ocr, request).await? {
+        WorkerResponse::TextLocations(locations) => Ok(locations),
+        other => Err(format!("Unexpected OCR worker response: {:?}", other)),
+    }
+}
+
+async fn stop(kind: WorkerKind) {
+    let mut slot = WORKERS[&kind].lock().await;
+    if let Some(mut worker) = slot.take() {
+        worker.shut_down().await;
+    }
+    with_health(kind, |health| health.pid = None);
+}
+
+fn status(kind: WorkerKind, settings: &WorkerProcessSettings) -> WorkerProcessStatus {
+    with_health(kind, |health| WorkerProcessStatus {
+        kind,
+        isolated: settings.isolates(kind),
+        running: health.pid.is_some(),
+        pid: health.pid,
+        restarts: health.starts.saturating_sub(1),
+        recent_crashes: health.crashes.iter().filter(|at| at.elapsed() < CRASH_WINDOW).count(),
+        last_crash: health.last_crash.clone(),
+        last_crash_at: health.last_crash_at,
+        respawn_paused_seconds: health.respawn_pause(Instant::now()).map(|wait| wait.as_secs()),
+    })
+    .unwrap_or(WorkerProcessStatus {
+        kind,
+        isolated: settings.isolates(kind),
+        running: false,
+        pid: None,
+        restarts: 0,
+        recent_crashes: 0,
+        last_crash: None,
+        last_crash_at: None,
+        respawn_paused_seconds: None,
+    })
+}
+
+#[crate::command]
+pub fn get_worker_processes() -> Result<Vec<WorkerProcessStatus>, String> {
+    let settings = read_settings(&SETTINGS);
+    Ok(WorkerKind::ALL.iter().map(|kind| status(*kind, &settings)).collect())
+}
+
+/// Choose which subsystems run in worker processes. Workers no longer needed are stopped.
+#[crate::command]
+pub async fn save_worker_process_settings(settings: WorkerProcessSettings) -> Result<Vec<WorkerProcessStatus>, String> {
+    crate::app_lock::require_unlocked(crate::app_lock::CommandGroup::SettingsChanges)?;
+    save_json_settings(SETTINGS_FILE, "worker process settings", &settings)?;
+
+    *SETTINGS
+        .write()
+        .map_err(|_| "Failed to update worker process settings".to_string())? = settings.clone();
+    for kind in WorkerKind::ALL {
+        if !settings.isolates(kind) {
+            stop(kind).await;
+        }
+    }
+    get_worker_processes()
+}
+
+/// Restart a worker now, clearing its crash history so a paused respawn resumes
+#[crate::command]
+pub async fn restart_worker_process(kind: WorkerKind) -> Result<WorkerProcessStatus, String> {
+    if !is_isolated(kind) {
+        return Err(format!("{} doesn't run in a worker process", kind.label()));
+    }
+    stop(kind).await;
+    with_health(kind, |health| health.crashes.clear());
+    {
+        let mut slot = WORKERS[&kind].lock().await;
+        start_if_needed(kind, &mut slot).await?;
+    }
+    Ok(status(kind, &read_settings(&SETTINGS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_crashes_pause_respawn() {
+        let start = Instant::now();
+        let mut health = WorkerHealth::default();
+        for seconds in [0, 10, 20] {
+            assert_eq!(health.respawn_pause(start + Duration::from_secs(seconds)), None);
+            health.crashes.push_back(start + Duration::from_secs(seconds));
+        }
+        assert_eq!(health.respawn_pause(start + Duration::from_secs(30)), Some(Duration::from_secs(90)));
+        // Once the first crash ages out, respawning is allowed again
+        assert_eq!(health.respawn_pause(start + CRASH_WINDOW), None);
+    }
+}
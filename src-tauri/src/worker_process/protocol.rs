@@ -0,0 +1,120 @@
+// Wire format between the app and its worker processes: one JSON message per line on stdin/stdout.
+// Native code in the worker may print to stdout too, so protocol lines carry a marker prefix and
+// anything else is treated as log output.
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+
+use crate::speech::{TranscriptionResult, WhisperModelConfig};
+
+pub const LINE_MARKER: &str = "\u{1e}enteract-worker ";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum WorkerRequest {
+    Ping,
+    Transcribe {
+        /// 16kHz mono f32 samples, little-endian and base64 encoded
+        audio: String,
+        config: WhisperModelConfig,
+        candidates: Vec<String>,
+    },
+    FindText {
+        image_base64: String,
+        target_text: String,
+        confidence_threshold: f64,
+        case_sensitive: bool,
+        #[serde(default)]
+        fuzzy: bool,
+        #[serde(default)]
+        similarity_threshold: Option<f64>,
+        /// OCR language tag; `None` uses the user's profile languages
+        #[serde(default)]
+        language: Option<String>,
+    },
+    ScanText {
+        image_base64: String,
+        confidence_threshold: f64,
+        show_all: bool,
+    },
+}
+
+impl WorkerRequest {
+    pub fn method(&self) -> &'static str {
+        match self {
+            Self::Ping => "ping",
+            Self::Transcribe { .. } => "transcribe",
+            Self::FindText { .. } => "find_text",
+            Self::ScanText { .. } => "scan_text",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum WorkerResponse {
+    Pong,
+    Transcription(TranscriptionResult),
+    /// OCR matches, in the shape the MCP tools use
+    TextLocations(serde_json::Value),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequestEnvelope {
+    pub id: u64,
+    pub request: WorkerRequest,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseEnvelope {
+    pub id: u64,
+    pub result: Result<WorkerResponse, String>,
+}
+
+pub fn encode_line<T: Serialize>(message: &T) -> Result<String, String> {
+    serde_json::to_string(message)
+        .map(|json| format!("{}{}\n", LINE_MARKER, json))
+        .map_err(|e| format!("Failed to encode worker message: {}", e))
+}
+
+/// `None` for lines that aren't protocol messages
+pub fn decode_line<T: for<'de> Deserialize<'de>>(line: &str) -> Option<Result<T, String>> {
+    let json = line.trim_end().strip_prefix(LINE_MARKER)?;
+    Some(serde_json::from_str(json).map_err(|e| format!("Malformed worker message: {}", e)))
+}
+
+pub fn encode_audio(samples: &[f32]) -> String {
+    let bytes: Vec<u8> = samples.iter().flat_map(|sample| sample.to_le_bytes()).collect();
+    general_purpose::STANDARD.encode(bytes)
+}
+
+pub fn decode_audio(encoded: &str) -> Result<Vec<f32>, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Failed to decode worker audio: {}", e))?;
+    if bytes.len() % 4 != 0 {
+        return Err("Worker audio isn't a whole number of samples".to_string());
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_round_trip() {
+        let samples = vec![0.0, -0.5, 0.25, 1.0];
+        assert_eq!(decode_audio(&encode_audio(&samples)).unwrap(), samples);
+
+        let line = encode_line(&ResponseEnvelope { id: 7, result: Ok(WorkerResponse::Pong) }).unwrap();
+        let decoded: ResponseEnvelope = decode_line(&line).unwrap().unwrap();
+        assert_eq!(decoded.id, 7);
+        assert!(matches!(decoded.result, Ok(WorkerResponse::Pong)));
+
+        // Output from native code isn't mistaken for a response
+        assert!(decode_line::<ResponseEnvelope>("whisper_init_from_file: loading model\n").is_none());
+    }
+}
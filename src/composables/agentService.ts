@@ -221,6 +221,12 @@ export class AgentService {
             console.log(`🤖 Started ${agentType} response with ${data.model}`)
             if (currentHistory[streamingMessageIndex]) {
               currentHistory[streamingMessageIndex].text = `🤖 ${agentName} (${data.model})▋`
+              // Saved with the message so the response can be reproduced later
+              currentHistory[streamingMessageIndex].metadata = {
+                ...currentHistory[streamingMessageIndex].metadata,
+                model: data.model,
+                seed: data.seed
+              }
             }
             setTimeout(() => {
               AgentService.scrollChatToBottom()
@@ -147,10 +147,13 @@ export function useTransparency() {
       return
     }
     
-    // Escape: Emergency restore
+    // Escape: Emergency restore, and stop any computer-use automation
     if (event.key === 'Escape') {
       event.preventDefault()
       emergencyRestore()
+      invoke('stop_all_mcp_activity').catch((error) => {
+        console.error('Failed to stop MCP activity:', error)
+      })
       return
     }
     
@@ -13,9 +13,10 @@ export interface EnhancedDocument {
   access_count: number
   last_accessed: string | null
   is_cached: boolean
-  embedding_status: 'pending' | 'processing' | 'completed' | 'failed'
+  embedding_status: 'pending' | 'processing' | 'completed' | 'failed' | 'evicted'
   chunk_count: number
   metadata: string | null
+  collection_id: string | null
 }
 
 export interface EnhancedDocumentChunk {
@@ -40,6 +41,9 @@ export interface ChunkingConfig {
   min_chunk_size: number
   respect_sentence_boundaries: boolean
   respect_paragraph_boundaries: boolean
+  // Chunking strategy by file extension or MIME type
+  strategies?: Record<string, 'standard' | 'markdown' | 'code' | 'semantic'>
+  semantic_similarity_threshold?: number
 }
 
 export interface EmbeddingConfig {
@@ -60,12 +64,15 @@ export interface EnhancedRagSettings {
   max_document_size_mb: number
   max_collection_size_gb: number
   max_cached_documents: number
+  archive_evicted_files?: boolean
   auto_embedding: boolean
   background_processing: boolean
   reranking_enabled: boolean
   chunking_config: ChunkingConfig
   embedding_config: EmbeddingConfig
   search_config: SearchConfig
+  embedding_concurrency?: number
+  embedding_max_attempts?: number
 }
 
 export interface EnhancedStorageStats {
@@ -73,10 +80,14 @@ export interface EnhancedStorageStats {
   total_size_bytes: number
   total_size_mb: number
   cached_documents: number
+  evicted_documents: number
+  archived_documents: number
+  storage_used_bytes: number
   total_chunks: number
   embedded_chunks: number
   embedding_coverage: number
   max_cached_documents: number
+  max_collection_size_gb: number
   max_document_size_mb: number
   embedding_model: string
   reranking_enabled: boolean
@@ -168,7 +179,8 @@ class EnhancedRagService {
 
   async searchDocuments(
     query: string,
-    contextDocumentIds: string[] = []
+    contextDocumentIds: string[] = [],
+    collectionId: string | null = null
   ): Promise<EnhancedDocumentChunk[]> {
     try {
       if (!this.initialized) {
@@ -177,7 +189,8 @@ class EnhancedRagService {
 
       const chunks = await invoke<EnhancedDocumentChunk[]>('search_enhanced_documents', {
         query,
-        contextDocumentIds
+        contextDocumentIds,
+        collectionId
       })
       return chunks
     } catch (error) {
@@ -288,7 +301,13 @@ class EnhancedRagService {
     }
   }
 
-  async checkDocumentDuplicate(file: File): Promise<{ isDuplicate: boolean; existingDocument?: EnhancedDocument }> {
+  async checkDocumentDuplicate(file: File): Promise<{
+    isDuplicate: boolean
+    existingDocument?: EnhancedDocument
+    isNearDuplicate?: boolean
+    similarity?: number
+    nearDuplicateDocument?: EnhancedDocument
+  }> {
     try {
       if (!this.initialized) {
         await this.initialize()
@@ -299,12 +318,16 @@ class EnhancedRagService {
       
       const result = await invoke<Record<string, any>>('check_document_duplicate', {
         fileName: file.name,
-        fileContent: Array.from(uint8Array)
+        fileContent: Array.from(uint8Array),
+        fileType: file.type || null
       })
       
       return {
         isDuplicate: result.is_duplicate as boolean,
-        existingDocument: result.existing_document as EnhancedDocument | undefined
+        existingDocument: result.existing_document as EnhancedDocument | undefined,
+        isNearDuplicate: result.is_near_duplicate as boolean | undefined,
+        similarity: result.similarity as number | undefined,
+        nearDuplicateDocument: result.near_duplicate_document as EnhancedDocument | undefined
       }
     } catch (error) {
       console.error('Duplicate check failed:', error)
@@ -312,6 +335,20 @@ class EnhancedRagService {
     }
   }
 
+  // Upload a file as the new version of an existing document, keeping its id, collection and tags
+  async replaceDocument(documentId: string, file: File): Promise<Record<string, any>> {
+    if (!this.initialized) {
+      await this.initialize()
+    }
+    const uint8Array = new Uint8Array(await file.arrayBuffer())
+    return invoke<Record<string, any>>('replace_enhanced_document', {
+      documentId,
+      fileName: file.name,
+      fileContent: Array.from(uint8Array),
+      fileType: file.type
+    })
+  }
+
   async getDocumentEmbeddingStatus(documentIds: string[]): Promise<Record<string, string>> {
     try {
       if (!this.initialized) {
@@ -430,6 +467,7 @@ class EnhancedRagService {
       max_document_size_mb: 50.0,
       max_collection_size_gb: 2.0,
       max_cached_documents: 10,
+      archive_evicted_files: false,
       auto_embedding: true,
       background_processing: true,
       reranking_enabled: false,
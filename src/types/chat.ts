@@ -39,6 +39,7 @@ export interface ChatMessage {
     type: 'start' | 'chunk' | 'error' | 'complete'
     text?: string
     model?: string
+    seed?: number
     error?: string
     done?: boolean
   }
\ No newline at end of file
@@ -93,6 +93,7 @@ export interface MessageMetadata {
   analysisType?: string[]
   searchQueries?: string[]
   sources?: string[]
+  seed?: number | null
 }
 
 // File upload types